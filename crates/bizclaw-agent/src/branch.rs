@@ -0,0 +1,100 @@
+//! Conversation branching — "regenerate" and "edit & rerun" support.
+//!
+//! Forking a conversation truncates the history at a user turn and keeps the
+//! removed tail as a [`ConversationBranch`], so earlier answers stay retrievable.
+
+use bizclaw_core::types::{Message, Role};
+
+/// A discarded tail of the conversation, kept so the UI can show prior branches.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConversationBranch {
+    /// Index in the conversation where the branch was cut.
+    pub forked_at: usize,
+    /// Messages that were removed (starting with the user turn).
+    pub messages: Vec<Message>,
+    /// When the fork happened.
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Find the index of the last user message, if any.
+pub fn last_user_index(conversation: &[Message]) -> Option<usize> {
+    conversation.iter().rposition(|m| m.role == Role::User)
+}
+
+/// Truncate `conversation` so that the user message at `index` and everything
+/// after it is removed, returning the removed tail as a branch.
+///
+/// Context messages injected right before the user turn (knowledge, memory,
+/// guardrails) are dropped too — they are re-injected when the turn is re-run.
+/// The system prompt at index 0 is never removed.
+pub fn fork_at(conversation: &mut Vec<Message>, index: usize) -> Option<ConversationBranch> {
+    if index == 0 || index >= conversation.len() || conversation[index].role != Role::User {
+        return None;
+    }
+    let messages: Vec<Message> = conversation.drain(index..).collect();
+    while conversation.len() > 1
+        && conversation.last().is_some_and(|m| m.role == Role::System)
+    {
+        conversation.pop();
+    }
+    Some(ConversationBranch {
+        forked_at: index,
+        messages,
+        created_at: chrono::Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Message> {
+        vec![
+            Message::system("You are helpful."),
+            Message::user("first"),
+            Message::assistant("answer 1"),
+            Message::system("[Knowledge Base]\n...\n[End knowledge]"),
+            Message::user("second"),
+            Message::assistant("answer 2"),
+        ]
+    }
+
+    #[test]
+    fn test_regenerate_replaces_only_last_assistant() {
+        let mut conv = sample();
+        let idx = last_user_index(&conv).unwrap();
+        assert_eq!(idx, 4);
+
+        let branch = fork_at(&mut conv, idx).unwrap();
+        assert_eq!(branch.messages.len(), 2);
+        assert_eq!(branch.messages[1].content, "answer 2");
+
+        // Re-run the same user turn
+        conv.push(Message::user(&branch.messages[0].content));
+        conv.push(Message::assistant("answer 2 (regenerated)"));
+
+        assert_eq!(conv.len(), 5);
+        assert_eq!(conv[2].content, "answer 1");
+        assert_eq!(conv[3].content, "second");
+        assert_eq!(conv[4].content, "answer 2 (regenerated)");
+    }
+
+    #[test]
+    fn test_edit_truncates_at_index() {
+        let mut conv = sample();
+        let branch = fork_at(&mut conv, 1).unwrap();
+        assert_eq!(conv.len(), 1);
+        assert_eq!(conv[0].role, Role::System);
+        assert_eq!(branch.forked_at, 1);
+        assert_eq!(branch.messages.len(), 5);
+    }
+
+    #[test]
+    fn test_fork_rejects_non_user_index() {
+        let mut conv = sample();
+        assert!(fork_at(&mut conv, 0).is_none());
+        assert!(fork_at(&mut conv, 2).is_none());
+        assert!(fork_at(&mut conv, 99).is_none());
+        assert_eq!(conv.len(), 6);
+    }
+}
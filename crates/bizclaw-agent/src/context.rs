@@ -3,7 +3,170 @@
 //! Handles conversation history, context window limits,
 //! and message summarization when context grows too large.
 
-use bizclaw_core::types::Message;
+use bizclaw_core::config::{ContextConfig, OversizedInput};
+use bizclaw_core::text::truncate_chars;
+use bizclaw_core::types::{Message, Role};
+
+/// Prefix of a system message holding a pinned memory (see [`pinned_memory`]).
+pub const PINNED_MEMORY_MARKER: &str = "[Pinned memory]";
+
+/// Prefix of the summary left behind by auto-compaction.
+pub const COMPACTION_MARKER: &str = "[Compacted:";
+
+/// A system message that survives history trimming.
+pub fn pinned_memory(text: &str) -> Message {
+    Message::system(format!("{PINNED_MEMORY_MARKER}\n{text}"))
+}
+
+/// Whether trimming must keep `message`: messages the user pinned, pinned
+/// memories and compaction summaries (which stand in for turns that are
+/// already gone).
+pub fn is_pinned(message: &Message) -> bool {
+    message.pinned
+        || (message.role == Role::System
+            && (message.content.starts_with(PINNED_MEMORY_MARKER)
+                || message.content.starts_with(COMPACTION_MARKER)))
+}
+
+/// Whether `message` can be pinned on its own: a user message or a plain
+/// assistant reply. Tool calls and results only make sense together.
+pub fn can_pin(message: &Message) -> bool {
+    match message.role {
+        Role::User => true,
+        Role::Assistant => message.tool_calls.as_ref().is_none_or(|c| c.is_empty()),
+        Role::System | Role::Tool => false,
+    }
+}
+
+/// Keep only the last `max_turns` turns of `conversation`. A turn is a user
+/// message, the context injected just before it, and everything up to the
+/// next turn, so assistant tool calls stay with their results. The system
+/// prompt (first message) and pinned messages are always kept; a pinned
+/// tool call or result keeps its whole exchange. Returns how many messages
+/// were dropped; `0` turns means unlimited.
+pub fn trim_turns(conversation: &mut Vec<Message>, max_turns: usize) -> usize {
+    if max_turns == 0 || conversation.len() <= 1 {
+        return 0;
+    }
+    let turn_starts: Vec<usize> = conversation
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, m)| m.role == Role::User)
+        .map(|(i, _)| i)
+        .collect();
+    if turn_starts.len() <= max_turns {
+        return 0;
+    }
+
+    // Context injected right before the oldest kept turn belongs to it.
+    let mut cut = turn_starts[turn_starts.len() - max_turns];
+    while cut > 1
+        && conversation[cut - 1].role == Role::System
+        && !is_pinned(&conversation[cut - 1])
+    {
+        cut -= 1;
+    }
+    let mut keep: Vec<bool> = conversation
+        .iter()
+        .enumerate()
+        .map(|(i, m)| i == 0 || i >= cut || is_pinned(m))
+        .collect();
+    // An assistant tool call and the results after it stand or fall together.
+    let mut start = 1;
+    while start < cut {
+        let mut end = start + 1;
+        if conversation[start]
+            .tool_calls
+            .as_ref()
+            .is_some_and(|c| !c.is_empty())
+        {
+            while end < cut && conversation[end].role == Role::Tool {
+                end += 1;
+            }
+            if keep[start..end].contains(&true) {
+                keep[start..end].fill(true);
+            }
+        }
+        start = end;
+    }
+    let before = conversation.len();
+    let mut keep = keep.into_iter();
+    conversation.retain(|_| keep.next().unwrap_or(true));
+    before - conversation.len()
+}
+
+/// Rough token count of `text`, the same heuristic the agent uses for its
+/// context window (~3 bytes per token for mixed English and Vietnamese).
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len() / 3
+}
+
+/// Cut `text` down to about `max_tokens`, on a character boundary, and say
+/// how much was left out so the model does not take the rest as missing.
+pub fn truncate_tool_result(text: &str, max_tokens: usize) -> String {
+    let max_bytes = max_tokens.saturating_mul(3);
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let mut end = max_bytes;
+    while end > 0 && !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}\n…[truncated: {} of {} bytes omitted]",
+        &text[..end],
+        text.len() - end,
+        text.len()
+    )
+}
+
+/// A user message checked against `max_input_chars`/`max_input_tokens`.
+#[derive(Debug, PartialEq)]
+pub enum InputCheck {
+    /// Within both limits.
+    Fits,
+    /// Over a limit in truncate mode: the start of the message, and the
+    /// number of characters kept.
+    Truncated { text: String, kept: usize },
+    /// Over a limit in reject mode.
+    Rejected { chars: usize, limit: usize },
+}
+
+/// Check a user message against the input limits in `config`, before it
+/// reaches the provider. A token limit is applied with the same
+/// ~3 bytes per token estimate as [`estimate_tokens`].
+pub fn check_input(text: &str, config: &ContextConfig) -> InputCheck {
+    let max_bytes = match config.max_input_tokens {
+        0 => usize::MAX,
+        tokens => tokens.saturating_mul(3),
+    };
+    let max_chars = match config.max_input_chars {
+        0 => usize::MAX,
+        chars => chars,
+    };
+    let chars = text.chars().count();
+    if chars <= max_chars && text.len() <= max_bytes {
+        return InputCheck::Fits;
+    }
+    let mut end = truncate_chars(text, max_chars).len().min(max_bytes);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let kept = text[..end].chars().count();
+    match config.oversized_input {
+        OversizedInput::Reject => InputCheck::Rejected { chars, limit: kept },
+        OversizedInput::Truncate => InputCheck::Truncated {
+            text: format!(
+                "{}\n…[message truncated: {} of {} characters omitted]",
+                &text[..end],
+                chars - kept,
+                chars
+            ),
+            kept,
+        },
+    }
+}
 
 /// Manages conversation context with window limits.
 pub struct ConversationContext {
@@ -129,4 +292,153 @@ mod tests {
         ctx.push(Message::user("abcdefgh")); // 8 chars = ~2 tokens
         assert!(ctx.estimated_tokens() > 0);
     }
+
+    #[test]
+    fn test_trim_turns_keeps_system_and_pinned() {
+        let mut conversation = vec![Message::system("System"), pinned_memory("User is vegan")];
+        for i in 1..=5 {
+            conversation.push(Message::system(format!("[Knowledge Base] turn {i}")));
+            conversation.push(Message::user(format!("q{i}")));
+            conversation.push(Message::assistant(format!("a{i}")));
+        }
+
+        let dropped = trim_turns(&mut conversation, 2);
+        assert_eq!(dropped, 9);
+        let contents: Vec<&str> = conversation.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(
+            contents,
+            vec![
+                "System",
+                "[Pinned memory]\nUser is vegan",
+                "[Knowledge Base] turn 4",
+                "q4",
+                "a4",
+                "[Knowledge Base] turn 5",
+                "q5",
+                "a5",
+            ]
+        );
+
+        // Within the limit (or unlimited): nothing changes.
+        assert_eq!(trim_turns(&mut conversation, 2), 0);
+        assert_eq!(trim_turns(&mut conversation, 0), 0);
+        assert_eq!(conversation.len(), 8);
+    }
+
+    #[test]
+    fn test_trim_turns_keeps_pinned_turns() {
+        let mut conversation = vec![Message::system("System")];
+        for i in 1..=4 {
+            conversation.push(Message::user(format!("q{i}")));
+            conversation.push(Message::assistant(format!("a{i}")));
+        }
+        conversation[3].pinned = true; // q2: "always answer in French"
+
+        let dropped = trim_turns(&mut conversation, 1);
+        assert_eq!(dropped, 5);
+        let contents: Vec<&str> = conversation.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["System", "q2", "q4", "a4"]);
+
+        // Once unpinned it goes with the next trim.
+        conversation[1].pinned = false;
+        conversation.push(Message::user("q5"));
+        assert_eq!(trim_turns(&mut conversation, 1), 3);
+        let contents: Vec<&str> = conversation.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["System", "q5"]);
+    }
+
+    #[test]
+    fn test_trim_turns_keeps_tool_results_with_their_turn() {
+        let mut conversation = vec![Message::system("System")];
+        conversation.push(Message::user("old"));
+        conversation.push(Message::assistant("old answer"));
+        conversation.push(Message::user("weather?"));
+        conversation.push(Message::assistant(""));
+        conversation.push(Message::tool("sunny", "call_1"));
+        conversation.push(Message::assistant("It's sunny."));
+
+        trim_turns(&mut conversation, 1);
+        assert_eq!(conversation.len(), 5);
+        assert_eq!(conversation[1].content, "weather?");
+        assert_eq!(conversation[3].role, Role::Tool);
+    }
+
+    #[test]
+    fn test_trim_turns_around_pinned_tool_exchange() {
+        let mut call = Message::assistant("");
+        call.tool_calls = Some(vec![bizclaw_core::types::ToolCall {
+            id: "call_1".into(),
+            r#type: "function".into(),
+            function: bizclaw_core::types::FunctionCall {
+                name: "weather".into(),
+                arguments: "{}".into(),
+            },
+        }]);
+        assert!(!can_pin(&call));
+        assert!(!can_pin(&Message::tool("sunny", "call_1")));
+        assert!(can_pin(&Message::assistant("It's sunny.")));
+        assert!(can_pin(&Message::user("weather?")));
+
+        let mut conversation = vec![Message::system("System")];
+        conversation.push(Message::user("weather?"));
+        conversation.push(call);
+        conversation.push(Message::tool("sunny", "call_1"));
+        conversation.push(Message::assistant("It's sunny."));
+        conversation.push(Message::user("thanks"));
+        conversation.push(Message::assistant("You're welcome."));
+        // Pinned before pinning was limited, e.g. in a restored session.
+        conversation[3].pinned = true;
+
+        assert_eq!(trim_turns(&mut conversation, 1), 2);
+        let roles: Vec<Role> = conversation.iter().map(|m| m.role.clone()).collect();
+        assert_eq!(
+            roles,
+            vec![
+                Role::System,
+                Role::Assistant,
+                Role::Tool,
+                Role::User,
+                Role::Assistant
+            ]
+        );
+        assert!(conversation[1].tool_calls.is_some());
+    }
+
+    #[test]
+    fn test_truncate_tool_result_on_char_boundary() {
+        assert_eq!(truncate_tool_result("short", 10), "short");
+        // "ạ" is 3 bytes; a 1-token (3-byte) cut falls inside the first one.
+        let cut = truncate_tool_result("aạạ", 1);
+        assert_eq!(cut, "a\n…[truncated: 6 of 7 bytes omitted]");
+    }
+
+    #[test]
+    fn test_check_input_limits() {
+        let mut config = ContextConfig {
+            max_input_chars: 5,
+            ..Default::default()
+        };
+        assert_eq!(check_input("hello", &config), InputCheck::Fits);
+        assert_eq!(
+            check_input("xin chào", &config),
+            InputCheck::Rejected { chars: 8, limit: 5 }
+        );
+
+        config.oversized_input = OversizedInput::Truncate;
+        assert_eq!(
+            check_input("xin chào", &config),
+            InputCheck::Truncated {
+                text: "xin c\n…[message truncated: 3 of 8 characters omitted]".into(),
+                kept: 5,
+            }
+        );
+
+        // A token limit counts bytes, and never splits a character.
+        config.max_input_chars = 0;
+        config.max_input_tokens = 1;
+        let InputCheck::Truncated { kept, .. } = check_input("aạạ", &config) else {
+            panic!("expected truncation");
+        };
+        assert_eq!(kept, 1);
+    }
 }
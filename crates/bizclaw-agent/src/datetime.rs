@@ -0,0 +1,164 @@
+//! Current date/time context — keeps the model from guessing "today".
+//!
+//! Timezones are resolved to fixed UTC offsets: explicit offsets ("+07:00",
+//! "UTC+7") and a table of common zone names. Zones that observe daylight
+//! saving are not in the table; use an explicit offset for those.
+
+use bizclaw_core::config::DEFAULT_DATETIME_FORMAT;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset, Utc};
+
+/// Prefix of the injected system message (used to replace it each turn).
+pub const DATETIME_MARKER: &str = "[Current date/time]";
+
+/// Memory entry id holding the user's timezone, when known.
+pub const USER_TIMEZONE_MEMORY_ID: &str = "personal:timezone";
+
+/// Zone name → UTC offset in minutes.
+const ZONES: &[(&str, i32)] = &[
+    ("Asia/Ho_Chi_Minh", 7 * 60),
+    ("Asia/Saigon", 7 * 60),
+    ("Asia/Bangkok", 7 * 60),
+    ("Asia/Jakarta", 7 * 60),
+    ("Asia/Phnom_Penh", 7 * 60),
+    ("Asia/Vientiane", 7 * 60),
+    ("Asia/Singapore", 8 * 60),
+    ("Asia/Kuala_Lumpur", 8 * 60),
+    ("Asia/Manila", 8 * 60),
+    ("Asia/Shanghai", 8 * 60),
+    ("Asia/Hong_Kong", 8 * 60),
+    ("Asia/Taipei", 8 * 60),
+    ("Australia/Perth", 8 * 60),
+    ("Asia/Tokyo", 9 * 60),
+    ("Asia/Seoul", 9 * 60),
+    ("Australia/Brisbane", 10 * 60),
+    ("Asia/Kolkata", 5 * 60 + 30),
+    ("Asia/Dubai", 4 * 60),
+    ("Europe/Moscow", 3 * 60),
+    ("Europe/Istanbul", 3 * 60),
+];
+
+/// Resolve a timezone string to a fixed offset.
+pub fn parse_timezone(tz: &str) -> Option<FixedOffset> {
+    let tz = tz.trim();
+    if tz.is_empty() {
+        return None;
+    }
+    if let Some((_, minutes)) = ZONES.iter().find(|(name, _)| name.eq_ignore_ascii_case(tz)) {
+        return FixedOffset::east_opt(minutes * 60);
+    }
+    let upper = tz.to_ascii_uppercase();
+    let rest = upper
+        .strip_prefix("UTC")
+        .or_else(|| upper.strip_prefix("GMT"))
+        .unwrap_or(&upper);
+    if rest.is_empty() || upper == "Z" {
+        return FixedOffset::east_opt(0);
+    }
+    parse_offset(rest)
+}
+
+/// Parse "+7", "-05", "+07:00" or "+0530".
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let (sign, digits) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((h, m)) => (h.parse::<i32>().ok()?, m.parse::<i32>().ok()?),
+        None if digits.len() == 4 => (digits[..2].parse().ok()?, digits[2..].parse().ok()?),
+        None => (digits.parse().ok()?, 0),
+    };
+    if hours > 14 || minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Whether `format` is a `strftime` string chrono can render. Formatting
+/// with an invalid one panics.
+pub fn is_valid_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+}
+
+/// The system message announcing the current date/time in `tz`.
+///
+/// Unknown timezones fall back to UTC, invalid formats to [`DEFAULT_DATETIME_FORMAT`].
+pub fn datetime_context(now: DateTime<Utc>, tz: &str, format: &str) -> String {
+    let format = if is_valid_format(format) {
+        format
+    } else {
+        tracing::warn!("Invalid datetime format '{format}', using '{DEFAULT_DATETIME_FORMAT}'");
+        DEFAULT_DATETIME_FORMAT
+    };
+    let (offset, label) = match parse_timezone(tz) {
+        Some(offset) => (offset, tz.trim().to_string()),
+        None => {
+            tracing::warn!("Unknown timezone '{tz}', using UTC");
+            (
+                FixedOffset::east_opt(0).expect("zero offset"),
+                "UTC".to_string(),
+            )
+        }
+    };
+    let local = now.with_timezone(&offset);
+    format!(
+        "{DATETIME_MARKER} {} ({label}, UTC{})",
+        local.format(format),
+        local.format("%:z")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn fixed_clock() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 31, 20, 15, 0).unwrap()
+    }
+
+    #[test]
+    fn test_context_uses_timezone_and_format() {
+        // 20:15 UTC on the 31st is already 1 April in Vietnam.
+        let ctx = datetime_context(fixed_clock(), "Asia/Ho_Chi_Minh", "%A, %Y-%m-%d %H:%M");
+        assert_eq!(
+            ctx,
+            "[Current date/time] Wednesday, 2026-04-01 03:15 (Asia/Ho_Chi_Minh, UTC+07:00)"
+        );
+
+        let ctx = datetime_context(fixed_clock(), "-05:00", "%d/%m/%Y");
+        assert!(ctx.contains("31/03/2026"), "{ctx}");
+        assert!(ctx.ends_with("(-05:00, UTC-05:00)"));
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone("UTC").unwrap().local_minus_utc(), 0);
+        assert_eq!(parse_timezone("utc+7").unwrap().local_minus_utc(), 7 * 3600);
+        assert_eq!(parse_timezone("+0530").unwrap().local_minus_utc(), 19800);
+        assert_eq!(
+            parse_timezone("asia/tokyo").unwrap().local_minus_utc(),
+            9 * 3600
+        );
+        assert!(parse_timezone("Mars/Olympus").is_none());
+        assert!(parse_timezone("+25").is_none());
+
+        let ctx = datetime_context(fixed_clock(), "Mars/Olympus", "%H:%M");
+        assert!(ctx.contains("20:15 (UTC, UTC+00:00)"));
+    }
+
+    #[test]
+    fn test_invalid_format_falls_back() {
+        assert!(is_valid_format("%d/%m/%Y"));
+        assert!(!is_valid_format("%Q %Y"));
+        assert!(!is_valid_format("%"));
+
+        let ctx = datetime_context(fixed_clock(), "UTC", "%Q %Y");
+        assert_eq!(
+            ctx,
+            "[Current date/time] Tuesday, 2026-03-31 20:15 (UTC, UTC+00:00)"
+        );
+    }
+}
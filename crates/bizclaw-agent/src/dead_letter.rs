@@ -0,0 +1,91 @@
+//! Dead-letter queue — keeps channel messages the agent failed to answer.
+//!
+//! When a turn fails (provider down, rate limited, …) the incoming message
+//! and the error are stored in the DataStore (`dead_letters`) instead of
+//! being lost. Once the cause is fixed an admin can replay them: the answer
+//! goes to the original thread and a replay that succeeds removes the entry.
+
+use bizclaw_core::error::BizClawError;
+use bizclaw_core::traits::Channel;
+use bizclaw_core::types::{DeadLetter, IncomingMessage};
+use bizclaw_db::store::DataStore;
+
+use crate::Agent;
+use crate::error::AgentError;
+
+/// Most dead letters kept; older ones are dropped first.
+pub const MAX_DEAD_LETTERS: usize = 500;
+
+/// [`Agent::process_incoming`], storing `msg` as a dead letter if it fails.
+/// The agent's error is returned either way.
+pub async fn process_or_dead_letter(
+    agent: &mut Agent,
+    store: &dyn DataStore,
+    msg: &IncomingMessage,
+) -> Result<String, AgentError> {
+    let result = agent.process_incoming(msg).await;
+    if let Err(e) = &result {
+        record(store, None, msg, &e.to_string()).await;
+    }
+    result
+}
+
+/// Store `msg` as a dead letter for a turn that failed with `error`, for
+/// callers that do not run the agent through [`process_or_dead_letter`].
+/// `agent_name` is the orchestrator agent that failed it, `None` for the
+/// default agent. Storage errors are logged, never returned.
+pub async fn record(
+    store: &dyn DataStore,
+    agent_name: Option<&str>,
+    msg: &IncomingMessage,
+    error: &str,
+) {
+    let letter = DeadLetter::new(msg, agent_name, error);
+    if let Err(store_err) = store.push_dead_letter(&letter, MAX_DEAD_LETTERS).await {
+        tracing::warn!(
+            "[{}] Failed to dead-letter message: {store_err}",
+            msg.channel
+        );
+    }
+}
+
+/// Run dead letter `id` through `agent` again and send the response to the
+/// original thread, over the channel `channel_for` returns for the letter's
+/// channel name. Returns the response, or `None` if there is no such dead
+/// letter. The entry is removed only once the reply is delivered, so a
+/// failed replay can be tried again later.
+pub async fn replay<F>(
+    agent: &mut Agent,
+    store: &dyn DataStore,
+    id: &str,
+    channel_for: F,
+) -> Result<Option<String>, AgentError>
+where
+    F: FnOnce(&str) -> Option<Box<dyn Channel>>,
+{
+    let Some(letter) = store.get_dead_letter(id).await? else {
+        return Ok(None);
+    };
+    replay_letter(agent, store, &letter, channel_for).await.map(Some)
+}
+
+/// [`replay`] for a dead letter already read from `store`, e.g. to pick the
+/// agent named by its `agent_name`.
+pub async fn replay_letter<F>(
+    agent: &mut Agent,
+    store: &dyn DataStore,
+    letter: &DeadLetter,
+    channel_for: F,
+) -> Result<String, AgentError>
+where
+    F: FnOnce(&str) -> Option<Box<dyn Channel>>,
+{
+    let channel = channel_for(&letter.channel).ok_or_else(|| {
+        BizClawError::Channel(format!("Channel '{}' is not configured", letter.channel))
+    })?;
+    let reply = agent.handle_incoming(&letter.message).await?;
+    let response = reply.content.clone();
+    channel.send(reply).await?;
+    store.delete_dead_letter(&letter.id).await?;
+    Ok(response)
+}
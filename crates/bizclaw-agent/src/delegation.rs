@@ -0,0 +1,340 @@
+//! Delegation executor — runs delegated tasks without overloading the target.
+//!
+//! Before a delegation starts, the target's active delegations are counted
+//! against the `max_concurrent` of the link that allows it. When the target
+//! is full the delegation waits, and starts as soon as a running one
+//! finishes (here or, noticed by polling, in another process sharing the
+//! store).
+//!
+//! A delegation whose future is dropped before it finishes is marked failed
+//! by its [`DelegationSlot`]; ones left running by a crash are failed once
+//! they are older than the executor's `stale_after`.
+
+use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::types::{Delegation, DelegationEvent, DelegationEventKind, DelegationStatus};
+use bizclaw_db::store::DataStore;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
+use tokio::time::Instant;
+
+use crate::orchestrator::safe_truncate;
+
+/// Concurrency limit when no link covers a pair (same as a new link's).
+pub const DEFAULT_MAX_CONCURRENT: u32 = 3;
+
+/// Runs delegations, queueing those whose target is at its limit.
+pub struct DelegationExecutor {
+    store: Arc<dyn DataStore>,
+    /// Held while counting active delegations and claiming a slot, so two
+    /// delegations cannot both take the last one.
+    admission: Mutex<()>,
+    /// Wakes queued delegations when one finishes.
+    slot_freed: Arc<Notify>,
+    /// How often queued delegations re-check the store.
+    poll_interval: Duration,
+    /// Longest a delegation waits for a slot before failing.
+    max_wait: Duration,
+    /// Age after which a delegation still active is taken to be abandoned.
+    stale_after: Duration,
+}
+
+/// A claimed slot on the delegation's target. Finish it with
+/// [`finish`](Self::finish); dropping it unfinished marks the delegation failed.
+pub struct DelegationSlot {
+    store: Arc<dyn DataStore>,
+    slot_freed: Arc<Notify>,
+    id: String,
+    finished: bool,
+}
+
+impl DelegationExecutor {
+    pub fn new(store: Arc<dyn DataStore>) -> Self {
+        Self {
+            store,
+            admission: Mutex::new(()),
+            slot_freed: Arc::new(Notify::new()),
+            poll_interval: Duration::from_secs(1),
+            max_wait: Duration::from_secs(300),
+            stale_after: Duration::from_secs(3600),
+        }
+    }
+
+    /// Set how often queued delegations re-check the store.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Set the longest a delegation waits for a slot.
+    pub fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    /// Set the age after which an active delegation is failed as abandoned.
+    pub fn with_stale_after(mut self, stale_after: Duration) -> Self {
+        self.stale_after = stale_after;
+        self
+    }
+
+    /// Concurrency limit for delegations from `from` to `to`: the lowest
+    /// `max_concurrent` among links allowing it.
+    pub async fn max_concurrent(&self, from: &str, to: &str) -> Result<u32> {
+        let links = self.store.list_links(from).await?;
+        Ok(links
+            .iter()
+            .filter(|l| l.allows(from, to))
+            .map(|l| l.max_concurrent)
+            .min()
+            .unwrap_or(DEFAULT_MAX_CONCURRENT))
+    }
+
+    /// Run `work` as `delegation` once the target has a free slot, recording
+    /// its status and events in the store. Returns the work's result.
+    pub async fn run<F>(&self, delegation: &Delegation, work: F) -> Result<String>
+    where
+        F: Future<Output = Result<String>>,
+    {
+        let slot = self.acquire(delegation).await?;
+        let result = work.await;
+        slot.finish(&result).await?;
+        result
+    }
+
+    /// Wait for a free slot on the target, then store `delegation` as running.
+    pub async fn acquire(&self, delegation: &Delegation) -> Result<DelegationSlot> {
+        let to = &delegation.to_agent;
+        let limit = self.max_concurrent(&delegation.from_agent, to).await?;
+        let deadline = Instant::now() + self.max_wait;
+        loop {
+            // Registered before checking, so a slot freed meanwhile still wakes us.
+            let freed = self.slot_freed.notified();
+            {
+                let _admission = self.admission.lock().await;
+                let stale_before = chrono::Duration::from_std(self.stale_after)
+                    .ok()
+                    .and_then(|age| chrono::Utc::now().checked_sub_signed(age));
+                if let Some(stale_before) = stale_before {
+                    let stale = self.store.fail_stale_delegations(to, stale_before).await?;
+                    if stale > 0 {
+                        tracing::warn!("Failed {stale} abandoned delegation(s) to '{to}'");
+                    }
+                }
+                if self.store.active_delegation_count(to).await? < limit {
+                    self.store.create_delegation(delegation).await?;
+                    self.store
+                        .update_delegation(&delegation.id, DelegationStatus::Running, None, None)
+                        .await?;
+                    break;
+                }
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(BizClawError::Delegation(format!(
+                    "Agent '{to}' still at max concurrent delegations ({limit}) after {}s",
+                    self.max_wait.as_secs()
+                )));
+            }
+            tracing::debug!("Delegation to '{to}' queued ({limit} already active)");
+            let wait = self.poll_interval.min(deadline - now);
+            let _ = tokio::time::timeout(wait, freed).await;
+        }
+        // Claimed from here on: released even if the event write below fails.
+        let slot = DelegationSlot {
+            store: self.store.clone(),
+            slot_freed: self.slot_freed.clone(),
+            id: delegation.id.clone(),
+            finished: false,
+        };
+        let started = DelegationEvent::new(DelegationEventKind::Started, "");
+        self.store
+            .append_delegation_event(&delegation.id, &started)
+            .await?;
+        Ok(slot)
+    }
+}
+
+impl DelegationSlot {
+    /// Store the outcome of the delegation and free its slot.
+    pub async fn finish(mut self, result: &Result<String>) -> Result<()> {
+        self.finished = true;
+        let recorded = record_outcome(self.store.as_ref(), &self.id, result).await;
+        self.slot_freed.notify_waiters();
+        recorded
+    }
+}
+
+impl Drop for DelegationSlot {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let (store, slot_freed, id) =
+            (self.store.clone(), self.slot_freed.clone(), self.id.clone());
+        runtime.spawn(async move {
+            let cancelled = Err(BizClawError::Delegation("Delegation was cancelled".into()));
+            if let Err(e) = record_outcome(store.as_ref(), &id, &cancelled).await {
+                tracing::warn!("Failed to mark cancelled delegation {id}: {e}");
+            }
+            slot_freed.notify_waiters();
+        });
+    }
+}
+
+/// Store the outcome of a delegation.
+async fn record_outcome(store: &dyn DataStore, id: &str, result: &Result<String>) -> Result<()> {
+    let event = match result {
+        Ok(response) => {
+            store
+                .update_delegation(
+                    id,
+                    DelegationStatus::Completed,
+                    Some(safe_truncate(response, 10000)),
+                    None,
+                )
+                .await?;
+            DelegationEvent::new(DelegationEventKind::Completed, "")
+        }
+        Err(e) => {
+            let error = e.to_string();
+            store
+                .update_delegation(id, DelegationStatus::Failed, None, Some(&error))
+                .await?;
+            DelegationEvent::new(DelegationEventKind::Failed, &error)
+        }
+    };
+    store.append_delegation_event(id, &event).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bizclaw_core::types::{AgentLink, DelegationMode, LinkDirection};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_third_delegation_waits_for_a_free_slot() {
+        let store = Arc::new(bizclaw_db::SqliteStore::in_memory().unwrap());
+        store.migrate().await.unwrap();
+        let mut link = AgentLink::new("lead", "worker", LinkDirection::Outbound);
+        link.max_concurrent = 2;
+        store.create_link(&link).await.unwrap();
+        let executor =
+            DelegationExecutor::new(store.clone()).with_poll_interval(Duration::from_millis(10));
+        assert_eq!(executor.max_concurrent("lead", "worker").await.unwrap(), 2);
+
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let delegate = |n: usize| {
+            let delegation = Delegation::new(
+                "lead",
+                "worker",
+                &format!("task {n}"),
+                DelegationMode::Async,
+            );
+            let (running, peak, executor) = (&running, &peak, &executor);
+            async move {
+                let work = async {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok(format!("done {n}"))
+                };
+                executor.run(&delegation, work).await
+            }
+        };
+
+        let (a, b, c) = tokio::join!(delegate(1), delegate(2), delegate(3));
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            [a.unwrap(), b.unwrap(), c.unwrap()],
+            ["done 1", "done 2", "done 3"]
+        );
+        assert_eq!(store.active_delegation_count("worker").await.unwrap(), 0);
+        let delegations = store.list_delegations("worker", 10).await.unwrap();
+        assert_eq!(delegations.len(), 3);
+        assert!(
+            delegations
+                .iter()
+                .all(|d| d.status == DelegationStatus::Completed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_queued_delegation_times_out() {
+        let store = Arc::new(bizclaw_db::SqliteStore::in_memory().unwrap());
+        store.migrate().await.unwrap();
+        let mut link = AgentLink::new("lead", "worker", LinkDirection::Outbound);
+        link.max_concurrent = 1;
+        store.create_link(&link).await.unwrap();
+        // A delegation left running, e.g. by another process.
+        let stuck = Delegation::new("lead", "worker", "stuck", DelegationMode::Async);
+        store.create_delegation(&stuck).await.unwrap();
+
+        let executor = DelegationExecutor::new(store.clone())
+            .with_poll_interval(Duration::from_millis(10))
+            .with_max_wait(Duration::from_millis(50));
+        let delegation = Delegation::new("lead", "worker", "next", DelegationMode::Async);
+        let err = executor
+            .run(&delegation, async { Ok("never".to_string()) })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("max concurrent"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_dropped_delegation_frees_its_slot() {
+        let store = Arc::new(bizclaw_db::SqliteStore::in_memory().unwrap());
+        store.migrate().await.unwrap();
+        let executor = DelegationExecutor::new(store.clone());
+        let delegation = Delegation::new("lead", "worker", "task", DelegationMode::Async);
+        let work = executor.run(&delegation, std::future::pending());
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), work)
+                .await
+                .is_err()
+        );
+
+        // The drop guard records the failure in the background.
+        for _ in 0..50 {
+            if store.active_delegation_count("worker").await.unwrap() == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let stored = store.get_delegation(&delegation.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, DelegationStatus::Failed);
+        assert!(stored.error.unwrap().contains("cancelled"));
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_delegation_is_failed_after_stale_after() {
+        let store = Arc::new(bizclaw_db::SqliteStore::in_memory().unwrap());
+        store.migrate().await.unwrap();
+        let mut link = AgentLink::new("lead", "worker", LinkDirection::Outbound);
+        link.max_concurrent = 1;
+        store.create_link(&link).await.unwrap();
+        // Left running by a crash two hours ago.
+        let mut abandoned = Delegation::new("lead", "worker", "old", DelegationMode::Async);
+        abandoned.created_at = chrono::Utc::now() - chrono::Duration::hours(2);
+        store.create_delegation(&abandoned).await.unwrap();
+
+        let executor = DelegationExecutor::new(store.clone())
+            .with_poll_interval(Duration::from_millis(10))
+            .with_max_wait(Duration::from_millis(50));
+        let delegation = Delegation::new("lead", "worker", "next", DelegationMode::Async);
+        let response = executor
+            .run(&delegation, async { Ok("done".to_string()) })
+            .await
+            .unwrap();
+        assert_eq!(response, "done");
+        let stored = store.get_delegation(&abandoned.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, DelegationStatus::Failed);
+    }
+}
@@ -0,0 +1,230 @@
+//! Structured errors for [`Agent::process`](crate::Agent::process).
+//!
+//! An [`AgentError`] keeps the underlying [`BizClawError`] for logs and adds
+//! an [`AgentErrorKind`], so channels can show the user a message that fits
+//! the failure (without internals) and retry logic can branch on it.
+
+use bizclaw_core::error::BizClawError;
+use bizclaw_core::i18n::{Locale, t};
+
+/// What went wrong, in terms a caller can act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentErrorKind {
+    /// The provider could not be reached or answered with a server error.
+    ProviderUnavailable,
+    /// The provider (or our own limiter) is throttling requests.
+    RateLimited,
+    /// A tool the turn depended on failed.
+    ToolFailed,
+    /// The conversation no longer fits the model's context window.
+    ContextTooLong,
+    /// A security policy blocked the request.
+    SecurityBlocked,
+    /// The user's message is over `context.max_input_chars`/`max_input_tokens`.
+    InputTooLong,
+    /// Anything else — configuration, storage, bugs.
+    Internal,
+}
+
+impl AgentErrorKind {
+    /// Classify a raw error. Provider errors only carry the HTTP status and
+    /// body as text, so those are matched on well-known phrases.
+    pub fn of(error: &BizClawError) -> Self {
+        match error {
+            BizClawError::RateLimited(_) => Self::RateLimited,
+            BizClawError::Security(_)
+            | BizClawError::PermissionDenied(_)
+            | BizClawError::NoPermission(_) => Self::SecurityBlocked,
+            BizClawError::Tool(_) | BizClawError::ToolNotFound(_) => Self::ToolFailed,
+            BizClawError::InputTooLong(_) => Self::InputTooLong,
+            BizClawError::Provider(msg)
+            | BizClawError::Http(msg)
+            | BizClawError::Inference(msg) => {
+                let msg = msg.to_ascii_lowercase();
+                if CONTEXT_PHRASES.iter().any(|p| msg.contains(p)) {
+                    Self::ContextTooLong
+                } else if RATE_LIMIT_PHRASES.iter().any(|p| msg.contains(p)) {
+                    Self::RateLimited
+                } else {
+                    Self::ProviderUnavailable
+                }
+            }
+            BizClawError::Timeout(_) | BizClawError::ProviderNotFound(_) => {
+                Self::ProviderUnavailable
+            }
+            _ => Self::Internal,
+        }
+    }
+
+    /// Stable identifier for APIs and logs (`provider_unavailable`, …).
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::ProviderUnavailable => "provider_unavailable",
+            Self::RateLimited => "rate_limited",
+            Self::ToolFailed => "tool_failed",
+            Self::ContextTooLong => "context_too_long",
+            Self::SecurityBlocked => "security_blocked",
+            Self::InputTooLong => "input_too_long",
+            Self::Internal => "internal",
+        }
+    }
+
+    /// Whether the same request may succeed if tried again later.
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::ProviderUnavailable | Self::RateLimited)
+    }
+
+    /// Message to show the end user, with no error details.
+    pub fn user_message(self, locale: Locale) -> &'static str {
+        let key = match self {
+            Self::ProviderUnavailable => "agent.error.provider_unavailable",
+            Self::RateLimited => "agent.error.rate_limited",
+            Self::ToolFailed => "agent.error.tool_failed",
+            Self::ContextTooLong => "agent.error.context_too_long",
+            Self::SecurityBlocked => "agent.error.security_blocked",
+            Self::InputTooLong => "agent.error.input_too_long",
+            Self::Internal => "agent.error.internal",
+        };
+        t(locale, key)
+    }
+}
+
+const CONTEXT_PHRASES: &[&str] = &[
+    "context_length_exceeded",
+    "maximum context length",
+    "context window",
+    "prompt is too long",
+    "too many tokens",
+];
+
+const RATE_LIMIT_PHRASES: &[&str] = &[
+    "429",
+    "rate limit",
+    "rate_limit",
+    "too many requests",
+    "insufficient_quota",
+];
+
+/// Error returned by [`Agent::process`](crate::Agent::process) and friends.
+#[derive(Debug, thiserror::Error)]
+#[error("{source}")]
+pub struct AgentError {
+    pub kind: AgentErrorKind,
+    #[source]
+    pub source: BizClawError,
+}
+
+impl AgentError {
+    /// Message to show the end user, with no error details.
+    pub fn user_message(&self, locale: Locale) -> &'static str {
+        self.kind.user_message(locale)
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.kind.is_retryable()
+    }
+}
+
+impl From<BizClawError> for AgentError {
+    fn from(source: BizClawError) -> Self {
+        Self {
+            kind: AgentErrorKind::of(&source),
+            source,
+        }
+    }
+}
+
+impl From<AgentError> for BizClawError {
+    fn from(error: AgentError) -> Self {
+        error.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(error: BizClawError) -> AgentErrorKind {
+        AgentError::from(error).kind
+    }
+
+    #[test]
+    fn test_failures_map_to_categories() {
+        use AgentErrorKind::*;
+        let cases = [
+            (
+                BizClawError::Http("connection refused".into()),
+                ProviderUnavailable,
+            ),
+            (BizClawError::Timeout("60s".into()), ProviderUnavailable),
+            (
+                BizClawError::Provider(
+                    "openai API error 503 Service Unavailable: overloaded".into(),
+                ),
+                ProviderUnavailable,
+            ),
+            (
+                BizClawError::Provider("openai API error 429 Too Many Requests: slow down".into()),
+                RateLimited,
+            ),
+            (
+                BizClawError::RateLimited("30 requests/min".into()),
+                RateLimited,
+            ),
+            (
+                BizClawError::Provider(
+                    r#"openai API error 400: {"error":{"code":"context_length_exceeded"}}"#.into(),
+                ),
+                ContextTooLong,
+            ),
+            (
+                BizClawError::Provider("anthropic API error 400: prompt is too long".into()),
+                ContextTooLong,
+            ),
+            (BizClawError::Tool("shell exited with 1".into()), ToolFailed),
+            (BizClawError::ToolNotFound("browser".into()), ToolFailed),
+            (
+                BizClawError::Security("command blocked".into()),
+                SecurityBlocked,
+            ),
+            (
+                BizClawError::PermissionDenied("/etc/passwd".into()),
+                SecurityBlocked,
+            ),
+            (
+                BizClawError::InputTooLong("40000 characters, limit 32000".into()),
+                InputTooLong,
+            ),
+            (BizClawError::Config("no provider".into()), Internal),
+            (BizClawError::Memory("disk full".into()), Internal),
+        ];
+        for (error, expected) in cases {
+            let shown = error.to_string();
+            assert_eq!(kind(error), expected, "{shown}");
+        }
+    }
+
+    #[test]
+    fn test_user_message_hides_details() {
+        let error = AgentError::from(BizClawError::Provider(
+            "openai API error 500: upstream https://internal:8080 failed".into(),
+        ));
+        assert!(error.is_retryable());
+        // Logs keep the full error; users get a localized, detail-free message.
+        assert!(error.to_string().contains("internal:8080"));
+        for locale in [Locale::En, Locale::Vi] {
+            let message = error.user_message(locale);
+            assert!(!message.contains("internal:8080"));
+            assert!(
+                !message.starts_with("agent.error."),
+                "missing catalog entry"
+            );
+        }
+        assert!(!AgentErrorKind::SecurityBlocked.is_retryable());
+
+        // Converting back keeps the original error for `?` in BizClaw results.
+        let source: BizClawError = error.into();
+        assert!(matches!(source, BizClawError::Provider(_)));
+    }
+}
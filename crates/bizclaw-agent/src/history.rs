@@ -0,0 +1,166 @@
+//! Saved CLI conversations — one JSONL file per `bizclaw chat` session.
+//!
+//! Each line is a serialized [`Message`]. System messages (the system prompt
+//! and injected context) are not saved; they are rebuilt when the
+//! conversation is restored. Only the most recent `max_messages` are kept.
+
+use bizclaw_core::config::ChatHistoryConfig;
+use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::types::{Message, Role};
+use std::path::{Path, PathBuf};
+
+/// A directory of saved conversations.
+#[derive(Debug, Clone)]
+pub struct ChatHistory {
+    dir: PathBuf,
+    max_messages: usize,
+}
+
+impl ChatHistory {
+    pub fn new(dir: impl Into<PathBuf>, max_messages: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            max_messages: max_messages.max(1),
+        }
+    }
+
+    pub fn from_config(config: &ChatHistoryConfig) -> Self {
+        Self::new(config.dir(), config.max_messages)
+    }
+
+    /// A fresh, time-ordered session id, e.g. `20261016-091500-1a2b`.
+    pub fn new_session_id() -> String {
+        let suffix: String = uuid::Uuid::new_v4().simple().to_string()[..4].to_string();
+        format!("{}-{suffix}", chrono::Local::now().format("%Y%m%d-%H%M%S"))
+    }
+
+    /// File holding a session (names are restricted to safe characters).
+    pub fn path(&self, session: &str) -> Result<PathBuf> {
+        let valid = !session.is_empty()
+            && session
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(BizClawError::Other(format!(
+                "Invalid session name '{session}' (use letters, digits, '-' or '_')"
+            )));
+        }
+        Ok(self.dir.join(format!("{session}.jsonl")))
+    }
+
+    /// Write a session's non-system messages, keeping the newest `max_messages`.
+    pub fn save(&self, session: &str, messages: &[Message]) -> Result<PathBuf> {
+        let path = self.path(session)?;
+        let kept: Vec<&Message> = messages.iter().filter(|m| m.role != Role::System).collect();
+        let start = kept.len().saturating_sub(self.max_messages);
+        let mut out = String::new();
+        for message in &kept[start..] {
+            out.push_str(&serde_json::to_string(message)?);
+            out.push('\n');
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        // Write then rename so a crash never leaves a half-written session.
+        let tmp = path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, out)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(path)
+    }
+
+    /// Read a saved session. Unreadable lines are skipped.
+    pub fn load(&self, session: &str) -> Result<Vec<Message>> {
+        let path = self.path(session)?;
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| BizClawError::Other(format!("Cannot read session '{session}': {e}")))?;
+        Ok(text
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect())
+    }
+
+    /// Saved sessions, most recently written first.
+    pub fn list(&self) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut sessions: Vec<(std::time::SystemTime, String)> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let path = e.path();
+                let name = session_name(&path)?;
+                let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+                Some((modified, name))
+            })
+            .collect();
+        sessions.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.cmp(&a.1)));
+        sessions.into_iter().map(|(_, name)| name).collect()
+    }
+
+    /// The most recently written session, for `--resume`.
+    pub fn latest(&self) -> Option<String> {
+        self.list().into_iter().next()
+    }
+}
+
+fn session_name(path: &Path) -> Option<String> {
+    if path.extension()? != "jsonl" {
+        return None;
+    }
+    path.file_stem()?.to_str().map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_history(name: &str, max: usize) -> ChatHistory {
+        let dir =
+            std::env::temp_dir().join(format!("bizclaw-history-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        ChatHistory::new(dir, max)
+    }
+
+    #[test]
+    fn test_save_and_reload_conversation() {
+        let history = temp_history("roundtrip", 3);
+        let messages = vec![
+            Message::system("You are BizClaw."),
+            Message::user("Hi"),
+            Message::assistant("Hello!"),
+            Message::user("Price of SKU-1?"),
+            Message::assistant("120k VND"),
+        ];
+        history.save("work", &messages).unwrap();
+
+        let loaded = history.load("work").unwrap();
+        let contents: Vec<&str> = loaded.iter().map(|m| m.content.as_str()).collect();
+        // System prompt dropped, capped to the newest 3 messages.
+        assert_eq!(contents, vec!["Hello!", "Price of SKU-1?", "120k VND"]);
+        assert_eq!(loaded[0].role, Role::Assistant);
+
+        assert!(history.save("../escape", &messages).is_err());
+        assert!(history.load("missing").is_err());
+        let _ = std::fs::remove_dir_all(&history.dir);
+    }
+
+    #[test]
+    fn test_latest_is_most_recent_session() {
+        let history = temp_history("latest", 10);
+        assert!(history.latest().is_none());
+
+        let old = history.save("older", &[Message::user("a")]).unwrap();
+        history.save("newer", &[Message::user("b")]).unwrap();
+        // Make the ordering independent of filesystem timestamp granularity.
+        let past = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        std::fs::File::options()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(past)
+            .unwrap();
+
+        assert_eq!(history.latest().as_deref(), Some("newer"));
+        assert_eq!(history.list(), vec!["newer", "older"]);
+        let _ = std::fs::remove_dir_all(&history.dir);
+    }
+}
@@ -9,21 +9,35 @@
 //! - **Auto-compaction**: Summarizes long conversations to prevent context overflow
 //! - **Session management**: Thread isolation via session_id
 //! - **Context tracking**: Monitor conversation length and estimate token usage
+//! - **Branching**: Regenerate the last answer or edit a past message and re-run
 
+pub mod branch;
 pub mod context;
+pub mod datetime;
+pub mod dead_letter;
+pub mod delegation;
 pub mod discovery;
 pub mod engine;
+pub mod error;
+pub mod history;
 pub mod loop_detector;
 pub mod orchestrator;
+pub mod persona;
 pub mod proactive;
+pub mod welcome;
 
 use bizclaw_core::config::BizClawConfig;
 use bizclaw_core::error::Result;
+use bizclaw_core::text::truncate_chars;
 use bizclaw_core::traits::Provider;
 use bizclaw_core::traits::SecurityPolicy;
 use bizclaw_core::traits::memory::MemoryBackend;
-use bizclaw_core::traits::provider::GenerateParams;
-use bizclaw_core::types::{Message, OutgoingMessage};
+use bizclaw_core::traits::provider::{GenerateParams, OnText};
+use bizclaw_core::traits::tool::OnOutput;
+use bizclaw_core::types::{ImageContent, Message, OutgoingMessage, ProviderResponse, Refusal};
+use std::borrow::Cow;
+
+pub use error::{AgentError, AgentErrorKind};
 
 /// Prompt cache — caches serialized system prompt + tool definitions to avoid
 /// re-serializing on every request.
@@ -64,6 +78,117 @@ impl PromptCache {
     }
 }
 
+/// Receives tool output as it is produced: the tool name, then a chunk.
+pub type OnToolOutput<'a> = dyn Fn(&str, &str) + Send + Sync + 'a;
+
+/// Longest error message kept in tool usage stats.
+const MAX_TOOL_ERROR_CHARS: usize = 500;
+
+/// Ollama model used to embed knowledge search queries.
+const QUERY_EMBEDDING_MODEL: &str = "nomic-embed-text";
+
+/// Knowledge chunks embedded per turn, so new documents join vector search
+/// a batch at a time.
+const KNOWLEDGE_INDEX_BATCH: usize = 32;
+
+/// Longest a turn waits on the embedding endpoint, for the query and again
+/// for indexing, before going on without it.
+const EMBEDDING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Embedding client for knowledge search: Ollama at `OLLAMA_HOST`.
+fn query_embedder() -> bizclaw_knowledge::embeddings::EmbeddingClient {
+    use bizclaw_knowledge::embeddings::{EmbeddingClient, EmbeddingConfig};
+    EmbeddingClient::new(EmbeddingConfig {
+        provider: "ollama".into(),
+        endpoint: std::env::var("OLLAMA_HOST")
+            .unwrap_or_else(|_| "http://localhost:11434".to_string()),
+        model: QUERY_EMBEDDING_MODEL.into(),
+        api_key: String::new(),
+    })
+}
+
+/// Check `arguments` against the tool's declared schema before running it.
+/// Empty arguments count as `{}`, as some providers send them for tools
+/// without parameters.
+fn check_arguments(
+    tool: &dyn bizclaw_core::traits::Tool,
+    arguments: &str,
+) -> std::result::Result<(), String> {
+    let args: serde_json::Value = if arguments.trim().is_empty() {
+        serde_json::json!({})
+    } else {
+        serde_json::from_str(arguments).map_err(|e| format!("arguments are not valid JSON: {e}"))?
+    };
+    bizclaw_tools::registry::validate_args(&tool.definition(), &args)
+}
+
+/// Execute a tool and record the call (outcome + duration) in memory, so
+/// per-tool stats exist in standalone mode too. A failed write is logged,
+/// never surfaced to the model. Arguments that do not match the tool's
+/// schema are not executed; the model gets the problems back to retry.
+/// With `on_output`, the tool's output is passed on as it is produced.
+async fn run_tool(
+    tool: &dyn bizclaw_core::traits::Tool,
+    arguments: &str,
+    memory: &dyn MemoryBackend,
+    on_output: Option<&OnOutput<'_>>,
+) -> Result<bizclaw_core::types::ToolResult> {
+    let started = std::time::Instant::now();
+    let result = match check_arguments(tool, arguments) {
+        Ok(()) => match on_output {
+            Some(on_output) => tool.execute_streaming(arguments, on_output).await,
+            None => tool.execute(arguments).await,
+        },
+        Err(problems) => {
+            tracing::warn!("🚫 Rejected arguments for '{}': {problems}", tool.name());
+            Ok(bizclaw_core::types::ToolResult {
+                tool_call_id: String::new(),
+                output: format!(
+                    "Invalid arguments for tool '{}':\n{problems}\n\
+                     Call the tool again with arguments that match its parameter schema.",
+                    tool.name()
+                ),
+                success: false,
+                data: None,
+            })
+        }
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let error = match &result {
+        Ok(r) if r.success => None,
+        Ok(r) => Some(r.output.chars().take(MAX_TOOL_ERROR_CHARS).collect::<String>()),
+        Err(e) => Some(e.to_string().chars().take(MAX_TOOL_ERROR_CHARS).collect()),
+    };
+    if let Err(e) = memory
+        .record_tool_usage(tool.name(), error.is_none(), duration_ms, error.as_deref())
+        .await
+    {
+        tracing::warn!("Failed to record tool usage for '{}': {e}", tool.name());
+    }
+    result
+}
+
+/// Tool definitions to offer the provider. Providers without tool support
+/// get none, so the agent answers in plain text instead of failing.
+fn tools_for_provider(
+    provider: &dyn Provider,
+    defs: &[bizclaw_core::types::ToolDefinition],
+) -> Vec<bizclaw_core::types::ToolDefinition> {
+    if provider.capabilities().supports_tools {
+        defs.to_vec()
+    } else {
+        if !defs.is_empty() {
+            tracing::debug!(
+                "🚫 Provider '{}' does not support tools — sending {} tool(s) as none",
+                provider.name(),
+                defs.len()
+            );
+        }
+        vec![]
+    }
+}
+
 /// Context statistics for monitoring.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct ContextStats {
@@ -99,20 +224,39 @@ pub struct Agent {
     /// Knowledge base for RAG (optional, shared with gateway)
     knowledge:
         Option<std::sync::Arc<tokio::sync::Mutex<Option<bizclaw_knowledge::KnowledgeStore>>>>,
+    /// Embeds knowledge queries and chunks; cached once a data store is attached
+    embedder: tokio::sync::Mutex<bizclaw_knowledge::embeddings::EmbeddingClient>,
     /// Context statistics from last process() call
     last_stats: ContextStats,
     /// 3-Tier Memory: daily log manager for persisting compaction summaries
     daily_log: bizclaw_memory::brain::DailyLogManager,
     /// Tool loop detector — prevents infinite tool call loops
     loop_detector: loop_detector::LoopDetector,
+    /// Conversation tails discarded by regenerate / edit (most recent last)
+    branches: Vec<branch::ConversationBranch>,
+    /// Orchestration data store (traces, usage) when running under an orchestrator
+    store: Option<std::sync::Arc<dyn bizclaw_db::store::DataStore>>,
+    /// Reasoning-model thoughts behind the last answer (kept out of replies)
+    last_reasoning: Option<String>,
+    /// Provider safety refusal behind the last answer, if it was refused
+    last_refusal: Option<Refusal>,
+    /// Fallback provider that produced the last answer, if the configured one was unreachable
+    last_fallback: Option<String>,
+    /// Persona adopted through a handoff, with the agent's own parts
+    persona: Option<persona::ActivePersona>,
 }
 
+/// Max number of discarded branches kept in memory.
+const MAX_BRANCHES: usize = 20;
+
 impl Agent {
     /// Create a new agent from configuration (sync, no MCP).
-    pub fn new(config: BizClawConfig) -> Result<Self> {
+    pub fn new(mut config: BizClawConfig) -> Result<Self> {
+        bizclaw_providers::model_alias::apply(&mut config);
         let provider = bizclaw_providers::create_provider(&config)?;
         let memory = bizclaw_memory::create_memory(&config.memory)?;
-        let tools = bizclaw_tools::ToolRegistry::with_defaults();
+        let mut tools = bizclaw_tools::ToolRegistry::with_defaults();
+        tools.retain(|name| config.autonomy.allows_tool(name));
         let security = bizclaw_security::DefaultSecurityPolicy::new(config.autonomy.clone());
 
         // 3-Tier Memory: assemble brain context from workspace files
@@ -143,6 +287,7 @@ impl Agent {
             prompt_cache,
             session_id: "default".to_string(),
             knowledge: None,
+            embedder: tokio::sync::Mutex::new(query_embedder()),
             last_stats: ContextStats {
                 message_count: 1,
                 estimated_tokens: 0,
@@ -154,11 +299,18 @@ impl Agent {
             },
             daily_log,
             loop_detector: loop_detector::LoopDetector::new(),
+            branches: Vec::new(),
+            store: None,
+            last_reasoning: None,
+            last_refusal: None,
+            last_fallback: None,
+            persona: None,
         })
     }
 
     /// Create a new agent with MCP server support (async).
-    pub async fn new_with_mcp(config: BizClawConfig) -> Result<Self> {
+    pub async fn new_with_mcp(mut config: BizClawConfig) -> Result<Self> {
+        bizclaw_providers::model_alias::apply(&mut config);
         // CRITICAL: create_provider is sync and can block (e.g., brain GGUF loading).
         // Run it on a blocking thread so it doesn't stall the tokio runtime.
         let config_clone = config.clone();
@@ -207,6 +359,7 @@ impl Agent {
                 tracing::info!("✅ {} MCP tool(s) registered", total_mcp_tools);
             }
         }
+        tools.retain(|name| config.autonomy.allows_tool(name));
 
         // 3-Tier Memory: assemble brain context from workspace files
         let brain_ws = bizclaw_memory::brain::BrainWorkspace::default();
@@ -235,8 +388,15 @@ impl Agent {
             prompt_cache,
             session_id: "default".to_string(),
             knowledge: None,
+            embedder: tokio::sync::Mutex::new(query_embedder()),
             daily_log,
             loop_detector: loop_detector::LoopDetector::new(),
+            branches: Vec::new(),
+            store: None,
+            last_reasoning: None,
+            last_refusal: None,
+            last_fallback: None,
+            persona: None,
             last_stats: ContextStats {
                 message_count: 1,
                 estimated_tokens: 0,
@@ -271,7 +431,62 @@ impl Agent {
     /// Process a user message and generate a response.
     ///
     /// Uses Think-Act-Observe loop with Quality Gate evaluation.
-    pub async fn process(&mut self, user_message: &str) -> Result<String> {
+    pub async fn process(
+        &mut self,
+        user_message: &str,
+    ) -> std::result::Result<String, AgentError> {
+        self.run_turn(user_message, Vec::new(), None, None, None).await
+    }
+
+    /// Like [`Agent::process`], but model text is passed to `on_text` as it
+    /// streams in. Tool calls streamed alongside the text are executed as
+    /// usual and the turn continues with their results.
+    pub async fn process_stream(
+        &mut self,
+        user_message: &str,
+        on_text: &OnText<'_>,
+    ) -> std::result::Result<String, AgentError> {
+        self.run_turn(user_message, Vec::new(), Some(on_text), None, None).await
+    }
+
+    /// Like [`Agent::process_stream`], and the output of each tool the turn
+    /// runs is passed to `on_tool_output` as the tool produces it. The model
+    /// still sees each tool's complete result.
+    pub async fn process_stream_with_tools(
+        &mut self,
+        user_message: &str,
+        on_text: &OnText<'_>,
+        on_tool_output: &OnToolOutput<'_>,
+    ) -> std::result::Result<String, AgentError> {
+        self.run_turn(user_message, Vec::new(), Some(on_text), Some(on_tool_output), None)
+            .await
+    }
+
+    async fn run_turn(
+        &mut self,
+        user_message: &str,
+        images: Vec<ImageContent>,
+        on_text: Option<&OnText<'_>>,
+        on_tool_output: Option<&OnToolOutput<'_>>,
+        channel: Option<&str>,
+    ) -> std::result::Result<String, AgentError> {
+        // Oversized input is refused or cut before it reaches the provider
+        let (user_message, truncated_to) =
+            match context::check_input(user_message, &self.config.context) {
+                context::InputCheck::Fits => (Cow::Borrowed(user_message), None),
+                context::InputCheck::Truncated { text, kept } => {
+                    tracing::warn!("✂️ User message truncated to {kept} characters");
+                    (Cow::Owned(text), Some(kept))
+                }
+                context::InputCheck::Rejected { chars, limit } => {
+                    return Err(bizclaw_core::error::BizClawError::InputTooLong(format!(
+                        "{chars} characters, limit {limit}"
+                    ))
+                    .into());
+                }
+            };
+        let user_message: &str = &user_message;
+
         let mut compacted = false;
         let estimated_tokens = self.estimate_tokens();
         let max_context = self.config.brain.context_length as usize;
@@ -307,37 +522,70 @@ impl Agent {
             ));
         }
 
-        self.conversation.push(Message::user(user_message));
+        // Current date/time — replace last turn's line instead of piling them up
+        if self.config.datetime.enabled {
+            self.conversation
+                .retain(|m| !m.content.starts_with(datetime::DATETIME_MARKER));
+            let tz = match self.user_timezone().await {
+                Some(tz) => tz,
+                None => self.config.datetime.timezone.clone(),
+            };
+            self.conversation.push(Message::system(datetime::datetime_context(
+                chrono::Utc::now(),
+                &tz,
+                &self.config.datetime.format,
+            )));
+        }
+
+        self.conversation.push(Message::user(user_message).with_images(images));
 
-        // Trim conversation
-        if self.conversation.len() > 41 {
-            let system = self.conversation[0].clone();
-            let keep = self.conversation.len() - 40;
-            let tail: Vec<_> = self.conversation.drain(keep..).collect();
-            self.conversation.clear();
-            self.conversation.push(system);
-            self.conversation.extend(tail);
+        // Trim conversation to the configured number of turns
+        let dropped =
+            context::trim_turns(&mut self.conversation, self.config.context.max_history_turns);
+        if dropped > 0 {
+            tracing::debug!("Dropped {dropped} messages from the oldest turns");
         }
 
-        let tool_defs = self.prompt_cache.tool_defs(&self.tools).to_vec();
-        let params = GenerateParams {
-            model: self.config.default_model.clone(),
-            temperature: self.config.default_temperature,
-            max_tokens: self.config.brain.max_tokens,
-            top_p: 0.9,
-            stop: vec![],
-        };
+        let mut tool_defs =
+            tools_for_provider(self.provider.as_ref(), self.prompt_cache.tool_defs(&self.tools));
+        tool_defs.retain(|d| self.config.autonomy.allows_tool(&d.name));
+        let params = self.params_for(channel);
 
         // Think-Act-Observe Loop
         const MAX_ROUNDS: usize = 5;
         let mut final_content = String::new();
         let mut tool_rounds = 0;
+        let mut reasoning: Vec<String> = Vec::new();
+        self.last_refusal = None;
+        self.last_fallback = None;
 
         for round in 0..=MAX_ROUNDS {
             let tools = if round < MAX_ROUNDS { &tool_defs } else { &vec![] };
             tracing::debug!("🧠 Think round {}/{}", round + 1, MAX_ROUNDS);
 
-            let resp = self.provider.chat(&self.conversation, tools, &params).await?;
+            let resp = match on_text {
+                Some(on_text) => {
+                    self.provider
+                        .chat_stream(&self.conversation, tools, &params, on_text)
+                        .await?
+                }
+                None => self.provider.chat(&self.conversation, tools, &params).await?,
+            };
+            // Reasoning stays out of the conversation: it is not replayed to
+            // the model and never reaches the channel.
+            reasoning.extend(resp.reasoning.clone());
+            if resp.fallback.is_some() {
+                self.last_fallback = resp.fallback.clone();
+            }
+
+            if let Some(refusal) = resp.refusal {
+                tracing::warn!("🛑 Provider refused to answer ({})", refusal.reason);
+                final_content =
+                    bizclaw_core::i18n::t(self.config.locale(), "agent.refused").to_string();
+                self.conversation.push(Message::assistant(&final_content));
+                self.last_refusal = Some(refusal);
+                break;
+            }
 
             if resp.tool_calls.is_empty() {
                 final_content = resp.content.unwrap_or_else(|| "I'm not sure how to respond.".into());
@@ -360,34 +608,39 @@ impl Agent {
                     ));
                     continue;
                 }
-                // Security check for shell, execute_code, and file tools
-                let is_tool_blocked = match tc.function.name.as_str() {
-                    "shell" => {
-                        if let Ok(args) = serde_json::from_str::<serde_json::Value>(&tc.function.arguments) {
-                            if let Some(cmd) = args["command"].as_str() {
-                                !self.security.check_command(cmd).await.unwrap_or(false)
+                // Channel policy, then security checks for shell and file tools
+                let is_tool_blocked = !self.config.autonomy.allows_tool(&tc.function.name)
+                    || match tc.function.name.as_str() {
+                        "shell" => {
+                            if let Ok(args) = serde_json::from_str::<serde_json::Value>(&tc.function.arguments) {
+                                if let Some(cmd) = args["command"].as_str() {
+                                    !self.security.check_command(cmd).await.unwrap_or(false)
+                                } else { false }
                             } else { false }
-                        } else { false }
-                    }
-                    "file" => {
-                        if let Ok(args) = serde_json::from_str::<serde_json::Value>(&tc.function.arguments) {
-                            let path = args["path"].as_str().unwrap_or("");
-                            !self.security.check_path(path).await.unwrap_or(false)
-                        } else { false }
-                    }
-                    _ => false,
-                };
+                        }
+                        "file" => {
+                            if let Ok(args) = serde_json::from_str::<serde_json::Value>(&tc.function.arguments) {
+                                let path = args["path"].as_str().unwrap_or("");
+                                !self.security.check_path(path).await.unwrap_or(false)
+                            } else { false }
+                        }
+                        _ => false,
+                    };
                 if is_tool_blocked {
                     results.push(Message::tool(format!("Permission denied: '{}'", tc.function.name), &tc.id));
                     continue;
                 }
                 if let Some(tool) = self.tools.get(&tc.function.name) {
-                    match tool.execute(&tc.function.arguments).await {
+                    let name = tc.function.name.as_str();
+                    let forward = on_tool_output.map(|f| move |chunk: &str| f(name, chunk));
+                    let on_output = forward.as_ref().map(|f| f as &OnOutput<'_>);
+                    match run_tool(tool, &tc.function.arguments, self.memory.as_ref(), on_output).await {
                         Ok(r) => {
-                            let out = if r.output.len() > 4000 {
-                                format!("{}...[truncated]", &r.output[..4000])
-                            } else { r.output };
-                            results.push(Message::tool(&out, &tc.id));
+                            let out = self.condense_tool_result(&tc.function.name, r.render()).await;
+                            // Images travel as attachments; the provider decides
+                            // whether the model sees them or a text note.
+                            let images = r.image_content().into_iter().collect();
+                            results.push(Message::tool(&out, &tc.id).with_images(images));
                         }
                         Err(e) => results.push(Message::tool(format!("Error: {e}"), &tc.id)),
                     }
@@ -402,6 +655,8 @@ impl Agent {
                 content: resp.content.clone().unwrap_or_default(),
                 name: None, tool_call_id: None,
                 tool_calls: Some(resp.tool_calls.clone()),
+                images: Vec::new(),
+                pinned: false,
             });
             for r in results { self.conversation.push(r); }
             tracing::debug!("🔍 Observe — looping to Think");
@@ -412,9 +667,10 @@ impl Agent {
             self.conversation.push(Message::assistant(&final_content));
         }
 
-        // Quality Gate
+        // Quality Gate (a refusal is not revised into an answer)
         if let Some(ref gate) = self.config.quality_gate
-            && !gate.evaluator_prompt.is_empty() {
+            && !gate.evaluator_prompt.is_empty()
+            && self.last_refusal.is_none() {
                 let max_rev = gate.max_revisions.unwrap_or(2) as usize;
                 for rev in 0..max_rev {
                     let ep = format!("{}\n\nUSER: {}\nRESPONSE: {}\n\nReply APPROVED or REVISION_NEEDED: <feedback>",
@@ -423,6 +679,7 @@ impl Agent {
                     let epar = GenerateParams {
                         model: gate.evaluator_model.clone().unwrap_or(self.config.default_model.clone()),
                         temperature: 0.3, max_tokens: 500, top_p: 0.9, stop: vec![],
+                        tool_choice: Default::default(), json_mode: false, seed: None,
                     };
                     match self.provider.chat(&em, &[], &epar).await {
                         Ok(er) => {
@@ -445,6 +702,7 @@ impl Agent {
             }
 
         // Save memory + update stats
+        self.last_reasoning = (!reasoning.is_empty()).then(|| reasoning.join("\n\n"));
         self.save_memory(user_message, &final_content).await;
         let new_tokens = self.estimate_tokens();
         self.last_stats = ContextStats {
@@ -455,9 +713,58 @@ impl Agent {
             session_id: self.session_id.clone(),
         };
 
+        if let Some(kept) = truncated_to {
+            let notice = bizclaw_core::i18n::tr(
+                self.config.locale(),
+                "agent.input_truncated",
+                &[("limit", &kept.to_string())],
+            );
+            return Ok(format!("{notice}\n\n{final_content}"));
+        }
         Ok(final_content)
     }
 
+    /// Regenerate the last answer: drop the last user turn and everything after
+    /// it, then re-run that same user message.
+    pub async fn regenerate_last(&mut self) -> std::result::Result<String, AgentError> {
+        let index = branch::last_user_index(&self.conversation).ok_or_else(|| {
+            bizclaw_core::error::BizClawError::Other("No user message to regenerate".into())
+        })?;
+        let user_message = self.conversation[index].content.clone();
+        self.fork_conversation(index)?;
+        self.process(&user_message).await
+    }
+
+    /// Edit the user message at `index` and continue the conversation from there.
+    /// Everything from `index` onward is replaced by the new turn.
+    pub async fn edit_and_rerun(
+        &mut self,
+        index: usize,
+        new_content: &str,
+    ) -> std::result::Result<String, AgentError> {
+        self.fork_conversation(index)?;
+        self.process(new_content).await
+    }
+
+    /// Truncate the conversation at `index`, keeping the removed tail as a branch.
+    fn fork_conversation(&mut self, index: usize) -> Result<()> {
+        let forked = branch::fork_at(&mut self.conversation, index).ok_or_else(|| {
+            bizclaw_core::error::BizClawError::Other(format!(
+                "Message {index} is not a user message in this conversation"
+            ))
+        })?;
+        tracing::info!("🌿 Forked conversation at message {} ({} messages moved to branch)", index, forked.messages.len());
+        self.branches.push(forked);
+        if self.branches.len() > MAX_BRANCHES {
+            self.branches.remove(0);
+        }
+        Ok(())
+    }
+
+    /// Previously discarded conversation branches (oldest first).
+    pub fn branches(&self) -> &[branch::ConversationBranch] {
+        &self.branches
+    }
 
     /// Search the knowledge base for relevant context.
     /// Uses hybrid search (keyword + vector) when embeddings are available.
@@ -465,9 +772,13 @@ impl Agent {
         // IMPORTANT: Get embedding BEFORE acquiring kb lock.
         // rusqlite::Connection is not Send, so the MutexGuard cannot be
         // held across .await points (like the reqwest call for embeddings).
+        let kb_arc = self.knowledge.as_ref()?;
         let query_embedding = self.get_query_embedding(query).await;
+        // Embeddings work: bring chunks added since the last turn into vector search.
+        if query_embedding.is_some() {
+            self.index_knowledge().await;
+        }
 
-        let kb_arc = self.knowledge.as_ref()?;
         let kb_lock = kb_arc.lock().await;
         let kb = kb_lock.as_ref()?;
 
@@ -509,47 +820,61 @@ impl Agent {
         Some(context)
     }
 
-    /// Generate embedding for a query string (via Ollama or configured provider).
+    /// Generate embedding for a query string (via Ollama).
+    /// Served from the embedding cache when the data store has it.
     /// Returns None if embeddings are not available.
     async fn get_query_embedding(&self, query: &str) -> Option<Vec<f32>> {
-        // Try Ollama endpoint for embeddings
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .ok()?;
-
-        let endpoint = std::env::var("OLLAMA_HOST")
-            .unwrap_or_else(|_| "http://localhost:11434".to_string());
-
-        let body = serde_json::json!({
-            "model": "nomic-embed-text",
-            "input": [query]
-        });
-
-        let resp = client
-            .post(format!("{}/api/embed", endpoint))
-            .json(&body)
-            .send()
-            .await
-            .ok()?;
+        let mut embedder = self.embedder.lock().await;
+        match tokio::time::timeout(EMBEDDING_TIMEOUT, embedder.embed_one(query)).await {
+            Ok(embedding) => embedding.ok().filter(|v| !v.is_empty()),
+            Err(_) => {
+                tracing::warn!("Query embedding timed out after {}s", EMBEDDING_TIMEOUT.as_secs());
+                None
+            }
+        }
+    }
 
-        if !resp.status().is_success() {
-            return None;
+    /// Embed knowledge chunks that have no embedding yet, one batch per call.
+    async fn index_knowledge(&self) {
+        let Some(kb) = &self.knowledge else {
+            return;
+        };
+        let mut embedder = self.embedder.lock().await;
+        let indexing =
+            bizclaw_knowledge::embeddings::index_chunks(kb, &mut embedder, KNOWLEDGE_INDEX_BATCH);
+        match tokio::time::timeout(EMBEDDING_TIMEOUT, indexing).await {
+            Ok(Ok(0)) => {}
+            Ok(Ok(n)) => tracing::debug!("Knowledge RAG: embedded {n} chunk(s)"),
+            Ok(Err(e)) => tracing::warn!("Knowledge chunk embedding failed: {e}"),
+            Err(_) => tracing::warn!("Knowledge chunk embedding timed out; retrying next turn"),
         }
+    }
 
-        let result: serde_json::Value = resp.json().await.ok()?;
-        let embeddings = result.get("embeddings")?.as_array()?;
-        let first = embeddings.first()?.as_array()?;
-        let vec: Vec<f32> = first
-            .iter()
-            .filter_map(|v: &serde_json::Value| v.as_f64().map(|f| f as f32))
-            .collect();
+    /// The user's timezone, if one was saved to memory.
+    async fn user_timezone(&self) -> Option<String> {
+        let entry = self.memory.get(datetime::USER_TIMEZONE_MEMORY_ID).await.ok()??;
+        let tz = entry.content.trim().to_string();
+        datetime::parse_timezone(&tz).map(|_| tz)
+    }
 
-        if vec.is_empty() {
-            None
-        } else {
-            Some(vec)
+    /// Remember the user's timezone (e.g. "Asia/Ho_Chi_Minh" or "+07:00").
+    pub async fn set_user_timezone(&self, tz: &str) -> Result<()> {
+        if datetime::parse_timezone(tz).is_none() {
+            return Err(bizclaw_core::error::BizClawError::Config(format!(
+                "Unknown timezone '{tz}'"
+            )));
         }
+        let now = chrono::Utc::now();
+        self.memory
+            .save(bizclaw_core::traits::memory::MemoryEntry {
+                id: datetime::USER_TIMEZONE_MEMORY_ID.into(),
+                content: tz.trim().to_string(),
+                metadata: serde_json::json!({"type": "personal", "key": "timezone"}),
+                embedding: None,
+                created_at: now,
+                updated_at: now,
+            })
+            .await
     }
 
     /// Retrieve relevant past conversations from memory (FTS5-powered).
@@ -642,6 +967,86 @@ impl Agent {
         }
     }
 
+    /// Shrink a tool result that would crowd out the context window
+    /// (`context.tool_result_max_tokens`): summarize it with the model if
+    /// `context.summarize_tool_results` is set, else truncate it with a
+    /// marker. With `context.stash_tool_results` the full text goes to memory.
+    async fn condense_tool_result(&self, tool_name: &str, output: String) -> String {
+        let max_tokens = self.config.context.tool_result_max_tokens;
+        let tokens = context::estimate_tokens(&output);
+        if max_tokens == 0 || tokens <= max_tokens {
+            return output;
+        }
+        tracing::info!("✂️ Condensing '{tool_name}' result (~{tokens} tokens, limit {max_tokens})");
+
+        let summary = if self.config.context.summarize_tool_results {
+            self.summarize_tool_result(tool_name, &output, max_tokens).await
+        } else {
+            None
+        };
+        let mut condensed =
+            summary.unwrap_or_else(|| context::truncate_tool_result(&output, max_tokens));
+
+        if self.config.context.stash_tool_results {
+            let id = uuid::Uuid::new_v4().to_string();
+            let entry = bizclaw_core::traits::memory::MemoryEntry {
+                id: id.clone(),
+                content: format!("Tool result ({tool_name}):\n{output}"),
+                metadata: serde_json::json!({
+                    "session_id": self.session_id,
+                    "kind": "tool_result",
+                    "tool": tool_name,
+                }),
+                embedding: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            };
+            match self.memory.save(entry).await {
+                Ok(()) => condensed.push_str(&format!(
+                    "\n[Full result saved to memory as {id}; use memory_search for details.]"
+                )),
+                Err(e) => tracing::warn!("Failed to stash '{tool_name}' result: {e}"),
+            }
+        }
+        condensed
+    }
+
+    /// Ask the model to summarize an oversized tool result. The request
+    /// itself is capped at half the context window. `None` on failure.
+    async fn summarize_tool_result(
+        &self,
+        tool_name: &str,
+        output: &str,
+        max_tokens: usize,
+    ) -> Option<String> {
+        let budget = (self.config.brain.context_length as usize / 2).max(max_tokens);
+        let prompt = format!(
+            "Summarize this output of the '{tool_name}' tool in at most {max_tokens} tokens. \
+             Keep every fact, number, name and error a follow-up answer may need.\n\n{}",
+            context::truncate_tool_result(output, budget)
+        );
+        let messages = vec![
+            Message::system("You condense tool output. Reply with the summary only."),
+            Message::user(prompt),
+        ];
+        let params = GenerateParams {
+            max_tokens: max_tokens as u32,
+            temperature: 0.2,
+            stop: Vec::new(),
+            ..self.params_for(None)
+        };
+        match self.provider.chat(&messages, &[], &params).await {
+            Ok(resp) => resp
+                .content
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| format!("[Summary of {} bytes of '{tool_name}' output]\n{s}", output.len())),
+            Err(e) => {
+                tracing::warn!("Tool result summary failed, truncating instead: {e}");
+                None
+            }
+        }
+    }
+
     /// Public wrapper to save streamed conversations to memory.
     pub async fn save_memory_public(&self, user_msg: &str, assistant_msg: &str) {
         self.save_memory(user_msg, assistant_msg).await;
@@ -664,6 +1069,9 @@ impl Agent {
         // Create a summary of old messages
         let mut summary_parts = Vec::new();
         for msg in &old_messages {
+            if context::is_pinned(msg) {
+                continue; // kept verbatim below
+            }
             let prefix = match msg.role {
                 bizclaw_core::types::Role::User => "User",
                 bizclaw_core::types::Role::Assistant => "AI",
@@ -672,7 +1080,7 @@ impl Agent {
             };
             // Take first 100 chars of each message
             let content = if msg.content.len() > 100 {
-                format!("{}...", &msg.content[..100])
+                format!("{}...", truncate_chars(&msg.content, 100))
             } else {
                 msg.content.clone()
             };
@@ -685,9 +1093,15 @@ impl Agent {
             summary_parts.join("\n")
         );
 
-        // Rebuild conversation: system + summary + recent
+        // Rebuild conversation: system + pinned + summary + recent
+        let pinned: Vec<_> = old_messages
+            .iter()
+            .filter(|m| context::is_pinned(m))
+            .cloned()
+            .collect();
         self.conversation.clear();
         self.conversation.push(system);
+        self.conversation.extend(pinned);
         self.conversation.push(Message::system(&summary));
         self.conversation.extend(recent);
 
@@ -707,28 +1121,143 @@ impl Agent {
     fn estimate_tokens(&self) -> usize {
         self.conversation
             .iter()
-            .map(|m| {
-                let chars = m.content.len();
-                // Rough estimate: mix of English and Vietnamese
-                chars / 3
-            })
+            .map(|m| context::estimate_tokens(&m.content))
             .sum()
     }
 
+    /// Process a message that arrived via `channel`, using that channel's
+    /// system prompt (`identity.channel_prompts`) and generation options
+    /// (`generation.channels`) for this turn only.
+    pub async fn process_from(
+        &mut self,
+        channel: &str,
+        user_message: &str,
+    ) -> std::result::Result<String, AgentError> {
+        self.process_from_with_images(channel, user_message, Vec::new()).await
+    }
+
+    /// Like [`Agent::process_from`] for a whole channel message, including
+    /// any images sent with it. Providers without vision get a short note
+    /// in place of each image.
+    pub async fn process_incoming(
+        &mut self,
+        msg: &bizclaw_core::types::IncomingMessage,
+    ) -> std::result::Result<String, AgentError> {
+        self.process_from_with_images(&msg.channel, &msg.content, msg.images.clone())
+            .await
+    }
+
+    async fn process_from_with_images(
+        &mut self,
+        channel: &str,
+        user_message: &str,
+        images: Vec<ImageContent>,
+    ) -> std::result::Result<String, AgentError> {
+        // The channel's policy applies to this turn only, so an agent shared
+        // by several channels (the gateway) keeps its own autonomy elsewhere.
+        let saved = self.config.channel.policies.contains_key(channel).then(|| {
+            let autonomy = self.config.channel.autonomy_for(channel, &self.config.autonomy);
+            let security = bizclaw_security::DefaultSecurityPolicy::new(autonomy.clone());
+            (
+                std::mem::replace(&mut self.config.autonomy, autonomy),
+                std::mem::replace(&mut self.security, security),
+            )
+        });
+        let result = self.run_channel_turn(channel, user_message, images).await;
+        if let Some((autonomy, security)) = saved {
+            self.config.autonomy = autonomy;
+            self.security = security;
+        }
+        result
+    }
+
+    async fn run_channel_turn(
+        &mut self,
+        channel: &str,
+        user_message: &str,
+        images: Vec<ImageContent>,
+    ) -> std::result::Result<String, AgentError> {
+        let Some(prompt) = self.config.identity.channel_prompt(channel) else {
+            return self.run_turn(user_message, images, None, None, Some(channel)).await;
+        };
+        let brain_context = bizclaw_memory::brain::BrainWorkspace::default().assemble_brain();
+        let channel_prompt = if brain_context.trim().is_empty() {
+            prompt.to_string()
+        } else {
+            format!("{}\n\n{}", prompt, brain_context)
+        };
+        let default_prompt =
+            std::mem::replace(&mut self.conversation[0], Message::system(&channel_prompt));
+        let result = self.run_turn(user_message, images, None, None, Some(channel)).await;
+        self.conversation[0] = default_prompt;
+        result
+    }
+
     /// Process incoming message and create an outgoing response.
     pub async fn handle_incoming(
         &mut self,
         msg: &bizclaw_core::types::IncomingMessage,
     ) -> Result<OutgoingMessage> {
-        let response = self.process(&msg.content).await?;
+        let response = self.process_incoming(msg).await?;
+        // In groups, reply to the triggering message so the answer stays in context.
+        let reply_to_message_id = match msg.thread_type {
+            bizclaw_core::types::ThreadType::Group => msg.message_id.clone(),
+            bizclaw_core::types::ThreadType::Direct => None,
+        };
         Ok(OutgoingMessage {
             thread_id: msg.thread_id.clone(),
             content: response,
             thread_type: msg.thread_type.clone(),
             reply_to: None,
+            reply_to_message_id,
+            quote: None,
         })
     }
 
+    /// Generation parameters from the agent's config (model, temperature, max tokens).
+    pub fn default_params(&self) -> GenerateParams {
+        self.params_for(None)
+    }
+
+    /// Like [`default_params`](Self::default_params), with the `generation`
+    /// overrides for `channel` applied.
+    pub fn params_for(&self, channel: Option<&str>) -> GenerateParams {
+        let generation = &self.config.generation;
+        let max_tokens = match generation.max_tokens_for(channel) {
+            0 => self.config.brain.max_tokens,
+            n => n,
+        };
+        GenerateParams {
+            model: self.config.default_model.clone(),
+            temperature: self.config.default_temperature,
+            max_tokens,
+            top_p: 0.9,
+            stop: generation.stop_for(channel).to_vec(),
+            tool_choice: Default::default(),
+            json_mode: false,
+            seed: None,
+        }
+    }
+
+    /// Raw completion of a single prompt with the agent's provider — no system
+    /// prompt, history, memory or tools. Used by the llama.cpp-style `/completion` API.
+    pub async fn complete(&self, prompt: &str, params: &GenerateParams) -> Result<ProviderResponse> {
+        self.provider.chat(&[Message::user(prompt)], &[], params).await
+    }
+
+    /// [`complete`](Self::complete), passing text to `on_text` as the provider
+    /// streams it.
+    pub async fn complete_stream(
+        &self,
+        prompt: &str,
+        params: &GenerateParams,
+        on_text: &OnText<'_>,
+    ) -> Result<ProviderResponse> {
+        self.provider
+            .chat_stream(&[Message::user(prompt)], &[], params, on_text)
+            .await
+    }
+
     /// Get provider name.
     pub fn provider_name(&self) -> &str {
         self.provider.name()
@@ -762,23 +1291,1118 @@ impl Agent {
         }
     }
 
+    /// Attach the orchestration data store. Enables the `usage_stats` tool,
+    /// which reports usage recorded under `agent_name`, and the `notes` and
+    /// `message_agent` tools.
+    pub fn set_store(
+        &mut self,
+        agent_name: &str,
+        store: std::sync::Arc<dyn bizclaw_db::store::DataStore>,
+    ) {
+        self.tools.register_usage_stats(agent_name, store.clone());
+        self.tools.register_notes(agent_name, store.clone());
+        self.tools.register_message_agent(agent_name, store.clone());
+        self.tools.retain(|name| self.config.autonomy.allows_tool(name));
+        self.prompt_cache = PromptCache::new(&self.config.identity.system_prompt, &self.tools);
+        let cache_config = bizclaw_db::EmbeddingCacheConfig::from_memory_config(&self.config.memory);
+        if let Some(config) = cache_config {
+            let cache = bizclaw_db::StoreEmbeddingCache::new(store.clone(), config);
+            *self.embedder.get_mut() = query_embedder().with_cache(std::sync::Arc::new(cache));
+        }
+        self.store = Some(store);
+    }
+
+    /// Apply `channel`'s policy from `[channel.policies]`: a stricter
+    /// autonomy level and fewer tools than the global config, if configured.
+    pub fn restrict_to_channel(&mut self, channel: &str) {
+        self.config.autonomy = self
+            .config
+            .channel
+            .autonomy_for(channel, &self.config.autonomy);
+        self.security = bizclaw_security::DefaultSecurityPolicy::new(self.config.autonomy.clone());
+        self.tools.retain(|name| self.config.autonomy.allows_tool(name));
+        self.prompt_cache = PromptCache::new(&self.config.identity.system_prompt, &self.tools);
+    }
+
+    /// Get the attached data store, if any.
+    pub fn store(&self) -> Option<&std::sync::Arc<dyn bizclaw_db::store::DataStore>> {
+        self.store.as_ref()
+    }
+
     /// Get total tool count (native + MCP).
     pub fn tool_count(&self) -> usize {
         self.tools.list().len()
     }
 
+    /// Per-tool call stats recorded by the memory backend, most used first.
+    pub async fn tool_usage(&self) -> Result<Vec<bizclaw_core::traits::memory::ToolUsage>> {
+        self.memory.tool_usage().await
+    }
+
     /// Get conversation history.
     pub fn conversation(&self) -> &[Message] {
         &self.conversation
     }
 
+    /// Pin a memory into the conversation. Unlike injected context, it is
+    /// never dropped when old turns are trimmed.
+    pub fn pin_memory(&mut self, text: &str) {
+        // After the system prompt and any earlier pinned memories.
+        let at = 1 + self.conversation[1..]
+            .iter()
+            .take_while(|m| context::is_pinned(m))
+            .count();
+        self.conversation.insert(at, context::pinned_memory(text));
+    }
+
+    /// Pin or unpin the message at `index` in [`Agent::conversation`]. Pinned
+    /// messages are kept when old turns are trimmed or compacted. Only user
+    /// messages and plain assistant replies can be pinned: the system prompt
+    /// (index 0) is always kept, and tool calls and results only make sense
+    /// together.
+    pub fn set_pinned(&mut self, index: usize, pinned: bool) -> Result<()> {
+        match self.conversation.get_mut(index) {
+            Some(message) if index > 0 && (!pinned || context::can_pin(message)) => {
+                message.pinned = pinned;
+                Ok(())
+            }
+            Some(_) if index > 0 => Err(bizclaw_core::error::BizClawError::Other(format!(
+                "Message {index} is not a user message or plain assistant reply and cannot be pinned"
+            ))),
+            _ => Err(bizclaw_core::error::BizClawError::Other(format!(
+                "No message {index} to pin in this conversation"
+            ))),
+        }
+    }
+
+    /// Pin the latest user message. Returns its index, or `None` if the
+    /// conversation has no user message yet.
+    pub fn pin_last_user_message(&mut self) -> Option<usize> {
+        let index = self
+            .conversation
+            .iter()
+            .rposition(|m| m.role == bizclaw_core::types::Role::User)?;
+        self.conversation[index].pinned = true;
+        Some(index)
+    }
+
+    /// Unpin every pinned message. Returns how many were unpinned.
+    pub fn unpin_all(&mut self) -> usize {
+        let mut count = 0;
+        for message in self.conversation.iter_mut().filter(|m| m.pinned) {
+            message.pinned = false;
+            count += 1;
+        }
+        count
+    }
+
     /// Clear conversation history (keep system prompt).
     pub fn clear_conversation(&mut self) {
         self.conversation.truncate(1);
     }
 
+    /// Replace the conversation with saved messages (keeps the system prompt;
+    /// system messages in `messages` are ignored).
+    pub fn restore_conversation(&mut self, messages: Vec<Message>) {
+        self.clear_conversation();
+        self.conversation
+            .extend(messages.into_iter().filter(|m| m.role != bizclaw_core::types::Role::System));
+    }
+
     /// Get last context statistics.
     pub fn context_stats(&self) -> &ContextStats {
         &self.last_stats
     }
+
+    /// Reasoning the model produced for the last answer, for traces and debugging.
+    pub fn last_reasoning(&self) -> Option<&str> {
+        self.last_reasoning.as_deref()
+    }
+
+    /// Why the last answer was refused, if the provider declined it. The
+    /// reply itself is the localized `agent.refused` message.
+    pub fn last_refusal(&self) -> Option<&Refusal> {
+        self.last_refusal.as_ref()
+    }
+
+    /// The fallback provider (e.g. `brain`) that answered the last message
+    /// because the configured provider was unreachable.
+    pub fn last_fallback(&self) -> Option<&str> {
+        self.last_fallback.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bizclaw_core::traits::provider::ProviderCapabilities;
+    use bizclaw_core::types::{ModelInfo, ProviderResponse, ToolDefinition};
+
+    struct StubProvider {
+        caps: ProviderCapabilities,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for StubProvider {
+        fn name(&self) -> &str {
+            "stub"
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            Ok(ProviderResponse::text("ok"))
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            self.caps
+        }
+    }
+
+    fn defs() -> Vec<ToolDefinition> {
+        vec![ToolDefinition {
+            name: "shell".into(),
+            description: "Run a command".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }]
+    }
+
+    struct FlakyTool;
+
+    #[async_trait::async_trait]
+    impl bizclaw_core::traits::Tool for FlakyTool {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn definition(&self) -> ToolDefinition {
+            ToolDefinition {
+                name: "flaky".into(),
+                description: "Succeeds, fails softly or errors".into(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "required": ["mode"],
+                    "properties": {"mode": {"type": "string", "enum": ["ok", "soft", "fail"]}}
+                }),
+            }
+        }
+
+        async fn execute(&self, arguments: &str) -> Result<bizclaw_core::types::ToolResult> {
+            let args: serde_json::Value = serde_json::from_str(arguments).unwrap();
+            match args["mode"].as_str() {
+                Some("ok") => Ok(bizclaw_core::types::ToolResult {
+                    tool_call_id: String::new(),
+                    output: "done".into(),
+                    success: true,
+                    data: None,
+                }),
+                Some("soft") => Ok(bizclaw_core::types::ToolResult {
+                    tool_call_id: String::new(),
+                    output: "not found".into(),
+                    success: false,
+                    data: None,
+                }),
+                _ => Err(bizclaw_core::error::BizClawError::Tool("boom".into())),
+            }
+        }
+    }
+
+    const OK: &str = r#"{"mode": "ok"}"#;
+
+    #[tokio::test]
+    async fn test_tool_calls_update_usage_stats() {
+        let memory = bizclaw_memory::sqlite::SqliteMemory::in_memory().unwrap();
+        assert!(run_tool(&FlakyTool, OK, &memory, None).await.is_ok());
+        assert!(run_tool(&FlakyTool, r#"{"mode": "soft"}"#, &memory, None).await.is_ok());
+        assert!(run_tool(&FlakyTool, r#"{"mode": "fail"}"#, &memory, None).await.is_err());
+        run_tool(&FlakyTool, OK, &memory, None).await.unwrap();
+
+        let stats = memory.tool_usage().await.unwrap();
+        assert_eq!(stats.len(), 1);
+        let flaky = &stats[0];
+        assert_eq!(flaky.tool_name, "flaky");
+        assert_eq!(flaky.usage_count, 4);
+        assert_eq!(flaky.success_count, 2);
+        assert_eq!(flaky.failure_count, 2);
+        assert!(flaky.last_error.as_deref().unwrap().contains("boom"));
+        assert!(flaky.avg_duration_ms < 1000);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments_returned_to_model() {
+        let memory = bizclaw_memory::sqlite::SqliteMemory::in_memory().unwrap();
+
+        // Wrong type: rejected before execute() could panic on it.
+        let result = run_tool(&FlakyTool, r#"{"mode": 3}"#, &memory, None).await.unwrap();
+        assert!(!result.success);
+        assert!(result.output.starts_with("Invalid arguments for tool 'flaky':"));
+        assert!(result.output.contains("arguments.mode: expected string, got number"));
+
+        let result = run_tool(&FlakyTool, "{}", &memory, None).await.unwrap();
+        assert!(result.output.contains("missing required argument `mode`"));
+        let result = run_tool(&FlakyTool, "{mode: ok", &memory, None).await.unwrap();
+        assert!(result.output.contains("arguments are not valid JSON"));
+
+        // Valid arguments pass through to the tool.
+        assert_eq!(run_tool(&FlakyTool, OK, &memory, None).await.unwrap().output, "done");
+        let flaky = &memory.tool_usage().await.unwrap()[0];
+        assert_eq!(flaky.failure_count, 3);
+        assert_eq!(flaky.success_count, 1);
+    }
+
+    /// Replays canned Anthropic SSE streams, one per model call, in small
+    /// chunks so tool input JSON is split across deltas and reads.
+    struct AnthropicStreamStub {
+        streams: std::sync::Mutex<std::collections::VecDeque<&'static str>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for AnthropicStreamStub {
+        fn name(&self) -> &str {
+            "anthropic"
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            Err(bizclaw_core::error::BizClawError::Provider("stream only".into()))
+        }
+
+        async fn chat_stream(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+            on_text: &OnText<'_>,
+        ) -> Result<ProviderResponse> {
+            use bizclaw_providers::anthropic_stream::{SseDecoder, StreamAccumulator};
+            let stream = self.streams.lock().unwrap().pop_front().expect("unexpected call");
+            let mut decoder = SseDecoder::default();
+            let mut acc = StreamAccumulator::default();
+            for chunk in stream.as_bytes().chunks(5) {
+                for event in decoder.push(chunk) {
+                    if let Some(text) = acc.apply(&event)? {
+                        on_text(&text);
+                    }
+                }
+            }
+            acc.finish()
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    /// Records the arguments of every call.
+    struct RecordingTool(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+    #[async_trait::async_trait]
+    impl bizclaw_core::traits::Tool for RecordingTool {
+        fn name(&self) -> &str {
+            "weather"
+        }
+
+        fn definition(&self) -> ToolDefinition {
+            ToolDefinition {
+                name: "weather".into(),
+                description: "Weather forecast".into(),
+                parameters: serde_json::json!({"type": "object"}),
+            }
+        }
+
+        async fn execute(&self, arguments: &str) -> Result<bizclaw_core::types::ToolResult> {
+            self.0.lock().unwrap().push(arguments.to_string());
+            Ok(bizclaw_core::types::ToolResult {
+                tool_call_id: String::new(),
+                output: "Sunny, 31°C".into(),
+                success: true,
+                data: None,
+            })
+        }
+    }
+
+    fn test_agent(provider: Box<dyn Provider>, tools: bizclaw_tools::ToolRegistry) -> Agent {
+        let config = BizClawConfig::default();
+        let prompt_cache = PromptCache::new("You are helpful.", &tools);
+        Agent {
+            provider,
+            memory: Box::new(bizclaw_memory::sqlite::SqliteMemory::in_memory().unwrap()),
+            tools,
+            security: bizclaw_security::DefaultSecurityPolicy::new(config.autonomy.clone()),
+            injection_scanner: bizclaw_security::injection::InjectionScanner::new(),
+            conversation: vec![Message::system("You are helpful.")],
+            prompt_cache,
+            session_id: "default".into(),
+            knowledge: None,
+            embedder: tokio::sync::Mutex::new(query_embedder()),
+            last_stats: ContextStats {
+                message_count: 1,
+                estimated_tokens: 0,
+                utilization_pct: 0.0,
+                max_context: 128000,
+                last_tool_rounds: 0,
+                compacted: false,
+                session_id: "default".into(),
+            },
+            daily_log: bizclaw_memory::brain::DailyLogManager::new(std::env::temp_dir()),
+            loop_detector: loop_detector::LoopDetector::new(),
+            branches: Vec::new(),
+            store: None,
+            last_reasoning: None,
+            last_refusal: None,
+            last_fallback: None,
+            persona: None,
+            config,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streamed_tool_use_is_executed() {
+        const TOOL_TURN: &str = concat!(
+            "data: {\"type\":\"content_block_start\",\"index\":0,",
+            "\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,",
+            "\"delta\":{\"type\":\"text_delta\",\"text\":\"Let me check. \"}}\n\n",
+            "data: {\"type\":\"content_block_start\",\"index\":1,",
+            "\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_1\",\"name\":\"weather\",\"input\":{}}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,",
+            "\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"city\\\": \\\"Hu\"}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,",
+            "\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"e\\\", \\\"days\\\": 2}\"}}\n\n",
+            "data: {\"type\":\"content_block_stop\",\"index\":1}\n\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\"}}\n\n",
+        );
+        const ANSWER_TURN: &str = concat!(
+            "data: {\"type\":\"content_block_start\",\"index\":0,",
+            "\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,",
+            "\"delta\":{\"type\":\"text_delta\",\"text\":\"Sunny in Hue.\"}}\n\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"}}\n\n",
+        );
+
+        let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut tools = bizclaw_tools::ToolRegistry::new();
+        tools.register(Box::new(RecordingTool(calls.clone())));
+        let provider = AnthropicStreamStub {
+            streams: std::sync::Mutex::new([TOOL_TURN, ANSWER_TURN].into()),
+        };
+        let mut agent = test_agent(Box::new(provider), tools);
+
+        let shown = std::sync::Mutex::new(String::new());
+        let on_text = |text: &str| shown.lock().unwrap().push_str(text);
+        let answer = agent.process_stream("Weather in Hue?", &on_text).await.unwrap();
+
+        assert_eq!(answer, "Sunny in Hue.");
+        assert_eq!(*shown.lock().unwrap(), "Let me check. Sunny in Hue.");
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        let args: serde_json::Value = serde_json::from_str(&calls[0]).unwrap();
+        assert_eq!(args, serde_json::json!({"city": "Hue", "days": 2}));
+
+        // The turn continued with the tool result.
+        let result = agent
+            .conversation()
+            .iter()
+            .find(|m| m.role == bizclaw_core::types::Role::Tool)
+            .unwrap();
+        assert_eq!(result.tool_call_id.as_deref(), Some("toolu_1"));
+        assert_eq!(agent.context_stats().last_tool_rounds, 1);
+    }
+
+    /// Reports build progress in three chunks.
+    struct BuildTool;
+
+    impl BuildTool {
+        async fn build(&self, on_output: &OnOutput<'_>) -> Result<bizclaw_core::types::ToolResult> {
+            let mut output = String::new();
+            for chunk in ["compiling\n", "linking\n", "done\n"] {
+                on_output(chunk);
+                output.push_str(chunk);
+                tokio::task::yield_now().await;
+            }
+            Ok(bizclaw_core::types::ToolResult {
+                tool_call_id: String::new(),
+                output,
+                success: true,
+                data: None,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl bizclaw_core::traits::Tool for BuildTool {
+        fn name(&self) -> &str {
+            "build"
+        }
+
+        fn definition(&self) -> ToolDefinition {
+            ToolDefinition {
+                name: "build".into(),
+                description: "Build the project".into(),
+                parameters: serde_json::json!({"type": "object"}),
+            }
+        }
+
+        async fn execute(&self, _arguments: &str) -> Result<bizclaw_core::types::ToolResult> {
+            self.build(&|_| {}).await
+        }
+
+        async fn execute_streaming(
+            &self,
+            _arguments: &str,
+            on_output: &OnOutput<'_>,
+        ) -> Result<bizclaw_core::types::ToolResult> {
+            self.build(on_output).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_output_streamed_in_order() {
+        let call = |id: &str, name: &str| bizclaw_core::types::ToolCall {
+            id: id.into(),
+            r#type: "function".into(),
+            function: bizclaw_core::types::FunctionCall {
+                name: name.into(),
+                arguments: "{}".into(),
+            },
+        };
+        let provider = ScriptedProvider(std::sync::Mutex::new(vec![
+            ProviderResponse {
+                tool_calls: vec![call("call_1", "build"), call("call_2", "weather")],
+                ..ProviderResponse::text("")
+            },
+            ProviderResponse::text("Build passed."),
+        ]));
+        let mut tools = bizclaw_tools::ToolRegistry::new();
+        tools.register(Box::new(BuildTool));
+        tools.register(Box::new(RecordingTool(Default::default())));
+        let mut agent = test_agent(Box::new(provider), tools);
+
+        let streamed = std::sync::Mutex::new(Vec::new());
+        let on_tool_output =
+            |tool: &str, chunk: &str| streamed.lock().unwrap().push(format!("{tool}: {chunk}"));
+        let answer = agent
+            .process_stream_with_tools("Build it", &|_| {}, &on_tool_output)
+            .await
+            .unwrap();
+
+        assert_eq!(answer, "Build passed.");
+        // Chunks arrive in order; a tool without streaming support sends its
+        // whole output once.
+        assert_eq!(
+            *streamed.lock().unwrap(),
+            [
+                "build: compiling\n",
+                "build: linking\n",
+                "build: done\n",
+                "weather: Sunny, 31°C",
+            ]
+        );
+        // The model gets the complete result.
+        let result = agent
+            .conversation()
+            .iter()
+            .find(|m| m.tool_call_id.as_deref() == Some("call_1"))
+            .unwrap();
+        assert_eq!(result.content, "compiling\nlinking\ndone\n");
+    }
+
+    #[tokio::test]
+    async fn test_old_turns_dropped_past_turn_limit() {
+        let provider = StubProvider {
+            caps: ProviderCapabilities::default(),
+        };
+        let mut agent = test_agent(Box::new(provider), bizclaw_tools::ToolRegistry::new());
+        agent.config.context.max_history_turns = 2;
+        agent.pin_memory("Customer prefers Vietnamese.");
+
+        for q in ["q1", "q2", "q3", "q4"] {
+            agent.process(q).await.unwrap();
+        }
+
+        let conversation = agent.conversation();
+        assert_eq!(conversation[0].content, "You are helpful.");
+        assert_eq!(
+            conversation[1].content,
+            "[Pinned memory]\nCustomer prefers Vietnamese."
+        );
+        let users: Vec<&str> = conversation
+            .iter()
+            .filter(|m| m.role == bizclaw_core::types::Role::User)
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(users, vec!["q3", "q4"]);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_message_survives_trimming() {
+        let provider = StubProvider {
+            caps: ProviderCapabilities::default(),
+        };
+        let mut agent = test_agent(Box::new(provider), bizclaw_tools::ToolRegistry::new());
+        agent.config.context.max_history_turns = 1;
+        let users = |agent: &Agent| -> Vec<String> {
+            agent
+                .conversation()
+                .iter()
+                .filter(|m| m.role == bizclaw_core::types::Role::User)
+                .map(|m| m.content.clone())
+                .collect()
+        };
+
+        agent.process("Always answer in French.").await.unwrap();
+        assert!(agent.pin_last_user_message().is_some());
+        agent.process("q2").await.unwrap();
+        agent.process("q3").await.unwrap();
+        assert_eq!(users(&agent), vec!["Always answer in French.", "q3"]);
+        assert!(agent.set_pinned(0, true).is_err());
+        agent.conversation.push(Message::tool("sunny", "call_1"));
+        let tool_result = agent.conversation().len() - 1;
+        assert!(agent.set_pinned(tool_result, true).is_err());
+        agent.conversation.pop();
+
+        assert_eq!(agent.unpin_all(), 1);
+        agent.process("q4").await.unwrap();
+        assert_eq!(users(&agent), vec!["q4"]);
+    }
+
+    /// Max tokens and stop sequences of each recorded call.
+    type RecordedParams = std::sync::Arc<std::sync::Mutex<Vec<(u32, Vec<String>)>>>;
+
+    /// Records the generation parameters of every call.
+    struct ParamsRecorder(RecordedParams);
+
+    #[async_trait::async_trait]
+    impl Provider for ParamsRecorder {
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            let call = (params.max_tokens, params.stop.clone());
+            self.0.lock().unwrap().push(call);
+            Ok(ProviderResponse::text("ok"))
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generation_options_reach_provider() {
+        let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let provider = ParamsRecorder(calls.clone());
+        let mut agent = test_agent(Box::new(provider), bizclaw_tools::ToolRegistry::new());
+        agent.config.generation.max_tokens = 512;
+        agent.config.generation.stop = vec!["END".into()];
+        let zalo = bizclaw_core::config::GenerationOverride {
+            max_tokens: Some(100),
+            stop: None,
+        };
+        agent.config.generation.channels.insert("zalo".into(), zalo);
+
+        agent.process("hi").await.unwrap();
+        agent.process_from("zalo", "hi").await.unwrap();
+        agent.config.generation.max_tokens = 0;
+        agent.process_from("telegram", "hi").await.unwrap();
+
+        let calls = calls.lock().unwrap();
+        let end = vec!["END".to_string()];
+        assert_eq!(calls[0], (512, end.clone()));
+        assert_eq!(calls[1], (100, end.clone()));
+        // Unset falls back to the brain's max_tokens.
+        assert_eq!(calls[2], (BizClawConfig::default().brain.max_tokens, end));
+    }
+
+    /// Answers with queued responses, in order.
+    struct ScriptedProvider(std::sync::Mutex<Vec<ProviderResponse>>);
+
+    #[async_trait::async_trait]
+    impl Provider for ScriptedProvider {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            Ok(self.0.lock().unwrap().remove(0))
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    /// Fails with a connection error while `down` is set.
+    struct OutageProvider {
+        down: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for OutageProvider {
+        fn name(&self) -> &str {
+            "outage"
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            if self.down.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(bizclaw_core::error::BizClawError::Http("connection refused".into()));
+            }
+            Ok(ProviderResponse::text("We open at 9am."))
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_message_dead_lettered_and_replayed() {
+        use bizclaw_db::DataStore;
+
+        let store = bizclaw_db::SqliteStore::in_memory().unwrap();
+        store.migrate().await.unwrap();
+        let down = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+        let provider = OutageProvider { down: down.clone() };
+        let mut agent = test_agent(Box::new(provider), bizclaw_tools::ToolRegistry::new());
+        let msg = bizclaw_core::types::IncomingMessage {
+            channel: "telegram".into(),
+            thread_id: "42".into(),
+            sender_id: "7".into(),
+            sender_name: None,
+            content: "When do you open?".into(),
+            thread_type: bizclaw_core::types::ThreadType::Direct,
+            timestamp: chrono::Utc::now(),
+            reply_to: None,
+            message_id: None,
+            images: Vec::new(),
+        };
+
+        let err = dead_letter::process_or_dead_letter(&mut agent, &store, &msg)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind, AgentErrorKind::ProviderUnavailable);
+        let letters = store.list_dead_letters(10).await.unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].message.content, "When do you open?");
+        assert!(letters[0].error.contains("connection refused"));
+
+        // Still down: the replay fails and the entry is kept.
+        let id = letters[0].id.clone();
+        let sent = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let telegram = |name: &str| {
+            assert_eq!(name, "telegram");
+            Some(Box::new(RecordingChannel(sent.clone())) as Box<dyn bizclaw_core::traits::Channel>)
+        };
+        assert!(dead_letter::replay(&mut agent, &store, &id, telegram).await.is_err());
+        assert!(store.get_dead_letter(&id).await.unwrap().is_some());
+
+        // No channel to answer on: nothing is sent and the entry is kept.
+        down.store(false, std::sync::atomic::Ordering::SeqCst);
+        assert!(dead_letter::replay(&mut agent, &store, &id, |_| None).await.is_err());
+        assert!(store.get_dead_letter(&id).await.unwrap().is_some());
+
+        let reply = dead_letter::replay(&mut agent, &store, &id, telegram).await.unwrap();
+        assert_eq!(reply.as_deref(), Some("We open at 9am."));
+        let sent = sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].thread_id, "42");
+        assert_eq!(sent[0].content, "We open at 9am.");
+        assert!(store.list_dead_letters(10).await.unwrap().is_empty());
+        assert_eq!(dead_letter::replay(&mut agent, &store, &id, |_| None).await.unwrap(), None);
+    }
+
+    /// Records every message sent through it.
+    struct RecordingChannel(std::sync::Arc<std::sync::Mutex<Vec<OutgoingMessage>>>);
+
+    #[async_trait::async_trait]
+    impl bizclaw_core::traits::Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+
+        async fn listen(
+            &self,
+        ) -> Result<Box<dyn futures::Stream<Item = bizclaw_core::types::IncomingMessage> + Send + Unpin>>
+        {
+            Ok(Box::new(futures::stream::pending()))
+        }
+
+        async fn send(&self, message: OutgoingMessage) -> Result<()> {
+            self.0.lock().unwrap().push(message);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refusal_becomes_friendly_reply() {
+        let refused = || ProviderResponse {
+            content: Some("partial".into()),
+            finish_reason: Some(bizclaw_core::types::FINISH_CONTENT_FILTER.into()),
+            refusal: Refusal::detect(Some("content_filter"), None),
+            ..ProviderResponse::text("")
+        };
+        let provider = ScriptedProvider(std::sync::Mutex::new(vec![
+            refused(),
+            ProviderResponse::text("Hello!"),
+            refused(),
+        ]));
+        let mut agent = test_agent(Box::new(provider), bizclaw_tools::ToolRegistry::new());
+
+        let reply = agent.process("something unsafe").await.unwrap();
+        assert_eq!(reply, "Sorry, I can't help with that request.");
+        assert_eq!(agent.last_refusal().unwrap().reason, "content_filter");
+        assert_eq!(agent.conversation.last().unwrap().content, reply);
+
+        // The next, normal answer clears it.
+        assert_eq!(agent.process("hi").await.unwrap(), "Hello!");
+        assert!(agent.last_refusal().is_none());
+
+        // The reply follows the configured locale.
+        agent.config.locale = "vi".into();
+        let reply = agent.process("something unsafe").await.unwrap();
+        assert_eq!(reply, "Xin lỗi, tôi không thể giúp với yêu cầu này.");
+    }
+
+    #[tokio::test]
+    async fn test_datetime_context_uses_the_users_timezone() {
+        let provider = ScriptedProvider(std::sync::Mutex::new(vec![
+            ProviderResponse::text("One."),
+            ProviderResponse::text("Two."),
+        ]));
+        let mut agent = test_agent(Box::new(provider), bizclaw_tools::ToolRegistry::new());
+        let has_datetime = |agent: &Agent| {
+            agent
+                .conversation
+                .iter()
+                .find(|m| m.content.starts_with(datetime::DATETIME_MARKER))
+                .map(|m| m.content.clone())
+        };
+
+        // Off unless configured.
+        agent.process("hi").await.unwrap();
+        assert_eq!(has_datetime(&agent), None);
+
+        agent.config.datetime.enabled = true;
+        assert!(agent.set_user_timezone("Mars/Olympus").await.is_err());
+        agent.set_user_timezone("Asia/Ho_Chi_Minh").await.unwrap();
+        agent.process("what day is it?").await.unwrap();
+        assert!(has_datetime(&agent).unwrap().contains("(Asia/Ho_Chi_Minh, UTC+07:00)"));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_provider_answered_locally() {
+        let down = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+        let provider = bizclaw_providers::local_fallback::LocalFallbackProvider::new(
+            Box::new(OutageProvider { down: down.clone() }),
+            Box::new(ScriptedProvider(std::sync::Mutex::new(vec![ProviderResponse::text(
+                "Offline answer.",
+            )]))),
+        );
+        let mut agent = test_agent(Box::new(provider), bizclaw_tools::ToolRegistry::new());
+
+        assert_eq!(agent.process("When do you open?").await.unwrap(), "Offline answer.");
+        assert_eq!(agent.last_fallback(), Some("scripted"));
+
+        // Back online: answered by the cloud provider again.
+        down.store(false, std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(agent.process("When do you open?").await.unwrap(), "We open at 9am.");
+        assert_eq!(agent.last_fallback(), None);
+    }
+
+    /// Answers with queued responses and records the messages of every call.
+    struct TranscriptProvider {
+        replies: std::sync::Mutex<Vec<ProviderResponse>>,
+        seen: Transcript,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for TranscriptProvider {
+        fn name(&self) -> &str {
+            "transcript"
+        }
+
+        async fn chat(
+            &self,
+            messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            self.seen.lock().unwrap().push(messages.to_vec());
+            Ok(self.replies.lock().unwrap().remove(0))
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    /// Returns a page far larger than any context window.
+    struct HugeTool;
+
+    #[async_trait::async_trait]
+    impl bizclaw_core::traits::Tool for HugeTool {
+        fn name(&self) -> &str {
+            "http_fetch"
+        }
+
+        fn definition(&self) -> ToolDefinition {
+            ToolDefinition {
+                name: "http_fetch".into(),
+                description: "Fetch a URL".into(),
+                parameters: serde_json::json!({"type": "object"}),
+            }
+        }
+
+        async fn execute(&self, _arguments: &str) -> Result<bizclaw_core::types::ToolResult> {
+            Ok(bizclaw_core::types::ToolResult {
+                tool_call_id: String::new(),
+                output: "Giá vàng hôm nay. ".repeat(20_000),
+                success: true,
+                data: None,
+            })
+        }
+    }
+
+    fn fetch_call() -> ProviderResponse {
+        ProviderResponse {
+            tool_calls: vec![bizclaw_core::types::ToolCall {
+                id: "call_1".into(),
+                r#type: "function".into(),
+                function: bizclaw_core::types::FunctionCall {
+                    name: "http_fetch".into(),
+                    arguments: "{}".into(),
+                },
+            }],
+            ..ProviderResponse::text("")
+        }
+    }
+
+    type Transcript = std::sync::Arc<std::sync::Mutex<Vec<Vec<Message>>>>;
+
+    fn huge_tool_agent(replies: Vec<ProviderResponse>) -> (Agent, Transcript) {
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let provider = TranscriptProvider {
+            replies: std::sync::Mutex::new(replies),
+            seen: seen.clone(),
+        };
+        let mut tools = bizclaw_tools::ToolRegistry::new();
+        tools.register(Box::new(HugeTool));
+        let mut agent = test_agent(Box::new(provider), tools);
+        agent.config.context.tool_result_max_tokens = 200;
+        (agent, seen)
+    }
+
+    fn tool_message(messages: &[Message]) -> &Message {
+        messages
+            .iter()
+            .find(|m| m.role == bizclaw_core::types::Role::Tool)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_oversized_input_rejected_or_truncated() {
+        let (mut agent, seen) = huge_tool_agent(vec![ProviderResponse::text("Got it.")]);
+        agent.config.context.max_input_chars = 100;
+        let pasted_log = "ERROR connection reset\n".repeat(50);
+
+        let error = agent.process(&pasted_log).await.unwrap_err();
+        assert_eq!(error.kind, AgentErrorKind::InputTooLong);
+        assert!(
+            error
+                .user_message(bizclaw_core::i18n::Locale::En)
+                .contains("too long")
+        );
+        assert!(seen.lock().unwrap().is_empty(), "provider was called");
+
+        agent.config.context.oversized_input = bizclaw_core::config::OversizedInput::Truncate;
+        let reply = agent.process(&pasted_log).await.unwrap();
+        assert!(reply.starts_with("✂️"), "{reply}");
+        assert!(reply.contains("first 100 characters"), "{reply}");
+        assert!(reply.ends_with("Got it."));
+        let seen = seen.lock().unwrap();
+        let sent = seen[0]
+            .iter()
+            .rfind(|m| m.role == bizclaw_core::types::Role::User)
+            .unwrap();
+        assert!(sent.content.len() < 200, "{} bytes", sent.content.len());
+        assert!(sent.content.contains("[message truncated:"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_tool_result_truncated_and_stashed() {
+        let replies = vec![fetch_call(), ProviderResponse::text("Done.")];
+        let (mut agent, seen) = huge_tool_agent(replies);
+        agent.config.context.stash_tool_results = true;
+
+        assert_eq!(agent.process("Giá vàng?").await.unwrap(), "Done.");
+
+        // The follow-up call got the condensed result, not 420 KB.
+        let result = tool_message(&seen.lock().unwrap()[1]).content.clone();
+        assert!(result.len() < 1000, "{} bytes", result.len());
+        assert!(result.contains("[truncated:"));
+        assert!(result.contains("Full result saved to memory"));
+        let mut stashed = agent.memory.list(None).await.unwrap();
+        stashed.retain(|e| e.metadata["kind"] == "tool_result");
+        assert_eq!(stashed.len(), 1);
+        assert_eq!(stashed[0].metadata["tool"], "http_fetch");
+        assert!(stashed[0].content.len() > 420_000);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_tool_result_summarized() {
+        let (mut agent, seen) = huge_tool_agent(vec![
+            fetch_call(),
+            ProviderResponse::text("Gold price page, repeated headline."),
+            ProviderResponse::text("Done."),
+        ]);
+        agent.config.context.summarize_tool_results = true;
+
+        assert_eq!(agent.process("Giá vàng?").await.unwrap(), "Done.");
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 3);
+        // The summary request itself is capped at half the context window.
+        let request: usize = seen[1].iter().map(|m| m.content.len()).sum();
+        let half_window = agent.config.brain.context_length as usize / 2;
+        assert!(request < half_window * 3 + 500, "{request} bytes");
+        let result = &tool_message(&seen[2]).content;
+        assert!(result.starts_with("[Summary of 420000 bytes of 'http_fetch' output]"));
+        assert!(result.ends_with("Gold price page, repeated headline."));
+    }
+
+    #[test]
+    fn test_channel_policy_removes_shell_tool() {
+        let channel_agent = |channel: &str| {
+            let mut agent = test_agent(
+                Box::new(StubProvider {
+                    caps: ProviderCapabilities::default(),
+                }),
+                bizclaw_tools::ToolRegistry::with_defaults(),
+            );
+            agent.config.channel.policies.insert(
+                "telegram".into(),
+                bizclaw_core::config::ChannelPolicy {
+                    autonomy_level: Some("readonly".into()),
+                    ..Default::default()
+                },
+            );
+            agent.restrict_to_channel(channel);
+            agent
+        };
+        let agent = channel_agent("cli");
+        let restricted = channel_agent("telegram");
+
+        assert!(agent.tools.get("shell").is_some());
+        assert!(restricted.tools.get("shell").is_none());
+        assert!(restricted.tools.get("web_search").is_some());
+        assert!(
+            !restricted
+                .prompt_cache
+                .cached_tool_defs
+                .iter()
+                .any(|d| d.name == "shell")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_channel_policy_applies_per_turn() {
+        let (mut agent, seen) = huge_tool_agent(vec![
+            fetch_call(),
+            ProviderResponse::text("Done."),
+        ]);
+        agent.config.channel.policies.insert(
+            "whatsapp".into(),
+            bizclaw_core::config::ChannelPolicy {
+                denied_tools: vec!["http_fetch".into()],
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(agent.process_from("whatsapp", "Giá vàng?").await.unwrap(), "Done.");
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(tool_message(&seen[1]).content, "Permission denied: 'http_fetch'");
+        // Other channels still get the tool.
+        assert!(agent.tools.get("http_fetch").is_some());
+        assert!(agent.config.autonomy.allows_tool("http_fetch"));
+    }
+
+    #[test]
+    fn test_tools_skipped_when_provider_lacks_support() {
+        let no_tools = StubProvider {
+            caps: ProviderCapabilities {
+                supports_tools: false,
+                ..Default::default()
+            },
+        };
+        assert!(tools_for_provider(&no_tools, &defs()).is_empty());
+
+        let with_tools = StubProvider {
+            caps: ProviderCapabilities::default(),
+        };
+        assert_eq!(tools_for_provider(&with_tools, &defs()).len(), 1);
+    }
 }
@@ -14,14 +14,17 @@
 use bizclaw_core::error::{BizClawError, Result};
 use bizclaw_core::types::*;
 use bizclaw_db::store::DataStore;
+use bizclaw_db::trace_buffer::{TraceBuffer, TraceBufferConfig};
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::Agent;
+use crate::delegation::{DelegationExecutor, DelegationSlot};
+use crate::persona::Persona;
 
 /// Safely truncate a string at a character boundary (UTF-8 safe).
 /// Avoids panic on Vietnamese/CJK multi-byte characters.
-fn safe_truncate(s: &str, max_bytes: usize) -> &str {
+pub(crate) fn safe_truncate(s: &str, max_bytes: usize) -> &str {
     if s.len() <= max_bytes {
         return s;
     }
@@ -54,6 +57,10 @@ pub struct Orchestrator {
     pub message_log: Vec<AgentMessage>,
     /// Data store for orchestration state (delegations, teams, handoffs, traces).
     store: Option<Arc<dyn DataStore>>,
+    /// Runs delegations within the links' `max_concurrent` (needs `store`).
+    delegations: Option<Arc<DelegationExecutor>>,
+    /// Batches trace writes to `store` off the request path.
+    traces: Option<TraceBuffer>,
     /// Lane configuration for workload isolation.
     pub lane_config: LaneConfig,
 }
@@ -68,6 +75,34 @@ pub struct AgentMessage {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+/// A delegation that passed its checks and has yet to claim a slot on
+/// its target.
+pub struct PendingDelegation {
+    delegation: Delegation,
+    executor: Option<Arc<DelegationExecutor>>,
+}
+
+impl PendingDelegation {
+    /// Wait until the target has a free slot (immediately without a store).
+    pub async fn ready(self) -> Result<ReadyDelegation> {
+        let slot = match &self.executor {
+            Some(executor) => Some(executor.acquire(&self.delegation).await?),
+            None => None,
+        };
+        Ok(ReadyDelegation {
+            delegation: self.delegation,
+            slot,
+        })
+    }
+}
+
+/// A delegation holding a slot on its target, ready to run. Dropping it
+/// without running it marks the delegation failed.
+pub struct ReadyDelegation {
+    delegation: Delegation,
+    slot: Option<DelegationSlot>,
+}
+
 impl Orchestrator {
     /// Create a new empty orchestrator.
     pub fn new() -> Self {
@@ -76,16 +111,21 @@ impl Orchestrator {
             default_agent: None,
             message_log: Vec::new(),
             store: None,
+            delegations: None,
+            traces: None,
             lane_config: LaneConfig::default(),
         }
     }
 
     /// Create orchestrator with a data store for persistent orchestration state.
+    /// Must be called inside a Tokio runtime (starts the trace writer).
     pub fn with_store(store: Arc<dyn DataStore>) -> Self {
         Self {
             agents: HashMap::new(),
             default_agent: None,
             message_log: Vec::new(),
+            traces: Some(TraceBuffer::spawn(store.clone(), TraceBufferConfig::default())),
+            delegations: Some(Arc::new(DelegationExecutor::new(store.clone()))),
             store: Some(store),
             lane_config: LaneConfig::default(),
         }
@@ -93,9 +133,21 @@ impl Orchestrator {
 
     /// Set the data store (can be set after creation).
     pub fn set_store(&mut self, store: Arc<dyn DataStore>) {
+        for (name, named) in self.agents.iter_mut() {
+            named.agent.set_store(name, store.clone());
+        }
+        self.traces = Some(TraceBuffer::spawn(store.clone(), TraceBufferConfig::default()));
+        self.delegations = Some(Arc::new(DelegationExecutor::new(store.clone())));
         self.store = Some(store);
     }
 
+    /// Write buffered traces and stop the trace writer. Call before exit.
+    pub async fn shutdown(&self) {
+        if let Some(traces) = &self.traces {
+            traces.shutdown().await;
+        }
+    }
+
     /// Get reference to the data store.
     pub fn store(&self) -> Option<&Arc<dyn DataStore>> {
         self.store.as_ref()
@@ -108,7 +160,10 @@ impl Orchestrator {
     }
 
     /// Add an agent to the orchestrator.
-    pub fn add_agent(&mut self, name: &str, role: &str, description: &str, agent: Agent) {
+    pub fn add_agent(&mut self, name: &str, role: &str, description: &str, mut agent: Agent) {
+        if let Some(store) = &self.store {
+            agent.set_store(name, store.clone());
+        }
         let is_first = self.agents.is_empty();
         self.agents.insert(
             name.to_string(),
@@ -176,55 +231,79 @@ impl Orchestrator {
 
     /// Send a message to a specific agent, respecting any active handoff.
     pub async fn send_to(&mut self, agent_name: &str, message: &str) -> Result<String> {
-        // Check for active handoff — route to handoff target if present
-        let actual_agent = if let Some(store) = &self.store {
-            if let Ok(Some(handoff)) = store.active_handoff(agent_name).await {
-                tracing::debug!(
-                    "Handoff active: {} → {}, routing message",
-                    handoff.from_agent,
-                    handoff.to_agent
-                );
-                handoff.to_agent.clone()
-            } else {
-                agent_name.to_string()
-            }
-        } else {
-            agent_name.to_string()
-        };
+        self.send_via(agent_name, None, message).await
+    }
 
-        let named = self.agents.get_mut(&actual_agent).ok_or_else(|| {
-            BizClawError::AgentNotFound(format!("Agent '{}' not found", actual_agent))
+    /// Like [`send_to`](Self::send_to) for a message that arrived via
+    /// `channel`, so that channel's prompt, generation options and policy apply.
+    pub async fn send_from(
+        &mut self,
+        agent_name: &str,
+        channel: &str,
+        message: &str,
+    ) -> Result<String> {
+        self.send_via(agent_name, Some(channel), message).await
+    }
+
+    async fn send_via(
+        &mut self,
+        agent_name: &str,
+        channel: Option<&str>,
+        message: &str,
+    ) -> Result<String> {
+        // Speak as the handoff target (if any) in this agent's own conversation
+        self.apply_handoff(agent_name).await?;
+
+        let named = self.agents.get_mut(agent_name).ok_or_else(|| {
+            BizClawError::AgentNotFound(format!("Agent '{}' not found", agent_name))
         })?;
 
         named.message_count += 1;
         let start = std::time::Instant::now();
-        let response = named.agent.process(message).await?;
+        let response = match channel {
+            Some(channel) => named.agent.process_from(channel, message).await?,
+            None => named.agent.process(message).await?,
+        };
         let latency = start.elapsed().as_millis() as u64;
 
         // Record LLM trace if store is available
-        if let Some(store) = &self.store {
+        if let Some(traces) = &self.traces {
             let mut trace = LlmTrace::new(
-                &actual_agent,
+                agent_name,
                 named.agent.provider_name(),
                 named.agent.model_name(),
             );
             trace.latency_ms = latency;
-            trace.status = "completed".to_string();
+            match named.agent.last_refusal() {
+                Some(refusal) => {
+                    trace.status = "refused".to_string();
+                    trace.error = Some(refusal.reason.clone());
+                    if let Some(message) = &refusal.message {
+                        trace.metadata["refusal"] = serde_json::json!(message);
+                    }
+                }
+                None => trace.status = "completed".to_string(),
+            }
+            // Answered offline by the local brain: trace it under that provider.
+            if let Some(local) = named.agent.last_fallback() {
+                trace.metadata["fallback_from"] = serde_json::json!(trace.provider);
+                trace.provider = local.to_string();
+            }
             let stats = named.agent.context_stats();
             trace.total_tokens = stats.estimated_tokens as u32;
-            let _ = store.record_trace(&trace).await;
+            traces.record(trace);
         }
 
         self.message_log.push(AgentMessage {
             from: "user".to_string(),
-            to: actual_agent.to_string(),
+            to: agent_name.to_string(),
             content: message.to_string(),
             response: Some(response.clone()),
             timestamp: chrono::Utc::now(),
         });
 
         // Run quality gates if configured
-        let response = self.run_quality_gates(&actual_agent, &response).await?;
+        let response = self.run_quality_gates(agent_name, &response).await?;
 
         Ok(response)
     }
@@ -258,6 +337,24 @@ impl Orchestrator {
         task: &str,
         mode: DelegationMode,
     ) -> Result<String> {
+        let pending = self
+            .begin_delegation(from_agent, to_agent, task, mode)
+            .await?;
+        let ready = pending.ready().await?;
+        self.run_delegation(ready).await
+    }
+
+    /// Check that a delegation is allowed, without running it. Wait for it
+    /// with [`PendingDelegation::ready`] — which needs no access to the
+    /// orchestrator, so callers sharing it behind a lock can release the
+    /// lock meanwhile — then run it with [`run_delegation`](Self::run_delegation).
+    pub async fn begin_delegation(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        task: &str,
+        mode: DelegationMode,
+    ) -> Result<PendingDelegation> {
         // Verify both agents exist
         if !self.agents.contains_key(from_agent) {
             return Err(BizClawError::AgentNotFound(from_agent.to_string()));
@@ -290,81 +387,64 @@ impl Orchestrator {
                     to_agent, active_count, max_load
                 )));
             }
+        }
 
-            // Create delegation record
-            let delegation = Delegation::new(from_agent, to_agent, task, mode.clone());
-            store.create_delegation(&delegation).await?;
-
-            // Mark as running
-            store
-                .update_delegation(&delegation.id, DelegationStatus::Running, None, None)
-                .await?;
-
-            // Process the task
-            let to = self.agents.get_mut(to_agent).ok_or_else(|| {
-                BizClawError::AgentNotFound(to_agent.to_string())
-            })?;
-            to.message_count += 1;
-            let delegate_prompt = format!(
-                "[Delegation from agent '{from_agent}']\n\
-                 Task: {task}\n\
-                 Please process this task and return a clear result."
-            );
-            let result = to.agent.process(&delegate_prompt).await;
-
-            match &result {
-                Ok(response) => {
-                    store
-                        .update_delegation(
-                            &delegation.id,
-                            DelegationStatus::Completed,
-                            Some(safe_truncate(response, 10000)),
-                            None,
-                        )
-                        .await?;
-                }
-                Err(e) => {
-                    store
-                        .update_delegation(
-                            &delegation.id,
-                            DelegationStatus::Failed,
-                            None,
-                            Some(&e.to_string()),
-                        )
-                        .await?;
-                }
-            }
+        Ok(PendingDelegation {
+            delegation: Delegation::new(from_agent, to_agent, task, mode),
+            executor: self.delegations.clone(),
+        })
+    }
 
-            let response = result?;
-            self.message_log.push(AgentMessage {
-                from: from_agent.to_string(),
-                to: to_agent.to_string(),
-                content: task.to_string(),
-                response: Some(response.clone()),
-                timestamp: chrono::Utc::now(),
-            });
-            Ok(response)
-        } else {
-            // Fallback: no store, simple delegation (backward compatible)
-            let to = self.agents.get_mut(to_agent).ok_or_else(|| {
-                BizClawError::AgentNotFound(to_agent.to_string())
-            })?;
-            to.message_count += 1;
-            let delegate_prompt = format!(
-                "[Delegation from agent '{from_agent}']\n\
-                 Task: {task}\n\
-                 Please process this task and return a clear result."
-            );
-            let response = to.agent.process(&delegate_prompt).await?;
-            self.message_log.push(AgentMessage {
-                from: from_agent.to_string(),
-                to: to_agent.to_string(),
-                content: task.to_string(),
-                response: Some(response.clone()),
-                timestamp: chrono::Utc::now(),
-            });
-            Ok(response)
+    /// Run a delegation whose slot is claimed, recording its outcome.
+    pub async fn run_delegation(&mut self, ready: ReadyDelegation) -> Result<String> {
+        let ReadyDelegation { delegation, slot } = ready;
+        let (from_agent, task) = (&delegation.from_agent, &delegation.task);
+        let result = match self.agents.get_mut(&delegation.to_agent) {
+            Some(to) => {
+                to.message_count += 1;
+                let delegate_prompt = format!(
+                    "[Delegation from agent '{from_agent}']\n\
+                     Task: {task}\n\
+                     Please process this task and return a clear result."
+                );
+                to.agent
+                    .process(&delegate_prompt)
+                    .await
+                    .map_err(BizClawError::from)
+            }
+            // Removed while the delegation waited for a slot
+            None => Err(BizClawError::AgentNotFound(delegation.to_agent.clone())),
+        };
+        if let Some(slot) = slot {
+            slot.finish(&result).await?;
         }
+        let response = result?;
+        self.message_log.push(AgentMessage {
+            from: from_agent.to_string(),
+            to: delegation.to_agent.clone(),
+            content: task.to_string(),
+            response: Some(response.clone()),
+            timestamp: chrono::Utc::now(),
+        });
+        Ok(response)
+    }
+
+    /// Post a progress update on a running delegation.
+    pub async fn post_delegation_event(
+        &self,
+        delegation_id: &str,
+        kind: DelegationEventKind,
+        message: &str,
+    ) -> Result<()> {
+        let store = self.require_store()?;
+        store
+            .append_delegation_event(delegation_id, &DelegationEvent::new(kind, message))
+            .await
+    }
+
+    /// Progress updates posted on a delegation, oldest first.
+    pub async fn delegation_events(&self, delegation_id: &str) -> Result<Vec<DelegationEvent>> {
+        self.require_store()?.list_delegation_events(delegation_id).await
     }
 
     // ── Agent Handoff ──────────────────────────────────────
@@ -397,11 +477,56 @@ impl Orchestrator {
         Ok(())
     }
 
-    /// Clear handoff — return to original agent routing.
-    pub async fn clear_handoff(&self, session_id: &str) -> Result<()> {
+    /// Clear handoff — the session's agent returns to its own persona.
+    pub async fn clear_handoff(&mut self, session_id: &str) -> Result<()> {
         let store = self.require_store()?;
         store.clear_handoff(session_id).await?;
         tracing::info!("Handoff cleared for session: {}", session_id);
+        if self.agents.contains_key(session_id) {
+            self.apply_handoff(session_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Sync `agent_name`'s persona with the handoff for its session (the
+    /// session ID is the agent name): adopt the target agent's system prompt,
+    /// provider and tools while a handoff is active, restore the agent's own
+    /// once it is cleared.
+    pub async fn apply_handoff(&mut self, agent_name: &str) -> Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        let target = match store.active_handoff(agent_name).await {
+            Ok(handoff) => handoff.map(|h| h.to_agent).filter(|to| to != agent_name),
+            Err(e) => {
+                tracing::warn!("Handoff lookup for '{agent_name}' failed: {e}");
+                return Ok(());
+            }
+        };
+        let named = self
+            .agents
+            .get(agent_name)
+            .ok_or_else(|| BizClawError::AgentNotFound(format!("Agent '{agent_name}' not found")))?;
+        if target.as_deref() == named.agent.persona_name() {
+            return Ok(());
+        }
+
+        let persona = match &target {
+            Some(to) => {
+                let target = self
+                    .agents
+                    .get(to)
+                    .ok_or_else(|| BizClawError::AgentNotFound(to.clone()))?;
+                Some(Persona::of(to, &target.agent)?)
+            }
+            None => None,
+        };
+        if let Some(named) = self.agents.get_mut(agent_name) {
+            match persona {
+                Some(persona) => named.agent.adopt_persona(persona),
+                None => named.agent.restore_persona(),
+            }
+        }
         Ok(())
     }
 
@@ -658,18 +783,18 @@ impl Orchestrator {
             }
         }
 
-        // Atomic claim — check not already assigned
-        if task.assigned_to.is_some() {
+        // Atomic claim — only one of several concurrent claims wins
+        if !store.claim_task(task_id, agent_name).await? {
+            let owner = store
+                .get_task(task_id)
+                .await?
+                .and_then(|t| t.assigned_to)
+                .unwrap_or_default();
             return Err(BizClawError::Team(format!(
                 "Task '{}' already claimed by '{}'",
-                task_id,
-                task.assigned_to.unwrap_or_default()
+                task_id, owner
             )));
         }
-
-        store
-            .update_task(task_id, TaskStatus::InProgress, Some(agent_name), None)
-            .await?;
         Ok(())
     }
 
@@ -745,6 +870,9 @@ impl Orchestrator {
     /// Get recent LLM traces.
     pub async fn list_traces(&self, limit: usize) -> Result<Vec<LlmTrace>> {
         let store = self.require_store()?;
+        if let Some(traces) = &self.traces {
+            traces.flush().await;
+        }
         store.list_traces(limit).await
     }
 
@@ -877,6 +1005,55 @@ mod tests {
         Agent::new(BizClawConfig::default()).expect("test agent creation failed")
     }
 
+    #[tokio::test]
+    async fn test_handoff_switches_persona_and_clear_reverts() {
+        let store = Arc::new(bizclaw_db::SqliteStore::in_memory().unwrap());
+        store.migrate().await.unwrap();
+        let mut orch = Orchestrator::with_store(store);
+        orch.add_agent("support", "assistant", "Support", make_test_agent());
+        let billing = BizClawConfig {
+            default_model: "gpt-4o".into(),
+            identity: bizclaw_core::traits::identity::Identity {
+                system_prompt: "You are the billing specialist.".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut billing = Agent::new(billing).unwrap();
+        billing.tools.retain(|name| name == "calendar");
+        orch.add_agent("billing", "billing", "Billing", billing);
+
+        type Snapshot = (Option<String>, String, String, Vec<String>);
+        let support = |orch: &Orchestrator| -> Snapshot {
+            let agent = &orch.agents["support"].agent;
+            (
+                agent.persona_name().map(String::from),
+                agent.model_name().to_string(),
+                agent.conversation()[0].content.clone(),
+                agent.tools.tool_names(),
+            )
+        };
+        let (_, own_model, own_prompt, own_tools) = support(&orch);
+
+        orch.handoff("support", "billing", "support", Some("refund"))
+            .await
+            .unwrap();
+        // The next turn starts by syncing the persona with the handoff.
+        orch.apply_handoff("support").await.unwrap();
+        let (persona, model, prompt, tools) = support(&orch);
+        assert_eq!(persona.as_deref(), Some("billing"));
+        assert_eq!(model, "gpt-4o");
+        assert!(prompt.starts_with("You are the billing specialist."));
+        // Billing's own tools, not a fresh default set.
+        assert!(tools.iter().any(|t| t == "calendar"));
+        assert!(!tools.iter().any(|t| t == "shell"));
+        // Same agent, same conversation — only the persona changed.
+        assert_eq!(orch.agents["support"].agent.conversation().len(), 1);
+
+        orch.clear_handoff("support").await.unwrap();
+        assert_eq!(support(&orch), (None, own_model, own_prompt, own_tools));
+    }
+
     #[test]
     fn test_orchestrator_new() {
         let orch = Orchestrator::new();
@@ -1043,13 +1220,13 @@ mod tests {
         assert!(md.contains("Helpful bot"));
     }
 
-    #[test]
-    fn test_with_store() {
+    #[tokio::test]
+    async fn test_with_store() {
         let store = Arc::new(
             bizclaw_db::SqliteStore::in_memory().unwrap()
         );
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        rt.block_on(store.migrate()).unwrap();
+        store.migrate().await.unwrap();
+        // The trace buffer's writer task needs a runtime.
         let orch = Orchestrator::with_store(store);
         assert!(orch.store().is_some());
     }
@@ -0,0 +1,148 @@
+//! Persona switching — lets one agent speak as another mid-conversation.
+//!
+//! An active handoff does not move the conversation to a different agent:
+//! the session's agent adopts the target's persona (system prompt, provider,
+//! model settings and tools) and keeps its own history. Clearing the handoff
+//! restores the original persona.
+
+use bizclaw_core::config::BizClawConfig;
+use bizclaw_core::error::Result;
+use bizclaw_core::traits::Provider;
+use bizclaw_core::types::Message;
+
+use crate::{Agent, PromptCache};
+
+/// The parts of an agent that define who it is.
+pub struct Persona {
+    /// Name of the agent this persona belongs to.
+    pub name: String,
+    config: BizClawConfig,
+    provider: Box<dyn Provider>,
+    tools: bizclaw_tools::ToolRegistry,
+    system_prompt: String,
+}
+
+impl Persona {
+    /// Build `agent`'s persona with a fresh provider. The tools are
+    /// `agent`'s own, shared with it.
+    pub fn of(name: &str, agent: &Agent) -> Result<Self> {
+        let config = agent.config.clone();
+        let provider = bizclaw_providers::create_provider(&config)?;
+        Ok(Self::new(
+            name,
+            config,
+            provider,
+            agent.tools.clone(),
+            agent.conversation[0].content.clone(),
+        ))
+    }
+
+    /// `agent`'s own persona on the model `alias` names in
+    /// `model_aliases`, or `None` if `alias` is not an alias. Only the config
+    /// and provider change; the tools are `agent`'s own.
+    pub fn aliased(alias: &str, agent: &Agent) -> Result<Option<Self>> {
+        let mut config = agent.config.clone();
+        config.default_model = alias.to_string();
+        if !bizclaw_providers::model_alias::apply(&mut config) {
+            return Ok(None);
+        }
+        let provider = bizclaw_providers::create_provider(&config)?;
+        Ok(Some(Self::new(
+            alias,
+            config,
+            provider,
+            agent.tools.clone(),
+            agent.conversation[0].content.clone(),
+        )))
+    }
+
+    pub fn new(
+        name: &str,
+        config: BizClawConfig,
+        provider: Box<dyn Provider>,
+        tools: bizclaw_tools::ToolRegistry,
+        system_prompt: String,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            config,
+            provider,
+            tools,
+            system_prompt,
+        }
+    }
+}
+
+/// An adopted persona and the agent's own parts it replaced.
+pub(crate) struct ActivePersona {
+    name: String,
+    config: BizClawConfig,
+    provider: Box<dyn Provider>,
+    tools: bizclaw_tools::ToolRegistry,
+    system_prompt: String,
+}
+
+impl Agent {
+    /// Speak as `persona` until [`restore_persona`](Self::restore_persona).
+    /// Switching again replaces the adopted persona; the original is kept.
+    pub fn adopt_persona(&mut self, persona: Persona) {
+        self.restore_persona();
+        let Persona {
+            name,
+            config,
+            provider,
+            tools,
+            system_prompt,
+        } = persona;
+        self.persona = Some(ActivePersona {
+            name: name.clone(),
+            config: std::mem::replace(&mut self.config, config),
+            provider: std::mem::replace(&mut self.provider, provider),
+            tools: std::mem::replace(&mut self.tools, tools),
+            system_prompt: std::mem::replace(&mut self.conversation[0].content, system_prompt),
+        });
+        self.prompt_cache = PromptCache::new(&self.conversation[0].content, &self.tools);
+        tracing::info!("🎭 Persona switched to '{name}'");
+    }
+
+    /// Return to the agent's own persona. No-op if none was adopted.
+    pub fn restore_persona(&mut self) {
+        let Some(original) = self.persona.take() else {
+            return;
+        };
+        self.config = original.config;
+        self.provider = original.provider;
+        self.tools = original.tools;
+        self.conversation[0] = Message::system(original.system_prompt);
+        self.prompt_cache = PromptCache::new(&self.conversation[0].content, &self.tools);
+        tracing::info!("🎭 Persona '{}' released", original.name);
+    }
+
+    /// Name of the adopted persona, if any.
+    pub fn persona_name(&self) -> Option<&str> {
+        self.persona.as_ref().map(|p| p.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliased_persona_keeps_own_tools() {
+        let config = BizClawConfig {
+            model_aliases: [("fast".to_string(), "gpt-4o".to_string())].into(),
+            ..Default::default()
+        };
+        let mut agent = Agent::new(config).unwrap();
+        agent.tools.retain(|name| name == "calendar");
+        assert!(Persona::aliased("gpt-4o-mini", &agent).unwrap().is_none());
+
+        let persona = Persona::aliased("fast", &agent).unwrap().unwrap();
+        agent.adopt_persona(persona);
+        assert_eq!(agent.model_name(), "gpt-4o");
+        assert_eq!(agent.tools.tool_names(), ["calendar"]);
+        agent.restore_persona();
+        assert_eq!(agent.tools.tool_names(), ["calendar"]);
+    }
+}
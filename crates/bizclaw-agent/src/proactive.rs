@@ -236,7 +236,7 @@ pub async fn spawn_proactive_loop<F, Fut, S, SFut>(
                         tracing::info!(
                             "  🤖 Agent response: {}",
                             if response.len() > 200 {
-                                format!("{}...", &response[..200])
+                                format!("{}...", bizclaw_core::text::truncate_chars(&response, 200))
                             } else {
                                 response
                             }
@@ -0,0 +1,120 @@
+//! First-contact welcome — greets each new sender once per channel.
+//!
+//! The template comes from the channel's `welcome_message` config. Senders
+//! are remembered in the DataStore (`channel_senders`), so the greeting is
+//! not repeated after a restart.
+
+use bizclaw_core::config::BizClawConfig;
+use bizclaw_core::error::Result;
+use bizclaw_core::types::IncomingMessage;
+use bizclaw_db::store::DataStore;
+
+/// Fill `{bot_name}`, `{sender_name}` and `{channel}` in a template.
+pub fn render(template: &str, bot_name: &str, msg: &IncomingMessage) -> String {
+    let sender = msg
+        .sender_name
+        .as_deref()
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or("there");
+    template
+        .replace("{bot_name}", bot_name)
+        .replace("{sender_name}", sender)
+        .replace("{channel}", &msg.channel)
+}
+
+/// The rendered welcome if this is the sender's first message on the
+/// channel, otherwise `None`. The sender is marked as seen either way.
+pub async fn first_contact(
+    store: &dyn DataStore,
+    template: &str,
+    bot_name: &str,
+    msg: &IncomingMessage,
+) -> Result<Option<String>> {
+    if msg.sender_id.is_empty() || !store.mark_sender_seen(&msg.channel, &msg.sender_id).await? {
+        return Ok(None);
+    }
+    Ok(Some(render(template, bot_name, msg)))
+}
+
+/// [`first_contact`] using the channel's configured template and the
+/// identity name. Channels without a welcome message never track senders.
+pub async fn welcome_for(
+    store: &dyn DataStore,
+    config: &BizClawConfig,
+    msg: &IncomingMessage,
+) -> Result<Option<String>> {
+    match config.channel.welcome_message(&msg.channel) {
+        Some(template) => first_contact(store, template, &config.identity.name, msg).await,
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bizclaw_core::config::TelegramChannelConfig;
+    use bizclaw_core::types::ThreadType;
+    use bizclaw_db::SqliteStore;
+
+    fn incoming(sender_id: &str, sender_name: Option<&str>) -> IncomingMessage {
+        IncomingMessage {
+            channel: "telegram".into(),
+            thread_id: sender_id.into(),
+            sender_id: sender_id.into(),
+            sender_name: sender_name.map(String::from),
+            content: "hello".into(),
+            thread_type: ThreadType::Direct,
+            timestamp: chrono::Utc::now(),
+            reply_to: None,
+            message_id: None,
+            images: Vec::new(),
+        }
+    }
+
+    fn config() -> BizClawConfig {
+        let mut config = BizClawConfig::default();
+        config.identity.name = "Mai".into();
+        config.channel.telegram = Some(TelegramChannelConfig {
+            enabled: true,
+            bot_token: "123:abc".into(),
+            allowed_chat_ids: vec![],
+            welcome_message: Some("Hi {sender_name}, I'm {bot_name} on {channel}.".into()),
+            parse_mode: Default::default(),
+            chat_parse_modes: Default::default(),
+        });
+        config
+    }
+
+    #[tokio::test]
+    async fn test_welcome_only_on_first_message() {
+        let store = SqliteStore::in_memory().unwrap();
+        store.migrate().await.unwrap();
+        let config = config();
+
+        let first = welcome_for(&store, &config, &incoming("42", Some("Lan")))
+            .await
+            .unwrap();
+        assert_eq!(first.as_deref(), Some("Hi Lan, I'm Mai on telegram."));
+        let again = welcome_for(&store, &config, &incoming("42", Some("Lan")))
+            .await
+            .unwrap();
+        assert!(again.is_none());
+
+        // A different sender is greeted too.
+        let other = welcome_for(&store, &config, &incoming("43", None))
+            .await
+            .unwrap();
+        assert_eq!(other.as_deref(), Some("Hi there, I'm Mai on telegram."));
+    }
+
+    #[tokio::test]
+    async fn test_no_welcome_without_template() {
+        let store = SqliteStore::in_memory().unwrap();
+        store.migrate().await.unwrap();
+        let config = BizClawConfig::default();
+        let msg = incoming("42", Some("Lan"));
+        assert!(welcome_for(&store, &config, &msg).await.unwrap().is_none());
+        // Not tracked, so enabling a welcome later still greets this sender.
+        assert!(store.mark_sender_seen("telegram", "42").await.unwrap());
+    }
+}
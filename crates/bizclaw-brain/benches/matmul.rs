@@ -0,0 +1,79 @@
+//! Dequantize-then-multiply vs direct quantized matmul on a Q4_K weight.
+//!
+//! Run with `cargo bench -p bizclaw-brain --bench matmul`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bizclaw_brain::gguf::GgmlType;
+use bizclaw_brain::{quant, tensor};
+
+/// A 4096 x 4096 projection, the size of a 7B model's attention weights.
+const ROWS: usize = 4096;
+const COLS: usize = 4096;
+const ITERATIONS: u32 = 10;
+
+/// Deterministic pseudo-random Q4_K matrix.
+fn q4_k_matrix(rows: usize, cols: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_u32;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state
+    };
+    let n_blocks = rows * cols / 256;
+    let mut data = Vec::with_capacity(n_blocks * 144);
+    for _ in 0..n_blocks {
+        let d = 0.001 + (next() % 1000) as f32 / 50_000.0;
+        let dmin = (next() % 1000) as f32 / 100_000.0;
+        data.extend(half::f16::from_f32(d).to_le_bytes());
+        data.extend(half::f16::from_f32(dmin).to_le_bytes());
+        data.extend((0..140).map(|_| next() as u8));
+    }
+    data
+}
+
+fn time(mut f: impl FnMut()) -> Duration {
+    f(); // warm-up
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let data = q4_k_matrix(ROWS, COLS);
+    let input: Vec<f32> = (0..COLS)
+        .map(|i| ((i * 7) % 13) as f32 / 6.0 - 1.0)
+        .collect();
+    let mut output = vec![0.0f32; ROWS];
+
+    let dequant = time(|| {
+        let mut weight = vec![0.0f32; ROWS * COLS];
+        quant::dequantize_row(&data, &mut weight, ROWS * COLS, GgmlType::Q4K).unwrap();
+        tensor::matmul(&mut output, &weight, black_box(&input), ROWS, COLS);
+        black_box(&output);
+    });
+    let direct = time(|| {
+        tensor::matmul_quantized(
+            &mut output,
+            &data,
+            GgmlType::Q4K,
+            black_box(&input),
+            ROWS,
+            COLS,
+        )
+        .unwrap();
+        black_box(&output);
+    });
+
+    println!("Q4_K matmul {ROWS}x{COLS}, mean of {ITERATIONS} runs");
+    println!("  dequantize + matmul: {dequant:>10.2?}");
+    println!("  quantized matmul:    {direct:>10.2?}");
+    println!(
+        "  speedup:             {:>9.2}x",
+        dequant.as_secs_f64() / direct.as_secs_f64()
+    );
+}
@@ -0,0 +1,283 @@
+//! LRU cache of loaded models.
+//!
+//! Models are cached under a key naming the file and the engine settings it
+//! was loaded with, so each is mmapped once per process however many agents
+//! use it with those settings. The
+//! cache keeps at most `max_models` models and `max_bytes` of model files
+//! loaded; loading another evicts the least recently used. Models idle for
+//! longer than `idle_ttl` are unloaded on the next cache access.
+//!
+//! Eviction only drops the cache's handle: requests already holding a model
+//! finish normally, and the model is freed when the last one is done.
+//! Concurrent requests for a model that is not loaded wait for a single load.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use bizclaw_core::error::Result;
+
+/// How much the cache may hold.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheLimits {
+    /// Most models loaded at once (at least 1).
+    pub max_models: usize,
+    /// Combined size cap in bytes. 0 = no cap.
+    pub max_bytes: u64,
+    /// Unload models unused for this long. `None` = never.
+    pub idle_ttl: Option<Duration>,
+}
+
+struct Entry<T> {
+    value: Arc<T>,
+    bytes: u64,
+    /// Order of last use; higher is more recent.
+    tick: u64,
+    last_used: Instant,
+}
+
+struct Inner<T> {
+    entries: HashMap<String, Entry<T>>,
+    /// One lock per model being loaded, so a model is loaded only once.
+    loading: HashMap<String, Arc<Mutex<()>>>,
+    tick: u64,
+}
+
+/// Loaded models by key.
+pub struct ModelCache<T> {
+    limits: CacheLimits,
+    inner: Mutex<Inner<T>>,
+}
+
+impl<T> ModelCache<T> {
+    pub fn new(limits: CacheLimits) -> Self {
+        Self {
+            limits: CacheLimits {
+                max_models: limits.max_models.max(1),
+                ..limits
+            },
+            inner: Mutex::new(Inner {
+                entries: HashMap::new(),
+                loading: HashMap::new(),
+                tick: 0,
+            }),
+        }
+    }
+
+    /// The model cached under `key`, loading it with `load` if it is not
+    /// cached. `bytes` is its memory footprint (the file size for an
+    /// mmapped model).
+    pub fn get_or_load(
+        &self,
+        key: &str,
+        bytes: u64,
+        load: impl FnOnce() -> Result<T>,
+    ) -> Result<Arc<T>> {
+        let load_lock = {
+            let mut inner = self.lock();
+            self.evict_idle_locked(&mut inner);
+            if let Some(value) = Self::touch(&mut inner, key) {
+                return Ok(value);
+            }
+            inner.loading.entry(key.to_string()).or_default().clone()
+        };
+
+        let _loading = load_lock.lock().unwrap_or_else(|p| p.into_inner());
+        // Someone else may have loaded it while we waited.
+        if let Some(value) = Self::touch(&mut self.lock(), key) {
+            return Ok(value);
+        }
+        let loaded = load();
+
+        let mut inner = self.lock();
+        inner.loading.remove(key);
+        let value = Arc::new(loaded?);
+        self.make_room(&mut inner, bytes);
+        inner.tick += 1;
+        let tick = inner.tick;
+        inner.entries.insert(
+            key.to_string(),
+            Entry {
+                value: value.clone(),
+                bytes,
+                tick,
+                last_used: Instant::now(),
+            },
+        );
+        tracing::info!(
+            "🧠 Loaded {key} ({} model(s), {} MB cached)",
+            inner.entries.len(),
+            Self::bytes_of(&inner) / 1024 / 1024
+        );
+        Ok(value)
+    }
+
+    /// Cached model keys, most recently used first.
+    pub fn loaded(&self) -> Vec<String> {
+        let inner = self.lock();
+        let mut entries: Vec<_> = inner.entries.iter().collect();
+        entries.sort_by_key(|(_, e)| std::cmp::Reverse(e.tick));
+        entries.into_iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    /// Combined size of the cached models.
+    pub fn loaded_bytes(&self) -> u64 {
+        Self::bytes_of(&self.lock())
+    }
+
+    /// Unload models idle past the TTL. Returns how many were unloaded.
+    pub fn evict_idle(&self) -> usize {
+        self.evict_idle_locked(&mut self.lock())
+    }
+
+    fn touch(inner: &mut Inner<T>, key: &str) -> Option<Arc<T>> {
+        inner.tick += 1;
+        let tick = inner.tick;
+        let entry = inner.entries.get_mut(key)?;
+        entry.tick = tick;
+        entry.last_used = Instant::now();
+        Some(entry.value.clone())
+    }
+
+    /// Evict least recently used models until one of `bytes` fits.
+    fn make_room(&self, inner: &mut Inner<T>, bytes: u64) {
+        loop {
+            let over_count = inner.entries.len() >= self.limits.max_models;
+            let over_bytes =
+                self.limits.max_bytes > 0 && Self::bytes_of(inner) + bytes > self.limits.max_bytes;
+            if !(over_count || over_bytes) {
+                return;
+            }
+            let Some(lru) = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.tick)
+                .map(|(key, _)| key.clone())
+            else {
+                // Nothing left to evict; a model bigger than the cap still loads.
+                return;
+            };
+            inner.entries.remove(&lru);
+            tracing::info!("🧠 Unloaded {lru} (least recently used)");
+        }
+    }
+
+    fn evict_idle_locked(&self, inner: &mut Inner<T>) -> usize {
+        let Some(ttl) = self.limits.idle_ttl else {
+            return 0;
+        };
+        let before = inner.entries.len();
+        inner.entries.retain(|key, entry| {
+            let keep = entry.last_used.elapsed() < ttl;
+            if !keep {
+                tracing::info!("🧠 Unloaded {key} (idle)");
+            }
+            keep
+        });
+        before - inner.entries.len()
+    }
+
+    fn bytes_of(inner: &Inner<T>) -> u64 {
+        inner.entries.values().map(|e| e.bytes).sum()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner<T>> {
+        self.inner.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn cache(max_models: usize, max_bytes: u64) -> ModelCache<String> {
+        ModelCache::new(CacheLimits {
+            max_models,
+            max_bytes,
+            idle_ttl: None,
+        })
+    }
+
+    fn load(cache: &ModelCache<String>, name: &str, bytes: u64) -> Arc<String> {
+        cache
+            .get_or_load(name, bytes, || Ok(name.to_string()))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = cache(2, 0);
+        load(&cache, "a", 1);
+        load(&cache, "b", 1);
+        // Using "a" again makes "b" the least recently used.
+        load(&cache, "a", 1);
+        load(&cache, "c", 1);
+        assert_eq!(cache.loaded(), ["c", "a"]);
+
+        load(&cache, "d", 1);
+        assert_eq!(cache.loaded(), ["d", "c"]);
+    }
+
+    #[test]
+    fn test_byte_cap_unloads_lru() {
+        let cache = cache(10, 100);
+        load(&cache, "small", 30);
+        load(&cache, "medium", 50);
+        assert_eq!(cache.loaded_bytes(), 80);
+
+        // 80 + 40 > 100: the oldest goes, and that is enough.
+        let held = load(&cache, "large", 40);
+        assert_eq!(cache.loaded(), ["large", "medium"]);
+        assert_eq!(cache.loaded_bytes(), 90);
+
+        // A model larger than the cap still loads, alone.
+        load(&cache, "huge", 500);
+        assert_eq!(cache.loaded(), ["huge"]);
+        // An evicted model stays usable by whoever holds it.
+        assert_eq!(*held, "large");
+    }
+
+    #[test]
+    fn test_concurrent_requests_load_once() {
+        let cache = Arc::new(cache(1, 0));
+        let loads = Arc::new(AtomicUsize::new(0));
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let cache = cache.clone();
+                let loads = loads.clone();
+                std::thread::spawn(move || {
+                    cache
+                        .get_or_load("m", 1, || {
+                            loads.fetch_add(1, Ordering::SeqCst);
+                            std::thread::sleep(Duration::from_millis(20));
+                            Ok("m".to_string())
+                        })
+                        .unwrap()
+                })
+            })
+            .collect();
+        for t in threads {
+            assert_eq!(*t.join().unwrap(), "m");
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_idle_models_unloaded_and_failed_load_not_cached() {
+        let cache = ModelCache::new(CacheLimits {
+            max_models: 4,
+            max_bytes: 0,
+            idle_ttl: Some(Duration::ZERO),
+        });
+        load(&cache, "a", 1);
+        assert_eq!(cache.evict_idle(), 1);
+        assert!(cache.loaded().is_empty());
+
+        let failed = cache.get_or_load("bad", 1, || {
+            Err(bizclaw_core::error::BizClawError::Brain("corrupt".into()))
+        });
+        assert!(failed.is_err());
+        assert!(cache.loaded().is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+//! Model catalog — downloadable GGUF models for `bizclaw brain`.
+//!
+//! The built-in catalog is embedded at compile time. A remote catalog (same
+//! JSON format) can replace it, and custom entries from `[brain] catalog`
+//! are merged on top, overriding built-in entries with the same name.
+
+use bizclaw_core::error::{BizClawError, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+pub use bizclaw_core::config::ModelCatalogEntry as CatalogEntry;
+
+const EMBEDDED_CATALOG: &str = include_str!("catalog.json");
+
+/// A list of downloadable models.
+#[derive(Debug, Clone, Default)]
+pub struct ModelCatalog {
+    entries: Vec<CatalogEntry>,
+}
+
+/// Catalog JSON is either `{"models": [...]}` or a bare array.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CatalogFile {
+    Wrapped { models: Vec<CatalogEntry> },
+    Bare(Vec<CatalogEntry>),
+}
+
+impl ModelCatalog {
+    /// The catalog shipped with this build.
+    pub fn embedded() -> Self {
+        Self::from_json(EMBEDDED_CATALOG).expect("embedded catalog.json is valid")
+    }
+
+    /// Parse a catalog from JSON.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let file: CatalogFile = serde_json::from_str(json)
+            .map_err(|e| BizClawError::Brain(format!("Invalid model catalog: {e}")))?;
+        let entries = match file {
+            CatalogFile::Wrapped { models } => models,
+            CatalogFile::Bare(models) => models,
+        };
+        if let Some(bad) = entries
+            .iter()
+            .find(|e| e.name.trim().is_empty() || e.url.trim().is_empty())
+        {
+            return Err(BizClawError::Brain(format!(
+                "Invalid model catalog: entry '{}' needs a name and url",
+                bad.name
+            )));
+        }
+        Ok(Self { entries })
+    }
+
+    /// Merge custom entries; an entry with an existing name replaces it.
+    pub fn with_custom(mut self, custom: &[CatalogEntry]) -> Self {
+        for entry in custom {
+            match self
+                .entries
+                .iter_mut()
+                .find(|e| e.name.eq_ignore_ascii_case(&entry.name))
+            {
+                Some(existing) => *existing = entry.clone(),
+                None => self.entries.push(entry.clone()),
+            }
+        }
+        self
+    }
+
+    pub fn entries(&self) -> &[CatalogEntry] {
+        &self.entries
+    }
+
+    /// Find a model by name or alias (case-insensitive).
+    pub fn resolve(&self, name: &str) -> Option<&CatalogEntry> {
+        let name = name.trim();
+        self.entries.iter().find(|e| {
+            e.name.eq_ignore_ascii_case(name)
+                || e.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+        })
+    }
+
+    /// Models whose recommended RAM fits in `ram_mb`, smallest first.
+    pub fn fitting_ram(&self, ram_mb: u64) -> Vec<&CatalogEntry> {
+        let mut fits: Vec<&CatalogEntry> = self
+            .entries
+            .iter()
+            .filter(|e| e.min_ram_mb <= ram_mb)
+            .collect();
+        fits.sort_by_key(|e| e.size_mb);
+        fits
+    }
+}
+
+/// Local file name for a catalog entry (last URL path segment).
+pub fn file_name(entry: &CatalogEntry) -> String {
+    let path = entry.url.split(['?', '#']).next().unwrap_or(&entry.url);
+    match path.rsplit('/').next() {
+        Some(name) if name.ends_with(".gguf") => name.to_string(),
+        _ => format!("{}.gguf", entry.name),
+    }
+}
+
+/// SHA-256 in a Hugging Face `X-Linked-Etag` header (the quoted LFS object
+/// id), used to verify entries without a `sha256`. `None` when the etag is
+/// not a SHA-256, e.g. for files stored outside LFS.
+pub fn sha256_from_etag(etag: &str) -> Option<String> {
+    let etag = etag.trim().trim_start_matches("W/").trim_matches('"');
+    (etag.len() == 64 && etag.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then(|| etag.to_ascii_lowercase())
+}
+
+/// Total system RAM in MB (Linux only; `None` elsewhere).
+pub fn system_ram_mb() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    meminfo
+        .lines()
+        .find(|l| l.starts_with("MemTotal:"))
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb / 1024)
+}
+
+/// Check a downloaded file against an expected SHA-256 (hex, case-insensitive).
+pub fn verify_sha256(path: &Path, expected: &str) -> Result<bool> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| BizClawError::Brain(format!("Open {}: {e}", path.display())))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1 << 20];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| BizClawError::Brain(format!("Read {}: {e}", path.display())))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    let actual: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    Ok(actual.eq_ignore_ascii_case(expected.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, size_mb: u64, min_ram_mb: u64) -> CatalogEntry {
+        CatalogEntry {
+            name: name.into(),
+            aliases: vec![],
+            url: format!("https://example.com/{name}.Q4_K_M.gguf?download=true"),
+            sha256: None,
+            size_mb,
+            min_ram_mb,
+            quant: "Q4_K_M".into(),
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_catalog_parsing() {
+        let catalog = ModelCatalog::embedded();
+        assert!(catalog.entries().len() >= 5);
+        assert!(catalog.entries().iter().all(|e| e.url.ends_with(".gguf")));
+
+        let bare = ModelCatalog::from_json(
+            r#"[{"name": "m", "url": "https://x/m.gguf", "size_mb": 10, "sha256": "ab"}]"#,
+        )
+        .unwrap();
+        assert_eq!(bare.entries()[0].sha256.as_deref(), Some("ab"));
+        assert_eq!(bare.entries()[0].min_ram_mb, 0);
+
+        assert!(ModelCatalog::from_json(r#"{"models": [{"name": "", "url": "x"}]}"#).is_err());
+        assert!(ModelCatalog::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_name_resolution() {
+        let catalog = ModelCatalog::embedded();
+        assert_eq!(catalog.resolve("TinyLlama").unwrap().name, "tinyllama-1.1b");
+        assert_eq!(catalog.resolve("llama3.2").unwrap().name, "llama-3.2-1b");
+        assert!(catalog.resolve("gpt-5").is_none());
+
+        let custom = catalog.with_custom(&[entry("my-model", 100, 256), entry("phi-2", 1, 1)]);
+        assert_eq!(
+            file_name(custom.resolve("my-model").unwrap()),
+            "my-model.Q4_K_M.gguf"
+        );
+        assert_eq!(custom.resolve("phi-2").unwrap().size_mb, 1);
+    }
+
+    #[test]
+    fn test_low_ram_filter() {
+        let catalog = ModelCatalog::default().with_custom(&[
+            entry("big", 4000, 8192),
+            entry("small", 300, 512),
+            entry("mid", 700, 1024),
+        ]);
+        let names: Vec<&str> = catalog
+            .fitting_ram(1024)
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["small", "mid"]);
+        assert!(catalog.fitting_ram(256).is_empty());
+    }
+
+    #[test]
+    fn test_sha256_from_etag() {
+        let sha = "AB01".repeat(16);
+        assert_eq!(
+            sha256_from_etag(&format!("\"{sha}\"")),
+            Some(sha.to_ascii_lowercase())
+        );
+        // Git blob ids (non-LFS files) are not checksums of the content.
+        assert_eq!(sha256_from_etag("\"a3f1c2e4b5d6978812345678901234567890abcd\""), None);
+        assert_eq!(sha256_from_etag(""), None);
+    }
+
+    #[test]
+    fn test_verify_sha256() {
+        let path = std::env::temp_dir().join(format!("bizclaw-sha-{}.bin", std::process::id()));
+        std::fs::write(&path, b"abc").unwrap();
+        let abc = "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD";
+        assert!(verify_sha256(&path, abc).unwrap());
+        assert!(!verify_sha256(&path, "00").unwrap());
+        std::fs::remove_file(&path).ok();
+    }
+}
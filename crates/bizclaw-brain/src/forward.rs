@@ -262,12 +262,6 @@ fn matmul_weight(
     let data = model.tensor_data(idx)?;
     let tensor = &model.gguf.tensors[idx];
 
-    // Dequantize entire weight matrix
-    let n_elements = rows * cols;
-    let mut weight = vec![0.0f32; n_elements];
-    quant::dequantize_row(data, &mut weight, n_elements, tensor.ggml_type)?;
-
-    // MatMul
-    tensor::matmul(output, &weight, input, rows, cols);
-    Ok(())
+    // Quantized weights with a direct kernel skip the f32 copy
+    tensor::matmul_stored(output, data, tensor.ggml_type, input, rows, cols)
 }
@@ -11,6 +11,8 @@
 )]
 
 pub mod attention;
+pub mod cache;
+pub mod catalog;
 pub mod forward;
 pub mod gguf;
 pub mod grammar;
@@ -21,6 +23,7 @@ pub mod model;
 pub mod quant;
 pub mod rope;
 pub mod sampler;
+pub mod scheduler;
 pub mod simd;
 pub mod tensor;
 pub mod thread_pool;
@@ -39,6 +42,12 @@ pub struct BrainConfig {
     pub temperature: f32,
     pub top_p: f32,
     pub json_mode: bool,
+    /// Sampling seed; the same seed and prompt give the same output.
+    /// `None` seeds from entropy. Irrelevant at temperature 0 (greedy).
+    pub seed: Option<u64>,
+    /// Run a one-token generation right after loading, so the first real
+    /// request does not pay for page faults and thread pool start-up.
+    pub warm_up: bool,
 }
 
 impl Default for BrainConfig {
@@ -50,6 +59,8 @@ impl Default for BrainConfig {
             temperature: 0.7,
             top_p: 0.9,
             json_mode: false,
+            seed: None,
+            warm_up: false,
         }
     }
 }
@@ -59,6 +70,8 @@ pub struct BrainEngine {
     config: BrainConfig,
     /// Loaded model (mmap)
     model: Option<LoadedModel>,
+    /// Whether a warm-up generation has run on the loaded model.
+    warm: bool,
 }
 
 /// A loaded model ready for inference.
@@ -71,30 +84,68 @@ struct LoadedModel {
     weights: forward::TransformerWeights,
     /// BPE tokenizer
     tokenizer: tokenizer::BpeTokenizer,
-    /// KV cache for generation
-    kv_cache: kv_cache::KvCache,
     /// Sampler
     sampler: sampler::Sampler,
     /// Model file path
     path: PathBuf,
 }
 
+/// State of one in-progress generation, advanced token by token.
+pub struct GenerationSession {
+    input_tokens: Vec<u32>,
+    output_tokens: Vec<u32>,
+    /// Next position to run through the model.
+    pos: usize,
+    max_gen: usize,
+    kv_cache: kv_cache::KvCache,
+    capacity: usize,
+    logits: Vec<f32>,
+    /// Sampling RNG (seeded when a seed was requested).
+    rng: rand::rngs::StdRng,
+    done: bool,
+}
+
+impl GenerationSession {
+    /// Number of tokens generated so far.
+    pub fn generated(&self) -> usize {
+        self.output_tokens.len()
+    }
+}
+
+impl scheduler::Decoder for BrainEngine {
+    type Session = GenerationSession;
+
+    fn begin(
+        &mut self,
+        prompt: &str,
+        max_tokens: u32,
+        seed: Option<u64>,
+    ) -> Result<GenerationSession> {
+        self.start_seeded_session(prompt, max_tokens, seed.or(self.config.seed))
+    }
+
+    fn step(&mut self, session: &mut GenerationSession) -> Result<bool> {
+        self.step_session(session)
+    }
+
+    fn finish(&mut self, session: GenerationSession) -> String {
+        self.finish_session(session)
+    }
+}
+
 impl BrainEngine {
     /// Create a new brain engine (model not yet loaded).
     pub fn new(config: BrainConfig) -> Self {
         Self {
             config,
             model: None,
+            warm: false,
         }
     }
 
     /// Load a model from a GGUF file.
     pub fn load(model_path: &Path) -> Result<Self> {
-        let config = BrainConfig::default();
-        let mut engine = Self {
-            config,
-            model: None,
-        };
+        let mut engine = Self::new(BrainConfig::default());
         engine.load_model(model_path)?;
         Ok(engine)
     }
@@ -104,6 +155,7 @@ impl BrainEngine {
         tracing::info!("Loading model from: {}", model_path.display());
 
         let mmap_model = mmap::MmapModel::load(model_path)?;
+        model::check_architecture(&mmap_model.gguf)?;
         let params = model::ModelParams::from_gguf(&mmap_model.gguf);
 
         tracing::info!(
@@ -133,16 +185,16 @@ impl BrainEngine {
 
         tracing::info!("Tokenizer loaded: vocab_size={}", tokenizer.vocab_size());
 
-        // Create KV cache
-        let kv_cache = kv_cache::KvCache::new(
-            params.n_layers as usize,
-            params.max_seq_len as usize,
-            params.n_kv_heads as usize,
-            params.head_dim as usize,
-        );
+        // KV caches are allocated per generation session (see `start_session`)
+        let kv_bytes = 2
+            * params.n_layers as usize
+            * params.max_seq_len as usize
+            * params.n_kv_heads as usize
+            * params.head_dim as usize
+            * std::mem::size_of::<f32>();
         tracing::info!(
-            "KV cache: {:.1} MB",
-            kv_cache.memory_usage() as f64 / 1024.0 / 1024.0
+            "KV cache (max per session): {:.1} MB",
+            kv_bytes as f64 / 1024.0 / 1024.0
         );
 
         // Create sampler
@@ -159,12 +211,17 @@ impl BrainEngine {
             params,
             weights,
             tokenizer,
-            kv_cache,
             sampler,
             path: model_path.to_path_buf(),
         });
+        self.warm = false;
 
         tracing::info!("✅ Model loaded successfully: {}", model_path.display());
+        if self.config.warm_up
+            && let Err(e) = self.warm_up()
+        {
+            tracing::warn!("Brain warm-up failed: {e}");
+        }
         Ok(())
     }
 
@@ -173,71 +230,159 @@ impl BrainEngine {
         self.model.is_some()
     }
 
-    /// Generate text completion using the loaded model.
+    /// Generate one token from a dummy prompt and discard it. This faults
+    /// in the mmapped weights and starts the rayon pool up front.
+    pub fn warm_up(&mut self) -> Result<std::time::Duration> {
+        let start = std::time::Instant::now();
+        let mut session = self.start_seeded_session("Hello", 1, Some(0))?;
+        while !self.step_session(&mut session)? {}
+        self.finish_session(session);
+        let elapsed = start.elapsed();
+        self.warm = true;
+        tracing::info!("🔥 Brain warm-up done in {}ms", elapsed.as_millis());
+        Ok(elapsed)
+    }
+
+    /// Check if a model is loaded and has been warmed up.
+    pub fn is_warm(&self) -> bool {
+        self.model.is_some() && self.warm
+    }
+
+    /// Generate text completion using the loaded model (configured seed).
     pub fn generate(&mut self, prompt: &str, max_tokens: u32) -> Result<String> {
+        self.generate_seeded(prompt, max_tokens, self.config.seed)
+    }
+
+    /// Generate with an explicit sampling seed (`None` = from entropy).
+    pub fn generate_seeded(
+        &mut self,
+        prompt: &str,
+        max_tokens: u32,
+        seed: Option<u64>,
+    ) -> Result<String> {
+        let mut session = self.start_seeded_session(prompt, max_tokens, seed)?;
+        while !self.step_session(&mut session)? {}
+        Ok(self.finish_session(session))
+    }
+
+    /// Tokenize a prompt and allocate a KV cache for step-wise generation.
+    pub fn start_session(&self, prompt: &str, max_tokens: u32) -> Result<GenerationSession> {
+        self.start_seeded_session(prompt, max_tokens, self.config.seed)
+    }
+
+    /// [`start_session`](Self::start_session) with an explicit sampling seed.
+    pub fn start_seeded_session(
+        &self,
+        prompt: &str,
+        max_tokens: u32,
+        seed: Option<u64>,
+    ) -> Result<GenerationSession> {
         let model = self
             .model
-            .as_mut()
+            .as_ref()
             .ok_or_else(|| BizClawError::Brain("Model not loaded".into()))?;
 
         // Tokenize prompt
         let mut input_tokens = vec![model.tokenizer.bos_id];
         input_tokens.extend(model.tokenizer.encode(prompt));
 
-        let total_len = input_tokens.len();
+        let max_gen = max_tokens.min(self.config.max_tokens) as usize;
+        let capacity = (input_tokens.len() + max_gen).min(model.params.max_seq_len as usize);
         tracing::debug!(
             "Generate: prompt_len={}, input_tokens={}",
             prompt.len(),
-            total_len
+            input_tokens.len()
         );
 
-        let mut output_tokens = Vec::new();
-        let max_gen = max_tokens.min(self.config.max_tokens) as usize;
-        let mut logits = vec![0.0f32; model.params.vocab_size as usize];
-
-        for step in 0..total_len + max_gen {
-            // Get the token to process
-            let token = if step < total_len {
-                input_tokens[step]
-            } else if let Some(&last) = output_tokens.last() {
-                last
+        Ok(GenerationSession {
+            input_tokens,
+            output_tokens: Vec::new(),
+            pos: 0,
+            max_gen,
+            kv_cache: kv_cache::KvCache::new(
+                model.params.n_layers as usize,
+                capacity,
+                model.params.n_kv_heads as usize,
+                model.params.head_dim as usize,
+            ),
+            capacity,
+            logits: vec![0.0f32; model.params.vocab_size as usize],
+            rng: sampler::rng_for(seed),
+            done: false,
+        })
+    }
+
+    /// Run one forward pass for a session (one prompt or output token).
+    /// Returns `true` once the session has finished generating.
+    pub fn step_session(&self, session: &mut GenerationSession) -> Result<bool> {
+        let model = self
+            .model
+            .as_ref()
+            .ok_or_else(|| BizClawError::Brain("Model not loaded".into()))?;
+        if session.done {
+            return Ok(true);
+        }
+
+        let total_len = session.input_tokens.len();
+        let step = session.pos;
+        if step >= session.capacity || session.output_tokens.len() >= session.max_gen {
+            session.done = true;
+            return Ok(true);
+        }
+
+        // Get the token to process
+        let token = if step < total_len {
+            session.input_tokens[step]
+        } else if let Some(&last) = session.output_tokens.last() {
+            last
+        } else {
+            session.done = true;
+            return Ok(true);
+        };
+
+        // Run forward pass
+        forward::forward(
+            &model.mmap_model,
+            &model.weights,
+            &model.params,
+            &mut session.kv_cache,
+            token,
+            step,
+            &mut session.logits,
+        )?;
+        session.pos += 1;
+
+        // Only sample after processing all input tokens
+        if step + 1 >= total_len {
+            let all_tokens: Vec<u32> = session
+                .input_tokens
+                .iter()
+                .chain(session.output_tokens.iter())
+                .copied()
+                .collect();
+            let next_token =
+                model
+                    .sampler
+                    .sample_with(&mut session.logits, &all_tokens, &mut session.rng);
+
+            // Check for EOS
+            if next_token == model.tokenizer.eos_id {
+                session.done = true;
             } else {
-                break;
-            };
-
-            // Run forward pass
-            forward::forward(
-                &model.mmap_model,
-                &model.weights,
-                &model.params,
-                &mut model.kv_cache,
-                token,
-                step,
-                &mut logits,
-            )?;
-
-            // Only sample after processing all input tokens
-            if step >= total_len - 1 {
-                let all_tokens: Vec<u32> = input_tokens
-                    .iter()
-                    .chain(output_tokens.iter())
-                    .copied()
-                    .collect();
-                let next_token = model.sampler.sample(&mut logits, &all_tokens);
-
-                // Check for EOS
-                if next_token == model.tokenizer.eos_id {
-                    break;
-                }
-
-                output_tokens.push(next_token);
+                session.output_tokens.push(next_token);
             }
         }
 
-        // Decode output tokens
-        let output = model.tokenizer.decode(&output_tokens);
-        tracing::debug!("Generated {} tokens", output_tokens.len());
-        Ok(output)
+        Ok(session.done)
+    }
+
+    /// Decode the generated tokens of a session.
+    pub fn finish_session(&self, session: GenerationSession) -> String {
+        tracing::debug!("Generated {} tokens", session.output_tokens.len());
+        match &self.model {
+            Some(model) => model.tokenizer.decode(&session.output_tokens),
+            None => String::new(),
+        }
     }
 
     /// Generate with JSON grammar constraint.
@@ -264,3 +409,128 @@ impl BrainEngine {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write a one-layer LLaMA GGUF with small F32 weights.
+    fn write_tiny_model(path: &Path) {
+        write_model(path, "llama", &[]);
+    }
+
+    /// Like [`write_tiny_model`], declaring `arch` and the extra
+    /// `{arch}.*` metadata in `extra`.
+    fn write_model(path: &Path, arch: &str, extra: &[(&str, u32)]) {
+        const DIM: u64 = 8;
+        const HIDDEN: u64 = 16;
+        const VOCAB: u64 = 300;
+
+        fn string(out: &mut Vec<u8>, s: &str) {
+            out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+            out.extend_from_slice(s.as_bytes());
+        }
+
+        let mut meta_u32 = vec![
+            ("embedding_length", DIM as u32),
+            ("feed_forward_length", HIDDEN as u32),
+            ("block_count", 1),
+            ("attention.head_count", 2),
+            ("attention.head_count_kv", 2),
+            ("vocab_size", VOCAB as u32),
+            ("context_length", 64),
+        ];
+        meta_u32.extend_from_slice(extra);
+        let tensors: [(&str, &[u64]); 12] = [
+            ("token_embd.weight", &[DIM, VOCAB]),
+            ("output_norm.weight", &[DIM]),
+            ("output.weight", &[DIM, VOCAB]),
+            ("blk.0.attn_norm.weight", &[DIM]),
+            ("blk.0.attn_q.weight", &[DIM, DIM]),
+            ("blk.0.attn_k.weight", &[DIM, DIM]),
+            ("blk.0.attn_v.weight", &[DIM, DIM]),
+            ("blk.0.attn_output.weight", &[DIM, DIM]),
+            ("blk.0.ffn_norm.weight", &[DIM]),
+            ("blk.0.ffn_gate.weight", &[DIM, HIDDEN]),
+            ("blk.0.ffn_up.weight", &[DIM, HIDDEN]),
+            ("blk.0.ffn_down.weight", &[HIDDEN, DIM]),
+        ];
+
+        let mut out = b"GGUF".to_vec();
+        out.extend_from_slice(&3u32.to_le_bytes());
+        out.extend_from_slice(&(tensors.len() as u64).to_le_bytes());
+        out.extend_from_slice(&(meta_u32.len() as u64 + 1).to_le_bytes());
+        string(&mut out, "general.architecture");
+        out.extend_from_slice(&8u32.to_le_bytes());
+        string(&mut out, arch);
+        for (key, value) in meta_u32 {
+            string(&mut out, &format!("{arch}.{key}"));
+            out.extend_from_slice(&4u32.to_le_bytes());
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let mut offset = 0u64;
+        for (name, dims) in tensors {
+            string(&mut out, name);
+            out.extend_from_slice(&(dims.len() as u32).to_le_bytes());
+            for d in dims {
+                out.extend_from_slice(&d.to_le_bytes());
+            }
+            out.extend_from_slice(&0u32.to_le_bytes()); // F32
+            out.extend_from_slice(&offset.to_le_bytes());
+            offset += dims.iter().product::<u64>() * 4;
+        }
+        out.resize(out.len().div_ceil(32) * 32, 0);
+        for i in 0..offset / 4 {
+            out.extend_from_slice(&(((i % 7) as f32 - 3.0) * 0.01).to_le_bytes());
+        }
+        std::fs::write(path, out).unwrap();
+    }
+
+    #[test]
+    fn test_warm_up_on_load() {
+        let path = std::env::temp_dir().join(format!("bizclaw-warm-{}.gguf", std::process::id()));
+        write_tiny_model(&path);
+
+        let mut cold = BrainEngine::new(BrainConfig::default());
+        cold.load_model(&path).unwrap();
+        assert!(cold.is_loaded());
+        assert!(!cold.is_warm());
+
+        let mut engine = BrainEngine::new(BrainConfig {
+            warm_up: true,
+            ..Default::default()
+        });
+        engine.load_model(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(engine.is_warm());
+        assert!(engine.generate("Hi", 4).is_ok());
+    }
+
+    #[test]
+    fn test_unsupported_architecture_rejected_at_load() {
+        let path = std::env::temp_dir().join(format!("bizclaw-arch-{}.gguf", std::process::id()));
+        let load = |arch: &str, extra: &[(&str, u32)]| {
+            write_model(&path, arch, extra);
+            BrainEngine::new(BrainConfig::default()).load_model(&path)
+        };
+
+        let vision = load("mllama", &[]);
+        let moe = load("llama", &[("expert_count", 8), ("expert_used_count", 2)]);
+        let mistral = load("mistral", &[]);
+        let _ = std::fs::remove_file(&path);
+
+        let Err(BizClawError::Brain(msg)) = vision else {
+            panic!("expected a Brain error, got {vision:?}");
+        };
+        assert_eq!(
+            msg,
+            "unsupported architecture: mllama (supported: llama, mistral)"
+        );
+        let Err(BizClawError::Brain(msg)) = moe else {
+            panic!("expected a Brain error, got {moe:?}");
+        };
+        assert!(msg.starts_with("unsupported architecture: llama with 8 experts"));
+        assert!(mistral.is_ok());
+    }
+}
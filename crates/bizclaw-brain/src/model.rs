@@ -4,6 +4,35 @@
 //! Reads weights from mmap, dequantizes on-the-fly, and computes
 //! the forward pass producing logits for the next token.
 
+use bizclaw_core::error::{BizClawError, Result};
+
+/// `general.architecture` values the forward pass runs: LLaMA and models
+/// with the same layout (TinyLlama, SmolLM2, Mistral).
+pub const SUPPORTED_ARCHITECTURES: &[&str] = &["llama", "mistral"];
+
+/// Reject a model the forward pass can't run, so it fails at load time
+/// instead of generating garbage. Models without `general.architecture`
+/// are treated as LLaMA.
+pub fn check_architecture(gguf: &crate::gguf::GgufFile) -> Result<()> {
+    let arch = gguf.architecture().unwrap_or("llama");
+    let supported = SUPPORTED_ARCHITECTURES.join(", ");
+    if !SUPPORTED_ARCHITECTURES.contains(&arch) {
+        return Err(BizClawError::Brain(format!(
+            "unsupported architecture: {arch} (supported: {supported})"
+        )));
+    }
+    // Mixtral-style MoE models keep the `llama` name but add experts.
+    if let Some(experts) = gguf
+        .get_u32(&format!("{arch}.expert_count"))
+        .filter(|&n| n > 0)
+    {
+        return Err(BizClawError::Brain(format!(
+            "unsupported architecture: {arch} with {experts} experts (mixture of experts; supported: {supported})"
+        )));
+    }
+    Ok(())
+}
+
 /// Model hyperparameters extracted from GGUF metadata.
 #[derive(Debug, Clone)]
 pub struct ModelParams {
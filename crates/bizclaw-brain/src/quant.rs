@@ -1,4 +1,5 @@
-//! Quantization kernels — dequantize quantized weight blocks to f32.
+//! Quantization kernels — dequantize quantized weight blocks to f32, or
+//! take dot products with them directly.
 //!
 //! Supports Q4_0, Q4_K_M, Q6_K, Q8_0 formats used by GGUF models.
 
@@ -34,6 +35,123 @@ pub fn dequantize_q8_0(block: &[u8], output: &mut [f32]) {
     }
 }
 
+/// Scale and min of Q4_K sub-block `j`, packed as 6-bit values in `scales`.
+#[inline]
+fn q4_k_scale_min(j: usize, scales: &[u8]) -> (f32, f32) {
+    let (sc, m) = if j < 4 {
+        (scales[j] & 63, scales[j + 4] & 63)
+    } else {
+        (
+            (scales[j + 4] & 0x0F) | ((scales[j - 4] >> 6) << 4),
+            (scales[j + 4] >> 4) | ((scales[j] >> 6) << 4),
+        )
+    };
+    (sc as f32, m as f32)
+}
+
+/// Dequantize Q4_K block (144 bytes → 256 f32 values).
+/// Format: d (f16) + dmin (f16) + 12 bytes of packed 6-bit scales and mins
+/// + 128 bytes of 4-bit values, as 8 sub-blocks of 32.
+pub fn dequantize_q4_k(block: &[u8], output: &mut [f32]) {
+    debug_assert!(block.len() >= 144);
+    debug_assert!(output.len() >= 256);
+
+    let d = half::f16::from_le_bytes([block[0], block[1]]).to_f32();
+    let dmin = half::f16::from_le_bytes([block[2], block[3]]).to_f32();
+    let scales = &block[4..16];
+
+    // Each 32 bytes of `qs` hold two sub-blocks: low nibbles, then high.
+    for (chunk, qs) in block[16..144].chunks_exact(32).enumerate() {
+        let (sc_lo, m_lo) = q4_k_scale_min(chunk * 2, scales);
+        let (sc_hi, m_hi) = q4_k_scale_min(chunk * 2 + 1, scales);
+        let out = &mut output[chunk * 64..chunk * 64 + 64];
+        for (l, &q) in qs.iter().enumerate() {
+            out[l] = d * sc_lo * (q & 0x0F) as f32 - dmin * m_lo;
+            out[l + 32] = d * sc_hi * (q >> 4) as f32 - dmin * m_hi;
+        }
+    }
+}
+
+/// Dot product of a Q4_K block with 256 f32 values, without dequantizing.
+/// Each sub-block contributes `d·sc·Σ(q·x) − dmin·m·Σx`.
+pub fn vec_dot_q4_k(block: &[u8], x: &[f32]) -> f32 {
+    debug_assert!(block.len() >= 144);
+    debug_assert!(x.len() >= 256);
+
+    let d = half::f16::from_le_bytes([block[0], block[1]]).to_f32();
+    let dmin = half::f16::from_le_bytes([block[2], block[3]]).to_f32();
+    let scales = &block[4..16];
+
+    let mut sum = 0.0f32;
+    for (chunk, qs) in block[16..144].chunks_exact(32).enumerate() {
+        let (sc_lo, m_lo) = q4_k_scale_min(chunk * 2, scales);
+        let (sc_hi, m_hi) = q4_k_scale_min(chunk * 2 + 1, scales);
+        let x_lo = &x[chunk * 64..chunk * 64 + 32];
+        let x_hi = &x[chunk * 64 + 32..chunk * 64 + 64];
+        let (mut qx_lo, mut qx_hi, mut sx_lo, mut sx_hi) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+        for l in 0..32 {
+            let q = qs[l];
+            qx_lo += (q & 0x0F) as f32 * x_lo[l];
+            qx_hi += (q >> 4) as f32 * x_hi[l];
+            sx_lo += x_lo[l];
+            sx_hi += x_hi[l];
+        }
+        sum += d * (sc_lo * qx_lo + sc_hi * qx_hi) - dmin * (m_lo * sx_lo + m_hi * sx_hi);
+    }
+    sum
+}
+
+/// Dot product of a Q4_0 block with 32 f32 values, without dequantizing.
+pub fn vec_dot_q4_0(block: &[u8], x: &[f32]) -> f32 {
+    debug_assert!(block.len() >= 18);
+    debug_assert!(x.len() >= 32);
+
+    let scale = half::f16::from_le_bytes([block[0], block[1]]).to_f32();
+    let mut sum = 0.0f32;
+    for i in 0..16 {
+        let byte = block[2 + i];
+        sum += ((byte & 0x0F) as f32 - 8.0) * x[i * 2];
+        sum += ((byte >> 4) as f32 - 8.0) * x[i * 2 + 1];
+    }
+    sum * scale
+}
+
+/// Dot product of a Q8_0 block with 32 f32 values, without dequantizing.
+pub fn vec_dot_q8_0(block: &[u8], x: &[f32]) -> f32 {
+    debug_assert!(block.len() >= 34);
+    debug_assert!(x.len() >= 32);
+
+    let scale = half::f16::from_le_bytes([block[0], block[1]]).to_f32();
+    let sum: f32 = (0..32).map(|i| block[2 + i] as i8 as f32 * x[i]).sum();
+    sum * scale
+}
+
+/// Whether [`vec_dot_row`] has a direct kernel for `ggml_type`.
+pub fn has_quantized_dot(ggml_type: crate::gguf::GgmlType) -> bool {
+    use crate::gguf::GgmlType;
+    matches!(ggml_type, GgmlType::Q4_0 | GgmlType::Q8_0 | GgmlType::Q4K)
+}
+
+/// Dot product of one quantized row with `x` (`x.len()` values, a multiple
+/// of the block size). `None` if `ggml_type` has no direct kernel.
+pub fn vec_dot_row(data: &[u8], x: &[f32], ggml_type: crate::gguf::GgmlType) -> Option<f32> {
+    use crate::gguf::GgmlType;
+    let kernel: fn(&[u8], &[f32]) -> f32 = match ggml_type {
+        GgmlType::Q4_0 => vec_dot_q4_0,
+        GgmlType::Q8_0 => vec_dot_q8_0,
+        GgmlType::Q4K => vec_dot_q4_k,
+        _ => return None,
+    };
+    let block_size = ggml_type.block_size();
+    let type_size = ggml_type.type_size();
+    Some(
+        data.chunks_exact(type_size)
+            .zip(x.chunks_exact(block_size))
+            .map(|(block, xs)| kernel(block, xs))
+            .sum(),
+    )
+}
+
 /// Dequantize a full row of quantized data to f32.
 /// Dispatches to the correct dequantization kernel based on type.
 pub fn dequantize_row(
@@ -83,6 +201,15 @@ pub fn dequantize_row(
                 dequantize_q8_0(block_data, &mut output[b * block_size..]);
             }
         }
+        crate::gguf::GgmlType::Q4K => {
+            let block_size = 256;
+            let type_size = 144;
+            let n_blocks = n_elements / block_size;
+            for b in 0..n_blocks {
+                let block_data = &data[b * type_size..];
+                dequantize_q4_k(block_data, &mut output[b * block_size..]);
+            }
+        }
         _ => {
             // For unsupported types, fill with zeros
             tracing::warn!(
@@ -116,4 +243,68 @@ mod tests {
         assert!((output[0] - 1.0).abs() < 0.01);
         assert!((output[1] - 2.0).abs() < 0.01);
     }
+
+    /// A Q4_K block where every sub-block has scale 2 and min 3, and every
+    /// byte of `qs` is `qs_byte`.
+    fn q4_k_block(d: f32, dmin: f32, qs_byte: u8) -> Vec<u8> {
+        let mut block = vec![0u8; 144];
+        block[0..2].copy_from_slice(&half::f16::from_f32(d).to_le_bytes());
+        block[2..4].copy_from_slice(&half::f16::from_f32(dmin).to_le_bytes());
+        block[4..8].fill(2); // scales 0..4
+        block[8..12].fill(3); // mins 0..4
+        block[12..16].fill(0x32); // scale 2 / min 3 for sub-blocks 4..8
+        block[16..].fill(qs_byte);
+        block
+    }
+
+    #[test]
+    fn test_dequantize_q4_k() {
+        let block = q4_k_block(1.0, 0.5, 0x51);
+        let mut output = vec![0.0f32; 256];
+        dequantize_q4_k(&block, &mut output);
+        // value = d·2·q − dmin·3: low nibble 1 → 0.5, high nibble 5 → 8.5
+        for chunk in output.chunks(64) {
+            assert!(chunk[..32].iter().all(|&v| (v - 0.5).abs() < 1e-6));
+            assert!(chunk[32..].iter().all(|&v| (v - 8.5).abs() < 1e-6));
+        }
+    }
+
+    #[test]
+    fn test_vec_dot_matches_dequantized() {
+        use crate::gguf::GgmlType;
+        let x: Vec<f32> = (0..512)
+            .map(|i| ((i * 37) % 19) as f32 / 7.0 - 1.3)
+            .collect();
+        let mut q4_0 = vec![0u8; 18];
+        q4_0[0..2].copy_from_slice(&half::f16::from_f32(0.25).to_le_bytes());
+        for (i, b) in q4_0[2..].iter_mut().enumerate() {
+            *b = (i * 29 % 256) as u8;
+        }
+        let mut q8_0 = vec![0u8; 34];
+        q8_0[0..2].copy_from_slice(&half::f16::from_f32(0.1).to_le_bytes());
+        for (i, b) in q8_0[2..].iter_mut().enumerate() {
+            *b = (i * 53 % 256) as u8;
+        }
+        let mut q4_k = q4_k_block(0.03, 0.01, 0);
+        q4_k.extend(q4_k_block(0.02, 0.04, 0));
+        for (i, b) in q4_k.iter_mut().enumerate().filter(|(i, _)| i % 144 >= 16) {
+            *b = (i * 71 % 256) as u8;
+        }
+
+        for (ggml_type, data, n) in [
+            (GgmlType::Q4_0, &q4_0, 32),
+            (GgmlType::Q8_0, &q8_0, 32),
+            (GgmlType::Q4K, &q4_k, 512),
+        ] {
+            let mut weights = vec![0.0f32; n];
+            dequantize_row(data, &mut weights, n, ggml_type).unwrap();
+            let expected: f32 = weights.iter().zip(&x).map(|(w, x)| w * x).sum();
+            let direct = vec_dot_row(data, &x[..n], ggml_type).unwrap();
+            assert!(
+                (direct - expected).abs() <= 1e-4 * expected.abs().max(1.0),
+                "{ggml_type:?}: {direct} vs {expected}"
+            );
+        }
+        assert!(vec_dot_row(&[], &[], GgmlType::Q6K).is_none());
+    }
 }
@@ -1,10 +1,14 @@
 //! Temperature + Top-p/Top-k sampling for token generation.
 
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 /// Sampler configuration.
 #[derive(Debug, Clone)]
 pub struct SamplerConfig {
+    /// `0` (or below) selects greedy decoding: always the highest logit,
+    /// lowest token id on ties. Greedy output is fully deterministic, so
+    /// any sampling seed is irrelevant there.
     pub temperature: f32,
     pub top_p: f32,
     pub top_k: u32,
@@ -34,8 +38,19 @@ impl Sampler {
         Self { config }
     }
 
-    /// Sample a token from logits.
+    /// Sample a token from logits using the thread-local RNG.
     pub fn sample(&self, logits: &mut [f32], last_tokens: &[u32]) -> u32 {
+        self.sample_with(logits, last_tokens, &mut rand::thread_rng())
+    }
+
+    /// Sample a token from logits, drawing randomness from `rng` — pass a
+    /// seeded RNG (see [`rng_for`]) for reproducible output.
+    pub fn sample_with<R: Rng + ?Sized>(
+        &self,
+        logits: &mut [f32],
+        last_tokens: &[u32],
+        rng: &mut R,
+    ) -> u32 {
         // Apply repeat penalty
         if self.config.repeat_penalty != 1.0 {
             let n = last_tokens.len().min(self.config.repeat_last_n);
@@ -51,19 +66,19 @@ impl Sampler {
             }
         }
 
+        // Greedy fast path: no scaling, sorting or softmax needed
+        if self.config.temperature <= 0.0 {
+            return argmax(logits);
+        }
+
         // Apply temperature
-        if self.config.temperature > 0.0 && self.config.temperature != 1.0 {
+        if self.config.temperature != 1.0 {
             let inv_temp = 1.0 / self.config.temperature;
             for logit in logits.iter_mut() {
                 *logit *= inv_temp;
             }
         }
 
-        // If temperature is 0, return argmax (greedy)
-        if self.config.temperature <= 0.0 {
-            return argmax(logits);
-        }
-
         // Create sorted indices
         let mut indices: Vec<(usize, f32)> =
             logits.iter().enumerate().map(|(i, &v)| (i, v)).collect();
@@ -109,7 +124,6 @@ impl Sampler {
         }
 
         // Random sampling
-        let mut rng = rand::thread_rng();
         let r: f32 = rng.r#gen();
         let mut cumulative = 0.0;
         for &(idx, prob) in &probs {
@@ -124,12 +138,94 @@ impl Sampler {
     }
 }
 
+/// RNG for one generation: seeded when `seed` is set, otherwise from entropy.
+pub fn rng_for(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
 /// Return the index of the maximum value (greedy decoding).
+///
+/// Ties go to the lowest index and NaN never wins, so the choice is stable.
 fn argmax(values: &[f32]) -> u32 {
-    values
-        .iter()
-        .enumerate()
-        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
-        .map(|(i, _)| i as u32)
-        .unwrap_or(0)
+    let mut best = 0;
+    let mut best_value = f32::NEG_INFINITY;
+    for (i, &v) in values.iter().enumerate() {
+        if v > best_value {
+            best = i;
+            best_value = v;
+        }
+    }
+    best as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn greedy() -> Sampler {
+        Sampler::new(SamplerConfig {
+            temperature: 0.0,
+            ..Default::default()
+        })
+    }
+
+    /// Stand-in for a forward pass: logits depend only on the context and
+    /// contain plenty of exact ties.
+    fn fake_logits(context: &[u32]) -> Vec<f32> {
+        let last = context.last().copied().unwrap_or(0);
+        (0..64u32)
+            .map(|t| ((t * 7 + last * 3) % 5) as f32)
+            .collect()
+    }
+
+    fn run(sampler: &Sampler, prompt: &[u32], n: usize) -> Vec<u8> {
+        let mut tokens = prompt.to_vec();
+        for _ in 0..n {
+            let mut logits = fake_logits(&tokens);
+            let next = sampler.sample(&mut logits, &tokens);
+            tokens.push(next);
+        }
+        tokens.iter().flat_map(|t| t.to_le_bytes()).collect()
+    }
+
+    fn run_seeded(seed: u64, n: usize) -> Vec<u32> {
+        let sampler = Sampler::new(SamplerConfig {
+            temperature: 0.9,
+            top_k: 0,
+            top_p: 1.0,
+            ..Default::default()
+        });
+        let mut rng = rng_for(Some(seed));
+        let mut tokens = vec![1];
+        for _ in 0..n {
+            let mut logits = fake_logits(&tokens);
+            let next = sampler.sample_with(&mut logits, &tokens, &mut rng);
+            tokens.push(next);
+        }
+        tokens
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_sampling() {
+        assert_eq!(run_seeded(42, 48), run_seeded(42, 48));
+        assert_ne!(run_seeded(42, 48), run_seeded(43, 48));
+    }
+
+    #[test]
+    fn test_greedy_ties_pick_lowest_id() {
+        let mut logits = vec![0.5, 2.0, f32::NAN, 2.0, -1.0];
+        assert_eq!(greedy().sample(&mut logits, &[]), 1);
+        assert_eq!(argmax(&[f32::NAN, f32::NAN]), 0);
+    }
+
+    #[test]
+    fn test_greedy_runs_are_identical() {
+        let prompt = [1, 15, 42, 7];
+        let first = run(&greedy(), &prompt, 48);
+        let second = run(&greedy(), &prompt, 48);
+        assert_eq!(first, second);
+    }
 }
@@ -0,0 +1,229 @@
+//! Fair scheduling of concurrent generation requests.
+//!
+//! The engine runs on a dedicated worker thread. Up to `max_in_flight`
+//! sessions are active at once and each advances one token per round
+//! (round-robin), so a long generation cannot starve a short one.
+//! Requests beyond the limit wait in the queue until a slot frees up.
+
+use bizclaw_core::error::{BizClawError, Result};
+use std::collections::VecDeque;
+use std::sync::mpsc;
+use tokio::sync::oneshot;
+
+/// A step-wise text generator driven by the [`Scheduler`].
+pub trait Decoder: Send + 'static {
+    /// Per-request generation state (tokens, KV cache, position).
+    type Session: Send + 'static;
+
+    /// Prepare a session for a prompt. `seed` fixes the sampling RNG.
+    fn begin(&mut self, prompt: &str, max_tokens: u32, seed: Option<u64>) -> Result<Self::Session>;
+
+    /// Advance a session by one token. Returns `true` when generation is finished.
+    fn step(&mut self, session: &mut Self::Session) -> Result<bool>;
+
+    /// Turn a finished session into its output text.
+    fn finish(&mut self, session: Self::Session) -> String;
+}
+
+struct Job {
+    prompt: String,
+    max_tokens: u32,
+    seed: Option<u64>,
+    reply: oneshot::Sender<Result<String>>,
+}
+
+/// Handle to a decoder running on its own worker thread.
+pub struct Scheduler {
+    tx: mpsc::Sender<Job>,
+    max_in_flight: usize,
+}
+
+impl Scheduler {
+    /// Move `decoder` onto a worker thread, interleaving up to `max_in_flight` requests.
+    pub fn spawn<D: Decoder>(decoder: D, max_in_flight: usize) -> Self {
+        let max_in_flight = max_in_flight.max(1);
+        let (tx, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("brain-scheduler".into())
+            .spawn(move || run(decoder, rx, max_in_flight))
+            .expect("failed to spawn brain scheduler thread");
+        Self { tx, max_in_flight }
+    }
+
+    /// Maximum number of sessions decoded concurrently.
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
+
+    /// Queue a prompt and wait for its completion. With a `seed`, sampling
+    /// is reproducible (falls back to the engine's configured seed).
+    pub async fn generate(
+        &self,
+        prompt: &str,
+        max_tokens: u32,
+        seed: Option<u64>,
+    ) -> Result<String> {
+        let (reply, rx) = oneshot::channel();
+        self.tx
+            .send(Job {
+                prompt: prompt.to_string(),
+                max_tokens,
+                seed,
+                reply,
+            })
+            .map_err(|_| BizClawError::Brain("Brain scheduler stopped".into()))?;
+        rx.await
+            .map_err(|_| BizClawError::Brain("Brain scheduler dropped the request".into()))?
+    }
+}
+
+fn run<D: Decoder>(mut decoder: D, rx: mpsc::Receiver<Job>, max_in_flight: usize) {
+    let mut active: VecDeque<(D::Session, oneshot::Sender<Result<String>>)> = VecDeque::new();
+
+    loop {
+        // Idle: block until work arrives (or every handle is dropped).
+        if active.is_empty() {
+            match rx.recv() {
+                Ok(job) => admit(&mut decoder, &mut active, job),
+                Err(_) => return,
+            }
+        }
+        while active.len() < max_in_flight {
+            match rx.try_recv() {
+                Ok(job) => admit(&mut decoder, &mut active, job),
+                Err(_) => break,
+            }
+        }
+
+        // One round: every active session advances by one token.
+        for _ in 0..active.len() {
+            let Some((mut session, reply)) = active.pop_front() else {
+                break;
+            };
+            if reply.is_closed() {
+                // Caller went away — free the slot.
+                continue;
+            }
+            match decoder.step(&mut session) {
+                Ok(false) => active.push_back((session, reply)),
+                Ok(true) => {
+                    let _ = reply.send(Ok(decoder.finish(session)));
+                }
+                Err(e) => {
+                    let _ = reply.send(Err(e));
+                }
+            }
+        }
+    }
+}
+
+fn admit<D: Decoder>(
+    decoder: &mut D,
+    active: &mut VecDeque<(D::Session, oneshot::Sender<Result<String>>)>,
+    job: Job,
+) {
+    match decoder.begin(&job.prompt, job.max_tokens, job.seed) {
+        Ok(session) => active.push_back((session, job.reply)),
+        Err(e) => {
+            let _ = job.reply.send(Err(e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Emits one character per step and records the order of steps.
+    struct MockDecoder {
+        trace: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct MockSession {
+        name: String,
+        remaining: u32,
+        out: String,
+    }
+
+    impl Decoder for MockDecoder {
+        type Session = MockSession;
+
+        fn begin(
+            &mut self,
+            prompt: &str,
+            max_tokens: u32,
+            _seed: Option<u64>,
+        ) -> Result<MockSession> {
+            Ok(MockSession {
+                name: prompt.to_string(),
+                remaining: max_tokens,
+                out: String::new(),
+            })
+        }
+
+        fn step(&mut self, s: &mut MockSession) -> Result<bool> {
+            self.trace.lock().unwrap().push(s.name.clone());
+            s.out.push('x');
+            s.remaining -= 1;
+            Ok(s.remaining == 0)
+        }
+
+        fn finish(&mut self, s: MockSession) -> String {
+            s.out
+        }
+    }
+
+    fn mock() -> (MockDecoder, Arc<Mutex<Vec<String>>>) {
+        let trace = Arc::new(Mutex::new(Vec::new()));
+        (
+            MockDecoder {
+                trace: trace.clone(),
+            },
+            trace,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_both_progress() {
+        let (decoder, trace) = mock();
+        let scheduler = Scheduler::spawn(decoder, 2);
+
+        let (long, short) = tokio::join!(
+            scheduler.generate("long", 200, None),
+            scheduler.generate("short", 5, None)
+        );
+        assert_eq!(long.unwrap().len(), 200);
+        assert_eq!(short.unwrap().len(), 5);
+
+        let trace = trace.lock().unwrap();
+        let last_short = trace.iter().rposition(|n| n == "short").unwrap();
+        let last_long = trace.iter().rposition(|n| n == "long").unwrap();
+        // The short request finished while the long one was still decoding.
+        assert!(last_short < last_long);
+        assert!(
+            last_short < 20,
+            "short request starved: finished at step {last_short}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight_queues_extra_requests() {
+        let (decoder, trace) = mock();
+        let scheduler = Scheduler::spawn(decoder, 1);
+        assert_eq!(scheduler.max_in_flight(), 1);
+
+        let (a, b) = tokio::join!(
+            scheduler.generate("a", 3, None),
+            scheduler.generate("b", 3, None)
+        );
+        assert!(a.is_ok() && b.is_ok());
+
+        // With a single slot the requests run back to back, never interleaved.
+        let trace = trace.lock().unwrap().join("");
+        assert!(
+            trace == "aaabbb" || trace == "bbbaaa",
+            "unexpected order: {trace}"
+        );
+    }
+}
@@ -2,6 +2,11 @@
 //!
 //! Pure Rust implementations with future SIMD acceleration.
 
+use bizclaw_core::error::{BizClawError, Result};
+
+use crate::gguf::GgmlType;
+use crate::quant;
+
 /// RMS normalization (Root Mean Square Layer Normalization).
 /// Used in LLaMA instead of LayerNorm.
 pub fn rmsnorm(output: &mut [f32], input: &[f32], weight: &[f32], eps: f32) {
@@ -20,6 +25,10 @@ pub fn rmsnorm(output: &mut [f32], input: &[f32], weight: &[f32], eps: f32) {
     }
 }
 
+/// Rows multiplied together by [`matmul`], so each `vec` element is loaded
+/// once per block instead of once per row.
+const ROW_BLOCK: usize = 4;
+
 /// Matrix-vector multiply: output = mat * vec.
 /// mat is [rows x cols] in row-major order.
 pub fn matmul(output: &mut [f32], mat: &[f32], vec: &[f32], rows: usize, cols: usize) {
@@ -27,10 +36,101 @@ pub fn matmul(output: &mut [f32], mat: &[f32], vec: &[f32], rows: usize, cols: u
     debug_assert_eq!(vec.len(), cols);
     debug_assert_eq!(output.len(), rows);
 
-    for i in 0..rows {
-        let row = &mat[i * cols..(i + 1) * cols];
-        output[i] = dot_product(row, vec);
+    for (block, out) in output.chunks_mut(ROW_BLOCK).enumerate() {
+        let rows = &mat[block * ROW_BLOCK * cols..][..out.len() * cols];
+        if out.len() < ROW_BLOCK {
+            for (o, row) in out.iter_mut().zip(rows.chunks_exact(cols)) {
+                *o = dot_product(row, vec);
+            }
+            continue;
+        }
+        let (r0, rest) = rows.split_at(cols);
+        let (r1, rest) = rest.split_at(cols);
+        let (r2, r3) = rest.split_at(cols);
+        let mut acc = [0.0f32; ROW_BLOCK];
+        for (j, &x) in vec.iter().enumerate() {
+            acc[0] += r0[j] * x;
+            acc[1] += r1[j] * x;
+            acc[2] += r2[j] * x;
+            acc[3] += r3[j] * x;
+        }
+        out.copy_from_slice(&acc);
+    }
+}
+
+/// How a weight stored as some [`GgmlType`] is multiplied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatmulPath {
+    /// Convert to f32, then blocked [`matmul`].
+    F32,
+    /// Dot products straight from the quantized blocks ([`matmul_quantized`]).
+    Quantized,
+    /// No direct kernel: dequantize the whole matrix, then [`matmul`].
+    Dequantize,
+}
+
+/// Pick the matmul kernel for a weight of `ggml_type` with `cols` columns.
+pub fn matmul_path(ggml_type: GgmlType, cols: usize) -> MatmulPath {
+    match ggml_type {
+        GgmlType::F32 | GgmlType::F16 => MatmulPath::F32,
+        t if quant::has_quantized_dot(t) && cols.is_multiple_of(t.block_size()) => {
+            MatmulPath::Quantized
+        }
+        _ => MatmulPath::Dequantize,
+    }
+}
+
+/// Matrix-vector multiply over a stored weight: output = weight * vec, where
+/// `data` is the raw tensor data of a [rows x cols] matrix of `ggml_type`.
+/// Quantized types with a direct kernel skip the full dequantization.
+pub fn matmul_stored(
+    output: &mut [f32],
+    data: &[u8],
+    ggml_type: GgmlType,
+    vec: &[f32],
+    rows: usize,
+    cols: usize,
+) -> Result<()> {
+    match matmul_path(ggml_type, cols) {
+        MatmulPath::Quantized => matmul_quantized(output, data, ggml_type, vec, rows, cols),
+        MatmulPath::F32 | MatmulPath::Dequantize => {
+            let mut weight = vec![0.0f32; rows * cols];
+            quant::dequantize_row(data, &mut weight, rows * cols, ggml_type)?;
+            matmul(output, &weight, vec, rows, cols);
+            Ok(())
+        }
+    }
+}
+
+/// Matrix-vector multiply straight from quantized rows: output = mat * vec.
+/// Each row of `data` is `cols / block_size` blocks of `ggml_type`.
+pub fn matmul_quantized(
+    output: &mut [f32],
+    data: &[u8],
+    ggml_type: GgmlType,
+    vec: &[f32],
+    rows: usize,
+    cols: usize,
+) -> Result<()> {
+    debug_assert_eq!(vec.len(), cols);
+    debug_assert_eq!(output.len(), rows);
+
+    if matmul_path(ggml_type, cols) != MatmulPath::Quantized {
+        return Err(BizClawError::Brain(format!(
+            "No quantized matmul for {ggml_type:?} with {cols} columns"
+        )));
+    }
+    let row_bytes = cols / ggml_type.block_size() * ggml_type.type_size();
+    if data.len() < rows * row_bytes {
+        return Err(BizClawError::Brain(format!(
+            "Weight data too short: {} bytes for {rows}x{cols} {ggml_type:?}",
+            data.len()
+        )));
     }
+    for (o, row) in output.iter_mut().zip(data.chunks_exact(row_bytes)) {
+        *o = quant::vec_dot_row(row, vec, ggml_type).unwrap_or_default();
+    }
+    Ok(())
 }
 
 /// Dot product of two vectors.
@@ -121,6 +221,74 @@ mod tests {
         assert!((output[1] - 15.0).abs() < 1e-6); // 4+5+6
     }
 
+    #[test]
+    fn test_blocked_matmul_matches_row_dots() {
+        // 7 rows: one full block of 4 plus a remainder of 3.
+        let (rows, cols) = (7, 5);
+        let mat: Vec<f32> = (0..rows * cols).map(|i| (i % 11) as f32 - 4.5).collect();
+        let vec_in: Vec<f32> = (0..cols).map(|i| i as f32 * 0.25 + 1.0).collect();
+        let mut output = vec![0.0; rows];
+        matmul(&mut output, &mat, &vec_in, rows, cols);
+        for (i, row) in mat.chunks(cols).enumerate() {
+            assert!((output[i] - dot_product(row, &vec_in)).abs() < 1e-5);
+        }
+    }
+
+    /// Deterministic pseudo-random Q4_K matrix of `rows` x `cols`.
+    fn q4_k_matrix(rows: usize, cols: usize) -> Vec<u8> {
+        let mut state = 0x2545_f491_u32;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        let n_blocks = rows * cols / 256;
+        let mut data = Vec::with_capacity(n_blocks * 144);
+        for _ in 0..n_blocks {
+            let d = 0.001 + (next() % 1000) as f32 / 50_000.0;
+            let dmin = (next() % 1000) as f32 / 100_000.0;
+            data.extend(half::f16::from_f32(d).to_le_bytes());
+            data.extend(half::f16::from_f32(dmin).to_le_bytes());
+            data.extend((0..140).map(|_| next() as u8));
+        }
+        data
+    }
+
+    #[test]
+    fn test_quantized_matmul_matches_dequantized() {
+        let (rows, cols) = (24, 512);
+        let data = q4_k_matrix(rows, cols);
+        let vec_in: Vec<f32> = (0..cols)
+            .map(|i| ((i * 7) % 13) as f32 / 6.0 - 1.0)
+            .collect();
+        assert_eq!(matmul_path(GgmlType::Q4K, cols), MatmulPath::Quantized);
+
+        let mut weight = vec![0.0f32; rows * cols];
+        quant::dequantize_row(&data, &mut weight, rows * cols, GgmlType::Q4K).unwrap();
+        let mut expected = vec![0.0; rows];
+        matmul(&mut expected, &weight, &vec_in, rows, cols);
+
+        let mut direct = vec![0.0; rows];
+        matmul_stored(&mut direct, &data, GgmlType::Q4K, &vec_in, rows, cols).unwrap();
+        for (d, e) in direct.iter().zip(&expected) {
+            assert!((d - e).abs() <= 1e-3 * e.abs().max(1.0), "{d} vs {e}");
+        }
+    }
+
+    #[test]
+    fn test_matmul_path_selection() {
+        assert_eq!(matmul_path(GgmlType::F32, 64), MatmulPath::F32);
+        assert_eq!(matmul_path(GgmlType::F16, 64), MatmulPath::F32);
+        assert_eq!(matmul_path(GgmlType::Q8_0, 64), MatmulPath::Quantized);
+        assert_eq!(matmul_path(GgmlType::Q4K, 256), MatmulPath::Quantized);
+        // Rows that don't split into whole blocks fall back.
+        assert_eq!(matmul_path(GgmlType::Q4K, 96), MatmulPath::Dequantize);
+        assert_eq!(matmul_path(GgmlType::Q6K, 256), MatmulPath::Dequantize);
+        let mut out = vec![0.0; 1];
+        assert!(matmul_quantized(&mut out, &[], GgmlType::Q4K, &[0.0; 256], 1, 256).is_err());
+    }
+
     #[test]
     fn test_silu() {
         let mut v = vec![0.0, 1.0, -1.0];
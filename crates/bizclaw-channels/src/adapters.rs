@@ -55,6 +55,8 @@ impl LineChannel {
                         },
                         timestamp: chrono::Utc::now(),
                         reply_to: event["replyToken"].as_str().map(String::from),
+                        message_id: event["message"]["id"].as_str().map(String::from),
+                        images: Vec::new(),
                     });
                 }
             }
@@ -132,6 +134,8 @@ impl TeamsChannel {
             },
             timestamp: chrono::Utc::now(),
             reply_to: payload["replyToId"].as_str().map(String::from),
+            message_id: payload["id"].as_str().map(String::from),
+            images: Vec::new(),
         })
     }
 }
@@ -333,6 +337,8 @@ impl MessengerChannel {
                                 thread_type: ThreadType::Direct,
                                 timestamp: chrono::Utc::now(),
                                 reply_to: None,
+                                message_id: msg["message"]["mid"].as_str().map(String::from),
+                                images: Vec::new(),
                             });
                         }
                     }
@@ -63,6 +63,8 @@ impl Channel for CliChannel {
                             thread_type: ThreadType::Direct,
                             timestamp: chrono::Utc::now(),
                             reply_to: None,
+                            message_id: None,
+                            images: Vec::new(),
                         };
                     }
                     Ok(None) => break,
@@ -2,14 +2,23 @@
 //!
 //! Connects to Discord Gateway for real-time events (messages, reactions, etc.)
 //! and uses REST API for sending messages.
+//!
+//! The `/ask <prompt>` slash command is registered on READY. Each invocation
+//! is deferred right away (Discord allows 3s for the first response), routed
+//! to the agent like a normal message, and answered by editing the deferred
+//! response.
 
+use crate::reconnect::{Backoff, FailureKind, classify};
+use crate::status::StatusRegistry;
 use async_trait::async_trait;
 use bizclaw_core::error::{BizClawError, Result};
 use bizclaw_core::traits::Channel;
-use bizclaw_core::types::{IncomingMessage, OutgoingMessage, ThreadType};
+use bizclaw_core::types::{ImageContent, IncomingMessage, OutgoingMessage, ThreadType};
 use futures::stream::Stream;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 
 /// Discord channel configuration.
@@ -32,10 +41,19 @@ fn default_intents() -> u64 {
 }
 
 /// Discord Bot channel.
+///
+/// Clones share the deferred slash commands, so the clone that listens and
+/// the one that replies agree on which messages are interactions.
+#[derive(Clone)]
 pub struct DiscordChannel {
     config: DiscordConfig,
     client: reqwest::Client,
+    api_base: String,
     connected: bool,
+    /// Deferred slash commands awaiting their answer, by interaction ID.
+    interactions: Arc<Mutex<HashMap<String, SlashCommand>>>,
+    /// Name the gateway loop reports its health under.
+    status_key: String,
 }
 
 impl DiscordChannel {
@@ -56,14 +74,46 @@ impl DiscordChannel {
         Self {
             config,
             client,
+            api_base: "https://discord.com/api/v10".into(),
             connected: false,
+            interactions: Arc::default(),
+            status_key: "discord".into(),
         }
     }
 
+    /// Use a different REST API base URL (e.g. another API version, or tests).
+    pub fn with_api_base(mut self, api_base: &str) -> Self {
+        self.api_base = api_base.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Report gateway health under `key` instead of `discord`, so several
+    /// bots show up separately.
+    pub fn with_status_key(mut self, key: &str) -> Self {
+        self.status_key = key.to_string();
+        self
+    }
+
     /// Send a message to a channel.
     pub async fn send_message(&self, channel_id: &str, content: &str) -> Result<()> {
-        let url = format!("https://discord.com/api/v10/channels/{channel_id}/messages");
-        let body = serde_json::json!({ "content": content });
+        self.send_reply(channel_id, content, None).await
+    }
+
+    /// Send a message, as a reply to `reply_to` (a message ID) when given.
+    pub async fn send_reply(
+        &self,
+        channel_id: &str,
+        content: &str,
+        reply_to: Option<&str>,
+    ) -> Result<()> {
+        let url = format!("{}/channels/{channel_id}/messages", self.api_base);
+        let mut body = serde_json::json!({ "content": content });
+        if let Some(id) = reply_to {
+            body["message_reference"] = serde_json::json!({
+                "message_id": id,
+                "fail_if_not_exists": false,
+            });
+        }
 
         let response = self
             .client
@@ -72,18 +122,79 @@ impl DiscordChannel {
             .send()
             .await
             .map_err(|e| BizClawError::Channel(format!("Discord send failed: {e}")))?;
+        check_status(response).await
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(BizClawError::Channel(format!("Discord {status}: {text}")));
-        }
+    /// Register (overwrite) the bot's global slash commands.
+    pub async fn register_commands(&self, application_id: &str) -> Result<()> {
+        let url = format!("{}/applications/{application_id}/commands", self.api_base);
+        let response = self
+            .client
+            .put(&url)
+            .json(&slash_commands())
+            .send()
+            .await
+            .map_err(|e| BizClawError::Channel(format!("Discord command registration: {e}")))?;
+        check_status(response).await
+    }
+
+    /// Acknowledge a slash command with a deferred response ("thinking…").
+    /// The answer is delivered later by [`Channel::send`] with the
+    /// interaction ID as `reply_to_message_id`.
+    pub async fn defer_interaction(&self, command: &SlashCommand) -> Result<()> {
+        let url = format!(
+            "{}/interactions/{}/{}/callback",
+            self.api_base, command.interaction_id, command.token
+        );
+        let response = self
+            .client
+            .post(&url)
+            // DEFERRED_CHANNEL_MESSAGE_WITH_SOURCE
+            .json(&serde_json::json!({ "type": 5 }))
+            .send()
+            .await
+            .map_err(|e| BizClawError::Channel(format!("Discord interaction defer: {e}")))?;
+        check_status(response).await?;
+        self.interactions
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .insert(command.interaction_id.clone(), command.clone());
         Ok(())
     }
 
+    /// Replace the deferred response of a slash command with `content`.
+    pub async fn edit_interaction_response(
+        &self,
+        command: &SlashCommand,
+        content: &str,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/webhooks/{}/{}/messages/@original",
+            self.api_base, command.application_id, command.token
+        );
+        // Message content is capped at 2000 characters.
+        let content: String = content.chars().take(2000).collect();
+        let response = self
+            .client
+            .patch(&url)
+            .json(&serde_json::json!({ "content": content }))
+            .send()
+            .await
+            .map_err(|e| BizClawError::Channel(format!("Discord interaction edit: {e}")))?;
+        check_status(response).await
+    }
+
+    /// Take the deferred slash command with this interaction ID, if any.
+    fn take_interaction(&self, interaction_id: &str) -> Option<SlashCommand> {
+        self.interactions
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .remove(interaction_id)
+    }
+
     /// Send typing indicator.
     pub async fn send_typing_indicator(&self, channel_id: &str) -> Result<()> {
-        let url = format!("https://discord.com/api/v10/channels/{channel_id}/typing");
+        let url = format!("{}/channels/{channel_id}/typing", self.api_base);
         let _ = self.client.post(&url).send().await;
         Ok(())
     }
@@ -92,7 +203,7 @@ impl DiscordChannel {
     pub async fn get_me(&self) -> Result<DiscordUser> {
         let response = self
             .client
-            .get("https://discord.com/api/v10/users/@me")
+            .get(format!("{}/users/@me", self.api_base))
             .send()
             .await
             .map_err(|e| BizClawError::Channel(format!("getMe failed: {e}")))?;
@@ -106,10 +217,15 @@ impl DiscordChannel {
     pub async fn get_gateway_url(&self) -> Result<String> {
         let response = self
             .client
-            .get("https://discord.com/api/v10/gateway/bot")
+            .get(format!("{}/gateway/bot", self.api_base))
             .send()
             .await
             .map_err(|e| BizClawError::Channel(format!("Gateway request failed: {e}")))?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(BizClawError::AuthFailed(
+                "Discord rejected the bot token".into(),
+            ));
+        }
 
         let body: serde_json::Value = response
             .json()
@@ -129,7 +245,9 @@ impl DiscordChannel {
 
         tokio::spawn(async move {
             let channel = self;
-            let mut backoff_secs: u64 = 5;
+            let mut backoff = Backoff::default();
+            let status = StatusRegistry::global();
+            let mut commands_registered = false;
 
             // ═══ Reconnect loop ═══
             loop {
@@ -139,11 +257,22 @@ impl DiscordChannel {
                 let gateway_url = match channel.get_gateway_url().await {
                     Ok(url) => url,
                     Err(e) => {
-                        tracing::error!(
-                            "Failed to get gateway URL: {e}, retrying in {backoff_secs}s..."
-                        );
-                        tokio::time::sleep(tokio::time::Duration::from_secs(backoff_secs)).await;
-                        backoff_secs = (backoff_secs * 2).min(60);
+                        if classify(&e) == FailureKind::Fatal {
+                            tracing::error!("Discord Gateway stopped (fatal): {e}");
+                            status.failed(&channel.status_key, &e.to_string());
+                            return;
+                        }
+                        let Some(delay) = backoff.next_delay() else {
+                            tracing::error!(
+                                "Discord Gateway stopped after {} failed attempts: {e}",
+                                backoff.attempts()
+                            );
+                            status.failed(&channel.status_key, &e.to_string());
+                            return;
+                        };
+                        tracing::error!("Failed to get gateway URL: {e}, retrying in {delay:?}...");
+                        status.reconnecting(&channel.status_key, &e.to_string(), backoff.attempts());
+                        tokio::time::sleep(delay).await;
                         continue;
                     }
                 };
@@ -153,17 +282,24 @@ impl DiscordChannel {
                 let (mut ws, _) = match ws_result {
                     Ok(conn) => conn,
                     Err(e) => {
-                        tracing::error!(
-                            "Gateway WebSocket failed: {e}, retrying in {backoff_secs}s..."
-                        );
-                        tokio::time::sleep(tokio::time::Duration::from_secs(backoff_secs)).await;
-                        backoff_secs = (backoff_secs * 2).min(60);
+                        let Some(delay) = backoff.next_delay() else {
+                            tracing::error!(
+                                "Discord Gateway stopped after {} failed attempts: {e}",
+                                backoff.attempts()
+                            );
+                            status.failed(&channel.status_key, &e.to_string());
+                            return;
+                        };
+                        tracing::error!("Gateway WebSocket failed: {e}, retrying in {delay:?}...");
+                        status.reconnecting(&channel.status_key, &e.to_string(), backoff.attempts());
+                        tokio::time::sleep(delay).await;
                         continue;
                     }
                 };
 
                 // Reset backoff on successful connect
-                backoff_secs = 5;
+                backoff.reset();
+                status.connected(&channel.status_key);
                 tracing::info!("Discord Gateway connected");
 
                 use futures::{SinkExt, StreamExt};
@@ -175,6 +311,11 @@ impl DiscordChannel {
 
                 loop {
                     tokio::select! {
+                        _ = tx.closed() => {
+                            tracing::info!("Discord Gateway closing (receiver dropped)");
+                            let _ = ws.close(None).await;
+                            return;
+                        }
                         msg = ws.next() => {
                             match msg {
                                 Some(Ok(WsMsg::Text(text))) => {
@@ -219,6 +360,32 @@ impl DiscordChannel {
                                                     let user = payload["d"]["user"]["username"]
                                                         .as_str().unwrap_or("unknown");
                                                     tracing::info!("Discord Gateway READY as {user}");
+                                                    if !commands_registered
+                                                        && let Some(app_id) =
+                                                            payload["d"]["application"]["id"].as_str()
+                                                    {
+                                                        match channel.register_commands(app_id).await {
+                                                            Ok(()) => commands_registered = true,
+                                                            Err(e) => tracing::warn!(
+                                                                "Discord slash commands not registered: {e}"
+                                                            ),
+                                                        }
+                                                    }
+                                                }
+                                                "INTERACTION_CREATE" => {
+                                                    let Some(command) =
+                                                        SlashCommand::from_interaction(&payload["d"])
+                                                    else {
+                                                        continue;
+                                                    };
+                                                    if let Err(e) = channel.defer_interaction(&command).await {
+                                                        tracing::warn!("Discord /{} not deferred: {e}", command.name);
+                                                        continue;
+                                                    }
+                                                    if tx.send(command.to_incoming()).is_err() {
+                                                        tracing::info!("Discord stream closed (receiver dropped)");
+                                                        return;
+                                                    }
                                                 }
                                                 "MESSAGE_CREATE" => {
                                                     let d = &payload["d"];
@@ -244,6 +411,9 @@ impl DiscordChannel {
                                                         timestamp: chrono::Utc::now(),
                                                         reply_to: d["referenced_message"]["id"]
                                                             .as_str().map(String::from),
+                                                        message_id: d["id"].as_str()
+                                                            .map(String::from),
+                                                        images: image_attachments(d),
                                                     };
 
                                                     if tx.send(msg).is_err() {
@@ -265,7 +435,13 @@ impl DiscordChannel {
                                         _ => {}
                                     }
                                 }
-                                Some(Ok(WsMsg::Close(_))) => {
+                                Some(Ok(WsMsg::Close(frame))) => {
+                                    // 4004 = authentication failed — reconnecting cannot help
+                                    if frame.as_ref().is_some_and(|f| u16::from(f.code) == 4004) {
+                                        tracing::error!("Discord Gateway closed: authentication failed, not reconnecting");
+                                        status.failed(&channel.status_key, "authentication failed");
+                                        return;
+                                    }
                                     tracing::warn!("Discord Gateway closed by server");
                                     break; // → reconnect
                                 }
@@ -292,9 +468,17 @@ impl DiscordChannel {
                 }
 
                 // Disconnected — reconnect after backoff
-                tracing::info!("Discord Gateway disconnected, reconnecting in {backoff_secs}s...");
-                tokio::time::sleep(tokio::time::Duration::from_secs(backoff_secs)).await;
-                backoff_secs = (backoff_secs * 2).min(60);
+                let Some(delay) = backoff.next_delay() else {
+                    tracing::error!(
+                        "Discord Gateway stopped after {} failed attempts",
+                        backoff.attempts()
+                    );
+                    status.failed(&channel.status_key, "gateway connection lost");
+                    return;
+                };
+                tracing::info!("Discord Gateway disconnected, reconnecting in {delay:?}...");
+                status.reconnecting(&channel.status_key, "gateway connection lost", backoff.attempts());
+                tokio::time::sleep(delay).await;
             } // end reconnect loop
         });
 
@@ -340,8 +524,25 @@ impl Channel for DiscordChannel {
     }
 
     async fn send(&self, message: OutgoingMessage) -> Result<()> {
-        self.send_message(&message.thread_id, &message.content)
-            .await
+        if let Some(command) = message
+            .reply_to_message_id
+            .as_deref()
+            .and_then(|id| self.take_interaction(id))
+        {
+            return self
+                .edit_interaction_response(&command, &message.content)
+                .await;
+        }
+        match message.reply_to_message_id.as_deref() {
+            Some(id) => {
+                self.send_reply(&message.thread_id, &message.content, Some(id))
+                    .await
+            }
+            None => {
+                self.send_message(&message.thread_id, &message.content_with_quote())
+                    .await
+            }
+        }
     }
 
     async fn send_typing(&self, thread_id: &str) -> Result<()> {
@@ -371,3 +572,258 @@ pub struct DiscordMessage {
     pub content: String,
     pub guild_id: Option<String>,
 }
+
+/// A `/ask` slash command from an `INTERACTION_CREATE` event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlashCommand {
+    pub interaction_id: String,
+    /// Interaction token — authorizes the callback and response edits.
+    pub token: String,
+    pub application_id: String,
+    pub channel_id: String,
+    pub user_id: String,
+    pub username: Option<String>,
+    pub in_guild: bool,
+    pub name: String,
+    pub prompt: String,
+}
+
+impl SlashCommand {
+    /// Parse an interaction payload (the event's `d`). Only application
+    /// commands named `ask` with a non-empty `prompt` are accepted.
+    pub fn from_interaction(d: &serde_json::Value) -> Option<Self> {
+        // 2 = APPLICATION_COMMAND
+        if d["type"].as_u64() != Some(2) {
+            return None;
+        }
+        let name = d["data"]["name"].as_str()?;
+        if name != "ask" {
+            return None;
+        }
+        let prompt = d["data"]["options"]
+            .as_array()?
+            .iter()
+            .find(|o| o["name"] == "prompt")?["value"]
+            .as_str()?
+            .trim();
+        if prompt.is_empty() {
+            return None;
+        }
+        // Guild invocations carry `member.user`, DMs carry `user`.
+        let in_guild = d["guild_id"].is_string();
+        let user = if in_guild { &d["member"]["user"] } else { &d["user"] };
+        Some(Self {
+            interaction_id: d["id"].as_str()?.into(),
+            token: d["token"].as_str()?.into(),
+            application_id: d["application_id"].as_str()?.into(),
+            channel_id: d["channel_id"].as_str()?.into(),
+            user_id: user["id"].as_str()?.into(),
+            username: user["username"].as_str().map(String::from),
+            in_guild,
+            name: name.into(),
+            prompt: prompt.into(),
+        })
+    }
+
+    /// The command as a message for the agent. `message_id` is the
+    /// interaction ID, which routes the reply back to the interaction.
+    pub fn to_incoming(&self) -> IncomingMessage {
+        IncomingMessage {
+            channel: "discord".into(),
+            thread_id: self.channel_id.clone(),
+            sender_id: self.user_id.clone(),
+            sender_name: self.username.clone(),
+            content: self.prompt.clone(),
+            thread_type: if self.in_guild {
+                ThreadType::Group
+            } else {
+                ThreadType::Direct
+            },
+            timestamp: chrono::Utc::now(),
+            reply_to: None,
+            message_id: Some(self.interaction_id.clone()),
+            images: Vec::new(),
+        }
+    }
+}
+
+/// Global slash commands registered on startup.
+fn slash_commands() -> serde_json::Value {
+    serde_json::json!([{
+        "name": "ask",
+        "description": "Ask the assistant a question",
+        // 3 = STRING option
+        "options": [{
+            "type": 3,
+            "name": "prompt",
+            "description": "What do you want to ask?",
+            "required": true,
+        }],
+    }])
+}
+
+/// Image attachments of a `MESSAGE_CREATE` payload. Discord serves them
+/// from its CDN, so providers fetch them by URL.
+fn image_attachments(message: &serde_json::Value) -> Vec<ImageContent> {
+    message["attachments"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|a| {
+            let media_type = a["content_type"].as_str()?;
+            let url = a["url"].as_str()?;
+            media_type
+                .starts_with("image/")
+                .then(|| ImageContent::from_url(media_type, url))
+        })
+        .collect()
+}
+
+async fn check_status(response: reqwest::Response) -> Result<()> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(BizClawError::Channel(format!("Discord {status}: {text}")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{capture_one_request, capture_requests};
+
+    fn channel(base: &str) -> DiscordChannel {
+        DiscordChannel::new(DiscordConfig {
+            bot_token: "bot-token".into(),
+            enabled: true,
+            intents: default_intents(),
+        })
+        .with_api_base(base)
+    }
+
+    fn outgoing(reply_to_message_id: Option<&str>) -> OutgoingMessage {
+        OutgoingMessage {
+            thread_id: "998877".into(),
+            content: "Yes, in stock.".into(),
+            thread_type: ThreadType::Group,
+            reply_to: None,
+            reply_to_message_id: reply_to_message_id.map(String::from),
+            quote: Some("Do you have size M?".into()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_includes_message_reference() {
+        let (base, request) = capture_one_request(r#"{"id": "1"}"#).await;
+        channel(&base).send(outgoing(Some("112233"))).await.unwrap();
+
+        let (head, body) = request.await.unwrap();
+        assert!(head.starts_with("POST /channels/998877/messages "), "{head}");
+        assert!(head.contains("Bot bot-token"));
+        assert_eq!(body["message_reference"]["message_id"], "112233");
+        assert_eq!(body["content"], "Yes, in stock.");
+    }
+
+    #[tokio::test]
+    async fn test_send_without_reference_quotes_text() {
+        let (base, request) = capture_one_request(r#"{"id": "1"}"#).await;
+        channel(&base).send(outgoing(None)).await.unwrap();
+
+        let (_, body) = request.await.unwrap();
+        assert!(body.get("message_reference").is_none());
+        assert_eq!(body["content"], "> Do you have size M?\n\nYes, in stock.");
+    }
+
+    fn interaction(guild: bool, prompt: &str) -> serde_json::Value {
+        let mut d = serde_json::json!({
+            "id": "5001",
+            "type": 2,
+            "token": "itoken",
+            "application_id": "7007",
+            "channel_id": "998877",
+            "data": {
+                "name": "ask",
+                "options": [{ "type": 3, "name": "prompt", "value": prompt }],
+            },
+        });
+        if guild {
+            d["guild_id"] = "42".into();
+            d["member"] = serde_json::json!({ "user": { "id": "1234", "username": "lan" } });
+        } else {
+            d["user"] = serde_json::json!({ "id": "1234", "username": "lan" });
+        }
+        d
+    }
+
+    #[test]
+    fn test_parse_slash_command() {
+        let cmd = SlashCommand::from_interaction(&interaction(true, " Giá áo size M? ")).unwrap();
+        assert_eq!(cmd.interaction_id, "5001");
+        assert_eq!(cmd.application_id, "7007");
+        assert_eq!(cmd.user_id, "1234");
+        assert_eq!(cmd.prompt, "Giá áo size M?");
+        assert!(cmd.in_guild);
+
+        let msg = cmd.to_incoming();
+        assert_eq!(msg.thread_id, "998877");
+        assert_eq!(msg.sender_name.as_deref(), Some("lan"));
+        assert_eq!(msg.message_id.as_deref(), Some("5001"));
+        assert_eq!(msg.thread_type, ThreadType::Group);
+
+        // DMs carry the user at the top level.
+        let dm = SlashCommand::from_interaction(&interaction(false, "hi")).unwrap();
+        assert_eq!(dm.user_id, "1234");
+        assert_eq!(dm.to_incoming().thread_type, ThreadType::Direct);
+
+        // Empty prompts, other commands and non-command interactions are ignored.
+        assert!(SlashCommand::from_interaction(&interaction(true, "  ")).is_none());
+        let mut other = interaction(true, "hi");
+        other["data"]["name"] = "help".into();
+        assert!(SlashCommand::from_interaction(&other).is_none());
+        let mut button = interaction(true, "hi");
+        button["type"] = 3.into();
+        assert!(SlashCommand::from_interaction(&button).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_slash_command_is_deferred_then_edited() {
+        let (base, requests) = capture_requests(vec![(204, ""), (200, r#"{"id": "9"}"#)]).await;
+        let listener = channel(&base);
+        // The replying clone shares the deferred interactions.
+        let replier = listener.clone();
+
+        let cmd = SlashCommand::from_interaction(&interaction(true, "hi")).unwrap();
+        listener.defer_interaction(&cmd).await.unwrap();
+        let mut reply = outgoing(cmd.to_incoming().message_id.as_deref());
+        reply.content = "Hello Lan!".into();
+        replier.send(reply).await.unwrap();
+
+        let requests = requests.await.unwrap();
+        let (head, body) = &requests[0];
+        assert!(head.starts_with("POST /interactions/5001/itoken/callback "), "{head}");
+        assert_eq!(body["type"], 5);
+        let (head, body) = &requests[1];
+        assert!(head.starts_with("PATCH /webhooks/7007/itoken/messages/@original "), "{head}");
+        assert_eq!(body["content"], "Hello Lan!");
+
+        // Answered once: the interaction is no longer pending.
+        assert!(replier.take_interaction("5001").is_none());
+    }
+
+    #[test]
+    fn test_image_attachments() {
+        let message = serde_json::json!({
+            "attachments": [
+                {"url": "https://cdn.discordapp.com/a.png", "content_type": "image/png"},
+                {"url": "https://cdn.discordapp.com/b.pdf", "content_type": "application/pdf"},
+                {"url": "https://cdn.discordapp.com/c.bin"}
+            ]
+        });
+        assert_eq!(
+            image_attachments(&message),
+            vec![ImageContent::from_url("image/png", "https://cdn.discordapp.com/a.png")]
+        );
+        assert!(image_attachments(&serde_json::json!({})).is_empty());
+    }
+}
@@ -11,6 +11,7 @@ use bizclaw_core::types::{IncomingMessage, OutgoingMessage, ThreadType};
 use futures::stream::Stream;
 use serde::{Deserialize, Serialize};
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 
@@ -113,9 +114,13 @@ async fn connect_imap_tls(
 }
 
 /// Email channel — async IMAP reading + SMTP sending.
+///
+/// Clones share the connection state and the last seen message, so the
+/// clone that polls and the one that replies stay in step.
+#[derive(Clone)]
 pub struct EmailChannel {
     config: EmailConfig,
-    connected: bool,
+    connected: Arc<AtomicBool>,
     last_seen_uid: Arc<Mutex<u32>>,
 }
 
@@ -123,7 +128,7 @@ impl EmailChannel {
     pub fn new(config: EmailConfig) -> Self {
         Self {
             config,
-            connected: false,
+            connected: Arc::default(),
             last_seen_uid: Arc::new(Mutex::new(0)),
         }
     }
@@ -200,18 +205,17 @@ impl EmailChannel {
     /// Start IMAP polling loop — returns a stream of IncomingMessages.
     pub fn start_polling(self) -> EmailPollingStream {
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        let config = self.config.clone();
-        let last_seen = self.last_seen_uid.clone();
 
         tokio::spawn(async move {
-            let ch = EmailChannel {
-                config,
-                connected: true,
-                last_seen_uid: last_seen,
-            };
+            let ch = self;
+            let status = crate::status::StatusRegistry::global();
+            let mut failures = 0u32;
             loop {
                 match ch.fetch_unread().await {
                     Ok(emails) => {
+                        failures = 0;
+                        ch.connected.store(true, Ordering::Relaxed);
+                        status.connected("email");
                         for em in emails {
                             let incoming = IncomingMessage {
                                 channel: "email".into(),
@@ -222,13 +226,20 @@ impl EmailChannel {
                                 thread_type: ThreadType::Direct,
                                 timestamp: chrono::Utc::now(),
                                 reply_to: em.message_id,
+                                message_id: None,
+                                images: Vec::new(),
                             };
                             if tx.send(incoming).is_err() {
                                 return;
                             }
                         }
                     }
-                    Err(e) => tracing::error!("IMAP poll: {e}"),
+                    Err(e) => {
+                        tracing::error!("IMAP poll: {e}");
+                        failures += 1;
+                        ch.connected.store(false, Ordering::Relaxed);
+                        status.reconnecting("email", &e.to_string(), failures);
+                    }
                 }
                 tokio::time::sleep(std::time::Duration::from_secs(ch.config.poll_interval_secs))
                     .await;
@@ -266,18 +277,18 @@ impl Channel for EmailChannel {
             .map_err(|e| BizClawError::AuthFailed(format!("IMAP auth: {}", e.0)))?;
         session.logout().await.ok();
 
-        self.connected = true;
+        self.connected.store(true, Ordering::Relaxed);
         tracing::info!("📧 Email connected: {}", self.config.email);
         Ok(())
     }
 
     async fn disconnect(&mut self) -> Result<()> {
-        self.connected = false;
+        self.connected.store(false, Ordering::Relaxed);
         Ok(())
     }
 
     fn is_connected(&self) -> bool {
-        self.connected
+        self.connected.load(Ordering::Relaxed)
     }
 
     async fn send(&self, message: OutgoingMessage) -> Result<()> {
@@ -6,11 +6,18 @@
 pub mod cli;
 pub mod discord;
 pub mod email;
+pub mod reconnect;
+pub mod shutdown;
+pub mod status;
 pub mod telegram;
 pub mod webhook;
 pub mod whatsapp;
 pub mod zalo;
 pub mod slack;
 pub mod tts;
+pub mod typing;
 pub mod xiaozhi;
 pub mod adapters;
+
+#[cfg(test)]
+mod test_support;
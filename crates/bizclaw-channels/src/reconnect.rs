@@ -0,0 +1,184 @@
+//! Reconnect policy shared by long-running channel loops.
+//!
+//! Delays grow exponentially with random jitter so that many bots restarting
+//! together do not hammer the upstream API in lockstep. Fatal errors (a
+//! revoked or invalid token) stop the loop immediately instead of retrying
+//! forever; transient errors retry until `max_attempts` consecutive failures.
+
+use bizclaw_core::error::BizClawError;
+use rand::Rng;
+use std::time::Duration;
+
+/// How a channel failure should be handled by a reconnect loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// Network blip, 5xx, rate limit — retry after a backoff delay.
+    Transient,
+    /// Bad credentials or configuration — retrying cannot succeed.
+    Fatal,
+}
+
+/// Classify a channel error as fatal or transient.
+pub fn classify(err: &BizClawError) -> FailureKind {
+    match err {
+        BizClawError::AuthFailed(_)
+        | BizClawError::ApiKeyMissing(_)
+        | BizClawError::Config(_)
+        | BizClawError::ConfigNotFound(_)
+        | BizClawError::PermissionDenied(_) => FailureKind::Fatal,
+        BizClawError::Channel(msg) | BizClawError::Http(msg) => {
+            let lower = msg.to_lowercase();
+            let fatal = [
+                "401",
+                "unauthorized",
+                "invalid token",
+                "authentication failed",
+            ]
+            .iter()
+            .any(|needle| lower.contains(needle));
+            if fatal {
+                FailureKind::Fatal
+            } else {
+                FailureKind::Transient
+            }
+        }
+        _ => FailureKind::Transient,
+    }
+}
+
+/// Exponential backoff with jitter and a cap on consecutive attempts.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    base: Duration,
+    max: Duration,
+    /// Give up after this many consecutive failures (0 = never).
+    max_attempts: u32,
+    attempt: u32,
+}
+
+impl Default for Backoff {
+    /// 5s doubling up to 5 minutes, giving up after 20 consecutive failures.
+    fn default() -> Self {
+        Self::new(Duration::from_secs(5), Duration::from_secs(300), 20)
+    }
+}
+
+impl Backoff {
+    pub fn new(base: Duration, max: Duration, max_attempts: u32) -> Self {
+        Self {
+            base,
+            max: max.max(base),
+            max_attempts,
+            attempt: 0,
+        }
+    }
+
+    /// Consecutive failures recorded since the last [`reset`](Self::reset).
+    pub fn attempts(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Call after a successful connect so the next failure starts from `base`.
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+
+    /// Record a failure and return how long to wait, or `None` once the
+    /// attempt cap is reached.
+    pub fn next_delay(&mut self) -> Option<Duration> {
+        let factor = rand::thread_rng().r#gen::<f64>();
+        self.next_delay_with(factor)
+    }
+
+    /// Same as [`next_delay`](Self::next_delay) with an explicit jitter
+    /// factor in `[0, 1)`.
+    pub fn next_delay_with(&mut self, jitter: f64) -> Option<Duration> {
+        if self.max_attempts > 0 && self.attempt >= self.max_attempts {
+            return None;
+        }
+        let delay = jittered_delay(self.base, self.max, self.attempt, jitter);
+        self.attempt += 1;
+        Some(delay)
+    }
+}
+
+/// Capped exponential delay for `attempt` (0-based), with "equal jitter":
+/// half of the delay is fixed and the other half scales with `jitter`.
+pub fn jittered_delay(base: Duration, max: Duration, attempt: u32, jitter: f64) -> Duration {
+    let exp = base
+        .checked_mul(1u32.checked_shl(attempt.min(31)).unwrap_or(u32::MAX))
+        .unwrap_or(max)
+        .min(max);
+    let half = exp / 2;
+    half + half.mul_f64(jitter.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_sequence_with_jitter() {
+        let mut b = Backoff::new(Duration::from_secs(5), Duration::from_secs(60), 0);
+        // No jitter → lower bound (half the exponential delay)
+        let low: Vec<u64> = (0..6)
+            .map(|_| b.next_delay_with(0.0).unwrap().as_millis() as u64)
+            .collect();
+        assert_eq!(low, vec![2500, 5000, 10_000, 20_000, 30_000, 30_000]);
+
+        // Full jitter → upper bound, capped at max
+        b.reset();
+        let high: Vec<u64> = (0..6)
+            .map(|_| b.next_delay_with(1.0).unwrap().as_secs())
+            .collect();
+        assert_eq!(high, vec![5, 10, 20, 40, 60, 60]);
+
+        // Random jitter always stays within the bounds
+        b.reset();
+        for attempt in 0..10 {
+            let d = b.next_delay().unwrap();
+            let cap = jittered_delay(b.base, b.max, attempt, 1.0);
+            assert!(d >= cap / 2 && d <= cap, "attempt {attempt}: {d:?}");
+        }
+    }
+
+    #[test]
+    fn test_backoff_gives_up_after_max_attempts() {
+        let mut b = Backoff::new(Duration::from_secs(1), Duration::from_secs(10), 3);
+        assert!(b.next_delay().is_some());
+        assert!(b.next_delay().is_some());
+        assert!(b.next_delay().is_some());
+        assert_eq!(b.next_delay(), None);
+        assert_eq!(b.attempts(), 3);
+        b.reset();
+        assert!(b.next_delay().is_some());
+    }
+
+    #[test]
+    fn test_fatal_vs_transient() {
+        assert_eq!(
+            classify(&BizClawError::AuthFailed("bad token".into())),
+            FailureKind::Fatal
+        );
+        assert_eq!(
+            classify(&BizClawError::Channel(
+                "Telegram API error: Unauthorized".into()
+            )),
+            FailureKind::Fatal
+        );
+        assert_eq!(
+            classify(&BizClawError::Channel(
+                "Telegram getUpdates failed: connection reset".into()
+            )),
+            FailureKind::Transient
+        );
+        assert_eq!(
+            classify(&BizClawError::Timeout("poll".into())),
+            FailureKind::Transient
+        );
+        assert_eq!(
+            classify(&BizClawError::RateLimited("429".into())),
+            FailureKind::Transient
+        );
+    }
+}
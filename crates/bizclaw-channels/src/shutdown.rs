@@ -0,0 +1,183 @@
+//! Graceful shutdown for channel listener loops.
+//!
+//! `serve` hands every channel loop a clone of one [`Shutdown`]. When it is
+//! triggered (Ctrl-C), each loop stops taking new messages from
+//! [`next_message`], lets the reply it is working on finish, then calls
+//! [`disconnect`]. Dropping the message stream also stops the channel's
+//! background polling task.
+
+use bizclaw_core::traits::Channel;
+use bizclaw_core::types::IncomingMessage;
+use futures::{Stream, StreamExt};
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Cloneable shutdown signal shared by the server and all channel loops.
+#[derive(Clone)]
+pub struct Shutdown {
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self {
+            tx: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Signal every listener to stop. Idempotent.
+    pub fn trigger(&self) {
+        self.tx.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    /// Resolve once [`trigger`](Self::trigger) has been called.
+    pub async fn wait(&self) {
+        let mut rx = self.tx.subscribe();
+        // The sender lives in `self`, so this only returns once triggered.
+        let _ = rx.wait_for(|stopped| *stopped).await;
+    }
+}
+
+/// Next message from `stream`, or `None` once the stream ends or
+/// `shutdown` fires. Call it between messages only — the reply being handled
+/// is never interrupted.
+pub async fn next_message<S>(stream: &mut S, shutdown: &Shutdown) -> Option<IncomingMessage>
+where
+    S: Stream<Item = IncomingMessage> + Unpin,
+{
+    tokio::select! {
+        biased;
+        _ = shutdown.wait() => None,
+        next = stream.next() => next,
+    }
+}
+
+/// Drop the message stream (stopping the channel's poller) and disconnect.
+pub async fn disconnect<S>(channel: &mut dyn Channel, stream: S) {
+    drop(stream);
+    if let Err(e) = channel.disconnect().await {
+        tracing::warn!("📡 Channel '{}' disconnect failed: {e}", channel.name());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use bizclaw_core::error::Result;
+    use bizclaw_core::types::{OutgoingMessage, ThreadType};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    struct MockChannel {
+        disconnected: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl Channel for MockChannel {
+        fn name(&self) -> &str {
+            "mock"
+        }
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+        async fn disconnect(&mut self) -> Result<()> {
+            self.disconnected.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+        fn is_connected(&self) -> bool {
+            !self.disconnected.load(Ordering::SeqCst)
+        }
+        async fn listen(
+            &self,
+        ) -> Result<Box<dyn tokio_stream::Stream<Item = IncomingMessage> + Send + Unpin>> {
+            Ok(Box::new(tokio_stream::empty()))
+        }
+        async fn send(&self, _message: OutgoingMessage) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn incoming(content: &str) -> IncomingMessage {
+        IncomingMessage {
+            channel: "mock".into(),
+            thread_id: "1".into(),
+            sender_id: "1".into(),
+            sender_name: None,
+            content: content.into(),
+            thread_type: ThreadType::Direct,
+            timestamp: chrono::Utc::now(),
+            reply_to: None,
+            message_id: None,
+            images: Vec::new(),
+        }
+    }
+
+    /// The loop shape `serve` uses for every channel.
+    async fn channel_loop<S>(
+        mut channel: MockChannel,
+        mut stream: S,
+        shutdown: Shutdown,
+    ) -> Vec<String>
+    where
+        S: Stream<Item = IncomingMessage> + Unpin,
+    {
+        let mut handled = Vec::new();
+        while let Some(msg) = next_message(&mut stream, &shutdown).await {
+            // Shutdown arrives while this reply is in flight.
+            if msg.content == "first" {
+                shutdown.trigger();
+                tokio::task::yield_now().await;
+            }
+            handled.push(msg.content);
+        }
+        disconnect(&mut channel, stream).await;
+        handled
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_disconnects_and_exits_loop() {
+        let disconnected = Arc::new(AtomicBool::new(false));
+        let channel = MockChannel {
+            disconnected: disconnected.clone(),
+        };
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let stream = tokio_stream::wrappers::UnboundedReceiverStream::new(rx);
+        let shutdown = Shutdown::new();
+        let handled = tokio::spawn(channel_loop(channel, stream, shutdown.clone()));
+
+        tx.send(incoming("first")).unwrap();
+        tx.send(incoming("second")).unwrap();
+        let handled = tokio::time::timeout(std::time::Duration::from_secs(5), handled)
+            .await
+            .expect("loop exits after shutdown")
+            .unwrap();
+        // The in-flight message finished; the queued one was not started.
+        assert_eq!(handled, vec!["first"]);
+        assert!(disconnected.load(Ordering::SeqCst));
+        assert!(shutdown.is_triggered());
+        // The stream was dropped, so pollers see a closed channel.
+        assert!(tx.is_closed());
+    }
+
+    #[tokio::test]
+    async fn test_stream_end_also_exits() {
+        let disconnected = Arc::new(AtomicBool::new(false));
+        let channel = MockChannel {
+            disconnected: disconnected.clone(),
+        };
+        let stream = tokio_stream::iter(vec![incoming("a"), incoming("b")]);
+        let handled = channel_loop(channel, stream, Shutdown::new()).await;
+        assert_eq!(handled, vec!["a", "b"]);
+        assert!(disconnected.load(Ordering::SeqCst));
+    }
+}
@@ -110,6 +110,8 @@ impl SlackChannel {
             },
             timestamp: chrono::Utc::now(),
             reply_to: event["thread_ts"].as_str().map(String::from),
+            message_id: event["ts"].as_str().map(String::from),
+            images: Vec::new(),
         })
     }
 }
@@ -0,0 +1,181 @@
+//! Live channel health, as last reported by each running channel loop.
+//!
+//! A channel can be configured and enabled yet not working: a revoked token,
+//! a flapping connection, a listener that gave up. The polling and gateway
+//! loops report their state here, and the gateway serves it from
+//! `GET /api/v1/channels/status`.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+
+/// Connection state of a channel loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelState {
+    /// Connected and receiving messages.
+    Connected,
+    /// Lost its connection and waiting to retry.
+    Reconnecting,
+    /// Stopped; not receiving messages.
+    Disconnected,
+    /// Stopped on an error that retrying cannot fix.
+    Error,
+}
+
+/// Last reported health of one channel.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChannelStatus {
+    pub state: ChannelState,
+    /// Error behind a `Reconnecting` or `Error` state.
+    pub error: Option<String>,
+    /// Consecutive failed attempts while reconnecting.
+    pub attempts: u32,
+    /// When the channel last received a message.
+    pub last_event_at: Option<DateTime<Utc>>,
+    /// When the state last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ChannelStatus {
+    fn new() -> Self {
+        Self {
+            state: ChannelState::Disconnected,
+            error: None,
+            attempts: 0,
+            last_event_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Channel statuses keyed by channel name.
+#[derive(Default)]
+pub struct StatusRegistry {
+    channels: Mutex<HashMap<String, ChannelStatus>>,
+}
+
+static REGISTRY: OnceLock<StatusRegistry> = OnceLock::new();
+
+impl StatusRegistry {
+    /// The process-wide registry shared by channel loops and the gateway.
+    pub fn global() -> &'static StatusRegistry {
+        REGISTRY.get_or_init(StatusRegistry::default)
+    }
+
+    /// The channel is connected; clears any earlier error.
+    pub fn connected(&self, channel: &str) {
+        self.set(channel, ChannelState::Connected, None, 0);
+    }
+
+    /// The channel failed and will retry; `attempt` counts consecutive failures.
+    pub fn reconnecting(&self, channel: &str, error: &str, attempt: u32) {
+        self.set(channel, ChannelState::Reconnecting, Some(error), attempt);
+    }
+
+    /// The channel stopped on `error` and will not retry.
+    pub fn failed(&self, channel: &str, error: &str) {
+        self.set(channel, ChannelState::Error, Some(error), 0);
+    }
+
+    /// The channel stopped cleanly. An earlier error is kept, so a loop that
+    /// gave up still shows why.
+    pub fn disconnected(&self, channel: &str) {
+        self.update(channel, |status| {
+            if status.state != ChannelState::Error {
+                status.state = ChannelState::Disconnected;
+                status.updated_at = Utc::now();
+            }
+        });
+    }
+
+    /// The channel received a message.
+    pub fn event(&self, channel: &str) {
+        self.update(channel, |status| status.last_event_at = Some(Utc::now()));
+    }
+
+    /// Status of `channel`, if its loop has reported anything.
+    pub fn get(&self, channel: &str) -> Option<ChannelStatus> {
+        self.lock().get(channel).cloned()
+    }
+
+    /// All reported statuses, by channel name.
+    pub fn snapshot(&self) -> BTreeMap<String, ChannelStatus> {
+        self.lock()
+            .iter()
+            .map(|(name, status)| (name.clone(), status.clone()))
+            .collect()
+    }
+
+    fn set(&self, channel: &str, state: ChannelState, error: Option<&str>, attempts: u32) {
+        self.update(channel, |status| {
+            if status.state != state {
+                status.state = state;
+                status.updated_at = Utc::now();
+            }
+            status.error = error.map(str::to_string);
+            status.attempts = attempts;
+        });
+    }
+
+    fn update(&self, channel: &str, f: impl FnOnce(&mut ChannelStatus)) {
+        let mut channels = self.lock();
+        f(channels
+            .entry(channel.to_string())
+            .or_insert_with(ChannelStatus::new));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, ChannelStatus>> {
+        self.channels.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnecting_channel_reports_error() {
+        let registry = StatusRegistry::default();
+        assert!(registry.get("telegram").is_none());
+
+        registry.connected("telegram");
+        registry.event("telegram");
+        registry.reconnecting("telegram", "getUpdates failed: timed out", 2);
+
+        let status = registry.get("telegram").unwrap();
+        assert_eq!(status.state, ChannelState::Reconnecting);
+        assert_eq!(
+            status.error.as_deref(),
+            Some("getUpdates failed: timed out")
+        );
+        assert_eq!(status.attempts, 2);
+        assert!(status.last_event_at.is_some());
+
+        registry.connected("telegram");
+        let status = registry.get("telegram").unwrap();
+        assert_eq!(status.state, ChannelState::Connected);
+        assert!(status.error.is_none());
+        assert_eq!(status.attempts, 0);
+    }
+
+    #[test]
+    fn test_failed_channel_keeps_error_after_stop() {
+        let registry = StatusRegistry::default();
+        registry.failed("discord", "authentication failed");
+        registry.disconnected("discord");
+        registry.disconnected("email");
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot["discord"].state, ChannelState::Error);
+        assert_eq!(
+            snapshot["discord"].error.as_deref(),
+            Some("authentication failed")
+        );
+        assert_eq!(snapshot["email"].state, ChannelState::Disconnected);
+
+        let json = serde_json::to_value(&snapshot["discord"]).unwrap();
+        assert_eq!(json["state"], "error");
+    }
+}
@@ -1,12 +1,18 @@
 //! Telegram Bot channel — long polling + message sending via Bot API.
 
+use crate::reconnect::{Backoff, FailureKind, classify};
+use crate::status::StatusRegistry;
 use async_trait::async_trait;
+use bizclaw_core::config::TelegramParseMode;
 use bizclaw_core::error::{BizClawError, Result};
 use bizclaw_core::traits::Channel;
-use bizclaw_core::types::{IncomingMessage, OutgoingMessage, ThreadType};
+use bizclaw_core::types::{ImageContent, IncomingMessage, OutgoingMessage, ThreadType};
 use futures::stream::Stream;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::task::{Context, Poll};
 
 /// Telegram channel configuration.
@@ -17,6 +23,42 @@ pub struct TelegramConfig {
     pub enabled: bool,
     #[serde(default = "default_poll_interval")]
     pub poll_interval: u64,
+    /// Formatting for outgoing messages.
+    #[serde(default)]
+    pub parse_mode: TelegramParseMode,
+    /// Per-chat formatting overrides, keyed by chat id.
+    #[serde(default)]
+    pub chat_parse_modes: HashMap<String, TelegramParseMode>,
+}
+
+impl TelegramConfig {
+    /// Config with the bot token and defaults for everything else.
+    pub fn new(bot_token: impl Into<String>) -> Self {
+        Self {
+            bot_token: bot_token.into(),
+            enabled: true,
+            poll_interval: default_poll_interval(),
+            parse_mode: TelegramParseMode::default(),
+            chat_parse_modes: HashMap::new(),
+        }
+    }
+
+    /// Config from the `[channel.telegram]` section.
+    pub fn from_channel_config(cfg: &bizclaw_core::config::TelegramChannelConfig) -> Self {
+        Self {
+            parse_mode: cfg.parse_mode,
+            chat_parse_modes: cfg.chat_parse_modes.clone(),
+            ..Self::new(cfg.bot_token.clone())
+        }
+    }
+
+    /// Formatting used for messages to `chat_id`.
+    pub fn parse_mode_for(&self, chat_id: i64) -> TelegramParseMode {
+        self.chat_parse_modes
+            .get(&chat_id.to_string())
+            .copied()
+            .unwrap_or(self.parse_mode)
+    }
 }
 
 fn default_true() -> bool {
@@ -26,12 +68,22 @@ fn default_poll_interval() -> u64 {
     1
 }
 
+/// Reaction used to acknowledge a message the agent has started on.
+pub const ACK_REACTION: &str = "👀";
+
 /// Telegram Bot channel with polling loop.
+///
+/// Clones share the connection state, so the clone that polls and the one
+/// that replies agree on whether the bot is connected.
+#[derive(Clone)]
 pub struct TelegramChannel {
     config: TelegramConfig,
     client: reqwest::Client,
+    api_base: String,
     last_update_id: i64,
-    connected: bool,
+    connected: Arc<AtomicBool>,
+    /// Name the polling loop reports its health under.
+    status_key: String,
 }
 
 impl TelegramChannel {
@@ -39,16 +91,28 @@ impl TelegramChannel {
         Self {
             config,
             client: reqwest::Client::new(),
+            api_base: "https://api.telegram.org".into(),
             last_update_id: 0,
-            connected: false,
+            connected: Arc::default(),
+            status_key: "telegram".into(),
         }
     }
 
+    /// Use a different Bot API server (self-hosted Bot API, or tests).
+    pub fn with_api_base(mut self, api_base: &str) -> Self {
+        self.api_base = api_base.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Report polling health under `key` instead of `telegram`, so several
+    /// bots show up separately.
+    pub fn with_status_key(mut self, key: &str) -> Self {
+        self.status_key = key.to_string();
+        self
+    }
+
     fn api_url(&self, method: &str) -> String {
-        format!(
-            "https://api.telegram.org/bot{}/{}",
-            self.config.bot_token, method
-        )
+        format!("{}/bot{}/{}", self.api_base, self.config.bot_token, method)
     }
 
     /// Get updates using long polling.
@@ -71,9 +135,15 @@ impl TelegramChannel {
             .map_err(|e| BizClawError::Channel(format!("Invalid Telegram response: {e}")))?;
 
         if !body.ok {
+            let description = body.description.unwrap_or_default();
+            // 401/404 from getUpdates mean the bot token is invalid or revoked
+            if matches!(body.error_code, Some(401) | Some(404)) {
+                return Err(BizClawError::AuthFailed(format!(
+                    "Telegram bot token rejected: {description}"
+                )));
+            }
             return Err(BizClawError::Channel(format!(
-                "Telegram API error: {}",
-                body.description.unwrap_or_default()
+                "Telegram API error: {description}"
             )));
         }
 
@@ -84,13 +154,113 @@ impl TelegramChannel {
         Ok(updates)
     }
 
+    /// Download a photo by `file_id` (resolved with `getFile`). Telegram
+    /// re-encodes photos as JPEG.
+    pub async fn download_photo(&self, file_id: &str) -> Result<ImageContent> {
+        let body: TelegramApiResponse<TelegramFile> = self
+            .client
+            .get(self.api_url("getFile"))
+            .query(&[("file_id", file_id)])
+            .send()
+            .await
+            .map_err(|e| BizClawError::Channel(format!("Telegram getFile failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| BizClawError::Channel(format!("Invalid getFile response: {e}")))?;
+        let file_path = body
+            .result
+            .and_then(|f| f.file_path)
+            .ok_or_else(|| {
+                BizClawError::Channel(format!(
+                    "Telegram getFile: {}",
+                    body.description.as_deref().unwrap_or("no file path")
+                ))
+            })?;
+
+        // The file URL embeds the bot token, so the bytes are passed on
+        // rather than the URL.
+        let url = format!("{}/file/bot{}/{}", self.api_base, self.config.bot_token, file_path);
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| BizClawError::Channel(format!("Telegram file download failed: {e}")))?;
+        if !response.status().is_success() {
+            return Err(BizClawError::Channel(format!(
+                "Telegram file download failed: {}",
+                response.status()
+            )));
+        }
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| BizClawError::Channel(format!("Telegram file download failed: {e}")))?;
+        use base64::Engine;
+        Ok(ImageContent::new(
+            "image/jpeg",
+            base64::engine::general_purpose::STANDARD.encode(bytes),
+        ))
+    }
+
     /// Send a text message.
     pub async fn send_message(&self, chat_id: i64, text: &str) -> Result<()> {
-        let body = serde_json::json!({
+        self.send_reply(chat_id, text, None).await
+    }
+
+    /// Send a text message, as a reply to `reply_to_message_id` when given.
+    ///
+    /// The text is escaped for the chat's parse mode. If Telegram still
+    /// rejects the formatting (400), it is resent once as plain text.
+    pub async fn send_reply(
+        &self,
+        chat_id: i64,
+        text: &str,
+        reply_to_message_id: Option<i64>,
+    ) -> Result<()> {
+        let mode = self.config.parse_mode_for(chat_id);
+        let formatted = format_text(text, mode);
+        let mut result = self
+            .post_message(chat_id, &formatted, mode, reply_to_message_id)
+            .await?;
+        if !result.ok && result.error_code == Some(400) && mode != TelegramParseMode::Plain {
+            tracing::warn!(
+                "[telegram] {mode:?} message rejected ({}) — resending as plain text",
+                result.description.as_deref().unwrap_or("bad request")
+            );
+            result = self
+                .post_message(chat_id, text, TelegramParseMode::Plain, reply_to_message_id)
+                .await?;
+        }
+
+        if !result.ok {
+            return Err(BizClawError::Channel(format!(
+                "Send failed: {}",
+                result.description.unwrap_or_default()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn post_message(
+        &self,
+        chat_id: i64,
+        text: &str,
+        mode: TelegramParseMode,
+        reply_to_message_id: Option<i64>,
+    ) -> Result<TelegramApiResponse<serde_json::Value>> {
+        let mut body = serde_json::json!({
             "chat_id": chat_id,
             "text": text,
-            "parse_mode": "Markdown",
         });
+        if let Some(parse_mode) = parse_mode_name(mode) {
+            body["parse_mode"] = parse_mode.into();
+        }
+        if let Some(id) = reply_to_message_id {
+            body["reply_to_message_id"] = id.into();
+            // Still deliver the answer if the original message was deleted
+            body["allow_sending_without_reply"] = true.into();
+        }
 
         let response = self
             .client
@@ -100,18 +270,10 @@ impl TelegramChannel {
             .await
             .map_err(|e| BizClawError::Channel(format!("sendMessage failed: {e}")))?;
 
-        let result: TelegramApiResponse<serde_json::Value> = response
+        response
             .json()
             .await
-            .map_err(|e| BizClawError::Channel(format!("Invalid send response: {e}")))?;
-
-        if !result.ok {
-            return Err(BizClawError::Channel(format!(
-                "Send failed: {}",
-                result.description.unwrap_or_default()
-            )));
-        }
-        Ok(())
+            .map_err(|e| BizClawError::Channel(format!("Invalid send response: {e}")))
     }
 
     /// Send typing indicator.
@@ -129,6 +291,33 @@ impl TelegramChannel {
         Ok(())
     }
 
+    /// React to a message with an emoji.
+    pub async fn set_reaction(&self, chat_id: i64, message_id: i64, emoji: &str) -> Result<()> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_id": message_id,
+            "reaction": [{"type": "emoji", "emoji": emoji}],
+        });
+        let response = self
+            .client
+            .post(self.api_url("setMessageReaction"))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| BizClawError::Channel(format!("setMessageReaction failed: {e}")))?;
+        let result: TelegramApiResponse<serde_json::Value> = response
+            .json()
+            .await
+            .map_err(|e| BizClawError::Channel(format!("Invalid reaction response: {e}")))?;
+        if !result.ok {
+            return Err(BizClawError::Channel(format!(
+                "Reaction failed: {}",
+                result.description.unwrap_or_default()
+            )));
+        }
+        Ok(())
+    }
+
     /// Get bot info.
     pub async fn get_me(&self) -> Result<TelegramUser> {
         let response = self
@@ -152,22 +341,61 @@ impl TelegramChannel {
         // Spawn polling task
         tokio::spawn(async move {
             let mut channel = self;
+            let mut backoff = Backoff::default();
+            let status = StatusRegistry::global();
             tracing::info!("Telegram polling loop started");
 
             loop {
-                match channel.get_updates().await {
+                // Stop as soon as the listener drops the stream (e.g. on shutdown)
+                // instead of waiting out the long poll.
+                let result = tokio::select! {
+                    _ = tx.closed() => {
+                        tracing::info!("Telegram polling stopped (receiver dropped)");
+                        return;
+                    }
+                    result = channel.get_updates() => result,
+                };
+                match result {
                     Ok(updates) => {
+                        backoff.reset();
+                        channel.connected.store(true, Ordering::Relaxed);
+                        status.connected(&channel.status_key);
                         for update in updates {
-                            if let Some(msg) = update.to_incoming()
-                                && tx.send(msg).is_err() {
-                                    tracing::info!("Telegram polling stopped (receiver dropped)");
-                                    return;
+                            let Some(mut msg) = update.to_incoming() else {
+                                continue;
+                            };
+                            if let Some(photo) = update.largest_photo() {
+                                match channel.download_photo(&photo.file_id).await {
+                                    Ok(image) => msg.images.push(image),
+                                    Err(e) => tracing::warn!("Telegram photo not loaded: {e}"),
                                 }
+                            }
+                            if tx.send(msg).is_err() {
+                                tracing::info!("Telegram polling stopped (receiver dropped)");
+                                return;
+                            }
                         }
                     }
                     Err(e) => {
-                        tracing::error!("Telegram polling error: {e}");
-                        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                        if classify(&e) == FailureKind::Fatal {
+                            tracing::error!("Telegram polling stopped (fatal): {e}");
+                            channel.connected.store(false, Ordering::Relaxed);
+                            status.failed(&channel.status_key, &e.to_string());
+                            return;
+                        }
+                        let Some(delay) = backoff.next_delay() else {
+                            tracing::error!(
+                                "Telegram polling stopped after {} failed attempts: {e}",
+                                backoff.attempts()
+                            );
+                            channel.connected.store(false, Ordering::Relaxed);
+                            status.failed(&channel.status_key, &e.to_string());
+                            return;
+                        };
+                        tracing::warn!("Telegram polling error: {e}, retrying in {delay:?}");
+                        channel.connected.store(false, Ordering::Relaxed);
+                        status.reconnecting(&channel.status_key, &e.to_string(), backoff.attempts());
+                        tokio::time::sleep(delay).await;
                     }
                 }
 
@@ -210,17 +438,17 @@ impl Channel for TelegramChannel {
             me.username.as_deref().unwrap_or("unknown"),
             me.first_name
         );
-        self.connected = true;
+        self.connected.store(true, Ordering::Relaxed);
         Ok(())
     }
 
     async fn disconnect(&mut self) -> Result<()> {
-        self.connected = false;
+        self.connected.store(false, Ordering::Relaxed);
         Ok(())
     }
 
     fn is_connected(&self) -> bool {
-        self.connected
+        self.connected.load(Ordering::Relaxed)
     }
 
     async fn send(&self, message: OutgoingMessage) -> Result<()> {
@@ -228,7 +456,15 @@ impl Channel for TelegramChannel {
             .thread_id
             .parse()
             .map_err(|_| BizClawError::Channel("Invalid chat_id".into()))?;
-        self.send_message(chat_id, &message.content).await
+        let reply_to = message
+            .reply_to_message_id
+            .as_deref()
+            .and_then(|id| id.parse::<i64>().ok());
+        let text = match reply_to {
+            Some(_) => message.content.clone(),
+            None => message.content_with_quote(),
+        };
+        self.send_reply(chat_id, &text, reply_to).await
     }
 
     async fn send_typing(&self, thread_id: &str) -> Result<()> {
@@ -238,6 +474,14 @@ impl Channel for TelegramChannel {
         Ok(())
     }
 
+    async fn ack(&self, thread_id: &str, message_id: &str) -> Result<()> {
+        let (Ok(chat_id), Ok(message_id)) = (thread_id.parse::<i64>(), message_id.parse::<i64>())
+        else {
+            return Ok(());
+        };
+        self.set_reaction(chat_id, message_id, ACK_REACTION).await
+    }
+
     async fn listen(&self) -> Result<Box<dyn Stream<Item = IncomingMessage> + Send + Unpin>> {
         // For listen(), return a pending stream
         // For actual polling, use start_polling() which consumes self
@@ -245,6 +489,155 @@ impl Channel for TelegramChannel {
     }
 }
 
+// --- Formatting ---
+
+/// Bot API `parse_mode` value; plain text sends none.
+fn parse_mode_name(mode: TelegramParseMode) -> Option<&'static str> {
+    match mode {
+        TelegramParseMode::Markdown => Some("Markdown"),
+        TelegramParseMode::MarkdownV2 => Some("MarkdownV2"),
+        TelegramParseMode::Html => Some("HTML"),
+        TelegramParseMode::Plain => None,
+    }
+}
+
+/// A piece of model output: code and `**bold**` keep their formatting,
+/// everything else is escaped.
+#[derive(Debug, PartialEq)]
+enum Span<'a> {
+    Text(&'a str),
+    Bold(&'a str),
+    Code(&'a str),
+    Block { lang: &'a str, code: &'a str },
+}
+
+fn spans(text: &str) -> Vec<Span<'_>> {
+    let mut spans = Vec::new();
+    let mut plain = 0;
+    let mut i = 0;
+    while let Some(offset) = text[i..].find(['`', '*']) {
+        let at = i + offset;
+        let rest = &text[at..];
+        let (found, marker_len) = if let Some(body) = rest.strip_prefix("```") {
+            let block = body.find("```").map(|end| {
+                let inner = &body[..end];
+                let (lang, code) = match inner.split_once('\n') {
+                    Some((lang, code)) if !lang.contains(char::is_whitespace) => (lang, code),
+                    _ => ("", inner),
+                };
+                let code = code.strip_suffix('\n').unwrap_or(code);
+                (Span::Block { lang, code }, end + 6)
+            });
+            (block, 3)
+        } else if let Some(body) = rest.strip_prefix('`') {
+            let code = body
+                .find(['`', '\n'])
+                .filter(|&end| end > 0 && body[end..].starts_with('`'))
+                .map(|end| (Span::Code(&body[..end]), end + 2));
+            (code, 1)
+        } else if let Some(body) = rest.strip_prefix("**") {
+            let bold = body
+                .find("**")
+                .filter(|&end| end > 0 && !body[..end].contains('\n'))
+                .map(|end| (Span::Bold(&body[..end]), end + 4));
+            (bold, 2)
+        } else {
+            (None, 1)
+        };
+        match found {
+            Some((span, len)) => {
+                if plain < at {
+                    spans.push(Span::Text(&text[plain..at]));
+                }
+                spans.push(span);
+                i = at + len;
+                plain = i;
+            }
+            // Unmatched marker: keep it as text.
+            None => i = at + marker_len,
+        }
+    }
+    if plain < text.len() {
+        spans.push(Span::Text(&text[plain..]));
+    }
+    spans
+}
+
+fn escape_with(text: &str, special: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if special.contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Format model output for `mode`, escaping everything Telegram would
+/// otherwise parse as markup.
+pub fn format_text(text: &str, mode: TelegramParseMode) -> String {
+    const LEGACY: &str = "_*`[";
+    const V2: &str = "\\_*[]()~`>#+-=|{}.!";
+    const V2_CODE: &str = "\\`";
+
+    if mode == TelegramParseMode::Plain {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len() + 16);
+    for span in spans(text) {
+        match (mode, span) {
+            (TelegramParseMode::Plain, _) => {} // returned above
+
+            (TelegramParseMode::Markdown, Span::Text(t)) => out.push_str(&escape_with(t, LEGACY)),
+            // Legacy entities cannot nest or contain escapes.
+            (TelegramParseMode::Markdown, Span::Bold(t)) if t.contains(|c| LEGACY.contains(c)) => {
+                out.push_str(&escape_with(t, LEGACY))
+            }
+            (TelegramParseMode::Markdown, Span::Bold(t)) => out.push_str(&format!("*{t}*")),
+            (TelegramParseMode::Markdown, Span::Code(c)) => out.push_str(&format!("`{c}`")),
+            (TelegramParseMode::Markdown, Span::Block { lang, code }) => {
+                out.push_str(&format!("```{lang}\n{code}\n```"))
+            }
+
+            (TelegramParseMode::MarkdownV2, Span::Text(t)) => out.push_str(&escape_with(t, V2)),
+            (TelegramParseMode::MarkdownV2, Span::Bold(t)) => {
+                out.push_str(&format!("*{}*", escape_with(t, V2)))
+            }
+            (TelegramParseMode::MarkdownV2, Span::Code(c)) => {
+                out.push_str(&format!("`{}`", escape_with(c, V2_CODE)))
+            }
+            (TelegramParseMode::MarkdownV2, Span::Block { lang, code }) => out.push_str(&format!(
+                "```{lang}\n{}\n```",
+                escape_with(code, V2_CODE)
+            )),
+
+            (TelegramParseMode::Html, Span::Text(t)) => out.push_str(&escape_html(t)),
+            (TelegramParseMode::Html, Span::Bold(t)) => {
+                out.push_str(&format!("<b>{}</b>", escape_html(t)))
+            }
+            (TelegramParseMode::Html, Span::Code(c)) => {
+                out.push_str(&format!("<code>{}</code>", escape_html(c)))
+            }
+            (TelegramParseMode::Html, Span::Block { lang: "", code }) => {
+                out.push_str(&format!("<pre>{}</pre>", escape_html(code)))
+            }
+            (TelegramParseMode::Html, Span::Block { lang, code }) => out.push_str(&format!(
+                "<pre><code class=\"language-{}\">{}</code></pre>",
+                escape_html(lang),
+                escape_html(code)
+            )),
+        }
+    }
+    out
+}
+
 // --- Telegram API Types ---
 
 #[derive(Debug, Deserialize)]
@@ -252,6 +645,8 @@ pub struct TelegramApiResponse<T> {
     pub ok: bool,
     pub result: Option<T>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub error_code: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -268,6 +663,24 @@ pub struct TelegramMessage {
     pub text: Option<String>,
     pub date: i64,
     pub reply_to_message: Option<Box<TelegramMessage>>,
+    /// A photo in several sizes, smallest first.
+    #[serde(default)]
+    pub photo: Vec<TelegramPhotoSize>,
+    /// Text sent with a photo.
+    pub caption: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramPhotoSize {
+    pub file_id: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramFile {
+    pub file_id: String,
+    pub file_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -291,7 +704,12 @@ impl TelegramUpdate {
     /// Convert to BizClaw IncomingMessage.
     pub fn to_incoming(&self) -> Option<IncomingMessage> {
         let msg = self.message.as_ref()?;
-        let text = msg.text.as_ref()?;
+        let text = match (&msg.text, &msg.caption) {
+            (Some(text), _) | (None, Some(text)) => text.clone(),
+            // A photo without a caption is still a message.
+            (None, None) if !msg.photo.is_empty() => String::new(),
+            (None, None) => return None,
+        };
         let from = msg.from.as_ref()?;
 
         // Skip bot messages
@@ -311,7 +729,7 @@ impl TelegramUpdate {
                     .map(|l| format!(" {l}"))
                     .unwrap_or_default()
             )),
-            content: text.clone(),
+            content: text,
             thread_type: match msg.chat.chat_type.as_str() {
                 "private" => ThreadType::Direct,
                 _ => ThreadType::Group,
@@ -321,6 +739,215 @@ impl TelegramUpdate {
                 .reply_to_message
                 .as_ref()
                 .map(|r| r.message_id.to_string()),
+            message_id: Some(msg.message_id.to_string()),
+            images: Vec::new(),
         })
     }
+
+    /// The largest size of the message's photo, if it has one. Loaded
+    /// separately with [`TelegramChannel::download_photo`].
+    pub fn largest_photo(&self) -> Option<&TelegramPhotoSize> {
+        self.message
+            .as_ref()?
+            .photo
+            .iter()
+            .max_by_key(|p| p.width * p.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{capture_one_request, capture_requests};
+
+    #[tokio::test]
+    async fn test_ack_reacts_to_message() {
+        let (base, request) = capture_one_request(r#"{"ok": true, "result": true}"#).await;
+        let channel = TelegramChannel::new(TelegramConfig::new("123:abc"))
+        .with_api_base(&base);
+
+        channel.ack("-1001", "42").await.unwrap();
+
+        let (head, body) = request.await.unwrap();
+        assert!(head.starts_with("POST /bot123:abc/setMessageReaction "), "{head}");
+        assert_eq!(body["chat_id"], -1001);
+        assert_eq!(body["message_id"], 42);
+        assert_eq!(body["reaction"][0]["emoji"], ACK_REACTION);
+    }
+
+    #[tokio::test]
+    async fn test_send_includes_reply_reference() {
+        let (base, request) = capture_one_request(r#"{"ok": true, "result": {}}"#).await;
+        let channel = TelegramChannel::new(TelegramConfig::new("123:abc"))
+        .with_api_base(&base);
+
+        channel
+            .send(OutgoingMessage {
+                thread_id: "-1001".into(),
+                content: "Yes, in stock.".into(),
+                thread_type: ThreadType::Group,
+                reply_to: None,
+                reply_to_message_id: Some("42".into()),
+                quote: Some("Do you have size M?".into()),
+            })
+            .await
+            .unwrap();
+
+        let (head, body) = request.await.unwrap();
+        assert!(head.starts_with("POST /bot123:abc/sendMessage "), "{head}");
+        assert_eq!(body["reply_to_message_id"], 42);
+        // Native reply shows the original, so the quote is not repeated.
+        assert_eq!(body["text"], "Yes, in stock.");
+    }
+
+    #[tokio::test]
+    async fn test_polling_marks_clones_connected() {
+        let (base, _request) = capture_one_request(r#"{"ok": true, "result": []}"#).await;
+        let channel = TelegramChannel::new(TelegramConfig::new("123:abc")).with_api_base(&base);
+        let handle = channel.clone();
+        assert!(!handle.is_connected());
+
+        let _stream = channel.start_polling();
+        for _ in 0..100 {
+            if handle.is_connected() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(handle.is_connected());
+    }
+
+    const SAMPLE: &str = "Use `snake_case` for **my_var** (v1.5)!\n```rust\nlet x = a_b * 2;\n```";
+
+    #[test]
+    fn test_format_markdown_v2_escapes() {
+        assert_eq!(
+            format_text(SAMPLE, TelegramParseMode::MarkdownV2),
+            "Use `snake_case` for *my\\_var* \\(v1\\.5\\)\\!\n```rust\nlet x = a_b * 2;\n```"
+        );
+        assert_eq!(
+            format_text("path C:\\tmp `a\\b`", TelegramParseMode::MarkdownV2),
+            "path C:\\\\tmp `a\\\\b`"
+        );
+    }
+
+    #[test]
+    fn test_format_html_escapes() {
+        assert_eq!(
+            format_text(SAMPLE, TelegramParseMode::Html),
+            "Use <code>snake_case</code> for <b>my_var</b> (v1.5)!\n\
+             <pre><code class=\"language-rust\">let x = a_b * 2;</code></pre>"
+        );
+        assert_eq!(
+            format_text("if a < b && c > d", TelegramParseMode::Html),
+            "if a &lt; b &amp;&amp; c &gt; d"
+        );
+    }
+
+    #[test]
+    fn test_format_legacy_markdown_and_plain() {
+        assert_eq!(
+            format_text(SAMPLE, TelegramParseMode::Markdown),
+            "Use `snake_case` for my\\_var (v1.5)!\n```rust\nlet x = a_b * 2;\n```"
+        );
+        assert_eq!(
+            format_text("2 * 3 = 6, see [docs] and `unclosed", TelegramParseMode::Markdown),
+            "2 \\* 3 = 6, see \\[docs] and \\`unclosed"
+        );
+        assert_eq!(format_text("**ok**", TelegramParseMode::Markdown), "*ok*");
+        assert_eq!(format_text(SAMPLE, TelegramParseMode::Plain), SAMPLE);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_markup_is_resent_as_plain() {
+        let (base, requests) = capture_requests(vec![
+            (
+                400,
+                r#"{"ok": false, "error_code": 400, "description": "Bad Request: can't parse entities"}"#,
+            ),
+            (200, r#"{"ok": true, "result": {}}"#),
+        ])
+        .await;
+        let mut config = TelegramConfig::new("123:abc");
+        config.chat_parse_modes.insert("-1001".into(), TelegramParseMode::MarkdownV2);
+        let channel = TelegramChannel::new(config).with_api_base(&base);
+
+        channel.send_message(-1001, "Done_ok!").await.unwrap();
+
+        let requests = requests.await.unwrap();
+        let (_, first) = &requests[0];
+        assert_eq!(first["parse_mode"], "MarkdownV2");
+        assert_eq!(first["text"], "Done\\_ok\\!");
+        let (_, retry) = &requests[1];
+        assert!(retry.get("parse_mode").is_none());
+        assert_eq!(retry["text"], "Done_ok!");
+    }
+
+    #[tokio::test]
+    async fn test_plain_mode_sends_no_parse_mode() {
+        let (base, request) = capture_one_request(r#"{"ok": true, "result": {}}"#).await;
+        let config = TelegramConfig {
+            parse_mode: TelegramParseMode::Plain,
+            ..TelegramConfig::new("123:abc")
+        };
+        let channel = TelegramChannel::new(config).with_api_base(&base);
+
+        channel.send_message(7, "a_b *c*").await.unwrap();
+
+        let (_, body) = request.await.unwrap();
+        assert!(body.get("parse_mode").is_none());
+        assert_eq!(body["text"], "a_b *c*");
+    }
+
+    #[test]
+    fn test_incoming_carries_message_id() {
+        let update: TelegramUpdate = serde_json::from_value(serde_json::json!({
+            "update_id": 1,
+            "message": {
+                "message_id": 42,
+                "from": {"id": 7, "is_bot": false, "first_name": "Lan"},
+                "chat": {"id": 7, "type": "private"},
+                "text": "hi",
+                "date": 0
+            }
+        }))
+        .unwrap();
+        assert_eq!(update.to_incoming().unwrap().message_id.as_deref(), Some("42"));
+    }
+
+    #[tokio::test]
+    async fn test_photo_message_downloads_largest_size() {
+        let update: TelegramUpdate = serde_json::from_value(serde_json::json!({
+            "update_id": 2,
+            "message": {
+                "message_id": 43,
+                "from": {"id": 7, "is_bot": false, "first_name": "Lan"},
+                "chat": {"id": 7, "type": "private"},
+                "date": 0,
+                "caption": "Is this in stock?",
+                "photo": [
+                    {"file_id": "small", "width": 90, "height": 90},
+                    {"file_id": "large", "width": 1280, "height": 960}
+                ]
+            }
+        }))
+        .unwrap();
+        let incoming = update.to_incoming().unwrap();
+        assert_eq!(incoming.content, "Is this in stock?");
+        let photo = update.largest_photo().unwrap();
+        assert_eq!(photo.file_id, "large");
+
+        let (base, requests) = capture_requests(vec![
+            (200, r#"{"ok": true, "result": {"file_id": "large", "file_path": "photos/1.jpg"}}"#),
+            (200, "JPEG"),
+        ])
+        .await;
+        let channel = TelegramChannel::new(TelegramConfig::new("123:abc")).with_api_base(&base);
+        let image = channel.download_photo(&photo.file_id).await.unwrap();
+        assert_eq!(image, ImageContent::new("image/jpeg", "SlBFRw=="));
+
+        let requests = requests.await.unwrap();
+        assert!(requests[0].0.starts_with("GET /bot123:abc/getFile?file_id=large "));
+        assert!(requests[1].0.starts_with("GET /file/bot123:abc/photos/1.jpg "));
+    }
 }
@@ -0,0 +1,66 @@
+//! Test helpers shared by channel implementations.
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Serve one HTTP request on a local port, replying 200 with `body`.
+/// Returns the base URL and a handle resolving to the request head and JSON body.
+pub async fn capture_one_request(
+    body: &'static str,
+) -> (String, tokio::task::JoinHandle<(String, serde_json::Value)>) {
+    let (base, handle) = capture_requests(vec![(200, body)]).await;
+    let handle = tokio::spawn(async move { handle.await.unwrap().remove(0) });
+    (base, handle)
+}
+
+/// Serve one request per entry of `replies`, answering each with its status
+/// and body. Resolves to the request heads and JSON bodies, in order.
+pub async fn capture_requests(
+    replies: Vec<(u16, &'static str)>,
+) -> (
+    String,
+    tokio::task::JoinHandle<Vec<(String, serde_json::Value)>>,
+) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        for (status, body) in replies {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            requests.push(read_request(&mut sock).await);
+            let resp = format!(
+                "HTTP/1.1 {status} X\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            sock.write_all(resp.as_bytes()).await.unwrap();
+        }
+        requests
+    });
+    (base, handle)
+}
+
+/// Read a request head and its JSON body (`Null` if the body is not JSON).
+async fn read_request(sock: &mut TcpStream) -> (String, serde_json::Value) {
+    let mut raw = Vec::new();
+    let mut buf = [0u8; 4096];
+    let (head, body_start) = loop {
+        let n = sock.read(&mut buf).await.unwrap();
+        raw.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&raw).to_string();
+        if let Some(head_end) = text.find("\r\n\r\n") {
+            let len = text
+                .lines()
+                .find_map(|l| {
+                    l.to_ascii_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                })
+                .unwrap_or(0);
+            if raw.len() >= head_end + 4 + len || n == 0 {
+                break (text[..head_end].to_string(), head_end + 4);
+            }
+        }
+    };
+    let json = serde_json::from_slice(&raw[body_start..]).unwrap_or_default();
+    (head, json)
+}
@@ -2,6 +2,7 @@
 //!
 //! Converts agent responses to audio for voice assistants like Xiaozhi.
 
+use bizclaw_core::text::truncate_chars;
 use serde::{Deserialize, Serialize};
 
 /// TTS provider configuration.
@@ -101,7 +102,7 @@ impl TtsEngine {
         if !response.status().is_success() {
             let status = response.status();
             let err: String = response.text().await.unwrap_or_default();
-            return Err(format!("OpenAI TTS {status}: {}", &err[..err.len().min(200)]));
+            return Err(format!("OpenAI TTS {status}: {}", truncate_chars(&err, 200)));
         }
 
         let audio: Vec<u8> = response.bytes().await.map_err(|e| format!("Read error: {e}"))?.to_vec();
@@ -140,7 +141,7 @@ impl TtsEngine {
         if !response.status().is_success() {
             let status = response.status();
             let err: String = response.text().await.unwrap_or_default();
-            return Err(format!("ElevenLabs TTS {status}: {}", &err[..err.len().min(200)]));
+            return Err(format!("ElevenLabs TTS {status}: {}", truncate_chars(&err, 200)));
         }
 
         let audio: Vec<u8> = response.bytes().await.map_err(|e| format!("Read error: {e}"))?.to_vec();
@@ -0,0 +1,132 @@
+//! Typing heartbeat — keeps the "typing…" indicator up during slow replies.
+//!
+//! Most platforms drop a typing indicator after a few seconds, so a single
+//! `send_typing` before a long generation leaves the user looking at a quiet
+//! chat. [`while_typing`] re-sends it every `[channel.typing] interval_secs`
+//! until the reply is ready, giving up after `max_secs`.
+
+use bizclaw_core::config::TypingConfig;
+use bizclaw_core::traits::Channel;
+use std::future::Future;
+use tokio::time::Instant;
+
+/// Await `work` while showing a typing indicator in `thread_id`.
+///
+/// The indicator is sent right away and then every configured interval.
+/// Failed indicators are only logged; they never affect `work`.
+pub async fn while_typing<F: Future>(
+    channel: &dyn Channel,
+    thread_id: &str,
+    config: &TypingConfig,
+    work: F,
+) -> F::Output {
+    let Some(interval) = config.interval() else {
+        return work.await;
+    };
+    let deadline = Instant::now() + config.max_duration();
+    tokio::pin!(work);
+    loop {
+        // Typing is sent inside the select, so a slow indicator request
+        // never holds up the reply.
+        let beat = async {
+            if let Err(e) = channel.send_typing(thread_id).await {
+                tracing::debug!("[{}] Typing indicator failed: {e}", channel.name());
+            }
+            tokio::time::sleep(interval).await;
+        };
+        tokio::select! {
+            output = &mut work => return output,
+            _ = beat => {}
+        }
+        if Instant::now() >= deadline {
+            return work.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use bizclaw_core::error::Result;
+    use bizclaw_core::types::{IncomingMessage, OutgoingMessage};
+    use futures::stream::{self, Stream};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct CountingChannel {
+        typing: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Channel for CountingChannel {
+        fn name(&self) -> &str {
+            "counting"
+        }
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+        async fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+        fn is_connected(&self) -> bool {
+            true
+        }
+        async fn listen(&self) -> Result<Box<dyn Stream<Item = IncomingMessage> + Send + Unpin>> {
+            Ok(Box::new(stream::empty()))
+        }
+        async fn send(&self, _message: OutgoingMessage) -> Result<()> {
+            Ok(())
+        }
+        async fn send_typing(&self, _thread_id: &str) -> Result<()> {
+            self.typing.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn typing(interval_secs: u64, max_secs: u64) -> TypingConfig {
+        TypingConfig {
+            interval_secs,
+            max_secs,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_slow_reply_sends_typing_until_done() {
+        let channel = CountingChannel::default();
+        let reply = while_typing(&channel, "chat-1", &typing(4, 120), async {
+            tokio::time::sleep(Duration::from_secs(13)).await;
+            "done"
+        })
+        .await;
+        assert_eq!(reply, "done");
+        // At 0s, 4s, 8s and 12s.
+        assert_eq!(channel.typing.load(Ordering::SeqCst), 4);
+
+        // Nothing more once the reply is out.
+        tokio::time::sleep(Duration::from_secs(20)).await;
+        assert_eq!(channel.typing.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_typing_stops_after_max_secs() {
+        let channel = CountingChannel::default();
+        while_typing(&channel, "chat-1", &typing(5, 10), async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        })
+        .await;
+        // At 0s and 5s; stopped at 10s.
+        assert_eq!(channel.typing.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_zero_interval_disables_typing() {
+        let channel = CountingChannel::default();
+        while_typing(&channel, "chat-1", &typing(0, 120), async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        })
+        .await;
+        assert_eq!(channel.typing.load(Ordering::SeqCst), 0);
+    }
+}
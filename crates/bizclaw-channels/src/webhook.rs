@@ -1,6 +1,10 @@
 //! Webhook channel — receive inbound HTTP webhooks and send outbound.
 //!
 //! Useful for integrating with external systems (Zapier, n8n, custom APIs).
+//!
+//! Named webhooks (`POST /api/v1/webhook/:name`) accept arbitrary JSON payloads:
+//! a [`WebhookMapping`] picks the sender id, text and thread out of the body
+//! with simple JSON paths such as `message.from.id` or `events[0].text`.
 
 use async_trait::async_trait;
 use bizclaw_core::error::{BizClawError, Result};
@@ -25,6 +29,125 @@ fn default_true() -> bool {
     true
 }
 
+/// JSON-path mapping that turns an arbitrary webhook body into an [`IncomingMessage`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookMapping {
+    /// Path to the sender id (e.g. `message.from.id`).
+    #[serde(default = "default_sender_path")]
+    pub sender_path: String,
+    /// Path to the message text (e.g. `message.text`).
+    #[serde(default = "default_text_path")]
+    pub text_path: String,
+    /// Optional path to a thread/conversation id. Falls back to the sender id.
+    #[serde(default)]
+    pub thread_path: Option<String>,
+}
+
+fn default_sender_path() -> String {
+    "sender_id".into()
+}
+
+fn default_text_path() -> String {
+    "content".into()
+}
+
+impl Default for WebhookMapping {
+    fn default() -> Self {
+        Self {
+            sender_path: default_sender_path(),
+            text_path: default_text_path(),
+            thread_path: Some("thread_id".into()),
+        }
+    }
+}
+
+/// Resolve a dotted JSON path (`a.b[0].c`, optional leading `$.`) against a value.
+pub fn json_path_get<'a>(
+    value: &'a serde_json::Value,
+    path: &str,
+) -> Option<&'a serde_json::Value> {
+    let path = path.trim().trim_start_matches('$').trim_start_matches('.');
+    if path.is_empty() {
+        return Some(value);
+    }
+    let mut current = value;
+    for segment in path.split('.') {
+        let (key, indices) = match segment.find('[') {
+            Some(pos) => (&segment[..pos], &segment[pos..]),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = current.get(key)?;
+        }
+        for idx in indices.split('[').filter(|s| !s.is_empty()) {
+            let n: usize = idx.trim_end_matches(']').parse().ok()?;
+            current = current.get(n)?;
+        }
+    }
+    Some(current)
+}
+
+/// Render a JSON scalar as text (numbers and bools are common for ids).
+fn json_scalar_to_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Constant-time comparison of a shared secret against the value sent in a header.
+pub fn verify_shared_secret(expected: &str, provided: Option<&str>) -> bool {
+    let Some(provided) = provided else {
+        return false;
+    };
+    if expected.is_empty() || expected.len() != provided.len() {
+        return false;
+    }
+    expected
+        .bytes()
+        .zip(provided.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+/// Map an arbitrary webhook payload into an [`IncomingMessage`] for the named webhook.
+pub fn map_inbound(
+    name: &str,
+    payload: &serde_json::Value,
+    mapping: &WebhookMapping,
+) -> Result<IncomingMessage> {
+    let content = json_path_get(payload, &mapping.text_path)
+        .and_then(json_scalar_to_string)
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| {
+            BizClawError::Channel(format!("No message text at '{}'", mapping.text_path))
+        })?;
+    let sender_id = json_path_get(payload, &mapping.sender_path)
+        .and_then(json_scalar_to_string)
+        .unwrap_or_else(|| "webhook-user".into());
+    let thread_id = mapping
+        .thread_path
+        .as_deref()
+        .and_then(|p| json_path_get(payload, p))
+        .and_then(json_scalar_to_string)
+        .unwrap_or_else(|| sender_id.clone());
+
+    Ok(IncomingMessage {
+        channel: format!("webhook:{name}"),
+        thread_id,
+        sender_id,
+        sender_name: None,
+        content,
+        thread_type: ThreadType::Direct,
+        timestamp: chrono::Utc::now(),
+        reply_to: None,
+        message_id: None,
+        images: Vec::new(),
+    })
+}
+
 /// Webhook channel.
 pub struct WebhookChannel {
     config: WebhookConfig,
@@ -80,6 +203,8 @@ impl WebhookChannel {
             thread_type: ThreadType::Direct,
             timestamp: chrono::Utc::now(),
             reply_to: None,
+            message_id: None,
+            images: serde_json::from_value(json["images"].clone()).unwrap_or_default(),
         })
     }
 }
@@ -111,6 +236,8 @@ impl Channel for WebhookChannel {
                 "thread_id": message.thread_id,
                 "content": message.content,
                 "reply_to": message.reply_to,
+                "reply_to_message_id": message.reply_to_message_id,
+                "quote": message.quote,
             });
 
             self.client
@@ -146,4 +273,45 @@ mod tests {
         assert_eq!(msg.sender_id, "user1");
         assert_eq!(msg.channel, "webhook");
     }
+
+    #[test]
+    fn test_verify_shared_secret() {
+        assert!(verify_shared_secret("s3cret", Some("s3cret")));
+        assert!(!verify_shared_secret("s3cret", Some("s3creT")));
+        assert!(!verify_shared_secret("s3cret", Some("s3cret-longer")));
+        assert!(!verify_shared_secret("s3cret", None));
+        assert!(!verify_shared_secret("", Some("")));
+    }
+
+    #[test]
+    fn test_map_inbound_with_json_paths() {
+        let payload = serde_json::json!({
+            "event": "message",
+            "data": {
+                "from": {"id": 42, "name": "Lan"},
+                "messages": [{"body": "Xin chào"}],
+                "conversation": "conv-7"
+            }
+        });
+        let mapping = WebhookMapping {
+            sender_path: "data.from.id".into(),
+            text_path: "$.data.messages[0].body".into(),
+            thread_path: Some("data.conversation".into()),
+        };
+        let msg = map_inbound("crm", &payload, &mapping).unwrap();
+        assert_eq!(msg.channel, "webhook:crm");
+        assert_eq!(msg.sender_id, "42");
+        assert_eq!(msg.content, "Xin chào");
+        assert_eq!(msg.thread_id, "conv-7");
+    }
+
+    #[test]
+    fn test_map_inbound_missing_text() {
+        let payload = serde_json::json!({"sender_id": "u1"});
+        assert!(map_inbound("crm", &payload, &WebhookMapping::default()).is_err());
+
+        let payload = serde_json::json!({"sender_id": "u1", "content": "hi"});
+        let msg = map_inbound("crm", &payload, &WebhookMapping::default()).unwrap();
+        assert_eq!(msg.thread_id, "u1");
+    }
 }
@@ -26,11 +26,43 @@ pub struct WhatsAppConfig {
     pub business_id: String,
 }
 
+/// Header carrying Meta's HMAC-SHA256 signature of a webhook body.
+pub const SIGNATURE_HEADER: &str = "x-hub-signature-256";
+
+/// `X-Hub-Signature-256` value for `body`:
+/// `sha256=<hex HMAC-SHA256 of the body keyed by the app secret>`.
+pub fn signature(app_secret: &str, body: &[u8]) -> String {
+    use hmac::{Hmac, Mac};
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(app_secret.as_bytes())
+        .expect("HMAC can take key of any size");
+    mac.update(body);
+    let hex: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    format!("sha256={hex}")
+}
+
+/// Check a webhook body against its `X-Hub-Signature-256` header (see
+/// [`signature`]). A missing or malformed header, or an empty app secret, fails.
+pub fn verify_signature(app_secret: &str, body: &[u8], signature: Option<&str>) -> bool {
+    let Some(provided) = signature.map(str::trim).filter(|s| s.starts_with("sha256=")) else {
+        return false;
+    };
+    if app_secret.is_empty() {
+        return false;
+    }
+    let expected = self::signature(app_secret, body);
+    crate::webhook::verify_shared_secret(&expected, Some(&provided.to_ascii_lowercase()))
+}
 
 /// WhatsApp Business channel implementation.
 pub struct WhatsAppChannel {
     config: WhatsAppConfig,
     client: reqwest::Client,
+    api_base: String,
     connected: bool,
 }
 
@@ -39,18 +71,30 @@ impl WhatsAppChannel {
         Self {
             config,
             client: reqwest::Client::new(),
+            api_base: "https://graph.facebook.com/v21.0".into(),
             connected: false,
         }
     }
 
-    /// Send a text message via WhatsApp Cloud API.
-    async fn send_text_message(&self, to: &str, text: &str) -> Result<String> {
+    /// Use a different Graph API base URL (e.g. another API version, or tests).
+    pub fn with_api_base(mut self, api_base: &str) -> Self {
+        self.api_base = api_base.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Send a text message via WhatsApp Cloud API, optionally replying to a message.
+    async fn send_text_message(
+        &self,
+        to: &str,
+        text: &str,
+        reply_to: Option<&str>,
+    ) -> Result<String> {
         let url = format!(
-            "https://graph.facebook.com/v21.0/{}/messages",
-            self.config.phone_number_id
+            "{}/{}/messages",
+            self.api_base, self.config.phone_number_id
         );
 
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "messaging_product": "whatsapp",
             "recipient_type": "individual",
             "to": to,
@@ -60,6 +104,9 @@ impl WhatsAppChannel {
                 "body": text
             }
         });
+        if let Some(id) = reply_to {
+            body["context"] = serde_json::json!({ "message_id": id });
+        }
 
         let response = self
             .client
@@ -100,8 +147,8 @@ impl WhatsAppChannel {
     /// Mark a message as read.
     pub async fn mark_as_read(&self, message_id: &str) -> Result<()> {
         let url = format!(
-            "https://graph.facebook.com/v21.0/{}/messages",
-            self.config.phone_number_id
+            "{}/{}/messages",
+            self.api_base, self.config.phone_number_id
         );
 
         let body = serde_json::json!({
@@ -110,7 +157,8 @@ impl WhatsAppChannel {
             "message_id": message_id
         });
 
-        self.client
+        let response = self
+            .client
             .post(&url)
             .header(
                 "Authorization",
@@ -121,6 +169,13 @@ impl WhatsAppChannel {
             .await
             .map_err(|e| BizClawError::Channel(format!("WhatsApp mark-read failed: {e}")))?;
 
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(BizClawError::Channel(format!(
+                "WhatsApp mark-read error {status}: {error_text}"
+            )));
+        }
         Ok(())
     }
 }
@@ -145,8 +200,8 @@ impl Channel for WhatsAppChannel {
 
         // Verify token by checking phone number
         let url = format!(
-            "https://graph.facebook.com/v21.0/{}",
-            self.config.phone_number_id
+            "{}/{}",
+            self.api_base, self.config.phone_number_id
         );
 
         let response = self
@@ -195,8 +250,16 @@ impl Channel for WhatsAppChannel {
     }
 
     async fn send(&self, message: OutgoingMessage) -> Result<()> {
-        self.send_text_message(&message.thread_id, &message.content)
-            .await?;
+        match message.reply_to_message_id.as_deref() {
+            Some(id) => {
+                self.send_text_message(&message.thread_id, &message.content, Some(id))
+                    .await?
+            }
+            None => {
+                self.send_text_message(&message.thread_id, &message.content_with_quote(), None)
+                    .await?
+            }
+        };
         Ok(())
     }
 
@@ -204,4 +267,101 @@ impl Channel for WhatsAppChannel {
         // WhatsApp doesn't support typing indicators via Cloud API
         Ok(())
     }
+
+    async fn ack(&self, _thread_id: &str, message_id: &str) -> Result<()> {
+        self.mark_as_read(message_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::capture_one_request;
+
+    const PAYLOAD: &str = r#"{"object":"whatsapp_business_account","entry":[]}"#;
+
+    fn sign(secret: &str, body: &str) -> String {
+        signature(secret, body.as_bytes())
+    }
+
+    #[test]
+    fn test_signed_payload_is_accepted() {
+        let signature = sign("app-secret", PAYLOAD);
+        assert!(verify_signature(
+            "app-secret",
+            PAYLOAD.as_bytes(),
+            Some(&signature)
+        ));
+    }
+
+    #[test]
+    fn test_tampered_or_unsigned_payload_is_rejected() {
+        let signature = sign("app-secret", PAYLOAD);
+        let tampered = PAYLOAD.replace("[]", r#"[{"id":"1"}]"#);
+        assert!(!verify_signature(
+            "app-secret",
+            tampered.as_bytes(),
+            Some(&signature)
+        ));
+        assert!(!verify_signature(
+            "other-secret",
+            PAYLOAD.as_bytes(),
+            Some(&signature)
+        ));
+        assert!(!verify_signature("app-secret", PAYLOAD.as_bytes(), None));
+        assert!(!verify_signature("", PAYLOAD.as_bytes(), Some(&sign("", PAYLOAD))));
+        let bare = signature.trim_start_matches("sha256=");
+        assert!(!verify_signature(
+            "app-secret",
+            PAYLOAD.as_bytes(),
+            Some(bare)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ack_marks_message_read() {
+        let (base, request) = capture_one_request(r#"{"success": true}"#).await;
+        let channel = WhatsAppChannel::new(WhatsAppConfig {
+            access_token: "EAAG-test".into(),
+            phone_number_id: "1055".into(),
+            ..Default::default()
+        })
+        .with_api_base(&base);
+
+        channel.ack("84901234567", "wamid.HBgL").await.unwrap();
+
+        let (head, body) = request.await.unwrap();
+        assert!(head.starts_with("POST /1055/messages "), "{head}");
+        assert!(head.to_lowercase().contains("authorization: bearer eaag-test"));
+        assert_eq!(body["status"], "read");
+        assert_eq!(body["message_id"], "wamid.HBgL");
+        assert_eq!(body["messaging_product"], "whatsapp");
+    }
+
+    #[tokio::test]
+    async fn test_send_includes_reply_context() {
+        let (base, request) = capture_one_request(r#"{"messages": [{"id": "wamid.OUT"}]}"#).await;
+        let channel = WhatsAppChannel::new(WhatsAppConfig {
+            access_token: "EAAG-test".into(),
+            phone_number_id: "1055".into(),
+            ..Default::default()
+        })
+        .with_api_base(&base);
+
+        channel
+            .send(OutgoingMessage {
+                thread_id: "84901234567".into(),
+                content: "Yes, in stock.".into(),
+                thread_type: bizclaw_core::types::ThreadType::Direct,
+                reply_to: None,
+                reply_to_message_id: Some("wamid.IN".into()),
+                quote: None,
+            })
+            .await
+            .unwrap();
+
+        let (_, body) = request.await.unwrap();
+        assert_eq!(body["context"]["message_id"], "wamid.IN");
+        assert_eq!(body["text"]["body"], "Yes, in stock.");
+    }
 }
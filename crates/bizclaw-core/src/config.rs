@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use crate::error::Result;
 use crate::traits::identity::Identity;
@@ -24,6 +25,93 @@ pub struct LlmConfig {
     /// Generation temperature.
     #[serde(default = "default_temperature")]
     pub temperature: f32,
+    /// Extra HTTP headers sent with every request (e.g. `OpenAI-Organization`).
+    #[serde(default)]
+    pub extra_headers: std::collections::HashMap<String, String>,
+    /// HTTP(S)/SOCKS proxy for provider requests. Empty = direct connection.
+    #[serde(default)]
+    pub proxy_url: String,
+    /// Azure OpenAI `api-version` query parameter. Setting it switches the
+    /// request URL to the deployment form and uses the `api-key` header.
+    #[serde(default)]
+    pub api_version: String,
+    /// Azure OpenAI deployment name. Empty = use `model`.
+    #[serde(default)]
+    pub deployment: String,
+    /// Concurrency and rate limits applied around provider calls.
+    #[serde(default)]
+    pub limits: ProviderLimitsConfig,
+    /// Fast-fail calls to a provider that keeps failing.
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Include request and response bodies (user content) in debug logs of
+    /// provider calls. Auth headers are always redacted.
+    #[serde(default)]
+    pub log_request_bodies: bool,
+    /// Answer with the local brain (`[brain]`) when the provider cannot be
+    /// reached. Only connection failures and timeouts fall back; error
+    /// responses such as a 400 do not.
+    #[serde(default)]
+    pub fallback_to_brain: bool,
+}
+
+/// Limits on provider calls. Requests over a limit wait in line instead of
+/// failing. `0` disables a limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderLimitsConfig {
+    /// Max in-flight provider calls across all providers in this process.
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+    /// Max in-flight calls to one provider (e.g. "openai").
+    #[serde(default)]
+    pub max_concurrent_per_provider: usize,
+    /// Max calls per minute to one provider.
+    #[serde(default)]
+    pub requests_per_minute: u32,
+}
+
+fn default_max_concurrent() -> usize {
+    16
+}
+
+impl Default for ProviderLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: default_max_concurrent(),
+            max_concurrent_per_provider: 0,
+            requests_per_minute: 0,
+        }
+    }
+}
+
+/// Per-provider circuit breaker. After `failure_threshold` consecutive
+/// failures, calls fail immediately for `cooldown_secs`; then one probe call
+/// decides whether the provider is back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the breaker. `0` disables it.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// How long an open breaker fast-fails before probing again.
+    #[serde(default = "default_breaker_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+fn default_failure_threshold() -> u32 {
+    5
+}
+
+fn default_breaker_cooldown_secs() -> u64 {
+    30
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: default_failure_threshold(),
+            cooldown_secs: default_breaker_cooldown_secs(),
+        }
+    }
 }
 
 impl Default for LlmConfig {
@@ -34,6 +122,14 @@ impl Default for LlmConfig {
             api_key: String::new(),
             endpoint: String::new(),
             temperature: default_temperature(),
+            extra_headers: Default::default(),
+            proxy_url: String::new(),
+            api_version: String::new(),
+            deployment: String::new(),
+            limits: ProviderLimitsConfig::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            log_request_bodies: false,
+            fallback_to_brain: false,
         }
     }
 }
@@ -80,6 +176,32 @@ pub struct BizClawConfig {
     /// Quality Gate — optional evaluator for response review.
     #[serde(default)]
     pub quality_gate: Option<QualityGateConfig>,
+    /// Current date/time injected into the agent's context each turn.
+    #[serde(default)]
+    pub datetime: DateTimeConfig,
+    /// Saved `bizclaw chat` conversations.
+    #[serde(default)]
+    pub chat_history: ChatHistoryConfig,
+    /// How much conversation history the agent keeps in context.
+    #[serde(default)]
+    pub context: ContextConfig,
+    /// Response length and stop sequences sent with each provider request.
+    #[serde(default)]
+    pub generation: GenerationConfig,
+    /// Short names for models, e.g. `fast = "groq:llama-3.1-8b-instant"` or
+    /// `smart = "claude-sonnet-4"`. A `provider:` prefix also switches the
+    /// provider. Resolved wherever a model is selected by name.
+    #[serde(default)]
+    pub model_aliases: std::collections::HashMap<String, String>,
+    /// Locale for user-facing bot messages ("en" or "vi").
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    /// Conversation starters offered by dashboards and channels.
+    #[serde(default)]
+    pub suggestions: SuggestionsConfig,
+    /// Features the gateway runs; the platform turns them off per tenant.
+    #[serde(default)]
+    pub features: FeaturesConfig,
 }
 
 fn default_api_key() -> String {
@@ -94,6 +216,9 @@ fn default_model() -> String {
 fn default_temperature() -> f32 {
     0.7
 }
+fn default_locale() -> String {
+    "en".into()
+}
 
 impl Default for BizClawConfig {
     fn default() -> Self {
@@ -115,11 +240,56 @@ impl Default for BizClawConfig {
             channel: ChannelConfig::default(),
             mcp_servers: vec![],
             quality_gate: None,
+            datetime: DateTimeConfig::default(),
+            chat_history: ChatHistoryConfig::default(),
+            context: ContextConfig::default(),
+            generation: GenerationConfig::default(),
+            model_aliases: Default::default(),
+            locale: default_locale(),
+            suggestions: SuggestionsConfig::default(),
+            features: FeaturesConfig::default(),
         }
     }
 }
 
 impl BizClawConfig {
+    /// Parsed locale for user-facing messages.
+    pub fn locale(&self) -> crate::i18n::Locale {
+        crate::i18n::Locale::parse(&self.locale)
+    }
+
+    /// Config for agents answering `channel`: the global one with the
+    /// channel's `[channel.models.<name>]` provider and model, if set. A model
+    /// alias is left for the agent to resolve. When the provider changes, the
+    /// global API key and endpoint are dropped, as they belong to the old one;
+    /// the caller fills in the new provider's (the gateway keeps them per
+    /// provider) or it falls back to the provider's environment variables.
+    pub fn for_channel(&self, channel: &str) -> Self {
+        let mut config = self.clone();
+        let Some(choice) = self.channel.models.get(channel) else {
+            return config;
+        };
+        if let Some(provider) = choice.provider.as_deref().filter(|p| !p.is_empty()) {
+            let current = if config.llm.provider.is_empty() {
+                &config.default_provider
+            } else {
+                &config.llm.provider
+            };
+            if current != provider {
+                config.llm.api_key.clear();
+                config.llm.endpoint.clear();
+                config.api_key.clear();
+            }
+            config.default_provider = provider.to_string();
+            config.llm.provider = provider.to_string();
+        }
+        if let Some(model) = choice.model.as_deref().filter(|m| !m.is_empty()) {
+            config.default_model = model.to_string();
+            config.llm.model = model.to_string();
+        }
+        config
+    }
+
     /// Load config from the default path (~/.bizclaw/config.toml).
     pub fn load() -> Result<Self> {
         let path = Self::default_path();
@@ -143,15 +313,22 @@ impl BizClawConfig {
 
     /// Save config to the default path.
     pub fn save(&self) -> Result<()> {
-        let path = Self::default_path();
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
+        self.save_to(&Self::default_path())
+    }
+
+    /// Save config to `path` with [`write_config_file`].
+    pub fn save_to(&self, path: &Path) -> Result<()> {
         let content = toml::to_string_pretty(self).map_err(|e| {
             crate::error::BizClawError::Config(format!("Failed to serialize config: {e}"))
         })?;
-        std::fs::write(&path, content)?;
-        Ok(())
+        write_config_file(path, content.as_bytes())
+    }
+
+    /// Where [`write_config_file`] keeps the previous version of `path`.
+    pub fn backup_path(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".bak");
+        path.with_file_name(name)
     }
 
     /// Get the default config path.
@@ -170,6 +347,61 @@ impl BizClawConfig {
     }
 }
 
+/// Serializes config writers in this process, so two saves can't interleave
+/// their backup and rename steps.
+static CONFIG_WRITE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Replace a config file atomically. Readers see the old file or the new one,
+/// never a partial write. The previous version is kept at
+/// [`BizClawConfig::backup_path`] if it was valid TOML, so a corrupted file
+/// never overwrites a good backup.
+pub fn write_config_file(path: &Path, contents: &[u8]) -> Result<()> {
+    let _guard = CONFIG_WRITE_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    if let Ok(previous) = std::fs::read_to_string(path)
+        && toml::from_str::<toml::Table>(&previous).is_ok()
+    {
+        write_atomic(&BizClawConfig::backup_path(path), previous.as_bytes())?;
+    }
+    write_atomic(path, contents)
+}
+
+/// Replace `path` with `contents` via a temp file in the same directory and a
+/// rename, so a crash or failed write leaves the previous file intact.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    use std::io::Write;
+    write_atomic_with(path, |file| file.write_all(contents))
+}
+
+fn write_atomic_with(
+    path: &Path,
+    write: impl FnOnce(&mut std::fs::File) -> std::io::Result<()>,
+) -> Result<()> {
+    static NEXT_TMP: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(dir)?;
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("config");
+    let seq = NEXT_TMP.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let tmp = dir.join(format!(".{name}.{}.{seq}.tmp", std::process::id()));
+
+    let result = std::fs::File::create(&tmp).and_then(|mut file| {
+        write(&mut file)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    });
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
 /// Brain (local LLM) configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BrainConfig {
@@ -193,8 +425,34 @@ pub struct BrainConfig {
     pub top_p: f32,
     #[serde(default)]
     pub json_mode: bool,
+    /// Max concurrent generations decoded round-robin (others queue).
+    #[serde(default = "default_max_in_flight")]
+    pub max_in_flight: u32,
     #[serde(default)]
     pub fallback: Option<BrainFallback>,
+    /// Extra downloadable models, merged over the built-in catalog.
+    #[serde(default)]
+    pub catalog: Vec<ModelCatalogEntry>,
+    /// Optional URL of a JSON model catalog fetched by `brain list`/`download`.
+    #[serde(default)]
+    pub catalog_url: Option<String>,
+    /// Sampling seed for reproducible output (`None` = random each run).
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Most models kept loaded at once across all agents. Loading another
+    /// unloads the least recently used one.
+    #[serde(default = "default_max_loaded_models")]
+    pub max_loaded_models: usize,
+    /// Cap on the combined size of loaded model files, in MB. 0 = no cap.
+    #[serde(default)]
+    pub max_loaded_mb: u64,
+    /// Unload a model after this many seconds without requests. 0 = never.
+    #[serde(default)]
+    pub idle_unload_secs: u64,
+    /// Run a one-token generation after loading a model, so the first
+    /// request is not slowed by cold caches.
+    #[serde(default)]
+    pub warm_up: bool,
 }
 
 fn bool_true() -> bool {
@@ -218,6 +476,12 @@ fn default_cache_dir() -> String {
 fn default_top_p() -> f32 {
     0.9
 }
+fn default_max_in_flight() -> u32 {
+    4
+}
+fn default_max_loaded_models() -> usize {
+    2
+}
 
 impl Default for BrainConfig {
     fn default() -> Self {
@@ -232,7 +496,15 @@ impl Default for BrainConfig {
             temperature: default_temperature(),
             top_p: default_top_p(),
             json_mode: false,
+            max_in_flight: default_max_in_flight(),
             fallback: None,
+            catalog: vec![],
+            catalog_url: None,
+            seed: None,
+            max_loaded_models: default_max_loaded_models(),
+            max_loaded_mb: 0,
+            idle_unload_secs: 0,
+            warm_up: false,
         }
     }
 }
@@ -243,6 +515,28 @@ pub struct BrainFallback {
     pub model: String,
 }
 
+/// A downloadable GGUF model for the local brain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelCatalogEntry {
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub url: String,
+    /// Expected SHA-256 of the file (hex), verified after download. Unset =
+    /// the checksum Hugging Face publishes for the file, if any.
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub size_mb: u64,
+    /// Recommended system RAM to run the model.
+    #[serde(default)]
+    pub min_ram_mb: u64,
+    #[serde(default)]
+    pub quant: String,
+    #[serde(default)]
+    pub description: String,
+}
+
 /// Memory configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryConfig {
@@ -256,6 +550,13 @@ pub struct MemoryConfig {
     pub vector_weight: f32,
     #[serde(default = "default_keyword_weight")]
     pub keyword_weight: f32,
+    /// Days a cached embedding stays valid. 0 = no expiry.
+    #[serde(default = "default_embedding_cache_ttl_days")]
+    pub embedding_cache_ttl_days: u32,
+    /// Most embeddings kept in the cache; the oldest are dropped first.
+    /// 0 = don't cache embeddings.
+    #[serde(default = "default_embedding_cache_max_entries")]
+    pub embedding_cache_max_entries: usize,
 }
 
 fn default_memory_backend() -> String {
@@ -270,6 +571,12 @@ fn default_vector_weight() -> f32 {
 fn default_keyword_weight() -> f32 {
     0.3
 }
+fn default_embedding_cache_ttl_days() -> u32 {
+    30
+}
+fn default_embedding_cache_max_entries() -> usize {
+    10_000
+}
 
 impl Default for MemoryConfig {
     fn default() -> Self {
@@ -279,6 +586,8 @@ impl Default for MemoryConfig {
             embedding_provider: default_embedding_provider(),
             vector_weight: default_vector_weight(),
             keyword_weight: default_keyword_weight(),
+            embedding_cache_ttl_days: default_embedding_cache_ttl_days(),
+            embedding_cache_max_entries: default_embedding_cache_max_entries(),
         }
     }
 }
@@ -292,6 +601,9 @@ pub struct GatewayConfig {
     pub host: String,
     #[serde(default = "bool_true")]
     pub require_pairing: bool,
+    /// Shown to users while maintenance mode is on. Empty = built-in message.
+    #[serde(default)]
+    pub maintenance_message: String,
 }
 
 fn default_port() -> u16 {
@@ -307,6 +619,7 @@ impl Default for GatewayConfig {
             port: default_port(),
             host: default_host(),
             require_pairing: true,
+            maintenance_message: String::new(),
         }
     }
 }
@@ -322,6 +635,15 @@ pub struct AutonomyConfig {
     pub allowed_commands: Vec<String>,
     #[serde(default = "default_forbidden_paths")]
     pub forbidden_paths: Vec<String>,
+    /// Agent workspace directory. Empty = `~/.bizclaw/workspace`.
+    #[serde(default)]
+    pub workspace_dir: String,
+    /// Only these tools are available. Unset = all tools.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    /// These tools are never available.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub denied_tools: Vec<String>,
 }
 
 fn default_autonomy_level() -> String {
@@ -349,10 +671,83 @@ impl Default for AutonomyConfig {
             workspace_only: true,
             allowed_commands: default_allowed_commands(),
             forbidden_paths: default_forbidden_paths(),
+            workspace_dir: String::new(),
+            allowed_tools: None,
+            denied_tools: Vec::new(),
         }
     }
 }
 
+/// Autonomy levels from most to least restricted.
+pub const AUTONOMY_LEVELS: [&str; 3] = ["readonly", "supervised", "full"];
+
+/// Tools that run code, write files or change config. Unavailable at the
+/// "readonly" level.
+pub const MUTATING_TOOLS: &[&str] = &[
+    "shell",
+    "file",
+    "edit_file",
+    "execute_code",
+    "config_manager",
+];
+
+impl AutonomyConfig {
+    /// Whether the agent may use tool `name`.
+    pub fn allows_tool(&self, name: &str) -> bool {
+        if self.denied_tools.iter().any(|t| t == name) {
+            return false;
+        }
+        if let Some(allowed) = &self.allowed_tools
+            && !allowed.iter().any(|t| t == name)
+        {
+            return false;
+        }
+        !(self.level == "readonly" && MUTATING_TOOLS.contains(&name))
+    }
+
+    /// This config tightened by `policy`: the stricter of the two levels,
+    /// only tools both allow, and every tool either denies.
+    pub fn restricted_by(&self, policy: &ChannelPolicy) -> Self {
+        let mut restricted = self.clone();
+        if let Some(level) = &policy.autonomy_level
+            && autonomy_rank(level) < autonomy_rank(&self.level)
+        {
+            restricted.level = level.clone();
+        }
+        restricted.allowed_tools = match (&self.allowed_tools, &policy.allowed_tools) {
+            (Some(global), Some(channel)) => Some(
+                global
+                    .iter()
+                    .filter(|t| channel.contains(t))
+                    .cloned()
+                    .collect(),
+            ),
+            (global, channel) => global.clone().or_else(|| channel.clone()),
+        };
+        for tool in &policy.denied_tools {
+            if !restricted.denied_tools.contains(tool) {
+                restricted.denied_tools.push(tool.clone());
+            }
+        }
+        restricted
+    }
+
+    /// Resolved workspace directory.
+    pub fn workspace_path(&self) -> PathBuf {
+        if self.workspace_dir.is_empty() {
+            BizClawConfig::home_dir().join("workspace")
+        } else {
+            PathBuf::from(&self.workspace_dir)
+        }
+    }
+}
+
+/// Position of `level` in [`AUTONOMY_LEVELS`]; unknown levels count as
+/// "supervised", the default.
+fn autonomy_rank(level: &str) -> usize {
+    AUTONOMY_LEVELS.iter().position(|l| *l == level).unwrap_or(1)
+}
+
 /// Runtime configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuntimeConfig {
@@ -419,6 +814,246 @@ pub struct ChannelConfig {
     pub whatsapp: Option<WhatsAppChannelConfig>,
     #[serde(default)]
     pub webhook: Option<WebhookChannelConfig>,
+    /// Tool and autonomy restrictions per channel, keyed by channel name
+    /// (e.g. `[channel.policies.telegram]`).
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub policies: std::collections::HashMap<String, ChannelPolicy>,
+    /// Provider and model per channel, keyed by channel name
+    /// (e.g. `[channel.models.telegram]`).
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub models: std::collections::HashMap<String, ChannelModel>,
+    /// Typing indicator kept up while a reply is generated (`[channel.typing]`).
+    #[serde(default)]
+    pub typing: TypingConfig,
+}
+
+/// Shortest allowed gap between typing indicators, so a low setting can't
+/// flood the channel's API.
+pub const MIN_TYPING_INTERVAL_SECS: u64 = 2;
+
+/// Typing heartbeat for slow replies: the indicator is re-sent every
+/// `interval_secs` until the reply goes out, for at most `max_secs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypingConfig {
+    /// Seconds between typing indicators. `0` disables them.
+    #[serde(default = "default_typing_interval_secs")]
+    pub interval_secs: u64,
+    /// Stop re-sending after this many seconds, even if the reply is not ready.
+    #[serde(default = "default_typing_max_secs")]
+    pub max_secs: u64,
+}
+
+fn default_typing_interval_secs() -> u64 {
+    4
+}
+
+fn default_typing_max_secs() -> u64 {
+    120
+}
+
+impl Default for TypingConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_typing_interval_secs(),
+            max_secs: default_typing_max_secs(),
+        }
+    }
+}
+
+impl TypingConfig {
+    /// Gap between typing indicators, at least [`MIN_TYPING_INTERVAL_SECS`].
+    /// `None` when the heartbeat is disabled.
+    pub fn interval(&self) -> Option<Duration> {
+        (self.interval_secs > 0)
+            .then(|| Duration::from_secs(self.interval_secs.max(MIN_TYPING_INTERVAL_SECS)))
+    }
+
+    /// How long to keep re-sending the indicator.
+    pub fn max_duration(&self) -> Duration {
+        Duration::from_secs(self.max_secs)
+    }
+}
+
+/// Restrictions for agents answering one channel. They only tighten the
+/// global `[autonomy]` settings, never loosen them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChannelPolicy {
+    /// Autonomy level for this channel; ignored if the global level is stricter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autonomy_level: Option<String>,
+    /// Only these tools are available. Unset = whatever the global config allows.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    /// These tools are never available on this channel.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub denied_tools: Vec<String>,
+}
+
+/// Provider and model for agents answering one channel, so a busy channel
+/// can run on a cheap model and another on a capable one.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChannelModel {
+    /// Provider for this channel. Unset = the global provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Model or `model_aliases` name for this channel. Unset = the global model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl ChannelConfig {
+    /// Welcome message template configured for `channel`, if any.
+    pub fn welcome_message(&self, channel: &str) -> Option<&str> {
+        let message = match channel {
+            "zalo" => self.zalo.as_ref()?.welcome_message.as_deref(),
+            "telegram" => self.telegram.as_ref()?.welcome_message.as_deref(),
+            "discord" => self.discord.as_ref()?.welcome_message.as_deref(),
+            "email" => self.email.as_ref()?.welcome_message.as_deref(),
+            "whatsapp" => self.whatsapp.as_ref()?.welcome_message.as_deref(),
+            "webhook" => self.webhook.as_ref()?.welcome_message.as_deref(),
+            _ => None,
+        };
+        message.filter(|m| !m.trim().is_empty())
+    }
+
+    /// Autonomy settings for agents answering `channel`: the global
+    /// `autonomy` restricted by the channel's policy, if it has one.
+    pub fn autonomy_for(&self, channel: &str, autonomy: &AutonomyConfig) -> AutonomyConfig {
+        match self.policies.get(channel) {
+            Some(policy) => autonomy.restricted_by(policy),
+            None => autonomy.clone(),
+        }
+    }
+
+    /// Channel types that can be configured one at a time.
+    pub const KINDS: [&'static str; 6] = [
+        "telegram", "zalo", "discord", "email", "whatsapp", "webhook",
+    ];
+
+    /// Fields of `kind`'s config that hold credentials.
+    pub fn secret_fields(kind: &str) -> &'static [&'static str] {
+        match kind {
+            "telegram" | "discord" => &["bot_token"],
+            "zalo" => &["oa_access_token"],
+            "email" => &["password"],
+            "whatsapp" => &["access_token", "webhook_verify_token", "app_secret"],
+            "webhook" => &["secret"],
+            _ => &[],
+        }
+    }
+
+    /// `kind`'s config as JSON, or `None` if that channel is not configured.
+    pub fn get(&self, kind: &str) -> Result<Option<serde_json::Value>> {
+        let value = match kind {
+            "telegram" => self.telegram.as_ref().map(serde_json::to_value),
+            "zalo" => self.zalo.as_ref().map(serde_json::to_value),
+            "discord" => self.discord.as_ref().map(serde_json::to_value),
+            "email" => self.email.as_ref().map(serde_json::to_value),
+            "whatsapp" => self.whatsapp.as_ref().map(serde_json::to_value),
+            "webhook" => self.webhook.as_ref().map(serde_json::to_value),
+            _ => return Err(unknown_channel(kind)),
+        };
+        Ok(value.transpose()?)
+    }
+
+    /// Validate `value` as `kind`'s config and replace the current one.
+    /// Nothing changes if validation fails.
+    pub fn set(&mut self, kind: &str, value: serde_json::Value) -> Result<()> {
+        match kind {
+            "telegram" => {
+                let cfg: TelegramChannelConfig = parse_channel(kind, value)?;
+                if cfg.enabled && cfg.bot_token.is_empty() {
+                    return Err(invalid_channel(kind, "bot_token is required"));
+                }
+                if !cfg.bot_token.is_empty() && !cfg.bot_token.contains(':') {
+                    return Err(invalid_channel(
+                        kind,
+                        "bot_token must look like '123456:ABC...'",
+                    ));
+                }
+                self.telegram = Some(cfg);
+            }
+            "zalo" => {
+                let cfg: ZaloChannelConfig = parse_channel(kind, value)?;
+                if !matches!(cfg.mode.as_str(), "personal" | "official") {
+                    return Err(invalid_channel(
+                        kind,
+                        "mode must be 'personal' or 'official'",
+                    ));
+                }
+                self.zalo = Some(cfg);
+            }
+            "discord" => {
+                let cfg: DiscordChannelConfig = parse_channel(kind, value)?;
+                if cfg.enabled && cfg.bot_token.is_empty() {
+                    return Err(invalid_channel(kind, "bot_token is required"));
+                }
+                self.discord = Some(cfg);
+            }
+            "email" => {
+                let cfg: EmailChannelConfig = parse_channel(kind, value)?;
+                if cfg.enabled && cfg.smtp_host.is_empty() {
+                    return Err(invalid_channel(kind, "smtp_host is required"));
+                }
+                if !cfg.email.is_empty() && !cfg.email.contains('@') {
+                    return Err(invalid_channel(kind, "email is not a valid address"));
+                }
+                if cfg.smtp_port == 0 || cfg.imap_port == 0 {
+                    return Err(invalid_channel(kind, "ports must be non-zero"));
+                }
+                self.email = Some(cfg);
+            }
+            "whatsapp" => {
+                let cfg: WhatsAppChannelConfig = parse_channel(kind, value)?;
+                if cfg.enabled && (cfg.phone_number_id.is_empty() || cfg.access_token.is_empty()) {
+                    return Err(invalid_channel(
+                        kind,
+                        "phone_number_id and access_token are required",
+                    ));
+                }
+                self.whatsapp = Some(cfg);
+            }
+            "webhook" => {
+                let cfg: WebhookChannelConfig = parse_channel(kind, value)?;
+                let url = cfg.outbound_url.as_str();
+                if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(invalid_channel(kind, "outbound_url must be an http(s) URL"));
+                }
+                self.webhook = Some(cfg);
+            }
+            _ => return Err(unknown_channel(kind)),
+        }
+        Ok(())
+    }
+
+    /// Remove `kind`'s config. Returns whether it was configured.
+    pub fn remove(&mut self, kind: &str) -> Result<bool> {
+        let removed = match kind {
+            "telegram" => self.telegram.take().is_some(),
+            "zalo" => self.zalo.take().is_some(),
+            "discord" => self.discord.take().is_some(),
+            "email" => self.email.take().is_some(),
+            "whatsapp" => self.whatsapp.take().is_some(),
+            "webhook" => self.webhook.take().is_some(),
+            _ => return Err(unknown_channel(kind)),
+        };
+        Ok(removed)
+    }
+}
+
+fn parse_channel<T: serde::de::DeserializeOwned>(
+    kind: &str,
+    value: serde_json::Value,
+) -> Result<T> {
+    serde_json::from_value(value).map_err(|e| invalid_channel(kind, &e.to_string()))
+}
+
+fn invalid_channel(kind: &str, reason: &str) -> crate::error::BizClawError {
+    crate::error::BizClawError::Config(format!("Invalid {kind} config: {reason}"))
+}
+
+fn unknown_channel(kind: &str) -> crate::error::BizClawError {
+    crate::error::BizClawError::Config(format!("Unknown channel: {kind}"))
 }
 
 /// Zalo channel configuration.
@@ -440,6 +1075,10 @@ pub struct ZaloChannelConfig {
     /// Zalo user_id to receive notifications (admin recipient).
     #[serde(default)]
     pub notify_user_id: String,
+    /// Sent once to each new sender before the first reply. Supports
+    /// `{bot_name}`, `{sender_name}` and `{channel}` placeholders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub welcome_message: Option<String>,
 }
 
 fn default_zalo_mode() -> String {
@@ -456,6 +1095,7 @@ impl Default for ZaloChannelConfig {
             allowlist: ZaloAllowlistConfig::default(),
             oa_access_token: String::new(),
             notify_user_id: String::new(),
+            welcome_message: None,
         }
     }
 }
@@ -555,6 +1195,28 @@ pub struct TelegramChannelConfig {
     pub bot_token: String,
     #[serde(default)]
     pub allowed_chat_ids: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub welcome_message: Option<String>,
+    /// Formatting for outgoing messages: "markdown" (default), "markdownv2",
+    /// "html" or "plain".
+    #[serde(default)]
+    pub parse_mode: TelegramParseMode,
+    /// Per-chat overrides, keyed by chat id (e.g. `"-1001234" = "plain"`).
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub chat_parse_modes: std::collections::HashMap<String, TelegramParseMode>,
+}
+
+/// How Telegram should render outgoing text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TelegramParseMode {
+    /// Legacy Markdown (`*bold*`, `_italic_`, `` `code` ``).
+    #[default]
+    Markdown,
+    MarkdownV2,
+    Html,
+    /// No formatting; text is sent as-is.
+    Plain,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -563,6 +1225,8 @@ pub struct DiscordChannelConfig {
     pub bot_token: String,
     #[serde(default)]
     pub allowed_channel_ids: Vec<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub welcome_message: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -581,6 +1245,8 @@ pub struct EmailChannelConfig {
     pub email: String,
     #[serde(default)]
     pub password: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub welcome_message: Option<String>,
 }
 
 fn default_imap_port_cfg() -> u16 {
@@ -600,8 +1266,15 @@ pub struct WhatsAppChannelConfig {
     pub phone_number_id: String,
     #[serde(default)]
     pub webhook_verify_token: String,
+    /// Meta app secret. Webhook POSTs must carry a matching
+    /// `X-Hub-Signature-256` HMAC-SHA256 of the body; without it set,
+    /// every webhook POST is rejected.
+    #[serde(default)]
+    pub app_secret: String,
     #[serde(default)]
     pub business_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub welcome_message: Option<String>,
 }
 
 /// Generic Webhook channel configuration.
@@ -617,6 +1290,8 @@ pub struct WebhookChannelConfig {
     /// URL to POST outbound replies/messages to.
     #[serde(default)]
     pub outbound_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub welcome_message: Option<String>,
 }
 
 /// MCP server entry — one per [[mcp_servers]] in config.toml.
@@ -655,6 +1330,298 @@ pub struct QualityGateConfig {
     pub max_revisions: Option<u32>,
 }
 
+/// Optional gateway features, all on by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeaturesConfig {
+    /// Installed skills are offered to the agent and can be installed or
+    /// created from the dashboard.
+    #[serde(default = "bool_true")]
+    pub skills: bool,
+    /// Scheduled tasks run in the background.
+    #[serde(default = "bool_true")]
+    pub heartbeat: bool,
+    /// Config, channel and provider settings can be read and changed from
+    /// the dashboard.
+    #[serde(default = "bool_true")]
+    pub settings: bool,
+}
+
+impl Default for FeaturesConfig {
+    fn default() -> Self {
+        Self {
+            skills: true,
+            heartbeat: true,
+            settings: true,
+        }
+    }
+}
+
+/// Date/time context configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DateTimeConfig {
+    /// Prepend the current date/time to the context on every turn.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Default timezone: "UTC", an offset like "+07:00", or a zone name like
+    /// "Asia/Ho_Chi_Minh". A timezone stored in the user's memory wins.
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// chrono `strftime` format for the date/time line.
+    #[serde(default = "default_datetime_format")]
+    pub format: String,
+}
+
+fn default_timezone() -> String {
+    "UTC".into()
+}
+/// Date/time format used by default, and when the configured one is not a
+/// valid `strftime` string.
+pub const DEFAULT_DATETIME_FORMAT: &str = "%A, %Y-%m-%d %H:%M";
+
+fn default_datetime_format() -> String {
+    DEFAULT_DATETIME_FORMAT.into()
+}
+
+impl Default for DateTimeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timezone: default_timezone(),
+            format: default_datetime_format(),
+        }
+    }
+}
+
+/// Interactive CLI conversation history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatHistoryConfig {
+    /// Save each `bizclaw chat` session so it can be resumed.
+    #[serde(default = "bool_true")]
+    pub enabled: bool,
+    /// Directory of `<session>.jsonl` files. Empty = `~/.bizclaw/history`.
+    #[serde(default)]
+    pub dir: String,
+    /// Most recent messages kept per saved session.
+    #[serde(default = "default_history_max_messages")]
+    pub max_messages: usize,
+}
+
+fn default_history_max_messages() -> usize {
+    200
+}
+
+impl ChatHistoryConfig {
+    /// Resolved history directory.
+    pub fn dir(&self) -> PathBuf {
+        if self.dir.trim().is_empty() {
+            BizClawConfig::home_dir().join("history")
+        } else {
+            PathBuf::from(shellexpand::tilde(self.dir.trim()).as_ref())
+        }
+    }
+}
+
+impl Default for ChatHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: String::new(),
+            max_messages: default_history_max_messages(),
+        }
+    }
+}
+
+/// Conversation context window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextConfig {
+    /// Most recent user turns kept in the conversation (a turn is a user
+    /// message plus the replies and tool calls that follow it). Older turns
+    /// are dropped; the system prompt and pinned memories always stay.
+    /// 0 = unlimited.
+    #[serde(default = "default_max_history_turns")]
+    pub max_history_turns: usize,
+    /// Tool results larger than this many (estimated) tokens are condensed
+    /// before they enter the conversation, so the next model call still
+    /// fits the context window. 0 = never condense.
+    #[serde(default = "default_tool_result_max_tokens")]
+    pub tool_result_max_tokens: usize,
+    /// Condense oversized tool results by asking the model for a summary
+    /// instead of cutting them off. Falls back to truncation on failure.
+    #[serde(default)]
+    pub summarize_tool_results: bool,
+    /// Save the full text of condensed tool results to memory, where the
+    /// `memory_search` tool can find the details later.
+    #[serde(default)]
+    pub stash_tool_results: bool,
+    /// Longest user message accepted, in characters. 0 = unlimited.
+    #[serde(default = "default_max_input_chars")]
+    pub max_input_chars: usize,
+    /// Longest user message accepted, in (estimated) tokens. 0 = unlimited.
+    #[serde(default)]
+    pub max_input_tokens: usize,
+    /// What to do with a message over either limit.
+    #[serde(default)]
+    pub oversized_input: OversizedInput,
+}
+
+/// Handling of user messages over `max_input_chars`/`max_input_tokens`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizedInput {
+    /// Refuse the message and tell the user it is too long.
+    #[default]
+    Reject,
+    /// Keep the start of the message and tell the user the rest was cut.
+    Truncate,
+}
+
+fn default_max_history_turns() -> usize {
+    20
+}
+
+fn default_max_input_chars() -> usize {
+    32_000
+}
+
+fn default_tool_result_max_tokens() -> usize {
+    1500
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            max_history_turns: default_max_history_turns(),
+            tool_result_max_tokens: default_tool_result_max_tokens(),
+            summarize_tool_results: false,
+            stash_tool_results: false,
+            max_input_chars: default_max_input_chars(),
+            max_input_tokens: 0,
+            oversized_input: OversizedInput::Reject,
+        }
+    }
+}
+
+/// Per-request generation options forwarded to the provider.
+///
+/// ```toml
+/// [generation]
+/// max_tokens = 1024
+/// stop = ["\nUser:"]
+///
+/// [generation.channels.zalo]
+/// max_tokens = 300
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationConfig {
+    /// Max tokens per response. 0 = use `brain.max_tokens`.
+    #[serde(default)]
+    pub max_tokens: u32,
+    /// Sequences that end the response early.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    /// Per-channel overrides keyed by channel name (`telegram`, `zalo`, ...).
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub channels: std::collections::HashMap<String, GenerationOverride>,
+}
+
+/// Channel-specific generation options. Unset fields use the global ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+}
+
+impl GenerationConfig {
+    /// Max tokens for a request arriving via `channel`; 0 = not configured.
+    pub fn max_tokens_for(&self, channel: Option<&str>) -> u32 {
+        self.channel(channel)
+            .and_then(|o| o.max_tokens)
+            .unwrap_or(self.max_tokens)
+    }
+
+    /// Stop sequences for a request arriving via `channel`.
+    pub fn stop_for(&self, channel: Option<&str>) -> &[String] {
+        self.channel(channel)
+            .and_then(|o| o.stop.as_deref())
+            .unwrap_or(&self.stop)
+    }
+
+    fn channel(&self, channel: Option<&str>) -> Option<&GenerationOverride> {
+        self.channels.get(channel?)
+    }
+}
+
+/// Conversation starters returned by `GET /api/v1/suggestions`.
+///
+/// ```toml
+/// [suggestions]
+/// prompts = ["Giờ mở cửa?", "Bảng giá dịch vụ"]
+/// dynamic = true   # also suggest the installed skills
+///
+/// [suggestions.agents]
+/// sales = ["Có khuyến mãi gì không?"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestionsConfig {
+    /// Starter prompts for every agent.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompts: Vec<String>,
+    /// Per-agent prompts keyed by agent name; they replace `prompts`.
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub agents: std::collections::HashMap<String, Vec<String>>,
+    /// Add a prompt for each installed skill after the configured ones.
+    #[serde(default)]
+    pub dynamic: bool,
+    /// Most suggestions returned. 0 = unlimited.
+    #[serde(default = "default_suggestions_limit")]
+    pub limit: usize,
+}
+
+fn default_suggestions_limit() -> usize {
+    6
+}
+
+impl Default for SuggestionsConfig {
+    fn default() -> Self {
+        Self {
+            prompts: vec![],
+            agents: Default::default(),
+            dynamic: false,
+            limit: default_suggestions_limit(),
+        }
+    }
+}
+
+impl SuggestionsConfig {
+    /// Starter prompts for `agent` (or the default agent), followed in
+    /// dynamic mode by one per name in `skills`.
+    pub fn for_agent(
+        &self,
+        agent: Option<&str>,
+        skills: &[String],
+        locale: crate::i18n::Locale,
+    ) -> Vec<String> {
+        let mut prompts = agent
+            .and_then(|name| self.agents.get(name))
+            .unwrap_or(&self.prompts)
+            .clone();
+        if self.dynamic {
+            for skill in skills {
+                let prompt = crate::i18n::tr(locale, "suggestions.skill", &[("skill", skill)]);
+                if !prompts.contains(&prompt) {
+                    prompts.push(prompt);
+                }
+            }
+        }
+        if self.limit > 0 {
+            prompts.truncate(self.limit);
+        }
+        prompts
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -668,6 +1635,62 @@ mod tests {
         assert_eq!(config.identity.name, "BizClaw");
     }
 
+    #[test]
+    fn test_configured_suggestions() {
+        let config: BizClawConfig = toml::from_str(
+            r#"
+            [suggestions]
+            prompts = ["Giờ mở cửa?", "Bảng giá dịch vụ"]
+
+            [suggestions.agents]
+            sales = ["Có khuyến mãi gì không?"]
+        "#,
+        )
+        .unwrap();
+        let suggestions = &config.suggestions;
+        let skills = vec!["Rust Expert".to_string()];
+        assert_eq!(
+            suggestions.for_agent(None, &skills, crate::i18n::Locale::Vi),
+            ["Giờ mở cửa?", "Bảng giá dịch vụ"]
+        );
+        assert_eq!(
+            suggestions.for_agent(Some("sales"), &skills, crate::i18n::Locale::Vi),
+            ["Có khuyến mãi gì không?"]
+        );
+        assert_eq!(
+            suggestions.for_agent(Some("support"), &[], crate::i18n::Locale::Vi),
+            ["Giờ mở cửa?", "Bảng giá dịch vụ"]
+        );
+    }
+
+    #[test]
+    fn test_dynamic_suggestions_use_skill_names() {
+        let mut suggestions = SuggestionsConfig {
+            prompts: vec!["Bảng giá dịch vụ".into()],
+            dynamic: true,
+            limit: 3,
+            ..Default::default()
+        };
+        let skills = vec![
+            "Rust Expert".to_string(),
+            "SQL Expert".to_string(),
+            "Content Writer".to_string(),
+        ];
+        assert_eq!(
+            suggestions.for_agent(None, &skills, crate::i18n::Locale::En),
+            [
+                "Bảng giá dịch vụ",
+                "What can Rust Expert help me with?",
+                "What can SQL Expert help me with?",
+            ]
+        );
+
+        suggestions.limit = 0;
+        let vi = suggestions.for_agent(None, &skills, crate::i18n::Locale::Vi);
+        assert_eq!(vi.len(), 4);
+        assert_eq!(vi[3], "Content Writer có thể giúp gì cho tôi?");
+    }
+
     #[test]
     fn test_config_from_toml() {
         let toml_str = r#"
@@ -679,12 +1702,16 @@ mod tests {
             name = "TestBot"
             persona = "A test assistant"
             system_prompt = "You are a test bot."
+
+            [model_aliases]
+            smart = "anthropic:claude-sonnet-4"
         "#;
 
         let config: BizClawConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.default_provider, "ollama");
         assert_eq!(config.default_model, "llama3.2");
         assert_eq!(config.identity.name, "TestBot");
+        assert_eq!(config.model_aliases["smart"], "anthropic:claude-sonnet-4");
     }
 
     #[test]
@@ -695,9 +1722,375 @@ mod tests {
         assert_eq!(config.gateway.port, 3000);
     }
 
+    #[test]
+    fn test_channel_welcome_message() {
+        let toml_str = r#"
+            [channel.telegram]
+            enabled = true
+            bot_token = "123:abc"
+            welcome_message = "Hi {sender_name}, I'm {bot_name}!"
+
+            [channel.discord]
+            enabled = true
+            bot_token = "xyz"
+            welcome_message = "  "
+        "#;
+        let config: BizClawConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.channel.welcome_message("telegram"),
+            Some("Hi {sender_name}, I'm {bot_name}!")
+        );
+        assert!(config.channel.welcome_message("discord").is_none());
+        assert!(config.channel.welcome_message("whatsapp").is_none());
+    }
+
+    #[test]
+    fn test_telegram_parse_modes() {
+        let toml_str = r#"
+            [channel.telegram]
+            enabled = true
+            bot_token = "123:abc"
+            parse_mode = "html"
+
+            [channel.telegram.chat_parse_modes]
+            "-1001" = "plain"
+            "42" = "markdownv2"
+        "#;
+        let config: BizClawConfig = toml::from_str(toml_str).unwrap();
+        let tg = config.channel.telegram.unwrap();
+        assert_eq!(tg.parse_mode, TelegramParseMode::Html);
+        assert_eq!(tg.chat_parse_modes["-1001"], TelegramParseMode::Plain);
+        assert_eq!(tg.chat_parse_modes["42"], TelegramParseMode::MarkdownV2);
+
+        let legacy: BizClawConfig =
+            toml::from_str("[channel.telegram]\nenabled = true\nbot_token = \"x\"\n").unwrap();
+        assert_eq!(
+            legacy.channel.telegram.unwrap().parse_mode,
+            TelegramParseMode::Markdown
+        );
+    }
+
+    #[test]
+    fn test_generation_channel_overrides() {
+        let config: BizClawConfig = toml::from_str(
+            r#"
+            [generation]
+            max_tokens = 1024
+            stop = ["END"]
+
+            [generation.channels.zalo]
+            max_tokens = 300
+
+            [generation.channels.email]
+            stop = []
+            "#,
+        )
+        .unwrap();
+        let generation = &config.generation;
+        assert_eq!(generation.max_tokens_for(None), 1024);
+        assert_eq!(generation.max_tokens_for(Some("zalo")), 300);
+        assert_eq!(generation.max_tokens_for(Some("email")), 1024);
+        assert_eq!(generation.stop_for(Some("zalo")), ["END"]);
+        assert!(generation.stop_for(Some("email")).is_empty());
+
+        let defaults = BizClawConfig::default().generation;
+        assert_eq!(defaults.max_tokens_for(Some("telegram")), 0);
+        assert!(defaults.stop_for(None).is_empty());
+    }
+
     #[test]
     fn test_home_dir() {
         let home = BizClawConfig::home_dir();
         assert!(home.to_string_lossy().contains("bizclaw"));
     }
+
+    fn temp_config_path(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("bizclaw-config-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("config.toml")
+    }
+
+    #[test]
+    fn test_channel_model_overrides() {
+        let config: BizClawConfig = toml::from_str(
+            r#"
+            default_provider = "openai"
+            default_model = "gpt-4o"
+            api_key = "sk-openai"
+
+            [channel.models.telegram]
+            provider = "groq"
+            model = "llama-3.1-8b-instant"
+
+            [channel.models.email]
+            model = "smart"
+            "#,
+        )
+        .unwrap();
+
+        let telegram = config.for_channel("telegram");
+        assert_eq!(telegram.llm.provider, "groq");
+        assert_eq!(telegram.default_model, "llama-3.1-8b-instant");
+        // The OpenAI key must not be sent to Groq.
+        assert!(telegram.api_key.is_empty());
+
+        let email = config.for_channel("email");
+        assert_eq!(email.default_provider, "openai");
+        assert_eq!(email.default_model, "smart");
+        assert_eq!(email.api_key, "sk-openai");
+
+        let discord = config.for_channel("discord");
+        assert_eq!(discord.default_model, "gpt-4o");
+        assert_eq!(discord.api_key, "sk-openai");
+    }
+
+    #[test]
+    fn test_channel_policy_only_tightens_autonomy() {
+        let toml_str = r#"
+            [autonomy]
+            level = "full"
+            denied_tools = ["browser"]
+
+            [channel.policies.telegram]
+            autonomy_level = "readonly"
+            allowed_tools = ["web_search", "shell", "browser"]
+
+            [channel.policies.discord]
+            autonomy_level = "full"
+            denied_tools = ["execute_code"]
+        "#;
+        let mut config: BizClawConfig = toml::from_str(toml_str).unwrap();
+
+        let telegram = config.channel.autonomy_for("telegram", &config.autonomy);
+        assert_eq!(telegram.level, "readonly");
+        assert!(telegram.allows_tool("web_search"));
+        assert!(!telegram.allows_tool("shell"));
+        assert!(!telegram.allows_tool("browser"));
+        assert!(!telegram.allows_tool("memory_search"));
+
+        let cli = config.channel.autonomy_for("cli", &config.autonomy);
+        assert!(cli.allows_tool("shell"));
+        assert!(!cli.allows_tool("browser"));
+
+        // A channel cannot raise the level above the global one.
+        config.autonomy.level = "supervised".into();
+        let discord = config.channel.autonomy_for("discord", &config.autonomy);
+        assert_eq!(discord.level, "supervised");
+        assert!(!discord.allows_tool("execute_code"));
+        assert!(discord.allows_tool("shell"));
+    }
+
+    #[test]
+    fn test_typing_interval_is_bounded() {
+        let config: BizClawConfig = toml::from_str("").unwrap();
+        assert_eq!(
+            config.channel.typing.interval(),
+            Some(Duration::from_secs(4))
+        );
+
+        let config: BizClawConfig =
+            toml::from_str("[channel.typing]\ninterval_secs = 1\nmax_secs = 30").unwrap();
+        assert_eq!(
+            config.channel.typing.interval(),
+            Some(Duration::from_secs(MIN_TYPING_INTERVAL_SECS))
+        );
+        assert_eq!(
+            config.channel.typing.max_duration(),
+            Duration::from_secs(30)
+        );
+
+        let config: BizClawConfig = toml::from_str("[channel.typing]\ninterval_secs = 0").unwrap();
+        assert_eq!(config.channel.typing.interval(), None);
+    }
+
+    #[test]
+    fn test_channel_round_trip_each_kind() {
+        let samples = [
+            (
+                "telegram",
+                serde_json::json!({"enabled": true, "bot_token": "123:abc", "allowed_chat_ids": [42]}),
+            ),
+            (
+                "zalo",
+                serde_json::json!({"enabled": true, "mode": "official", "notify_user_id": "u1"}),
+            ),
+            (
+                "discord",
+                serde_json::json!({"enabled": true, "bot_token": "tok", "allowed_channel_ids": [7]}),
+            ),
+            (
+                "email",
+                serde_json::json!({"enabled": true, "smtp_host": "smtp.x.io", "email": "a@x.io"}),
+            ),
+            (
+                "whatsapp",
+                serde_json::json!({"enabled": true, "phone_number_id": "1", "access_token": "t"}),
+            ),
+            (
+                "webhook",
+                serde_json::json!({"enabled": true, "secret": "s", "outbound_url": "https://x.io"}),
+            ),
+        ];
+        let path = temp_config_path("round-trip");
+        for (kind, value) in samples {
+            let mut config = BizClawConfig::default();
+            config.channel.set(kind, value.clone()).unwrap();
+            config.save_to(&path).unwrap();
+
+            let loaded = BizClawConfig::load_from(&path).unwrap();
+            let saved = loaded.channel.get(kind).unwrap().expect(kind);
+            for (field, expected) in value.as_object().unwrap() {
+                assert_eq!(&saved[field], expected, "{kind}.{field}");
+            }
+
+            let mut loaded = loaded;
+            assert!(loaded.channel.remove(kind).unwrap());
+            assert!(loaded.channel.get(kind).unwrap().is_none());
+        }
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_channel_set_rejects_invalid_config() {
+        let mut channels = ChannelConfig::default();
+        let bad = [
+            (
+                "telegram",
+                serde_json::json!({"enabled": true, "bot_token": ""}),
+            ),
+            (
+                "telegram",
+                serde_json::json!({"enabled": false, "bot_token": "no-colon"}),
+            ),
+            ("zalo", serde_json::json!({"mode": "bogus"})),
+            ("discord", serde_json::json!({"enabled": true})),
+            (
+                "email",
+                serde_json::json!({"enabled": true, "smtp_host": "h", "email": "nope"}),
+            ),
+            (
+                "whatsapp",
+                serde_json::json!({"enabled": true, "phone_number_id": "1"}),
+            ),
+            ("webhook", serde_json::json!({"outbound_url": "ftp://x"})),
+        ];
+        for (kind, value) in bad {
+            assert!(
+                channels.set(kind, value).is_err(),
+                "{kind} accepted invalid config"
+            );
+            assert!(channels.get(kind).unwrap().is_none());
+        }
+        assert!(channels.set("sms", serde_json::json!({})).is_err());
+        assert!(channels.remove("sms").is_err());
+    }
+
+    #[test]
+    fn test_concurrent_saves_leave_valid_config_and_backup() {
+        let path = temp_config_path("concurrent");
+        BizClawConfig::default().save_to(&path).unwrap();
+
+        let handles: Vec<_> = (0..16)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let mut config = BizClawConfig {
+                        default_model: format!("model-{i}"),
+                        ..Default::default()
+                    };
+                    // Large enough that unserialized writes would tear.
+                    config.identity.system_prompt = format!("prompt-{i} ").repeat(2000);
+                    for _ in 0..5 {
+                        config.save_to(&path).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let config = BizClawConfig::load_from(&path).unwrap();
+        let i = config.default_model.strip_prefix("model-").unwrap();
+        assert!(
+            config
+                .identity
+                .system_prompt
+                .starts_with(&format!("prompt-{i} "))
+        );
+        let backup = BizClawConfig::load_from(&BizClawConfig::backup_path(&path)).unwrap();
+        assert!(backup.default_model.starts_with("model-"));
+
+        // Only the config and its backup remain — no stray temp files.
+        let entries = std::fs::read_dir(path.parent().unwrap()).unwrap().count();
+        assert_eq!(entries, 2);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_corrupt_config_does_not_replace_backup() {
+        let path = temp_config_path("backup");
+        let mut config = BizClawConfig {
+            default_model: "first".into(),
+            ..Default::default()
+        };
+        config.save_to(&path).unwrap();
+        config.default_model = "second".into();
+        config.save_to(&path).unwrap();
+        let backup_path = BizClawConfig::backup_path(&path);
+        assert_eq!(backup_path.file_name().unwrap(), "config.toml.bak");
+        assert_eq!(
+            BizClawConfig::load_from(&backup_path)
+                .unwrap()
+                .default_model,
+            "first"
+        );
+
+        std::fs::write(&path, "default_model = \"tru").unwrap();
+        config.default_model = "third".into();
+        config.save_to(&path).unwrap();
+        assert_eq!(
+            BizClawConfig::load_from(&path).unwrap().default_model,
+            "third"
+        );
+        assert_eq!(
+            BizClawConfig::load_from(&backup_path)
+                .unwrap()
+                .default_model,
+            "first"
+        );
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_failed_write_keeps_existing_config() {
+        let path = temp_config_path("failed-write");
+        let mut config = BizClawConfig::default();
+        config
+            .channel
+            .set(
+                "discord",
+                serde_json::json!({"enabled": true, "bot_token": "keep"}),
+            )
+            .unwrap();
+        config.save_to(&path).unwrap();
+        let before = std::fs::read_to_string(&path).unwrap();
+
+        // Crash midway through writing the replacement.
+        let err = write_atomic_with(&path, |file| {
+            use std::io::Write;
+            file.write_all(b"[channel.discord]\nenabled = tr")?;
+            Err(std::io::Error::other("disk full"))
+        });
+        assert!(err.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), before);
+        let loaded = BizClawConfig::load_from(&path).unwrap();
+        assert_eq!(loaded.channel.discord.unwrap().bot_token, "keep");
+
+        // No temp files are left behind.
+        let entries = std::fs::read_dir(path.parent().unwrap()).unwrap().count();
+        assert_eq!(entries, 1);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
 }
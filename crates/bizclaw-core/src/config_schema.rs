@@ -0,0 +1,357 @@
+//! Machine-readable description of the configurable fields.
+//!
+//! Generated from a serialized [`BizClawConfig::default()`], so every field
+//! the config file accepts shows up with no list to keep in sync. Sections
+//! that are unset by default (`channel.telegram`, `quality_gate`) and maps
+//! keyed by channel or agent are filled in first so their fields show up
+//! too; map entries use `*` for the key (`channel.policies.*.denied_tools`).
+//! Paths use the config file's key names (`LLM.provider`, `brain.threads`).
+//! Choices for string fields that only take certain values come from
+//! [`KNOWN_OPTIONS`]; callers that know more (the provider registry) add
+//! them with [`ConfigSchema::set_options`].
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::config::{
+    BizClawConfig, BrainFallback, ChannelModel, ChannelPolicy, DiscordChannelConfig,
+    EmailChannelConfig, GenerationOverride, QualityGateConfig, TelegramChannelConfig,
+    WebhookChannelConfig, WhatsAppChannelConfig, ZaloChannelConfig,
+};
+
+/// Value type of a config field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    /// A list, e.g. `autonomy.allowed_commands`.
+    Array,
+    /// Free-form keys, e.g. `model_aliases`.
+    Map,
+    /// A value whose type the sample config does not show.
+    Optional,
+}
+
+/// One configurable field.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigField {
+    /// Dotted path, e.g. `memory.backend`.
+    pub path: String,
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    /// Allowed values; empty when any value of the type is accepted.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    /// Credentials: show as a password input and never echo the value.
+    pub secret: bool,
+    /// Value in the default config (`null` for secrets and unset values).
+    pub default: Value,
+}
+
+/// All configurable fields, sorted by path.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigSchema {
+    pub fields: Vec<ConfigField>,
+}
+
+/// Choices for string fields the config only accepts certain values for.
+pub const KNOWN_OPTIONS: &[(&str, &[&str])] = &[
+    ("memory.backend", &["sqlite", "none"]),
+    ("autonomy.level", &["readonly", "supervised", "full"]),
+    ("locale", &["en", "vi"]),
+];
+
+impl ConfigSchema {
+    /// Schema of [`BizClawConfig`].
+    pub fn generate() -> Self {
+        let defaults = sample(false);
+        let mut fields = Vec::new();
+        if let Value::Object(root) = sample(true) {
+            for (key, value) in root {
+                collect(key, value, &defaults, &mut fields);
+            }
+        }
+        let mut schema = Self { fields };
+        for (path, options) in KNOWN_OPTIONS {
+            schema.set_options(path, options.iter().map(|o| o.to_string()).collect());
+        }
+        schema
+    }
+
+    /// The field at `path`.
+    pub fn field(&self, path: &str) -> Option<&ConfigField> {
+        self.fields.iter().find(|f| f.path == path)
+    }
+
+    /// Restrict the field at `path` to `options`. Unknown paths are ignored.
+    pub fn set_options(&mut self, path: &str, options: Vec<String>) {
+        if let Some(field) = self.fields.iter_mut().find(|f| f.path == path) {
+            field.options = options;
+        }
+    }
+}
+
+/// Serialized default config with every optional section added with only
+/// its required keys. With `populated`, unset values and empty maps and
+/// lists are filled in as well, so serializing doesn't skip them.
+fn sample(populated: bool) -> Value {
+    let mut config = serde_json::to_value(BizClawConfig::default()).unwrap_or_default();
+    // Sections and entries as TOML would give them: parsed, then serialized.
+    fn section<T: DeserializeOwned + Serialize>(value: Value) -> Value {
+        serde_json::from_value::<T>(value)
+            .and_then(serde_json::to_value)
+            .unwrap_or_default()
+    }
+    let text = |value: Value| if populated { value } else { Value::Null };
+    let welcome = || json!({ "welcome_message": text(json!("")) });
+    let entry = |value: Value| {
+        if populated {
+            json!({ "*": value })
+        } else {
+            json!({})
+        }
+    };
+
+    config["channel"]["zalo"] = section::<ZaloChannelConfig>(welcome());
+    config["channel"]["email"] = section::<EmailChannelConfig>(welcome());
+    config["channel"]["whatsapp"] = section::<WhatsAppChannelConfig>(welcome());
+    config["channel"]["webhook"] = section::<WebhookChannelConfig>(welcome());
+    let mut bot = welcome();
+    bot["enabled"] = json!(false);
+    bot["bot_token"] = json!("");
+    config["channel"]["discord"] = section::<DiscordChannelConfig>(bot.clone());
+    bot["chat_parse_modes"] = entry(json!("markdown"));
+    config["channel"]["telegram"] = section::<TelegramChannelConfig>(bot);
+    config["channel"]["policies"] = entry(section::<ChannelPolicy>(json!({
+        "autonomy_level": "supervised",
+        "allowed_tools": [],
+        "denied_tools": ["shell"],
+    })));
+    config["channel"]["models"] = entry(section::<ChannelModel>(json!({
+        "provider": "openai",
+        "model": "gpt-4o-mini",
+    })));
+    config["generation"]["channels"] = entry(section::<GenerationOverride>(json!({
+        "max_tokens": 0,
+        "stop": [],
+    })));
+    config["suggestions"]["agents"] = entry(json!([]));
+    config["quality_gate"] = section::<QualityGateConfig>(json!({
+        "evaluator_model": text(json!("")),
+        "max_revisions": text(json!(0)),
+    }));
+    config["brain"]["fallback"] = section::<BrainFallback>(json!({
+        "provider": "",
+        "model": "",
+    }));
+    // Lists skipped when empty.
+    config["autonomy"]["denied_tools"] = json!([]);
+    config["generation"]["stop"] = json!([]);
+    config["suggestions"]["prompts"] = json!([]);
+    if populated {
+        config["autonomy"]["allowed_tools"] = json!([]);
+        config["brain"]["catalog_url"] = json!("");
+        config["brain"]["seed"] = json!(0);
+    }
+    config
+}
+
+/// Describe `value` at `path`, taking the default from the same path in
+/// `defaults`.
+fn collect(path: String, value: Value, defaults: &Value, fields: &mut Vec<ConfigField>) {
+    let field_type = match value {
+        Value::Object(map) if is_section(&map) => {
+            for (key, child) in map {
+                collect(format!("{path}.{key}"), child, defaults, fields);
+            }
+            return;
+        }
+        Value::Object(_) => FieldType::Map,
+        Value::String(_) => FieldType::String,
+        Value::Bool(_) => FieldType::Boolean,
+        Value::Number(n) if n.is_f64() => FieldType::Number,
+        Value::Number(_) => FieldType::Integer,
+        Value::Array(_) => FieldType::Array,
+        Value::Null => FieldType::Optional,
+    };
+    let default = match path.split('.').try_fold(defaults, |v, key| v.get(key)) {
+        // Config floats are f32: drop the noise of widening to f64.
+        Some(Value::Number(n)) if n.is_f64() => {
+            Value::from((n.as_f64().unwrap_or_default() * 1e6).round() / 1e6)
+        }
+        Some(value) => value.clone(),
+        None => Value::Null,
+    };
+    let secret = is_secret(path.rsplit('.').next().unwrap_or(&path));
+    fields.push(ConfigField {
+        path,
+        field_type,
+        options: Vec::new(),
+        secret,
+        default: if secret { Value::Null } else { default },
+    });
+}
+
+/// Whether an object is a config section (fixed fields) rather than a map
+/// with user-chosen keys. Maps are empty in the sample unless given a `*`
+/// entry.
+fn is_section(map: &serde_json::Map<String, Value>) -> bool {
+    !map.is_empty()
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key == "key"
+        || key.ends_with("_key")
+        || key.ends_with("token")
+        || key.contains("secret")
+        || key.contains("password")
+        || key.contains("cookie")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_describes_config_fields() {
+        let schema = ConfigSchema::generate();
+
+        let api_key = schema.field("api_key").unwrap();
+        assert_eq!(api_key.field_type, FieldType::String);
+        assert!(api_key.secret);
+        assert_eq!(api_key.default, Value::Null);
+
+        let backend = schema.field("memory.backend").unwrap();
+        assert_eq!(backend.options, ["sqlite", "none"]);
+        assert!(!backend.secret);
+
+        assert_eq!(schema.field("LLM.api_key").map(|f| f.secret), Some(true));
+        assert_eq!(
+            schema.field("brain.threads").unwrap().field_type,
+            FieldType::Integer
+        );
+        let temperature = schema.field("default_temperature").unwrap();
+        assert_eq!(temperature.field_type, FieldType::Number);
+        assert_eq!(temperature.default, 0.7);
+        assert_eq!(
+            schema.field("model_aliases").unwrap().field_type,
+            FieldType::Map
+        );
+        // Token counts are not credentials.
+        assert!(!schema.field("brain.max_tokens").unwrap().secret);
+    }
+
+    #[test]
+    fn test_schema_describes_optional_sections() {
+        let schema = ConfigSchema::generate();
+
+        let token = schema.field("channel.telegram.bot_token").unwrap();
+        assert_eq!(token.field_type, FieldType::String);
+        assert!(token.secret);
+        assert!(schema.field("channel.whatsapp.app_secret").unwrap().secret);
+        assert!(schema.field("channel.email.password").unwrap().secret);
+        assert_eq!(
+            schema.field("channel.email.imap_port").unwrap().default,
+            993
+        );
+        let welcome = schema.field("channel.zalo.welcome_message").unwrap();
+        assert_eq!(welcome.field_type, FieldType::String);
+        assert_eq!(welcome.default, Value::Null);
+        assert!(schema.field("quality_gate.max_revisions").is_some());
+        assert!(schema.field("brain.fallback.model").is_some());
+
+        // Maps keyed by channel describe their entries under `*`.
+        let level = schema.field("channel.policies.*.autonomy_level").unwrap();
+        assert_eq!(level.field_type, FieldType::String);
+        assert_eq!(level.default, Value::Null);
+        assert_eq!(
+            schema
+                .field("channel.policies.*.denied_tools")
+                .unwrap()
+                .field_type,
+            FieldType::Array
+        );
+        assert!(schema.field("channel.models.*.model").is_some());
+        assert!(schema.field("generation.channels.*.max_tokens").is_some());
+        assert_eq!(
+            schema.field("autonomy.denied_tools").unwrap().default,
+            Value::Array(vec![])
+        );
+    }
+
+    /// Paths of the values `collect` turns into fields.
+    fn leaf_paths(path: &str, value: &Value, paths: &mut Vec<String>) {
+        match value {
+            Value::Object(map) if is_section(map) => {
+                for (key, child) in map {
+                    let child_path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    leaf_paths(&child_path, child, paths);
+                }
+            }
+            _ => paths.push(path.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_schema_covers_every_config_field() {
+        let schema = ConfigSchema::generate();
+        let sample = sample(true);
+
+        // Every section the sample fills in parsed as its config type.
+        for path in [
+            "channel.zalo",
+            "channel.email",
+            "channel.whatsapp",
+            "channel.webhook",
+            "channel.discord",
+            "channel.telegram",
+            "channel.policies.*",
+            "channel.models.*",
+            "generation.channels.*",
+            "quality_gate",
+            "brain.fallback",
+        ] {
+            let section = path.split('.').try_fold(&sample, |v, key| v.get(key));
+            assert!(
+                section.is_some_and(|s| s.as_object().is_some_and(|m| !m.is_empty())),
+                "sample section {path} did not parse"
+            );
+        }
+
+        // Whatever a config serializes to, default or fully populated, is
+        // described by the schema.
+        let populated: BizClawConfig =
+            serde_json::from_value(sample.clone()).expect("sample is a valid config");
+        for config in [BizClawConfig::default(), populated] {
+            let mut paths = Vec::new();
+            leaf_paths("", &serde_json::to_value(&config).unwrap(), &mut paths);
+            for path in paths {
+                // An unset section is described by its fields.
+                let prefix = format!("{path}.");
+                assert!(
+                    schema.field(&path).is_some()
+                        || schema.fields.iter().any(|f| f.path.starts_with(&prefix)),
+                    "{path} is missing from the schema"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_options() {
+        let mut schema = ConfigSchema::generate();
+        schema.set_options("default_provider", vec!["openai".into(), "ollama".into()]);
+        let field = schema.field("default_provider").unwrap();
+        assert_eq!(field.options, ["openai", "ollama"]);
+        assert_eq!(field.default, "openai");
+    }
+}
@@ -110,6 +110,10 @@ pub enum BizClawError {
     #[error("Quality gate failed: {0}")]
     QualityGate(String),
 
+    // Input errors
+    #[error("Input too long: {0}")]
+    InputTooLong(String),
+
     // Database errors
     #[error("Database error: {0}")]
     Database(String),
@@ -211,6 +215,7 @@ mod tests {
             BizClawError::Handoff("h".into()),
             BizClawError::EvaluateLoop("e".into()),
             BizClawError::QualityGate("q".into()),
+            BizClawError::InputTooLong("i".into()),
             BizClawError::Database("d".into()),
             BizClawError::Other("o".into()),
         ];
@@ -219,8 +224,8 @@ mod tests {
             let display = err.to_string();
             assert!(!display.is_empty(), "Error should have display: {:?}", err);
         }
-        // There should be 31 variants
-        assert_eq!(errors.len(), 31);
+        // There should be 32 variants
+        assert_eq!(errors.len(), 32);
     }
 
     #[test]
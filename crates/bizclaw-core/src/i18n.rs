@@ -0,0 +1,191 @@
+//! Localization for user-facing bot messages.
+//!
+//! Messages are looked up by key in a per-locale catalog. Missing keys fall
+//! back to English, and unknown keys render as the key itself.
+//! Placeholders use `{name}` syntax and are filled by [`tr`].
+//!
+//! ```toml
+//! locale = "vi"   # in config.toml — "en" (default) or "vi"
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Supported locales for bot messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Vi,
+}
+
+impl Locale {
+    /// Parse a locale tag such as `vi`, `vi-VN` or `en_US`. Unknown tags map to English.
+    pub fn parse(tag: &str) -> Self {
+        let lang = tag
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match lang.as_str() {
+            "vi" => Self::Vi,
+            _ => Self::En,
+        }
+    }
+
+    /// Short language code (`en`, `vi`).
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Vi => "vi",
+        }
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::En => EN,
+            Self::Vi => VI,
+        }
+    }
+}
+
+/// Look up a message without placeholder substitution.
+pub fn t(locale: Locale, key: &str) -> &str {
+    lookup(locale.catalog(), key)
+        .or_else(|| lookup(EN, key))
+        .unwrap_or(key)
+}
+
+/// Look up a message and substitute `{name}` placeholders.
+pub fn tr(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    let mut out = t(locale, key).to_string();
+    for (name, value) in args {
+        out = out.replace(&format!("{{{name}}}"), value);
+    }
+    out
+}
+
+fn lookup(catalog: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    catalog.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+const EN: &[(&str, &str)] = &[
+    ("agent.error", "⚠️ Agent error: {error}"),
+    ("agent.unavailable", "❌ Error: {error}"),
+    ("agent.error.provider_unavailable", "⚠️ The AI service is temporarily unavailable. Please try again in a moment."),
+    ("agent.error.rate_limited", "⏳ Too many requests right now. Please wait a moment and try again."),
+    ("agent.error.tool_failed", "⚠️ Something went wrong while handling your request. Please try again."),
+    ("agent.error.context_too_long", "⚠️ This conversation is too long. Please start a new one or shorten your message."),
+    ("agent.error.security_blocked", "🚫 This request was blocked by the security policy."),
+    ("agent.error.internal", "⚠️ Something went wrong. Please try again later."),
+    ("agent.error.input_too_long", "⚠️ Your message is too long. Please shorten it or send it in smaller parts."),
+    ("agent.input_truncated", "✂️ Your message was too long, so only the first {limit} characters were read."),
+    ("agent.refused", "Sorry, I can't help with that request."),
+    ("maintenance.message", "🛠️ We're doing some maintenance right now. Please try again in a few minutes."),
+    ("suggestions.skill", "What can {skill} help me with?"),
+    ("pin.done", "📌 Pinned your last message. It stays in context until you `/unpin`."),
+    ("pin.nothing", "⚠️ There is no message to pin yet."),
+    ("unpin.done", "Unpinned {count} message(s)."),
+    ("timezone.done", "🕒 Your timezone is now {tz}."),
+    ("timezone.invalid", "⚠️ Unknown timezone '{tz}'. Use a zone name like `Asia/Ho_Chi_Minh` or an offset like `+07:00`."),
+    ("hand.none", "_No Hands yet._"),
+    ("hand.run_hint", "_Run: `/hand run <name>`_"),
+    ("hand.list_failed", "⚠️ Could not fetch the list of Hands."),
+    ("hand.read_failed", "⚠️ Could not read Hands data."),
+    ("hand.name_required", "⚠️ Hand name required. Example: `/hand run Research Hand`"),
+    ("hand.running", "⏳ Running *{name}*..."),
+    ("hand.done", "🤚 *{name}* — Done!"),
+    ("hand.run_failed", "❌ Failed to run {name}: {error}"),
+    ("hand.not_found", "❌ Hand not found: '{name}'\n_Use `/hand list` to see all Hands._"),
+    ("hand.invalid_command", "⚠️ Invalid command: `/hand {sub}`\n_Use `/hand list` or `/hand run <name>`_"),
+    ("workflow.name_required", "⚠️ Workflow name required. Example: `/run content-creation`\n_Use `/hand list` to see the list._"),
+    ("workflow.done", "🔄 *Workflow '{name}' — Done!*"),
+    ("workflow.error", "❌ Workflow error: {error}"),
+    ("api.connect_failed", "❌ API connection error: {error}"),
+    ("api.error", "❌ API error: {error}"),
+    ("data.read_failed", "⚠️ Could not read data."),
+    ("result.read_failed", "⚠️ Could not read the result."),
+    ("zalo.qr.step1", "1. Open the Zalo app on your phone"),
+    ("zalo.qr.step2", "2. Tap the QR icon in the search bar"),
+    ("zalo.qr.step3", "3. Scan this QR code to log in"),
+    ("zalo.qr.step4", "4. Confirm the login on your phone"),
+    ("zalo.qr.message", "Scan the QR code with Zalo on your phone"),
+    ("zalo.qr.error", "Could not generate the Zalo QR code"),
+    ("zalo.qr.fallback", "Open chat.zalo.me → F12 → Application → Cookies → copy everything and paste it into the Cookie field below"),
+];
+
+const VI: &[(&str, &str)] = &[
+    ("agent.error", "⚠️ Lỗi agent: {error}"),
+    ("agent.unavailable", "❌ Lỗi: {error}"),
+    ("agent.error.provider_unavailable", "⚠️ Dịch vụ AI tạm thời không khả dụng. Vui lòng thử lại sau ít phút."),
+    ("agent.error.rate_limited", "⏳ Hiện có quá nhiều yêu cầu. Vui lòng đợi một lát rồi thử lại."),
+    ("agent.error.tool_failed", "⚠️ Đã xảy ra lỗi khi xử lý yêu cầu. Vui lòng thử lại."),
+    ("agent.error.context_too_long", "⚠️ Cuộc trò chuyện quá dài. Vui lòng bắt đầu cuộc mới hoặc rút gọn tin nhắn."),
+    ("agent.error.security_blocked", "🚫 Yêu cầu này đã bị chặn bởi chính sách bảo mật."),
+    ("agent.error.internal", "⚠️ Đã xảy ra lỗi. Vui lòng thử lại sau."),
+    ("agent.error.input_too_long", "⚠️ Tin nhắn quá dài. Vui lòng rút gọn hoặc gửi thành nhiều phần nhỏ hơn."),
+    ("agent.input_truncated", "✂️ Tin nhắn quá dài nên chỉ {limit} ký tự đầu tiên được đọc."),
+    ("agent.refused", "Xin lỗi, tôi không thể giúp với yêu cầu này."),
+    ("maintenance.message", "🛠️ Hệ thống đang bảo trì. Vui lòng thử lại sau ít phút."),
+    ("suggestions.skill", "{skill} có thể giúp gì cho tôi?"),
+    ("pin.done", "📌 Đã ghim tin nhắn vừa rồi. Tin nhắn sẽ luôn được giữ trong ngữ cảnh cho đến khi bạn `/unpin`."),
+    ("pin.nothing", "⚠️ Chưa có tin nhắn nào để ghim."),
+    ("unpin.done", "Đã bỏ ghim {count} tin nhắn."),
+    ("timezone.done", "🕒 Đã đặt múi giờ của bạn là {tz}."),
+    ("timezone.invalid", "⚠️ Không nhận ra múi giờ '{tz}'. Dùng tên múi giờ như `Asia/Ho_Chi_Minh` hoặc độ lệch như `+07:00`."),
+    ("hand.none", "_Chưa có Hand nào._"),
+    ("hand.run_hint", "_Chạy: `/hand run <tên>`_"),
+    ("hand.list_failed", "⚠️ Không lấy được danh sách Hands."),
+    ("hand.read_failed", "⚠️ Lỗi đọc dữ liệu Hands."),
+    ("hand.name_required", "⚠️ Cần tên Hand. Ví dụ: `/hand run Research Hand`"),
+    ("hand.running", "⏳ Đang chạy *{name}*..."),
+    ("hand.done", "🤚 *{name}* — Hoàn thành!"),
+    ("hand.run_failed", "❌ Lỗi chạy {name}: {error}"),
+    ("hand.not_found", "❌ Không tìm thấy Hand: '{name}'\n_Dùng `/hand list` để xem danh sách._"),
+    ("hand.invalid_command", "⚠️ Lệnh không hợp lệ: `/hand {sub}`\n_Dùng `/hand list` hoặc `/hand run <tên>`_"),
+    ("workflow.name_required", "⚠️ Cần tên workflow. Ví dụ: `/run content-creation`\n_Dùng `/hand list` để xem danh sách._"),
+    ("workflow.done", "🔄 *Workflow '{name}' — Hoàn thành!*"),
+    ("workflow.error", "❌ Lỗi workflow: {error}"),
+    ("api.connect_failed", "❌ Lỗi kết nối API: {error}"),
+    ("api.error", "❌ Lỗi API: {error}"),
+    ("data.read_failed", "⚠️ Lỗi đọc dữ liệu."),
+    ("result.read_failed", "⚠️ Lỗi đọc kết quả."),
+    ("zalo.qr.step1", "1. Mở ứng dụng Zalo trên điện thoại"),
+    ("zalo.qr.step2", "2. Nhấn biểu tượng QR ở thanh tìm kiếm"),
+    ("zalo.qr.step3", "3. Quét mã QR này để đăng nhập"),
+    ("zalo.qr.step4", "4. Xác nhận đăng nhập trên điện thoại"),
+    ("zalo.qr.message", "Quét mã QR bằng Zalo trên điện thoại"),
+    ("zalo.qr.error", "Không thể tạo mã QR Zalo"),
+    ("zalo.qr.fallback", "Vui lòng vào chat.zalo.me → F12 → Application → Cookies → Copy toàn bộ và paste vào ô Cookie bên dưới"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_key_differs_per_locale() {
+        let en = tr(Locale::En, "agent.error", &[("error", "timeout")]);
+        let vi = tr(Locale::Vi, "agent.error", &[("error", "timeout")]);
+        assert_eq!(en, "⚠️ Agent error: timeout");
+        assert_eq!(vi, "⚠️ Lỗi agent: timeout");
+    }
+
+    #[test]
+    fn test_fallback() {
+        // Unknown locale tags fall back to English
+        assert_eq!(Locale::parse("fr-FR"), Locale::En);
+        assert_eq!(Locale::parse("vi_VN"), Locale::Vi);
+        assert_eq!(t(Locale::parse("de"), "data.read_failed"), "⚠️ Could not read data.");
+        // Unknown keys render as the key itself
+        assert_eq!(t(Locale::Vi, "no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn test_catalogs_cover_english_keys() {
+        for (key, _) in EN {
+            assert!(lookup(VI, key).is_some(), "missing vi translation for {key}");
+        }
+    }
+}
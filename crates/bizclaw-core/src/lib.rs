@@ -3,8 +3,14 @@
 //! Core traits, types, and configuration for the BizClaw AI assistant platform.
 //! Every subsystem is a trait — swap implementations with a config change.
 
+pub mod cancel;
 pub mod config;
+pub mod config_schema;
 pub mod error;
+pub mod i18n;
+pub mod maintenance;
+pub mod net;
+pub mod text;
 pub mod traits;
 pub mod types;
 
@@ -0,0 +1,78 @@
+//! Maintenance mode — stop taking new chats during an upgrade.
+//!
+//! While maintenance is on, the gateway answers chat requests with
+//! `503 Service Unavailable` and the maintenance message, and channel loops
+//! reply with the message instead of running the agent. Health probes keep
+//! reporting as usual.
+//!
+//! A [`Maintenance`] switch is cheap to clone; all clones share one state.
+//! [`Maintenance::global`] is the switch shared by the gateway and the
+//! channel loops of one process.
+
+use crate::i18n::{Locale, t};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Shared maintenance switch. Holds the message to show while on.
+#[derive(Debug, Clone, Default)]
+pub struct Maintenance {
+    message: Arc<RwLock<Option<String>>>,
+}
+
+static GLOBAL: OnceLock<Maintenance> = OnceLock::new();
+
+impl Maintenance {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The process-wide switch.
+    pub fn global() -> &'static Maintenance {
+        GLOBAL.get_or_init(Maintenance::new)
+    }
+
+    /// Turn maintenance on, showing `message` to users.
+    pub fn enable(&self, message: impl Into<String>) {
+        *self.message.write().unwrap_or_else(|e| e.into_inner()) = Some(message.into());
+    }
+
+    /// Turn maintenance off.
+    pub fn disable(&self) {
+        *self.message.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.message().is_some()
+    }
+
+    /// The message to show users, or `None` when maintenance is off.
+    pub fn message(&self) -> Option<String> {
+        self.message
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+/// Message shown when maintenance is turned on without one.
+pub fn default_message(locale: Locale) -> &'static str {
+    t(locale, "maintenance.message")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_state() {
+        let maintenance = Maintenance::new();
+        let clone = maintenance.clone();
+        assert!(!clone.is_active());
+
+        maintenance.enable(default_message(Locale::En));
+        assert!(clone.is_active());
+        assert!(clone.message().unwrap().contains("maintenance"));
+
+        clone.disable();
+        assert_eq!(maintenance.message(), None);
+    }
+}
@@ -0,0 +1,27 @@
+//! Network helpers shared across crates.
+
+/// Check if a URL is blocked by SSRF protection: non-http(s) schemes,
+/// loopback, link-local (cloud metadata) and private network hosts.
+/// Returns the reason when blocked.
+pub fn is_url_blocked(url: &str) -> Option<String> {
+    let lower_url = url.to_lowercase();
+    if !lower_url.starts_with("http://") && !lower_url.starts_with("https://") {
+        return Some("Only HTTP/HTTPS schemes allowed".into());
+    }
+    let blocked_patterns = [
+        "127.0.0.1", "localhost", "0.0.0.0", "[::1]", "[::0]",
+        "169.254.", "metadata.google", "metadata.aws",
+        "10.",  "192.168.",
+        "172.16.", "172.17.", "172.18.", "172.19.",
+        "172.20.", "172.21.", "172.22.", "172.23.",
+        "172.24.", "172.25.", "172.26.", "172.27.",
+        "172.28.", "172.29.", "172.30.", "172.31.",
+    ];
+    let host_part = lower_url.split("://").nth(1).unwrap_or("");
+    let host = host_part.split('/').next().unwrap_or("");
+    let host_no_port = host.split(':').next().unwrap_or("");
+    if blocked_patterns.iter().any(|p| host_no_port.contains(p)) {
+        return Some(format!("Cannot access internal/private network ({host_no_port})"));
+    }
+    None
+}
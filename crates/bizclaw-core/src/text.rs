@@ -0,0 +1,38 @@
+//! Text helpers shared across crates.
+
+/// The first `max_chars` characters of `s` (all of `s` if it is shorter).
+///
+/// Unlike `&s[..n]`, never splits a multibyte UTF-8 character, so it is safe
+/// on user content such as Vietnamese text.
+pub fn truncate_chars(s: &str, max_chars: usize) -> &str {
+    match s.char_indices().nth(max_chars) {
+        Some((end, _)) => &s[..end],
+        None => s,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("hello", 3), "hel");
+        assert_eq!(truncate_chars("hello", 10), "hello");
+        assert_eq!(truncate_chars("", 3), "");
+        assert_eq!(truncate_chars("Xin chào bạn", 6), "Xin ch");
+        assert_eq!(truncate_chars("Xin chào bạn", 8), "Xin chào");
+        assert_eq!(truncate_chars("日本語", 2), "日本");
+    }
+
+    #[test]
+    fn test_truncate_chars_never_splits_multibyte_chars() {
+        let text = "Cảm ơn quý khách đã đặt hàng 🛒 — 日本語";
+        let total = text.chars().count();
+        for n in 0..=total + 2 {
+            let cut = truncate_chars(text, n);
+            assert_eq!(cut.chars().count(), n.min(total));
+            assert!(text.starts_with(cut));
+        }
+    }
+}
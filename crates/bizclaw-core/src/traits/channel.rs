@@ -33,4 +33,11 @@ pub trait Channel: Send + Sync {
         let _ = thread_id;
         Ok(()) // Default no-op
     }
+
+    /// Acknowledge an incoming message (read receipt or reaction) when the
+    /// agent starts processing it, so users know a reply is on its way.
+    async fn ack(&self, thread_id: &str, message_id: &str) -> Result<()> {
+        let _ = (thread_id, message_id);
+        Ok(()) // Default no-op
+    }
 }
@@ -0,0 +1,78 @@
+//! Embedding Cache trait — skip re-embedding text seen before.
+//!
+//! Entries are keyed by [`embedding_cache_key`], a hash of the model and the
+//! text, so switching embedding models never returns vectors from the old one.
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+use crate::error::Result;
+
+/// Stores embeddings by content hash, consulted before calling an
+/// embeddings API.
+#[async_trait]
+pub trait EmbeddingCache: Send + Sync {
+    /// The cached embedding for `key`, if present and not expired.
+    async fn get(&self, key: &str) -> Result<Option<Vec<f32>>>;
+
+    /// Cache an embedding, replacing any previous one for `key`.
+    async fn put(&self, key: &str, embedding: &[f32]) -> Result<()>;
+}
+
+/// Cache key for `text` embedded with `model`.
+pub fn embedding_cache_key(model: &str, text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(model.as_bytes());
+    hasher.update([0]);
+    hasher.update(text.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Encode an embedding as little-endian `f32` bytes for storage.
+pub fn embedding_to_bytes(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Decode an embedding stored by [`embedding_to_bytes`].
+pub fn embedding_from_bytes(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_depends_on_model_and_text() {
+        let key = embedding_cache_key("nomic-embed-text", "opening hours");
+        assert_eq!(key.len(), 64);
+        assert_eq!(
+            key,
+            embedding_cache_key("nomic-embed-text", "opening hours")
+        );
+        assert_ne!(
+            key,
+            embedding_cache_key("text-embedding-3-small", "opening hours")
+        );
+        assert_ne!(
+            key,
+            embedding_cache_key("nomic-embed-text", "opening hours?")
+        );
+    }
+
+    #[test]
+    fn test_embedding_bytes_roundtrip() {
+        let embedding = vec![0.25, -1.5, 3.0];
+        assert_eq!(
+            embedding_from_bytes(&embedding_to_bytes(&embedding)),
+            embedding
+        );
+    }
+}
@@ -1,12 +1,32 @@
 //! Identity configuration trait.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Identity {
     pub name: String,
     pub persona: String,
     pub system_prompt: String,
+    /// Per-channel system prompts keyed by channel name (`telegram`, `email`, ...).
+    /// Channels without an entry use `system_prompt`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub channel_prompts: HashMap<String, String>,
+}
+
+impl Identity {
+    /// The override for `channel`, if one is configured and non-empty.
+    pub fn channel_prompt(&self, channel: &str) -> Option<&str> {
+        self.channel_prompts
+            .get(channel)
+            .map(String::as_str)
+            .filter(|p| !p.trim().is_empty())
+    }
+
+    /// System prompt for messages arriving via `channel`.
+    pub fn prompt_for(&self, channel: &str) -> &str {
+        self.channel_prompt(channel).unwrap_or(&self.system_prompt)
+    }
 }
 
 impl Default for Identity {
@@ -16,6 +36,37 @@ impl Default for Identity {
             persona: "A helpful AI assistant".into(),
             system_prompt:
                 "You are BizClaw, a fast and capable AI assistant. Be concise and helpful.".into(),
+            channel_prompts: HashMap::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_prompt_overrides_default() {
+        let identity: Identity = toml::from_str(
+            r#"
+            name = "Shop"
+            persona = "Sales assistant"
+            system_prompt = "You are the shop assistant."
+
+            [channel_prompts]
+            telegram = "Reply casually and keep it short."
+            email = ""
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            identity.prompt_for("telegram"),
+            "Reply casually and keep it short."
+        );
+        assert_eq!(identity.prompt_for("cli"), "You are the shop assistant.");
+        // An empty override falls back to the global prompt.
+        assert_eq!(identity.prompt_for("email"), "You are the shop assistant.");
+        assert!(identity.channel_prompt("cli").is_none());
+    }
+}
@@ -23,6 +23,20 @@ pub struct MemorySearchResult {
     pub score: f32,
 }
 
+/// Aggregated usage of one tool, kept by backends that record tool calls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolUsage {
+    pub tool_name: String,
+    pub usage_count: u64,
+    pub success_count: u64,
+    pub failure_count: u64,
+    /// Running mean over all calls, in milliseconds.
+    pub avg_duration_ms: u64,
+    pub last_error: Option<String>,
+    pub tips: Option<String>,
+    pub last_used: chrono::DateTime<chrono::Utc>,
+}
+
 /// Memory Backend trait — every persistence layer implements this.
 #[async_trait]
 pub trait MemoryBackend: Send + Sync {
@@ -46,4 +60,20 @@ pub trait MemoryBackend: Send + Sync {
 
     /// Clear all memories.
     async fn clear(&self) -> Result<()>;
+
+    /// Record one tool call. Backends without tool stats ignore it.
+    async fn record_tool_usage(
+        &self,
+        _tool_name: &str,
+        _success: bool,
+        _duration_ms: u64,
+        _error: Option<&str>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Tool usage stats, most used first.
+    async fn tool_usage(&self) -> Result<Vec<ToolUsage>> {
+        Ok(Vec::new())
+    }
 }
@@ -3,6 +3,7 @@
 //! Swap implementations with a config change, zero code changes.
 
 pub mod channel;
+pub mod embedding_cache;
 pub mod identity;
 pub mod memory;
 pub mod observer;
@@ -13,7 +14,8 @@ pub mod tool;
 pub mod tunnel;
 
 pub use channel::Channel;
+pub use embedding_cache::EmbeddingCache;
 pub use memory::MemoryBackend;
-pub use provider::Provider;
+pub use provider::{Provider, ProviderCapabilities};
 pub use security::SecurityPolicy;
 pub use tool::Tool;
@@ -3,7 +3,7 @@
 use async_trait::async_trait;
 
 use crate::error::Result;
-use crate::types::{Message, ModelInfo, ProviderResponse, ToolDefinition};
+use crate::types::{Message, ModelInfo, ProviderResponse, ToolChoice, ToolDefinition};
 
 /// Configuration for generation parameters.
 #[derive(Debug, Clone)]
@@ -13,6 +13,13 @@ pub struct GenerateParams {
     pub max_tokens: u32,
     pub top_p: f32,
     pub stop: Vec<String>,
+    /// Tool selection policy (ignored when no tools are sent).
+    pub tool_choice: ToolChoice,
+    /// Ask the backend for a JSON object response (`response_format: json_object`).
+    /// Only honoured by providers where [`Provider::supports_json_mode`] is true.
+    pub json_mode: bool,
+    /// Sampling seed for reproducible output, where the backend supports it.
+    pub seed: Option<u64>,
 }
 
 impl Default for GenerateParams {
@@ -23,10 +30,44 @@ impl Default for GenerateParams {
             max_tokens: 4096,
             top_p: 0.9,
             stop: vec![],
+            tool_choice: ToolChoice::Auto,
+            json_mode: false,
+            seed: None,
         }
     }
 }
 
+/// What a provider can do, so callers can degrade gracefully instead of
+/// failing at request time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProviderCapabilities {
+    /// Backend can stream tokens (SSE).
+    pub supports_streaming: bool,
+    /// Backend accepts tool definitions and returns tool calls.
+    pub supports_tools: bool,
+    /// Backend accepts image inputs.
+    pub supports_vision: bool,
+    /// Backend enforces JSON output natively (`response_format: json_object`).
+    pub supports_json: bool,
+    /// Largest context window (tokens) among the provider's models.
+    pub max_context: u32,
+}
+
+impl Default for ProviderCapabilities {
+    fn default() -> Self {
+        Self {
+            supports_streaming: false,
+            supports_tools: true,
+            supports_vision: false,
+            supports_json: false,
+            max_context: 4096,
+        }
+    }
+}
+
+/// Receives streamed text as it arrives (see [`Provider::chat_stream`]).
+pub type OnText<'a> = dyn Fn(&str) + Send + Sync + 'a;
+
 /// Provider trait — every LLM backend implements this.
 #[async_trait]
 pub trait Provider: Send + Sync {
@@ -41,9 +82,42 @@ pub trait Provider: Send + Sync {
         params: &GenerateParams,
     ) -> Result<ProviderResponse>;
 
+    /// Streaming chat completion. Text is passed to `on_text` as it arrives;
+    /// the returned response is complete, including tool calls assembled
+    /// from the stream. The default calls [`Provider::chat`] and delivers
+    /// the whole answer at once.
+    async fn chat_stream(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        params: &GenerateParams,
+        on_text: &OnText<'_>,
+    ) -> Result<ProviderResponse> {
+        let resp = self.chat(messages, tools, params).await?;
+        if let Some(text) = &resp.content
+            && !text.is_empty()
+        {
+            on_text(text);
+        }
+        Ok(resp)
+    }
+
     /// List available models for this provider.
     async fn list_models(&self) -> Result<Vec<ModelInfo>>;
 
     /// Check if the provider is available and configured.
     async fn health_check(&self) -> Result<bool>;
+
+    /// Whether the backend enforces JSON output natively (`GenerateParams::json_mode`).
+    fn supports_json_mode(&self) -> bool {
+        false
+    }
+
+    /// Feature support for this provider.
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            supports_json: self.supports_json_mode(),
+            ..Default::default()
+        }
+    }
 }
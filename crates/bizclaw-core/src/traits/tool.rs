@@ -5,6 +5,9 @@ use async_trait::async_trait;
 use crate::error::Result;
 use crate::types::{ToolDefinition, ToolResult};
 
+/// Receives tool output as it is produced (see [`Tool::execute_streaming`]).
+pub type OnOutput<'a> = dyn Fn(&str) + Send + Sync + 'a;
+
 /// Tool trait — every executable tool implements this.
 #[async_trait]
 pub trait Tool: Send + Sync {
@@ -16,4 +19,19 @@ pub trait Tool: Send + Sync {
 
     /// Execute the tool with given arguments.
     async fn execute(&self, arguments: &str) -> Result<ToolResult>;
+
+    /// Execute the tool, passing output to `on_output` as it is produced.
+    /// The returned result is complete. The default calls [`Tool::execute`]
+    /// and delivers the whole output at once.
+    async fn execute_streaming(
+        &self,
+        arguments: &str,
+        on_output: &OnOutput<'_>,
+    ) -> Result<ToolResult> {
+        let result = self.execute(arguments).await?;
+        if !result.output.is_empty() {
+            on_output(&result.output);
+        }
+        Ok(result)
+    }
 }
@@ -0,0 +1,95 @@
+//! Bulk import of JSONL records into memory or the knowledge base.
+//!
+//! An import never stops at a bad line: each record that cannot be parsed or
+//! stored is listed in the [`ImportReport`] with its line number, and the
+//! rest are imported.
+
+use serde::{Deserialize, Serialize};
+
+/// A record that could not be imported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportFailure {
+    /// 1-based line number in the input.
+    pub line: usize,
+    pub error: String,
+}
+
+/// Outcome of a bulk import.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Records stored.
+    pub imported: usize,
+    /// Chunks indexed (knowledge imports only).
+    #[serde(default)]
+    pub chunks: usize,
+    pub failures: Vec<ImportFailure>,
+}
+
+impl ImportReport {
+    /// Records seen so far, stored or not.
+    pub fn processed(&self) -> usize {
+        self.imported + self.failures.len()
+    }
+
+    pub fn fail(&mut self, line: usize, error: impl Into<String>) {
+        self.failures.push(ImportFailure {
+            line,
+            error: error.into(),
+        });
+    }
+}
+
+/// One parsed JSONL record.
+pub type JsonRecord = serde_json::Map<String, serde_json::Value>;
+
+/// The records of a JSONL document with their 1-based line numbers. Blank
+/// lines are skipped; a line that is not a JSON object is an `Err`.
+pub fn jsonl_records(
+    input: &str,
+) -> impl Iterator<Item = (usize, Result<JsonRecord, String>)> + '_ {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let record = match serde_json::from_str(line) {
+                Ok(serde_json::Value::Object(map)) => Ok(map),
+                Ok(_) => Err("expected a JSON object".to_string()),
+                Err(e) => Err(format!("invalid JSON: {e}")),
+            };
+            (i + 1, record)
+        })
+}
+
+/// Number of records [`jsonl_records`] yields for `input`.
+pub fn jsonl_record_count(input: &str) -> usize {
+    input.lines().filter(|line| !line.trim().is_empty()).count()
+}
+
+/// String field `key` of `record`, if present and non-empty.
+pub fn record_str<'a>(record: &'a JsonRecord, key: &str) -> Option<&'a str> {
+    record
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsonl_records_reports_bad_lines() {
+        let input = "{\"content\":\"a\"}\n\nnot json\n[1]\n{\"content\":\"b\"}\n";
+        let records: Vec<_> = jsonl_records(input).collect();
+        assert_eq!(jsonl_record_count(input), 4);
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].0, 1);
+        assert!(records[0].1.is_ok());
+        assert_eq!(records[1].0, 3);
+        let error = records[1].1.as_ref().unwrap_err();
+        assert!(error.starts_with("invalid JSON"));
+        assert_eq!(records[2].1, Err("expected a JSON object".to_string()));
+        assert_eq!(records[3].0, 5);
+    }
+}
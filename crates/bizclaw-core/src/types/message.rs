@@ -34,6 +34,70 @@ pub struct Message {
     pub tool_call_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<super::ToolCall>>,
+    /// Images attached to this message (e.g. a screenshot returned by a tool).
+    /// Providers send them to vision models and describe them otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImageContent>,
+    /// Pinned by the user: kept in context when old turns are trimmed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+}
+
+/// An image attached to a message: base64 bytes, or a URL the provider
+/// fetches itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageContent {
+    /// MIME type, e.g. `image/png`.
+    pub media_type: String,
+    /// Base64-encoded image bytes. Empty for a URL image.
+    #[serde(default)]
+    pub data: String,
+    /// Publicly reachable image URL, sent instead of `data`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl ImageContent {
+    pub fn new(media_type: impl Into<String>, data: impl Into<String>) -> Self {
+        Self {
+            media_type: media_type.into(),
+            data: data.into(),
+            url: None,
+        }
+    }
+
+    /// An image the provider downloads from `url`.
+    pub fn from_url(media_type: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            media_type: media_type.into(),
+            data: String::new(),
+            url: Some(url.into()),
+        }
+    }
+
+    /// URL for OpenAI-style `image_url` content parts: the image's own URL,
+    /// or a `data:` URL holding the bytes.
+    pub fn image_url(&self) -> String {
+        match &self.url {
+            Some(url) => url.clone(),
+            None => format!("data:{};base64,{}", self.media_type, self.data),
+        }
+    }
+
+    /// Text stand-in for models that cannot see images.
+    pub fn describe(&self) -> String {
+        if self.url.is_some() {
+            return format!(
+                "[Image attachment ({}) omitted: the current model cannot view images]",
+                self.media_type
+            );
+        }
+        let kb = (self.data.len() * 3 / 4).div_ceil(1024);
+        format!(
+            "[Image attachment ({}, ~{kb} KB) omitted: the current model cannot view images]",
+            self.media_type
+        )
+    }
 }
 
 impl Message {
@@ -44,6 +108,8 @@ impl Message {
             name: None,
             tool_call_id: None,
             tool_calls: None,
+            images: Vec::new(),
+            pinned: false,
         }
     }
 
@@ -54,6 +120,8 @@ impl Message {
             name: None,
             tool_call_id: None,
             tool_calls: None,
+            images: Vec::new(),
+            pinned: false,
         }
     }
 
@@ -64,6 +132,8 @@ impl Message {
             name: None,
             tool_call_id: None,
             tool_calls: None,
+            images: Vec::new(),
+            pinned: false,
         }
     }
 
@@ -74,8 +144,28 @@ impl Message {
             name: None,
             tool_call_id: Some(tool_call_id.into()),
             tool_calls: None,
+            images: Vec::new(),
+            pinned: false,
         }
     }
+
+    /// Attach images to this message.
+    pub fn with_images(mut self, images: Vec<ImageContent>) -> Self {
+        self.images.extend(images);
+        self
+    }
+
+    /// Content with a text note in place of each image, for text-only models.
+    pub fn content_with_image_notes(&self) -> String {
+        let mut content = self.content.clone();
+        for image in &self.images {
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            content.push_str(&image.describe());
+        }
+        content
+    }
 }
 
 /// Incoming message from a channel.
@@ -89,6 +179,12 @@ pub struct IncomingMessage {
     pub thread_type: ThreadType,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub reply_to: Option<String>,
+    /// Platform ID of this message, used to acknowledge it (see `Channel::ack`).
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// Images sent with the message (photos, screenshots).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImageContent>,
 }
 
 /// Outgoing message to a channel.
@@ -97,7 +193,28 @@ pub struct OutgoingMessage {
     pub thread_id: String,
     pub content: String,
     pub thread_type: ThreadType,
+    /// Conversation thread to post in (Slack `thread_ts`, email `In-Reply-To`).
     pub reply_to: Option<String>,
+    /// Platform ID of a specific message to reply to (Telegram reply, Discord reference).
+    #[serde(default)]
+    pub reply_to_message_id: Option<String>,
+    /// Text being answered. Shown as a quote when the reply can't reference
+    /// the original message natively.
+    #[serde(default)]
+    pub quote: Option<String>,
+}
+
+impl OutgoingMessage {
+    /// `content` with `quote` prepended as a Markdown blockquote (unchanged without a quote).
+    pub fn content_with_quote(&self) -> String {
+        match self.quote.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            Some(quote) => {
+                let quoted: Vec<String> = quote.lines().map(|l| format!("> {l}")).collect();
+                format!("{}\n\n{}", quoted.join("\n"), self.content)
+            }
+            None => self.content.clone(),
+        }
+    }
 }
 
 /// Thread type for channel messages.
@@ -115,6 +232,51 @@ pub struct ProviderResponse {
     pub tool_calls: Vec<super::ToolCall>,
     pub finish_reason: Option<String>,
     pub usage: Option<Usage>,
+    /// Reasoning emitted separately from the answer by reasoning models
+    /// (deepseek-reasoner, o-series). For debugging only — never shown to users.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    /// Set when the provider's safety system blocked the answer or the
+    /// model declined to give one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refusal: Option<Refusal>,
+    /// Name of the fallback provider that answered because the configured
+    /// one was unreachable (e.g. `brain` for the local model).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+}
+
+/// `finish_reason` of a refused response, whatever the provider called it.
+pub const FINISH_CONTENT_FILTER: &str = "content_filter";
+
+/// A content-filter or safety refusal reported by a provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Refusal {
+    /// The provider's own finish/stop reason (`content_filter`, `refusal`, `SAFETY`…).
+    pub reason: String,
+    /// Explanation the provider sent with the refusal, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl Refusal {
+    /// Detect a refusal from a raw finish/stop reason and the provider's
+    /// refusal text (OpenAI's `message.refusal`).
+    pub fn detect(finish_reason: Option<&str>, message: Option<&str>) -> Option<Self> {
+        let message = message.filter(|m| !m.trim().is_empty()).map(String::from);
+        match finish_reason {
+            Some(r @ ("content_filter" | "refusal" | "safety" | "SAFETY" | "RECITATION")) => {
+                Some(Self {
+                    reason: r.to_string(),
+                    message,
+                })
+            }
+            _ => message.map(|message| Self {
+                reason: "refusal".to_string(),
+                message: Some(message),
+            }),
+        }
+    }
 }
 
 impl ProviderResponse {
@@ -124,6 +286,9 @@ impl ProviderResponse {
             tool_calls: vec![],
             finish_reason: Some("stop".into()),
             usage: None,
+            reasoning: None,
+            refusal: None,
+            fallback: None,
         }
     }
 
@@ -133,6 +298,9 @@ impl ProviderResponse {
             tool_calls,
             finish_reason: Some("tool_calls".into()),
             usage: None,
+            reasoning: None,
+            refusal: None,
+            fallback: None,
         }
     }
 }
@@ -162,6 +330,36 @@ mod tests {
         assert_eq!(asst.role, Role::Assistant);
     }
 
+    #[test]
+    fn test_refusal_detection() {
+        let filtered = Refusal::detect(Some("content_filter"), None).unwrap();
+        assert_eq!(filtered.reason, "content_filter");
+        assert_eq!(filtered.message, None);
+
+        let refused = Refusal::detect(Some("stop"), Some("I can't help with that.")).unwrap();
+        assert_eq!(refused.reason, "refusal");
+        assert_eq!(refused.message.as_deref(), Some("I can't help with that."));
+
+        assert!(Refusal::detect(Some("stop"), None).is_none());
+        assert!(Refusal::detect(Some("length"), Some("  ")).is_none());
+        assert!(Refusal::detect(None, None).is_none());
+    }
+
+    #[test]
+    fn test_content_with_quote() {
+        let mut msg = OutgoingMessage {
+            thread_id: "42".into(),
+            content: "Yes, in stock.".into(),
+            thread_type: ThreadType::Group,
+            reply_to: None,
+            reply_to_message_id: None,
+            quote: None,
+        };
+        assert_eq!(msg.content_with_quote(), "Yes, in stock.");
+        msg.quote = Some("Do you have\nsize M?".into());
+        assert_eq!(msg.content_with_quote(), "> Do you have\n> size M?\n\nYes, in stock.");
+    }
+
     #[test]
     fn test_role_display() {
         assert_eq!(Role::System.to_string(), "system");
@@ -1,10 +1,12 @@
 //! BizClaw message types, tool calls, model info, and orchestration primitives.
 
+pub mod import;
 pub mod message;
 pub mod model;
 pub mod orchestration;
 pub mod tool_call;
 
+pub use import::*;
 pub use message::*;
 pub use model::*;
 pub use orchestration::*;
@@ -3,6 +3,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::message::IncomingMessage;
+
 // ── Agent Link (Permission) ────────────────────────────────
 
 /// Direction of an agent link (who can delegate to whom).
@@ -122,6 +124,37 @@ impl Delegation {
     }
 }
 
+/// Kind of progress event posted while a delegation runs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DelegationEventKind {
+    Started,
+    /// Free-form progress note from the executing agent.
+    Progress,
+    /// Intermediate output the initiator may want before completion.
+    PartialResult,
+    Completed,
+    Failed,
+}
+
+/// A progress update on a delegation, polled by the initiator or dashboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelegationEvent {
+    pub kind: DelegationEventKind,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DelegationEvent {
+    pub fn new(kind: DelegationEventKind, message: &str) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
 // ── Agent Teams ────────────────────────────────────────────
 
 /// Role within a team.
@@ -277,6 +310,60 @@ impl TeamMessage {
     }
 }
 
+// ── Link Messages ──────────────────────────────────────────
+
+/// Delivery status of a direct message between linked agents.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkMessageStatus {
+    /// Stored, not yet fetched by the recipient.
+    Pending,
+    /// Fetched by the recipient.
+    Delivered,
+    /// Acknowledged by the recipient.
+    Read,
+}
+
+impl std::fmt::Display for LinkMessageStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pending => write!(f, "pending"),
+            Self::Delivered => write!(f, "delivered"),
+            Self::Read => write!(f, "read"),
+        }
+    }
+}
+
+/// A direct message sent over an [`AgentLink`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkMessage {
+    pub id: String,
+    pub link_id: String,
+    pub from_agent: String,
+    pub to_agent: String,
+    pub content: String,
+    pub status: LinkMessageStatus,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+impl LinkMessage {
+    pub fn new(link: &AgentLink, from: &str, to: &str, content: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            link_id: link.id.clone(),
+            from_agent: from.to_string(),
+            to_agent: to.to_string(),
+            content: content.to_string(),
+            status: LinkMessageStatus::Pending,
+            created_at: Utc::now(),
+            delivered_at: None,
+            read_at: None,
+        }
+    }
+}
+
 // ── Agent Handoff ──────────────────────────────────────────
 
 /// A handoff record — conversation control transfer.
@@ -417,6 +504,108 @@ impl LlmTrace {
     }
 }
 
+/// Aggregate LLM usage for one agent over a time window.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UsageStats {
+    pub agent_name: String,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub requests: u64,
+    /// Traces that recorded an error.
+    pub errors: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    pub avg_latency_ms: f64,
+}
+
+/// What usage summary rows are grouped by.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageGroupBy {
+    /// UTC calendar day, `YYYY-MM-DD`.
+    Day,
+    Provider,
+    Model,
+}
+
+impl std::fmt::Display for UsageGroupBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Day => write!(f, "day"),
+            Self::Provider => write!(f, "provider"),
+            Self::Model => write!(f, "model"),
+        }
+    }
+}
+
+impl std::str::FromStr for UsageGroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(Self::Day),
+            "provider" => Ok(Self::Provider),
+            "model" => Ok(Self::Model),
+            other => Err(format!(
+                "unknown group_by '{other}' (use day, provider or model)"
+            )),
+        }
+    }
+}
+
+/// LLM usage of all agents aggregated over one group — a day, provider or
+/// model, per [`UsageGroupBy`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UsageSummaryRow {
+    pub group: String,
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    pub avg_latency_ms: f64,
+}
+
+// ── Agent Notes ────────────────────────────────────────────
+
+/// A persistent key-value note owned by one agent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AgentNote {
+    pub agent_name: String,
+    pub key: String,
+    pub value: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ── Dead Letters ───────────────────────────────────────────
+
+/// A channel message the agent failed to process, kept so it can be
+/// replayed once the cause (e.g. a provider outage) is fixed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub id: String,
+    pub channel: String,
+    /// Orchestrator agent the message was for; `None` for the default agent.
+    #[serde(default)]
+    pub agent_name: Option<String>,
+    pub message: IncomingMessage,
+    pub error: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DeadLetter {
+    pub fn new(message: &IncomingMessage, agent_name: Option<&str>, error: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            channel: message.channel.clone(),
+            agent_name: agent_name.map(str::to_string),
+            message: message.clone(),
+            error: error.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
 // ── Lane-based Scheduler ───────────────────────────────────
 
 /// Execution lane for workload isolation.
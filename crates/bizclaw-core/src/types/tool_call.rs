@@ -17,6 +17,36 @@ pub struct FunctionCall {
     pub arguments: String,
 }
 
+impl ToolCall {
+    /// Build a function tool call. `arguments` is a JSON-encoded object.
+    pub fn function(id: impl Into<String>, name: impl Into<String>, arguments: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            r#type: "function".to_string(),
+            function: FunctionCall {
+                name: name.into(),
+                arguments: arguments.into(),
+            },
+        }
+    }
+}
+
+/// How the model is allowed to pick tools — translated by each provider
+/// into its native `tool_choice` / `tool_config` field.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolChoice {
+    /// Model decides whether to call tools.
+    #[default]
+    Auto,
+    /// Never call tools.
+    None,
+    /// Must call at least one tool.
+    Required,
+    /// Must call this specific tool.
+    Function(String),
+}
+
 /// Tool definition for LLM providers.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolDefinition {
@@ -31,4 +61,192 @@ pub struct ToolResult {
     pub tool_call_id: String,
     pub output: String,
     pub success: bool,
+    /// Structured payload (JSON, table, file reference) alongside `output`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<ToolData>,
+}
+
+/// Content types understood by [`ToolResult::render`].
+pub mod content_type {
+    pub const JSON: &str = "application/json";
+    /// `{"columns": [..], "rows": [[..], ..]}`
+    pub const TABLE: &str = "application/vnd.bizclaw.table+json";
+    /// `{"path": "..", "size": 123, "mime": ".."}`
+    pub const FILE_REF: &str = "application/vnd.bizclaw.file-ref+json";
+    /// `{"media_type": "image/png", "data": "<base64>"}` — forwarded to vision models.
+    pub const IMAGE: &str = "application/vnd.bizclaw.image+json";
+}
+
+/// Structured tool output with its content type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolData {
+    pub content_type: String,
+    pub value: serde_json::Value,
+}
+
+impl ToolResult {
+    /// Successful result carrying JSON data; `summary` is the plain-text part.
+    pub fn json(summary: impl Into<String>, value: serde_json::Value) -> Self {
+        Self::with_data(summary, content_type::JSON, value)
+    }
+
+    /// Successful result carrying structured data of any content type.
+    pub fn with_data(
+        summary: impl Into<String>,
+        content_type: &str,
+        value: serde_json::Value,
+    ) -> Self {
+        Self {
+            tool_call_id: String::new(),
+            output: summary.into(),
+            success: true,
+            data: Some(ToolData {
+                content_type: content_type.to_string(),
+                value,
+            }),
+        }
+    }
+
+    /// Successful result carrying an image (e.g. a screenshot or chart).
+    pub fn image(summary: impl Into<String>, image: super::ImageContent) -> Self {
+        Self::with_data(
+            summary,
+            content_type::IMAGE,
+            serde_json::to_value(image).unwrap_or_default(),
+        )
+    }
+
+    /// The image carried by this result, if any.
+    pub fn image_content(&self) -> Option<super::ImageContent> {
+        let data = self.data.as_ref()?;
+        if data.content_type != content_type::IMAGE {
+            return None;
+        }
+        serde_json::from_value(data.value.clone()).ok()
+    }
+
+    /// Text shown to the model: `output` followed by the rendered data.
+    pub fn render(&self) -> String {
+        let Some(data) = &self.data else {
+            return self.output.clone();
+        };
+        let rendered = match data.content_type.as_str() {
+            content_type::TABLE => render_table(&data.value),
+            content_type::FILE_REF => render_file_ref(&data.value),
+            // The image itself travels as a message attachment, not as text.
+            content_type::IMAGE => Some(format!(
+                "🖼️ Image ({})",
+                data.value["media_type"].as_str().unwrap_or("image")
+            )),
+            ct if ct.ends_with("json") => None,
+            _ => data.value.as_str().map(String::from),
+        }
+        .unwrap_or_else(|| {
+            let pretty = serde_json::to_string_pretty(&data.value)
+                .unwrap_or_else(|_| data.value.to_string());
+            format!("```json\n{pretty}\n```")
+        });
+        if self.output.trim().is_empty() {
+            rendered
+        } else {
+            format!("{}\n{rendered}", self.output)
+        }
+    }
+}
+
+/// Markdown table from `{"columns": [..], "rows": [[..], ..]}`.
+fn render_table(value: &serde_json::Value) -> Option<String> {
+    let cell = |v: &serde_json::Value| match v {
+        serde_json::Value::String(s) => s.replace('|', "\\|"),
+        other => other.to_string(),
+    };
+    let columns = value["columns"].as_array()?;
+    let rows = value["rows"].as_array()?;
+    let mut out = format!(
+        "| {} |\n|{}",
+        columns.iter().map(cell).collect::<Vec<_>>().join(" | "),
+        " --- |".repeat(columns.len())
+    );
+    for row in rows {
+        let cells = row.as_array()?;
+        out.push_str(&format!(
+            "\n| {} |",
+            cells.iter().map(cell).collect::<Vec<_>>().join(" | ")
+        ));
+    }
+    Some(out)
+}
+
+/// One-line summary of a file reference.
+fn render_file_ref(value: &serde_json::Value) -> Option<String> {
+    let path = value["path"].as_str()?;
+    let mut details = Vec::new();
+    if let Some(size) = value["size"].as_u64() {
+        details.push(format!("{size} bytes"));
+    }
+    if let Some(mime) = value["mime"].as_str() {
+        details.push(mime.to_string());
+    }
+    Some(if details.is_empty() {
+        format!("📎 File: {path}")
+    } else {
+        format!("📎 File: {path} ({})", details.join(", "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_data_keeps_content_type() {
+        let result = ToolResult::json("2 orders", serde_json::json!({"orders": [1, 2]}));
+        let wire = serde_json::to_string(&result).unwrap();
+        let back: ToolResult = serde_json::from_str(&wire).unwrap();
+        assert_eq!(back.data.as_ref().unwrap().content_type, content_type::JSON);
+        assert_eq!(back.data, result.data);
+
+        let rendered = back.render();
+        assert!(rendered.starts_with("2 orders\n```json\n"));
+        assert!(rendered.contains("\"orders\": ["));
+
+        // Plain results are unchanged and deserialize without `data`.
+        let plain: ToolResult =
+            serde_json::from_str(r#"{"tool_call_id": "", "output": "ok", "success": true}"#)
+                .unwrap();
+        assert!(plain.data.is_none());
+        assert_eq!(plain.render(), "ok");
+    }
+
+    #[test]
+    fn test_render_table_and_file_ref() {
+        let table = ToolResult::with_data(
+            "",
+            content_type::TABLE,
+            serde_json::json!({"columns": ["sku", "qty"], "rows": [["A|1", 3]]}),
+        );
+        assert_eq!(
+            table.render(),
+            "| sku | qty |\n| --- | --- |\n| A\\|1 | 3 |"
+        );
+
+        let file = ToolResult::with_data(
+            "Exported report",
+            content_type::FILE_REF,
+            serde_json::json!({"path": "out/report.csv", "size": 2048, "mime": "text/csv"}),
+        );
+        assert_eq!(
+            file.render(),
+            "Exported report\n📎 File: out/report.csv (2048 bytes, text/csv)"
+        );
+    }
+
+    #[test]
+    fn test_image_result() {
+        let image = crate::types::ImageContent::new("image/png", "iVBORw0KGgo=");
+        let result = ToolResult::image("Chart of sales", image.clone());
+        assert_eq!(result.image_content(), Some(image));
+        assert_eq!(result.render(), "Chart of sales\n🖼️ Image (image/png)");
+        assert!(ToolResult::json("x", serde_json::json!({})).image_content().is_none());
+    }
 }
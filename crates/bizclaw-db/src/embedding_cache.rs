@@ -0,0 +1,145 @@
+//! Embedding cache backed by a [`DataStore`].
+//!
+//! Entries older than the TTL are ignored on lookup and dropped when the
+//! cache is pruned. Pruning also drops the oldest entries beyond the size
+//! limit; it runs every [`PRUNE_EVERY`] writes, so the cache can briefly
+//! hold a few more entries than the limit.
+
+use async_trait::async_trait;
+use bizclaw_core::config::MemoryConfig;
+use bizclaw_core::error::Result;
+use bizclaw_core::traits::EmbeddingCache;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::store::DataStore;
+
+/// Writes between prunes.
+pub const PRUNE_EVERY: usize = 100;
+
+/// Limits of a [`StoreEmbeddingCache`].
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddingCacheConfig {
+    /// Ignore entries older than this. `None` = no expiry.
+    pub ttl: Option<Duration>,
+    /// Most entries kept.
+    pub max_entries: usize,
+}
+
+impl EmbeddingCacheConfig {
+    /// Limits from the `[memory]` config section, or `None` when the cache
+    /// is disabled (`embedding_cache_max_entries = 0`).
+    pub fn from_memory_config(config: &MemoryConfig) -> Option<Self> {
+        (config.embedding_cache_max_entries > 0).then(|| Self {
+            ttl: (config.embedding_cache_ttl_days > 0)
+                .then(|| Duration::from_secs(u64::from(config.embedding_cache_ttl_days) * 86_400)),
+            max_entries: config.embedding_cache_max_entries,
+        })
+    }
+}
+
+/// [`EmbeddingCache`] stored in the `embedding_cache` table.
+pub struct StoreEmbeddingCache {
+    store: Arc<dyn DataStore>,
+    config: EmbeddingCacheConfig,
+    writes: AtomicUsize,
+}
+
+impl StoreEmbeddingCache {
+    pub fn new(store: Arc<dyn DataStore>, config: EmbeddingCacheConfig) -> Self {
+        Self {
+            store,
+            config,
+            writes: AtomicUsize::new(0),
+        }
+    }
+
+    /// Drop expired entries and the oldest beyond the size limit. Returns
+    /// how many were dropped.
+    pub async fn prune(&self) -> Result<usize> {
+        self.store
+            .prune_embedding_cache(self.config.max_entries, self.cutoff())
+            .await
+    }
+
+    fn cutoff(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let ttl = chrono::Duration::from_std(self.config.ttl?).ok()?;
+        Some(chrono::Utc::now() - ttl)
+    }
+}
+
+#[async_trait]
+impl EmbeddingCache for StoreEmbeddingCache {
+    async fn get(&self, key: &str) -> Result<Option<Vec<f32>>> {
+        self.store.get_cached_embedding(key, self.cutoff()).await
+    }
+
+    async fn put(&self, key: &str, embedding: &[f32]) -> Result<()> {
+        self.store.put_cached_embedding(key, embedding).await?;
+        if self
+            .writes
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(PRUNE_EVERY)
+        {
+            self.prune().await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SqliteStore;
+
+    async fn new_cache(ttl: Option<Duration>, max_entries: usize) -> StoreEmbeddingCache {
+        let store = SqliteStore::in_memory().unwrap();
+        store.migrate().await.unwrap();
+        StoreEmbeddingCache::new(Arc::new(store), EmbeddingCacheConfig { ttl, max_entries })
+    }
+
+    #[tokio::test]
+    async fn test_put_then_get() {
+        let cache = new_cache(None, 10).await;
+        assert_eq!(cache.get("a").await.unwrap(), None);
+        cache.put("a", &[0.5, -0.25]).await.unwrap();
+        assert_eq!(cache.get("a").await.unwrap(), Some(vec![0.5, -0.25]));
+        cache.put("a", &[1.0]).await.unwrap();
+        assert_eq!(cache.get("a").await.unwrap(), Some(vec![1.0]));
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_newest_and_drops_expired() {
+        let cache = new_cache(None, 2).await;
+        for key in ["a", "b", "c"] {
+            cache.put(key, &[1.0]).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(cache.prune().await.unwrap(), 1);
+        assert_eq!(cache.get("a").await.unwrap(), None);
+        assert!(cache.get("c").await.unwrap().is_some());
+
+        let expired = new_cache(Some(Duration::ZERO), 10).await;
+        // The first write prunes; the second does not.
+        expired.put("a", &[1.0]).await.unwrap();
+        expired.put("b", &[1.0]).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(expired.get("b").await.unwrap(), None);
+        assert_eq!(expired.prune().await.unwrap(), 1);
+    }
+
+    #[test]
+    fn test_config_from_memory_config() {
+        let mut memory = MemoryConfig::default();
+        let config = EmbeddingCacheConfig::from_memory_config(&memory).unwrap();
+        assert_eq!(config.ttl, Some(Duration::from_secs(30 * 86_400)));
+        assert_eq!(config.max_entries, 10_000);
+
+        memory.embedding_cache_ttl_days = 0;
+        let config = EmbeddingCacheConfig::from_memory_config(&memory).unwrap();
+        assert_eq!(config.ttl, None);
+        memory.embedding_cache_max_entries = 0;
+        assert!(EmbeddingCacheConfig::from_memory_config(&memory).is_none());
+    }
+}
@@ -5,14 +5,20 @@
 //! - **PostgreSQL** (optional, managed mode) — multi-tenant, pgvector
 //!
 //! All orchestration data (delegations, teams, tasks, handoffs, traces)
-//! flows through this abstraction layer.
+//! flows through this abstraction layer. Schema changes are versioned in
+//! [`migrations`] and applied by `DataStore::migrate()`.
 
+pub mod embedding_cache;
+pub mod migrations;
 pub mod store;
 pub mod sqlite;
+pub mod trace_buffer;
 #[cfg(feature = "postgres")]
 pub mod postgres;
 
+pub use embedding_cache::{EmbeddingCacheConfig, StoreEmbeddingCache};
 pub use store::DataStore;
 pub use sqlite::SqliteStore;
+pub use trace_buffer::{TraceBuffer, TraceBufferConfig};
 #[cfg(feature = "postgres")]
 pub use postgres::PostgresStore;
@@ -0,0 +1,418 @@
+//! Versioned schema migrations for both DataStore backends.
+//!
+//! Each [`Migration`] has a version number and the SQL for SQLite and
+//! PostgreSQL. Applied versions are recorded in `schema_version`, so only
+//! pending steps run on startup. Never edit a released migration — append
+//! a new one instead.
+//!
+//! Databases created before versioning already contain the v1 tables; v1
+//! only uses `IF NOT EXISTS`, so it is a no-op for them and later steps
+//! (such as added columns) still apply.
+
+/// One ordered schema change.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sqlite: &'static str,
+    pub postgres: &'static str,
+}
+
+/// All migrations, in ascending version order.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "orchestration baseline schema",
+        sqlite: SQLITE_V1,
+        postgres: POSTGRES_V1,
+    },
+    Migration {
+        version: 2,
+        description: "add team_tasks.priority",
+        sqlite: "ALTER TABLE team_tasks ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;",
+        postgres: "ALTER TABLE team_tasks ADD COLUMN IF NOT EXISTS priority INTEGER NOT NULL DEFAULT 0;",
+    },
+    Migration {
+        version: 3,
+        description: "add delegation_events",
+        sqlite: "
+            CREATE TABLE IF NOT EXISTS delegation_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                delegation_id TEXT NOT NULL REFERENCES delegations(id) ON DELETE CASCADE,
+                kind TEXT NOT NULL,
+                message TEXT NOT NULL DEFAULT '',
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE INDEX IF NOT EXISTS idx_deleg_events ON delegation_events(delegation_id, id);
+        ",
+        postgres: "
+            CREATE TABLE IF NOT EXISTS delegation_events (
+                id BIGSERIAL PRIMARY KEY,
+                delegation_id TEXT NOT NULL REFERENCES delegations(id) ON DELETE CASCADE,
+                kind TEXT NOT NULL,
+                message TEXT NOT NULL DEFAULT '',
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+            CREATE INDEX IF NOT EXISTS idx_deleg_events ON delegation_events(delegation_id, id);
+        ",
+    },
+    Migration {
+        version: 4,
+        description: "add agent_notes",
+        sqlite: "
+            CREATE TABLE IF NOT EXISTS agent_notes (
+                agent_name TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (agent_name, key)
+            );
+        ",
+        postgres: "
+            CREATE TABLE IF NOT EXISTS agent_notes (
+                agent_name TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (agent_name, key)
+            );
+        ",
+    },
+    Migration {
+        version: 5,
+        description: "add channel_senders",
+        sqlite: "
+            CREATE TABLE IF NOT EXISTS channel_senders (
+                channel TEXT NOT NULL,
+                sender_id TEXT NOT NULL,
+                first_seen_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (channel, sender_id)
+            );
+        ",
+        postgres: "
+            CREATE TABLE IF NOT EXISTS channel_senders (
+                channel TEXT NOT NULL,
+                sender_id TEXT NOT NULL,
+                first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (channel, sender_id)
+            );
+        ",
+    },
+    Migration {
+        version: 6,
+        description: "add link_messages",
+        sqlite: "
+            CREATE TABLE IF NOT EXISTS link_messages (
+                id TEXT PRIMARY KEY,
+                link_id TEXT NOT NULL,
+                from_agent TEXT NOT NULL,
+                to_agent TEXT NOT NULL,
+                content TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                delivered_at TEXT,
+                read_at TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_link_messages_to ON link_messages(to_agent, status);
+            CREATE INDEX IF NOT EXISTS idx_link_messages_link ON link_messages(link_id, status);
+        ",
+        postgres: "
+            CREATE TABLE IF NOT EXISTS link_messages (
+                id TEXT PRIMARY KEY,
+                link_id TEXT NOT NULL,
+                from_agent TEXT NOT NULL,
+                to_agent TEXT NOT NULL,
+                content TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                delivered_at TIMESTAMPTZ,
+                read_at TIMESTAMPTZ
+            );
+            CREATE INDEX IF NOT EXISTS idx_link_messages_to ON link_messages(to_agent, status);
+            CREATE INDEX IF NOT EXISTS idx_link_messages_link ON link_messages(link_id, status);
+        ",
+    },
+    Migration {
+        version: 7,
+        description: "add embedding_cache",
+        sqlite: "
+            CREATE TABLE IF NOT EXISTS embedding_cache (
+                key TEXT PRIMARY KEY,
+                embedding BLOB NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE INDEX IF NOT EXISTS idx_embedding_cache_created ON embedding_cache(created_at);
+        ",
+        postgres: "
+            CREATE TABLE IF NOT EXISTS embedding_cache (
+                key TEXT PRIMARY KEY,
+                embedding BYTEA NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+            CREATE INDEX IF NOT EXISTS idx_embedding_cache_created ON embedding_cache(created_at);
+        ",
+    },
+    Migration {
+        version: 8,
+        description: "add dead_letters",
+        sqlite: "
+            CREATE TABLE IF NOT EXISTS dead_letters (
+                id TEXT PRIMARY KEY,
+                channel TEXT NOT NULL,
+                message TEXT NOT NULL,
+                error TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE INDEX IF NOT EXISTS idx_dead_letters_created ON dead_letters(created_at);
+        ",
+        postgres: "
+            CREATE TABLE IF NOT EXISTS dead_letters (
+                id TEXT PRIMARY KEY,
+                channel TEXT NOT NULL,
+                message JSONB NOT NULL,
+                error TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+            CREATE INDEX IF NOT EXISTS idx_dead_letters_created ON dead_letters(created_at);
+        ",
+    },
+    Migration {
+        version: 9,
+        description: "add dead_letters.agent_name",
+        sqlite: "ALTER TABLE dead_letters ADD COLUMN agent_name TEXT;",
+        postgres: "ALTER TABLE dead_letters ADD COLUMN IF NOT EXISTS agent_name TEXT;",
+    },
+];
+
+/// Table that records applied migrations (same DDL works on both backends).
+pub const SCHEMA_VERSION_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS schema_version (
+        version BIGINT PRIMARY KEY,
+        description TEXT NOT NULL,
+        applied_at TEXT NOT NULL
+    );
+";
+
+/// Latest schema version known to this build.
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Migrations newer than `current`, in order.
+pub fn pending(current: i64) -> impl Iterator<Item = &'static Migration> {
+    MIGRATIONS.iter().filter(move |m| m.version > current)
+}
+
+const SQLITE_V1: &str = "
+    CREATE TABLE IF NOT EXISTS agent_links (
+        id TEXT PRIMARY KEY,
+        source_agent TEXT NOT NULL,
+        target_agent TEXT NOT NULL,
+        direction TEXT NOT NULL DEFAULT 'outbound',
+        max_concurrent INTEGER NOT NULL DEFAULT 3,
+        settings TEXT NOT NULL DEFAULT '{}',
+        created_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    CREATE INDEX IF NOT EXISTS idx_links_source ON agent_links(source_agent);
+    CREATE INDEX IF NOT EXISTS idx_links_target ON agent_links(target_agent);
+
+    CREATE TABLE IF NOT EXISTS delegations (
+        id TEXT PRIMARY KEY,
+        from_agent TEXT NOT NULL,
+        to_agent TEXT NOT NULL,
+        task TEXT NOT NULL,
+        mode TEXT NOT NULL DEFAULT 'sync',
+        status TEXT NOT NULL DEFAULT 'pending',
+        result TEXT,
+        error TEXT,
+        created_at TEXT NOT NULL DEFAULT (datetime('now')),
+        completed_at TEXT
+    );
+    CREATE INDEX IF NOT EXISTS idx_deleg_from ON delegations(from_agent);
+    CREATE INDEX IF NOT EXISTS idx_deleg_to ON delegations(to_agent);
+    CREATE INDEX IF NOT EXISTS idx_deleg_status ON delegations(status);
+
+    CREATE TABLE IF NOT EXISTS teams (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE,
+        description TEXT NOT NULL DEFAULT '',
+        members TEXT NOT NULL DEFAULT '[]',
+        created_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+
+    CREATE TABLE IF NOT EXISTS team_tasks (
+        id TEXT PRIMARY KEY,
+        team_id TEXT NOT NULL,
+        title TEXT NOT NULL,
+        description TEXT NOT NULL DEFAULT '',
+        status TEXT NOT NULL DEFAULT 'pending',
+        created_by TEXT NOT NULL,
+        assigned_to TEXT,
+        blocked_by TEXT NOT NULL DEFAULT '[]',
+        result TEXT,
+        created_at TEXT NOT NULL DEFAULT (datetime('now')),
+        updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+        FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE CASCADE
+    );
+    CREATE INDEX IF NOT EXISTS idx_tasks_team ON team_tasks(team_id);
+    CREATE INDEX IF NOT EXISTS idx_tasks_assigned ON team_tasks(assigned_to);
+
+    CREATE TABLE IF NOT EXISTS team_messages (
+        id TEXT PRIMARY KEY,
+        team_id TEXT NOT NULL,
+        from_agent TEXT NOT NULL,
+        to_agent TEXT,
+        content TEXT NOT NULL,
+        read INTEGER NOT NULL DEFAULT 0,
+        created_at TEXT NOT NULL DEFAULT (datetime('now')),
+        FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE CASCADE
+    );
+
+    CREATE TABLE IF NOT EXISTS handoffs (
+        id TEXT PRIMARY KEY,
+        from_agent TEXT NOT NULL,
+        to_agent TEXT NOT NULL,
+        session_id TEXT NOT NULL,
+        reason TEXT,
+        context_summary TEXT,
+        active INTEGER NOT NULL DEFAULT 1,
+        created_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    CREATE INDEX IF NOT EXISTS idx_handoff_session ON handoffs(session_id, active);
+
+    CREATE TABLE IF NOT EXISTS llm_traces (
+        id TEXT PRIMARY KEY,
+        agent_name TEXT NOT NULL,
+        provider TEXT NOT NULL,
+        model TEXT NOT NULL,
+        prompt_tokens INTEGER NOT NULL DEFAULT 0,
+        completion_tokens INTEGER NOT NULL DEFAULT 0,
+        total_tokens INTEGER NOT NULL DEFAULT 0,
+        latency_ms INTEGER NOT NULL DEFAULT 0,
+        cache_hit INTEGER NOT NULL DEFAULT 0,
+        cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+        cache_write_tokens INTEGER NOT NULL DEFAULT 0,
+        status TEXT NOT NULL DEFAULT 'pending',
+        error TEXT,
+        metadata TEXT NOT NULL DEFAULT '{}',
+        created_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    CREATE INDEX IF NOT EXISTS idx_traces_agent ON llm_traces(agent_name);
+    CREATE INDEX IF NOT EXISTS idx_traces_time ON llm_traces(created_at DESC);
+";
+
+const POSTGRES_V1: &str = r#"
+    CREATE TABLE IF NOT EXISTS agent_links (
+        id TEXT PRIMARY KEY,
+        source_agent TEXT NOT NULL,
+        target_agent TEXT NOT NULL,
+        direction TEXT NOT NULL DEFAULT 'outbound',
+        max_concurrent INTEGER NOT NULL DEFAULT 3,
+        settings JSONB NOT NULL DEFAULT '{}',
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
+    CREATE INDEX IF NOT EXISTS idx_links_source ON agent_links(source_agent);
+    CREATE INDEX IF NOT EXISTS idx_links_target ON agent_links(target_agent);
+
+    CREATE TABLE IF NOT EXISTS delegations (
+        id TEXT PRIMARY KEY,
+        from_agent TEXT NOT NULL,
+        to_agent TEXT NOT NULL,
+        task TEXT NOT NULL,
+        mode TEXT NOT NULL DEFAULT 'sync',
+        status TEXT NOT NULL DEFAULT 'pending',
+        result TEXT,
+        error TEXT,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        completed_at TIMESTAMPTZ
+    );
+    CREATE INDEX IF NOT EXISTS idx_deleg_from ON delegations(from_agent);
+    CREATE INDEX IF NOT EXISTS idx_deleg_to ON delegations(to_agent);
+    CREATE INDEX IF NOT EXISTS idx_deleg_status ON delegations(status);
+
+    CREATE TABLE IF NOT EXISTS teams (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE,
+        description TEXT NOT NULL DEFAULT '',
+        members JSONB NOT NULL DEFAULT '[]',
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
+
+    CREATE TABLE IF NOT EXISTS team_tasks (
+        id TEXT PRIMARY KEY,
+        team_id TEXT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+        title TEXT NOT NULL,
+        description TEXT NOT NULL DEFAULT '',
+        status TEXT NOT NULL DEFAULT 'pending',
+        created_by TEXT NOT NULL,
+        assigned_to TEXT,
+        blocked_by JSONB NOT NULL DEFAULT '[]',
+        result TEXT,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
+    CREATE INDEX IF NOT EXISTS idx_tasks_team ON team_tasks(team_id);
+    CREATE INDEX IF NOT EXISTS idx_tasks_assigned ON team_tasks(assigned_to);
+
+    CREATE TABLE IF NOT EXISTS team_messages (
+        id TEXT PRIMARY KEY,
+        team_id TEXT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+        from_agent TEXT NOT NULL,
+        to_agent TEXT,
+        content TEXT NOT NULL,
+        read BOOLEAN NOT NULL DEFAULT FALSE,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
+
+    CREATE TABLE IF NOT EXISTS handoffs (
+        id TEXT PRIMARY KEY,
+        from_agent TEXT NOT NULL,
+        to_agent TEXT NOT NULL,
+        session_id TEXT NOT NULL,
+        reason TEXT,
+        context_summary TEXT,
+        active BOOLEAN NOT NULL DEFAULT TRUE,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
+    CREATE INDEX IF NOT EXISTS idx_handoff_session ON handoffs(session_id, active);
+
+    CREATE TABLE IF NOT EXISTS llm_traces (
+        id TEXT PRIMARY KEY,
+        agent_name TEXT NOT NULL,
+        provider TEXT NOT NULL,
+        model TEXT NOT NULL,
+        prompt_tokens INTEGER NOT NULL DEFAULT 0,
+        completion_tokens INTEGER NOT NULL DEFAULT 0,
+        total_tokens INTEGER NOT NULL DEFAULT 0,
+        latency_ms BIGINT NOT NULL DEFAULT 0,
+        cache_hit BOOLEAN NOT NULL DEFAULT FALSE,
+        cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+        cache_write_tokens INTEGER NOT NULL DEFAULT 0,
+        status TEXT NOT NULL DEFAULT 'pending',
+        error TEXT,
+        metadata JSONB NOT NULL DEFAULT '{}',
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
+    CREATE INDEX IF NOT EXISTS idx_traces_agent ON llm_traces(agent_name);
+    CREATE INDEX IF NOT EXISTS idx_traces_time ON llm_traces(created_at DESC);
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_strictly_increasing() {
+        for pair in MIGRATIONS.windows(2) {
+            assert!(pair[0].version < pair[1].version);
+        }
+        assert_eq!(MIGRATIONS[0].version, 1);
+    }
+
+    #[test]
+    fn test_pending() {
+        assert_eq!(pending(0).count(), MIGRATIONS.len());
+        assert_eq!(pending(latest_version()).count(), 0);
+        assert_eq!(pending(1).next().map(|m| m.version), Some(2));
+    }
+}
@@ -5,10 +5,12 @@
 
 use async_trait::async_trait;
 use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::traits::embedding_cache::{embedding_from_bytes, embedding_to_bytes};
 use bizclaw_core::types::*;
 use sqlx::postgres::PgPoolOptions;
-use sqlx::{PgPool, Row};
+use sqlx::{Executor, PgPool, Row};
 
+use crate::migrations;
 use crate::store::DataStore;
 
 /// PostgreSQL-backed data store for managed multi-tenant mode.
@@ -41,129 +43,44 @@ impl DataStore for PostgresStore {
     }
 
     async fn migrate(&self) -> Result<()> {
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS agent_links (
-                id TEXT PRIMARY KEY,
-                source_agent TEXT NOT NULL,
-                target_agent TEXT NOT NULL,
-                direction TEXT NOT NULL DEFAULT 'outbound',
-                max_concurrent INTEGER NOT NULL DEFAULT 3,
-                settings JSONB NOT NULL DEFAULT '{}',
-                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
-            );
-            CREATE INDEX IF NOT EXISTS idx_links_source ON agent_links(source_agent);
-            CREATE INDEX IF NOT EXISTS idx_links_target ON agent_links(target_agent);
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| BizClawError::Database(format!("Migrate agent_links: {e}")))?;
-
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS delegations (
-                id TEXT PRIMARY KEY,
-                from_agent TEXT NOT NULL,
-                to_agent TEXT NOT NULL,
-                task TEXT NOT NULL,
-                mode TEXT NOT NULL DEFAULT 'sync',
-                status TEXT NOT NULL DEFAULT 'pending',
-                result TEXT,
-                error TEXT,
-                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-                completed_at TIMESTAMPTZ
-            );
-            CREATE INDEX IF NOT EXISTS idx_deleg_from ON delegations(from_agent);
-            CREATE INDEX IF NOT EXISTS idx_deleg_to ON delegations(to_agent);
-            CREATE INDEX IF NOT EXISTS idx_deleg_status ON delegations(status);
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| BizClawError::Database(format!("Migrate delegations: {e}")))?;
-
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS teams (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL UNIQUE,
-                description TEXT NOT NULL DEFAULT '',
-                members JSONB NOT NULL DEFAULT '[]',
-                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
-            );
-
-            CREATE TABLE IF NOT EXISTS team_tasks (
-                id TEXT PRIMARY KEY,
-                team_id TEXT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
-                title TEXT NOT NULL,
-                description TEXT NOT NULL DEFAULT '',
-                status TEXT NOT NULL DEFAULT 'pending',
-                created_by TEXT NOT NULL,
-                assigned_to TEXT,
-                blocked_by JSONB NOT NULL DEFAULT '[]',
-                result TEXT,
-                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
-            );
-            CREATE INDEX IF NOT EXISTS idx_tasks_team ON team_tasks(team_id);
-            CREATE INDEX IF NOT EXISTS idx_tasks_assigned ON team_tasks(assigned_to);
-
-            CREATE TABLE IF NOT EXISTS team_messages (
-                id TEXT PRIMARY KEY,
-                team_id TEXT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
-                from_agent TEXT NOT NULL,
-                to_agent TEXT,
-                content TEXT NOT NULL,
-                read BOOLEAN NOT NULL DEFAULT FALSE,
-                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
-            );
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| BizClawError::Database(format!("Migrate teams: {e}")))?;
+        self.pool
+            .execute(migrations::SCHEMA_VERSION_TABLE)
+            .await
+            .map_err(|e| BizClawError::Database(format!("Migrate schema_version: {e}")))?;
+        let current: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM schema_version")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| BizClawError::Database(format!("Schema version: {e}")))?;
 
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS handoffs (
-                id TEXT PRIMARY KEY,
-                from_agent TEXT NOT NULL,
-                to_agent TEXT NOT NULL,
-                session_id TEXT NOT NULL,
-                reason TEXT,
-                context_summary TEXT,
-                active BOOLEAN NOT NULL DEFAULT TRUE,
-                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
-            );
-            CREATE INDEX IF NOT EXISTS idx_handoff_session ON handoffs(session_id, active);
-
-            CREATE TABLE IF NOT EXISTS llm_traces (
-                id TEXT PRIMARY KEY,
-                agent_name TEXT NOT NULL,
-                provider TEXT NOT NULL,
-                model TEXT NOT NULL,
-                prompt_tokens INTEGER NOT NULL DEFAULT 0,
-                completion_tokens INTEGER NOT NULL DEFAULT 0,
-                total_tokens INTEGER NOT NULL DEFAULT 0,
-                latency_ms BIGINT NOT NULL DEFAULT 0,
-                cache_hit BOOLEAN NOT NULL DEFAULT FALSE,
-                cache_read_tokens INTEGER NOT NULL DEFAULT 0,
-                cache_write_tokens INTEGER NOT NULL DEFAULT 0,
-                status TEXT NOT NULL DEFAULT 'pending',
-                error TEXT,
-                metadata JSONB NOT NULL DEFAULT '{}',
-                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
-            );
-            CREATE INDEX IF NOT EXISTS idx_traces_agent ON llm_traces(agent_name);
-            CREATE INDEX IF NOT EXISTS idx_traces_time ON llm_traces(created_at DESC);
-            "#,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| BizClawError::Database(format!("Migrate handoffs+traces: {e}")))?;
+        for m in migrations::pending(current) {
+            let mut tx = self
+                .pool
+                .begin()
+                .await
+                .map_err(|e| BizClawError::Database(format!("Migration tx: {e}")))?;
+            (&mut *tx)
+                .execute(m.postgres)
+                .await
+                .map_err(|e| {
+                    BizClawError::Database(format!("Migration v{} ({}): {e}", m.version, m.description))
+                })?;
+            sqlx::query("INSERT INTO schema_version (version, description, applied_at) VALUES ($1, $2, $3)")
+                .bind(m.version)
+                .bind(m.description)
+                .bind(chrono::Utc::now().to_rfc3339())
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| BizClawError::Database(format!("Record migration: {e}")))?;
+            tx.commit()
+                .await
+                .map_err(|e| BizClawError::Database(format!("Migration commit: {e}")))?;
+            tracing::info!("PostgreSQL migration v{} applied: {}", m.version, m.description);
+        }
 
-        tracing::info!("PostgreSQL orchestration schema migrated");
+        tracing::info!(
+            "PostgreSQL orchestration schema at v{}",
+            migrations::latest_version()
+        );
         Ok(())
     }
 
@@ -209,15 +126,7 @@ impl DataStore for PostgresStore {
 
         Ok(rows
             .iter()
-            .map(|r| AgentLink {
-                id: r.get("id"),
-                source_agent: r.get("source_agent"),
-                target_agent: r.get("target_agent"),
-                direction: parse_direction(&r.get::<String, _>("direction")),
-                max_concurrent: r.get::<i32, _>("max_concurrent") as u32,
-                settings: r.get("settings"),
-                created_at: r.get("created_at"),
-            })
+            .map(row_to_link)
             .collect())
     }
 
@@ -232,15 +141,7 @@ impl DataStore for PostgresStore {
 
         Ok(rows
             .iter()
-            .map(|r| AgentLink {
-                id: r.get("id"),
-                source_agent: r.get("source_agent"),
-                target_agent: r.get("target_agent"),
-                direction: parse_direction(&r.get::<String, _>("direction")),
-                max_concurrent: r.get::<i32, _>("max_concurrent") as u32,
-                settings: r.get("settings"),
-                created_at: r.get("created_at"),
-            })
+            .map(row_to_link)
             .collect())
     }
 
@@ -357,6 +258,63 @@ impl DataStore for PostgresStore {
         Ok(row.get::<i32, _>("cnt") as u32)
     }
 
+    async fn fail_stale_delegations(
+        &self,
+        to_agent: &str,
+        before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32> {
+        let done = sqlx::query(
+            "UPDATE delegations SET status = 'failed', error = 'Delegation abandoned', completed_at = NOW()
+             WHERE to_agent = $1 AND status IN ('pending', 'running') AND created_at < $2",
+        )
+        .bind(to_agent)
+        .bind(before)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Fail stale delegations: {e}")))?;
+        Ok(done.rows_affected() as u32)
+    }
+
+    async fn append_delegation_event(
+        &self,
+        delegation_id: &str,
+        event: &DelegationEvent,
+    ) -> Result<()> {
+        let kind = serde_json::to_string(&event.kind).unwrap_or_default().trim_matches('"').to_string();
+        sqlx::query(
+            "INSERT INTO delegation_events (delegation_id, kind, message, created_at)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(delegation_id)
+        .bind(&kind)
+        .bind(&event.message)
+        .bind(event.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Append delegation event: {e}")))?;
+        Ok(())
+    }
+
+    async fn list_delegation_events(&self, delegation_id: &str) -> Result<Vec<DelegationEvent>> {
+        let rows = sqlx::query(
+            "SELECT kind, message, created_at FROM delegation_events
+             WHERE delegation_id = $1 ORDER BY id",
+        )
+        .bind(delegation_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("List delegation events: {e}")))?;
+
+        Ok(rows
+            .iter()
+            .map(|r| DelegationEvent {
+                kind: parse_delegation_event_kind(&r.get::<String, _>("kind")),
+                message: r.get("message"),
+                created_at: r.get("created_at"),
+            })
+            .collect())
+    }
+
     // ── Teams ──────────────────────────────────────────────
 
     async fn create_team(&self, team: &AgentTeam) -> Result<()> {
@@ -477,6 +435,19 @@ impl DataStore for PostgresStore {
         Ok(())
     }
 
+    async fn claim_task(&self, id: &str, agent_name: &str) -> Result<bool> {
+        let claimed = sqlx::query(
+            "UPDATE team_tasks SET status = 'in_progress', assigned_to = $1, updated_at = NOW()
+             WHERE id = $2 AND (assigned_to IS NULL OR status = 'pending')",
+        )
+        .bind(agent_name)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Claim task: {e}")))?;
+        Ok(claimed.rows_affected() == 1)
+    }
+
     async fn get_task(&self, id: &str) -> Result<Option<TeamTask>> {
         let row = sqlx::query(
             "SELECT id, team_id, title, description, status, created_by, assigned_to, blocked_by, result, created_at, updated_at
@@ -613,6 +584,119 @@ impl DataStore for PostgresStore {
         Ok(())
     }
 
+    // ── Link Messages ──────────────────────────────────────
+
+    async fn send_link_message(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        content: &str,
+    ) -> Result<LinkMessage> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| BizClawError::Database(format!("Begin: {e}")))?;
+        // Locking the link rows serializes senders, so concurrent sends
+        // cannot both slip past `max_concurrent`.
+        let rows = sqlx::query(
+            "SELECT id, source_agent, target_agent, direction, max_concurrent, settings, created_at
+             FROM agent_links
+             WHERE (source_agent = $1 AND target_agent = $2)
+                OR (source_agent = $2 AND target_agent = $1)
+             ORDER BY created_at
+             FOR UPDATE",
+        )
+        .bind(from_agent)
+        .bind(to_agent)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Find link: {e}")))?;
+        let link = rows
+            .iter()
+            .map(row_to_link)
+            .find(|link| link.allows(from_agent, to_agent))
+            .ok_or_else(|| {
+                BizClawError::NoPermission(format!(
+                    "no link allows {from_agent} to message {to_agent}"
+                ))
+            })?;
+
+        let pending: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM link_messages WHERE link_id = $1 AND status = 'pending'",
+        )
+        .bind(&link.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Count link messages: {e}")))?;
+        if pending >= link.max_concurrent as i64 {
+            return Err(BizClawError::RateLimited(format!(
+                "link {} has {pending} undelivered messages (max {})",
+                link.id, link.max_concurrent
+            )));
+        }
+
+        let msg = LinkMessage::new(&link, from_agent, to_agent, content);
+        sqlx::query(
+            "INSERT INTO link_messages (id, link_id, from_agent, to_agent, content, status, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(&msg.id)
+        .bind(&msg.link_id)
+        .bind(&msg.from_agent)
+        .bind(&msg.to_agent)
+        .bind(&msg.content)
+        .bind(msg.status.to_string())
+        .bind(msg.created_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Send link message: {e}")))?;
+        tx.commit()
+            .await
+            .map_err(|e| BizClawError::Database(format!("Commit: {e}")))?;
+        Ok(msg)
+    }
+
+    async fn fetch_link_messages(&self, agent_name: &str) -> Result<Vec<LinkMessage>> {
+        let rows = sqlx::query(
+            "UPDATE link_messages SET status = 'delivered', delivered_at = NOW()
+             WHERE to_agent = $1 AND status = 'pending'
+             RETURNING id, link_id, from_agent, to_agent, content, status, created_at, delivered_at, read_at",
+        )
+        .bind(agent_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Fetch link messages: {e}")))?;
+        let mut messages: Vec<LinkMessage> = rows.iter().map(row_to_link_message).collect();
+        messages.sort_by_key(|m| m.created_at);
+        Ok(messages)
+    }
+
+    async fn get_link_message(&self, id: &str) -> Result<Option<LinkMessage>> {
+        let row = sqlx::query(
+            "SELECT id, link_id, from_agent, to_agent, content, status, created_at, delivered_at, read_at
+             FROM link_messages WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Get link message: {e}")))?;
+        Ok(row.map(|r| row_to_link_message(&r)))
+    }
+
+    async fn mark_link_messages_read(&self, message_ids: &[String]) -> Result<()> {
+        sqlx::query(
+            "UPDATE link_messages
+             SET status = 'read', read_at = NOW(), delivered_at = COALESCE(delivered_at, NOW())
+             WHERE id = ANY($1) AND status != 'read'",
+        )
+        .bind(message_ids)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Mark link messages read: {e}")))?;
+        Ok(())
+    }
+
     // ── Handoffs ───────────────────────────────────────────
 
     async fn create_handoff(&self, h: &Handoff) -> Result<()> {
@@ -649,16 +733,7 @@ impl DataStore for PostgresStore {
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| BizClawError::Database(format!("Active handoff: {e}")))?;
-        Ok(row.map(|r| Handoff {
-            id: r.get("id"),
-            from_agent: r.get("from_agent"),
-            to_agent: r.get("to_agent"),
-            session_id: r.get("session_id"),
-            reason: r.get("reason"),
-            context_summary: r.get("context_summary"),
-            active: r.get("active"),
-            created_at: r.get("created_at"),
-        }))
+        Ok(row.as_ref().map(row_to_handoff))
     }
 
     async fn clear_handoff(&self, session_id: &str) -> Result<()> {
@@ -670,32 +745,37 @@ impl DataStore for PostgresStore {
         Ok(())
     }
 
+    async fn list_handoffs(&self, session_id: &str) -> Result<Vec<Handoff>> {
+        let rows = sqlx::query(
+            "SELECT id, from_agent, to_agent, session_id, reason, context_summary, active, created_at
+             FROM handoffs WHERE session_id = $1
+             ORDER BY created_at",
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("List handoffs: {e}")))?;
+        Ok(rows.iter().map(row_to_handoff).collect())
+    }
+
     // ── LLM Traces ─────────────────────────────────────────
 
     async fn record_trace(&self, t: &LlmTrace) -> Result<()> {
-        sqlx::query(
-            "INSERT INTO llm_traces (id, agent_name, provider, model, prompt_tokens, completion_tokens, total_tokens, latency_ms, cache_hit, cache_read_tokens, cache_write_tokens, status, error, metadata, created_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
-        )
-        .bind(&t.id)
-        .bind(&t.agent_name)
-        .bind(&t.provider)
-        .bind(&t.model)
-        .bind(t.prompt_tokens as i32)
-        .bind(t.completion_tokens as i32)
-        .bind(t.total_tokens as i32)
-        .bind(t.latency_ms as i64)
-        .bind(t.cache_hit)
-        .bind(t.cache_read_tokens as i32)
-        .bind(t.cache_write_tokens as i32)
-        .bind(&t.status)
-        .bind(&t.error)
-        .bind(&t.metadata)
-        .bind(t.created_at)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| BizClawError::Database(format!("Record trace: {e}")))?;
-        Ok(())
+        insert_trace(&self.pool, t).await
+    }
+
+    async fn record_traces(&self, traces: &[LlmTrace]) -> Result<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| BizClawError::Database(format!("Record traces tx: {e}")))?;
+        for t in traces {
+            insert_trace(&mut *tx, t).await?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| BizClawError::Database(format!("Record traces commit: {e}")))
     }
 
     async fn list_traces(&self, limit: usize) -> Result<Vec<LlmTrace>> {
@@ -762,6 +842,297 @@ impl DataStore for PostgresStore {
             })
             .collect())
     }
+
+    async fn usage_stats(
+        &self,
+        agent_name: &str,
+        since: Option<chrono::DateTime<chrono::Utc>>,
+        until: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<UsageStats> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS requests,
+                    COUNT(error) AS errors,
+                    COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+                    COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens,
+                    COALESCE(SUM(total_tokens), 0)::BIGINT AS total_tokens,
+                    COALESCE(AVG(latency_ms), 0)::DOUBLE PRECISION AS avg_latency_ms
+             FROM llm_traces
+             WHERE agent_name = $1
+               AND ($2::TIMESTAMPTZ IS NULL OR created_at >= $2)
+               AND ($3::TIMESTAMPTZ IS NULL OR created_at < $3)",
+        )
+        .bind(agent_name)
+        .bind(since)
+        .bind(until)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Usage stats: {e}")))?;
+        Ok(UsageStats {
+            agent_name: agent_name.to_string(),
+            since,
+            until,
+            requests: row.get::<i64, _>("requests") as u64,
+            errors: row.get::<i64, _>("errors") as u64,
+            prompt_tokens: row.get::<i64, _>("prompt_tokens") as u64,
+            completion_tokens: row.get::<i64, _>("completion_tokens") as u64,
+            total_tokens: row.get::<i64, _>("total_tokens") as u64,
+            avg_latency_ms: row.get("avg_latency_ms"),
+        })
+    }
+
+    async fn usage_summary(
+        &self,
+        group_by: UsageGroupBy,
+        since: Option<chrono::DateTime<chrono::Utc>>,
+        until: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Vec<UsageSummaryRow>> {
+        let group = match group_by {
+            UsageGroupBy::Day => "to_char(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD')",
+            UsageGroupBy::Provider => "provider",
+            UsageGroupBy::Model => "model",
+        };
+        let rows = sqlx::query(&format!(
+            "SELECT {group} AS grp,
+                    COUNT(*) AS requests,
+                    COALESCE(SUM(prompt_tokens), 0)::BIGINT AS prompt_tokens,
+                    COALESCE(SUM(completion_tokens), 0)::BIGINT AS completion_tokens,
+                    COALESCE(SUM(total_tokens), 0)::BIGINT AS total_tokens,
+                    COALESCE(AVG(latency_ms), 0)::DOUBLE PRECISION AS avg_latency_ms
+             FROM llm_traces
+             WHERE ($1::TIMESTAMPTZ IS NULL OR created_at >= $1)
+               AND ($2::TIMESTAMPTZ IS NULL OR created_at < $2)
+             GROUP BY grp ORDER BY grp"
+        ))
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Usage summary: {e}")))?;
+        Ok(rows
+            .iter()
+            .map(|r| UsageSummaryRow {
+                group: r.get("grp"),
+                requests: r.get::<i64, _>("requests") as u64,
+                prompt_tokens: r.get::<i64, _>("prompt_tokens") as u64,
+                completion_tokens: r.get::<i64, _>("completion_tokens") as u64,
+                total_tokens: r.get::<i64, _>("total_tokens") as u64,
+                avg_latency_ms: r.get("avg_latency_ms"),
+            })
+            .collect())
+    }
+
+    // ── Agent Notes ────────────────────────────────────────
+
+    async fn set_note(&self, agent_name: &str, key: &str, value: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO agent_notes (agent_name, key, value, updated_at) VALUES ($1, $2, $3, NOW())
+             ON CONFLICT (agent_name, key) DO UPDATE SET value = $3, updated_at = NOW()",
+        )
+        .bind(agent_name)
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Set note: {e}")))?;
+        Ok(())
+    }
+
+    async fn get_note(&self, agent_name: &str, key: &str) -> Result<Option<AgentNote>> {
+        let row = sqlx::query(
+            "SELECT agent_name, key, value, updated_at FROM agent_notes
+             WHERE agent_name = $1 AND key = $2",
+        )
+        .bind(agent_name)
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Get note: {e}")))?;
+        Ok(row.map(|r| row_to_note(&r)))
+    }
+
+    async fn list_notes(&self, agent_name: &str) -> Result<Vec<AgentNote>> {
+        let rows = sqlx::query(
+            "SELECT agent_name, key, value, updated_at FROM agent_notes
+             WHERE agent_name = $1 ORDER BY key",
+        )
+        .bind(agent_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("List notes: {e}")))?;
+        Ok(rows.iter().map(row_to_note).collect())
+    }
+
+    async fn delete_note(&self, agent_name: &str, key: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM agent_notes WHERE agent_name = $1 AND key = $2")
+            .bind(agent_name)
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| BizClawError::Database(format!("Delete note: {e}")))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ── Channel Senders ────────────────────────────────────
+
+    async fn mark_sender_seen(&self, channel: &str, sender_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO channel_senders (channel, sender_id) VALUES ($1, $2)
+             ON CONFLICT (channel, sender_id) DO NOTHING",
+        )
+        .bind(channel)
+        .bind(sender_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Mark sender seen: {e}")))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ── Embedding Cache ────────────────────────────────────
+
+    async fn get_cached_embedding(
+        &self,
+        key: &str,
+        since: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Option<Vec<f32>>> {
+        let bytes: Option<Vec<u8>> = sqlx::query_scalar(
+            "SELECT embedding FROM embedding_cache
+             WHERE key = $1 AND ($2::TIMESTAMPTZ IS NULL OR created_at >= $2)",
+        )
+        .bind(key)
+        .bind(since)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Get cached embedding: {e}")))?;
+        Ok(bytes.map(|b| embedding_from_bytes(&b)))
+    }
+
+    async fn put_cached_embedding(&self, key: &str, embedding: &[f32]) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO embedding_cache (key, embedding, created_at) VALUES ($1, $2, NOW())
+             ON CONFLICT (key) DO UPDATE SET embedding = $2, created_at = NOW()",
+        )
+        .bind(key)
+        .bind(embedding_to_bytes(embedding))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Cache embedding: {e}")))?;
+        Ok(())
+    }
+
+    async fn prune_embedding_cache(
+        &self,
+        max_entries: usize,
+        before: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<usize> {
+        let mut dropped = 0;
+        if let Some(before) = before {
+            dropped += sqlx::query("DELETE FROM embedding_cache WHERE created_at < $1")
+                .bind(before)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| BizClawError::Database(format!("Expire embeddings: {e}")))?
+                .rows_affected();
+        }
+        dropped += sqlx::query(
+            "DELETE FROM embedding_cache WHERE key IN (
+                 SELECT key FROM embedding_cache ORDER BY created_at DESC OFFSET $1
+             )",
+        )
+        .bind(max_entries as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Prune embeddings: {e}")))?
+        .rows_affected();
+        Ok(dropped as usize)
+    }
+
+    // ── Dead Letters ───────────────────────────────────────
+
+    async fn push_dead_letter(&self, letter: &DeadLetter, max_entries: usize) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO dead_letters (id, channel, agent_name, message, error, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&letter.id)
+        .bind(&letter.channel)
+        .bind(&letter.agent_name)
+        .bind(sqlx::types::Json(&letter.message))
+        .bind(&letter.error)
+        .bind(letter.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Push dead letter: {e}")))?;
+        sqlx::query(
+            "DELETE FROM dead_letters WHERE id IN (
+                 SELECT id FROM dead_letters ORDER BY created_at DESC OFFSET $1
+             )",
+        )
+        .bind(max_entries as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Trim dead letters: {e}")))?;
+        Ok(())
+    }
+
+    async fn list_dead_letters(&self, limit: usize) -> Result<Vec<DeadLetter>> {
+        let rows = sqlx::query(
+            "SELECT id, channel, agent_name, message, error, created_at FROM dead_letters
+             ORDER BY created_at DESC LIMIT $1",
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("List dead letters: {e}")))?;
+        Ok(rows.iter().map(row_to_dead_letter).collect())
+    }
+
+    async fn get_dead_letter(&self, id: &str) -> Result<Option<DeadLetter>> {
+        let row = sqlx::query(
+            "SELECT id, channel, agent_name, message, error, created_at FROM dead_letters
+             WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Database(format!("Get dead letter: {e}")))?;
+        Ok(row.map(|r| row_to_dead_letter(&r)))
+    }
+
+    async fn delete_dead_letter(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM dead_letters WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| BizClawError::Database(format!("Delete dead letter: {e}")))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Insert one trace through the pool or an open transaction.
+async fn insert_trace<'e, E: sqlx::PgExecutor<'e>>(executor: E, t: &LlmTrace) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO llm_traces (id, agent_name, provider, model, prompt_tokens, completion_tokens, total_tokens, latency_ms, cache_hit, cache_read_tokens, cache_write_tokens, status, error, metadata, created_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
+    )
+    .bind(&t.id)
+    .bind(&t.agent_name)
+    .bind(&t.provider)
+    .bind(&t.model)
+    .bind(t.prompt_tokens as i32)
+    .bind(t.completion_tokens as i32)
+    .bind(t.total_tokens as i32)
+    .bind(t.latency_ms as i64)
+    .bind(t.cache_hit)
+    .bind(t.cache_read_tokens as i32)
+    .bind(t.cache_write_tokens as i32)
+    .bind(&t.status)
+    .bind(&t.error)
+    .bind(&t.metadata)
+    .bind(t.created_at)
+    .execute(executor)
+    .await
+    .map_err(|e| BizClawError::Database(format!("Record trace: {e}")))?;
+    Ok(())
 }
 
 // ── Parsing helpers ────────────────────────────────────────
@@ -791,6 +1162,16 @@ fn parse_delegation_status(s: &str) -> DelegationStatus {
     }
 }
 
+fn parse_delegation_event_kind(s: &str) -> DelegationEventKind {
+    match s {
+        "started" => DelegationEventKind::Started,
+        "partial_result" => DelegationEventKind::PartialResult,
+        "completed" => DelegationEventKind::Completed,
+        "failed" => DelegationEventKind::Failed,
+        _ => DelegationEventKind::Progress,
+    }
+}
+
 fn parse_task_status(s: &str) -> TaskStatus {
     match s {
         "in_progress" => TaskStatus::InProgress,
@@ -800,3 +1181,190 @@ fn parse_task_status(s: &str) -> TaskStatus {
         _ => TaskStatus::Pending,
     }
 }
+
+fn parse_link_message_status(s: &str) -> LinkMessageStatus {
+    match s {
+        "delivered" => LinkMessageStatus::Delivered,
+        "read" => LinkMessageStatus::Read,
+        _ => LinkMessageStatus::Pending,
+    }
+}
+
+fn row_to_link(r: &sqlx::postgres::PgRow) -> AgentLink {
+    AgentLink {
+        id: r.get("id"),
+        source_agent: r.get("source_agent"),
+        target_agent: r.get("target_agent"),
+        direction: parse_direction(&r.get::<String, _>("direction")),
+        max_concurrent: r.get::<i32, _>("max_concurrent") as u32,
+        settings: r.get("settings"),
+        created_at: r.get("created_at"),
+    }
+}
+
+fn row_to_link_message(r: &sqlx::postgres::PgRow) -> LinkMessage {
+    LinkMessage {
+        id: r.get("id"),
+        link_id: r.get("link_id"),
+        from_agent: r.get("from_agent"),
+        to_agent: r.get("to_agent"),
+        content: r.get("content"),
+        status: parse_link_message_status(&r.get::<String, _>("status")),
+        created_at: r.get("created_at"),
+        delivered_at: r.get("delivered_at"),
+        read_at: r.get("read_at"),
+    }
+}
+
+fn row_to_handoff(r: &sqlx::postgres::PgRow) -> Handoff {
+    Handoff {
+        id: r.get("id"),
+        from_agent: r.get("from_agent"),
+        to_agent: r.get("to_agent"),
+        session_id: r.get("session_id"),
+        reason: r.get("reason"),
+        context_summary: r.get("context_summary"),
+        active: r.get("active"),
+        created_at: r.get("created_at"),
+    }
+}
+
+fn row_to_dead_letter(r: &sqlx::postgres::PgRow) -> DeadLetter {
+    DeadLetter {
+        id: r.get("id"),
+        channel: r.get("channel"),
+        agent_name: r.get("agent_name"),
+        message: r.get::<sqlx::types::Json<IncomingMessage>, _>("message").0,
+        error: r.get("error"),
+        created_at: r.get("created_at"),
+    }
+}
+
+fn row_to_note(r: &sqlx::postgres::PgRow) -> AgentNote {
+    AgentNote {
+        agent_name: r.get("agent_name"),
+        key: r.get("key"),
+        value: r.get("value"),
+        updated_at: r.get("updated_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Store for tests that need a live server; set `BIZCLAW_TEST_POSTGRES_URL`
+    /// to run them, otherwise they are skipped.
+    async fn test_store() -> Option<PostgresStore> {
+        let dsn = std::env::var("BIZCLAW_TEST_POSTGRES_URL").ok()?;
+        let store = PostgresStore::connect(&dsn).await.unwrap();
+        store.migrate().await.unwrap();
+        Some(store)
+    }
+
+    #[tokio::test]
+    async fn test_delegation_events_in_order() {
+        let Some(store) = test_store().await else {
+            return;
+        };
+        let d = Delegation::new("lead", "researcher", "market scan", DelegationMode::Async);
+        store.create_delegation(&d).await.unwrap();
+
+        let events = [
+            (DelegationEventKind::Started, ""),
+            (DelegationEventKind::Progress, "searching competitors"),
+            (DelegationEventKind::PartialResult, "3 competitors found"),
+            (DelegationEventKind::Completed, "report ready"),
+        ];
+        for (kind, message) in &events {
+            let event = DelegationEvent::new(kind.clone(), message);
+            store.append_delegation_event(&d.id, &event).await.unwrap();
+        }
+
+        let stored = store.list_delegation_events(&d.id).await.unwrap();
+        let got: Vec<_> = stored.iter().map(|e| (e.kind.clone(), e.message.as_str())).collect();
+        assert_eq!(got, events.to_vec());
+
+        let orphan = DelegationEvent::new(DelegationEventKind::Progress, "lost");
+        assert!(store.append_delegation_event("missing", &orphan).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_claims_have_one_winner() {
+        let Some(store) = test_store().await else {
+            return;
+        };
+        let store = std::sync::Arc::new(store);
+        let team = AgentTeam::new(&format!("team-{}", uuid::Uuid::new_v4().simple()), "");
+        store.create_team(&team).await.unwrap();
+        let task = TeamTask::new(&team.id, "Research", "Do research", "lead");
+        store.create_task(&task).await.unwrap();
+
+        let claims: Vec<_> = (0..8)
+            .map(|i| {
+                let store = store.clone();
+                let id = task.id.clone();
+                tokio::spawn(async move { store.claim_task(&id, &format!("agent-{i}")).await })
+            })
+            .collect();
+        let mut winners = 0;
+        for claim in claims {
+            winners += claim.await.unwrap().unwrap() as usize;
+        }
+        assert_eq!(winners, 1);
+    }
+
+    #[tokio::test]
+    async fn test_notes_are_scoped_per_agent() {
+        let Some(store) = test_store().await else {
+            return;
+        };
+        // Unique agent names so reruns against the same database stay independent.
+        let run = uuid::Uuid::new_v4().simple().to_string();
+        let (support, sales) = (format!("support-{run}"), format!("sales-{run}"));
+        store.set_note(&support, "todo", "call Lan back").await.unwrap();
+        store.set_note(&support, "todo", "call Lan back at 3pm").await.unwrap();
+        store.set_note(&support, "apology", "sent").await.unwrap();
+        store.set_note(&sales, "todo", "send price list").await.unwrap();
+
+        let note = store.get_note(&support, "todo").await.unwrap().unwrap();
+        assert_eq!(note.value, "call Lan back at 3pm");
+        let keys: Vec<String> =
+            store.list_notes(&support).await.unwrap().into_iter().map(|n| n.key).collect();
+        assert_eq!(keys, vec!["apology", "todo"]);
+
+        assert!(store.delete_note(&support, "todo").await.unwrap());
+        assert!(!store.delete_note(&support, "todo").await.unwrap());
+        assert!(store.get_note(&sales, "todo").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_handoff_history_in_order() {
+        let Some(store) = test_store().await else {
+            return;
+        };
+        let session = uuid::Uuid::new_v4().simple().to_string();
+        let chain = [("support", "billing"), ("billing", "refunds"), ("refunds", "support")];
+        for (from, to) in chain {
+            store.create_handoff(&Handoff::new(from, to, &session, None)).await.unwrap();
+        }
+
+        let history = store.list_handoffs(&session).await.unwrap();
+        let hops: Vec<_> =
+            history.iter().map(|h| (h.from_agent.as_str(), h.to_agent.as_str())).collect();
+        assert_eq!(hops, chain);
+        let active: Vec<bool> = history.iter().map(|h| h.active).collect();
+        assert_eq!(active, [false, false, true]);
+    }
+
+    #[tokio::test]
+    async fn test_mark_sender_seen_once_per_channel() {
+        let Some(store) = test_store().await else {
+            return;
+        };
+        let sender = uuid::Uuid::new_v4().simple().to_string();
+        assert!(store.mark_sender_seen("telegram", &sender).await.unwrap());
+        assert!(!store.mark_sender_seen("telegram", &sender).await.unwrap());
+        assert!(store.mark_sender_seen("discord", &sender).await.unwrap());
+    }
+}
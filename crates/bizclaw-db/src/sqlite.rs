@@ -2,11 +2,13 @@
 
 use async_trait::async_trait;
 use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::traits::embedding_cache::{embedding_from_bytes, embedding_to_bytes};
 use bizclaw_core::types::*;
 use rusqlite::{params, Connection};
 use std::path::Path;
 use std::sync::Mutex;
 
+use crate::migrations;
 use crate::store::DataStore;
 
 /// SQLite-backed data store for standalone mode.
@@ -51,108 +53,33 @@ impl DataStore for SqliteStore {
     // ── Migrate ────────────────────────────────────────────
 
     async fn migrate(&self) -> Result<()> {
-        let conn = self.db();
-        conn.execute_batch(
-            "
-            CREATE TABLE IF NOT EXISTS agent_links (
-                id TEXT PRIMARY KEY,
-                source_agent TEXT NOT NULL,
-                target_agent TEXT NOT NULL,
-                direction TEXT NOT NULL DEFAULT 'outbound',
-                max_concurrent INTEGER NOT NULL DEFAULT 3,
-                settings TEXT NOT NULL DEFAULT '{}',
-                created_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-            CREATE INDEX IF NOT EXISTS idx_links_source ON agent_links(source_agent);
-            CREATE INDEX IF NOT EXISTS idx_links_target ON agent_links(target_agent);
-
-            CREATE TABLE IF NOT EXISTS delegations (
-                id TEXT PRIMARY KEY,
-                from_agent TEXT NOT NULL,
-                to_agent TEXT NOT NULL,
-                task TEXT NOT NULL,
-                mode TEXT NOT NULL DEFAULT 'sync',
-                status TEXT NOT NULL DEFAULT 'pending',
-                result TEXT,
-                error TEXT,
-                created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                completed_at TEXT
-            );
-            CREATE INDEX IF NOT EXISTS idx_deleg_from ON delegations(from_agent);
-            CREATE INDEX IF NOT EXISTS idx_deleg_to ON delegations(to_agent);
-            CREATE INDEX IF NOT EXISTS idx_deleg_status ON delegations(status);
-
-            CREATE TABLE IF NOT EXISTS teams (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL UNIQUE,
-                description TEXT NOT NULL DEFAULT '',
-                members TEXT NOT NULL DEFAULT '[]',
-                created_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-
-            CREATE TABLE IF NOT EXISTS team_tasks (
-                id TEXT PRIMARY KEY,
-                team_id TEXT NOT NULL,
-                title TEXT NOT NULL,
-                description TEXT NOT NULL DEFAULT '',
-                status TEXT NOT NULL DEFAULT 'pending',
-                created_by TEXT NOT NULL,
-                assigned_to TEXT,
-                blocked_by TEXT NOT NULL DEFAULT '[]',
-                result TEXT,
-                created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
-                FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE CASCADE
-            );
-            CREATE INDEX IF NOT EXISTS idx_tasks_team ON team_tasks(team_id);
-            CREATE INDEX IF NOT EXISTS idx_tasks_assigned ON team_tasks(assigned_to);
-
-            CREATE TABLE IF NOT EXISTS team_messages (
-                id TEXT PRIMARY KEY,
-                team_id TEXT NOT NULL,
-                from_agent TEXT NOT NULL,
-                to_agent TEXT,
-                content TEXT NOT NULL,
-                read INTEGER NOT NULL DEFAULT 0,
-                created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                FOREIGN KEY (team_id) REFERENCES teams(id) ON DELETE CASCADE
-            );
-
-            CREATE TABLE IF NOT EXISTS handoffs (
-                id TEXT PRIMARY KEY,
-                from_agent TEXT NOT NULL,
-                to_agent TEXT NOT NULL,
-                session_id TEXT NOT NULL,
-                reason TEXT,
-                context_summary TEXT,
-                active INTEGER NOT NULL DEFAULT 1,
-                created_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-            CREATE INDEX IF NOT EXISTS idx_handoff_session ON handoffs(session_id, active);
-
-            CREATE TABLE IF NOT EXISTS llm_traces (
-                id TEXT PRIMARY KEY,
-                agent_name TEXT NOT NULL,
-                provider TEXT NOT NULL,
-                model TEXT NOT NULL,
-                prompt_tokens INTEGER NOT NULL DEFAULT 0,
-                completion_tokens INTEGER NOT NULL DEFAULT 0,
-                total_tokens INTEGER NOT NULL DEFAULT 0,
-                latency_ms INTEGER NOT NULL DEFAULT 0,
-                cache_hit INTEGER NOT NULL DEFAULT 0,
-                cache_read_tokens INTEGER NOT NULL DEFAULT 0,
-                cache_write_tokens INTEGER NOT NULL DEFAULT 0,
-                status TEXT NOT NULL DEFAULT 'pending',
-                error TEXT,
-                metadata TEXT NOT NULL DEFAULT '{}',
-                created_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-            CREATE INDEX IF NOT EXISTS idx_traces_agent ON llm_traces(agent_name);
-            CREATE INDEX IF NOT EXISTS idx_traces_time ON llm_traces(created_at DESC);
-            ",
-        )
-        .map_err(|e| BizClawError::Database(format!("Migration error: {e}")))?;
-        tracing::info!("SQLite orchestration schema migrated");
+        let mut conn = self.db();
+        conn.execute_batch(migrations::SCHEMA_VERSION_TABLE)
+            .map_err(|e| BizClawError::Database(format!("Migration error: {e}")))?;
+        let current: i64 = conn
+            .query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |r| r.get(0))
+            .map_err(|e| BizClawError::Database(format!("Schema version: {e}")))?;
+
+        for m in migrations::pending(current) {
+            let tx = conn
+                .transaction()
+                .map_err(|e| BizClawError::Database(format!("Migration tx: {e}")))?;
+            tx.execute_batch(m.sqlite).map_err(|e| {
+                BizClawError::Database(format!("Migration v{} ({}): {e}", m.version, m.description))
+            })?;
+            tx.execute(
+                "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, ?2, ?3)",
+                params![m.version, m.description, chrono::Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| BizClawError::Database(format!("Record migration: {e}")))?;
+            tx.commit()
+                .map_err(|e| BizClawError::Database(format!("Migration commit: {e}")))?;
+            tracing::info!("SQLite migration v{} applied: {}", m.version, m.description);
+        }
+        tracing::info!(
+            "SQLite orchestration schema at v{}",
+            migrations::latest_version()
+        );
         Ok(())
     }
 
@@ -195,18 +122,7 @@ impl DataStore for SqliteStore {
             )
             .map_err(|e| BizClawError::Database(format!("List links: {e}")))?;
         let rows = stmt
-            .query_map(params![agent_name], |row| {
-                Ok(AgentLink {
-                    id: row.get(0)?,
-                    source_agent: row.get(1)?,
-                    target_agent: row.get(2)?,
-                    direction: parse_direction(&row.get::<_, String>(3)?),
-                    max_concurrent: row.get(4)?,
-                    settings: serde_json::from_str(&row.get::<_, String>(5).unwrap_or_default())
-                        .unwrap_or_default(),
-                    created_at: parse_datetime(&row.get::<_, String>(6)?),
-                })
-            })
+            .query_map(params![agent_name], row_to_link)
             .map_err(|e| BizClawError::Database(format!("List links query: {e}")))?;
         let mut links = Vec::new();
         for row in rows {
@@ -224,18 +140,7 @@ impl DataStore for SqliteStore {
             )
             .map_err(|e| BizClawError::Database(format!("All links: {e}")))?;
         let rows = stmt
-            .query_map([], |row| {
-                Ok(AgentLink {
-                    id: row.get(0)?,
-                    source_agent: row.get(1)?,
-                    target_agent: row.get(2)?,
-                    direction: parse_direction(&row.get::<_, String>(3)?),
-                    max_concurrent: row.get(4)?,
-                    settings: serde_json::from_str(&row.get::<_, String>(5).unwrap_or_default())
-                        .unwrap_or_default(),
-                    created_at: parse_datetime(&row.get::<_, String>(6)?),
-                })
-            })
+            .query_map([], row_to_link)
             .map_err(|e| BizClawError::Database(format!("All links query: {e}")))?;
         let mut links = Vec::new();
         for row in rows {
@@ -365,6 +270,66 @@ impl DataStore for SqliteStore {
         Ok(count)
     }
 
+    async fn fail_stale_delegations(
+        &self,
+        to_agent: &str,
+        before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u32> {
+        let conn = self.db();
+        let failed = conn
+            .execute(
+                "UPDATE delegations SET status = 'failed', error = 'Delegation abandoned', completed_at = ?1
+                 WHERE to_agent = ?2 AND status IN ('pending', 'running') AND created_at < ?3",
+                params![chrono::Utc::now().to_rfc3339(), to_agent, before.to_rfc3339()],
+            )
+            .map_err(|e| BizClawError::Database(format!("Fail stale delegations: {e}")))?;
+        Ok(failed as u32)
+    }
+
+    async fn append_delegation_event(
+        &self,
+        delegation_id: &str,
+        event: &DelegationEvent,
+    ) -> Result<()> {
+        let conn = self.db();
+        conn.execute(
+            "INSERT INTO delegation_events (delegation_id, kind, message, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                delegation_id,
+                serde_json::to_string(&event.kind).unwrap_or_default().trim_matches('"'),
+                event.message,
+                event.created_at.to_rfc3339(),
+            ],
+        )
+        .map_err(|e| BizClawError::Database(format!("Append delegation event: {e}")))?;
+        Ok(())
+    }
+
+    async fn list_delegation_events(&self, delegation_id: &str) -> Result<Vec<DelegationEvent>> {
+        let conn = self.db();
+        let mut stmt = conn
+            .prepare(
+                "SELECT kind, message, created_at FROM delegation_events
+                 WHERE delegation_id = ?1 ORDER BY id",
+            )
+            .map_err(|e| BizClawError::Database(format!("List delegation events: {e}")))?;
+        let rows = stmt
+            .query_map(params![delegation_id], |row| {
+                Ok(DelegationEvent {
+                    kind: parse_delegation_event_kind(&row.get::<_, String>(0)?),
+                    message: row.get(1)?,
+                    created_at: parse_datetime(&row.get::<_, String>(2)?),
+                })
+            })
+            .map_err(|e| BizClawError::Database(format!("Delegation events query: {e}")))?;
+        let mut events = Vec::new();
+        for row in rows {
+            events.push(row.map_err(|e| BizClawError::Database(format!("Row: {e}")))?);
+        }
+        Ok(events)
+    }
+
     // ── Teams ──────────────────────────────────────────────
 
     async fn create_team(&self, team: &AgentTeam) -> Result<()> {
@@ -503,6 +468,18 @@ impl DataStore for SqliteStore {
         Ok(())
     }
 
+    async fn claim_task(&self, id: &str, agent_name: &str) -> Result<bool> {
+        let conn = self.db();
+        let claimed = conn
+            .execute(
+                "UPDATE team_tasks SET status = 'in_progress', assigned_to = ?1, updated_at = datetime('now')
+                 WHERE id = ?2 AND (assigned_to IS NULL OR status = 'pending')",
+                params![agent_name, id],
+            )
+            .map_err(|e| BizClawError::Database(format!("Claim task: {e}")))?;
+        Ok(claimed == 1)
+    }
+
     async fn get_task(&self, id: &str) -> Result<Option<TeamTask>> {
         let conn = self.db();
         let result = conn
@@ -661,6 +638,132 @@ impl DataStore for SqliteStore {
         Ok(())
     }
 
+    // ── Link Messages ──────────────────────────────────────
+
+    async fn send_link_message(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        content: &str,
+    ) -> Result<LinkMessage> {
+        // Lookup, limit check and insert under one lock, so concurrent
+        // senders cannot both slip past `max_concurrent`.
+        let conn = self.db();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, source_agent, target_agent, direction, max_concurrent, settings, created_at
+                 FROM agent_links
+                 WHERE (source_agent = ?1 AND target_agent = ?2)
+                    OR (source_agent = ?2 AND target_agent = ?1)
+                 ORDER BY created_at",
+            )
+            .map_err(|e| BizClawError::Database(format!("Find link: {e}")))?;
+        let rows = stmt
+            .query_map(params![from_agent, to_agent], row_to_link)
+            .map_err(|e| BizClawError::Database(format!("Find link query: {e}")))?;
+        let mut link = None;
+        for row in rows {
+            let candidate = row.map_err(|e| BizClawError::Database(format!("Row: {e}")))?;
+            if candidate.allows(from_agent, to_agent) {
+                link = Some(candidate);
+                break;
+            }
+        }
+        let link = link.ok_or_else(|| {
+            BizClawError::NoPermission(format!("no link allows {from_agent} to message {to_agent}"))
+        })?;
+
+        let pending: u32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM link_messages WHERE link_id = ?1 AND status = 'pending'",
+                params![link.id],
+                |r| r.get(0),
+            )
+            .map_err(|e| BizClawError::Database(format!("Count link messages: {e}")))?;
+        if pending >= link.max_concurrent {
+            return Err(BizClawError::RateLimited(format!(
+                "link {} has {pending} undelivered messages (max {})",
+                link.id, link.max_concurrent
+            )));
+        }
+
+        let msg = LinkMessage::new(&link, from_agent, to_agent, content);
+        conn.execute(
+            "INSERT INTO link_messages (id, link_id, from_agent, to_agent, content, status, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                msg.id,
+                msg.link_id,
+                msg.from_agent,
+                msg.to_agent,
+                msg.content,
+                msg.status.to_string(),
+                msg.created_at.to_rfc3339(),
+            ],
+        )
+        .map_err(|e| BizClawError::Database(format!("Send link message: {e}")))?;
+        Ok(msg)
+    }
+
+    async fn fetch_link_messages(&self, agent_name: &str) -> Result<Vec<LinkMessage>> {
+        let conn = self.db();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, link_id, from_agent, to_agent, content, status, created_at, delivered_at, read_at
+                 FROM link_messages WHERE to_agent = ?1 AND status = 'pending'
+                 ORDER BY created_at",
+            )
+            .map_err(|e| BizClawError::Database(format!("Fetch link messages: {e}")))?;
+        let rows = stmt
+            .query_map(params![agent_name], row_to_link_message)
+            .map_err(|e| BizClawError::Database(format!("Link messages query: {e}")))?;
+        let mut messages = Vec::new();
+        for row in rows {
+            messages.push(row.map_err(|e| BizClawError::Database(format!("Row: {e}")))?);
+        }
+        let now = chrono::Utc::now();
+        for msg in &mut messages {
+            conn.execute(
+                "UPDATE link_messages SET status = 'delivered', delivered_at = ?2 WHERE id = ?1",
+                params![msg.id, now.to_rfc3339()],
+            )
+            .map_err(|e| BizClawError::Database(format!("Mark delivered: {e}")))?;
+            msg.status = LinkMessageStatus::Delivered;
+            msg.delivered_at = Some(now);
+        }
+        Ok(messages)
+    }
+
+    async fn get_link_message(&self, id: &str) -> Result<Option<LinkMessage>> {
+        let conn = self.db();
+        let result = conn.query_row(
+            "SELECT id, link_id, from_agent, to_agent, content, status, created_at, delivered_at, read_at
+             FROM link_messages WHERE id = ?1",
+            params![id],
+            row_to_link_message,
+        );
+        match result {
+            Ok(msg) => Ok(Some(msg)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(BizClawError::Database(format!("Get link message: {e}"))),
+        }
+    }
+
+    async fn mark_link_messages_read(&self, message_ids: &[String]) -> Result<()> {
+        let conn = self.db();
+        let now = chrono::Utc::now().to_rfc3339();
+        for id in message_ids {
+            conn.execute(
+                "UPDATE link_messages
+                 SET status = 'read', read_at = ?2, delivered_at = COALESCE(delivered_at, ?2)
+                 WHERE id = ?1 AND status != 'read'",
+                params![id, now],
+            )
+            .map_err(|e| BizClawError::Database(format!("Mark link message read: {e}")))?;
+        }
+        Ok(())
+    }
+
     // ── Handoffs ───────────────────────────────────────────
 
     async fn create_handoff(&self, h: &Handoff) -> Result<()> {
@@ -697,18 +800,7 @@ impl DataStore for SqliteStore {
                  FROM handoffs WHERE session_id = ?1 AND active = 1
                  ORDER BY created_at DESC LIMIT 1",
                 params![session_id],
-                |row| {
-                    Ok(Handoff {
-                        id: row.get(0)?,
-                        from_agent: row.get(1)?,
-                        to_agent: row.get(2)?,
-                        session_id: row.get(3)?,
-                        reason: row.get(4)?,
-                        context_summary: row.get(5)?,
-                        active: row.get::<_, i32>(6)? != 0,
-                        created_at: parse_datetime(&row.get::<_, String>(7)?),
-                    })
-                },
+                row_to_handoff,
             )
             .ok();
         Ok(result)
@@ -724,34 +816,41 @@ impl DataStore for SqliteStore {
         Ok(())
     }
 
+    async fn list_handoffs(&self, session_id: &str) -> Result<Vec<Handoff>> {
+        let conn = self.db();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, from_agent, to_agent, session_id, reason, context_summary, active, created_at
+                 FROM handoffs WHERE session_id = ?1
+                 ORDER BY created_at, rowid",
+            )
+            .map_err(|e| BizClawError::Database(format!("List handoffs: {e}")))?;
+        let rows = stmt
+            .query_map(params![session_id], row_to_handoff)
+            .map_err(|e| BizClawError::Database(format!("List handoffs query: {e}")))?;
+        let mut handoffs = Vec::new();
+        for row in rows {
+            handoffs.push(row.map_err(|e| BizClawError::Database(format!("Row: {e}")))?);
+        }
+        Ok(handoffs)
+    }
+
     // ── LLM Traces ─────────────────────────────────────────
 
     async fn record_trace(&self, t: &LlmTrace) -> Result<()> {
-        let conn = self.db();
-        let metadata = serde_json::to_string(&t.metadata).unwrap_or_default();
-        conn.execute(
-            "INSERT INTO llm_traces (id, agent_name, provider, model, prompt_tokens, completion_tokens, total_tokens, latency_ms, cache_hit, cache_read_tokens, cache_write_tokens, status, error, metadata, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
-            params![
-                t.id,
-                t.agent_name,
-                t.provider,
-                t.model,
-                t.prompt_tokens,
-                t.completion_tokens,
-                t.total_tokens,
-                t.latency_ms as i64,
-                t.cache_hit as i32,
-                t.cache_read_tokens,
-                t.cache_write_tokens,
-                t.status,
-                t.error,
-                metadata,
-                t.created_at.to_rfc3339(),
-            ],
-        )
-        .map_err(|e| BizClawError::Database(format!("Record trace: {e}")))?;
-        Ok(())
+        insert_trace(&self.db(), t)
+    }
+
+    async fn record_traces(&self, traces: &[LlmTrace]) -> Result<()> {
+        let mut conn = self.db();
+        let tx = conn
+            .transaction()
+            .map_err(|e| BizClawError::Database(format!("Record traces tx: {e}")))?;
+        for t in traces {
+            insert_trace(&tx, t)?;
+        }
+        tx.commit()
+            .map_err(|e| BizClawError::Database(format!("Record traces commit: {e}")))
     }
 
     async fn list_traces(&self, limit: usize) -> Result<Vec<LlmTrace>> {
@@ -829,6 +928,297 @@ impl DataStore for SqliteStore {
         }
         Ok(traces)
     }
+
+    async fn usage_stats(
+        &self,
+        agent_name: &str,
+        since: Option<chrono::DateTime<chrono::Utc>>,
+        until: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<UsageStats> {
+        let conn = self.db();
+        // created_at is stored as RFC 3339 UTC, so string comparison is chronological.
+        let since_s = since.map(|t| t.to_rfc3339()).unwrap_or_default();
+        let until_s = until.map(|t| t.to_rfc3339());
+        let (requests, errors, prompt, completion, total, avg_latency) = conn
+            .query_row(
+                "SELECT COUNT(*),
+                        COALESCE(SUM(CASE WHEN error IS NOT NULL THEN 1 ELSE 0 END), 0),
+                        COALESCE(SUM(prompt_tokens), 0),
+                        COALESCE(SUM(completion_tokens), 0),
+                        COALESCE(SUM(total_tokens), 0),
+                        COALESCE(AVG(latency_ms), 0.0)
+                 FROM llm_traces
+                 WHERE agent_name = ?1 AND created_at >= ?2 AND (?3 IS NULL OR created_at < ?3)",
+                params![agent_name, since_s, until_s],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, i64>(4)?,
+                        row.get::<_, f64>(5)?,
+                    ))
+                },
+            )
+            .map_err(|e| BizClawError::Database(format!("Usage stats: {e}")))?;
+        Ok(UsageStats {
+            agent_name: agent_name.to_string(),
+            since,
+            until,
+            requests: requests as u64,
+            errors: errors as u64,
+            prompt_tokens: prompt as u64,
+            completion_tokens: completion as u64,
+            total_tokens: total as u64,
+            avg_latency_ms: avg_latency,
+        })
+    }
+
+    async fn usage_summary(
+        &self,
+        group_by: UsageGroupBy,
+        since: Option<chrono::DateTime<chrono::Utc>>,
+        until: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Vec<UsageSummaryRow>> {
+        let group = match group_by {
+            // RFC 3339 (and SQLite's own datetime) start with the UTC date.
+            UsageGroupBy::Day => "substr(created_at, 1, 10)",
+            UsageGroupBy::Provider => "provider",
+            UsageGroupBy::Model => "model",
+        };
+        let conn = self.db();
+        let since_s = since.map(|t| t.to_rfc3339()).unwrap_or_default();
+        let until_s = until.map(|t| t.to_rfc3339());
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {group} AS grp,
+                        COUNT(*),
+                        COALESCE(SUM(prompt_tokens), 0),
+                        COALESCE(SUM(completion_tokens), 0),
+                        COALESCE(SUM(total_tokens), 0),
+                        COALESCE(AVG(latency_ms), 0.0)
+                 FROM llm_traces
+                 WHERE created_at >= ?1 AND (?2 IS NULL OR created_at < ?2)
+                 GROUP BY grp ORDER BY grp"
+            ))
+            .map_err(|e| BizClawError::Database(format!("Usage summary: {e}")))?;
+        let rows = stmt
+            .query_map(params![since_s, until_s], |row| {
+                Ok(UsageSummaryRow {
+                    group: row.get(0)?,
+                    requests: row.get::<_, i64>(1)? as u64,
+                    prompt_tokens: row.get::<_, i64>(2)? as u64,
+                    completion_tokens: row.get::<_, i64>(3)? as u64,
+                    total_tokens: row.get::<_, i64>(4)? as u64,
+                    avg_latency_ms: row.get(5)?,
+                })
+            })
+            .map_err(|e| BizClawError::Database(format!("Usage summary query: {e}")))?;
+        let mut summary = Vec::new();
+        for row in rows {
+            summary.push(row.map_err(|e| BizClawError::Database(format!("Row: {e}")))?);
+        }
+        Ok(summary)
+    }
+
+    // ── Agent Notes ────────────────────────────────────────
+
+    async fn set_note(&self, agent_name: &str, key: &str, value: &str) -> Result<()> {
+        let conn = self.db();
+        conn.execute(
+            "INSERT INTO agent_notes (agent_name, key, value, updated_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(agent_name, key) DO UPDATE SET value = ?3, updated_at = ?4",
+            params![agent_name, key, value, chrono::Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| BizClawError::Database(format!("Set note: {e}")))?;
+        Ok(())
+    }
+
+    async fn get_note(&self, agent_name: &str, key: &str) -> Result<Option<AgentNote>> {
+        let conn = self.db();
+        let result = conn.query_row(
+            "SELECT agent_name, key, value, updated_at FROM agent_notes
+             WHERE agent_name = ?1 AND key = ?2",
+            params![agent_name, key],
+            row_to_note,
+        );
+        match result {
+            Ok(note) => Ok(Some(note)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(BizClawError::Database(format!("Get note: {e}"))),
+        }
+    }
+
+    async fn list_notes(&self, agent_name: &str) -> Result<Vec<AgentNote>> {
+        let conn = self.db();
+        let mut stmt = conn
+            .prepare(
+                "SELECT agent_name, key, value, updated_at FROM agent_notes
+                 WHERE agent_name = ?1 ORDER BY key",
+            )
+            .map_err(|e| BizClawError::Database(format!("List notes: {e}")))?;
+        let rows = stmt
+            .query_map(params![agent_name], row_to_note)
+            .map_err(|e| BizClawError::Database(format!("Notes query: {e}")))?;
+        let mut notes = Vec::new();
+        for row in rows {
+            notes.push(row.map_err(|e| BizClawError::Database(format!("Row: {e}")))?);
+        }
+        Ok(notes)
+    }
+
+    async fn delete_note(&self, agent_name: &str, key: &str) -> Result<bool> {
+        let conn = self.db();
+        let deleted = conn
+            .execute(
+                "DELETE FROM agent_notes WHERE agent_name = ?1 AND key = ?2",
+                params![agent_name, key],
+            )
+            .map_err(|e| BizClawError::Database(format!("Delete note: {e}")))?;
+        Ok(deleted > 0)
+    }
+
+    // ── Channel Senders ────────────────────────────────────
+
+    async fn mark_sender_seen(&self, channel: &str, sender_id: &str) -> Result<bool> {
+        let conn = self.db();
+        let inserted = conn
+            .execute(
+                "INSERT INTO channel_senders (channel, sender_id, first_seen_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(channel, sender_id) DO NOTHING",
+                params![channel, sender_id, chrono::Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| BizClawError::Database(format!("Mark sender seen: {e}")))?;
+        Ok(inserted > 0)
+    }
+
+    // ── Embedding Cache ────────────────────────────────────
+
+    async fn get_cached_embedding(
+        &self,
+        key: &str,
+        since: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<Option<Vec<f32>>> {
+        let conn = self.db();
+        let result = conn.query_row(
+            "SELECT embedding FROM embedding_cache
+             WHERE key = ?1 AND (?2 IS NULL OR created_at >= ?2)",
+            params![key, since.map(|t| t.to_rfc3339())],
+            |row| row.get::<_, Vec<u8>>(0),
+        );
+        match result {
+            Ok(bytes) => Ok(Some(embedding_from_bytes(&bytes))),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(BizClawError::Database(format!("Get cached embedding: {e}"))),
+        }
+    }
+
+    async fn put_cached_embedding(&self, key: &str, embedding: &[f32]) -> Result<()> {
+        let conn = self.db();
+        conn.execute(
+            "INSERT INTO embedding_cache (key, embedding, created_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(key) DO UPDATE SET embedding = ?2, created_at = ?3",
+            params![key, embedding_to_bytes(embedding), chrono::Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| BizClawError::Database(format!("Cache embedding: {e}")))?;
+        Ok(())
+    }
+
+    async fn prune_embedding_cache(
+        &self,
+        max_entries: usize,
+        before: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<usize> {
+        let conn = self.db();
+        let mut dropped = 0;
+        if let Some(before) = before {
+            dropped += conn
+                .execute(
+                    "DELETE FROM embedding_cache WHERE created_at < ?1",
+                    params![before.to_rfc3339()],
+                )
+                .map_err(|e| BizClawError::Database(format!("Expire embeddings: {e}")))?;
+        }
+        dropped += conn
+            .execute(
+                "DELETE FROM embedding_cache WHERE key IN (
+                     SELECT key FROM embedding_cache ORDER BY created_at DESC LIMIT -1 OFFSET ?1
+                 )",
+                params![max_entries as i64],
+            )
+            .map_err(|e| BizClawError::Database(format!("Prune embeddings: {e}")))?;
+        Ok(dropped)
+    }
+
+    // ── Dead Letters ───────────────────────────────────────
+
+    async fn push_dead_letter(&self, letter: &DeadLetter, max_entries: usize) -> Result<()> {
+        let message = serde_json::to_string(&letter.message)?;
+        let conn = self.db();
+        conn.execute(
+            "INSERT INTO dead_letters (id, channel, agent_name, message, error, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                letter.id,
+                letter.channel,
+                letter.agent_name,
+                message,
+                letter.error,
+                letter.created_at.to_rfc3339()
+            ],
+        )
+        .map_err(|e| BizClawError::Database(format!("Push dead letter: {e}")))?;
+        conn.execute(
+            "DELETE FROM dead_letters WHERE id IN (
+                 SELECT id FROM dead_letters ORDER BY created_at DESC LIMIT -1 OFFSET ?1
+             )",
+            params![max_entries as i64],
+        )
+        .map_err(|e| BizClawError::Database(format!("Trim dead letters: {e}")))?;
+        Ok(())
+    }
+
+    async fn list_dead_letters(&self, limit: usize) -> Result<Vec<DeadLetter>> {
+        let conn = self.db();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, channel, message, error, created_at, agent_name FROM dead_letters
+                 ORDER BY created_at DESC LIMIT ?1",
+            )
+            .map_err(|e| BizClawError::Database(format!("List dead letters: {e}")))?;
+        let rows = stmt
+            .query_map(params![limit as i64], row_to_dead_letter)
+            .map_err(|e| BizClawError::Database(format!("Dead letters query: {e}")))?;
+        let mut letters = Vec::new();
+        for row in rows {
+            letters.push(row.map_err(|e| BizClawError::Database(format!("Row: {e}")))?);
+        }
+        Ok(letters)
+    }
+
+    async fn get_dead_letter(&self, id: &str) -> Result<Option<DeadLetter>> {
+        let conn = self.db();
+        let result = conn.query_row(
+            "SELECT id, channel, message, error, created_at, agent_name FROM dead_letters
+             WHERE id = ?1",
+            params![id],
+            row_to_dead_letter,
+        );
+        match result {
+            Ok(letter) => Ok(Some(letter)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(BizClawError::Database(format!("Get dead letter: {e}"))),
+        }
+    }
+
+    async fn delete_dead_letter(&self, id: &str) -> Result<bool> {
+        let conn = self.db();
+        let deleted = conn
+            .execute("DELETE FROM dead_letters WHERE id = ?1", params![id])
+            .map_err(|e| BizClawError::Database(format!("Delete dead letter: {e}")))?;
+        Ok(deleted > 0)
+    }
 }
 
 // ── Parsing helpers ────────────────────────────────────────
@@ -858,6 +1248,16 @@ fn parse_delegation_status(s: &str) -> DelegationStatus {
     }
 }
 
+fn parse_delegation_event_kind(s: &str) -> DelegationEventKind {
+    match s {
+        "started" => DelegationEventKind::Started,
+        "partial_result" => DelegationEventKind::PartialResult,
+        "completed" => DelegationEventKind::Completed,
+        "failed" => DelegationEventKind::Failed,
+        _ => DelegationEventKind::Progress,
+    }
+}
+
 fn parse_task_status(s: &str) -> TaskStatus {
     match s {
         "in_progress" | "\"in_progress\"" => TaskStatus::InProgress,
@@ -868,12 +1268,111 @@ fn parse_task_status(s: &str) -> TaskStatus {
     }
 }
 
+fn parse_link_message_status(s: &str) -> LinkMessageStatus {
+    match s {
+        "delivered" => LinkMessageStatus::Delivered,
+        "read" => LinkMessageStatus::Read,
+        _ => LinkMessageStatus::Pending,
+    }
+}
+
 fn parse_datetime(s: &str) -> chrono::DateTime<chrono::Utc> {
     chrono::DateTime::parse_from_rfc3339(s)
         .map(|dt| dt.with_timezone(&chrono::Utc))
         .unwrap_or_else(|_| chrono::Utc::now())
 }
 
+fn row_to_link(row: &rusqlite::Row<'_>) -> rusqlite::Result<AgentLink> {
+    Ok(AgentLink {
+        id: row.get(0)?,
+        source_agent: row.get(1)?,
+        target_agent: row.get(2)?,
+        direction: parse_direction(&row.get::<_, String>(3)?),
+        max_concurrent: row.get(4)?,
+        settings: serde_json::from_str(&row.get::<_, String>(5).unwrap_or_default())
+            .unwrap_or_default(),
+        created_at: parse_datetime(&row.get::<_, String>(6)?),
+    })
+}
+
+fn row_to_link_message(row: &rusqlite::Row<'_>) -> rusqlite::Result<LinkMessage> {
+    Ok(LinkMessage {
+        id: row.get(0)?,
+        link_id: row.get(1)?,
+        from_agent: row.get(2)?,
+        to_agent: row.get(3)?,
+        content: row.get(4)?,
+        status: parse_link_message_status(&row.get::<_, String>(5)?),
+        created_at: parse_datetime(&row.get::<_, String>(6)?),
+        delivered_at: row.get::<_, Option<String>>(7)?.as_deref().map(parse_datetime),
+        read_at: row.get::<_, Option<String>>(8)?.as_deref().map(parse_datetime),
+    })
+}
+
+fn row_to_handoff(row: &rusqlite::Row<'_>) -> rusqlite::Result<Handoff> {
+    Ok(Handoff {
+        id: row.get(0)?,
+        from_agent: row.get(1)?,
+        to_agent: row.get(2)?,
+        session_id: row.get(3)?,
+        reason: row.get(4)?,
+        context_summary: row.get(5)?,
+        active: row.get::<_, i32>(6)? != 0,
+        created_at: parse_datetime(&row.get::<_, String>(7)?),
+    })
+}
+
+fn row_to_dead_letter(row: &rusqlite::Row<'_>) -> rusqlite::Result<DeadLetter> {
+    let message: String = row.get(2)?;
+    Ok(DeadLetter {
+        id: row.get(0)?,
+        channel: row.get(1)?,
+        agent_name: row.get(5)?,
+        message: serde_json::from_str(&message).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        error: row.get(3)?,
+        created_at: parse_datetime(&row.get::<_, String>(4)?),
+    })
+}
+
+fn row_to_note(row: &rusqlite::Row<'_>) -> rusqlite::Result<AgentNote> {
+    Ok(AgentNote {
+        agent_name: row.get(0)?,
+        key: row.get(1)?,
+        value: row.get(2)?,
+        updated_at: parse_datetime(&row.get::<_, String>(3)?),
+    })
+}
+
+/// Insert one row into `llm_traces`.
+fn insert_trace(conn: &Connection, t: &LlmTrace) -> Result<()> {
+    let metadata = serde_json::to_string(&t.metadata).unwrap_or_default();
+    conn.execute(
+        "INSERT INTO llm_traces (id, agent_name, provider, model, prompt_tokens, completion_tokens, total_tokens, latency_ms, cache_hit, cache_read_tokens, cache_write_tokens, status, error, metadata, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        params![
+            t.id,
+            t.agent_name,
+            t.provider,
+            t.model,
+            t.prompt_tokens,
+            t.completion_tokens,
+            t.total_tokens,
+            t.latency_ms as i64,
+            t.cache_hit as i32,
+            t.cache_read_tokens,
+            t.cache_write_tokens,
+            t.status,
+            t.error,
+            metadata,
+            t.created_at.to_rfc3339(),
+        ],
+    )
+    .map_err(|e| BizClawError::Database(format!("Record trace: {e}")))?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -884,6 +1383,40 @@ mod tests {
         store
     }
 
+    #[tokio::test]
+    async fn test_migrate_legacy_schema_keeps_data() {
+        // A database created before versioning: v1 tables, no schema_version.
+        let store = SqliteStore::in_memory().unwrap();
+        {
+            let conn = store.db();
+            conn.execute_batch(migrations::MIGRATIONS[0].sqlite).unwrap();
+            conn.execute("INSERT INTO teams (id, name) VALUES ('t1', 'ops')", [])
+                .unwrap();
+            conn.execute(
+                "INSERT INTO team_tasks (id, team_id, title, created_by) VALUES ('k1', 't1', 'Ship it', 'lead')",
+                [],
+            )
+            .unwrap();
+        }
+
+        store.migrate().await.unwrap();
+        // Running again is a no-op.
+        store.migrate().await.unwrap();
+
+        let conn = store.db();
+        let version: i64 = conn
+            .query_row("SELECT MAX(version) FROM schema_version", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(version, migrations::latest_version());
+        let (title, priority): (String, i64) = conn
+            .query_row("SELECT title, priority FROM team_tasks WHERE id = 'k1'", [], |r| {
+                Ok((r.get(0)?, r.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(title, "Ship it");
+        assert_eq!(priority, 0);
+    }
+
     #[tokio::test]
     async fn test_links_crud() {
         let store = test_store().await;
@@ -921,6 +1454,110 @@ mod tests {
         assert_eq!(count, 0); // completed, not active
     }
 
+    #[tokio::test]
+    async fn test_notes_are_scoped_per_agent() {
+        let store = test_store().await;
+        store.set_note("support", "todo", "call Lan back").await.unwrap();
+        store.set_note("support", "todo", "call Lan back at 3pm").await.unwrap();
+        store.set_note("support", "apology", "sent").await.unwrap();
+        store.set_note("sales", "todo", "send price list").await.unwrap();
+
+        let note = store.get_note("support", "todo").await.unwrap().unwrap();
+        assert_eq!(note.value, "call Lan back at 3pm");
+        let keys: Vec<String> = store
+            .list_notes("support")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|n| n.key)
+            .collect();
+        assert_eq!(keys, vec!["apology", "todo"]);
+        assert_eq!(
+            store.get_note("sales", "todo").await.unwrap().unwrap().value,
+            "send price list"
+        );
+
+        assert!(store.delete_note("support", "todo").await.unwrap());
+        assert!(!store.delete_note("support", "todo").await.unwrap());
+        assert!(store.get_note("support", "todo").await.unwrap().is_none());
+        assert!(store.get_note("sales", "todo").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_mark_sender_seen_once_per_channel() {
+        let store = test_store().await;
+        assert!(store.mark_sender_seen("telegram", "42").await.unwrap());
+        assert!(!store.mark_sender_seen("telegram", "42").await.unwrap());
+        assert!(store.mark_sender_seen("telegram", "43").await.unwrap());
+        assert!(store.mark_sender_seen("discord", "42").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_are_bounded() {
+        let store = test_store().await;
+        let mut ids = Vec::new();
+        for (i, text) in ["first", "second", "third"].into_iter().enumerate() {
+            let msg = IncomingMessage {
+                channel: "telegram".into(),
+                thread_id: "42".into(),
+                sender_id: "7".into(),
+                sender_name: None,
+                content: text.into(),
+                thread_type: ThreadType::Direct,
+                timestamp: chrono::Utc::now(),
+                reply_to: None,
+                message_id: None,
+                images: Vec::new(),
+            };
+            let agent = (i == 1).then_some("sales");
+            let mut letter = DeadLetter::new(&msg, agent, "provider down");
+            letter.created_at += chrono::Duration::seconds(i as i64);
+            store.push_dead_letter(&letter, 2).await.unwrap();
+            ids.push(letter.id);
+        }
+
+        let letters = store.list_dead_letters(10).await.unwrap();
+        let contents: Vec<_> = letters.iter().map(|l| l.message.content.as_str()).collect();
+        assert_eq!(contents, ["third", "second"]);
+        assert!(store.get_dead_letter(&ids[0]).await.unwrap().is_none());
+
+        let second = store.get_dead_letter(&ids[1]).await.unwrap().unwrap();
+        assert_eq!(second.channel, "telegram");
+        assert_eq!(second.agent_name.as_deref(), Some("sales"));
+        assert_eq!(second.error, "provider down");
+        assert!(store.delete_dead_letter(&ids[1]).await.unwrap());
+        assert!(!store.delete_dead_letter(&ids[1]).await.unwrap());
+        assert_eq!(store.list_dead_letters(10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delegation_events_in_order() {
+        let store = test_store().await;
+        let d = Delegation::new("lead", "researcher", "market scan", DelegationMode::Async);
+        store.create_delegation(&d).await.unwrap();
+
+        let events = [
+            (DelegationEventKind::Started, ""),
+            (DelegationEventKind::Progress, "searching competitors"),
+            (DelegationEventKind::PartialResult, "3 competitors found"),
+            (DelegationEventKind::Completed, "report ready"),
+        ];
+        for (kind, message) in &events {
+            let event = DelegationEvent::new(kind.clone(), message);
+            store.append_delegation_event(&d.id, &event).await.unwrap();
+        }
+
+        let stored = store.list_delegation_events(&d.id).await.unwrap();
+        let got: Vec<_> = stored.iter().map(|e| (e.kind.clone(), e.message.as_str())).collect();
+        assert_eq!(got, events.to_vec());
+        assert!(stored.windows(2).all(|w| w[0].created_at <= w[1].created_at));
+
+        assert!(store.list_delegation_events("other").await.unwrap().is_empty());
+        // Events must belong to an existing delegation.
+        let orphan = DelegationEvent::new(DelegationEventKind::Progress, "lost");
+        assert!(store.append_delegation_event("missing", &orphan).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_team_flow() {
         let store = test_store().await;
@@ -960,6 +1597,33 @@ mod tests {
         assert!(unread.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_concurrent_claims_have_one_winner() {
+        let store = std::sync::Arc::new(test_store().await);
+        let team = AgentTeam::new("dev-team", "Development");
+        store.create_team(&team).await.unwrap();
+        let task = TeamTask::new(&team.id, "Research", "Do research", "lead");
+        store.create_task(&task).await.unwrap();
+
+        let claims: Vec<_> = (0..8)
+            .map(|i| {
+                let store = store.clone();
+                let id = task.id.clone();
+                tokio::spawn(async move { store.claim_task(&id, &format!("agent-{i}")).await })
+            })
+            .collect();
+        let mut winners = 0;
+        for claim in claims {
+            winners += claim.await.unwrap().unwrap() as usize;
+        }
+        assert_eq!(winners, 1);
+
+        let claimed = store.get_task(&task.id).await.unwrap().unwrap();
+        assert_eq!(claimed.status, TaskStatus::InProgress);
+        assert!(claimed.assigned_to.unwrap().starts_with("agent-"));
+        assert!(!store.claim_task("missing", "agent-0").await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_handoff() {
         let store = test_store().await;
@@ -974,6 +1638,38 @@ mod tests {
         assert!(active.is_none());
     }
 
+    #[tokio::test]
+    async fn test_handoff_history() {
+        let store = test_store().await;
+        let chain = [("support", "billing"), ("billing", "refunds"), ("refunds", "support")];
+        for (from, to) in chain {
+            let reason = format!("{from} → {to}");
+            let h = Handoff::new(from, to, "session-1", Some(&reason));
+            store.create_handoff(&h).await.unwrap();
+        }
+        let other = Handoff::new("support", "sales", "session-2", None);
+        store.create_handoff(&other).await.unwrap();
+
+        let history = store.list_handoffs("session-1").await.unwrap();
+        let hops: Vec<_> = history
+            .iter()
+            .map(|h| (h.from_agent.as_str(), h.to_agent.as_str()))
+            .collect();
+        assert_eq!(hops, chain);
+        // Only the latest is active; earlier ones stay in the history.
+        assert_eq!(
+            history.iter().map(|h| h.active).collect::<Vec<_>>(),
+            [false, false, true]
+        );
+        assert_eq!(history[1].reason.as_deref(), Some("billing → refunds"));
+
+        store.clear_handoff("session-1").await.unwrap();
+        let history = store.list_handoffs("session-1").await.unwrap();
+        assert_eq!(history.len(), 3);
+        assert!(history.iter().all(|h| !h.active));
+        assert!(store.list_handoffs("session-3").await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_traces() {
         let store = test_store().await;
@@ -992,4 +1688,199 @@ mod tests {
         let agent_traces = store.list_agent_traces("agent-1", 10).await.unwrap();
         assert_eq!(agent_traces.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_link_message_respects_direction() {
+        let store = test_store().await;
+        let link = AgentLink::new("support", "research", LinkDirection::Outbound);
+        store.create_link(&link).await.unwrap();
+
+        let msg = store
+            .send_link_message("support", "research", "Find competitor pricing")
+            .await
+            .unwrap();
+        assert_eq!(msg.link_id, link.id);
+        assert_eq!(msg.status, LinkMessageStatus::Pending);
+
+        // Outbound-only: research cannot message support back.
+        let err = store
+            .send_link_message("research", "support", "Done")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BizClawError::NoPermission(_)));
+        assert!(store.send_link_message("support", "billing", "Hi").await.is_err());
+        assert!(store.fetch_link_messages("support").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_link_message_delivery_status() {
+        let store = test_store().await;
+        let mut link = AgentLink::new("lead", "writer", LinkDirection::Bidirectional);
+        link.max_concurrent = 2;
+        store.create_link(&link).await.unwrap();
+
+        let first = store
+            .send_link_message("lead", "writer", "Draft the intro")
+            .await
+            .unwrap();
+        store
+            .send_link_message("writer", "lead", "Which tone?")
+            .await
+            .unwrap();
+        // Two messages are waiting on the link: it is full.
+        let err = store
+            .send_link_message("lead", "writer", "And the outro")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BizClawError::RateLimited(_)));
+
+        let inbox = store.fetch_link_messages("writer").await.unwrap();
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(inbox[0].id, first.id);
+        assert_eq!(inbox[0].status, LinkMessageStatus::Delivered);
+        assert!(store.fetch_link_messages("writer").await.unwrap().is_empty());
+
+        let stored = store.get_link_message(&first.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, LinkMessageStatus::Delivered);
+        assert!(stored.delivered_at.is_some());
+        assert!(stored.read_at.is_none());
+
+        // Delivery frees a slot.
+        store
+            .send_link_message("lead", "writer", "And the outro")
+            .await
+            .unwrap();
+
+        store
+            .mark_link_messages_read(std::slice::from_ref(&first.id))
+            .await
+            .unwrap();
+        let stored = store.get_link_message(&first.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, LinkMessageStatus::Read);
+        assert!(stored.read_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_usage_stats_window() {
+        let store = test_store().await;
+        let now = chrono::Utc::now();
+        let seed = [
+            ("agent-1", 2, 100, 40, 1000),
+            ("agent-1", 5, 200, 60, 3000),
+            ("agent-1", 30, 999, 999, 9999), // outside the window
+            ("agent-2", 1, 500, 500, 500),   // other agent
+        ];
+        for (agent, hours_ago, prompt, completion, latency) in seed {
+            let mut t = LlmTrace::new(agent, "openai", "gpt-4o-mini");
+            t.prompt_tokens = prompt;
+            t.completion_tokens = completion;
+            t.total_tokens = prompt + completion;
+            t.latency_ms = latency;
+            t.created_at = now - chrono::Duration::hours(hours_ago);
+            store.record_trace(&t).await.unwrap();
+        }
+
+        let stats = store
+            .usage_stats("agent-1", Some(now - chrono::Duration::hours(24)), None)
+            .await
+            .unwrap();
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.prompt_tokens, 300);
+        assert_eq!(stats.completion_tokens, 100);
+        assert_eq!(stats.total_tokens, 400);
+        assert_eq!(stats.avg_latency_ms, 2000.0);
+
+        let all = store.usage_stats("agent-1", None, None).await.unwrap();
+        assert_eq!(all.requests, 3);
+    }
+
+    #[tokio::test]
+    async fn test_usage_summary_grouped() {
+        let store = test_store().await;
+        let at = |s: &str| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .unwrap()
+                .with_timezone(&chrono::Utc)
+        };
+        let seed = [
+            (
+                "2026-03-01T08:00:00Z",
+                "openai",
+                "gpt-4o-mini",
+                100,
+                20,
+                1000,
+            ),
+            (
+                "2026-03-01T23:59:00Z",
+                "anthropic",
+                "claude-haiku",
+                300,
+                60,
+                3000,
+            ),
+            ("2026-03-02T00:01:00Z", "openai", "gpt-4o-mini", 50, 10, 500),
+            ("2026-03-02T12:00:00Z", "openai", "gpt-4o", 200, 40, 1500),
+            ("2026-03-05T12:00:00Z", "openai", "gpt-4o", 999, 999, 9999), // outside the window
+        ];
+        for (i, (time, provider, model, prompt, completion, latency)) in
+            seed.into_iter().enumerate()
+        {
+            let mut t = LlmTrace::new(&format!("agent-{}", i % 2), provider, model);
+            t.prompt_tokens = prompt;
+            t.completion_tokens = completion;
+            t.total_tokens = prompt + completion;
+            t.latency_ms = latency;
+            t.created_at = at(time);
+            store.record_trace(&t).await.unwrap();
+        }
+        let since = Some(at("2026-03-01T00:00:00Z"));
+        let until = Some(at("2026-03-03T00:00:00Z"));
+        let row = |group: &str, requests, prompt, completion, avg_latency_ms| UsageSummaryRow {
+            group: group.to_string(),
+            requests,
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            total_tokens: prompt + completion,
+            avg_latency_ms,
+        };
+
+        let by_day = store
+            .usage_summary(UsageGroupBy::Day, since, until)
+            .await
+            .unwrap();
+        assert_eq!(
+            by_day,
+            [
+                row("2026-03-01", 2, 400, 80, 2000.0),
+                row("2026-03-02", 2, 250, 50, 1000.0),
+            ]
+        );
+
+        let by_provider = store
+            .usage_summary(UsageGroupBy::Provider, since, until)
+            .await
+            .unwrap();
+        assert_eq!(
+            by_provider,
+            [
+                row("anthropic", 1, 300, 60, 3000.0),
+                row("openai", 3, 350, 70, 1000.0),
+            ]
+        );
+
+        let by_model = store
+            .usage_summary(UsageGroupBy::Model, None, None)
+            .await
+            .unwrap();
+        let groups: Vec<_> = by_model
+            .iter()
+            .map(|r| (r.group.as_str(), r.requests))
+            .collect();
+        assert_eq!(
+            groups,
+            [("claude-haiku", 1), ("gpt-4o", 2), ("gpt-4o-mini", 2)]
+        );
+        assert_eq!(by_model[1].total_tokens, 240 + 1998);
+    }
 }
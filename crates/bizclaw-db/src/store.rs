@@ -3,9 +3,11 @@
 use async_trait::async_trait;
 use bizclaw_core::error::Result;
 use bizclaw_core::types::{
-    AgentLink, AgentTeam, Delegation, DelegationStatus, Handoff, LlmTrace, TeamMessage, TeamTask,
-    TaskStatus,
+    AgentLink, AgentNote, AgentTeam, DeadLetter, Delegation, DelegationEvent, DelegationStatus,
+    Handoff, LinkMessage, LlmTrace, TeamMessage, TeamTask, TaskStatus, UsageGroupBy, UsageStats,
+    UsageSummaryRow,
 };
+use chrono::{DateTime, Utc};
 
 /// Unified data store interface — implemented by SQLite and PostgreSQL.
 #[async_trait]
@@ -50,6 +52,20 @@ pub trait DataStore: Send + Sync {
     /// Count active delegations TO an agent (for concurrency limiting).
     async fn active_delegation_count(&self, to_agent: &str) -> Result<u32>;
 
+    /// Mark active delegations TO an agent created before `before` as failed
+    /// (abandoned by a crash). Returns how many were failed.
+    async fn fail_stale_delegations(&self, to_agent: &str, before: DateTime<Utc>) -> Result<u32>;
+
+    /// Post a progress event on a delegation.
+    async fn append_delegation_event(
+        &self,
+        delegation_id: &str,
+        event: &DelegationEvent,
+    ) -> Result<()>;
+
+    /// Events for a delegation, oldest first.
+    async fn list_delegation_events(&self, delegation_id: &str) -> Result<Vec<DelegationEvent>>;
+
     // ── Teams ──────────────────────────────────────────────
 
     /// Create a team.
@@ -81,6 +97,12 @@ pub trait DataStore: Send + Sync {
         result: Option<&str>,
     ) -> Result<()>;
 
+    /// Assign a task to `agent_name` and mark it in progress, unless another
+    /// agent got there first. The check and the update are one statement, so
+    /// of several concurrent claims exactly one wins. Returns whether this
+    /// claim succeeded; `false` also for an unknown task.
+    async fn claim_task(&self, id: &str, agent_name: &str) -> Result<bool>;
+
     /// Get a task by ID.
     async fn get_task(&self, id: &str) -> Result<Option<TeamTask>>;
 
@@ -101,6 +123,28 @@ pub trait DataStore: Send + Sync {
     /// Mark messages as read.
     async fn mark_read(&self, message_ids: &[String]) -> Result<()>;
 
+    // ── Link Messages ──────────────────────────────────────
+
+    /// Send a direct message over a link that allows `from_agent` to reach
+    /// `to_agent`. Fails if no such link exists, or if the link already has
+    /// `max_concurrent` messages waiting to be delivered.
+    async fn send_link_message(
+        &self,
+        from_agent: &str,
+        to_agent: &str,
+        content: &str,
+    ) -> Result<LinkMessage>;
+
+    /// Fetch an agent's pending link messages, oldest first, and mark them
+    /// delivered.
+    async fn fetch_link_messages(&self, agent_name: &str) -> Result<Vec<LinkMessage>>;
+
+    /// Get a link message, e.g. to check its delivery status.
+    async fn get_link_message(&self, id: &str) -> Result<Option<LinkMessage>>;
+
+    /// Mark link messages as read.
+    async fn mark_link_messages_read(&self, message_ids: &[String]) -> Result<()>;
+
     // ── Handoffs ───────────────────────────────────────────
 
     /// Create a handoff record.
@@ -112,17 +156,106 @@ pub trait DataStore: Send + Sync {
     /// Clear handoff — return to original routing.
     async fn clear_handoff(&self, session_id: &str) -> Result<()>;
 
+    /// Every handoff of a session, oldest first, including inactive ones —
+    /// which agent took over the conversation, when, and why.
+    async fn list_handoffs(&self, session_id: &str) -> Result<Vec<Handoff>>;
+
     // ── LLM Traces ─────────────────────────────────────────
 
     /// Record an LLM trace.
     async fn record_trace(&self, trace: &LlmTrace) -> Result<()>;
 
+    /// Record several traces at once. Backends should write them in a single
+    /// transaction; the default records them one by one.
+    async fn record_traces(&self, traces: &[LlmTrace]) -> Result<()> {
+        for trace in traces {
+            self.record_trace(trace).await?;
+        }
+        Ok(())
+    }
+
     /// List recent traces.
     async fn list_traces(&self, limit: usize) -> Result<Vec<LlmTrace>>;
 
     /// List traces for an agent.
     async fn list_agent_traces(&self, agent_name: &str, limit: usize) -> Result<Vec<LlmTrace>>;
 
+    /// Aggregate token usage, request count and latency for an agent.
+    /// `since` is inclusive, `until` exclusive; `None` leaves that side open.
+    async fn usage_stats(
+        &self,
+        agent_name: &str,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<UsageStats>;
+
+    /// Aggregate token usage, request count and latency of all agents,
+    /// one row per day, provider or model, ordered by group. `since` is
+    /// inclusive, `until` exclusive; `None` leaves that side open.
+    async fn usage_summary(
+        &self,
+        group_by: UsageGroupBy,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<Vec<UsageSummaryRow>>;
+
+    // ── Agent Notes ────────────────────────────────────────
+
+    /// Set a note for an agent, replacing any previous value for `key`.
+    async fn set_note(&self, agent_name: &str, key: &str, value: &str) -> Result<()>;
+
+    /// Get one of an agent's notes.
+    async fn get_note(&self, agent_name: &str, key: &str) -> Result<Option<AgentNote>>;
+
+    /// List an agent's notes, ordered by key.
+    async fn list_notes(&self, agent_name: &str) -> Result<Vec<AgentNote>>;
+
+    /// Delete a note. Returns whether it existed.
+    async fn delete_note(&self, agent_name: &str, key: &str) -> Result<bool>;
+
+    // ── Channel Senders ────────────────────────────────────
+
+    /// Record that `sender_id` has messaged `channel`. Returns `true` only
+    /// the first time, so callers can greet new senders exactly once.
+    async fn mark_sender_seen(&self, channel: &str, sender_id: &str) -> Result<bool>;
+
+    // ── Embedding Cache ────────────────────────────────────
+
+    /// Cached embedding for `key`, if it was stored at or after `since`
+    /// (any age when `None`).
+    async fn get_cached_embedding(
+        &self,
+        key: &str,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Option<Vec<f32>>>;
+
+    /// Cache an embedding, replacing any previous one for `key`.
+    async fn put_cached_embedding(&self, key: &str, embedding: &[f32]) -> Result<()>;
+
+    /// Drop cached embeddings stored before `before`, then the oldest beyond
+    /// `max_entries`. Returns how many were dropped.
+    async fn prune_embedding_cache(
+        &self,
+        max_entries: usize,
+        before: Option<DateTime<Utc>>,
+    ) -> Result<usize>;
+
+    // ── Dead Letters ───────────────────────────────────────
+
+    /// Store a message the agent failed to process, then drop the oldest
+    /// dead letters beyond `max_entries`.
+    async fn push_dead_letter(&self, letter: &DeadLetter, max_entries: usize) -> Result<()>;
+
+    /// List dead letters, newest first.
+    async fn list_dead_letters(&self, limit: usize) -> Result<Vec<DeadLetter>>;
+
+    /// Get a dead letter by ID.
+    async fn get_dead_letter(&self, id: &str) -> Result<Option<DeadLetter>>;
+
+    /// Delete a dead letter, e.g. after a successful replay. Returns whether
+    /// it existed.
+    async fn delete_dead_letter(&self, id: &str) -> Result<bool>;
+
     // ── Initialization ─────────────────────────────────────
 
     /// Run schema migrations.
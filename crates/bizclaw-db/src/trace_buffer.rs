@@ -0,0 +1,187 @@
+//! Buffered trace writes — keeps LLM trace inserts off the request path.
+//!
+//! [`TraceBuffer::record`] only queues the trace. A background task writes
+//! queued traces with [`DataStore::record_traces`] (one transaction) when the
+//! batch fills up or the flush interval elapses. Call
+//! [`TraceBuffer::shutdown`] before exit so the remainder is written.
+
+use bizclaw_core::types::LlmTrace;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::store::DataStore;
+
+/// When a [`TraceBuffer`] writes its queue.
+#[derive(Debug, Clone, Copy)]
+pub struct TraceBufferConfig {
+    /// Flush as soon as this many traces are queued.
+    pub max_batch: usize,
+    /// Flush whatever is queued at least this often.
+    pub flush_interval: Duration,
+}
+
+impl Default for TraceBufferConfig {
+    fn default() -> Self {
+        Self {
+            max_batch: 100,
+            flush_interval: Duration::from_secs(2),
+        }
+    }
+}
+
+enum Command {
+    Record(Box<LlmTrace>),
+    Flush(oneshot::Sender<()>),
+    Shutdown(oneshot::Sender<()>),
+}
+
+/// Queues traces and writes them to a [`DataStore`] in batches.
+pub struct TraceBuffer {
+    tx: mpsc::UnboundedSender<Command>,
+}
+
+impl TraceBuffer {
+    /// Start the background writer. Must be called inside a Tokio runtime.
+    pub fn spawn(store: Arc<dyn DataStore>, config: TraceBufferConfig) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run(store, config, rx));
+        Self { tx }
+    }
+
+    /// Queue a trace; never waits on the database.
+    pub fn record(&self, trace: LlmTrace) {
+        if self.tx.send(Command::Record(Box::new(trace))).is_err() {
+            tracing::warn!("Trace dropped: trace buffer is shut down");
+        }
+    }
+
+    /// Write everything queued so far and wait for it to land.
+    pub async fn flush(&self) {
+        self.request(Command::Flush).await;
+    }
+
+    /// Write the remainder and stop the writer. Later traces are dropped.
+    pub async fn shutdown(&self) {
+        self.request(Command::Shutdown).await;
+    }
+
+    async fn request(&self, command: fn(oneshot::Sender<()>) -> Command) {
+        let (done, wait) = oneshot::channel();
+        if self.tx.send(command(done)).is_ok() {
+            let _ = wait.await;
+        }
+    }
+}
+
+async fn run(
+    store: Arc<dyn DataStore>,
+    config: TraceBufferConfig,
+    mut rx: mpsc::UnboundedReceiver<Command>,
+) {
+    let mut pending: Vec<LlmTrace> = Vec::new();
+    let start = tokio::time::Instant::now() + config.flush_interval;
+    let mut ticker = tokio::time::interval_at(start, config.flush_interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            command = rx.recv() => match command {
+                Some(Command::Record(trace)) => {
+                    pending.push(*trace);
+                    if pending.len() >= config.max_batch.max(1) {
+                        write(store.as_ref(), &mut pending).await;
+                    }
+                }
+                Some(Command::Flush(done)) => {
+                    write(store.as_ref(), &mut pending).await;
+                    let _ = done.send(());
+                }
+                Some(Command::Shutdown(done)) => {
+                    rx.close();
+                    // Traces queued before the shutdown request still count.
+                    while let Ok(command) = rx.try_recv() {
+                        if let Command::Record(trace) = command {
+                            pending.push(*trace);
+                        }
+                    }
+                    write(store.as_ref(), &mut pending).await;
+                    let _ = done.send(());
+                    return;
+                }
+                None => {
+                    write(store.as_ref(), &mut pending).await;
+                    return;
+                }
+            },
+            _ = ticker.tick() => write(store.as_ref(), &mut pending).await,
+        }
+    }
+}
+
+/// Write and clear `pending`. A failed batch is logged and dropped so one
+/// bad write cannot grow the queue without bound.
+async fn write(store: &dyn DataStore, pending: &mut Vec<LlmTrace>) {
+    if pending.is_empty() {
+        return;
+    }
+    if let Err(e) = store.record_traces(pending).await {
+        tracing::warn!("Failed to write {} traces: {e}", pending.len());
+    }
+    pending.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SqliteStore;
+
+    async fn store() -> Arc<SqliteStore> {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        store.migrate().await.unwrap();
+        store
+    }
+
+    fn config() -> TraceBufferConfig {
+        // Long interval so only explicit flushes and full batches write.
+        TraceBufferConfig {
+            max_batch: 25,
+            flush_interval: Duration::from_secs(3600),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_buffered_traces_are_persisted_on_flush() {
+        let store = store().await;
+        let buffer = TraceBuffer::spawn(store.clone(), config());
+        for i in 0..60 {
+            buffer.record(LlmTrace::new(
+                &format!("agent-{}", i % 3),
+                "openai",
+                "gpt-4o",
+            ));
+        }
+        buffer.flush().await;
+        assert_eq!(store.list_traces(100).await.unwrap().len(), 60);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_flushes_remainder() {
+        let store = store().await;
+        let buffer = TraceBuffer::spawn(store.clone(), config());
+        for _ in 0..10 {
+            buffer.record(LlmTrace::new("agent-1", "openai", "gpt-4o"));
+        }
+        // Below the batch size and before the interval: nothing written yet.
+        tokio::task::yield_now().await;
+        assert!(store.list_traces(100).await.unwrap().is_empty());
+
+        buffer.shutdown().await;
+        assert_eq!(store.list_traces(100).await.unwrap().len(), 10);
+
+        // Traces after shutdown are dropped, not queued forever.
+        buffer.record(LlmTrace::new("agent-1", "openai", "gpt-4o"));
+        buffer.flush().await;
+        assert_eq!(store.list_traces(100).await.unwrap().len(), 10);
+    }
+}
@@ -0,0 +1,368 @@
+//! llama.cpp-compatible `/completion` API — plain prompt in, text out.
+//!
+//! Some tools (llama.cpp clients, older editor plugins) speak the llama.cpp
+//! server protocol rather than OpenAI chat. The prompt is sent as-is to the
+//! agent's configured provider (cloud or the local brain) without system
+//! prompt, history or tools. With `"stream": true` the reply is sent as SSE
+//! events of `{"content", "stop": false}` while the provider streams it,
+//! followed by a final `stop: true` event carrying the stats, matching
+//! llama.cpp.
+//!
+//! Authentication is the same as the OpenAI-compatible API.
+
+use axum::extract::State;
+use axum::response::sse::{Event, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, http::StatusCode};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use bizclaw_core::traits::provider::OnText;
+
+use super::openai_compat::{LlmTrace, estimate_cost, extract_api_key, record_trace, validate_key};
+use super::server::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct CompletionRequest {
+    pub prompt: String,
+    /// Max tokens to generate; `-1` (or absent) uses the agent's default.
+    #[serde(default)]
+    pub n_predict: Option<i64>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub stop: Vec<String>,
+    #[serde(default)]
+    pub stream: bool,
+    /// Agent to use (defaults to the main agent).
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Generated text after applying stop words.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub content: String,
+    pub model: String,
+    pub tokens_evaluated: u32,
+    pub tokens_predicted: u32,
+    /// The stop word that ended generation, if any.
+    pub stopping_word: Option<String>,
+    /// Generation hit `n_predict`.
+    pub stopped_limit: bool,
+}
+
+/// Cut `content` at the earliest stop word (providers without native stop
+/// support may run past it). Returns the stop word that matched.
+pub fn apply_stop(content: &str, stop: &[String]) -> (String, Option<String>) {
+    let earliest = stop
+        .iter()
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| content.find(s.as_str()).map(|pos| (pos, s)))
+        .min_by_key(|(pos, _)| *pos);
+    match earliest {
+        Some((pos, word)) => (content[..pos].to_string(), Some(word.clone())),
+        None => (content.to_string(), None),
+    }
+}
+
+/// Non-streaming response body (also the final event of a stream, with empty content).
+pub fn completion_response(c: &Completion) -> Value {
+    json!({
+        "content": c.content,
+        "model": c.model,
+        "tokens_predicted": c.tokens_predicted,
+        "tokens_evaluated": c.tokens_evaluated,
+        "stop": true,
+        "stopped_eos": c.stopping_word.is_none() && !c.stopped_limit,
+        "stopped_word": c.stopping_word.is_some(),
+        "stopped_limit": c.stopped_limit,
+        "stopping_word": c.stopping_word.clone().unwrap_or_default(),
+    })
+}
+
+/// Holds back streamed text that may turn out to be the start of a stop word,
+/// and drops everything from the first stop word on.
+#[derive(Debug, Default)]
+struct StopFilter {
+    stop: Vec<String>,
+    pending: String,
+    stopped: bool,
+}
+
+impl StopFilter {
+    fn new(stop: &[String]) -> Self {
+        Self {
+            stop: stop.iter().filter(|s| !s.is_empty()).cloned().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Add streamed text; returns the part that is safe to send.
+    fn push(&mut self, text: &str) -> String {
+        if self.stopped {
+            return String::new();
+        }
+        self.pending.push_str(text);
+        let (before, word) = apply_stop(&self.pending, &self.stop);
+        if word.is_some() {
+            self.stopped = true;
+            self.pending.clear();
+            return before;
+        }
+        let held = self
+            .stop
+            .iter()
+            .filter_map(|word| {
+                (1..word.len())
+                    .rev()
+                    .filter(|&n| word.is_char_boundary(n))
+                    .find(|&n| self.pending.ends_with(&word[..n]))
+            })
+            .max()
+            .unwrap_or(0);
+        let ready: String = self.pending.drain(..self.pending.len() - held).collect();
+        ready
+    }
+
+    /// Text still held back once the stream ended without a stop word.
+    fn finish(&mut self) -> String {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+// ─── POST /completion ────────────────────────────────────────────────────────
+
+pub async fn completion(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Json(req): Json<CompletionRequest>,
+) -> Result<Response, StatusCode> {
+    let key = extract_api_key(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
+    if !validate_key(&state, &key) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    if req.stream {
+        return Ok(stream_completion(state, req));
+    }
+
+    let start = std::time::Instant::now();
+    let (result, provider, model) = run_on_agent(&state, &req, None)
+        .await
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let resp = match result {
+        Ok(resp) => resp,
+        Err(e) => {
+            tracing::warn!("Completion failed: {e}");
+            let body = error_body(500, &e.to_string());
+            return Ok((StatusCode::INTERNAL_SERVER_ERROR, Json(body)).into_response());
+        }
+    };
+    let completion = finish(&state, &req, resp, provider, model, start);
+    Ok(Json(completion_response(&completion)).into_response())
+}
+
+/// Stream the reply as SSE while the provider generates it: one event per
+/// piece of text, then the final stats event (or an error event).
+fn stream_completion(state: Arc<AppState>, req: CompletionRequest) -> Response {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Value>();
+    tokio::spawn(async move {
+        let start = std::time::Instant::now();
+        let filter = std::sync::Mutex::new(StopFilter::new(&req.stop));
+        let send_text = |text: String| {
+            if !text.is_empty() {
+                let _ = tx.send(json!({"content": text, "stop": false}));
+            }
+        };
+        let on_text = |text: &str| {
+            send_text(filter.lock().unwrap_or_else(|p| p.into_inner()).push(text));
+        };
+        let last = match run_on_agent(&state, &req, Some(&on_text)).await {
+            None => error_body(503, "No agent available"),
+            Some((Err(e), _, _)) => {
+                tracing::warn!("Completion failed: {e}");
+                error_body(500, &e.to_string())
+            }
+            Some((Ok(resp), provider, model)) => {
+                send_text(filter.lock().unwrap_or_else(|p| p.into_inner()).finish());
+                let completion = finish(&state, &req, resp, provider, model, start);
+                completion_response(&Completion {
+                    content: String::new(),
+                    ..completion
+                })
+            }
+        };
+        let _ = tx.send(last);
+    });
+    let events = futures::stream::unfold(rx, |mut rx| async move {
+        let event = rx.recv().await?;
+        Some((Ok::<_, Infallible>(Event::default().data(event.to_string())), rx))
+    });
+    Sse::new(events).into_response()
+}
+
+fn error_body(code: u16, message: &str) -> Value {
+    json!({"error": {"code": code, "message": message, "type": "server_error"}})
+}
+
+/// Run the request on the agent named by `model`, else the main agent.
+/// `None` when there is no agent to run it on.
+async fn run_on_agent(
+    state: &AppState,
+    req: &CompletionRequest,
+    on_text: Option<&OnText<'_>>,
+) -> Option<(
+    bizclaw_core::error::Result<bizclaw_core::types::ProviderResponse>,
+    String,
+    String,
+)> {
+    let mut orch = state.orchestrator.lock().await;
+    if let Some(agent) = req.model.as_deref().and_then(|m| orch.get_agent_mut(m)) {
+        return Some(run(agent, req, on_text).await);
+    }
+    drop(orch);
+    let agent_lock = state.agent.lock().await;
+    let agent = agent_lock.as_ref()?;
+    Some(run(agent, req, on_text).await)
+}
+
+/// Cut the reply at the first stop word, then trace and bill it.
+fn finish(
+    state: &AppState,
+    req: &CompletionRequest,
+    resp: bizclaw_core::types::ProviderResponse,
+    provider: String,
+    model: String,
+    start: std::time::Instant,
+) -> Completion {
+    let agent_name = req.model.clone().unwrap_or_else(|| "default".into());
+    let reasoning = resp.reasoning;
+    let fallback = resp.fallback.map(|local| (local, provider.clone()));
+    let raw = resp.content.unwrap_or_default();
+    let (content, stopping_word) = apply_stop(&raw, &req.stop);
+    let (tokens_evaluated, tokens_predicted) = match resp.usage {
+        Some(u) => (u.prompt_tokens, u.completion_tokens),
+        None => ((req.prompt.len() / 4) as u32, (content.len() / 4) as u32),
+    };
+    let completion = Completion {
+        content,
+        model: model.clone(),
+        tokens_evaluated,
+        tokens_predicted,
+        stopped_limit: stopping_word.is_none() && resp.finish_reason.as_deref() == Some("length"),
+        stopping_word,
+    };
+
+    let cost = estimate_cost(&model, tokens_evaluated, tokens_predicted);
+    record_trace(
+        state,
+        &agent_name,
+        LlmTrace {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now(),
+            model,
+            provider,
+            prompt_tokens: tokens_evaluated,
+            completion_tokens: tokens_predicted,
+            total_tokens: tokens_evaluated + tokens_predicted,
+            latency_ms: start.elapsed().as_millis() as u64,
+            cost_usd: cost,
+            cache_hit: false,
+            status: "ok".into(),
+            tool_calls: 0,
+            error: None,
+            reasoning,
+            fallback_from: None,
+        }
+        .with_fallback(fallback),
+    );
+    let _ = state.db.track_usage("requests", 1.0);
+    let _ = state.db.track_usage("tokens_in", tokens_evaluated as f64);
+    let _ = state.db.track_usage("tokens_out", tokens_predicted as f64);
+    let _ = state.db.track_usage("cost_usd", cost);
+    completion
+}
+
+async fn run(
+    agent: &bizclaw_agent::Agent,
+    req: &CompletionRequest,
+    on_text: Option<&OnText<'_>>,
+) -> (
+    bizclaw_core::error::Result<bizclaw_core::types::ProviderResponse>,
+    String,
+    String,
+) {
+    let mut params = agent.default_params();
+    if let Some(n) = req.n_predict.filter(|n| *n > 0) {
+        params.max_tokens = n.min(u32::MAX as i64) as u32;
+    }
+    if let Some(t) = req.temperature {
+        params.temperature = t;
+    }
+    params.stop = req.stop.clone();
+    let result = match on_text {
+        Some(on_text) => agent.complete_stream(&req.prompt, &params, on_text).await,
+        None => agent.complete(&req.prompt, &params).await,
+    };
+    (result, agent.provider_name().to_string(), params.model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(content: &str) -> Completion {
+        Completion {
+            content: content.into(),
+            model: "tinyllama".into(),
+            tokens_evaluated: 5,
+            tokens_predicted: 3,
+            stopping_word: None,
+            stopped_limit: false,
+        }
+    }
+
+    #[test]
+    fn test_completion_response_shape() {
+        let (content, word) = apply_stop("Paris.\nQ: next", &["\nQ:".into(), "".into()]);
+        assert_eq!(content, "Paris.");
+        assert_eq!(word.as_deref(), Some("\nQ:"));
+
+        let body = completion_response(&Completion {
+            stopping_word: word,
+            ..sample(&content)
+        });
+        assert_eq!(body["content"], "Paris.");
+        assert_eq!(body["tokens_predicted"], 3);
+        assert_eq!(body["tokens_evaluated"], 5);
+        assert_eq!(body["stop"], true);
+        assert_eq!(body["stopped_word"], true);
+        assert_eq!(body["stopped_eos"], false);
+        assert_eq!(body["stopping_word"], "\nQ:");
+
+        let req: CompletionRequest =
+            serde_json::from_str(r#"{"prompt": "Hi", "n_predict": -1}"#).unwrap();
+        assert!(!req.stream);
+        assert!(req.stop.is_empty());
+    }
+
+    #[test]
+    fn test_stop_word_split_across_stream_chunks() {
+        let mut filter = StopFilter::new(&["\nQ:".into(), "".into()]);
+        assert_eq!(filter.push("Paris"), "Paris");
+        // Could be the start of "\nQ:", so it is held back...
+        assert_eq!(filter.push(".\n"), ".");
+        // ...and dropped once the stop word completes.
+        assert_eq!(filter.push("Q: next"), "");
+        assert_eq!(filter.push(" more"), "");
+        assert_eq!(filter.finish(), "");
+
+        let mut filter = StopFilter::new(&["\nQ:".into()]);
+        assert_eq!(filter.push("Line\n"), "Line");
+        assert_eq!(filter.push("Done"), "\nDone");
+        assert_eq!(filter.push("\n"), "");
+        assert_eq!(filter.finish(), "\n");
+    }
+}
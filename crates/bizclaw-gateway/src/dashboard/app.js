@@ -3046,17 +3046,18 @@ function TracesPage({ lang }) {
   const [traces, setTraces] = useState([]);
   const [stats, setStats] = useState({});
   const [loading, setLoading] = useState(true);
+  const [showReasoning, setShowReasoning] = useState(false);
 
   const load = async () => {
     try {
-      const res = await authFetch('/api/v1/traces');
+      const res = await authFetch('/api/v1/traces' + (showReasoning ? '?reasoning=true' : ''));
       const data = await res.json();
       setTraces(data.traces || []);
       setStats(data.stats || {});
     } catch (e) { console.error('Traces load:', e); }
     setLoading(false);
   };
-  useEffect(() => { load(); }, []);
+  useEffect(() => { load(); }, [showReasoning]);
 
   const clearTraces = async () => {
     if(!confirm('Xoá tất cả traces?')) return;
@@ -3089,7 +3090,12 @@ function TracesPage({ lang }) {
     </div>
 
     <div class="card">
-      <h3 style="margin-bottom:12px">📈 Recent Traces (${traces.length})</h3>
+      <div style="display:flex;justify-content:space-between;align-items:center;margin-bottom:12px">
+        <h3>📈 Recent Traces (${traces.length})</h3>
+        <label style="font-size:13px;color:var(--text2);cursor:pointer">
+          <input type="checkbox" checked=${showReasoning} onChange=${e=>setShowReasoning(e.target.checked)} /> 🧠 Show reasoning
+        </label>
+      </div>
       ${loading ? html`<div style="text-align:center;padding:20px;color:var(--text2)">Loading...</div>` : html`
         <table>
           <thead><tr>
@@ -3107,7 +3113,10 @@ function TracesPage({ lang }) {
               <td style="font-family:var(--mono);font-size:12px;color:var(--orange)">${fmtCost(t.cost_usd)}</td>
               <td>${t.cache_hit ? '✅' : '➖'}</td>
               <td><span class="badge ${t.status === 'ok' ? 'badge-green' : 'badge-red'}">${t.status}</span></td>
-            </tr>`)}
+            </tr>
+            ${showReasoning && t.reasoning ? html`<tr key=${t.id + '-r'}><td colspan="9">
+              <pre style="margin:0;white-space:pre-wrap;font-family:var(--mono);font-size:12px;color:var(--text2)">🧠 ${t.reasoning}</pre>
+            </td></tr>` : ''}`)}
           </tbody>
         </table>
       `}
@@ -0,0 +1,275 @@
+//! Workspace file browser — dashboard access to the agent workspace.
+//!
+//! Every path is resolved relative to the configured workspace
+//! (`autonomy.workspace_dir`) and must stay inside it after symlinks are
+//! resolved. Absolute paths and `..` components are rejected outright.
+
+use axum::{Json, extract::State};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use super::server::AppState;
+
+/// Max bytes returned by the content endpoint.
+const MAX_READ_BYTES: u64 = 1_048_576;
+
+/// A directory entry returned by the listing endpoint.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FileEntry {
+    pub name: String,
+    /// Path relative to the workspace root, using `/` separators.
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<String>,
+}
+
+/// Resolve `rel` inside `root`, rejecting traversal and symlink escapes.
+/// The target must exist.
+pub fn resolve_in_workspace(root: &Path, rel: &str) -> Result<PathBuf, String> {
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("Workspace unavailable: {e}"))?;
+    let rel = rel.trim().trim_start_matches('/');
+    let rel_path = Path::new(rel);
+    if rel_path
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!("Access denied: '{rel}' leaves the workspace"));
+    }
+    let target = root
+        .join(rel_path)
+        .canonicalize()
+        .map_err(|_| format!("Not found: '{rel}'"))?;
+    if !target.starts_with(&root) {
+        // A symlink inside the workspace points outside it.
+        return Err(format!("Access denied: '{rel}' leaves the workspace"));
+    }
+    Ok(target)
+}
+
+fn relative_display(root: &Path, path: &Path) -> String {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    path.strip_prefix(&root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// List a workspace directory (directories first, then by name).
+pub fn list_dir(root: &Path, rel: &str) -> Result<Vec<FileEntry>, String> {
+    let dir = resolve_in_workspace(root, rel)?;
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: '{rel}'"));
+    }
+    let mut entries: Vec<FileEntry> = std::fs::read_dir(&dir)
+        .map_err(|e| format!("Read dir: {e}"))?
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            Some(FileEntry {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                path: relative_display(root, &entry.path()),
+                is_dir: meta.is_dir(),
+                size: if meta.is_dir() { 0 } else { meta.len() },
+                modified: meta
+                    .modified()
+                    .ok()
+                    .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    Ok(entries)
+}
+
+/// Read a workspace file as text (lossy UTF-8), up to [`MAX_READ_BYTES`].
+pub fn read_text(root: &Path, rel: &str) -> Result<String, String> {
+    let path = resolve_in_workspace(root, rel)?;
+    if !path.is_file() {
+        return Err(format!("Not a file: '{rel}'"));
+    }
+    let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    if size > MAX_READ_BYTES {
+        return Err(format!(
+            "File too large to preview ({size} bytes, max {MAX_READ_BYTES})"
+        ));
+    }
+    let bytes = std::fs::read(&path).map_err(|e| format!("Read: {e}"))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Store an uploaded file in a workspace directory. `file_name` must be a
+/// bare file name; the destination directory must already exist.
+pub fn save_upload(
+    root: &Path,
+    rel_dir: &str,
+    file_name: &str,
+    data: &[u8],
+) -> Result<PathBuf, String> {
+    let dir = resolve_in_workspace(root, rel_dir)?;
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: '{rel_dir}'"));
+    }
+    let name = Path::new(file_name);
+    if file_name.is_empty()
+        || name.components().count() != 1
+        || !matches!(name.components().next(), Some(Component::Normal(_)))
+    {
+        return Err(format!("Invalid file name: '{file_name}'"));
+    }
+    let dest = dir.join(name);
+    if dest.is_symlink() {
+        return Err(format!("Access denied: '{file_name}' is a symlink"));
+    }
+    std::fs::write(&dest, data).map_err(|e| format!("Write: {e}"))?;
+    Ok(dest)
+}
+
+fn workspace_root(state: &AppState) -> PathBuf {
+    let cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+    cfg.autonomy.workspace_path()
+}
+
+/// GET /api/v1/files?path= — list a workspace directory.
+pub async fn list_files(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Json<serde_json::Value> {
+    let root = workspace_root(&state);
+    let _ = std::fs::create_dir_all(&root);
+    let rel = params.get("path").map(String::as_str).unwrap_or("");
+    match list_dir(&root, rel) {
+        Ok(entries) => Json(serde_json::json!({
+            "ok": true,
+            "path": rel,
+            "entries": entries,
+            "count": entries.len(),
+        })),
+        Err(e) => Json(serde_json::json!({"ok": false, "error": e})),
+    }
+}
+
+/// GET /api/v1/files/content?path= — read a workspace file.
+pub async fn read_file_content(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Json<serde_json::Value> {
+    let root = workspace_root(&state);
+    let Some(rel) = params.get("path") else {
+        return Json(serde_json::json!({"ok": false, "error": "'path' is required"}));
+    };
+    match read_text(&root, rel) {
+        Ok(content) => Json(serde_json::json!({
+            "ok": true,
+            "path": rel,
+            "size": content.len(),
+            "content": content,
+        })),
+        Err(e) => Json(serde_json::json!({"ok": false, "error": e})),
+    }
+}
+
+/// POST /api/v1/files/upload?path= — multipart upload (field "file") into a workspace directory.
+pub async fn upload_file(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+    mut multipart: axum::extract::Multipart,
+) -> Json<serde_json::Value> {
+    let root = workspace_root(&state);
+    let _ = std::fs::create_dir_all(&root);
+    let rel_dir = params.get("path").map(String::as_str).unwrap_or("");
+
+    while let Ok(Some(field)) = multipart.next_field().await {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file_name = field.file_name().unwrap_or("").to_string();
+        let data = match field.bytes().await {
+            Ok(b) => b,
+            Err(e) => {
+                return Json(serde_json::json!({"ok": false, "error": format!("Upload read: {e}")}));
+            }
+        };
+        return match save_upload(&root, rel_dir, &file_name, &data) {
+            Ok(dest) => {
+                tracing::info!(
+                    "📤 Workspace upload: {} ({} bytes)",
+                    dest.display(),
+                    data.len()
+                );
+                Json(serde_json::json!({
+                    "ok": true,
+                    "path": relative_display(&root, &dest),
+                    "size": data.len(),
+                }))
+            }
+            Err(e) => Json(serde_json::json!({"ok": false, "error": e})),
+        };
+    }
+    Json(serde_json::json!({
+        "ok": false,
+        "error": "No file uploaded. Use multipart/form-data with field name 'file'"
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_workspace() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("bizclaw-files-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("notes")).unwrap();
+        std::fs::write(root.join("notes/todo.md"), "- ship it").unwrap();
+        std::fs::write(root.join("README.md"), "hello").unwrap();
+        root
+    }
+
+    #[test]
+    fn test_list_directory() {
+        let root = temp_workspace();
+        let entries = list_dir(&root, "").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[0].name, "notes");
+        assert_eq!(entries[1].path, "README.md");
+
+        let nested = list_dir(&root, "notes").unwrap();
+        assert_eq!(nested[0].path, "notes/todo.md");
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_read_file() {
+        let root = temp_workspace();
+        assert_eq!(read_text(&root, "notes/todo.md").unwrap(), "- ship it");
+        assert!(read_text(&root, "notes").is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_blocks_traversal() {
+        let root = temp_workspace();
+        assert!(read_text(&root, "../../etc/passwd").is_err());
+        assert!(read_text(&root, "notes/../../outside").is_err());
+        assert!(list_dir(&root, "..").is_err());
+        assert!(save_upload(&root, "", "../evil.sh", b"x").is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_blocks_symlink_escape() {
+        let root = temp_workspace();
+        let outside =
+            std::env::temp_dir().join(format!("bizclaw-outside-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&outside, "secret").unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+        assert!(read_text(&root, "link").is_err());
+        std::fs::remove_dir_all(&root).ok();
+        std::fs::remove_file(&outside).ok();
+    }
+}
@@ -0,0 +1,226 @@
+//! Idempotency keys for the chat API.
+//!
+//! A client that retries a timed-out request sends the same
+//! `Idempotency-Key` header again. The first request with a key runs and its
+//! response is cached; retries get that response back instead of running
+//! (and billing) the turn a second time. A retry that arrives while the
+//! first request is still running waits for it.
+//!
+//! Keys are scoped by the caller (API key and session), so two clients never
+//! see each other's responses. A key reused for a request with a different
+//! body is refused rather than answered with the other request's response.
+//! Cached responses expire after a TTL, and the cache holds a bounded number
+//! of keys; the oldest are dropped first.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+/// Request header carrying the idempotency key.
+pub const HEADER: &str = "idempotency-key";
+
+/// How long a cached response is replayed.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Max cached keys before the oldest are dropped.
+pub const DEFAULT_MAX_KEYS: usize = 10_000;
+
+struct Slot {
+    created: Instant,
+    /// Fingerprint of the request that first used the key.
+    fingerprint: String,
+    response: Arc<OnceCell<Value>>,
+}
+
+/// Why [`IdempotencyCache::run`] returned no response.
+#[derive(Debug, PartialEq)]
+pub enum IdempotencyError<E> {
+    /// The key was first used for a request with a different fingerprint.
+    KeyReused,
+    /// Processing failed; nothing was cached.
+    Failed(E),
+}
+
+/// Responses cached by scoped idempotency key.
+pub struct IdempotencyCache {
+    ttl: Duration,
+    max_keys: usize,
+    slots: Mutex<HashMap<String, Slot>>,
+}
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL, DEFAULT_MAX_KEYS)
+    }
+}
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration, max_keys: usize) -> Self {
+        Self {
+            ttl,
+            max_keys,
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `process` once per `(scope, key)` within the TTL. Returns the
+    /// response and whether it was replayed from the cache. A failed run is
+    /// not cached, so the client can retry it. `fingerprint` identifies the
+    /// request; reusing the key with another fingerprint is refused.
+    pub async fn run<F, Fut, E>(
+        &self,
+        scope: &str,
+        key: &str,
+        fingerprint: &str,
+        process: F,
+    ) -> Result<(Value, bool), IdempotencyError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Value, E>>,
+    {
+        let response = self
+            .slot(&format!("{scope}\n{key}"), fingerprint)
+            .ok_or(IdempotencyError::KeyReused)?;
+        let mut replayed = true;
+        let value = response
+            .get_or_try_init(|| {
+                replayed = false;
+                process()
+            })
+            .await
+            .map_err(IdempotencyError::Failed)?;
+        Ok((value.clone(), replayed))
+    }
+
+    /// Number of cached keys, including expired ones not yet dropped.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// The key's response cell, or `None` if the key belongs to a request
+    /// with another fingerprint.
+    fn slot(&self, scoped_key: &str, fingerprint: &str) -> Option<Arc<OnceCell<Value>>> {
+        let mut slots = self.lock();
+        let now = Instant::now();
+        slots.retain(|_, slot| now.duration_since(slot.created) < self.ttl);
+        if let Some(slot) = slots.get(scoped_key) {
+            return (slot.fingerprint == fingerprint).then(|| slot.response.clone());
+        }
+        if slots.len() >= self.max_keys
+            && let Some(oldest) = slots
+                .iter()
+                .min_by_key(|(_, slot)| slot.created)
+                .map(|(k, _)| k.clone())
+        {
+            slots.remove(&oldest);
+        }
+        let response = Arc::new(OnceCell::new());
+        slots.insert(
+            scoped_key.to_string(),
+            Slot {
+                created: now,
+                fingerprint: fingerprint.to_string(),
+                response: response.clone(),
+            },
+        );
+        Some(response)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Slot>> {
+        self.slots.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn chat(
+        cache: &IdempotencyCache,
+        scope: &str,
+        key: &str,
+        calls: &AtomicUsize,
+    ) -> (Value, bool) {
+        cache
+            .run(scope, key, "body", || async {
+                let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                tokio::task::yield_now().await;
+                Ok::<_, ()>(json!({"id": format!("chatcmpl-{n}"), "content": "Hello!"}))
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_same_key_processed_once() {
+        let cache = IdempotencyCache::default();
+        let calls = AtomicUsize::new(0);
+
+        let (first, replayed) = chat(&cache, "tenant-a", "key-1", &calls).await;
+        assert!(!replayed);
+        let (retry, replayed) = chat(&cache, "tenant-a", "key-1", &calls).await;
+        assert!(replayed);
+        assert_eq!(retry, first);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // A retry racing the first request waits for it.
+        let (a, b) = tokio::join!(
+            chat(&cache, "tenant-a", "key-2", &calls),
+            chat(&cache, "tenant-a", "key-2", &calls),
+        );
+        assert_eq!(a.0, b.0);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // The same key from another caller is a different request.
+        let (other, replayed) = chat(&cache, "tenant-b", "key-1", &calls).await;
+        assert!(!replayed);
+        assert_ne!(other, first);
+    }
+
+    #[tokio::test]
+    async fn test_failures_not_cached_and_keys_expire() {
+        let cache = IdempotencyCache::new(Duration::ZERO, 10);
+        let failed = cache
+            .run("tenant-a", "key-1", "body", || async {
+                Err::<Value, _>("timeout")
+            })
+            .await;
+        assert_eq!(failed, Err(IdempotencyError::Failed("timeout")));
+
+        let calls = AtomicUsize::new(0);
+        chat(&cache, "tenant-a", "key-1", &calls).await;
+        let (_, replayed) = chat(&cache, "tenant-a", "key-1", &calls).await;
+        assert!(!replayed, "expired keys run again");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let cache = IdempotencyCache::new(DEFAULT_TTL, 2);
+        for key in ["k1", "k2", "k3"] {
+            chat(&cache, "tenant-a", key, &calls).await;
+        }
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_key_reused_for_another_request_is_refused() {
+        let cache = IdempotencyCache::default();
+        let calls = AtomicUsize::new(0);
+        chat(&cache, "tenant-a", "key-1", &calls).await;
+
+        let reused = cache
+            .run("tenant-a", "key-1", "other body", || async {
+                Ok::<_, ()>(json!({"content": "never"}))
+            })
+            .await;
+        assert_eq!(reused, Err(IdempotencyError::KeyReused));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
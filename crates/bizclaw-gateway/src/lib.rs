@@ -3,14 +3,32 @@
 
 pub mod dashboard;
 pub mod db;
+pub mod completion;
+pub mod files;
+pub mod idempotency;
 pub mod openai_compat;
 pub mod routes;
 pub mod server;
+pub mod sessions;
+pub mod usage_report;
 pub mod ws;
+pub mod ws_auth;
 
 use bizclaw_core::config::GatewayConfig;
 
 /// Start the gateway HTTP server.
 pub async fn start_server(config: &GatewayConfig) -> anyhow::Result<()> {
-    server::start(config).await
+    server::start(config, std::future::pending()).await
+}
+
+/// Start the gateway HTTP server, stopping gracefully once `shutdown`
+/// resolves: new connections are refused and in-flight requests finish.
+pub async fn start_server_with_shutdown<F>(
+    config: &GatewayConfig,
+    shutdown: F,
+) -> anyhow::Result<()>
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    server::start(config, shutdown).await
 }
@@ -6,16 +6,18 @@
 //! Authentication: `Authorization: Bearer <pairing-code>` or `api-key` header.
 
 use axum::extract::State;
+use axum::response::{IntoResponse, Response};
 use axum::{Json, http::StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
 
+use super::idempotency::IdempotencyError;
 use super::server::AppState;
 
 // ─── Types ───────────────────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct ChatCompletionRequest {
     pub model: String,
     pub messages: Vec<ChatMessage>,
@@ -29,6 +31,9 @@ pub struct ChatCompletionRequest {
     pub top_p: Option<f64>,
     #[serde(default)]
     pub stop: Option<Vec<String>>,
+    /// End-user id; scopes `Idempotency-Key`s per user.
+    #[serde(default)]
+    pub user: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -80,7 +85,7 @@ pub struct ModelListResponse {
 // ─── Auth helper ─────────────────────────────────────────────────────────────
 
 /// Extract API key from Authorization header (Bearer token) or x-api-key header.
-fn extract_api_key(headers: &axum::http::HeaderMap) -> Option<String> {
+pub(crate) fn extract_api_key(headers: &axum::http::HeaderMap) -> Option<String> {
     // Try Authorization: Bearer <key>
     if let Some(auth) = headers.get("authorization")
         && let Ok(val) = auth.to_str()
@@ -96,7 +101,7 @@ fn extract_api_key(headers: &axum::http::HeaderMap) -> Option<String> {
 }
 
 /// Validate API key against pairing code. Returns true if valid.
-fn validate_key(state: &AppState, key: &str) -> bool {
+pub(crate) fn validate_key(state: &AppState, key: &str) -> bool {
     let stored = state.pairing_code.lock().unwrap_or_else(|p| p.into_inner()).clone();
     // Constant-time comparison
     if key.len() != stored.len() {
@@ -109,19 +114,78 @@ fn validate_key(state: &AppState, key: &str) -> bool {
         == 0
 }
 
+/// Completion text for a failed turn: the error code and a detail-free
+/// message. The full error only goes to the log.
+fn agent_error_text(e: &bizclaw_agent::AgentError) -> String {
+    tracing::error!("Chat completion failed ({}): {e}", e.kind.code());
+    format!(
+        "Error ({}): {}",
+        e.kind.code(),
+        e.user_message(bizclaw_core::i18n::Locale::En)
+    )
+}
+
 // ─── POST /v1/chat/completions ───────────────────────────────────────────────
 
+/// With an `Idempotency-Key` header, a retry of the same request gets the
+/// first response back (marked `Idempotent-Replayed: true`) instead of
+/// running the turn again. Failed turns are not replayed, and reusing a key
+/// for a different request is refused with 422.
 pub async fn chat_completions(
     State(state): State<Arc<AppState>>,
     headers: axum::http::HeaderMap,
     Json(req): Json<ChatCompletionRequest>,
-) -> Result<Json<Value>, StatusCode> {
+) -> Result<Response, StatusCode> {
     // Auth check
     let key = extract_api_key(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
     if !validate_key(&state, &key) {
         return Err(StatusCode::UNAUTHORIZED);
     }
 
+    let idempotency_key = headers
+        .get(super::idempotency::HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|k| !k.is_empty());
+    let Some(idempotency_key) = idempotency_key else {
+        return Ok(complete_chat(&state, req)
+            .await
+            .map_or_else(|failed| failed, |r| Json(r).into_response()));
+    };
+    let scope = format!("{key}/{}", req.user.as_deref().unwrap_or_default());
+    let fingerprint = request_fingerprint(&req);
+    let result = state
+        .idempotency
+        .run(&scope, idempotency_key, &fingerprint, || complete_chat(&state, req))
+        .await;
+    let (response, replayed) = match result {
+        Ok(done) => done,
+        Err(IdempotencyError::Failed(failed)) => return Ok(failed),
+        Err(IdempotencyError::KeyReused) => {
+            let error = json!({"error": {
+                "message": "Idempotency-Key was already used for a different request",
+                "type": "invalid_request_error",
+            }});
+            return Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(error)).into_response());
+        }
+    };
+    if replayed {
+        tracing::info!("Replayed chat completion for idempotency key {idempotency_key}");
+        return Ok(([("Idempotent-Replayed", "true")], Json(response)).into_response());
+    }
+    Ok(Json(response).into_response())
+}
+
+/// SHA-256 of the request as parsed, identifying it for idempotency.
+fn request_fingerprint(req: &ChatCompletionRequest) -> String {
+    use sha2::{Digest, Sha256};
+    let body = serde_json::to_vec(req).unwrap_or_default();
+    format!("{:x}", Sha256::digest(body))
+}
+
+/// Run one chat completion and build the OpenAI response body. A turn the
+/// agent failed is answered through `Err`, so it is not cached for replay.
+async fn complete_chat(state: &AppState, req: ChatCompletionRequest) -> Result<Value, Response> {
     let start = std::time::Instant::now();
 
     // Route "model" field to agent name — if model matches an agent, use it
@@ -133,29 +197,53 @@ pub async fn chat_completions(
         .and_then(|m| m.content.as_deref())
         .unwrap_or("");
 
-    let response_text = {
+    let (agent_name, response_text, reasoning, fallback) = {
         // Try to find agent by model name first
         let mut orch = state.orchestrator.lock().await;
         if let Some(agent) = orch.get_agent_mut(&req.model) {
             // Use the named agent
-            match agent.process(user_content).await {
-                Ok(r) => r,
-                Err(e) => format!("Error: {e}"),
-            }
+            let (text, reasoning, fallback) = match agent.process(user_content).await {
+                Ok(r) => (Ok(r), agent.last_reasoning().map(String::from), fallback_of(agent)),
+                Err(e) => (Err(agent_error_text(&e)), None, None),
+            };
+            (req.model.clone(), text, reasoning, fallback)
         } else {
             // Fallback to default agent
             drop(orch);
             let mut agent_lock = state.agent.lock().await;
             if let Some(agent) = agent_lock.as_mut() {
-                match agent.process(user_content).await {
-                    Ok(r) => r,
-                    Err(e) => format!("Error: {e}"),
+                // A model alias ("fast", "smart", …) runs this request on the
+                // aliased provider/model, then switches back.
+                let aliased = if agent.persona_name().is_none() {
+                    match bizclaw_agent::persona::Persona::aliased(&req.model, agent) {
+                        Ok(persona) => persona,
+                        Err(e) => {
+                            tracing::warn!("Model alias '{}' unusable: {e}", req.model);
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+                let switched = aliased.is_some();
+                if let Some(persona) = aliased {
+                    agent.adopt_persona(persona);
+                }
+                let (text, reasoning, fallback) = match agent.process(user_content).await {
+                    Ok(r) => (Ok(r), agent.last_reasoning().map(String::from), fallback_of(agent)),
+                    Err(e) => (Err(agent_error_text(&e)), None, None),
+                };
+                if switched {
+                    agent.restore_persona();
                 }
+                ("default".to_string(), text, reasoning, fallback)
             } else {
-                return Err(StatusCode::SERVICE_UNAVAILABLE);
+                return Err(StatusCode::SERVICE_UNAVAILABLE.into_response());
             }
         }
     };
+    let failed = response_text.is_err();
+    let response_text = response_text.unwrap_or_else(|error| error);
 
     let elapsed = start.elapsed();
     let est_prompt_tokens = (user_content.len() / 4) as u32;
@@ -174,16 +262,14 @@ pub async fn chat_completions(
             latency_ms: elapsed.as_millis() as u64,
             cost_usd: estimate_cost(&req.model, est_prompt_tokens, est_completion_tokens),
             cache_hit: false,
-            status: "ok".into(),
+            status: if failed { "error" } else { "ok" }.into(),
             tool_calls: 0,
-            error: None,
-        };
-        let mut traces = state.traces.lock().unwrap_or_else(|p| p.into_inner());
-        // Cap at 10,000 traces to prevent unbounded memory growth
-        if traces.len() >= 10_000 {
-            traces.drain(..1_000); // Remove oldest 1,000 when full
+            error: failed.then(|| response_text.clone()),
+            reasoning,
+            fallback_from: None,
         }
-        traces.push(trace);
+        .with_fallback(fallback);
+        record_trace(state, &agent_name, trace);
     }
 
     // Track usage in PaaS DB (daily aggregation)
@@ -198,7 +284,7 @@ pub async fn chat_completions(
     // Broadcast activity event via WebSocket
     let _ = state.activity_tx.send(ActivityEvent {
         event_type: "llm.completed".into(),
-        agent: req.model.clone(),
+        agent: agent_name,
         detail: format!("{}tok in {}ms", est_prompt_tokens + est_completion_tokens, elapsed.as_millis()),
         timestamp: chrono::Utc::now(),
     });
@@ -223,7 +309,10 @@ pub async fn chat_completions(
         }
     });
 
-    Ok(Json(response))
+    if failed {
+        return Err(Json(response).into_response());
+    }
+    Ok(response)
 }
 
 // ─── GET /v1/models ──────────────────────────────────────────────────────────
@@ -259,6 +348,21 @@ pub async fn list_models(
         "owned_by": "bizclaw",
     }));
 
+    // Model aliases can be requested like models
+    let mut aliases: Vec<String> = {
+        let cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+        cfg.model_aliases.keys().cloned().collect()
+    };
+    aliases.sort();
+    for alias in aliases {
+        models.push(json!({
+            "id": alias,
+            "object": "model",
+            "created": chrono::Utc::now().timestamp(),
+            "owned_by": "bizclaw:alias",
+        }));
+    }
+
     Ok(Json(json!({
         "object": "list",
         "data": models,
@@ -283,6 +387,32 @@ pub struct LlmTrace {
     pub status: String,
     pub tool_calls: u32,
     pub error: Option<String>,
+    /// Reasoning-model thoughts, hidden from `/api/v1/traces` unless
+    /// `?reasoning=true` is passed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    /// The provider that failed over when `provider` is a local fallback.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_from: Option<String>,
+}
+
+impl LlmTrace {
+    /// Attribute the call to the local fallback that answered it, if any.
+    pub(crate) fn with_fallback(mut self, fallback: Option<(String, String)>) -> Self {
+        if let Some((local, from)) = fallback {
+            self.provider = local;
+            self.fallback_from = Some(from);
+        }
+        self
+    }
+}
+
+/// `(local fallback, provider it stood in for)` when the agent's last turn
+/// was answered offline.
+pub(crate) fn fallback_of(agent: &bizclaw_agent::Agent) -> Option<(String, String)> {
+    agent
+        .last_fallback()
+        .map(|local| (local.to_string(), agent.provider_name().to_string()))
 }
 
 /// Real-time activity event — broadcast to all connected dashboards.
@@ -294,10 +424,21 @@ pub struct ActivityEvent {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+/// Keep a trace for the dashboard and report it to the platform (if any).
+pub(crate) fn record_trace(state: &AppState, agent_name: &str, trace: LlmTrace) {
+    super::usage_report::report_trace(agent_name, &trace);
+    let mut traces = state.traces.lock().unwrap_or_else(|p| p.into_inner());
+    // Cap at 10,000 traces to prevent unbounded memory growth
+    if traces.len() >= 10_000 {
+        traces.drain(..1_000); // Remove oldest 1,000 when full
+    }
+    traces.push(trace);
+}
+
 // ─── Cost estimation ─────────────────────────────────────────────────────────
 
 /// Rough cost estimation per model (USD per 1M tokens).
-fn estimate_cost(model: &str, prompt_tokens: u32, completion_tokens: u32) -> f64 {
+pub(crate) fn estimate_cost(model: &str, prompt_tokens: u32, completion_tokens: u32) -> f64 {
     let (input_rate, output_rate) = match model {
         m if m.contains("gpt-4o-mini") => (0.15, 0.60),
         m if m.contains("gpt-4o") => (2.50, 10.00),
@@ -319,12 +460,28 @@ fn estimate_cost(model: &str, prompt_tokens: u32, completion_tokens: u32) -> f64
 
 // ─── Trace API Handlers ──────────────────────────────────────────────────────
 
+#[derive(Debug, Default, Deserialize)]
+pub struct TraceQuery {
+    /// Include reasoning-model thoughts in each trace.
+    #[serde(default)]
+    pub reasoning: bool,
+}
+
 /// GET /api/v1/traces — list recent LLM call traces.
 pub async fn list_traces(
     State(state): State<Arc<AppState>>,
+    axum::extract::Query(query): axum::extract::Query<TraceQuery>,
 ) -> Json<Value> {
     let traces = state.traces.lock().unwrap_or_else(|p| p.into_inner());
-    let recent: Vec<_> = traces.iter().rev().take(100).cloned().collect();
+    let recent: Vec<_> = traces
+        .iter()
+        .rev()
+        .take(100)
+        .map(|t| LlmTrace {
+            reasoning: t.reasoning.clone().filter(|_| query.reasoning),
+            ..t.clone()
+        })
+        .collect();
 
     // Aggregate stats
     let total_cost: f64 = traces.iter().map(|t| t.cost_usd).sum();
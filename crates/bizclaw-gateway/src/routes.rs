@@ -57,7 +57,7 @@ fn mask_secret(s: &str) -> String {
 /// - LLM section: config.llm.provider, config.llm.api_key, config.llm.endpoint
 ///
 /// `create_provider()` reads from `llm.*` FIRST, so we must set both.
-fn apply_provider_config_from_db(
+pub fn apply_provider_config_from_db(
     db: &GatewayDb,
     config: &mut bizclaw_core::config::BizClawConfig,
 ) {
@@ -185,6 +185,8 @@ pub async fn get_config(State(state): State<Arc<AppState>>) -> Json<serde_json::
                 "bot_token": mask_secret(&t.bot_token),
                 "bot_token_set": !t.bot_token.is_empty(),
                 "allowed_chat_ids": t.allowed_chat_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "),
+                "welcome_message": t.welcome_message,
+                "parse_mode": t.parse_mode,
             })),
             "zalo": cfg.channel.zalo.as_ref().map(|z| serde_json::json!({
                 "enabled": z.enabled,
@@ -194,12 +196,14 @@ pub async fn get_config(State(state): State<Arc<AppState>>) -> Json<serde_json::
                 "imei": z.personal.imei,
                 "self_listen": z.personal.self_listen,
                 "auto_reconnect": z.personal.auto_reconnect,
+                "welcome_message": z.welcome_message,
             })),
             "discord": cfg.channel.discord.as_ref().map(|d| serde_json::json!({
                 "enabled": d.enabled,
                 "bot_token": mask_secret(&d.bot_token),
                 "bot_token_set": !d.bot_token.is_empty(),
                 "allowed_channel_ids": d.allowed_channel_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "),
+                "welcome_message": d.welcome_message,
             })),
             "email": cfg.channel.email.as_ref().map(|e| serde_json::json!({
                 "enabled": e.enabled,
@@ -209,27 +213,70 @@ pub async fn get_config(State(state): State<Arc<AppState>>) -> Json<serde_json::
                 "smtp_pass": mask_secret(&e.password),
                 "imap_host": e.imap_host,
                 "imap_port": e.imap_port,
+                "welcome_message": e.welcome_message,
             })),
             "whatsapp": cfg.channel.whatsapp.as_ref().map(|w| serde_json::json!({
                 "enabled": w.enabled,
                 "phone_number_id": w.phone_number_id,
                 "access_token": mask_secret(&w.access_token),
+                "app_secret_set": !w.app_secret.is_empty(),
                 "business_id": w.business_id,
+                "welcome_message": w.welcome_message,
             })),
             "webhook": cfg.channel.webhook.as_ref().map(|wh| serde_json::json!({
                 "enabled": wh.enabled,
                 "secret": mask_secret(&wh.secret),
                 "secret_set": !wh.secret.is_empty(),
                 "outbound_url": wh.outbound_url,
+                "welcome_message": wh.welcome_message,
             })),
         },
     }))
 }
 
-/// Get full config as TOML string for export/display.
+/// Whether a config key holds a secret (API keys, tokens, passwords).
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    key.ends_with("key")
+        || key.ends_with("token")
+        || key.contains("secret")
+        || key.contains("password")
+        || key == "cookie"
+}
+
+/// Mask every secret string in a config value, including MCP server `env`.
+fn mask_secrets(value: &mut toml::Value) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                match value {
+                    toml::Value::String(s) if is_secret_key(key) => *s = mask_secret(s),
+                    toml::Value::Table(env) if key == "env" => {
+                        for (_, v) in env.iter_mut() {
+                            if let toml::Value::String(s) = v {
+                                *s = mask_secret(s);
+                            }
+                        }
+                    }
+                    other => mask_secrets(other),
+                }
+            }
+        }
+        toml::Value::Array(items) => items.iter_mut().for_each(mask_secrets),
+        _ => {}
+    }
+}
+
+/// Get full config as TOML string for export/display, secrets masked.
 pub async fn get_full_config(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
     let cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
-    let toml_str = toml::to_string_pretty(&*cfg).unwrap_or_default();
+    let toml_str = toml::Value::try_from(&*cfg)
+        .map(|mut value| {
+            mask_secrets(&mut value);
+            value
+        })
+        .and_then(|value| toml::to_string_pretty(&value))
+        .unwrap_or_default();
     Json(serde_json::json!({
         "ok": true,
         "toml": toml_str,
@@ -237,6 +284,27 @@ pub async fn get_full_config(State(state): State<Arc<AppState>>) -> Json<serde_j
     }))
 }
 
+/// Describe every configurable field so the dashboard can build its config
+/// form from the real config instead of a hand-kept list.
+pub async fn get_config_schema() -> Json<serde_json::Value> {
+    Json(serde_json::json!({"ok": true, "fields": config_schema().fields}))
+}
+
+/// [`ConfigSchema`](bizclaw_core::config_schema::ConfigSchema) with the
+/// provider fields limited to the providers this build knows.
+fn config_schema() -> bizclaw_core::config_schema::ConfigSchema {
+    let mut schema = bizclaw_core::config_schema::ConfigSchema::generate();
+    let mut providers: Vec<String> = bizclaw_providers::provider_registry::all_provider_names()
+        .into_iter()
+        .map(String::from)
+        .collect();
+    providers.push("brain".into());
+    for path in ["default_provider", "LLM.provider"] {
+        schema.set_options(path, providers.clone());
+    }
+    schema
+}
+
 /// Update config fields via JSON body.
 pub async fn update_config(
     State(state): State<Arc<AppState>>,
@@ -330,8 +398,6 @@ pub async fn update_config(
             cfg.mcp_servers = servers;
         }
 
-    // Save to disk
-    let content = toml::to_string_pretty(&*cfg).unwrap_or_default();
     let new_cfg = cfg.clone();
 
     // Build sync data for platform DB import
@@ -354,7 +420,7 @@ pub async fn update_config(
 
     drop(cfg); // Release lock before file write + agent reinit
 
-    match std::fs::write(&state.config_path, &content) {
+    match new_cfg.save_to(&state.config_path) {
         Ok(_) => {
             tracing::info!("✅ Config saved to {}", state.config_path.display());
 
@@ -404,6 +470,11 @@ pub async fn update_channel(
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
     let mut cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+    // Omitted = keep the current welcome message; empty string = turn it off.
+    let welcome_message = |current: Option<String>| match req.get("welcome_message") {
+        Some(v) => v.as_str().map(str::trim).filter(|m| !m.is_empty()).map(String::from),
+        None => current,
+    };
 
     match channel_type {
         "telegram" => {
@@ -424,15 +495,28 @@ pub async fn update_channel(
                 .split(',')
                 .filter_map(|s| s.trim().parse().ok())
                 .collect();
+            let current = cfg.channel.telegram.clone();
+            // Missing or unknown parse_mode keeps the current one.
+            let parse_mode = req
+                .get("parse_mode")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .or(current.as_ref().map(|t| t.parse_mode))
+                .unwrap_or_default();
             cfg.channel.telegram = Some(bizclaw_core::config::TelegramChannelConfig {
                 enabled,
                 bot_token: token,
                 allowed_chat_ids: chat_ids,
+                welcome_message: welcome_message(
+                    current.as_ref().and_then(|t| t.welcome_message.clone()),
+                ),
+                parse_mode,
+                chat_parse_modes: current.map(|t| t.chat_parse_modes).unwrap_or_default(),
             });
         }
         "zalo" => {
             let mut zalo_cfg = cfg.channel.zalo.clone().unwrap_or_default();
             zalo_cfg.enabled = enabled;
+            zalo_cfg.welcome_message = welcome_message(zalo_cfg.welcome_message.take());
             if let Some(v) = req.get("cookie").and_then(|v| v.as_str()) {
                 // Save cookie to file
                 let cookie_dir = state
@@ -471,6 +555,9 @@ pub async fn update_channel(
                 enabled,
                 bot_token: token,
                 allowed_channel_ids: ids,
+                welcome_message: welcome_message(
+                    cfg.channel.discord.as_ref().and_then(|d| d.welcome_message.clone()),
+                ),
             });
         }
         "email" => {
@@ -513,6 +600,9 @@ pub async fn update_channel(
                 password,
                 imap_host,
                 imap_port: 993,
+                welcome_message: welcome_message(
+                    cfg.channel.email.as_ref().and_then(|e| e.welcome_message.clone()),
+                ),
             });
         }
         "whatsapp" => {
@@ -542,10 +632,22 @@ pub async fn update_channel(
                     .and_then(|v| v.as_str())
                     .unwrap_or("")
                     .to_string(),
+                app_secret: match req.get("app_secret").and_then(|v| v.as_str()) {
+                    Some(v) if !v.contains('•') => v.to_string(),
+                    _ => cfg
+                        .channel
+                        .whatsapp
+                        .as_ref()
+                        .map(|w| w.app_secret.clone())
+                        .unwrap_or_default(),
+                },
                 business_id: req.get("business_id")
                     .and_then(|v| v.as_str())
                     .unwrap_or("")
                     .to_string(),
+                welcome_message: welcome_message(
+                    cfg.channel.whatsapp.as_ref().and_then(|w| w.welcome_message.clone()),
+                ),
             });
         }
         "webhook" => {
@@ -560,6 +662,9 @@ pub async fn update_channel(
                 enabled,
                 secret,
                 outbound_url,
+                welcome_message: welcome_message(
+                    cfg.channel.webhook.as_ref().and_then(|wh| wh.welcome_message.clone()),
+                ),
             });
         }
         _ => {
@@ -570,52 +675,150 @@ pub async fn update_channel(
     }
 
     // Save to disk
-    let content = toml::to_string_pretty(&*cfg).unwrap_or_default();
-    match std::fs::write(&state.config_path, &content) {
+    match cfg.save_to(&state.config_path) {
         Ok(_) => {
-            // Also save channels as standalone JSON for platform DB sync on restart
-            // This prevents channel loss when platform regenerates config.toml
-            if let Some(parent) = state.config_path.parent() {
-                let channels_json = serde_json::json!({
-                    // SECURITY: Never write secrets to sync file — only enabled flags + non-sensitive data
-                    "telegram": cfg.channel.telegram.as_ref().map(|t| serde_json::json!({
-                        "enabled": t.enabled,
-                        "bot_token_set": !t.bot_token.is_empty(),
-                        "allowed_chat_ids": t.allowed_chat_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "),
-                    })),
-                    "zalo": cfg.channel.zalo.as_ref().map(|z| serde_json::json!({
-                        "enabled": z.enabled,
-                        "mode": z.mode,
-                    })),
-                    "discord": cfg.channel.discord.as_ref().map(|d| serde_json::json!({
-                        "enabled": d.enabled,
-                        "bot_token_set": !d.bot_token.is_empty(),
-                    })),
-                    "email": cfg.channel.email.as_ref().map(|e| serde_json::json!({
-                        "enabled": e.enabled,
-                        "smtp_host": e.smtp_host,
-                        "smtp_port": e.smtp_port,
-                        "email": e.email,
-                    })),
-                    "whatsapp": cfg.channel.whatsapp.as_ref().map(|w| serde_json::json!({
-                        "enabled": w.enabled,
-                        "phone_number_id": w.phone_number_id,
-                    })),
-                    "webhook": cfg.channel.webhook.as_ref().map(|wh| serde_json::json!({
-                        "enabled": wh.enabled,
-                        "secret_set": !wh.secret.is_empty(),
-                        "outbound_url": wh.outbound_url,
-                    })),
-                });
-                let sync_path = parent.join("channels_sync.json");
-                std::fs::write(&sync_path, serde_json::to_string_pretty(&channels_json).unwrap_or_default()).ok();
-            }
+            write_channels_sync(&state, &cfg.channel);
             Json(serde_json::json!({"ok": true, "message": format!("{channel_type} config saved")}))
         }
         Err(e) => internal_error("gateway", e),
     }
 }
 
+/// Save channels as standalone JSON for platform DB sync on restart.
+/// This prevents channel loss when platform regenerates config.toml.
+fn write_channels_sync(state: &AppState, channels: &bizclaw_core::config::ChannelConfig) {
+    let Some(parent) = state.config_path.parent() else {
+        return;
+    };
+    let channels_json = serde_json::json!({
+        // SECURITY: Never write secrets to sync file — only enabled flags + non-sensitive data
+        "telegram": channels.telegram.as_ref().map(|t| serde_json::json!({
+            "enabled": t.enabled,
+            "bot_token_set": !t.bot_token.is_empty(),
+            "allowed_chat_ids": t.allowed_chat_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "),
+        })),
+        "zalo": channels.zalo.as_ref().map(|z| serde_json::json!({
+            "enabled": z.enabled,
+            "mode": z.mode,
+        })),
+        "discord": channels.discord.as_ref().map(|d| serde_json::json!({
+            "enabled": d.enabled,
+            "bot_token_set": !d.bot_token.is_empty(),
+        })),
+        "email": channels.email.as_ref().map(|e| serde_json::json!({
+            "enabled": e.enabled,
+            "smtp_host": e.smtp_host,
+            "smtp_port": e.smtp_port,
+            "email": e.email,
+        })),
+        "whatsapp": channels.whatsapp.as_ref().map(|w| serde_json::json!({
+            "enabled": w.enabled,
+            "phone_number_id": w.phone_number_id,
+        })),
+        "webhook": channels.webhook.as_ref().map(|wh| serde_json::json!({
+            "enabled": wh.enabled,
+            "secret_set": !wh.secret.is_empty(),
+            "outbound_url": wh.outbound_url,
+        })),
+    });
+    let sync_path = parent.join("channels_sync.json");
+    std::fs::write(&sync_path, serde_json::to_string_pretty(&channels_json).unwrap_or_default()).ok();
+}
+
+/// A channel's saved config with its secrets masked.
+fn masked_channel_config(kind: &str, mut config: serde_json::Value) -> serde_json::Value {
+    for field in bizclaw_core::config::ChannelConfig::secret_fields(kind) {
+        if let Some(v) = config.get_mut(*field)
+            && let Some(secret) = v.as_str()
+        {
+            *v = mask_secret(secret).into();
+        }
+    }
+    config
+}
+
+/// GET /api/v1/config/channels/{type} — one channel's saved config.
+pub async fn get_channel_config(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(kind): axum::extract::Path<String>,
+) -> Json<serde_json::Value> {
+    let cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+    match cfg.channel.get(&kind) {
+        Ok(Some(config)) => Json(serde_json::json!({
+            "ok": true,
+            "channel_type": kind,
+            "config": masked_channel_config(&kind, config),
+        })),
+        Ok(None) => Json(serde_json::json!({
+            "ok": false,
+            "error": format!("Channel '{kind}' is not configured"),
+        })),
+        Err(e) => Json(serde_json::json!({"ok": false, "error": e.to_string()})),
+    }
+}
+
+/// PUT /api/v1/config/channels/{type} — validate and replace one channel's config.
+/// Masked secrets (as returned by GET) keep the saved value. The in-memory
+/// config only changes once the file has been written.
+pub async fn put_channel_config(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(kind): axum::extract::Path<String>,
+    Json(mut body): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let mut cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+    if let Ok(Some(current)) = cfg.channel.get(&kind) {
+        for field in bizclaw_core::config::ChannelConfig::secret_fields(&kind) {
+            if let Some(v) = body.get_mut(*field)
+                && v.as_str().is_some_and(|s| s.contains('•'))
+            {
+                *v = current[*field].clone();
+            }
+        }
+    }
+
+    let mut updated = cfg.clone();
+    if let Err(e) = updated.channel.set(&kind, body) {
+        return Json(serde_json::json!({"ok": false, "error": e.to_string()}));
+    }
+    if let Err(e) = updated.save_to(&state.config_path) {
+        return internal_error("gateway", e);
+    }
+    *cfg = updated;
+    write_channels_sync(&state, &cfg.channel);
+
+    let saved = cfg.channel.get(&kind).ok().flatten().unwrap_or_default();
+    Json(serde_json::json!({
+        "ok": true,
+        "channel_type": kind,
+        "config": masked_channel_config(&kind, saved),
+    }))
+}
+
+/// DELETE /api/v1/config/channels/{type} — remove one channel's config.
+pub async fn delete_channel_config(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(kind): axum::extract::Path<String>,
+) -> Json<serde_json::Value> {
+    let mut cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+    let mut updated = cfg.clone();
+    match updated.channel.remove(&kind) {
+        Ok(true) => {}
+        Ok(false) => {
+            return Json(serde_json::json!({
+                "ok": false,
+                "error": format!("Channel '{kind}' is not configured"),
+            }));
+        }
+        Err(e) => return Json(serde_json::json!({"ok": false, "error": e.to_string()})),
+    }
+    if let Err(e) = updated.save_to(&state.config_path) {
+        return internal_error("gateway", e);
+    }
+    *cfg = updated;
+    write_channels_sync(&state, &cfg.channel);
+    Json(serde_json::json!({"ok": true, "message": format!("{kind} config removed")}))
+}
+
 /// Channel instances file path helper.
 fn channel_instances_path(state: &AppState) -> std::path::PathBuf {
     state.config_path.parent()
@@ -649,6 +852,19 @@ fn save_channel_instances(state: &AppState, instances: &[serde_json::Value]) {
     }
 }
 
+/// Record the runtime status of a channel instance (`connected` / `error`)
+/// so the dashboard can show why a bot stopped.
+fn set_channel_instance_status(state: &AppState, instance_id: &str, status: &str, error: Option<&str>) {
+    let mut instances = load_channel_instances(state);
+    let Some(inst) = instances.iter_mut().find(|i| i["id"].as_str() == Some(instance_id)) else {
+        return;
+    };
+    inst["status"] = serde_json::json!(status);
+    inst["last_error"] = serde_json::json!(error);
+    inst["status_at"] = serde_json::json!(chrono::Utc::now().to_rfc3339());
+    save_channel_instances(state, &instances);
+}
+
 /// List all channel instances (secrets masked for frontend display).
 pub async fn list_channel_instances(
     State(state): State<Arc<AppState>>,
@@ -735,21 +951,29 @@ pub async fn save_channel_instance(
                 let chat_ids: Vec<i64> = sync_body.get("allowed_chat_ids")
                     .and_then(|v| v.as_str()).unwrap_or("")
                     .split(',').filter_map(|s| s.trim().parse().ok()).collect();
+                let current = full_cfg.channel.telegram.take();
+                let welcome_message = current.as_ref().and_then(|t| t.welcome_message.clone());
+                let parse_mode = current.as_ref().map(|t| t.parse_mode).unwrap_or_default();
+                let chat_parse_modes = current.map(|t| t.chat_parse_modes).unwrap_or_default();
                 full_cfg.channel.telegram = Some(bizclaw_core::config::TelegramChannelConfig {
-                    enabled: true, bot_token: token, allowed_chat_ids: chat_ids,
+                    enabled: true, bot_token: token, allowed_chat_ids: chat_ids, welcome_message,
+                    parse_mode, chat_parse_modes,
                 });
             }
             "webhook" => {
                 let outbound = sync_body.get("webhook_url").and_then(|v| v.as_str()).unwrap_or("").to_string();
                 let secret = sync_body.get("webhook_secret").and_then(|v| v.as_str()).unwrap_or("").to_string();
+                let welcome_message =
+                    full_cfg.channel.webhook.as_ref().and_then(|wh| wh.welcome_message.clone());
                 full_cfg.channel.webhook = Some(bizclaw_core::config::WebhookChannelConfig {
-                    enabled: true, secret, outbound_url: outbound,
+                    enabled: true, secret, outbound_url: outbound, welcome_message,
                 });
             }
             _ => {} // Other types handled as-is
         }
-        let content = toml::to_string_pretty(&*full_cfg).unwrap_or_default();
-        std::fs::write(&state.config_path, &content).ok();
+        if let Err(e) = full_cfg.save_to(&state.config_path) {
+            tracing::warn!("Failed to save config: {e}");
+        }
         drop(full_cfg);
     }
 
@@ -768,11 +992,12 @@ pub async fn save_channel_instance(
     if enabled && channel_type == "telegram" && !agent_name.is_empty() {
         let bot_token = config.get("bot_token").and_then(|v| v.as_str()).unwrap_or("").to_string();
         if !bot_token.is_empty() {
+            let tg_config = agent_telegram_config(&state, &bot_token, &config);
             let s = state.clone();
             let an = agent_name.clone();
             let iid = instance_id.clone();
             tokio::spawn(async move {
-                spawn_telegram_polling(s, an, bot_token, iid).await;
+                spawn_telegram_polling(s, an, tg_config, iid).await;
             });
         }
     }
@@ -798,6 +1023,58 @@ pub async fn delete_channel_instance(
     Json(serde_json::json!({"ok": true, "message": "Instance deleted"}))
 }
 
+/// Localized reply sent to the user when the agent fails. The full error is
+/// logged; the user only sees a message for its [`AgentErrorKind`].
+///
+/// [`AgentErrorKind`]: bizclaw_agent::AgentErrorKind
+fn agent_error_reply(state: &AppState, e: &bizclaw_core::error::BizClawError) -> String {
+    let locale = state
+        .full_config
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .locale();
+    let kind = bizclaw_agent::AgentErrorKind::of(e);
+    tracing::error!("Agent error ({}): {e}", kind.code());
+    kind.user_message(locale).to_string()
+}
+
+/// Reply to a channel message routed to `agent_name` — the maintenance
+/// message while maintenance mode is on, otherwise the agent's answer.
+async fn channel_agent_reply(
+    state: &AppState,
+    agent_name: &str,
+    channel: &str,
+    text: &str,
+) -> String {
+    if let Some(message) = state.maintenance.message() {
+        return message;
+    }
+    let mut orch = state.orchestrator.lock().await;
+    match orch.send_from(agent_name, channel, text).await {
+        Ok(r) => r,
+        Err(e) => agent_error_reply(state, &e),
+    }
+}
+
+/// Welcome message for a sender's first contact on `msg.channel`, if that
+/// channel has one configured. Senders are tracked in the orchestration store.
+async fn channel_welcome(
+    state: &AppState,
+    msg: &bizclaw_core::types::IncomingMessage,
+    bot_name: &str,
+) -> Option<String> {
+    let template = {
+        let cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+        cfg.channel.welcome_message(&msg.channel)?.to_string()
+    };
+    bizclaw_agent::welcome::first_contact(state.orch_store.as_ref(), &template, bot_name, msg)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("[{}] Welcome check failed: {e}", msg.channel);
+            None
+        })
+}
+
 /// Webhook inbound — receives external messages, routes to bound agent, replies.
 /// POST /api/v1/webhook/inbound
 /// Body: {"content": "message", "sender_id": "user1", "thread_id": "optional"}
@@ -858,12 +1135,33 @@ pub async fn webhook_inbound(
 
     tracing::info!("[webhook] {} → agent '{}': {}", sender, agent_name, safe_truncate(&content, 100));
 
-    // Route to agent
+    // Route to agent; failed turns are dead-lettered
     let response = {
         let mut orch = state.orchestrator.lock().await;
-        match orch.send_to(&agent_name, &content).await {
+        match orch.send_from(&agent_name, "webhook", &content).await {
             Ok(r) => r,
-            Err(e) => format!("⚠️ Agent error: {e}"),
+            Err(e) => {
+                let incoming = bizclaw_core::types::IncomingMessage {
+                    channel: "webhook".into(),
+                    thread_id: json["thread_id"].as_str().unwrap_or("webhook").into(),
+                    sender_id: sender.clone(),
+                    sender_name: None,
+                    content: content.clone(),
+                    thread_type: bizclaw_core::types::ThreadType::Direct,
+                    timestamp: chrono::Utc::now(),
+                    reply_to: None,
+                    message_id: None,
+                    images: Vec::new(),
+                };
+                bizclaw_agent::dead_letter::record(
+                    state.orch_store.as_ref(),
+                    Some(&agent_name),
+                    &incoming,
+                    &e.to_string(),
+                )
+                .await;
+                agent_error_reply(&state, &e)
+            }
         }
     };
 
@@ -888,14 +1186,160 @@ pub async fn webhook_inbound(
     }))
 }
 
+/// Named webhook — generic inbound channel for arbitrary JSON payloads.
+/// POST /api/v1/webhook/{name}
+/// `name` matches a webhook channel instance id or name. Instance config:
+/// `webhook_secret` (checked against the `X-Webhook-Secret` header),
+/// `sender_path` / `text_path` / `thread_path` (JSON paths into the body) and
+/// `callback_url` (the agent reply is POSTed there; falls back to `webhook_url`).
+pub async fn webhook_named(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+    headers: axum::http::HeaderMap,
+    body: String,
+) -> Json<serde_json::Value> {
+    use bizclaw_channels::webhook::{WebhookMapping, map_inbound, verify_shared_secret};
+
+    let instances = load_channel_instances(&state);
+    let Some(inst) = instances.iter().find(|i| {
+        i["channel_type"].as_str() == Some("webhook")
+            && i["enabled"].as_bool() == Some(true)
+            && (i["id"].as_str() == Some(&name) || i["name"].as_str() == Some(&name))
+    }) else {
+        return Json(serde_json::json!({"ok": false, "error": format!("Webhook '{name}' not found")}));
+    };
+    let agent_name = inst["agent_name"].as_str().unwrap_or("").to_string();
+    if agent_name.is_empty() {
+        return Json(serde_json::json!({"ok": false, "error": format!("Webhook '{name}' is not bound to an agent")}));
+    }
+    let config = &inst["config"];
+
+    // Shared-secret check (only when a secret is configured)
+    let secret = config["webhook_secret"].as_str().unwrap_or("");
+    if !secret.is_empty() {
+        let provided = headers.get("x-webhook-secret").and_then(|v| v.to_str().ok());
+        if !verify_shared_secret(secret, provided) {
+            tracing::warn!("[webhook:{name}] Rejected request with invalid secret");
+            return Json(serde_json::json!({"ok": false, "error": "Invalid webhook secret"}));
+        }
+    }
+
+    let json: serde_json::Value = match serde_json::from_str(&body) {
+        Ok(v) => v,
+        Err(e) => return Json(serde_json::json!({"ok": false, "error": format!("Invalid JSON: {e}")})),
+    };
+
+    let defaults = WebhookMapping::default();
+    let path_or = |key: &str, default: &str| {
+        config[key]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .unwrap_or(default)
+            .to_string()
+    };
+    let mapping = WebhookMapping {
+        sender_path: path_or("sender_path", &defaults.sender_path),
+        text_path: path_or("text_path", &defaults.text_path),
+        thread_path: Some(path_or("thread_path", defaults.thread_path.as_deref().unwrap_or(""))),
+    };
+    let incoming = match map_inbound(&name, &json, &mapping) {
+        Ok(m) => m,
+        Err(e) => return Json(serde_json::json!({"ok": false, "error": e.to_string()})),
+    };
+
+    tracing::info!(
+        "[webhook:{}] {} → agent '{}': {}",
+        name,
+        incoming.sender_id,
+        agent_name,
+        safe_truncate(&incoming.content, 100)
+    );
+
+    let response = {
+        let mut orch = state.orchestrator.lock().await;
+        match orch.send_from(&agent_name, "webhook", &incoming.content).await {
+            Ok(r) => r,
+            Err(e) => {
+                bizclaw_agent::dead_letter::record(
+                    state.orch_store.as_ref(),
+                    Some(&agent_name),
+                    &incoming,
+                    &e.to_string(),
+                )
+                .await;
+                agent_error_reply(&state, &e)
+            }
+        }
+    };
+
+    let callback_url = config["callback_url"]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .or_else(|| config["webhook_url"].as_str())
+        .unwrap_or("")
+        .to_string();
+    if !callback_url.is_empty() {
+        let reply_body = serde_json::json!({
+            "content": response,
+            "sender_id": agent_name,
+            "recipient_id": incoming.sender_id,
+            "thread_id": incoming.thread_id,
+            "webhook": name,
+            "in_reply_to": incoming.content,
+        });
+        let client = reqwest::Client::new();
+        if let Err(e) = client.post(&callback_url).json(&reply_body).send().await {
+            tracing::error!("[webhook:{name}] Callback failed: {e}");
+        }
+    }
+
+    Json(serde_json::json!({
+        "ok": true,
+        "response": response,
+        "agent": agent_name,
+        "thread_id": incoming.thread_id,
+    }))
+}
+
+/// Config for a Telegram bot bound to an agent: formatting from
+/// `[channel.telegram]`, overridden by the instance's own `parse_mode` and
+/// `chat_parse_modes`.
+fn agent_telegram_config(
+    state: &AppState,
+    bot_token: &str,
+    instance: &serde_json::Value,
+) -> bizclaw_channels::telegram::TelegramConfig {
+    use bizclaw_channels::telegram::TelegramConfig;
+    let mut config = {
+        let cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+        match cfg.channel.telegram.as_ref() {
+            Some(tg) => TelegramConfig {
+                bot_token: bot_token.to_string(),
+                ..TelegramConfig::from_channel_config(tg)
+            },
+            None => TelegramConfig::new(bot_token),
+        }
+    };
+    if let Ok(mode) = serde_json::from_value(instance["parse_mode"].clone()) {
+        config.parse_mode = mode;
+    }
+    if let Ok(modes) = serde_json::from_value::<std::collections::HashMap<_, _>>(
+        instance["chat_parse_modes"].clone(),
+    ) {
+        config.chat_parse_modes.extend(modes);
+    }
+    config
+}
+
 /// Spawn a Telegram polling loop that routes messages to a specific agent.
 /// Reused by both save_channel_instance (manual) and auto_connect_channels (startup).
 pub async fn spawn_telegram_polling(
     state: Arc<AppState>,
     agent_name: String,
-    bot_token: String,
+    config: bizclaw_channels::telegram::TelegramConfig,
     instance_id: String,
 ) {
+    let bot_token = config.bot_token.clone();
     // Disconnect existing bot for this agent if any
     {
         let mut bots = state.telegram_bots.lock().await;
@@ -907,64 +1351,75 @@ pub async fn spawn_telegram_polling(
     }
 
     // Verify bot token
-    let tg = bizclaw_channels::telegram::TelegramChannel::new(
-        bizclaw_channels::telegram::TelegramConfig {
-            bot_token: bot_token.clone(),
-            enabled: true,
-            poll_interval: 1,
-        },
-    );
+    let tg = bizclaw_channels::telegram::TelegramChannel::new(config.clone());
     let bot_username = match tg.get_me().await {
         Ok(me) => me.username.unwrap_or_default(),
         Err(e) => {
             tracing::error!("[telegram] Bot token invalid for instance '{}': {}", instance_id, e);
+            set_channel_instance_status(&state, &instance_id, "error", Some(&e.to_string()));
             return;
         }
     };
     tracing::info!("[telegram] @{} connected → agent '{}' (instance: {})", bot_username, agent_name, instance_id);
+    set_channel_instance_status(&state, &instance_id, "connected", None);
 
     // Spawn polling loop
     let stop = Arc::new(tokio::sync::Notify::new());
     let stop_rx = stop.clone();
     let state_clone = state.clone();
     let agent_name_clone = agent_name.clone();
+    let instance_id_clone = instance_id.clone();
     let bot_token_for_state = bot_token.clone();
 
     tokio::spawn(async move {
-        let mut channel = bizclaw_channels::telegram::TelegramChannel::new(
-            bizclaw_channels::telegram::TelegramConfig {
-                bot_token: bot_token.clone(),
-                enabled: true,
-                poll_interval: 1,
-            },
-        );
+        let mut channel = bizclaw_channels::telegram::TelegramChannel::new(config);
+        let mut backoff = bizclaw_channels::reconnect::Backoff::default();
+        let status = bizclaw_channels::status::StatusRegistry::global();
+        let status_key = format!("telegram:{agent_name_clone}");
 
         loop {
             tokio::select! {
                 _ = stop_rx.notified() => {
                     tracing::info!("[telegram] Polling stopped for agent '{}'", agent_name_clone);
+                    status.disconnected(&status_key);
                     break;
                 }
                 result = channel.get_updates() => {
                     match result {
                         Ok(updates) => {
+                            backoff.reset();
+                            status.connected(&status_key);
                             for update in updates {
                                 if let Some(msg) = update.to_incoming() {
+                                    status.event(&status_key);
                                     let chat_id: i64 = msg.thread_id.parse().unwrap_or(0);
                                     let sender = msg.sender_name.clone().unwrap_or_default();
                                     let text = msg.content.clone();
 
                                     tracing::info!("[telegram] {} → agent '{}': {}", sender, agent_name_clone, safe_truncate(&text, 100));
                                     let _ = channel.send_typing(chat_id).await;
+                                    if let Some(id) = msg.message_id.as_deref()
+                                        && let Ok(id) = id.parse::<i64>()
+                                    {
+                                        let _ = channel
+                                            .set_reaction(
+                                                chat_id,
+                                                id,
+                                                bizclaw_channels::telegram::ACK_REACTION,
+                                            )
+                                            .await;
+                                    }
+
+                                    let welcome =
+                                        channel_welcome(&state_clone, &msg, &agent_name_clone).await;
+                                    if let Some(welcome) = welcome
+                                        && let Err(e) = channel.send_message(chat_id, &welcome).await
+                                    {
+                                        tracing::error!("[telegram] Welcome failed: {e}");
+                                    }
 
                                     // Route to agent
-                                    let response = {
-                                        let mut orch = state_clone.orchestrator.lock().await;
-                                        match orch.send_to(&agent_name_clone, &text).await {
-                                            Ok(r) => r,
-                                            Err(e) => format!("⚠️ Agent error: {e}"),
-                                        }
-                                    };
+                                    let response = channel_agent_reply(&state_clone, &agent_name_clone, "telegram", &text).await;
 
                                     if let Err(e) = channel.send_message(chat_id, &response).await {
                                         tracing::error!("[telegram] Reply failed: {e}");
@@ -973,8 +1428,23 @@ pub async fn spawn_telegram_polling(
                             }
                         }
                         Err(e) => {
-                            tracing::error!("[telegram] Polling error for '{}': {e}", agent_name_clone);
-                            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                            use bizclaw_channels::reconnect::{FailureKind, classify};
+                            let delay = match classify(&e) {
+                                FailureKind::Fatal => None,
+                                FailureKind::Transient => backoff.next_delay(),
+                            };
+                            let Some(delay) = delay else {
+                                tracing::error!(
+                                    "[telegram] Polling stopped for '{}' after {} attempt(s): {e}",
+                                    agent_name_clone, backoff.attempts()
+                                );
+                                set_channel_instance_status(&state_clone, &instance_id_clone, "error", Some(&e.to_string()));
+                                status.failed(&status_key, &e.to_string());
+                                break;
+                            };
+                            tracing::warn!("[telegram] Polling error for '{}': {e}, retrying in {delay:?}", agent_name_clone);
+                            status.reconnecting(&status_key, &e.to_string(), backoff.attempts());
+                            tokio::time::sleep(delay).await;
                         }
                     }
                 }
@@ -1011,7 +1481,8 @@ pub async fn spawn_discord_gateway(
             enabled: true,
             intents: 33281, // GUILDS | GUILD_MESSAGES | MESSAGE_CONTENT
         },
-    );
+    )
+    .with_status_key(&format!("discord:{agent_name}"));
 
     // Verify bot token
     match discord.get_me().await {
@@ -1021,9 +1492,11 @@ pub async fn spawn_discord_gateway(
         }
         Err(e) => {
             tracing::error!("[discord] Bot token invalid for instance '{}': {}", instance_id, e);
+            set_channel_instance_status(&state, &instance_id, "error", Some(&e.to_string()));
             return;
         }
     }
+    set_channel_instance_status(&state, &instance_id, "connected", None);
 
     let gateway = discord.start_gateway();
     let state_clone = state.clone();
@@ -1050,20 +1523,21 @@ pub async fn spawn_discord_gateway(
             let _ = reply_client.send_typing_indicator(&channel_id).await;
 
             // Route to agent
-            let response = {
-                let mut orch = state_clone.orchestrator.lock().await;
-                match orch.send_to(&agent_name_clone, &text).await {
-                    Ok(r) => r,
-                    Err(e) => format!("⚠️ Agent error: {e}"),
-                }
-            };
+            let response = channel_agent_reply(&state_clone, &agent_name_clone, "discord", &text).await;
 
             // Reply via Discord
             if let Err(e) = reply_client.send_message(&channel_id, &response).await {
                 tracing::error!("[discord] Reply failed: {e}");
             }
         }
+        // The gateway only ends the stream when it gives up reconnecting.
         tracing::warn!("[discord] Gateway stream ended for agent '{}'", agent_name_clone);
+        set_channel_instance_status(
+            &state_clone,
+            &instance_id,
+            "error",
+            Some("Discord Gateway stopped reconnecting (invalid token or too many failures)"),
+        );
     });
 }
 
@@ -1150,7 +1624,7 @@ pub async fn auto_connect_channels(state: Arc<AppState>) {
                     spawn_telegram_polling(
                         state.clone(),
                         agent_name.to_string(),
-                        bot_token,
+                        agent_telegram_config(&state, &bot_token, cfg),
                         instance_id.to_string(),
                     ).await;
                     connected += 1;
@@ -1482,6 +1956,85 @@ pub async fn list_channels(State(state): State<Arc<AppState>>) -> Json<serde_jso
     }))
 }
 
+/// Live health of each configured channel, as last reported by its loop.
+/// A configured channel that has not reported anything is `not_started`.
+pub async fn channel_status(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let configured: Vec<(&str, bool)> = {
+        let cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+        bizclaw_core::config::ChannelConfig::KINDS
+            .iter()
+            .filter_map(|kind| {
+                let value = cfg.channel.get(kind).ok().flatten()?;
+                Some((*kind, value["enabled"].as_bool().unwrap_or(false)))
+            })
+            .collect()
+    };
+    let mut reported = bizclaw_channels::status::StatusRegistry::global().snapshot();
+
+    let mut channels: Vec<serde_json::Value> = configured
+        .into_iter()
+        .map(|(name, enabled)| {
+            let status = match reported.remove(name) {
+                Some(status) => serde_json::to_value(status).unwrap_or_default(),
+                None => serde_json::json!({"state": "not_started"}),
+            };
+            let mut entry = serde_json::json!({"name": name, "enabled": enabled});
+            if let (Some(entry), Some(status)) = (entry.as_object_mut(), status.as_object()) {
+                entry.extend(status.clone());
+            }
+            entry
+        })
+        .collect();
+    // Channels started outside the config, e.g. agent-bound bots
+    // (`telegram:<agent>`, `discord:<agent>`)
+    for (name, status) in reported {
+        let mut entry = serde_json::to_value(status).unwrap_or_default();
+        entry["name"] = serde_json::json!(name);
+        channels.push(entry);
+    }
+    Json(serde_json::json!({ "channels": channels }))
+}
+
+/// Whether maintenance mode is on, and the message users see.
+pub async fn get_maintenance(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let message = state.maintenance.message();
+    Json(serde_json::json!({
+        "ok": true,
+        "enabled": message.is_some(),
+        "message": message,
+    }))
+}
+
+/// Turn maintenance mode on or off: `{"enabled": true, "message": "..."}`.
+/// Without a message, `gateway.maintenance_message` is used, then the built-in one.
+pub async fn set_maintenance(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let Some(enabled) = body["enabled"].as_bool() else {
+        return Json(serde_json::json!({"ok": false, "error": "'enabled' (bool) is required"}));
+    };
+    if enabled {
+        let message = match body["message"].as_str().map(str::trim) {
+            Some(m) if !m.is_empty() => m.to_string(),
+            _ => {
+                let cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+                if cfg.gateway.maintenance_message.is_empty() {
+                    bizclaw_core::maintenance::default_message(cfg.locale()).to_string()
+                } else {
+                    cfg.gateway.maintenance_message.clone()
+                }
+            }
+        };
+        state.maintenance.enable(message);
+        tracing::warn!("🛠️ Maintenance mode on — new chats are refused");
+    } else {
+        state.maintenance.disable();
+        tracing::info!("🛠️ Maintenance mode off");
+    }
+    get_maintenance(State(state)).await
+}
+
 /// List installed Ollama models.
 pub async fn ollama_models() -> Json<serde_json::Value> {
     let url = "http://localhost:11434/api/tags";
@@ -1605,8 +2158,15 @@ pub async fn brain_scan_models(State(state): State<Arc<AppState>>) -> Json<serde
 }
 
 /// Generate Zalo QR code for login.
-pub async fn zalo_qr_code(State(_state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+pub async fn zalo_qr_code(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
     use bizclaw_channels::zalo::client::auth::{ZaloAuth, ZaloCredentials};
+    use bizclaw_core::i18n::t;
+
+    let locale = state
+        .full_config
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .locale();
 
     let creds = ZaloCredentials::default();
     let mut auth = ZaloAuth::new(creds);
@@ -1618,19 +2178,19 @@ pub async fn zalo_qr_code(State(_state): State<Arc<AppState>>) -> Json<serde_jso
             "qr_id": qr.code,
             "imei": auth.credentials().imei,
             "instructions": [
-                "1. Mở ứng dụng Zalo trên điện thoại",
-                "2. Nhấn biểu tượng QR ở thanh tìm kiếm",
-                "3. Quét mã QR này để đăng nhập",
-                "4. Xác nhận đăng nhập trên điện thoại"
+                t(locale, "zalo.qr.step1"),
+                t(locale, "zalo.qr.step2"),
+                t(locale, "zalo.qr.step3"),
+                t(locale, "zalo.qr.step4")
             ],
-            "message": "Quét mã QR bằng Zalo trên điện thoại"
+            "message": t(locale, "zalo.qr.message")
         })),
         Err(e) => {
             tracing::error!("[zalo_qr] {e}");
             Json(serde_json::json!({
                 "ok": false,
-                "error": "Không thể tạo mã QR Zalo",
-                "fallback": "Vui lòng vào chat.zalo.me → F12 → Application → Cookies → Copy toàn bộ và paste vào ô Cookie bên dưới"
+                "error": t(locale, "zalo.qr.error"),
+                "fallback": t(locale, "zalo.qr.fallback")
             }))
         }
     }
@@ -1675,10 +2235,39 @@ pub async fn whatsapp_webhook_verify(
 }
 
 /// WhatsApp webhook handler (POST) — receives incoming messages from Meta.
+/// Payloads without a valid `X-Hub-Signature-256` are rejected with 403
+/// before anything is processed; so is everything while `app_secret` is unset.
 pub async fn whatsapp_webhook(
     State(state): State<Arc<AppState>>,
-    Json(body): Json<serde_json::Value>,
-) -> Json<serde_json::Value> {
+    headers: axum::http::HeaderMap,
+    raw_body: axum::body::Bytes,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+    use bizclaw_channels::whatsapp::{SIGNATURE_HEADER, verify_signature};
+
+    let app_secret = {
+        let cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+        cfg.channel.whatsapp.as_ref().map(|w| w.app_secret.clone()).unwrap_or_default()
+    };
+    if app_secret.is_empty() {
+        tracing::error!("[whatsapp] app_secret not set — rejecting unverifiable webhook");
+        return (axum::http::StatusCode::FORBIDDEN, "Forbidden").into_response();
+    }
+    let signature = headers.get(SIGNATURE_HEADER).and_then(|v| v.to_str().ok());
+    if !verify_signature(&app_secret, &raw_body, signature) {
+        tracing::warn!("[whatsapp] Rejected webhook with missing or invalid signature");
+        return (axum::http::StatusCode::FORBIDDEN, "Forbidden").into_response();
+    }
+    let body: serde_json::Value = match serde_json::from_slice(&raw_body) {
+        Ok(v) => v,
+        Err(e) => {
+            return (axum::http::StatusCode::BAD_REQUEST, format!("Invalid JSON: {e}"))
+                .into_response();
+        }
+    };
+    let status = bizclaw_channels::status::StatusRegistry::global();
+    status.connected("whatsapp");
+
     // Extract messages and spawn processing in background
     // (WhatsApp expects quick 200 OK response)
     let entry = &body["entry"];
@@ -1689,6 +2278,7 @@ pub async fn whatsapp_webhook(
                     let value = &change["value"];
                     if let Some(messages) = value["messages"].as_array() {
                         for msg in messages {
+                            status.event("whatsapp");
                             let msg_type = msg["type"].as_str().unwrap_or("");
                             if msg_type != "text" {
                                 continue;
@@ -1712,19 +2302,65 @@ pub async fn whatsapp_webhook(
 
                             // Spawn background task for agent processing + reply
                             let agent_lock = state.agent.clone();
+                            let state = state.clone();
                             tokio::spawn(async move {
-                                // Process through Agent Engine
-                                let response = {
+                                // Acknowledge with a read receipt before the (slow) agent reply
+                                if let Some(wa_cfg) = &wa_config {
+                                    let channel = bizclaw_channels::whatsapp::WhatsAppChannel::new(
+                                        bizclaw_channels::whatsapp::WhatsAppConfig {
+                                            access_token: wa_cfg.access_token.clone(),
+                                            phone_number_id: wa_cfg.phone_number_id.clone(),
+                                            ..Default::default()
+                                        },
+                                    );
+                                    if let Err(e) = channel.mark_as_read(&msg_id).await {
+                                        tracing::debug!("[whatsapp] Mark-read failed: {e}");
+                                    }
+                                }
+
+                                let incoming = bizclaw_core::types::IncomingMessage {
+                                    channel: "whatsapp".into(),
+                                    thread_id: from.clone(),
+                                    sender_id: from.clone(),
+                                    sender_name: None,
+                                    content: text.clone(),
+                                    thread_type: bizclaw_core::types::ThreadType::Direct,
+                                    timestamp: chrono::Utc::now(),
+                                    reply_to: None,
+                                    message_id: Some(msg_id.clone()),
+                                    images: Vec::new(),
+                                };
+
+                                // Process through Agent Engine; failed turns are dead-lettered
+                                let response = if let Some(message) = state.maintenance.message() {
+                                    message
+                                } else {
                                     let mut agent = agent_lock.lock().await;
                                     if let Some(agent) = agent.as_mut() {
-                                        match agent.process(&text).await {
+                                        let result = bizclaw_agent::dead_letter::process_or_dead_letter(
+                                            agent,
+                                            state.orch_store.as_ref(),
+                                            &incoming,
+                                        )
+                                        .await;
+                                        match result {
                                             Ok(r) => r,
-                                            Err(e) => format!("Error: {e}"),
+                                            Err(e) => agent_error_reply(&state, &e.source),
                                         }
                                     } else {
                                         "Agent not available".to_string()
                                     }
                                 };
+                                let bot_name = {
+                                    let cfg =
+                                        state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+                                    cfg.identity.name.clone()
+                                };
+                                let welcome = channel_welcome(&state, &incoming, &bot_name).await;
+                                let response = match welcome {
+                                    Some(welcome) => format!("{welcome}\n\n{response}"),
+                                    None => response,
+                                };
 
                                 // Send reply via WhatsApp Cloud API
                                 if let Some(wa_cfg) = wa_config {
@@ -1761,7 +2397,7 @@ pub async fn whatsapp_webhook(
         }
     }
 
-    Json(serde_json::json!({"status": "ok"}))
+    Json(serde_json::json!({"status": "ok"})).into_response()
 }
 
 // ---- Generic Webhook Inbound API ----
@@ -1851,9 +2487,9 @@ pub async fn _webhook_inbound_legacy(
     let response = {
         let mut agent = state.agent.lock().await;
         if let Some(agent) = agent.as_mut() {
-            match agent.process(&content).await {
+            match agent.process_from("webhook", &content).await {
                 Ok(r) => r,
-                Err(e) => format!("Error: {e}"),
+                Err(e) => agent_error_reply(&state, &e.source),
             }
         } else {
             "Agent not available".to_string()
@@ -2010,6 +2646,18 @@ pub async fn scheduler_toggle_task(
     Json(serde_json::json!({"ok": true, "enabled": enabled}))
 }
 
+/// Cancel the in-flight run of a scheduled task. The task keeps its schedule.
+pub async fn scheduler_cancel_task(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Json<serde_json::Value> {
+    if state.scheduler.lock().await.cancel_task(&id) {
+        Json(serde_json::json!({"ok": true, "id": id, "status": "cancelled"}))
+    } else {
+        Json(serde_json::json!({"ok": false, "error": format!("Task '{id}' is not running")}))
+    }
+}
+
 /// Get notification history.
 pub async fn scheduler_notifications(
     State(state): State<Arc<AppState>>,
@@ -2162,18 +2810,46 @@ pub async fn knowledge_list_docs(State(state): State<Arc<AppState>>) -> Json<ser
     }
 }
 
-/// Add a document to the knowledge base.
-pub async fn knowledge_add_doc(
-    State(state): State<Arc<AppState>>,
+/// Chunking options from the optional `split_mode` (paragraphs, tokens,
+/// sentences, headings), `chunk_size` and `chunk_overlap` request params.
+fn knowledge_chunk_options(
+    mode: Option<&str>,
+    size: Option<u64>,
+    overlap: Option<u64>,
+) -> std::result::Result<bizclaw_knowledge::ChunkOptions, String> {
+    let mut options = bizclaw_knowledge::ChunkOptions::default();
+    if let Some(mode) = mode.filter(|m| !m.trim().is_empty()) {
+        options.mode = mode.parse()?;
+    }
+    if let Some(size) = size {
+        options.size = size as usize;
+    }
+    if let Some(overlap) = overlap {
+        options.overlap = overlap as usize;
+    }
+    Ok(options)
+}
+
+/// Add a document to the knowledge base.
+pub async fn knowledge_add_doc(
+    State(state): State<Arc<AppState>>,
     Json(body): Json<serde_json::Value>,
 ) -> Json<serde_json::Value> {
     let name = body["name"].as_str().unwrap_or("unnamed.txt");
     let content = body["content"].as_str().unwrap_or("");
     let source = body["source"].as_str().unwrap_or("api");
+    let options = match knowledge_chunk_options(
+        body["split_mode"].as_str(),
+        body["chunk_size"].as_u64(),
+        body["chunk_overlap"].as_u64(),
+    ) {
+        Ok(options) => options,
+        Err(e) => return Json(serde_json::json!({"ok": false, "error": e})),
+    };
 
     let kb = state.knowledge.lock().await;
     match kb.as_ref() {
-        Some(store) => match store.add_document(name, content, source) {
+        Some(store) => match store.add_document(name, content, source, &options) {
             Ok(chunks) => Json(serde_json::json!({"ok": true, "chunks": chunks})),
             Err(e) => Json(serde_json::json!({"ok": false, "error": e})),
         },
@@ -2181,6 +2857,63 @@ pub async fn knowledge_add_doc(
     }
 }
 
+/// Fetch a web page or text file by URL and add it to the knowledge base.
+/// The download is size-capped and gzip bodies are decompressed with a limit;
+/// binary content types and internal/private addresses are rejected.
+pub async fn knowledge_add_url(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let Some(url) = body["url"].as_str().map(str::trim).filter(|u| !u.is_empty()) else {
+        return Json(serde_json::json!({"ok": false, "error": "url is required"}));
+    };
+    // Redirects are re-checked against the SSRF block list.
+    let client = match reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .redirect(bizclaw_knowledge::fetch::redirect_policy())
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => return internal_error("HTTP client", e),
+    };
+
+    // Fetch before taking the lock so a slow server doesn't block the KB.
+    let doc = match bizclaw_knowledge::fetch_document(
+        &client,
+        url,
+        bizclaw_knowledge::FetchLimits::default(),
+    )
+    .await
+    {
+        Ok(doc) => doc,
+        Err(e) => return Json(serde_json::json!({"ok": false, "error": e})),
+    };
+    let name = body["name"].as_str().unwrap_or(&doc.name);
+    let options = match knowledge_chunk_options(
+        body["split_mode"].as_str(),
+        body["chunk_size"].as_u64(),
+        body["chunk_overlap"].as_u64(),
+    ) {
+        Ok(options) => options,
+        Err(e) => return Json(serde_json::json!({"ok": false, "error": e})),
+    };
+
+    let kb = state.knowledge.lock().await;
+    match kb.as_ref() {
+        Some(store) => match store.add_text(name, &doc.text, &doc.url, &options) {
+            Ok(chunks) => Json(serde_json::json!({
+                "ok": true,
+                "name": name,
+                "chunks": chunks,
+                "content_type": doc.content_type,
+                "truncated": doc.truncated,
+            })),
+            Err(e) => Json(serde_json::json!({"ok": false, "error": e})),
+        },
+        None => Json(serde_json::json!({"ok": false, "error": "Knowledge base not available"})),
+    }
+}
+
 /// Remove a document from the knowledge base.
 pub async fn knowledge_remove_doc(
     State(state): State<Arc<AppState>>,
@@ -2197,7 +2930,8 @@ pub async fn knowledge_remove_doc(
 }
 
 /// Upload a file (PDF, TXT, MD, etc.) to the knowledge base.
-/// Accepts multipart/form-data with a "file" field.
+/// Accepts multipart/form-data with a "file" field, and optional
+/// "split_mode", "chunk_size" and "chunk_overlap" fields.
 /// PDFs are processed via pdf_oxide for text/markdown extraction.
 pub async fn knowledge_upload_file(
     State(state): State<Arc<AppState>>,
@@ -2205,11 +2939,28 @@ pub async fn knowledge_upload_file(
 ) -> Json<serde_json::Value> {
     let mut file_name = String::new();
     let mut file_data: Vec<u8> = Vec::new();
+    let mut split_mode = None;
+    let mut chunk_size = None;
+    let mut chunk_overlap = None;
 
     // Extract file from multipart
     while let Ok(Some(field)) = multipart.next_field().await {
         let name = field.name().unwrap_or("").to_string();
-        if name == "file" {
+        if matches!(name.as_str(), "split_mode" | "chunk_size" | "chunk_overlap") {
+            let value = field.text().await.unwrap_or_default();
+            let number = || value.trim().parse::<u64>().map_err(|_| format!("{name} must be a number"));
+            match name.as_str() {
+                "split_mode" => split_mode = Some(value.clone()),
+                "chunk_size" => match number() {
+                    Ok(n) => chunk_size = Some(n),
+                    Err(e) => return Json(serde_json::json!({"ok": false, "error": e})),
+                },
+                _ => match number() {
+                    Ok(n) => chunk_overlap = Some(n),
+                    Err(e) => return Json(serde_json::json!({"ok": false, "error": e})),
+                },
+            }
+        } else if name == "file" {
             file_name = field
                 .file_name()
                 .unwrap_or("unnamed.txt")
@@ -2233,6 +2984,11 @@ pub async fn knowledge_upload_file(
         }));
     }
 
+    let options = match knowledge_chunk_options(split_mode.as_deref(), chunk_size, chunk_overlap) {
+        Ok(options) => options,
+        Err(e) => return Json(serde_json::json!({"ok": false, "error": e})),
+    };
+
     let ext = file_name
         .rsplit('.')
         .next()
@@ -2250,12 +3006,12 @@ pub async fn knowledge_upload_file(
         Some(store) => {
             let result = match ext.as_str() {
                 "pdf" => {
-                    store.add_pdf_document(&file_name, &file_data, "upload")
+                    store.add_pdf_document(&file_name, &file_data, "upload", &options)
                 }
                 _ => {
                     // Text-based files: convert bytes to string
                     match String::from_utf8(file_data) {
-                        Ok(content) => store.add_document(&file_name, &content, "upload"),
+                        Ok(content) => store.add_document(&file_name, &content, "upload", &options),
                         Err(_) => Err("File is not valid UTF-8 text".into()),
                     }
                 }
@@ -2283,6 +3039,60 @@ pub async fn knowledge_upload_file(
     }
 }
 
+/// Bulk-import JSONL records. Body: `{"kind": "knowledge" | "memory",
+/// "content": "<jsonl>", "name"?: "faq.jsonl"}`. Bad lines are reported in
+/// `failures` and do not stop the import.
+pub async fn bulk_import(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let content = body["content"].as_str().unwrap_or("");
+    let name = body["name"].as_str().unwrap_or("import.jsonl");
+    let report = match body["kind"].as_str() {
+        Some("knowledge") => {
+            let kb = state.knowledge.lock().await;
+            let Some(store) = kb.as_ref() else {
+                return Json(serde_json::json!({
+                    "ok": false,
+                    "error": "Knowledge base not available"
+                }));
+            };
+            bizclaw_knowledge::import::import_jsonl(store, content, name, |_, _| {})
+        }
+        Some("memory") => {
+            let memory_config = state
+                .full_config
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .memory
+                .clone();
+            let memory = match bizclaw_memory::create_memory(&memory_config) {
+                Ok(memory) => memory,
+                Err(e) => return Json(serde_json::json!({"ok": false, "error": e.to_string()})),
+            };
+            bizclaw_memory::import::import_jsonl(memory.as_ref(), content, |_, _| {}).await
+        }
+        _ => {
+            return Json(serde_json::json!({
+                "ok": false,
+                "error": "kind must be \"knowledge\" or \"memory\""
+            }));
+        }
+    };
+    tracing::info!(
+        "📥 Imported {} record(s) from '{}' ({} failed)",
+        report.imported,
+        name,
+        report.failures.len()
+    );
+    Json(serde_json::json!({
+        "ok": true,
+        "imported": report.imported,
+        "chunks": report.chunks,
+        "failures": report.failures,
+    }))
+}
+
 // ---- Multi-Agent Orchestrator API ----
 
 /// List all agents in the orchestrator.
@@ -2658,13 +3468,8 @@ pub async fn connect_telegram(
     }
 
     // Verify bot token
-    let tg = bizclaw_channels::telegram::TelegramChannel::new(
-        bizclaw_channels::telegram::TelegramConfig {
-            bot_token: bot_token.clone(),
-            enabled: true,
-            poll_interval: 1,
-        },
-    );
+    let tg_config = agent_telegram_config(&state, &bot_token, &body);
+    let tg = bizclaw_channels::telegram::TelegramChannel::new(tg_config.clone());
     let bot_info = match tg.get_me().await {
         Ok(me) => me,
         Err(e) => {
@@ -2685,32 +3490,32 @@ pub async fn connect_telegram(
     let stop_rx = stop.clone();
     let state_clone = state.clone();
     let agent_name_clone = agent_name.clone();
-    let bot_token_clone = bot_token.clone();
 
     tokio::spawn(async move {
-        let mut channel = bizclaw_channels::telegram::TelegramChannel::new(
-            bizclaw_channels::telegram::TelegramConfig {
-                bot_token: bot_token_clone,
-                enabled: true,
-                poll_interval: 1,
-            },
-        );
+        let mut channel = bizclaw_channels::telegram::TelegramChannel::new(tg_config);
         tracing::info!(
             "[telegram] Polling started for agent '{}'",
             agent_name_clone
         );
+        let status = bizclaw_channels::status::StatusRegistry::global();
+        let status_key = format!("telegram:{agent_name_clone}");
+        let mut failures = 0u32;
 
         loop {
             tokio::select! {
                 _ = stop_rx.notified() => {
                     tracing::info!("[telegram] Polling stopped for agent '{}'", agent_name_clone);
+                    status.disconnected(&status_key);
                     break;
                 }
                 result = channel.get_updates() => {
                     match result {
                         Ok(updates) => {
+                            failures = 0;
+                            status.connected(&status_key);
                             for update in updates {
                                 if let Some(msg) = update.to_incoming() {
+                                    status.event(&status_key);
                                     let chat_id: i64 = msg.thread_id.parse().unwrap_or(0);
                                     let sender = msg.sender_name.clone().unwrap_or_default();
                                     let text = msg.content.clone();
@@ -2719,15 +3524,28 @@ pub async fn connect_telegram(
 
                                     // Send typing indicator
                                     let _ = channel.send_typing(chat_id).await;
+                                    if let Some(id) = msg.message_id.as_deref()
+                                        && let Ok(id) = id.parse::<i64>()
+                                    {
+                                        let _ = channel
+                                            .set_reaction(
+                                                chat_id,
+                                                id,
+                                                bizclaw_channels::telegram::ACK_REACTION,
+                                            )
+                                            .await;
+                                    }
+
+                                    let welcome =
+                                        channel_welcome(&state_clone, &msg, &agent_name_clone).await;
+                                    if let Some(welcome) = welcome
+                                        && let Err(e) = channel.send_message(chat_id, &welcome).await
+                                    {
+                                        tracing::error!("[telegram] Welcome failed: {e}");
+                                    }
 
                                     // Route to agent
-                                    let response = {
-                                        let mut orch = state_clone.orchestrator.lock().await;
-                                        match orch.send_to(&agent_name_clone, &text).await {
-                                            Ok(r) => r,
-                                            Err(e) => format!("⚠️ Agent error: {e}"),
-                                        }
-                                    };
+                                    let response = channel_agent_reply(&state_clone, &agent_name_clone, "telegram", &text).await;
 
                                     // Reply via Telegram
                                     if let Err(e) = channel.send_message(chat_id, &response).await {
@@ -2738,6 +3556,8 @@ pub async fn connect_telegram(
                         }
                         Err(e) => {
                             tracing::error!("[telegram] Polling error for '{}': {e}", agent_name_clone);
+                            failures += 1;
+                            status.reconnecting(&status_key, &e.to_string(), failures);
                             tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
                         }
                     }
@@ -3137,11 +3957,15 @@ mod tests {
     use std::sync::Mutex;
 
     fn test_state() -> State<Arc<AppState>> {
+        test_state_at(std::path::PathBuf::from("/tmp/test_config.toml"))
+    }
+
+    fn test_state_at(config_path: std::path::PathBuf) -> State<Arc<AppState>> {
         let (activity_tx, _rx) = tokio::sync::broadcast::channel(16);
         State(Arc::new(AppState {
             gateway_config: bizclaw_core::config::GatewayConfig::default(),
             full_config: Arc::new(Mutex::new(bizclaw_core::config::BizClawConfig::default())),
-            config_path: std::path::PathBuf::from("/tmp/test_config.toml"),
+            config_path,
             start_time: std::time::Instant::now(),
             pairing_code: Arc::new(Mutex::new(String::new())),
             auth_failures: Arc::new(tokio::sync::Mutex::new((0, std::time::Instant::now()))),
@@ -3165,6 +3989,11 @@ mod tests {
             activity_tx,
             activity_log: Arc::new(Mutex::new(Vec::new())),
             rate_limiter: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+            workflow_runs: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            sessions: Arc::new(Mutex::new(crate::sessions::SessionStore::default())),
+            ws_tickets: Arc::new(Mutex::new(crate::ws_auth::TicketStore::default())),
+            maintenance: bizclaw_core::maintenance::Maintenance::new(),
+            idempotency: Arc::new(crate::idempotency::IdempotencyCache::default()),
         }))
     }
 
@@ -3197,6 +4026,31 @@ mod tests {
 
     // ---- Providers & Channels ----
 
+    #[test]
+    fn test_channel_provider_resolved_from_db() {
+        let db = crate::db::GatewayDb::open(std::path::Path::new(":memory:")).unwrap();
+        db.update_provider_config("groq", Some("gsk-channel"), None).unwrap();
+        let config: bizclaw_core::config::BizClawConfig = toml::from_str(
+            r#"
+            default_provider = "openai"
+            api_key = "sk-openai"
+
+            [channel.models.telegram]
+            provider = "groq"
+            model = "llama-3.1-8b-instant"
+            "#,
+        )
+        .unwrap();
+
+        let mut telegram = config.for_channel("telegram");
+        apply_provider_config_from_db(&db, &mut telegram);
+        assert_eq!(telegram.llm.provider, "groq");
+        assert_eq!(telegram.llm.api_key, "gsk-channel");
+        let agent = bizclaw_agent::Agent::new(telegram).unwrap();
+        assert_eq!(agent.provider_name(), "groq");
+        assert_eq!(agent.model_name(), "llama-3.1-8b-instant");
+    }
+
     #[tokio::test]
     async fn test_list_providers() {
         let result = list_providers(test_state()).await;
@@ -3205,6 +4059,229 @@ mod tests {
         assert!(json["providers"].as_array().unwrap().len() >= 5);
     }
 
+    #[tokio::test]
+    async fn test_channel_status_reports_reconnecting() {
+        let state = test_state();
+        {
+            let mut cfg = state.full_config.lock().unwrap();
+            cfg.channel
+                .set("telegram", serde_json::json!({"enabled": true, "bot_token": "123:abc"}))
+                .unwrap();
+            cfg.channel.set("webhook", serde_json::json!({})).unwrap();
+        }
+        bizclaw_channels::status::StatusRegistry::global().reconnecting(
+            "telegram",
+            "getUpdates failed: connection reset",
+            3,
+        );
+
+        let json = channel_status(state).await.0;
+        let channels = json["channels"].as_array().unwrap();
+        let telegram = channels.iter().find(|c| c["name"] == "telegram").unwrap();
+        assert_eq!(telegram["enabled"], true);
+        assert_eq!(telegram["state"], "reconnecting");
+        assert_eq!(telegram["error"], "getUpdates failed: connection reset");
+        assert_eq!(telegram["attempts"], 3);
+        let webhook = channels.iter().find(|c| c["name"] == "webhook").unwrap();
+        assert_eq!(webhook["state"], "not_started");
+    }
+
+    #[tokio::test]
+    async fn test_channel_status_lists_agent_bots() {
+        let status = bizclaw_channels::status::StatusRegistry::global();
+        status.connected("telegram:status-sales");
+        status.failed("discord:status-support", "authentication failed");
+
+        let json = channel_status(test_state()).await.0;
+        let channels = json["channels"].as_array().unwrap();
+        let find = |name: &str| channels.iter().find(|c| c["name"] == name).unwrap();
+        assert_eq!(find("telegram:status-sales")["state"], "connected");
+        let discord = find("discord:status-support");
+        assert_eq!(discord["state"], "error");
+        assert_eq!(discord["error"], "authentication failed");
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_mode_refuses_chat_but_not_health() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use tower::ServiceExt;
+
+        let state = test_state();
+        let app = crate::server::build_router_from_arc(state.0.clone());
+        let chat = || {
+            Request::post("/v1/chat/completions")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"model":"default","messages":[{"role":"user","content":"hi"}]}"#,
+                ))
+                .unwrap()
+        };
+
+        let json = set_maintenance(
+            State(state.0.clone()),
+            Json(serde_json::json!({"enabled": true, "message": "Upgrading — back at 10:00"})),
+        )
+        .await
+        .0;
+        assert_eq!(json["enabled"], true);
+
+        let resp = app.clone().oneshot(chat()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "Upgrading — back at 10:00");
+
+        let health = Request::get("/health").body(Body::empty()).unwrap();
+        let resp = app.clone().oneshot(health).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // Without a message, the built-in one is used.
+        let json = set_maintenance(State(state.0.clone()), Json(serde_json::json!({"enabled": true})))
+            .await
+            .0;
+        assert_eq!(json["enabled"], true);
+        assert_eq!(
+            json["message"],
+            bizclaw_core::maintenance::default_message(bizclaw_core::i18n::Locale::En)
+        );
+
+        // Off again: the request reaches the handler, which wants an API key.
+        let json = set_maintenance(State(state.0.clone()), Json(serde_json::json!({"enabled": false})))
+            .await
+            .0;
+        assert_eq!(json["enabled"], false);
+        let resp = app.oneshot(chat()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_chat_idempotency_skips_failures_and_refuses_reused_keys() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use tower::ServiceExt;
+
+        let state = test_state();
+        *state.pairing_code.lock().unwrap() = "pairing".into();
+        let app = crate::server::build_router_from_arc(state.0.clone());
+        let chat = |key: &str, content: &str| {
+            Request::post("/v1/chat/completions")
+                .header("Content-Type", "application/json")
+                .header("Authorization", "Bearer pairing")
+                .header("Idempotency-Key", key)
+                .body(Body::from(format!(
+                    r#"{{"model":"default","messages":[{{"role":"user","content":"{content}"}}]}}"#
+                )))
+                .unwrap()
+        };
+
+        // A key reused with another body is refused.
+        let resp = app.clone().oneshot(chat("key-1", "hi")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let resp = app.clone().oneshot(chat("key-1", "bye")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        // A turn the agent fails is answered but not replayed on retry.
+        let config = bizclaw_core::config::BizClawConfig {
+            default_provider: "custom:http://127.0.0.1:1/v1".into(),
+            ..Default::default()
+        };
+        *state.agent.lock().await = Some(bizclaw_agent::Agent::new(config).unwrap());
+        for _ in 0..2 {
+            let resp = app.clone().oneshot(chat("key-2", "hi")).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(resp.headers().get("Idempotent-Replayed").is_none());
+            let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let content = body["choices"][0]["message"]["content"].as_str().unwrap();
+            assert!(content.starts_with("Error ("), "{content}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chat_traced_under_the_agent_that_answered() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let state = test_state();
+        *state.pairing_code.lock().unwrap() = "pairing".into();
+        let config = bizclaw_core::config::BizClawConfig {
+            default_provider: "custom:http://127.0.0.1:1/v1".into(),
+            ..Default::default()
+        };
+        *state.agent.lock().await = Some(bizclaw_agent::Agent::new(config.clone()).unwrap());
+        state.orchestrator.lock().await.add_agent(
+            "support",
+            "assistant",
+            "",
+            bizclaw_agent::Agent::new(config).unwrap(),
+        );
+        let app = crate::server::build_router_from_arc(state.0.clone());
+        let mut activity = state.activity_tx.subscribe();
+
+        for (model, agent) in [("gpt-4o", "default"), ("support", "support")] {
+            let req = Request::post("/v1/chat/completions")
+                .header("Content-Type", "application/json")
+                .header("Authorization", "Bearer pairing")
+                .body(Body::from(format!(
+                    r#"{{"model":"{model}","messages":[{{"role":"user","content":"hi"}}]}}"#
+                )))
+                .unwrap();
+            app.clone().oneshot(req).await.unwrap();
+            assert_eq!(activity.recv().await.unwrap().agent, agent);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delegation_waits_for_a_slot_without_locking_the_orchestrator() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use bizclaw_core::types::{AgentLink, Delegation, DelegationMode, LinkDirection};
+        use bizclaw_db::store::DataStore;
+        use std::time::Duration;
+        use tower::ServiceExt;
+
+        let state = test_state();
+        let store = Arc::new(bizclaw_db::SqliteStore::in_memory().unwrap());
+        store.migrate().await.unwrap();
+        let mut link = AgentLink::new("lead", "worker", LinkDirection::Outbound);
+        link.max_concurrent = 1;
+        store.create_link(&link).await.unwrap();
+        // The worker's only slot is taken.
+        let busy = Delegation::new("lead", "worker", "busy", DelegationMode::Async);
+        store.create_delegation(&busy).await.unwrap();
+        {
+            let mut orch = bizclaw_agent::orchestrator::Orchestrator::with_store(store.clone());
+            for name in ["lead", "worker"] {
+                let agent = bizclaw_agent::Agent::new(bizclaw_core::config::BizClawConfig::default())
+                    .unwrap();
+                orch.add_agent(name, "assistant", "", agent);
+            }
+            *state.orchestrator.lock().await = orch;
+        }
+        let app = crate::server::build_router_from_arc(state.0.clone());
+
+        let delegate = Request::post("/api/v1/orchestration/delegate")
+            .header("Content-Type", "application/json")
+            .body(Body::from(
+                r#"{"from_agent":"lead","to_agent":"worker","task":"summarize"}"#,
+            ))
+            .unwrap();
+        let queued = tokio::spawn(app.clone().oneshot(delegate));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!queued.is_finished(), "delegation should wait for the busy slot");
+
+        // Other orchestration requests are served while it waits.
+        let links = Request::get("/api/v1/orchestration/links").body(Body::empty()).unwrap();
+        let resp = tokio::time::timeout(Duration::from_secs(2), app.oneshot(links))
+            .await
+            .expect("orchestrator stayed locked while the delegation waited")
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        queued.abort();
+    }
+
     #[tokio::test]
     async fn test_list_channels() {
         let result = list_channels(test_state()).await;
@@ -3232,6 +4309,47 @@ mod tests {
         assert!(json.is_object());
     }
 
+    #[tokio::test]
+    async fn test_full_config_masks_secrets() {
+        let state = test_state();
+        {
+            let mut cfg = state.full_config.lock().unwrap();
+            cfg.api_key = "sk-live-1234567890".into();
+            cfg.llm.api_key = "sk-live-1234567890".into();
+            cfg.brain.max_tokens = 321;
+        }
+        let toml = get_full_config(state).await.0["toml"].as_str().unwrap().to_string();
+        assert!(!toml.contains("sk-live-1234567890"), "{toml}");
+        assert!(toml.contains("sk-l••••"));
+        assert!(toml.contains("max_tokens = 321"));
+    }
+
+    #[tokio::test]
+    async fn test_settings_feature_off_locks_config_routes() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use tower::ServiceExt;
+
+        let state = test_state();
+        state.full_config.lock().unwrap().features.settings = false;
+        let app = crate::server::build_router_from_arc(state.0.clone());
+        let get = |uri: &str| Request::get(uri).body(Body::empty()).unwrap();
+
+        for uri in ["/api/v1/config/full", "/api/v1/providers"] {
+            let resp = app.clone().oneshot(get(uri)).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN, "{uri}");
+        }
+        let update = Request::post("/api/v1/config/update")
+            .header("Content-Type", "application/json")
+            .body(Body::from(r#"{"api_base_url":"http://attacker"}"#))
+            .unwrap();
+        assert_eq!(app.clone().oneshot(update).await.unwrap().status(), StatusCode::FORBIDDEN);
+        assert!(state.full_config.lock().unwrap().api_base_url.is_empty());
+        // The sanitized overview stays readable for the dashboard.
+        let resp = app.oneshot(get("/api/v1/config")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
     #[tokio::test]
     async fn test_update_config() {
         let body = Json(serde_json::json!({
@@ -3366,6 +4484,112 @@ mod tests {
         assert!(!json["connected"].as_bool().unwrap());
     }
 
+    // ---- WhatsApp webhook ----
+
+    #[tokio::test]
+    async fn test_whatsapp_webhook_checks_signature() {
+        use bizclaw_channels::whatsapp::signature;
+
+        let state = test_state();
+        let payload = r#"{"object":"whatsapp_business_account","entry":[]}"#;
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert(
+            "x-hub-signature-256",
+            signature("app-secret", payload.as_bytes()).parse().unwrap(),
+        );
+
+        // No app secret configured: nothing can be verified, so nothing is accepted.
+        let unconfigured = whatsapp_webhook(state.clone(), headers.clone(), payload.into()).await;
+        assert_eq!(unconfigured.status(), axum::http::StatusCode::FORBIDDEN);
+
+        state.full_config.lock().unwrap().channel.whatsapp =
+            Some(bizclaw_core::config::WhatsAppChannelConfig {
+                enabled: true,
+                access_token: String::new(),
+                phone_number_id: "1055".into(),
+                webhook_verify_token: String::new(),
+                app_secret: "app-secret".into(),
+                business_id: String::new(),
+                welcome_message: None,
+            });
+
+        let signed = whatsapp_webhook(state.clone(), headers.clone(), payload.into()).await;
+        assert_eq!(signed.status(), axum::http::StatusCode::OK);
+
+        let tampered = payload.replace("[]", r#"[{"changes":[]}]"#);
+        let rejected = whatsapp_webhook(state.clone(), headers, tampered.into()).await;
+        assert_eq!(rejected.status(), axum::http::StatusCode::FORBIDDEN);
+
+        let unsigned =
+            whatsapp_webhook(state, axum::http::HeaderMap::new(), payload.into()).await;
+        assert_eq!(unsigned.status(), axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_reply_channel_for_dead_letters() {
+        let state = channel_state("dead-letter-reply");
+        assert!(reply_channel(&state, "whatsapp").is_none());
+        assert!(reply_channel(&state, "webhook:orders").is_none());
+        assert!(reply_channel(&state, "zalo").is_none());
+
+        state.full_config.lock().unwrap().channel.whatsapp =
+            Some(bizclaw_core::config::WhatsAppChannelConfig {
+                enabled: true,
+                access_token: "token".into(),
+                phone_number_id: "1055".into(),
+                webhook_verify_token: String::new(),
+                app_secret: "app-secret".into(),
+                business_id: String::new(),
+                welcome_message: None,
+            });
+        let instances = serde_json::json!([{
+            "id": "orders",
+            "channel_type": "webhook",
+            "config": {"callback_url": "https://example.com/replies"},
+        }]);
+        std::fs::write(channel_instances_path(&state), instances.to_string()).unwrap();
+
+        assert_eq!(reply_channel(&state, "whatsapp").unwrap().name(), "whatsapp");
+        assert_eq!(reply_channel(&state, "webhook:orders").unwrap().name(), "webhook");
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_replayed_through_its_agent() {
+        let state = test_state();
+        state.orch_store.migrate().await.unwrap();
+        let msg = bizclaw_core::types::IncomingMessage {
+            channel: "zalo".into(),
+            thread_id: "42".into(),
+            sender_id: "7".into(),
+            sender_name: None,
+            content: "Giá bao nhiêu?".into(),
+            thread_type: bizclaw_core::types::ThreadType::Direct,
+            timestamp: chrono::Utc::now(),
+            reply_to: None,
+            message_id: None,
+            images: Vec::new(),
+        };
+        bizclaw_agent::dead_letter::record(state.orch_store.as_ref(), Some("sales"), &msg, "down")
+            .await;
+        let letter = state.orch_store.list_dead_letters(1).await.unwrap().remove(0);
+        assert_eq!(letter.agent_name.as_deref(), Some("sales"));
+        let replay = || {
+            dead_letters_replay(State(state.0.clone()), axum::extract::Path(letter.id.clone()))
+        };
+
+        let json = replay().await.0;
+        assert_eq!(json["error"], "Agent 'sales' not found");
+
+        // With the agent back, the replay runs on it (not the default agent,
+        // which is missing) and fails only for want of a zalo channel.
+        let agent = bizclaw_agent::Agent::new(bizclaw_core::config::BizClawConfig::default()).unwrap();
+        state.orchestrator.lock().await.add_agent("sales", "sales", "", agent);
+        let json = replay().await.0;
+        assert_eq!(json["ok"], false);
+        assert_ne!(json["error"], "Agent not available");
+        assert!(state.orch_store.get_dead_letter(&letter.id).await.unwrap().is_some());
+    }
+
     // ---- Knowledge Base ----
 
     #[tokio::test]
@@ -3400,6 +4624,364 @@ mod tests {
         let json = result.0;
         assert!(json["ok"].as_bool().unwrap());
     }
+
+    #[tokio::test]
+    async fn test_scheduler_cancel_task() {
+        let state = test_state();
+        let id = {
+            let mut sched = state.scheduler.lock().await;
+            let task = bizclaw_scheduler::Task::interval(
+                "cancel-me",
+                60,
+                bizclaw_scheduler::tasks::TaskAction::Notify("x".into()),
+            );
+            let id = task.id.clone();
+            sched.add_task(task);
+            id
+        };
+        let idle = scheduler_cancel_task(State(state.0.clone()), axum::extract::Path(id.clone())).await;
+        assert_eq!(idle.0["ok"], false);
+
+        let token = state.scheduler.lock().await.start_run(&id);
+        let json = scheduler_cancel_task(State(state.0.clone()), axum::extract::Path(id.clone())).await.0;
+        assert_eq!(json["status"], "cancelled");
+        assert!(token.is_cancelled());
+        state.scheduler.lock().await.remove_task(&id);
+    }
+
+    // ---- Workflows ----
+
+    #[tokio::test]
+    async fn test_workflows_cancel_run() {
+        let state = test_state();
+        let missing = workflows_cancel_run(State(state.0.clone()), axum::extract::Path("nope".into())).await;
+        assert_eq!(missing.0["ok"], false);
+
+        let token = bizclaw_core::cancel::CancelToken::new();
+        state.0.workflow_runs.lock().unwrap().insert("run-1".into(), crate::server::WorkflowRun {
+            workflow_id: "wf".into(),
+            workflow_name: "WF".into(),
+            started_at: chrono::Utc::now(),
+            current_step: 1,
+            total_steps: 3,
+            cancel: token.clone(),
+        });
+        let listed = workflows_runs_list(State(state.0.clone())).await.0;
+        assert_eq!(listed["runs"][0]["status"], "running");
+
+        let json = workflows_cancel_run(State(state.0.clone()), axum::extract::Path("run-1".into())).await.0;
+        assert_eq!(json["ok"], true);
+        assert!(token.is_cancelled());
+        let listed = workflows_runs_list(State(state.0.clone())).await.0;
+        assert_eq!(listed["runs"][0]["status"], "cancelling");
+    }
+
+    #[tokio::test]
+    async fn test_workflow_run_streams_step_events() {
+        let dir = std::env::temp_dir().join(format!("bizclaw-wf-stream-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let state = test_state_at(dir.join("config.toml"));
+        let created = workflows_create(State(state.0.clone()), Json(serde_json::json!({
+            "name": "Two Step",
+            "steps": [
+                {"name": "Draft", "agent_role": "Writer"},
+                {"name": "Review", "agent_role": "Editor"},
+            ],
+        }))).await.0;
+        assert_eq!(created["ok"], true);
+
+        let mut params = std::collections::HashMap::new();
+        params.insert("stream".to_string(), "true".to_string());
+        let response = workflows_run_named(
+            State(state.0.clone()),
+            axum::extract::Path("Two Step".into()),
+            axum::extract::Query(params),
+            axum::body::Bytes::from(r#"{"input": "launch post"}"#),
+        )
+        .await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        let events: Vec<(&str, serde_json::Value)> = body
+            .split("\n\n")
+            .filter(|block| !block.trim().is_empty())
+            .map(|block| {
+                let event = block.lines().find_map(|l| l.strip_prefix("event: ")).unwrap();
+                let data = block.lines().find_map(|l| l.strip_prefix("data: ")).unwrap();
+                (event, serde_json::from_str(data).unwrap())
+            })
+            .collect();
+        let names: Vec<&str> = events.iter().map(|(event, _)| *event).collect();
+        assert_eq!(names, ["step_completed", "step_completed", "done"]);
+        assert_eq!(events[0].1["name"], "Draft");
+        assert_eq!(events[1].1["name"], "Review");
+        // No agent is configured in tests, so each step reports a failure.
+        assert_eq!(events[0].1["status"], "failed");
+        assert!(events[0].1["tokens"].as_u64().unwrap() > 0);
+        assert_eq!(events[2].1["steps_completed"], 2);
+        assert!(state.0.workflow_runs.lock().unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // ---- Sessions ----
+
+    #[tokio::test]
+    async fn test_sessions_clear() {
+        let state = test_state();
+        {
+            let mut sessions = state.0.sessions.lock().unwrap();
+            sessions.push("s1", "user", "hello");
+            sessions.push("s1", "assistant", "hi");
+        }
+        let json = sessions_clear(State(state.0.clone()), axum::extract::Path("s1".into())).await.0;
+        assert_eq!(json["ok"], true);
+        assert_eq!(json["cleared_messages"], 2);
+        assert!(state.0.sessions.lock().unwrap().history("s1").is_empty());
+    }
+
+    #[test]
+    fn test_agent_telegram_config_keeps_formatting() {
+        use bizclaw_core::config::TelegramParseMode;
+        let state = test_state();
+        let bare = agent_telegram_config(&state, "123:abc", &serde_json::json!({}));
+        assert_eq!(bare.bot_token, "123:abc");
+        assert_eq!(bare.parse_mode, TelegramParseMode::Markdown);
+
+        state
+            .full_config
+            .lock()
+            .unwrap()
+            .channel
+            .set(
+                "telegram",
+                serde_json::json!({
+                    "enabled": true,
+                    "bot_token": "999:main",
+                    "parse_mode": "html",
+                    "chat_parse_modes": {"-1001": "plain"},
+                }),
+            )
+            .unwrap();
+        let config = agent_telegram_config(
+            &state,
+            "123:abc",
+            &serde_json::json!({"chat_parse_modes": {"-1002": "markdownv2"}}),
+        );
+        assert_eq!(config.bot_token, "123:abc");
+        assert_eq!(config.parse_mode_for(42), TelegramParseMode::Html);
+        assert_eq!(config.parse_mode_for(-1001), TelegramParseMode::Plain);
+        assert_eq!(config.parse_mode_for(-1002), TelegramParseMode::MarkdownV2);
+
+        let plain = agent_telegram_config(&state, "123:abc", &serde_json::json!({"parse_mode": "plain"}));
+        assert_eq!(plain.parse_mode, TelegramParseMode::Plain);
+    }
+
+    #[tokio::test]
+    async fn test_skills_feature_off_hides_installed_skills() {
+        let state = channel_state("skills-off");
+        let install = |id: &str| {
+            skills_install(
+                State(state.0.clone()),
+                Json(serde_json::json!({"skill": id})),
+            )
+        };
+        assert_eq!(install("rust-expert").await.0["ok"], true);
+        let installed = |state: &AppState| {
+            skill_catalog(state)
+                .iter()
+                .filter(|s| s["installed"] == true)
+                .count()
+        };
+        assert_eq!(installed(&state), 1);
+
+        state.full_config.lock().unwrap().features.skills = false;
+        assert_eq!(installed(&state), 0);
+        assert_eq!(install("sql-expert").await.0["ok"], false);
+        let created = skills_create(
+            State(state.0.clone()),
+            Json(serde_json::json!({"name": "Custom"})),
+        )
+        .await;
+        assert_eq!(created.0["ok"], false);
+
+        // Turning skills back on restores what was installed.
+        state.full_config.lock().unwrap().features.skills = true;
+        assert_eq!(installed(&state), 1);
+    }
+
+    // ---- Per-channel config ----
+
+    fn channel_state(name: &str) -> State<Arc<AppState>> {
+        let dir = std::env::temp_dir().join(format!("bizclaw-channels-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        test_state_at(dir.join("config.toml"))
+    }
+
+    fn kind(k: &str) -> axum::extract::Path<String> {
+        axum::extract::Path(k.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_channel_config_round_trip() {
+        let state = channel_state("round-trip");
+        let samples = [
+            (
+                "telegram",
+                serde_json::json!({"enabled": true, "bot_token": "123456:secret", "allowed_chat_ids": [42]}),
+                "bot_token",
+            ),
+            (
+                "zalo",
+                serde_json::json!({"enabled": true, "mode": "official", "oa_access_token": "oa-secret"}),
+                "oa_access_token",
+            ),
+            (
+                "discord",
+                serde_json::json!({"enabled": true, "bot_token": "discord-secret", "allowed_channel_ids": [7]}),
+                "bot_token",
+            ),
+            (
+                "email",
+                serde_json::json!({"enabled": true, "smtp_host": "smtp.x.io", "email": "a@x.io", "password": "pw-secret"}),
+                "password",
+            ),
+            (
+                "whatsapp",
+                serde_json::json!({"enabled": true, "phone_number_id": "1", "access_token": "wa-secret"}),
+                "access_token",
+            ),
+            (
+                "webhook",
+                serde_json::json!({"enabled": true, "secret": "wh-secret", "outbound_url": "https://x.io/hook"}),
+                "secret",
+            ),
+        ];
+        for (k, body, secret_field) in samples {
+            let json = put_channel_config(State(state.0.clone()), kind(k), Json(body.clone())).await.0;
+            assert_eq!(json["ok"], true, "{k}: {json}");
+            let masked = json["config"][secret_field].as_str().unwrap().to_string();
+            assert!(masked.contains('•'), "{k} secret not masked");
+
+            // Saved to disk, not only in memory.
+            let on_disk = bizclaw_core::config::BizClawConfig::load_from(&state.0.config_path).unwrap();
+            let saved = on_disk.channel.get(k).unwrap().unwrap();
+            assert_eq!(saved[secret_field], body[secret_field]);
+
+            // Sending the masked value back keeps the real secret.
+            let mut resend = body.clone();
+            resend[secret_field] = masked.into();
+            resend["enabled"] = false.into();
+            let json = put_channel_config(State(state.0.clone()), kind(k), Json(resend)).await.0;
+            assert_eq!(json["ok"], true, "{k}: {json}");
+            let on_disk = bizclaw_core::config::BizClawConfig::load_from(&state.0.config_path).unwrap();
+            let saved = on_disk.channel.get(k).unwrap().unwrap();
+            assert_eq!(saved[secret_field], body[secret_field]);
+            assert_eq!(saved["enabled"], false);
+
+            let json = get_channel_config(State(state.0.clone()), kind(k)).await.0;
+            assert_eq!(json["ok"], true);
+            assert_eq!(json["config"]["enabled"], false);
+
+            let json = delete_channel_config(State(state.0.clone()), kind(k)).await.0;
+            assert_eq!(json["ok"], true);
+            let json = get_channel_config(State(state.0.clone()), kind(k)).await.0;
+            assert_eq!(json["ok"], false);
+        }
+        let _ = std::fs::remove_dir_all(state.0.config_path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_channel_config_rejects_invalid_and_unknown() {
+        let state = channel_state("invalid");
+        let json = put_channel_config(
+            State(state.0.clone()),
+            kind("webhook"),
+            Json(serde_json::json!({"enabled": true, "outbound_url": "not-a-url"})),
+        )
+        .await
+        .0;
+        assert_eq!(json["ok"], false);
+        assert!(json["error"].as_str().unwrap().contains("outbound_url"));
+        assert!(!state.0.config_path.exists());
+
+        let json = put_channel_config(State(state.0.clone()), kind("sms"), Json(serde_json::json!({}))).await.0;
+        assert_eq!(json["ok"], false);
+        let json = delete_channel_config(State(state.0.clone()), kind("telegram")).await.0;
+        assert_eq!(json["ok"], false);
+        let _ = std::fs::remove_dir_all(state.0.config_path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_channel_config_failed_write_keeps_existing() {
+        let state = channel_state("failed-write");
+        let body = serde_json::json!({"enabled": true, "bot_token": "discord-secret"});
+        let json = put_channel_config(State(state.0.clone()), kind("discord"), Json(body)).await.0;
+        assert_eq!(json["ok"], true);
+        let before = std::fs::read_to_string(&state.0.config_path).unwrap();
+
+        // A directory in the way makes the rename fail.
+        let blocked = test_state_at(state.0.config_path.parent().unwrap().to_path_buf());
+        *blocked.0.full_config.lock().unwrap() = state.0.full_config.lock().unwrap().clone();
+        let body = serde_json::json!({"enabled": true, "bot_token": "other"});
+        let json = put_channel_config(State(blocked.0.clone()), kind("discord"), Json(body)).await.0;
+        assert_eq!(json["ok"], false);
+        // Neither the in-memory config nor the file on disk changed.
+        let cfg = blocked.0.full_config.lock().unwrap().clone();
+        assert_eq!(cfg.channel.discord.unwrap().bot_token, "discord-secret");
+        assert_eq!(std::fs::read_to_string(&state.0.config_path).unwrap(), before);
+        let _ = std::fs::remove_dir_all(state.0.config_path.parent().unwrap());
+    }
+
+    // ---- Config schema ----
+
+    #[test]
+    fn test_config_schema_lists_providers_and_secrets() {
+        let schema = config_schema();
+        let provider = schema.field("default_provider").unwrap();
+        assert!(provider.options.iter().any(|p| p == "openai"));
+        assert!(provider.options.iter().any(|p| p == "brain"));
+        assert!(!provider.secret);
+        assert!(schema.field("api_key").unwrap().secret);
+
+        let json = serde_json::to_value(&schema.fields).unwrap();
+        let api_key = json.as_array().unwrap().iter().find(|f| f["path"] == "api_key");
+        assert_eq!(api_key.unwrap()["type"], "string");
+    }
+
+    // ---- WebSocket auth ----
+
+    #[tokio::test]
+    async fn test_ws_upgrade_requires_pairing_or_ticket() {
+        use crate::ws_auth::authorize;
+        use axum::http::HeaderMap;
+        use std::collections::HashMap;
+
+        let state = test_state();
+        *state.0.pairing_code.lock().unwrap() = "123456".into();
+        let none = HashMap::new();
+        assert_eq!(
+            authorize(&state.0, &HeaderMap::new(), &none),
+            Err(axum::http::StatusCode::UNAUTHORIZED)
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Pairing-Code", "123456".parse().unwrap());
+        let scope = authorize(&state.0, &headers, &none).unwrap();
+        assert!(!scope.pinned);
+
+        let body = serde_json::json!({"session_id": "s-42"});
+        let json = crate::ws_auth::issue_ticket(State(state.0.clone()), Some(Json(body)))
+            .await
+            .0;
+        let query: HashMap<String, String> =
+            [("ticket".to_string(), json["ticket"].as_str().unwrap().to_string())].into();
+        let scope = authorize(&state.0, &HeaderMap::new(), &query).unwrap();
+        assert!(scope.pinned);
+        assert_eq!(scope.session_id, "s-42");
+        // Tickets are single-use.
+        assert!(authorize(&state.0, &HeaderMap::new(), &query).is_err());
+    }
 }
 
 // ═══════════════════════════════════════════════════════
@@ -3661,8 +5243,20 @@ pub async fn orch_delegate(
         _ => bizclaw_core::types::DelegationMode::Sync,
     };
 
-    let mut orch = state.orchestrator.lock().await;
-    match orch.delegate_with_mode(from, to, task, mode).await {
+    // Wait for a free slot on the target without holding the orchestrator,
+    // so other orchestration requests are served meanwhile.
+    let result = async {
+        let pending = state
+            .orchestrator
+            .lock()
+            .await
+            .begin_delegation(from, to, task, mode)
+            .await?;
+        let ready = pending.ready().await?;
+        state.orchestrator.lock().await.run_delegation(ready).await
+    }
+    .await;
+    match result {
         Ok(response) => Json(serde_json::json!({
             "ok": true,
             "from": from,
@@ -3707,13 +5301,59 @@ pub async fn orch_clear_handoff(
     State(state): State<Arc<AppState>>,
     axum::extract::Path(session_id): axum::extract::Path<String>,
 ) -> Json<serde_json::Value> {
-    let orch = state.orchestrator.lock().await;
+    let mut orch = state.orchestrator.lock().await;
     match orch.clear_handoff(&session_id).await {
         Ok(()) => Json(serde_json::json!({"ok": true, "session": session_id})),
         Err(e) => internal_error("clear_handoff", e),
     }
 }
 
+/// Clear a chat session's history (the dashboard equivalent of CLI `/clear`).
+/// POST /api/v1/sessions/{id}/clear
+pub async fn sessions_clear(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(session_id): axum::extract::Path<String>,
+) -> Json<serde_json::Value> {
+    let cleared = state
+        .sessions
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .clear(&session_id);
+    // The Agent engine keeps its own conversation under its session id
+    let mut agent_cleared = false;
+    if let Some(agent) = state.agent.lock().await.as_mut()
+        && agent.session_id() == session_id
+    {
+        agent.clear_conversation();
+        agent_cleared = true;
+    }
+    Json(serde_json::json!({
+        "ok": true,
+        "session": session_id,
+        "cleared_messages": cleared,
+        "agent_cleared": agent_cleared,
+    }))
+}
+
+/// Pin or unpin a message of the agent conversation. Pinned messages are
+/// kept when old turns are trimmed. Body: `{"pinned": true}` (default true).
+/// PUT /api/v1/conversation/messages/{index}/pin
+pub async fn conversation_pin(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(index): axum::extract::Path<usize>,
+    Json(body): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let pinned = body["pinned"].as_bool().unwrap_or(true);
+    let mut agent = state.agent.lock().await;
+    let Some(agent) = agent.as_mut() else {
+        return Json(serde_json::json!({"ok": false, "error": "Agent not available"}));
+    };
+    match agent.set_pinned(index, pinned) {
+        Ok(()) => Json(serde_json::json!({"ok": true, "index": index, "pinned": pinned})),
+        Err(e) => Json(serde_json::json!({"ok": false, "error": e.to_string()})),
+    }
+}
+
 /// Run evaluate loop between two agents.
 /// POST /api/v1/orchestration/evaluate
 pub async fn orch_evaluate(
@@ -3844,6 +5484,24 @@ pub async fn orch_list_delegations(
     Json(serde_json::json!({"ok": true, "delegations": items, "count": items.len()}))
 }
 
+/// GET /api/v1/orchestration/delegations/{id}/events — progress updates, oldest first.
+/// Pass `?after=N` to get only events after the first N (for polling).
+pub async fn orch_delegation_events(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Json<serde_json::Value> {
+    let after = params.get("after").and_then(|s| s.parse::<usize>().ok()).unwrap_or(0);
+    match state.orch_store.list_delegation_events(&id).await {
+        Ok(events) => {
+            let total = events.len();
+            let items: Vec<_> = events.into_iter().skip(after).collect();
+            Json(serde_json::json!({"ok": true, "events": items, "total": total}))
+        }
+        Err(e) => Json(serde_json::json!({"ok": false, "error": e.to_string()})),
+    }
+}
+
 /// List LLM traces (observability).
 /// GET /api/v1/orchestration/traces?limit=50
 pub async fn orch_list_traces(
@@ -3870,6 +5528,199 @@ pub async fn orch_list_traces(
     Json(serde_json::json!({"ok": true, "traces": items, "count": items.len()}))
 }
 
+/// Token usage of all agents aggregated for billing.
+/// GET /api/v1/orchestration/usage?group_by=day|provider|model&since=2026-03-01&until=2026-04-01
+/// `since`/`until` take a date (UTC midnight) or an RFC 3339 time; `until` is exclusive.
+pub async fn orch_usage_summary(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Json<serde_json::Value> {
+    let group_by: bizclaw_core::types::UsageGroupBy =
+        match params.get("group_by").map_or(Ok(bizclaw_core::types::UsageGroupBy::Day), |g| g.parse()) {
+            Ok(g) => g,
+            Err(e) => return Json(serde_json::json!({"ok": false, "error": e})),
+        };
+    let parse_time = |key: &str| -> std::result::Result<Option<chrono::DateTime<chrono::Utc>>, String> {
+        let Some(value) = params.get(key).map(|v| v.trim()).filter(|v| !v.is_empty()) else {
+            return Ok(None);
+        };
+        if let Ok(t) = chrono::DateTime::parse_from_rfc3339(value) {
+            return Ok(Some(t.with_timezone(&chrono::Utc)));
+        }
+        chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(|d| Some(d.and_time(chrono::NaiveTime::MIN).and_utc()))
+            .map_err(|_| format!("{key} must be YYYY-MM-DD or an RFC 3339 time"))
+    };
+    let (since, until) = match (parse_time("since"), parse_time("until")) {
+        (Ok(since), Ok(until)) => (since, until),
+        (Err(e), _) | (_, Err(e)) => return Json(serde_json::json!({"ok": false, "error": e})),
+    };
+
+    match state.orch_store.usage_summary(group_by, since, until).await {
+        Ok(rows) => Json(serde_json::json!({
+            "ok": true,
+            "group_by": group_by.to_string(),
+            "since": since.map(|t| t.to_rfc3339()),
+            "until": until.map(|t| t.to_rfc3339()),
+            "rows": rows,
+        })),
+        Err(e) => Json(serde_json::json!({"ok": false, "error": e.to_string()})),
+    }
+}
+
+// ═══ Dead Letters API ═══
+
+/// List channel messages the agent failed to process, newest first.
+/// GET /api/v1/dead-letters?limit=50
+pub async fn dead_letters_list(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Json<serde_json::Value> {
+    let limit = params.get("limit").and_then(|s| s.parse::<usize>().ok()).unwrap_or(50);
+    match state.orch_store.list_dead_letters(limit).await {
+        Ok(letters) => {
+            let items: Vec<serde_json::Value> = letters.iter().map(|l| serde_json::json!({
+                "id": l.id,
+                "channel": l.channel,
+                "agent": l.agent_name,
+                "thread_id": l.message.thread_id,
+                "sender_id": l.message.sender_id,
+                "content": l.message.content,
+                "error": l.error,
+                "created_at": l.created_at.to_rfc3339(),
+            })).collect();
+            Json(serde_json::json!({"ok": true, "dead_letters": items, "count": items.len()}))
+        }
+        Err(e) => internal_error("list_dead_letters", e),
+    }
+}
+
+/// Run a dead-lettered message through the agent again and send the answer
+/// to the original thread. On success the entry is removed and the response
+/// returned; on failure it is kept.
+/// POST /api/v1/dead-letters/{id}/replay
+pub async fn dead_letters_replay(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Json<serde_json::Value> {
+    let letter = match state.orch_store.get_dead_letter(&id).await {
+        Ok(Some(letter)) => letter,
+        Ok(None) => return Json(serde_json::json!({"ok": false, "error": "Dead letter not found"})),
+        Err(e) => return internal_error("get_dead_letter", e),
+    };
+    let channel_for = |name: &str| reply_channel(&state, name);
+    let store = state.orch_store.as_ref();
+    // Replay through the agent that failed the message
+    let result = match &letter.agent_name {
+        Some(name) => {
+            let mut orch = state.orchestrator.lock().await;
+            let Some(agent) = orch.get_agent_mut(name) else {
+                return Json(serde_json::json!({"ok": false, "error": format!("Agent '{name}' not found")}));
+            };
+            bizclaw_agent::dead_letter::replay_letter(agent, store, &letter, channel_for).await
+        }
+        None => {
+            let mut agent_lock = state.agent.lock().await;
+            let Some(agent) = agent_lock.as_mut() else {
+                return Json(serde_json::json!({"ok": false, "error": "Agent not available"}));
+            };
+            bizclaw_agent::dead_letter::replay_letter(agent, store, &letter, channel_for).await
+        }
+    };
+    match result {
+        Ok(response) => Json(serde_json::json!({"ok": true, "response": response})),
+        Err(e) => {
+            tracing::error!("[replay_dead_letter:{id}] Agent error ({}): {e}", e.kind.code());
+            Json(serde_json::json!({"ok": false, "error": e.kind.code()}))
+        }
+    }
+}
+
+/// Channel to answer a replayed dead letter on, built from the config of
+/// `name`: a channel type, or `webhook:<name>` for a named webhook.
+fn reply_channel(state: &AppState, name: &str) -> Option<Box<dyn bizclaw_core::traits::Channel>> {
+    use bizclaw_channels::{discord, email, telegram, webhook, whatsapp};
+
+    let webhook_to = |url: &str| -> Box<dyn bizclaw_core::traits::Channel> {
+        Box::new(webhook::WebhookChannel::new(webhook::WebhookConfig {
+            outbound_url: Some(url.to_string()),
+            secret: None,
+            enabled: true,
+        }))
+    };
+    if name == "webhook" || name.starts_with("webhook:") {
+        let hook = name.strip_prefix("webhook:");
+        let instances = load_channel_instances(state);
+        let inst = instances.iter().find(|i| {
+            i["channel_type"].as_str() == Some("webhook")
+                && match hook {
+                    Some(hook) => i["id"].as_str() == Some(hook) || i["name"].as_str() == Some(hook),
+                    None => !i["agent_name"].as_str().unwrap_or("").is_empty(),
+                }
+        });
+        let url = inst.and_then(|i| {
+            ["callback_url", "webhook_url"]
+                .iter()
+                .filter_map(|key| i["config"][key].as_str())
+                .find(|url| !url.is_empty())
+                .map(str::to_string)
+        });
+        if let Some(url) = url {
+            return Some(webhook_to(&url));
+        }
+    }
+
+    let cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+    let channel: Box<dyn bizclaw_core::traits::Channel> = match name {
+        "telegram" => Box::new(telegram::TelegramChannel::new(
+            telegram::TelegramConfig::from_channel_config(cfg.channel.telegram.as_ref()?),
+        )),
+        "discord" => Box::new(discord::DiscordChannel::new(discord::DiscordConfig {
+            bot_token: cfg.channel.discord.as_ref()?.bot_token.clone(),
+            enabled: true,
+            intents: 33281,
+        })),
+        "whatsapp" => {
+            let wa = cfg.channel.whatsapp.as_ref()?;
+            Box::new(whatsapp::WhatsAppChannel::new(whatsapp::WhatsAppConfig {
+                access_token: wa.access_token.clone(),
+                phone_number_id: wa.phone_number_id.clone(),
+                ..Default::default()
+            }))
+        }
+        "email" => {
+            let em = cfg.channel.email.as_ref()?;
+            Box::new(email::EmailChannel::new(email::EmailConfig {
+                imap_host: em.imap_host.clone(),
+                imap_port: em.imap_port,
+                smtp_host: em.smtp_host.clone(),
+                smtp_port: em.smtp_port,
+                email: em.email.clone(),
+                password: em.password.clone(),
+                ..Default::default()
+            }))
+        }
+        "webhook" => webhook_to(
+            Some(cfg.channel.webhook.as_ref()?.outbound_url.as_str()).filter(|u| !u.is_empty())?,
+        ),
+        _ => return None,
+    };
+    Some(channel)
+}
+
+/// Discard a dead letter without replaying it.
+/// DELETE /api/v1/dead-letters/{id}
+pub async fn dead_letters_delete(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Json<serde_json::Value> {
+    match state.orch_store.delete_dead_letter(&id).await {
+        Ok(true) => Json(serde_json::json!({"ok": true})),
+        Ok(false) => Json(serde_json::json!({"ok": false, "error": "Dead letter not found"})),
+        Err(e) => internal_error("delete_dead_letter", e),
+    }
+}
+
 // ═══ MCP Servers API ═══
 pub async fn mcp_list_servers(
     State(state): State<Arc<AppState>>,
@@ -3917,7 +5768,7 @@ pub async fn xiaozhi_webhook(
         let mut orch = state.orchestrator.lock().await;
         match orch.send(&req.content).await {
             Ok(r) => r,
-            Err(e) => format!("⚠️ Agent error: {e}"),
+            Err(e) => agent_error_reply(&state, &e),
         }
     };
 
@@ -4100,49 +5951,81 @@ pub async fn workflows_delete(
     }
 }
 
-/// Run a workflow — execute steps sequentially through the agent.
-pub async fn workflows_run(
-    State(state): State<Arc<AppState>>,
-    Json(body): Json<serde_json::Value>,
-) -> Json<serde_json::Value> {
-    let workflow_id = body["workflow_id"].as_str().unwrap_or("");
-    let input = body["input"].as_str().unwrap_or("");
-
-    if workflow_id.is_empty() {
-        return Json(serde_json::json!({"ok": false, "error": "workflow_id is required"}));
-    }
-
-    // Find the workflow (check user files first, then built-in templates)
+/// Find a workflow by ID (user files first, then built-in templates) or by name.
+async fn find_workflow(state: &Arc<AppState>, key: &str) -> Option<serde_json::Value> {
     let wf_dir = state.config_path.parent()
         .unwrap_or(std::path::Path::new("."))
         .join("workflows");
-    let user_path = wf_dir.join(format!("{}.json", workflow_id));
+    let user_path = wf_dir.join(format!("{}.json", key));
 
-    let workflow: Option<serde_json::Value> = if user_path.exists() {
+    if user_path.exists() {
         std::fs::read_to_string(&user_path)
             .ok()
             .and_then(|s| serde_json::from_str(&s).ok())
     } else {
-        // Check built-in templates
         let list_resp = workflows_list(State(state.clone())).await;
         let wfs = list_resp.0["workflows"].as_array().cloned().unwrap_or_default();
-        wfs.into_iter().find(|w| w["id"].as_str() == Some(workflow_id))
-    };
+        wfs.into_iter().find(|w| w["id"].as_str() == Some(key) || w["name"].as_str() == Some(key))
+    }
+}
 
-    let workflow = match workflow {
-        Some(wf) => wf,
-        None => return Json(serde_json::json!({"ok": false, "error": format!("Workflow '{}' not found", workflow_id)})),
-    };
+/// Register a run so it can be listed and cancelled. Callers may pick the run
+/// ID up front so they can cancel the run while it executes.
+fn start_workflow_run(
+    state: &AppState,
+    body: &serde_json::Value,
+    workflow_id: &str,
+    workflow: &serde_json::Value,
+) -> Result<(String, bizclaw_core::cancel::CancelToken), String> {
+    let run_id = body["run_id"]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let cancel = bizclaw_core::cancel::CancelToken::new();
+    let mut runs = state.workflow_runs.lock().unwrap_or_else(|p| p.into_inner());
+    if runs.contains_key(&run_id) {
+        return Err(format!("Run '{}' is already in progress", run_id));
+    }
+    runs.insert(run_id.clone(), super::server::WorkflowRun {
+        workflow_id: workflow_id.to_string(),
+        workflow_name: workflow["name"].as_str().unwrap_or(workflow_id).to_string(),
+        started_at: chrono::Utc::now(),
+        current_step: 0,
+        total_steps: workflow["steps"].as_array().map_or(0, |s| s.len()),
+        cancel: cancel.clone(),
+    });
+    Ok((run_id, cancel))
+}
 
+/// Execute the steps of a registered run sequentially through the agent,
+/// calling `on_step` with each step's result as it completes. Returns the
+/// run summary.
+async fn execute_workflow_run(
+    state: &AppState,
+    run_id: &str,
+    workflow_id: &str,
+    workflow: &serde_json::Value,
+    input: &str,
+    cancel: &bizclaw_core::cancel::CancelToken,
+    mut on_step: impl FnMut(&serde_json::Value),
+) -> serde_json::Value {
     let steps = workflow["steps"].as_array().cloned().unwrap_or_default();
     let wf_name = workflow["name"].as_str().unwrap_or(workflow_id);
 
-    tracing::info!("▶ Running workflow '{}' ({} steps), input: {:?}", wf_name, steps.len(), input);
+    tracing::info!("▶ Running workflow '{}' ({} steps, run {}), input: {:?}", wf_name, steps.len(), run_id, input);
 
     let mut results: Vec<serde_json::Value> = Vec::new();
     let mut current_input = input.to_string();
+    let mut total_tokens = 0;
 
     for (i, step) in steps.iter().enumerate() {
+        if cancel.is_cancelled() {
+            break;
+        }
+        if let Some(run) = state.workflow_runs.lock().unwrap_or_else(|p| p.into_inner()).get_mut(run_id) {
+            run.current_step = i + 1;
+        }
         let step_name = step["name"].as_str().unwrap_or("Step");
         let agent_role = step["agent_role"].as_str().unwrap_or("Agent");
         let step_prompt = step["prompt"].as_str().unwrap_or("");
@@ -4158,37 +6041,161 @@ pub async fn workflows_run(
 
         tracing::info!("  → Step {}/{}: {} ({})", i + 1, steps.len(), step_name, agent_role);
 
-        let response = {
+        let step_run = async {
             let mut agent = state.agent.lock().await;
             if let Some(agent) = agent.as_mut() {
                 match agent.process(&prompt).await {
-                    Ok(r) => r,
-                    Err(e) => format!("Error in step '{}': {}", step_name, e),
+                    Ok(r) => (r, "completed"),
+                    Err(e) => (format!("Error in step '{}': {}", step_name, e), "failed"),
                 }
             } else {
-                "Agent not available".to_string()
+                ("Agent not available".to_string(), "failed")
             }
         };
+        let (response, status) = tokio::select! {
+            r = step_run => r,
+            _ = cancel.cancelled() => break,
+        };
 
-        results.push(serde_json::json!({
+        // Estimated — the agent does not report per-turn usage.
+        let tokens = bizclaw_agent::context::estimate_tokens(&prompt)
+            + bizclaw_agent::context::estimate_tokens(&response);
+        total_tokens += tokens;
+        let result = serde_json::json!({
             "step": i + 1,
             "name": step_name,
             "agent_role": agent_role,
+            "status": status,
+            "tokens": tokens,
             "output": response,
-        }));
+        });
+        on_step(&result);
+        results.push(result);
 
         current_input = response;
     }
 
-    tracing::info!("✅ Workflow '{}' completed ({} steps)", wf_name, results.len());
+    state.workflow_runs.lock().unwrap_or_else(|p| p.into_inner()).remove(run_id);
+    let status = if cancel.is_cancelled() {
+        tracing::warn!("🚫 Workflow '{}' cancelled after {} of {} steps", wf_name, results.len(), steps.len());
+        "cancelled"
+    } else {
+        tracing::info!("✅ Workflow '{}' completed ({} steps)", wf_name, results.len());
+        "completed"
+    };
 
-    Json(serde_json::json!({
+    serde_json::json!({
         "ok": true,
+        "run_id": run_id,
+        "status": status,
         "workflow": wf_name,
         "steps_completed": results.len(),
+        "total_tokens": total_tokens,
         "results": results,
         "final_output": current_input,
-    }))
+    })
+}
+
+/// Run a workflow — execute steps sequentially through the agent.
+pub async fn workflows_run(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let workflow_id = body["workflow_id"].as_str().unwrap_or("");
+    let input = body["input"].as_str().unwrap_or("");
+
+    if workflow_id.is_empty() {
+        return Json(serde_json::json!({"ok": false, "error": "workflow_id is required"}));
+    }
+
+    let Some(workflow) = find_workflow(&state, workflow_id).await else {
+        return Json(serde_json::json!({"ok": false, "error": format!("Workflow '{}' not found", workflow_id)}));
+    };
+    let (run_id, cancel) = match start_workflow_run(&state, &body, workflow_id, &workflow) {
+        Ok(run) => run,
+        Err(e) => return Json(serde_json::json!({"ok": false, "error": e})),
+    };
+
+    Json(execute_workflow_run(&state, &run_id, workflow_id, &workflow, input, &cancel, |_| {}).await)
+}
+
+/// Run a workflow by ID or name. With `?stream=true` the response is SSE:
+/// a `step_completed` event with each step's name, status, tokens and output
+/// as it finishes, then a `done` event with the run summary. Closing the
+/// stream cancels the run.
+/// POST /api/v1/workflows/{name}/run?stream=true
+pub async fn workflows_run_named(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(name): axum::extract::Path<String>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+    body: axum::body::Bytes,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+    use axum::response::sse::{Event, Sse};
+
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap_or_default();
+    let Some(workflow) = find_workflow(&state, &name).await else {
+        return Json(serde_json::json!({"ok": false, "error": format!("Workflow '{}' not found", name)}))
+            .into_response();
+    };
+    let workflow_id = workflow["id"].as_str().unwrap_or(&name).to_string();
+    let (run_id, cancel) = match start_workflow_run(&state, &body, &workflow_id, &workflow) {
+        Ok(run) => run,
+        Err(e) => return Json(serde_json::json!({"ok": false, "error": e})).into_response(),
+    };
+    let input = body["input"].as_str().unwrap_or("").to_string();
+
+    if params.get("stream").map(String::as_str) != Some("true") {
+        let summary = execute_workflow_run(&state, &run_id, &workflow_id, &workflow, &input, &cancel, |_| {}).await;
+        return Json(summary).into_response();
+    }
+
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Event>();
+    tokio::spawn(async move {
+        let summary = execute_workflow_run(&state, &run_id, &workflow_id, &workflow, &input, &cancel, |step| {
+            let event = Event::default().event("step_completed").data(step.to_string());
+            if tx.send(event).is_err() {
+                cancel.cancel();
+            }
+        })
+        .await;
+        let _ = tx.send(Event::default().event("done").data(summary.to_string()));
+    });
+    let events = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|event| (Ok::<_, std::convert::Infallible>(event), rx))
+    });
+    Sse::new(events).into_response()
+}
+
+/// List in-flight workflow runs.
+pub async fn workflows_runs_list(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let runs = state.workflow_runs.lock().unwrap_or_else(|p| p.into_inner());
+    let list: Vec<serde_json::Value> = runs.iter().map(|(id, run)| serde_json::json!({
+        "run_id": id,
+        "workflow_id": run.workflow_id,
+        "workflow": run.workflow_name,
+        "started_at": run.started_at.to_rfc3339(),
+        "current_step": run.current_step,
+        "total_steps": run.total_steps,
+        "status": if run.cancel.is_cancelled() { "cancelling" } else { "running" },
+    })).collect();
+    Json(serde_json::json!({"ok": true, "runs": list}))
+}
+
+/// Cancel an in-flight workflow run. Remaining steps are skipped.
+pub async fn workflows_cancel_run(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> Json<serde_json::Value> {
+    let runs = state.workflow_runs.lock().unwrap_or_else(|p| p.into_inner());
+    match runs.get(&id) {
+        Some(run) => {
+            run.cancel.cancel();
+            tracing::info!("🚫 Cancel requested for workflow run {} ('{}')", id, run.workflow_name);
+            Json(serde_json::json!({"ok": true, "run_id": id, "status": "cancelled"}))
+        }
+        None => Json(serde_json::json!({"ok": false, "error": format!("Workflow run '{}' not found", id)})),
+    }
 }
 
 // ═══ Skills API ═══
@@ -4205,6 +6212,11 @@ fn installed_skills_path(state: &AppState) -> std::path::PathBuf {
         .join("skills-installed.json")
 }
 
+/// Whether `[features] skills` is on for this gateway.
+fn skills_enabled(state: &AppState) -> bool {
+    state.full_config.lock().unwrap_or_else(|p| p.into_inner()).features.skills
+}
+
 fn load_installed_set(state: &AppState) -> std::collections::HashSet<String> {
     let path = installed_skills_path(state);
     std::fs::read_to_string(&path)
@@ -4225,7 +6237,17 @@ fn save_installed_set(state: &AppState, set: &std::collections::HashSet<String>)
 
 /// List available skills (built-in + user-created).
 pub async fn skills_list(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
-    let installed = load_installed_set(&state);
+    Json(serde_json::json!({"ok": true, "skills": skill_catalog(&state)}))
+}
+
+/// Built-in and user-created skills, each marked `installed`. None are
+/// installed while skills are turned off.
+fn skill_catalog(state: &AppState) -> Vec<serde_json::Value> {
+    let installed = if skills_enabled(state) {
+        load_installed_set(state)
+    } else {
+        Default::default()
+    };
 
     let builtin = vec![
         serde_json::json!({"id":"rust-expert","name":"Rust Expert","icon":"🦀","category":"coding","tags":["rust","systems","performance"],"version":"1.0.0","description":"Rust expert: ownership, async, performance tuning","builtin":true,
@@ -4257,7 +6279,7 @@ pub async fn skills_list(State(state): State<Arc<AppState>>) -> Json<serde_json:
     }).collect();
 
     // Load user-created skills
-    let dir = skills_dir(&state);
+    let dir = skills_dir(state);
     if dir.exists() {
         if let Ok(entries) = std::fs::read_dir(&dir) {
             for entry in entries.flatten() {
@@ -4275,7 +6297,34 @@ pub async fn skills_list(State(state): State<Arc<AppState>>) -> Json<serde_json:
         }
     }
 
-    Json(serde_json::json!({"ok": true, "skills": skills}))
+    skills
+}
+
+/// Conversation starters for `?agent=name` (or the default agent), from
+/// `[suggestions]`. In dynamic mode installed skills add their own.
+pub async fn suggestions_list(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Json<serde_json::Value> {
+    let agent = params.get("agent").filter(|a| !a.is_empty()).map(String::as_str);
+    let (config, locale) = {
+        let cfg = state.full_config.lock().unwrap_or_else(|p| p.into_inner());
+        (cfg.suggestions.clone(), cfg.locale())
+    };
+    let skills: Vec<String> = if config.dynamic {
+        skill_catalog(&state)
+            .iter()
+            .filter(|s| s["installed"].as_bool().unwrap_or(false))
+            .filter_map(|s| s["name"].as_str().map(String::from))
+            .collect()
+    } else {
+        vec![]
+    };
+    Json(serde_json::json!({
+        "ok": true,
+        "agent": agent,
+        "suggestions": config.for_agent(agent, &skills, locale),
+    }))
 }
 
 /// Create a new custom skill.
@@ -4283,6 +6332,9 @@ pub async fn skills_create(
     State(state): State<Arc<AppState>>,
     Json(body): Json<serde_json::Value>,
 ) -> Json<serde_json::Value> {
+    if !skills_enabled(&state) {
+        return Json(serde_json::json!({"ok": false, "error": "Skills are disabled for this agent"}));
+    }
     let name = body["name"].as_str().unwrap_or("Untitled Skill");
     let icon = body["icon"].as_str().unwrap_or("🧩");
     let category = body["category"].as_str().unwrap_or("custom");
@@ -4376,6 +6428,9 @@ pub async fn skills_install(
     if skill_id.is_empty() {
         return Json(serde_json::json!({"ok": false, "error": "skill ID is required"}));
     }
+    if !skills_enabled(&state) {
+        return Json(serde_json::json!({"ok": false, "error": "Skills are disabled for this agent"}));
+    }
     let mut installed = load_installed_set(&state);
     installed.insert(skill_id.to_string());
     save_installed_set(&state, &installed);
@@ -4435,6 +6490,35 @@ fn tools_dir(state: &AppState) -> std::path::PathBuf {
     dir
 }
 
+/// Per-tool usage stats (calls, success/failure split, average duration).
+/// Defaults to the main agent; `?agent=name` reads an orchestrated agent.
+pub async fn tools_usage(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Json<serde_json::Value> {
+    let result = match params.get("agent").filter(|a| !a.is_empty()) {
+        Some(name) => {
+            let mut orch = state.orchestrator.lock().await;
+            match orch.get_agent_mut(name) {
+                Some(agent) => agent.tool_usage().await,
+                None => {
+                    return Json(serde_json::json!({
+                        "ok": false, "error": format!("Agent '{name}' not found")
+                    }));
+                }
+            }
+        }
+        None => match state.agent.lock().await.as_ref() {
+            Some(agent) => agent.tool_usage().await,
+            None => return Json(serde_json::json!({"ok": false, "error": "Agent not available"})),
+        },
+    };
+    match result {
+        Ok(tools) => Json(serde_json::json!({"ok": true, "tools": tools})),
+        Err(e) => Json(serde_json::json!({"ok": false, "error": e.to_string()})),
+    }
+}
+
 /// List all tools (built-in + custom)
 pub async fn tools_list(
     State(state): State<Arc<AppState>>,
@@ -4742,6 +6826,7 @@ pub async fn get_system_metrics(
             "usage_month": monthly,
             "limits": limits,
             "uptime_seconds": uptime_secs,
+            "circuit_breakers": bizclaw_providers::circuit_breaker::states(),
         }
     }))
 }
@@ -7,6 +7,7 @@ use axum::{
     routing::{get, post, put},
 };
 use bizclaw_core::config::{BizClawConfig, GatewayConfig};
+use bizclaw_core::text::truncate_chars;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
@@ -47,6 +48,27 @@ pub struct AppState {
     pub activity_log: Arc<Mutex<Vec<super::openai_compat::ActivityEvent>>>,
     /// Rate limiter — IP → (count, window_start) for public endpoints.
     pub rate_limiter: Arc<tokio::sync::Mutex<std::collections::HashMap<String, (u32, std::time::Instant)>>>,
+    /// In-flight workflow runs — run_id → progress + cancellation token.
+    pub workflow_runs: Arc<Mutex<HashMap<String, WorkflowRun>>>,
+    /// Direct-mode chat sessions — bounded per session and in count.
+    pub sessions: Arc<Mutex<super::sessions::SessionStore>>,
+    /// Outstanding single-use WebSocket tickets.
+    pub ws_tickets: Arc<Mutex<super::ws_auth::TicketStore>>,
+    /// Maintenance switch — while on, chat routes answer 503.
+    pub maintenance: bizclaw_core::maintenance::Maintenance,
+    /// Chat responses cached by `Idempotency-Key`, replayed on client retries.
+    pub idempotency: Arc<super::idempotency::IdempotencyCache>,
+}
+
+/// A workflow run started via `/api/v1/workflows/run`.
+#[derive(Clone)]
+pub struct WorkflowRun {
+    pub workflow_id: String,
+    pub workflow_name: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub current_step: usize,
+    pub total_steps: usize,
+    pub cancel: bizclaw_core::cancel::CancelToken,
 }
 
 /// State for an active Telegram bot connected to an agent.
@@ -168,23 +190,53 @@ async fn require_pairing(
         .unwrap()
 }
 
-/// Rate-limiting middleware for public endpoints.
-/// Allows 60 requests per minute per IP.
-async fn rate_limit(
+/// Settings middleware — refuses the config and provider routes when the
+/// tenant's `[features] settings` is off (sandbox tenants).
+async fn require_settings(
     State(state): State<Arc<AppState>>,
     req: axum::http::Request<axum::body::Body>,
     next: axum::middleware::Next,
 ) -> axum::response::Response {
-    let ip = req.headers()
+    let enabled = state
+        .full_config
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .features
+        .settings;
+    if enabled {
+        return next.run(req).await;
+    }
+    axum::response::Response::builder()
+        .status(axum::http::StatusCode::FORBIDDEN)
+        .header("Content-Type", "application/json")
+        .body(axum::body::Body::from(
+            serde_json::json!({"ok": false, "error": "Settings are disabled for this agent"}).to_string()
+        ))
+        .unwrap()
+}
+
+/// Client IP as reported by the reverse proxy, or "unknown".
+pub(crate) fn client_ip(headers: &axum::http::HeaderMap) -> String {
+    headers
         .get("x-real-ip")
-        .or_else(|| req.headers().get("x-forwarded-for"))
+        .or_else(|| headers.get("x-forwarded-for"))
         .and_then(|v| v.to_str().ok())
         .unwrap_or("unknown")
         .split(',')
         .next()
         .unwrap_or("unknown")
         .trim()
-        .to_string();
+        .to_string()
+}
+
+/// Rate-limiting middleware for public endpoints.
+/// Allows 60 requests per minute per IP.
+async fn rate_limit(
+    State(state): State<Arc<AppState>>,
+    req: axum::http::Request<axum::body::Body>,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let ip = client_ip(req.headers());
 
     {
         let mut limiter = state.rate_limiter.lock().await;
@@ -213,6 +265,25 @@ async fn rate_limit(
     next.run(req).await
 }
 
+/// Maintenance gate — while maintenance mode is on, chat routes answer 503
+/// with the maintenance message instead of reaching the agent.
+async fn maintenance_gate(
+    State(state): State<Arc<AppState>>,
+    req: axum::http::Request<axum::body::Body>,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    match state.maintenance.message() {
+        Some(message) => axum::response::Response::builder()
+            .status(axum::http::StatusCode::SERVICE_UNAVAILABLE)
+            .header("Content-Type", "application/json")
+            .body(axum::body::Body::from(
+                serde_json::json!({"ok": false, "error": message, "maintenance": true}).to_string(),
+            ))
+            .unwrap(),
+        None => next.run(req).await,
+    }
+}
+
 /// Verify pairing code endpoint (public).
 async fn verify_pairing(
     State(state): State<Arc<AppState>>,
@@ -229,7 +300,7 @@ async fn verify_pairing(
 
 /// Constant-time string comparison to prevent timing attacks (M3).
 /// Does NOT short-circuit on length mismatch to avoid leaking length info.
-fn constant_time_eq(a: &str, b: &str) -> bool {
+pub(crate) fn constant_time_eq(a: &str, b: &str) -> bool {
     let len_eq = a.len() == b.len();
     // Always iterate over the longer string to avoid timing differences
     let max_len = a.len().max(b.len());
@@ -271,18 +342,38 @@ pub fn build_router(state: AppState) -> Router {
 
 pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
 
-    // Protected routes — require valid pairing code
-    let protected = Router::new()
-        .route("/api/v1/info", get(super::routes::system_info))
-        .route("/api/v1/config", get(super::routes::get_config))
+    // Settings routes — closed when [features] settings = false
+    let settings = Router::new()
         .route("/api/v1/config/update", post(super::routes::update_config))
         .route("/api/v1/config/full", get(super::routes::get_full_config))
+        .route("/api/v1/config/channels/{kind}", get(super::routes::get_channel_config))
+        .route("/api/v1/config/channels/{kind}", put(super::routes::put_channel_config))
+        .route(
+            "/api/v1/config/channels/{kind}",
+            axum::routing::delete(super::routes::delete_channel_config),
+        )
         .route("/api/v1/providers", get(super::routes::list_providers))
         .route("/api/v1/providers", post(super::routes::create_provider))
         .route("/api/v1/providers/{name}", put(super::routes::update_provider))
         .route("/api/v1/providers/{name}", axum::routing::delete(super::routes::delete_provider))
         .route("/api/v1/providers/{name}/models", get(super::routes::fetch_provider_models))
+        .route_layer(axum::middleware::from_fn_with_state(
+            shared.clone(),
+            require_settings,
+        ));
+
+    // Protected routes — require valid pairing code
+    let protected = Router::new()
+        .route("/api/v1/info", get(super::routes::system_info))
+        .route("/api/v1/config", get(super::routes::get_config))
+        .route("/api/v1/config/schema", get(super::routes::get_config_schema))
+        .merge(settings)
         .route("/api/v1/channels", get(super::routes::list_channels))
+        .route("/api/v1/channels/status", get(super::routes::channel_status))
+        .route(
+            "/api/v1/maintenance",
+            get(super::routes::get_maintenance).put(super::routes::set_maintenance),
+        )
         .route(
             "/api/v1/channels/update",
             post(super::routes::update_channel),
@@ -297,6 +388,10 @@ pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
             get(super::routes::brain_scan_models),
         )
         .route("/api/v1/zalo/qr", post(super::routes::zalo_qr_code))
+        // Workspace file browser (jailed to autonomy.workspace_dir)
+        .route("/api/v1/files", get(super::files::list_files))
+        .route("/api/v1/files/content", get(super::files::read_file_content))
+        .route("/api/v1/files/upload", post(super::files::upload_file))
         // Scheduler API
         .route(
             "/api/v1/scheduler/tasks",
@@ -314,6 +409,10 @@ pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
             "/api/v1/scheduler/tasks/{id}/toggle",
             post(super::routes::scheduler_toggle_task),
         )
+        .route(
+            "/api/v1/scheduler/tasks/{id}/cancel",
+            post(super::routes::scheduler_cancel_task),
+        )
         .route(
             "/api/v1/scheduler/notifications",
             get(super::routes::scheduler_notifications),
@@ -331,6 +430,10 @@ pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
             "/api/v1/knowledge/documents",
             post(super::routes::knowledge_add_doc),
         )
+        .route(
+            "/api/v1/knowledge/documents/url",
+            post(super::routes::knowledge_add_url),
+        )
         .route(
             "/api/v1/knowledge/documents/{id}",
             axum::routing::delete(super::routes::knowledge_remove_doc),
@@ -340,6 +443,8 @@ pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
             "/api/v1/knowledge/upload",
             post(super::routes::knowledge_upload_file),
         )
+        // Bulk JSONL import into knowledge or memory
+        .route("/api/v1/import", post(super::routes::bulk_import))
         // Multi-Agent Orchestrator API
         .route("/api/v1/agents", get(super::routes::list_agents))
         .route("/api/v1/agents", post(super::routes::create_agent))
@@ -364,7 +469,22 @@ pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
         .route("/api/v1/orchestration/links", get(super::routes::orch_list_links).post(super::routes::orch_create_link))
         .route("/api/v1/orchestration/links/{id}", axum::routing::delete(super::routes::orch_delete_link))
         .route("/api/v1/orchestration/delegations", get(super::routes::orch_list_delegations))
+        .route(
+            "/api/v1/orchestration/delegations/{id}/events",
+            get(super::routes::orch_delegation_events),
+        )
         .route("/api/v1/orchestration/traces", get(super::routes::orch_list_traces))
+        .route("/api/v1/orchestration/usage", get(super::routes::orch_usage_summary))
+        // Dead letters — failed channel messages
+        .route("/api/v1/dead-letters", get(super::routes::dead_letters_list))
+        .route(
+            "/api/v1/dead-letters/{id}",
+            axum::routing::delete(super::routes::dead_letters_delete),
+        )
+        .route(
+            "/api/v1/dead-letters/{id}/replay",
+            post(super::routes::dead_letters_replay),
+        )
         // Gallery API
         .route("/api/v1/gallery", get(super::routes::gallery_list))
         .route("/api/v1/gallery", post(super::routes::gallery_create))
@@ -432,6 +552,7 @@ pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
         // Tools CRUD API
         .route("/api/v1/tools", get(super::routes::tools_list))
         .route("/api/v1/tools", post(super::routes::tools_create))
+        .route("/api/v1/tools/usage", get(super::routes::tools_usage))
         .route("/api/v1/tools/{name}/toggle", post(super::routes::tools_toggle))
         .route("/api/v1/tools/{name}", axum::routing::delete(super::routes::tools_delete))
         // MCP Servers API (stub — returns configured MCP servers)
@@ -440,6 +561,11 @@ pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
         .route("/api/v1/workflows", get(super::routes::workflows_list))
         .route("/api/v1/workflows", post(super::routes::workflows_create))
         .route("/api/v1/workflows/run", post(super::routes::workflows_run))
+        .route("/api/v1/workflows/runs", get(super::routes::workflows_runs_list))
+        .route("/api/v1/workflows/runs/{id}/cancel", post(super::routes::workflows_cancel_run))
+        .route("/api/v1/workflows/{name}/run", post(super::routes::workflows_run_named))
+        .route("/api/v1/sessions/{id}/clear", post(super::routes::sessions_clear))
+        .route("/api/v1/conversation/messages/{index}/pin", put(super::routes::conversation_pin))
         .route("/api/v1/workflows/{id}", axum::routing::put(super::routes::workflows_update))
         .route("/api/v1/workflows/{id}", axum::routing::delete(super::routes::workflows_delete))
         .route("/api/v1/workflow-rules", get(super::routes::workflow_rules_list))
@@ -452,6 +578,7 @@ pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
         .route("/api/v1/skills/{id}", get(super::routes::skills_detail))
         .route("/api/v1/skills/{id}", axum::routing::put(super::routes::skills_update))
         .route("/api/v1/skills/{id}", axum::routing::delete(super::routes::skills_delete))
+        .route("/api/v1/suggestions", get(super::routes::suggestions_list))
         .route("/api/v1/tts/voices", get(super::routes::tts_voices))
         // PaaS: API Key Management
         .route("/api/v1/api-keys", get(super::routes::list_api_keys))
@@ -464,12 +591,31 @@ pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
         .route("/api/v1/usage/limits", axum::routing::put(super::routes::update_plan_limits))
         // PaaS: System Metrics
         .route("/api/v1/metrics", get(super::routes::get_system_metrics))
-        .route("/ws", get(super::ws::ws_handler))
+        .route("/api/v1/ws/ticket", post(super::ws_auth::issue_ticket))
         .route_layer(axum::middleware::from_fn_with_state(
             shared.clone(),
             require_pairing,
         ));
 
+    // Chat routes — public with their own auth, closed during maintenance
+    let chat = Router::new()
+        // WebSocket chat — authenticates the upgrade itself (ticket or pairing code)
+        .route("/ws", get(super::ws::ws_handler))
+        // Webhook inbound — public, auth via HMAC signature in header
+        .route("/api/v1/webhook/inbound", post(super::routes::webhook_inbound))
+        // Named generic webhooks — public, auth via X-Webhook-Secret
+        .route("/api/v1/webhook/{name}", post(super::routes::webhook_named))
+        // Xiaozhi webhook — public, auth via header signature
+        .route("/api/v1/xiaozhi/webhook", post(super::routes::xiaozhi_webhook))
+        // OpenAI-Compatible API — public with own auth (Bearer token)
+        .route("/v1/chat/completions", post(super::openai_compat::chat_completions))
+        // llama.cpp-compatible completion API — same auth as the OpenAI API
+        .route("/completion", post(super::completion::completion))
+        .route_layer(axum::middleware::from_fn_with_state(
+            shared.clone(),
+            maintenance_gate,
+        ));
+
     // Public routes — no auth
     let public = Router::new()
         .route("/", get(dashboard_page))
@@ -477,18 +623,14 @@ pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
         .route("/static/dashboard/{*path}", get(dashboard_static))
         .route("/health", get(super::routes::health_check))
         .route("/api/v1/verify-pairing", post(verify_pairing))
-        // WhatsApp webhook — must be public for Meta verification
+        // WhatsApp webhook — must be public for Meta verification.
+        // Not gated: Meta retries on 503, so maintenance is answered in-channel.
         .route(
             "/api/v1/webhook/whatsapp",
             get(super::routes::whatsapp_webhook_verify).post(super::routes::whatsapp_webhook),
         )
-        // Webhook inbound — public, auth via HMAC signature in header
-        .route("/api/v1/webhook/inbound", post(super::routes::webhook_inbound))
-        // Xiaozhi webhook — public, auth via header signature
-        .route("/api/v1/xiaozhi/webhook", post(super::routes::xiaozhi_webhook))
-        // OpenAI-Compatible API — public with own auth (Bearer token)
-        .route("/v1/chat/completions", post(super::openai_compat::chat_completions))
         .route("/v1/models", get(super::openai_compat::list_models))
+        .merge(chat)
         // Rate limiting for all public routes
         .route_layer(axum::middleware::from_fn_with_state(
             shared.clone(),
@@ -535,8 +677,11 @@ pub fn build_router_from_arc(shared: Arc<AppState>) -> Router {
         .with_state(shared)
 }
 
-/// Start the HTTP server.
-pub async fn start(config: &GatewayConfig) -> anyhow::Result<()> {
+/// Start the HTTP server; it stops gracefully when `shutdown` resolves.
+pub async fn start<F>(config: &GatewayConfig, shutdown: F) -> anyhow::Result<()>
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
     // Load full config for settings UI
     let config_path = std::env::var("BIZCLAW_CONFIG")
         .map(PathBuf::from)
@@ -763,7 +908,12 @@ pub async fn start(config: &GatewayConfig) -> anyhow::Result<()> {
     let config_for_sched = full_config.clone();
     let activity_tx_for_sched = activity_tx.clone();
     let db_for_sched = gateway_db.clone();
+    let heartbeat = full_config.features.heartbeat;
     tokio::spawn(async move {
+        if !heartbeat {
+            tracing::info!("⏰ Scheduler disabled by [features] heartbeat = false");
+            return;
+        }
         bizclaw_scheduler::engine::spawn_scheduler_with_agent(
             sched_clone,
             // Agent callback: execute prompt through orchestrator
@@ -787,7 +937,7 @@ pub async fn start(config: &GatewayConfig) -> anyhow::Result<()> {
                         detail: format!(
                             "{}",
                             if response.len() > 150 {
-                                format!("{}...", &response[..150])
+                                format!("{}...", truncate_chars(&response, 150))
                             } else {
                                 response.clone()
                             }
@@ -809,7 +959,7 @@ pub async fn start(config: &GatewayConfig) -> anyhow::Result<()> {
                                     "🤚 *Hand Report: {}*\n\n{}\n\n_— BizClaw Autonomous Hands_",
                                     task_name,
                                     if response.len() > 3500 {
-                                        format!("{}...", &response[..3500])
+                                        format!("{}...", truncate_chars(&response, 3500))
                                     } else {
                                         response.clone()
                                     }
@@ -893,6 +1043,12 @@ pub async fn start(config: &GatewayConfig) -> anyhow::Result<()> {
         activity_tx: activity_tx.clone(),
         activity_log: Arc::new(Mutex::new(Vec::new())),
         rate_limiter: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
+        workflow_runs: Arc::new(Mutex::new(HashMap::new())),
+        sessions: Arc::new(Mutex::new(super::sessions::SessionStore::default())),
+        ws_tickets: Arc::new(Mutex::new(super::ws_auth::TicketStore::default())),
+        // Shared with the channel loops running in this process
+        maintenance: bizclaw_core::maintenance::Maintenance::global().clone(),
+        idempotency: Arc::new(super::idempotency::IdempotencyCache::default()),
     };
 
     let state_arc = Arc::new(state);
@@ -911,7 +1067,12 @@ pub async fn start(config: &GatewayConfig) -> anyhow::Result<()> {
 
     tracing::info!("🌐 Gateway server listening on http://{}", addr);
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    // Write traces still buffered by the orchestrator
+    state_arc.orchestrator.lock().await.shutdown().await;
+    tracing::info!("🌐 Gateway server stopped");
     Ok(())
 }
 
@@ -0,0 +1,203 @@
+//! In-memory conversation sessions for direct-mode chat.
+//!
+//! Each session keeps its recent messages (`{"role", "content"}` JSON, the
+//! format sent to providers) under a message and token cap; the oldest
+//! messages are evicted first. The number of live sessions is capped too —
+//! when a new session would exceed it, the least recently used one is dropped.
+
+use std::collections::{HashMap, VecDeque};
+
+/// Caps applied by a [`SessionStore`].
+#[derive(Debug, Clone, Copy)]
+pub struct SessionLimits {
+    /// Max messages kept per session.
+    pub max_messages: usize,
+    /// Max estimated tokens kept per session (~4 chars per token).
+    pub max_tokens: usize,
+    /// Max live sessions before the least recently used is evicted.
+    pub max_sessions: usize,
+}
+
+impl Default for SessionLimits {
+    fn default() -> Self {
+        Self {
+            max_messages: 40,
+            max_tokens: 8_000,
+            max_sessions: 1_000,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Session {
+    messages: VecDeque<serde_json::Value>,
+    tokens: usize,
+    last_used: u64,
+}
+
+/// Bounded store of conversation sessions keyed by session id.
+#[derive(Debug, Default)]
+pub struct SessionStore {
+    limits: SessionLimits,
+    sessions: HashMap<String, Session>,
+    /// Monotonic use counter for LRU ordering.
+    clock: u64,
+}
+
+fn estimate_tokens(message: &serde_json::Value) -> usize {
+    message["content"]
+        .as_str()
+        .map(|c| c.len() / 4)
+        .unwrap_or(0)
+}
+
+impl SessionStore {
+    pub fn new(limits: SessionLimits) -> Self {
+        Self {
+            limits,
+            ..Default::default()
+        }
+    }
+
+    /// Number of live sessions.
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.sessions.contains_key(id)
+    }
+
+    /// Append a message, evicting the session's oldest messages over the caps.
+    pub fn push(&mut self, id: &str, role: &str, content: &str) {
+        self.clock += 1;
+        if !self.sessions.contains_key(id) {
+            self.evict_lru_if_full();
+        }
+        let limits = self.limits;
+        let session = self.sessions.entry(id.to_string()).or_default();
+        session.last_used = self.clock;
+
+        let message = serde_json::json!({"role": role, "content": content});
+        session.tokens += estimate_tokens(&message);
+        session.messages.push_back(message);
+
+        // Always keep the newest message, even if it alone exceeds the token cap.
+        while session.messages.len() > 1
+            && (session.messages.len() > limits.max_messages || session.tokens > limits.max_tokens)
+        {
+            if let Some(old) = session.messages.pop_front() {
+                session.tokens -= estimate_tokens(&old);
+            }
+        }
+    }
+
+    /// Messages of a session, oldest first (empty for unknown ids).
+    pub fn history(&mut self, id: &str) -> Vec<serde_json::Value> {
+        self.clock += 1;
+        match self.sessions.get_mut(id) {
+            Some(session) => {
+                session.last_used = self.clock;
+                session.messages.iter().cloned().collect()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Empty a session, returning how many messages were removed.
+    pub fn clear(&mut self, id: &str) -> usize {
+        match self.sessions.get_mut(id) {
+            Some(session) => {
+                let removed = session.messages.len();
+                session.messages.clear();
+                session.tokens = 0;
+                removed
+            }
+            None => 0,
+        }
+    }
+
+    /// Drop a session entirely (e.g. when its connection closes).
+    pub fn remove(&mut self, id: &str) -> bool {
+        self.sessions.remove(id).is_some()
+    }
+
+    fn evict_lru_if_full(&mut self) {
+        while self.sessions.len() >= self.limits.max_sessions.max(1) {
+            let Some(oldest) = self
+                .sessions
+                .iter()
+                .min_by_key(|(_, s)| s.last_used)
+                .map(|(id, _)| id.clone())
+            else {
+                break;
+            };
+            tracing::debug!("Evicting idle session {oldest}");
+            self.sessions.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(max_messages: usize, max_tokens: usize, max_sessions: usize) -> SessionStore {
+        SessionStore::new(SessionLimits {
+            max_messages,
+            max_tokens,
+            max_sessions,
+        })
+    }
+
+    #[test]
+    fn test_message_cap_evicts_oldest() {
+        let mut s = store(3, 10_000, 10);
+        for i in 0..5 {
+            s.push("a", "user", &format!("m{i}"));
+        }
+        let contents: Vec<String> = s
+            .history("a")
+            .iter()
+            .map(|m| m["content"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(contents, vec!["m2", "m3", "m4"]);
+    }
+
+    #[test]
+    fn test_token_cap_evicts_oldest() {
+        let mut s = store(100, 10, 10);
+        s.push("a", "user", &"x".repeat(24)); // ~6 tokens
+        s.push("a", "assistant", &"y".repeat(24)); // ~6 tokens → over cap
+        let history = s.history("a");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0]["role"], "assistant");
+    }
+
+    #[test]
+    fn test_session_cap_evicts_least_recently_used() {
+        let mut s = store(10, 10_000, 2);
+        s.push("a", "user", "hi");
+        s.push("b", "user", "hi");
+        s.history("a"); // touch a → b is now least recently used
+        s.push("c", "user", "hi");
+        assert_eq!(s.len(), 2);
+        assert!(s.contains("a"));
+        assert!(!s.contains("b"));
+        assert!(s.contains("c"));
+    }
+
+    #[test]
+    fn test_clear_empties_session() {
+        let mut s = store(10, 10_000, 10);
+        s.push("a", "user", "hello");
+        s.push("a", "assistant", "hi there");
+        assert_eq!(s.clear("a"), 2);
+        assert!(s.history("a").is_empty());
+        assert_eq!(s.clear("missing"), 0);
+    }
+}
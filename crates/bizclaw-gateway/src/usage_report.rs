@@ -0,0 +1,133 @@
+//! LLM usage reporting to the multi-tenant platform.
+//!
+//! When the platform starts a tenant it passes `BIZCLAW_PLATFORM_URL`,
+//! `BIZCLAW_TENANT_ID` and `BIZCLAW_USAGE_KEY`. Each recorded LLM trace is
+//! then POSTed (fire-and-forget) to the admin server so per-tenant usage
+//! shows up on the platform dashboard. Standalone gateways report nothing.
+
+use std::sync::OnceLock;
+
+use super::openai_compat::LlmTrace;
+
+/// Reports LLM traces to `POST {platform}/api/internal/tenants/{id}/usage`.
+#[derive(Debug, Clone)]
+pub struct UsageReporter {
+    endpoint: String,
+    key: String,
+    client: reqwest::Client,
+}
+
+impl UsageReporter {
+    pub fn new(platform_url: &str, tenant_id: &str, key: &str) -> Self {
+        Self {
+            endpoint: format!(
+                "{}/api/internal/tenants/{tenant_id}/usage",
+                platform_url.trim_end_matches('/')
+            ),
+            key: key.to_string(),
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Build a reporter from the environment set by the platform, if any.
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("BIZCLAW_PLATFORM_URL").ok()?;
+        let tenant_id = std::env::var("BIZCLAW_TENANT_ID").ok()?;
+        let key = std::env::var("BIZCLAW_USAGE_KEY").ok()?;
+        if url.is_empty() || tenant_id.is_empty() || key.is_empty() {
+            return None;
+        }
+        Some(Self::new(&url, &tenant_id, &key))
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Send one trace in the background. Failures are logged, never surfaced.
+    pub fn report(&self, agent_name: &str, trace: &LlmTrace) {
+        let body = serde_json::json!({ "records": [usage_record(agent_name, trace)] });
+        let request = self
+            .client
+            .post(&self.endpoint)
+            .header("X-Usage-Key", &self.key)
+            .json(&body);
+        tokio::spawn(async move {
+            match request.send().await {
+                Ok(resp) if !resp.status().is_success() => {
+                    tracing::debug!("Usage report rejected: HTTP {}", resp.status());
+                }
+                Err(e) => tracing::debug!("Usage report failed: {e}"),
+                Ok(_) => {}
+            }
+        });
+    }
+}
+
+/// The platform's usage record for a trace.
+pub fn usage_record(agent_name: &str, trace: &LlmTrace) -> serde_json::Value {
+    serde_json::json!({
+        "agent_name": agent_name,
+        "provider": trace.provider,
+        "model": trace.model,
+        "prompt_tokens": trace.prompt_tokens,
+        "completion_tokens": trace.completion_tokens,
+        "cost_usd": trace.cost_usd,
+        "created_at": trace.timestamp.to_rfc3339(),
+        "status": trace.status,
+    })
+}
+
+/// Report a trace with the process-wide reporter (no-op outside the platform).
+pub fn report_trace(agent_name: &str, trace: &LlmTrace) {
+    static REPORTER: OnceLock<Option<UsageReporter>> = OnceLock::new();
+    if let Some(reporter) = REPORTER.get_or_init(UsageReporter::from_env) {
+        reporter.report(agent_name, trace);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_record_from_trace() {
+        let trace = LlmTrace {
+            id: "t1".into(),
+            timestamp: chrono::Utc::now(),
+            model: "gpt-4o-mini".into(),
+            provider: "openai".into(),
+            prompt_tokens: 120,
+            completion_tokens: 30,
+            total_tokens: 150,
+            latency_ms: 800,
+            cost_usd: 0.0001,
+            cache_hit: false,
+            status: "ok".into(),
+            tool_calls: 0,
+            error: None,
+            reasoning: None,
+            fallback_from: None,
+        };
+        let record = usage_record("support", &trace);
+        assert_eq!(record["agent_name"], "support");
+        assert_eq!(record["prompt_tokens"], 120);
+        assert_eq!(record["completion_tokens"], 30);
+        assert_eq!(record["model"], "gpt-4o-mini");
+        assert_eq!(record["status"], "ok");
+
+        // A turn the local brain answered is reported under it.
+        let trace = trace.with_fallback(Some(("brain".into(), "openai".into())));
+        assert_eq!(usage_record("support", &trace)["provider"], "brain");
+        assert_eq!(trace.fallback_from.as_deref(), Some("openai"));
+
+        let reporter = UsageReporter::new("http://127.0.0.1:3000/", "tenant-1", "k");
+        assert_eq!(
+            reporter.endpoint(),
+            "http://127.0.0.1:3000/api/internal/tenants/tenant-1/usage"
+        );
+    }
+}
@@ -9,24 +9,66 @@
 //! → Client sends: {"type":"chat","content":"...","stream":true}
 //! ← Server sends: {"type":"chat_start","request_id":"..."}
 //! ← Server sends: {"type":"chat_chunk","request_id":"...","content":"token","index":0}
+//! ← Server sends: {"type":"tool_output","request_id":"...","tool":"shell","content":"..."}
 //! ← Server sends: {"type":"chat_done","request_id":"...","total_tokens":42}
+//! → Client sends: {"type":"pin","index":3} / {"type":"unpin","index":3}
+//! ← Server sends: {"type":"pinned","index":3,"pinned":true}
 
+use super::openai_compat::{self, LlmTrace};
 use super::server::AppState;
+use super::ws_auth::{self, WsScope};
 use axum::{
+    Json,
     extract::{
-        State,
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade, rejection::WebSocketUpgradeRejection},
     },
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 
-/// WebSocket upgrade handler.
+/// WebSocket upgrade handler. Authenticates before upgrading (see
+/// [`ws_auth`]); unauthenticated upgrades get a 401 and no socket.
 pub async fn ws_handler(
-    ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+    ws: Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Response {
+    // Same brute-force lockout as the pairing-code middleware.
+    {
+        let failures = state.auth_failures.lock().await;
+        if failures.0 >= 5 && failures.1.elapsed().as_secs() < 60 {
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [("Retry-After", "60")],
+                Json(serde_json::json!({"ok": false, "error": "Too many failed attempts. Try again in 60 seconds."})),
+            )
+                .into_response();
+        }
+    }
+    let scope = match ws_auth::authorize(&state, &headers, &query) {
+        Ok(scope) => scope,
+        Err(status) => {
+            let mut failures = state.auth_failures.lock().await;
+            failures.0 += 1;
+            failures.1 = std::time::Instant::now();
+            tracing::warn!("[security] Rejected WebSocket upgrade #{} — bad or missing credentials", failures.0);
+            return (
+                status,
+                Json(serde_json::json!({"ok": false, "error": "Unauthorized — invalid or missing ticket or pairing code"})),
+            )
+                .into_response();
+        }
+    };
+    match ws {
+        Ok(ws) => ws
+            .on_upgrade(move |socket| handle_socket(socket, state, scope))
+            .into_response(),
+        Err(rejection) => rejection.into_response(),
+    }
 }
 
 /// Resolve Ollama URL from config or env.
@@ -60,8 +102,12 @@ fn active_provider(state: &AppState) -> String {
 }
 
 /// Handle a WebSocket connection.
-async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
-    tracing::info!("WebSocket client connected");
+async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>, scope: WsScope) {
+    tracing::info!(
+        "WebSocket client connected (tenant={}, session={})",
+        scope.tenant,
+        scope.session_id
+    );
 
     let provider = active_provider(&state);
     let model = active_model(&state);
@@ -80,6 +126,7 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
         "provider": &provider,
         "model": &model,
         "agent_engine": has_agent_initial,
+        "session_id": &scope.session_id,
         "capabilities": if has_agent_initial {
             vec!["chat", "stream", "ping", "tools", "memory"]
         } else {
@@ -97,10 +144,9 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
     }
 
     let mut request_counter: u64 = 0;
-    // Fallback history for direct mode (when Agent engine is not available)
-    let mut fallback_history: Vec<serde_json::Value> = vec![
-        serde_json::json!({"role": "system", "content": "Bạn là BizClaw AI Assistant. Trả lời ngắn gọn, hữu ích bằng tiếng Việt. Nếu user nói tiếng Anh thì trả lời tiếng Anh."}),
-    ];
+    // Direct-mode history lives in the shared session store (bounded, clearable).
+    // Clients may pass "session_id" to resume unless a ticket pinned the session.
+    let fallback_system = serde_json::json!({"role": "system", "content": "Bạn là BizClaw AI Assistant. Trả lời ngắn gọn, hữu ích bằng tiếng Việt. Nếu user nói tiếng Anh thì trả lời tiếng Anh."});
 
     // Message loop
     while let Some(msg) = socket.recv().await {
@@ -129,6 +175,15 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
                             send_error(&mut socket, "Empty message").await;
                             continue;
                         }
+                        if !ws_auth::allow_chat(&state, &scope).await {
+                            send_error(&mut socket, "Rate limit exceeded. Please slow down.").await;
+                            continue;
+                        }
+                        // Sockets opened before maintenance began are refused per message
+                        if let Some(message) = state.maintenance.message() {
+                            send_error(&mut socket, &message).await;
+                            continue;
+                        }
 
                         // Re-read provider/model from config each request (may have changed)
                         let provider = active_provider(&state);
@@ -270,25 +325,65 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
                             )
                             .await;
 
+                            let start = std::time::Instant::now();
                             let result = {
                                 let mut agent = state.agent.lock().await;
                                 if let Some(agent) = agent.as_mut() {
                                     // Connect knowledge base for RAG
                                     agent.set_knowledge(state.knowledge.clone());
-                                    Some(agent.process(&content).await)
+                                    if stream {
+                                        // Forward tool output live while the turn runs
+                                        let (tx, mut rx) =
+                                            tokio::sync::mpsc::unbounded_channel::<(String, String)>();
+                                        let no_text = |_: &str| {};
+                                        let on_tool = move |tool: &str, chunk: &str| {
+                                            let _ = tx.send((tool.to_string(), chunk.to_string()));
+                                        };
+                                        let work = agent.process_stream_with_tools(
+                                            &content, &no_text, &on_tool,
+                                        );
+                                        tokio::pin!(work);
+                                        let result = loop {
+                                            tokio::select! {
+                                                r = &mut work => break r,
+                                                Some((tool, chunk)) = rx.recv() => {
+                                                    send_tool_output(&mut socket, &request_id, &tool, &chunk).await;
+                                                }
+                                            }
+                                        };
+                                        while let Ok((tool, chunk)) = rx.try_recv() {
+                                            send_tool_output(&mut socket, &request_id, &tool, &chunk)
+                                                .await;
+                                        }
+                                        Some(result)
+                                    } else {
+                                        Some(agent.process(&content).await)
+                                    }
                                 } else {
                                     None
                                 }
                             };
 
                             // Get context stats after processing
-                            let ctx_stats = {
+                            let (ctx_stats, fallback) = {
                                 let agent = state.agent.lock().await;
-                                agent.as_ref().map(|a| a.context_stats().clone())
+                                (
+                                    agent.as_ref().map(|a| a.context_stats().clone()),
+                                    agent.as_ref().and_then(openai_compat::fallback_of),
+                                )
                             };
 
                             match result {
                                 Some(Ok(response)) => {
+                                    record_agent_trace(
+                                        &state,
+                                        &provider,
+                                        &model,
+                                        &content,
+                                        &response,
+                                        start.elapsed(),
+                                        fallback,
+                                    );
                                     if stream {
                                         // Emit as rapid chunks for streaming UX
                                         let chunk_size = 8; // chars per chunk
@@ -346,12 +441,20 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
                                     }
                                 }
                                 Some(Err(e)) => {
+                                    tracing::error!("WS agent error ({}): {e}", e.kind.code());
+                                    let locale = state
+                                        .full_config
+                                        .lock()
+                                        .unwrap_or_else(|p| p.into_inner())
+                                        .locale();
                                     let _ = send_json(
                                         &mut socket,
                                         &serde_json::json!({
                                             "type": "chat_error",
                                             "request_id": &request_id,
-                                            "error": e.to_string(),
+                                            "error": e.user_message(locale),
+                                            "code": e.kind.code(),
+                                            "retryable": e.is_retryable(),
                                         }),
                                     )
                                     .await;
@@ -364,19 +467,16 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
                             // ═══════════════════════════════════════════
                             // STREAMING / DIRECT MODE
                             // ═══════════════════════════════════════════
-                            // Add user message to fallback history
-                            fallback_history
-                                .push(serde_json::json!({"role": "user", "content": &content}));
-
-                            // Keep history manageable (last 20 messages + system)
-                            if fallback_history.len() > 21 {
-                                let system = fallback_history[0].clone();
-                                let skip = fallback_history.len() - 20;
-                                let tail: Vec<_> = fallback_history.drain(skip..).collect();
-                                fallback_history.clear();
-                                fallback_history.push(system);
-                                fallback_history.extend(tail);
-                            }
+                            let session_id =
+                                scope.session_for(json["session_id"].as_str()).to_string();
+                            let fallback_history = {
+                                let mut sessions =
+                                    state.sessions.lock().unwrap_or_else(|p| p.into_inner());
+                                sessions.push(&session_id, "user", &content);
+                                let mut history = vec![fallback_system.clone()];
+                                history.extend(sessions.history(&session_id));
+                                history
+                            };
 
                             // Route to provider
                             let result = match provider.as_str() {
@@ -431,8 +531,12 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
 
                             match result {
                                 Ok(response) => {
-                                    // Add assistant response to fallback history
-                                    fallback_history.push(serde_json::json!({"role": "assistant", "content": &response}));
+                                    // Add assistant response to the session
+                                    state
+                                        .sessions
+                                        .lock()
+                                        .unwrap_or_else(|p| p.into_inner())
+                                        .push(&session_id, "assistant", &response);
 
                                     // Save to Agent memory if any agent exists (memory is provider-agnostic)
                                     {
@@ -494,6 +598,39 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
                         let _ = send_json(&mut socket, &status).await;
                     }
 
+                    "pin" | "unpin" => {
+                        // {"type":"pin","index":3}; without an index, the latest user message
+                        let pinned = msg_type == "pin";
+                        let result = {
+                            let mut agent = state.agent.lock().await;
+                            match (agent.as_mut(), json["index"].as_u64()) {
+                                (None, _) => Err("Agent not available".to_string()),
+                                (Some(agent), Some(index)) => agent
+                                    .set_pinned(index as usize, pinned)
+                                    .map(|()| index as usize)
+                                    .map_err(|e| e.to_string()),
+                                (Some(agent), None) if pinned => agent
+                                    .pin_last_user_message()
+                                    .ok_or_else(|| "No message to pin".to_string()),
+                                (Some(_), None) => Err("Missing message index".to_string()),
+                            }
+                        };
+                        match result {
+                            Ok(index) => {
+                                let _ = send_json(
+                                    &mut socket,
+                                    &serde_json::json!({
+                                        "type": "pinned",
+                                        "index": index,
+                                        "pinned": pinned,
+                                    }),
+                                )
+                                .await;
+                            }
+                            Err(e) => send_error(&mut socket, &e).await,
+                        }
+                    }
+
                     _ => {
                         send_error(&mut socket, &format!("Unknown message type: {msg_type}")).await;
                     }
@@ -514,6 +651,15 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
         }
     }
 
+    // A ticket's session may be resumed with a later ticket; keep it.
+    if !scope.pinned {
+        state
+            .sessions
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .remove(&scope.session_id);
+    }
+
     tracing::info!("WebSocket connection closed (total requests: {request_counter})");
 }
 
@@ -798,6 +944,53 @@ async fn send_json(socket: &mut WebSocket, value: &serde_json::Value) -> Result<
         })
 }
 
+/// Send a chunk of live tool output.
+async fn send_tool_output(socket: &mut WebSocket, request_id: &str, tool: &str, chunk: &str) {
+    let _ = send_json(
+        socket,
+        &serde_json::json!({
+            "type": "tool_output",
+            "request_id": request_id,
+            "tool": tool,
+            "content": chunk,
+        }),
+    )
+    .await;
+}
+
+/// Trace an agent-mode turn, attributed to the local fallback if one answered.
+fn record_agent_trace(
+    state: &AppState,
+    provider: &str,
+    model: &str,
+    prompt: &str,
+    response: &str,
+    elapsed: std::time::Duration,
+    fallback: Option<(String, String)>,
+) {
+    let prompt_tokens = (prompt.len() / 4) as u32;
+    let completion_tokens = (response.len() / 4) as u32;
+    let trace = LlmTrace {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: chrono::Utc::now(),
+        model: model.to_string(),
+        provider: provider.to_string(),
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens + completion_tokens,
+        latency_ms: elapsed.as_millis() as u64,
+        cost_usd: openai_compat::estimate_cost(model, prompt_tokens, completion_tokens),
+        cache_hit: false,
+        status: "ok".into(),
+        tool_calls: 0,
+        error: None,
+        reasoning: None,
+        fallback_from: None,
+    }
+    .with_fallback(fallback);
+    openai_compat::record_trace(state, "default", trace);
+}
+
 async fn send_error(socket: &mut WebSocket, message: &str) {
     let error = serde_json::json!({
         "type": "error",
@@ -0,0 +1,215 @@
+//! WebSocket authentication and per-connection scope.
+//!
+//! Browsers cannot set headers on a WebSocket upgrade, and a pairing code in
+//! the URL ends up in proxy logs. So a client first calls
+//! `POST /api/v1/ws/ticket` (pairing-code protected) and connects with
+//! `/ws?ticket=…`. Tickets are single-use, expire after [`TICKET_TTL`] and
+//! pin the connection to one chat session. The pairing code itself
+//! (`X-Pairing-Code` header or `?code=`) is still accepted and leaves the
+//! session up to the client.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+
+use super::server::AppState;
+
+/// How long an unused ticket stays valid.
+pub const TICKET_TTL: Duration = Duration::from_secs(60);
+
+/// Chat messages one client may send per minute, across all its connections.
+pub const MAX_CHATS_PER_MINUTE: u32 = 30;
+
+/// Who a WebSocket connection acts as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WsScope {
+    /// Tenant this gateway serves (`BIZCLAW_TENANT_ID`), or "local".
+    pub tenant: String,
+    /// Chat session the connection is bound to.
+    pub session_id: String,
+    /// Whether the session came from a ticket. Pinned connections ignore a
+    /// client-supplied `session_id`.
+    pub pinned: bool,
+    /// Client IP the connection came from.
+    pub client: String,
+}
+
+impl WsScope {
+    fn new(session_id: String, pinned: bool, client: String) -> Self {
+        Self {
+            tenant: std::env::var("BIZCLAW_TENANT_ID")
+                .ok()
+                .filter(|t| !t.is_empty())
+                .unwrap_or_else(|| "local".into()),
+            session_id,
+            pinned,
+            client,
+        }
+    }
+
+    /// Session for one message: the pinned one, else the client's choice.
+    pub fn session_for<'a>(&'a self, requested: Option<&'a str>) -> &'a str {
+        match requested.filter(|s| !s.is_empty()) {
+            Some(requested) if !self.pinned => requested,
+            _ => &self.session_id,
+        }
+    }
+
+    /// Key for this scope in the gateway's rate limiter. Keyed on the client,
+    /// not the session, so reconnecting does not reset the limit.
+    pub fn rate_key(&self) -> String {
+        format!("ws:{}:{}", self.tenant, self.client)
+    }
+}
+
+/// Outstanding WebSocket tickets.
+#[derive(Debug, Default)]
+pub struct TicketStore {
+    tickets: HashMap<String, (String, Instant)>,
+}
+
+impl TicketStore {
+    /// Issue a ticket for `session_id`.
+    pub fn issue(&mut self, session_id: &str) -> String {
+        self.tickets
+            .retain(|_, (_, issued)| issued.elapsed() < TICKET_TTL);
+        let ticket = uuid::Uuid::new_v4().simple().to_string();
+        self.tickets
+            .insert(ticket.clone(), (session_id.to_string(), Instant::now()));
+        ticket
+    }
+
+    /// Consume `ticket`, returning its session if it was valid.
+    pub fn redeem(&mut self, ticket: &str) -> Option<String> {
+        let (session_id, issued) = self.tickets.remove(ticket)?;
+        (issued.elapsed() < TICKET_TTL).then_some(session_id)
+    }
+}
+
+/// Authorize a WebSocket upgrade from its headers and query string.
+pub fn authorize(
+    state: &AppState,
+    headers: &HeaderMap,
+    query: &HashMap<String, String>,
+) -> Result<WsScope, StatusCode> {
+    let new_session = || format!("ws_{}", uuid::Uuid::new_v4());
+    let client = super::server::client_ip(headers);
+
+    if let Some(ticket) = query.get("ticket") {
+        let mut tickets = state.ws_tickets.lock().unwrap_or_else(|p| p.into_inner());
+        return match tickets.redeem(ticket) {
+            Some(session_id) => Ok(WsScope::new(session_id, true, client)),
+            None => Err(StatusCode::UNAUTHORIZED),
+        };
+    }
+
+    let expected = state
+        .pairing_code
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .clone();
+    // No pairing code configured: the gateway is open, as for HTTP routes.
+    if expected.is_empty() {
+        return Ok(WsScope::new(new_session(), false, client));
+    }
+    let code = headers
+        .get("X-Pairing-Code")
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| query.get("code").map(String::as_str))
+        .unwrap_or("");
+    if super::server::constant_time_eq(code, &expected) {
+        Ok(WsScope::new(new_session(), false, client))
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// Count one chat message against `scope`'s per-minute limit.
+pub async fn allow_chat(state: &AppState, scope: &WsScope) -> bool {
+    let mut limiter = state.rate_limiter.lock().await;
+    count_chat(&mut limiter, &scope.rate_key())
+}
+
+/// Count one message for `key`, dropping windows that have expired so
+/// clients that went away don't pile up.
+fn count_chat(limiter: &mut HashMap<String, (u32, Instant)>, key: &str) -> bool {
+    let window = Duration::from_secs(60);
+    limiter.retain(|_, (_, start)| start.elapsed() < window);
+    let entry = limiter
+        .entry(key.to_string())
+        .or_insert_with(|| (0, Instant::now()));
+    entry.0 += 1;
+    entry.0 <= MAX_CHATS_PER_MINUTE
+}
+
+/// POST /api/v1/ws/ticket — issue a single-use WebSocket ticket.
+/// Body (optional): `{"session_id": "..."}` to resume a session.
+pub async fn issue_ticket(
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<serde_json::Value>>,
+) -> Json<serde_json::Value> {
+    let session_id = body
+        .as_ref()
+        .and_then(|b| b["session_id"].as_str())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .unwrap_or_else(|| format!("ws_{}", uuid::Uuid::new_v4()));
+    let ticket = state
+        .ws_tickets
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .issue(&session_id);
+    Json(serde_json::json!({
+        "ok": true,
+        "ticket": ticket,
+        "session_id": session_id,
+        "expires_in": TICKET_TTL.as_secs(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticket_is_single_use() {
+        let mut store = TicketStore::default();
+        let ticket = store.issue("s1");
+        assert_eq!(store.redeem(&ticket).as_deref(), Some("s1"));
+        assert_eq!(store.redeem(&ticket), None);
+        assert_eq!(store.redeem("made-up"), None);
+    }
+
+    #[test]
+    fn test_pinned_scope_ignores_client_session() {
+        let pinned = WsScope::new("s1".into(), true, "10.0.0.1".into());
+        assert_eq!(pinned.session_for(Some("other")), "s1");
+        let open = WsScope::new("s1".into(), false, "10.0.0.1".into());
+        assert_eq!(open.session_for(Some("other")), "other");
+        assert_eq!(open.session_for(Some("")), "s1");
+        assert_eq!(open.session_for(None), "s1");
+    }
+
+    #[test]
+    fn test_reconnecting_does_not_reset_the_limit() {
+        let mut limiter = HashMap::new();
+        let first = WsScope::new("s1".into(), false, "10.0.0.1".into());
+        let second = WsScope::new("s2".into(), false, "10.0.0.1".into());
+        assert_eq!(first.rate_key(), second.rate_key());
+        for _ in 0..MAX_CHATS_PER_MINUTE {
+            assert!(count_chat(&mut limiter, &first.rate_key()));
+        }
+        assert!(!count_chat(&mut limiter, &second.rate_key()));
+
+        // Expired windows are evicted.
+        let stale = Instant::now() - Duration::from_secs(61);
+        limiter.insert("ws:local:10.0.0.2".into(), (3, stale));
+        let other = WsScope::new("s3".into(), false, "10.0.0.3".into());
+        assert!(count_chat(&mut limiter, &other.rate_key()));
+        assert!(!limiter.contains_key("ws:local:10.0.0.2"));
+    }
+}
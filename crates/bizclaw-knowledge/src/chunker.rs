@@ -1,10 +1,137 @@
 //! Document chunker — splits documents into search-friendly chunks.
 //! Designed for minimal memory: processes line-by-line, never loads full doc.
+//!
+//! [`split`] picks the splitter from [`ChunkOptions`]: paragraphs (the
+//! default), fixed word windows, sentences or markdown headings. With an
+//! overlap, each chunk starts with the last words of the one before, so a
+//! fact cut at a boundary is still found whole in one of them.
+
+use serde::{Deserialize, Serialize};
+
+/// Smallest chunk size accepted, in characters.
+pub const MIN_CHUNK_SIZE: usize = 100;
+
+/// How a document is split into chunks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitMode {
+    /// Paragraph and line breaks, falling back to words for long lines.
+    #[default]
+    Paragraphs,
+    /// Fixed windows of whole words, ignoring the layout.
+    Tokens,
+    /// Whole sentences, falling back to words for long ones.
+    Sentences,
+    /// One section per markdown heading; each chunk of a long section
+    /// repeats its heading.
+    Headings,
+}
+
+impl SplitMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Paragraphs => "paragraphs",
+            Self::Tokens => "tokens",
+            Self::Sentences => "sentences",
+            Self::Headings => "headings",
+        }
+    }
+}
+
+impl std::str::FromStr for SplitMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "paragraphs" | "paragraph" => Ok(Self::Paragraphs),
+            "tokens" | "token" | "words" => Ok(Self::Tokens),
+            "sentences" | "sentence" => Ok(Self::Sentences),
+            "headings" | "heading" | "markdown" => Ok(Self::Headings),
+            other => Err(format!(
+                "unknown split mode '{other}' (use paragraphs, tokens, sentences or headings)"
+            )),
+        }
+    }
+}
+
+/// Chunking parameters, stored with each document so it can be reindexed
+/// the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChunkOptions {
+    pub mode: SplitMode,
+    /// Max characters per chunk, overlap included (min [`MIN_CHUNK_SIZE`]).
+    pub size: usize,
+    /// Characters repeated from the end of the previous chunk (at most
+    /// half of `size`).
+    pub overlap: usize,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self {
+            mode: SplitMode::Paragraphs,
+            size: 500,
+            overlap: 50,
+        }
+    }
+}
+
+/// Split `text` into chunks as set by `options`.
+pub fn split(text: &str, options: &ChunkOptions) -> Vec<String> {
+    let size = options.size.max(MIN_CHUNK_SIZE);
+    let overlap = options.overlap.min(size / 2);
+    let budget = size - overlap;
+    match options.mode {
+        SplitMode::Paragraphs => with_overlap(chunk_lines(text, budget), overlap),
+        SplitMode::Tokens => with_overlap(pack(text.split_whitespace(), budget), overlap),
+        SplitMode::Sentences => with_overlap(pack(sentences(text), budget), overlap),
+        SplitMode::Headings => {
+            let mut chunks = Vec::new();
+            for (heading, body) in sections(text) {
+                // Overlap stays within the section; the heading is repeated instead.
+                let heading_len = heading.map_or(0, |h| h.len() + 1);
+                let body_budget = budget.saturating_sub(heading_len);
+                for chunk in with_overlap(chunk_lines(&body, body_budget), overlap) {
+                    chunks.push(match heading {
+                        Some(h) => format!("{h}\n{chunk}"),
+                        None => chunk,
+                    });
+                }
+            }
+            chunks
+        }
+    }
+}
+
+/// Chunk a raw document: extract its text (see [`extract_text`]) and split
+/// it. Markdown split by headings is split first, while the `#` markers
+/// are still there.
+pub fn chunk_document(content: &str, filename: &str, options: &ChunkOptions) -> Vec<String> {
+    if options.mode == SplitMode::Headings && is_markdown(filename) {
+        split(content, options)
+            .iter()
+            .map(|chunk| extract_text(chunk, filename))
+            .collect()
+    } else {
+        split(&extract_text(content, filename), options)
+    }
+}
+
+fn is_markdown(filename: &str) -> bool {
+    let ext = filename.rsplit('.').next().unwrap_or("").to_lowercase();
+    matches!(ext.as_str(), "md" | "markdown")
+}
 
 /// Split text into chunks of approximately `max_chars` characters.
 /// Breaks at paragraph boundaries and word boundaries.
 pub fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
-    let max_chars = max_chars.max(100); // Min 100 chars
+    chunk_lines(text, max_chars.max(100)) // Min 100 chars
+}
+
+/// [`chunk_text`] without the minimum, for [`split`], which has already
+/// taken the overlap and heading out of the chunk size.
+fn chunk_lines(text: &str, max_chars: usize) -> Vec<String> {
     let mut chunks = Vec::new();
     let mut current = String::new();
 
@@ -55,8 +182,128 @@ pub fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
     chunks
 }
 
+/// Pack `pieces` into chunks of at most `max_chars`, joined by spaces.
+/// Pieces longer than that are split by words.
+fn pack<'a>(pieces: impl IntoIterator<Item = &'a str>, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut push = |piece: &str, current: &mut String| {
+        if !current.is_empty() && current.len() + piece.len() + 1 > max_chars {
+            chunks.push(std::mem::take(current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(piece);
+    };
+    for piece in pieces {
+        if piece.len() > max_chars {
+            for word in piece.split_whitespace() {
+                push(word, &mut current);
+            }
+        } else {
+            push(piece, &mut current);
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Sentences of `text`: ended by `.`, `!`, `?` or `…` before whitespace,
+/// or by a line break.
+fn sentences(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    for line in text.lines() {
+        let mut start = 0;
+        let mut chars = line.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let at_break = chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+            if matches!(c, '.' | '!' | '?' | '…') && at_break {
+                let end = i + c.len_utf8();
+                out.push(line[start..end].trim());
+                start = end;
+            }
+        }
+        out.push(line[start..].trim());
+    }
+    out.retain(|s| !s.is_empty());
+    out
+}
+
+/// Split markdown into `(heading line, body)` sections. Text before the
+/// first heading has no heading; sections with no body are dropped.
+fn sections(text: &str) -> Vec<(Option<&str>, String)> {
+    let mut out: Vec<(Option<&str>, String)> = Vec::new();
+    let mut heading = None;
+    let mut body = String::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if is_heading(trimmed) {
+            if !body.trim().is_empty() {
+                out.push((heading, body.trim().to_string()));
+            }
+            body.clear();
+            heading = Some(trimmed);
+            continue;
+        }
+        body.push_str(line);
+        body.push('\n');
+    }
+    if !body.trim().is_empty() {
+        out.push((heading, body.trim().to_string()));
+    }
+    out
+}
+
+/// `# Title` through `###### Title`.
+fn is_heading(line: &str) -> bool {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    (1..=6).contains(&level) && line[level..].starts_with(' ')
+}
+
+/// Prefix each chunk after the first with the last words of the one before,
+/// at most `overlap` characters including the joining space.
+fn with_overlap(chunks: Vec<String>, overlap: usize) -> Vec<String> {
+    if overlap == 0 || chunks.len() < 2 {
+        return chunks;
+    }
+    let mut out = Vec::with_capacity(chunks.len());
+    for (i, chunk) in chunks.iter().enumerate() {
+        let tail = match i {
+            0 => "",
+            _ => tail_words(&chunks[i - 1], overlap - 1),
+        };
+        out.push(if tail.is_empty() {
+            chunk.clone()
+        } else {
+            format!("{tail} {chunk}")
+        });
+    }
+    out
+}
+
+/// The whole words in the last `max_chars` of `text`.
+fn tail_words(text: &str, max_chars: usize) -> &str {
+    if text.len() <= max_chars {
+        return text.trim();
+    }
+    let mut start = text.len() - max_chars;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    let tail = &text[start..];
+    if text[..start].ends_with(char::is_whitespace) {
+        return tail.trim();
+    }
+    // Started mid-word: drop the partial word.
+    tail.find(char::is_whitespace)
+        .map_or("", |i| tail[i..].trim())
+}
+
 /// Extract plain text from common file formats.
-/// Supports: .txt, .md, .json, .toml, .yaml, .csv, .log
+/// Supports: .txt, .md, .json, .html, .toml, .yaml, .csv, .log
 /// For Pi: no heavy PDF/DOCX parsing — keep it simple.
 pub fn extract_text(content: &str, filename: &str) -> String {
     let ext = filename.rsplit('.').next().unwrap_or("txt").to_lowercase();
@@ -83,10 +330,90 @@ pub fn extract_text(content: &str, filename: &str) -> String {
                 content.to_string()
             }
         }
+        "html" | "htm" => strip_html(content),
         _ => content.to_string(),
     }
 }
 
+/// Block-level tags that end a line of visible text.
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "br",
+    "div",
+    "li",
+    "tr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "pre",
+    "blockquote",
+    "table",
+    "ul",
+    "ol",
+];
+
+/// Reduce HTML to its visible text: drops tags, comments, `<script>` and
+/// `<style>` bodies, decodes the common entities and collapses whitespace.
+pub fn strip_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len() / 2);
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        if let Some(comment) = after.strip_prefix("!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(end) = after.find('>') else {
+            rest = "";
+            break;
+        };
+        let raw = &after[..end];
+        let closing = raw.starts_with('/');
+        let tag = raw
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        rest = &after[end + 1..];
+
+        if !closing && (tag == "script" || tag == "style") {
+            // ASCII lowercasing keeps byte offsets, so the index is valid in `rest`.
+            let close = format!("</{tag}");
+            rest = match rest.to_ascii_lowercase().find(&close) {
+                Some(i) => rest[i..].find('>').map_or("", |e| &rest[i + e + 1..]),
+                None => "",
+            };
+        } else if BLOCK_TAGS.contains(&tag.as_str()) {
+            out.push('\n');
+        }
+    }
+    out.push_str(rest);
+
+    let decoded = out
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    decoded
+        .lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Recursively extract string values from a JSON value.
 fn extract_json_strings(val: &serde_json::Value) -> String {
     match val {
@@ -142,6 +469,151 @@ mod tests {
         }
     }
 
+    /// Two sections of numbered sentences, with unique words throughout.
+    fn handbook() -> String {
+        let mut doc = String::from("Employee handbook.\n\n");
+        for (section, title) in ["Leave", "Remote work"].iter().enumerate() {
+            doc.push_str(&format!("## {title}\n\n"));
+            for n in 0..12 {
+                doc.push_str(&format!(
+                    "Rule s{section}n{n} applies to every team member w{section}x{n}. "
+                ));
+            }
+            doc.push_str("\n\n");
+        }
+        doc
+    }
+
+    fn options(mode: SplitMode, overlap: usize) -> ChunkOptions {
+        ChunkOptions {
+            mode,
+            size: 200,
+            overlap,
+        }
+    }
+
+    #[test]
+    fn test_split_modes_on_same_input() {
+        let doc = handbook();
+        let by = |mode| split(&doc, &options(mode, 0));
+        let paragraphs = by(SplitMode::Paragraphs);
+        let tokens = by(SplitMode::Tokens);
+        let sentences = by(SplitMode::Sentences);
+        let headings = by(SplitMode::Headings);
+
+        for chunks in [&paragraphs, &tokens, &sentences, &headings] {
+            assert!(chunks.len() > 2);
+            assert!(chunks.iter().all(|c| c.len() <= 200), "{chunks:#?}");
+        }
+        // Word windows run across the layout, so they pack the tightest.
+        assert!(tokens.len() <= sentences.len());
+        assert!(tokens.iter().all(|c| !c.contains('\n')));
+        // Sentences are never cut.
+        assert!(sentences.iter().all(|c| c.ends_with('.')));
+        assert!(
+            sentences
+                .iter()
+                .all(|c| c.starts_with("Rule") || c.starts_with("Employee") || c.starts_with('#'))
+        );
+        // No chunk spans two sections, and each repeats its heading.
+        assert_eq!(headings[0], "Employee handbook.");
+        for chunk in &headings[1..] {
+            assert!(chunk.starts_with("## "), "{chunk}");
+            assert_eq!(chunk.matches("## ").count(), 1);
+            let section = if chunk.starts_with("## Leave") {
+                "s0"
+            } else {
+                "s1"
+            };
+            assert!(
+                chunk
+                    .lines()
+                    .skip(1)
+                    .all(|l| l.is_empty() || l.contains(section))
+            );
+        }
+        // Paragraphs cut sentences and leave headings on their own.
+        assert_eq!(paragraphs.len(), 8);
+        assert!(paragraphs.iter().any(|c| c.trim_end() == "## Remote work"));
+        assert!(paragraphs.iter().any(|c| !c.trim_end().ends_with('.')));
+        assert_eq!([tokens.len(), sentences.len(), headings.len()], [6, 7, 7]);
+    }
+
+    #[test]
+    fn test_overlap_repeats_end_of_previous_chunk() {
+        let text = (0..300)
+            .map(|i| format!("w{i}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        for mode in [
+            SplitMode::Paragraphs,
+            SplitMode::Tokens,
+            SplitMode::Sentences,
+        ] {
+            let plain = split(&text, &options(mode, 0));
+            let overlapped = split(&text, &options(mode, 40));
+            assert!(overlapped.len() > plain.len(), "{mode:?}");
+
+            for pair in plain.windows(2) {
+                let last = pair[0].split_whitespace().last().unwrap();
+                assert!(!pair[1].split_whitespace().any(|w| w == last));
+            }
+            for pair in overlapped.windows(2) {
+                assert!(pair[1].len() <= 200);
+                // 40 characters of overlap, joining space included.
+                let tail = tail_words(&pair[0], 39);
+                assert!(tail.len() > 20 && tail.len() < 40, "{tail:?}");
+                assert!(
+                    pair[1].starts_with(&format!("{tail} ")),
+                    "{mode:?}: {pair:?}"
+                );
+            }
+        }
+        // Words are never cut, even in multi-byte text.
+        assert_eq!(tail_words("chính sách làm việc", 6), "việc");
+        assert_eq!(tail_words("chính", 3), "");
+    }
+
+    #[test]
+    fn test_chunks_fit_minimum_size() {
+        let mut doc = handbook();
+        doc.push_str(&"Rule lorem ipsum dolor sit amet. ".repeat(40));
+        for mode in [
+            SplitMode::Paragraphs,
+            SplitMode::Tokens,
+            SplitMode::Sentences,
+            SplitMode::Headings,
+        ] {
+            let options = ChunkOptions {
+                mode,
+                size: MIN_CHUNK_SIZE,
+                overlap: MIN_CHUNK_SIZE / 2,
+            };
+            let chunks = split(&doc, &options);
+            assert!(chunks.len() > 2, "{mode:?}");
+            assert!(
+                chunks.iter().all(|c| c.len() <= MIN_CHUNK_SIZE),
+                "{mode:?}: {chunks:#?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_chunk_document_splits_markdown_before_stripping() {
+        let md = "# Giờ mở cửa\n\nTừ 8h đến 22h.\n\n# Đổi trả\n\nTrong vòng 7 ngày.";
+        let options = ChunkOptions {
+            mode: SplitMode::Headings,
+            ..ChunkOptions::default()
+        };
+        let chunks = chunk_document(md, "faq.md", &options);
+        assert_eq!(
+            chunks,
+            ["Giờ mở cửa\nTừ 8h đến 22h.", "Đổi trả\nTrong vòng 7 ngày."]
+        );
+        assert_eq!("Headings".parse(), Ok(SplitMode::Headings));
+        assert!("pages".parse::<SplitMode>().is_err());
+    }
+
     #[test]
     fn test_extract_markdown() {
         let md = "# Title\n## Sub\n- item\n> quote";
@@ -149,4 +621,14 @@ mod tests {
         assert!(!text.contains('#'));
         assert!(text.contains("Title"));
     }
+
+    #[test]
+    fn test_extract_html() {
+        let html = "<html><head><title>Menu</title><style>p { color: red; }</style>\
+                    <script>var x = '<p>hidden</p>';</script></head>\
+                    <body><!-- nav --><h1>Tea &amp; Coffee</h1>\
+                    <p>Green tea:   <b>45k</b></p><p>Latte&nbsp;55k</p></body></html>";
+        let text = extract_text(html, "menu.html");
+        assert_eq!(text, "Menu\nTea & Coffee\nGreen tea: 45k\nLatte 55k");
+    }
 }
@@ -2,7 +2,13 @@
 //!
 //! Uses Ollama `/api/embed` by default (free, local).
 //! Falls back to OpenAI `/v1/embeddings` if configured.
+//! With an [`EmbeddingCache`] attached, texts embedded before are served
+//! from the cache and only new texts reach the provider.
 
+use std::sync::Arc;
+
+use bizclaw_core::traits::EmbeddingCache;
+use bizclaw_core::traits::embedding_cache::embedding_cache_key;
 use serde::{Deserialize, Serialize};
 
 /// Embedding provider configuration.
@@ -35,6 +41,9 @@ pub struct EmbeddingClient {
     client: reqwest::Client,
     /// Dimension of the embedding vectors (set after first call).
     pub dimension: usize,
+    cache: Option<Arc<dyn EmbeddingCache>>,
+    /// Texts served from the cache instead of the provider.
+    pub cache_hits: usize,
 }
 
 #[derive(Serialize)]
@@ -74,9 +83,17 @@ impl EmbeddingClient {
                 .build()
                 .unwrap_or_default(),
             dimension: 0,
+            cache: None,
+            cache_hits: 0,
         }
     }
 
+    /// Consult `cache` before calling the provider.
+    pub fn with_cache(mut self, cache: Arc<dyn EmbeddingCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
     /// Check if embeddings are available (provider != "none").
     pub fn is_available(&self) -> bool {
         self.config.provider != "none" && !self.config.endpoint.is_empty()
@@ -96,7 +113,49 @@ impl EmbeddingClient {
         if texts.is_empty() {
             return Ok(vec![]);
         }
+        let Some(cache) = self.cache.clone() else {
+            return self.fetch(texts).await;
+        };
+
+        let keys: Vec<String> = texts
+            .iter()
+            .map(|t| embedding_cache_key(&self.config.model, t))
+            .collect();
+        let mut results = Vec::with_capacity(texts.len());
+        for key in &keys {
+            // A cache that fails to answer is a miss, not an error.
+            results.push(cache.get(key).await.ok().flatten());
+        }
+        let missing: Vec<usize> = (0..texts.len()).filter(|&i| results[i].is_none()).collect();
+        self.cache_hits += texts.len() - missing.len();
+
+        if !missing.is_empty() {
+            let batch: Vec<String> = missing.iter().map(|&i| texts[i].clone()).collect();
+            let fetched = self.fetch(&batch).await?;
+            if fetched.len() != batch.len() {
+                return Err(format!(
+                    "Expected {} embeddings, got {}",
+                    batch.len(),
+                    fetched.len()
+                ));
+            }
+            for (i, embedding) in missing.into_iter().zip(fetched) {
+                if let Err(e) = cache.put(&keys[i], &embedding).await {
+                    tracing::warn!("Embedding cache write failed: {e}");
+                }
+                results[i] = Some(embedding);
+            }
+        }
+
+        let embeddings: Vec<Vec<f32>> = results.into_iter().flatten().collect();
+        if let Some(first) = embeddings.first() {
+            self.dimension = first.len();
+        }
+        Ok(embeddings)
+    }
 
+    /// Call the provider, bypassing the cache.
+    async fn fetch(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
         match self.config.provider.as_str() {
             "ollama" => self.embed_ollama(texts).await,
             "openai" | "gemini" | "deepseek" => self.embed_openai_compat(texts).await,
@@ -180,6 +239,34 @@ impl EmbeddingClient {
     }
 }
 
+/// Embed up to `limit` knowledge chunks that have no embedding yet and
+/// store the vectors, so vector search covers them. The store lock is not
+/// held while the provider is called. Returns how many chunks were embedded.
+pub async fn index_chunks(
+    kb: &tokio::sync::Mutex<Option<crate::KnowledgeStore>>,
+    client: &mut EmbeddingClient,
+    limit: usize,
+) -> Result<usize, String> {
+    let pending: Vec<(String, String, String)> = match kb.lock().await.as_ref() {
+        Some(store) => store.chunks_without_embeddings(limit),
+        None => return Ok(0),
+    };
+    if pending.is_empty() {
+        return Ok(0);
+    }
+    let texts: Vec<String> = pending.iter().map(|(_, _, text)| text.clone()).collect();
+    let embeddings = client.embed_batch(&texts).await?;
+
+    let kb = kb.lock().await;
+    let Some(store) = kb.as_ref() else {
+        return Ok(0);
+    };
+    for ((doc_id, chunk_idx, _), embedding) in pending.iter().zip(&embeddings) {
+        store.store_chunk_embedding(doc_id, chunk_idx, embedding)?;
+    }
+    Ok(embeddings.len())
+}
+
 /// Compute cosine similarity between two vectors.
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() || a.is_empty() {
@@ -257,6 +344,125 @@ mod tests {
         }
     }
 
+    #[derive(Default)]
+    struct MemoryCache(std::sync::Mutex<std::collections::HashMap<String, Vec<f32>>>);
+
+    #[async_trait::async_trait]
+    impl EmbeddingCache for MemoryCache {
+        async fn get(&self, key: &str) -> bizclaw_core::error::Result<Option<Vec<f32>>> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+
+        async fn put(&self, key: &str, embedding: &[f32]) -> bizclaw_core::error::Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(key.into(), embedding.to_vec());
+            Ok(())
+        }
+    }
+
+    /// Fake Ollama server answering every request with one embedding per
+    /// input. Returns its URL and a request counter.
+    async fn fake_ollama() -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::Ordering;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = calls.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                // Read until the JSON body is complete.
+                while let Ok(n) = socket.read(&mut buf).await {
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if n == 0 || (text.contains("\r\n\r\n") && text.trim_end().ends_with('}')) {
+                        break;
+                    }
+                }
+                counter.fetch_add(1, Ordering::SeqCst);
+                let text = String::from_utf8_lossy(&request);
+                let body = text.split("\r\n\r\n").nth(1).unwrap_or_default();
+                let inputs = serde_json::from_str::<serde_json::Value>(body)
+                    .ok()
+                    .and_then(|v| v["input"].as_array().map(|a| a.len()))
+                    .unwrap_or(0);
+                let response =
+                    serde_json::json!({ "embeddings": vec![[0.1, 0.2, 0.3]; inputs] }).to_string();
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                    response.len()
+                );
+                let _ = socket.write_all(reply.as_bytes()).await;
+            }
+        });
+        (url, calls)
+    }
+
+    #[tokio::test]
+    async fn test_cached_text_is_embedded_once() {
+        let (endpoint, calls) = fake_ollama().await;
+        let mut client = EmbeddingClient::new(EmbeddingConfig {
+            endpoint,
+            ..Default::default()
+        })
+        .with_cache(Arc::new(MemoryCache::default()));
+
+        let first = client.embed_one("opening hours").await.unwrap();
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(client.cache_hits, 0);
+
+        let second = client.embed_one("opening hours").await.unwrap();
+        assert_eq!(second, first);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(client.cache_hits, 1);
+
+        // Only the new text in a mixed batch reaches the provider.
+        let batch = client
+            .embed_batch(&["opening hours".into(), "return policy".into()])
+            .await
+            .unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_eq!(client.cache_hits, 2);
+        assert_eq!(client.dimension, 3);
+    }
+
+    #[tokio::test]
+    async fn test_index_chunks_reuses_cached_embeddings() {
+        use crate::{ChunkOptions, KnowledgeStore};
+        use std::sync::atomic::Ordering;
+
+        let (endpoint, calls) = fake_ollama().await;
+        let mut client = EmbeddingClient::new(EmbeddingConfig {
+            endpoint,
+            ..Default::default()
+        })
+        .with_cache(Arc::new(MemoryCache::default()));
+        let text = "Opening hours: 9am to 5pm, Monday to Saturday.";
+        let add = |store: &KnowledgeStore, name: &str| {
+            store.add_text(name, text, "test", &ChunkOptions::default()).unwrap();
+        };
+        let store = KnowledgeStore::open(std::path::Path::new(":memory:")).unwrap();
+        add(&store, "hours.txt");
+        let kb = tokio::sync::Mutex::new(Some(store));
+
+        assert_eq!(index_chunks(&kb, &mut client, 32).await.unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(index_chunks(&kb, &mut client, 32).await.unwrap(), 0);
+
+        // The same text in another document is served from the cache.
+        add(kb.lock().await.as_ref().unwrap(), "copy.txt");
+        assert_eq!(index_chunks(&kb, &mut client, 32).await.unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(client.cache_hits, 1);
+        assert_eq!(kb.lock().await.as_ref().unwrap().embedded_count(), 2);
+    }
+
     #[test]
     fn test_default_config() {
         let config = EmbeddingConfig::default();
@@ -0,0 +1,420 @@
+//! URL ingestion — fetch a remote document for the knowledge base.
+//!
+//! The body is streamed with a hard byte cap, gzip/deflate bodies are
+//! decompressed by hand with a second cap on the decompressed size (so a
+//! small gzip bomb cannot expand into gigabytes), and only text-like
+//! content types are accepted. Oversized documents are truncated rather
+//! than rejected; [`FetchedDocument::truncated`] reports when that happened.
+//!
+//! Loopback, link-local (cloud metadata) and private addresses are refused,
+//! for the URL itself and for every redirect when the client uses
+//! [`redirect_policy`].
+
+use std::io::Read;
+
+use bizclaw_core::net::is_url_blocked;
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE};
+
+use crate::chunker;
+
+/// Size caps for a single fetch.
+#[derive(Debug, Clone, Copy)]
+pub struct FetchLimits {
+    /// Most bytes read off the wire (compressed size).
+    pub max_download_bytes: usize,
+    /// Most bytes kept after decompression.
+    pub max_text_bytes: usize,
+}
+
+impl Default for FetchLimits {
+    fn default() -> Self {
+        Self {
+            max_download_bytes: 5 * 1024 * 1024,
+            max_text_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// A fetched document, reduced to plain text.
+#[derive(Debug, Clone)]
+pub struct FetchedDocument {
+    /// Final URL after redirects.
+    pub url: String,
+    /// Display name derived from the URL.
+    pub name: String,
+    /// Media type without parameters, e.g. `text/html`.
+    pub content_type: String,
+    /// Extracted plain text.
+    pub text: String,
+    /// Whether a size cap cut the document short.
+    pub truncated: bool,
+}
+
+/// Most redirects followed by [`redirect_policy`].
+const MAX_REDIRECTS: usize = 5;
+
+/// Redirect policy for fetch clients: every hop is checked against the
+/// SSRF block list, so a public URL can't redirect to an internal one.
+pub fn redirect_policy() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            return attempt.error(format!("Too many redirects (max {MAX_REDIRECTS})"));
+        }
+        match is_url_blocked(attempt.url().as_str()) {
+            Some(reason) => attempt.error(format!("Redirect blocked: {reason}")),
+            None => attempt.follow(),
+        }
+    })
+}
+
+/// Fetch `url` and extract its text, enforcing `limits`. Internal addresses
+/// are refused; build `client` with [`redirect_policy`] so redirects are
+/// checked too.
+pub async fn fetch_document(
+    client: &reqwest::Client,
+    url: &str,
+    limits: FetchLimits,
+) -> Result<FetchedDocument, String> {
+    if let Some(reason) = is_url_blocked(url) {
+        return Err(format!("URL blocked: {reason}"));
+    }
+    let doc = fetch_url(client, url, limits).await?;
+    // In case the client follows redirects without the policy.
+    if let Some(reason) = is_url_blocked(&doc.url) {
+        return Err(format!("URL blocked: {reason}"));
+    }
+    Ok(doc)
+}
+
+/// [`fetch_document`] without the SSRF checks on `url`.
+async fn fetch_url(
+    client: &reqwest::Client,
+    url: &str,
+    limits: FetchLimits,
+) -> Result<FetchedDocument, String> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(format!("Only http(s) URLs can be fetched: {url}"));
+    }
+
+    let mut resp = client
+        .get(url)
+        .header(ACCEPT_ENCODING, "gzip, deflate")
+        .send()
+        .await
+        .map_err(|e| match std::error::Error::source(&e) {
+            // e.g. the redirect policy's reason.
+            Some(cause) => format!("Fetch error: {e}: {cause}"),
+            None => format!("Fetch error: {e}"),
+        })?;
+    if !resp.status().is_success() {
+        return Err(format!("Fetch failed: HTTP {}", resp.status()));
+    }
+
+    let final_url = resp.url().to_string();
+    let header = |name: reqwest::header::HeaderName| {
+        resp.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_ascii_lowercase())
+    };
+    let declared_type = header(CONTENT_TYPE).map(|v| media_type(&v));
+    let encoding = header(CONTENT_ENCODING).unwrap_or_default();
+    if let Some(ct) = &declared_type
+        && !is_text_type(ct)
+    {
+        return Err(format!(
+            "Unsupported content type '{ct}': only text documents can be added"
+        ));
+    }
+
+    // Stream the body, stopping at the download cap.
+    let mut raw = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = resp.chunk().await.map_err(|e| format!("Read error: {e}"))? {
+        let room = limits.max_download_bytes - raw.len();
+        if chunk.len() > room {
+            raw.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        raw.extend_from_slice(&chunk);
+    }
+
+    let (body, cut) = decode_body(&raw, encoding.trim(), limits.max_text_bytes, truncated)?;
+    truncated |= cut;
+
+    let content_type = match declared_type {
+        Some(ct) => ct,
+        // No header: accept only if the bytes look like text.
+        None if body.iter().take(1024).any(|&b| b == 0) => {
+            return Err("Unsupported content: response looks binary".into());
+        }
+        None => "text/plain".into(),
+    };
+
+    let text = utf8_prefix(&body, truncated);
+    let text = chunker::extract_text(&text, extension_for(&content_type));
+    Ok(FetchedDocument {
+        name: name_from_url(&final_url),
+        url: final_url,
+        content_type,
+        text,
+        truncated,
+    })
+}
+
+/// Undo `Content-Encoding`, keeping at most `max_bytes` of output.
+/// Returns the body and whether it was cut at the cap. A corrupt stream is
+/// an error unless the download itself was truncated, in which case the
+/// bytes decoded so far are kept.
+fn decode_body(
+    raw: &[u8],
+    encoding: &str,
+    max_bytes: usize,
+    download_truncated: bool,
+) -> Result<(Vec<u8>, bool), String> {
+    let reader: Box<dyn Read + '_> = match encoding {
+        "" | "identity" => {
+            let cut = raw.len() > max_bytes;
+            return Ok((raw[..raw.len().min(max_bytes)].to_vec(), cut));
+        }
+        "gzip" | "x-gzip" => Box::new(GzDecoder::new(raw)),
+        // "deflate" is meant to be zlib-wrapped, but some servers send raw deflate.
+        "deflate" if is_zlib_header(raw) => Box::new(ZlibDecoder::new(raw)),
+        "deflate" => Box::new(DeflateDecoder::new(raw)),
+        other => return Err(format!("Unsupported content encoding '{other}'")),
+    };
+
+    let mut out = Vec::new();
+    let limit = max_bytes as u64 + 1;
+    if let Err(e) = reader.take(limit).read_to_end(&mut out)
+        && !download_truncated
+    {
+        return Err(format!("Decompression error: {e}"));
+    }
+    let cut = out.len() > max_bytes;
+    out.truncate(max_bytes);
+    Ok((out, cut))
+}
+
+/// RFC 1950 header: deflate method and a checksum divisible by 31.
+fn is_zlib_header(raw: &[u8]) -> bool {
+    match raw {
+        [cmf, flg, ..] => cmf & 0x0f == 8 && u16::from_be_bytes([*cmf, *flg]) % 31 == 0,
+        _ => false,
+    }
+}
+
+/// Decode UTF-8, dropping a character split by truncation.
+fn utf8_prefix(bytes: &[u8], truncated: bool) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(e) if truncated && e.error_len().is_none() => {
+            String::from_utf8_lossy(&bytes[..e.valid_up_to()]).into_owned()
+        }
+        Err(_) => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// `text/html; charset=utf-8` → `text/html`.
+fn media_type(value: &str) -> String {
+    value.split(';').next().unwrap_or("").trim().to_string()
+}
+
+fn is_text_type(ct: &str) -> bool {
+    ct.starts_with("text/")
+        || ct.ends_with("+json")
+        || ct.ends_with("+xml")
+        || matches!(
+            ct,
+            "application/json"
+                | "application/xml"
+                | "application/yaml"
+                | "application/x-yaml"
+                | "application/toml"
+                | "application/markdown"
+        )
+}
+
+/// Pseudo file name that routes the body through the right extractor.
+fn extension_for(ct: &str) -> &'static str {
+    match ct {
+        "text/html" | "application/xhtml+xml" => "page.html",
+        "text/markdown" | "text/x-markdown" | "application/markdown" => "page.md",
+        ct if ct.ends_with("json") => "page.json",
+        _ => "page.txt",
+    }
+}
+
+/// Last non-empty path segment, or the host for a bare domain.
+fn name_from_url(url: &str) -> String {
+    let without_query = url.split(['?', '#']).next().unwrap_or(url);
+    let path = without_query
+        .split_once("://")
+        .map_or(without_query, |(_, p)| p);
+    let mut segments = path.split('/').filter(|s| !s.is_empty());
+    let host = segments.next().unwrap_or(url);
+    segments.next_back().unwrap_or(host).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve one HTTP response with the given headers and body.
+    async fn serve_once(headers: &str, body: Vec<u8>) -> String {
+        serve_status("200 OK", headers, body).await
+    }
+
+    async fn serve_status(status: &str, headers: &str, body: Vec<u8>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (status, headers) = (status.to_string(), headers.to_string());
+        tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = sock.read(&mut buf).await;
+            let head = format!(
+                "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            sock.write_all(head.as_bytes()).await.unwrap();
+            let _ = sock.write_all(&body).await;
+        });
+        format!("http://{addr}/docs/menu")
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(data).unwrap();
+        enc.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_html_document() {
+        let html = "<html><body><h1>Menu</h1><p>Green tea &amp; lotus</p></body></html>";
+        let url = serve_once(
+            "Content-Type: text/html; charset=utf-8\r\n",
+            html.as_bytes().to_vec(),
+        )
+        .await;
+        let doc = fetch_url(&reqwest::Client::new(), &url, FetchLimits::default())
+            .await
+            .unwrap();
+        assert_eq!(doc.name, "menu");
+        assert_eq!(doc.content_type, "text/html");
+        assert_eq!(doc.text, "Menu\nGreen tea & lotus");
+        assert!(!doc.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_gzip_document() {
+        let url = serve_once(
+            "Content-Type: text/plain\r\nContent-Encoding: gzip\r\n",
+            gzip("Opening hours: 7am - 10pm".as_bytes()),
+        )
+        .await;
+        let doc = fetch_url(&reqwest::Client::new(), &url, FetchLimits::default())
+            .await
+            .unwrap();
+        assert_eq!(doc.text, "Opening hours: 7am - 10pm");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_truncates_at_caps() {
+        let limits = FetchLimits {
+            max_download_bytes: 1024,
+            max_text_bytes: 4096,
+        };
+
+        // Plain body over the download cap.
+        let url = serve_once("Content-Type: text/plain\r\n", vec![b'a'; 10_000]).await;
+        let doc = fetch_url(&reqwest::Client::new(), &url, limits)
+            .await
+            .unwrap();
+        assert!(doc.truncated);
+        assert_eq!(doc.text.len(), 1024);
+
+        // A small gzip bomb stays under the download cap but is cut at the
+        // decompressed cap.
+        let bomb = gzip(&vec![b'z'; 1_000_000]);
+        assert!(bomb.len() < 1024);
+        let url = serve_once(
+            "Content-Type: text/plain\r\nContent-Encoding: gzip\r\n",
+            bomb,
+        )
+        .await;
+        let doc = fetch_url(&reqwest::Client::new(), &url, limits)
+            .await
+            .unwrap();
+        assert!(doc.truncated);
+        assert_eq!(doc.text.len(), 4096);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rejects_binary() {
+        let url = serve_once("Content-Type: image/png\r\n", vec![0x89, b'P', b'N', b'G']).await;
+        let err = fetch_url(&reqwest::Client::new(), &url, FetchLimits::default())
+            .await
+            .unwrap_err();
+        assert!(err.contains("image/png"), "{err}");
+
+        let url = serve_once("", vec![0, 1, 2, 3]).await;
+        assert!(
+            fetch_url(&reqwest::Client::new(), &url, FetchLimits::default())
+                .await
+                .is_err()
+        );
+        assert!(
+            fetch_url(
+                &reqwest::Client::new(),
+                "file:///etc/passwd",
+                FetchLimits::default()
+            )
+            .await
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_blocks_internal_urls() {
+        let client = reqwest::Client::builder()
+            .redirect(redirect_policy())
+            .build()
+            .unwrap();
+        for url in [
+            "http://127.0.0.1:3000/api/v1/config",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://192.168.1.1/",
+            "http://localhost/",
+        ] {
+            let err = fetch_document(&client, url, FetchLimits::default())
+                .await
+                .unwrap_err();
+            assert!(err.starts_with("URL blocked"), "{url}: {err}");
+        }
+
+        // A redirect to the metadata address is refused before it is followed.
+        let url = serve_status(
+            "302 Found",
+            "Location: http://169.254.169.254/latest/meta-data/\r\n",
+            Vec::new(),
+        )
+        .await;
+        let err = fetch_url(&client, &url, FetchLimits::default())
+            .await
+            .unwrap_err();
+        assert!(err.contains("Redirect blocked"), "{err}");
+    }
+
+    #[test]
+    fn test_utf8_prefix_drops_split_char() {
+        let bytes = "phở".as_bytes();
+        assert_eq!(utf8_prefix(&bytes[..bytes.len() - 1], true), "ph");
+    }
+}
@@ -0,0 +1,90 @@
+//! Bulk import of documents from JSONL.
+//!
+//! One document per line, either `{"content": "...", "name"?: "...",
+//! "source"?: "..."}` (`text` works for `content`) or an FAQ pair
+//! `{"question": "...", "answer": "..."}`. Each document is chunked and
+//! indexed like an uploaded file.
+
+use bizclaw_core::types::{
+    ImportReport, JsonRecord, jsonl_record_count, jsonl_records, record_str,
+};
+
+use crate::chunker::ChunkOptions;
+use crate::store::KnowledgeStore;
+
+/// Import the JSONL `input` into `store`. Documents without a name are
+/// named `{file_name}#{line}`. `progress` is called with (records processed,
+/// total records) after each record.
+pub fn import_jsonl(
+    store: &KnowledgeStore,
+    input: &str,
+    file_name: &str,
+    mut progress: impl FnMut(usize, usize),
+) -> ImportReport {
+    let total = jsonl_record_count(input);
+    let mut report = ImportReport::default();
+    for (line, record) in jsonl_records(input) {
+        let indexed = record.and_then(|record| {
+            let text = document_text(&record)?;
+            let name = record_str(&record, "name")
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{file_name}#{line}"));
+            let source = record_str(&record, "source").unwrap_or("import");
+            store.add_text(&name, &text, source, &ChunkOptions::default())
+        });
+        match indexed {
+            Ok(chunks) => {
+                report.imported += 1;
+                report.chunks += chunks;
+            }
+            Err(e) => report.fail(line, e),
+        }
+        progress(report.processed(), total);
+    }
+    report
+}
+
+fn document_text(record: &JsonRecord) -> Result<String, String> {
+    if let Some(text) = record_str(record, "content").or_else(|| record_str(record, "text")) {
+        return Ok(text.to_string());
+    }
+    match (record_str(record, "question"), record_str(record, "answer")) {
+        (Some(q), Some(a)) => Ok(format!("Q: {q}\nA: {a}")),
+        (Some(_), None) => Err("FAQ record is missing `answer`".into()),
+        _ => Err("missing `content`".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_import_skips_malformed_line() {
+        let store = KnowledgeStore::open(Path::new(":memory:")).unwrap();
+        let input = r#"{"name": "returns", "content": "Returns are accepted within 30 days."}
+{"question": "Do you ship abroad?", "answer": "Yes, to most countries."
+{"question": "Do you ship abroad?", "answer": "Yes, to most countries."}
+{"question": "Opening hours?"}
+"#;
+        let mut last = (0, 0);
+        let report = import_jsonl(&store, input, "faq.jsonl", |done, total| {
+            last = (done, total)
+        });
+
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.chunks, 2);
+        assert_eq!(report.failures.len(), 2);
+        assert_eq!(report.failures[0].line, 2);
+        assert!(report.failures[0].error.starts_with("invalid JSON"));
+        assert_eq!(report.failures[1].line, 4);
+        assert_eq!(report.failures[1].error, "FAQ record is missing `answer`");
+        assert_eq!(last, (4, 4));
+
+        let names: Vec<String> = store.list_documents().into_iter().map(|d| d.1).collect();
+        assert!(names.contains(&"returns".to_string()));
+        assert!(names.contains(&"faq.jsonl#3".to_string()));
+        assert!(!store.search("ship abroad", 3).is_empty());
+    }
+}
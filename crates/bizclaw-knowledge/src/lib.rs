@@ -26,6 +26,8 @@
 
 pub mod chunker;
 pub mod embeddings;
+pub mod fetch;
+pub mod import;
 pub mod search;
 pub mod store;
 pub mod vector_store;
@@ -33,5 +35,7 @@ pub mod vector_store;
 #[cfg(feature = "pdf")]
 pub mod pdf;
 
+pub use chunker::{ChunkOptions, SplitMode};
+pub use fetch::{FetchLimits, FetchedDocument, fetch_document};
 pub use search::SearchResult;
 pub use store::KnowledgeStore;
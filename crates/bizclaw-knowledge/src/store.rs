@@ -5,7 +5,7 @@
 use rusqlite::{Connection, params};
 use std::path::{Path, PathBuf};
 
-use crate::chunker;
+use crate::chunker::{self, ChunkOptions};
 use crate::search::SearchResult;
 
 /// Knowledge store backed by SQLite FTS5.
@@ -47,6 +47,21 @@ impl KnowledgeStore {
         )
         .map_err(|e| format!("Schema error: {e}"))?;
 
+        // Chunking parameters, added later; rows from before were split by
+        // paragraphs into 500-char chunks without overlap.
+        let _ = conn.execute(
+            "ALTER TABLE documents ADD COLUMN split_mode TEXT NOT NULL DEFAULT 'paragraphs'",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE documents ADD COLUMN chunk_size INTEGER NOT NULL DEFAULT 500",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE documents ADD COLUMN chunk_overlap INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
         tracing::debug!("📚 Knowledge store opened: {}", path.display());
         Ok(Self { conn })
     }
@@ -59,57 +74,75 @@ impl KnowledgeStore {
 
     /// Add a document to the knowledge base.
     /// Automatically chunks and indexes the content.
-    pub fn add_document(&self, name: &str, content: &str, source: &str) -> Result<usize, String> {
+    pub fn add_document(
+        &self,
+        name: &str,
+        content: &str,
+        source: &str,
+        options: &ChunkOptions,
+    ) -> Result<usize, String> {
         // Extract text based on file extension
-        let text = chunker::extract_text(content, name);
-
-        // Chunk the text
-        let chunks = chunker::chunk_text(&text, 500);
-        let chunk_count = chunks.len();
-
-        // Insert document record
-        self.conn
-            .execute(
-                "INSERT INTO documents (name, source, chunk_count) VALUES (?1, ?2, ?3)",
-                params![name, source, chunk_count as i64],
-            )
-            .map_err(|e| format!("Insert doc error: {e}"))?;
-
-        let doc_id = self.conn.last_insert_rowid();
-
-        // Index chunks
-        for (idx, chunk) in chunks.iter().enumerate() {
-            self.conn
-                .execute(
-                    "INSERT INTO chunks (doc_id, chunk_idx, content) VALUES (?1, ?2, ?3)",
-                    params![doc_id.to_string(), idx.to_string(), chunk],
-                )
-                .map_err(|e| format!("Insert chunk error: {e}"))?;
-        }
+        let chunks = chunker::chunk_document(content, name, options);
+        self.insert_document(name, source, options, &chunks)
+    }
 
-        tracing::info!("📄 Added '{}' → {} chunks indexed", name, chunk_count);
-        Ok(chunk_count)
+    /// Add already-extracted plain text (e.g. a fetched web page) without
+    /// running it through the extension-based extractor again.
+    pub fn add_text(
+        &self,
+        name: &str,
+        text: &str,
+        source: &str,
+        options: &ChunkOptions,
+    ) -> Result<usize, String> {
+        let chunks = chunker::split(text, options);
+        self.insert_document(name, source, options, &chunks)
     }
 
     /// Add a PDF document from raw bytes.
     /// Uses pdf_oxide for text extraction with markdown preservation.
     /// Tries markdown extraction first (better RAG quality), falls back to plain text.
     #[cfg(feature = "pdf")]
-    pub fn add_pdf_document(&self, name: &str, data: &[u8], source: &str) -> Result<usize, String> {
+    pub fn add_pdf_document(
+        &self,
+        name: &str,
+        data: &[u8],
+        source: &str,
+        options: &ChunkOptions,
+    ) -> Result<usize, String> {
         // Try markdown first (preserves headings, tables, layout)
         // Fall back to plain text if markdown fails
         let text = crate::pdf::extract_markdown_from_pdf(data)
             .or_else(|_| crate::pdf::extract_text_from_pdf(data))?;
 
         // Same chunking + indexing pipeline as text documents
-        let chunks = chunker::chunk_text(&text, 500);
+        let chunks = chunker::split(&text, options);
+        self.insert_document(name, source, options, &chunks)
+    }
+
+    /// Insert the document record and index its chunks.
+    fn insert_document(
+        &self,
+        name: &str,
+        source: &str,
+        options: &ChunkOptions,
+        chunks: &[String],
+    ) -> Result<usize, String> {
         let chunk_count = chunks.len();
 
         // Insert document record
         self.conn
             .execute(
-                "INSERT INTO documents (name, source, chunk_count) VALUES (?1, ?2, ?3)",
-                params![name, source, chunk_count as i64],
+                "INSERT INTO documents (name, source, chunk_count, split_mode, chunk_size, chunk_overlap)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    name,
+                    source,
+                    chunk_count as i64,
+                    options.mode.as_str(),
+                    options.size as i64,
+                    options.overlap as i64
+                ],
             )
             .map_err(|e| format!("Insert doc error: {e}"))?;
 
@@ -125,10 +158,40 @@ impl KnowledgeStore {
                 .map_err(|e| format!("Insert chunk error: {e}"))?;
         }
 
-        tracing::info!("📄 Added PDF '{}' → {} chunks indexed", name, chunk_count);
+        tracing::info!(
+            "📄 Added '{}' → {} chunks indexed ({}, size {}, overlap {})",
+            name,
+            chunk_count,
+            options.mode.as_str(),
+            options.size,
+            options.overlap
+        );
         Ok(chunk_count)
     }
 
+    /// Chunking parameters a document was indexed with, to reindex it the
+    /// same way.
+    pub fn chunk_options(&self, doc_id: i64) -> Option<ChunkOptions> {
+        self.conn
+            .query_row(
+                "SELECT split_mode, chunk_size, chunk_overlap FROM documents WHERE id = ?1",
+                params![doc_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                    ))
+                },
+            )
+            .ok()
+            .map(|(mode, size, overlap)| ChunkOptions {
+                mode: mode.parse().unwrap_or_default(),
+                size: size.max(0) as usize,
+                overlap: overlap.max(0) as usize,
+            })
+    }
+
     /// Search the knowledge base using BM25 ranking.
     pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
         let limit = limit.min(10); // Max 10 results
@@ -265,21 +328,64 @@ impl KnowledgeStore {
         crate::vector_store::store_embedding(&self.conn, doc_id, chunk_idx, embedding)
     }
 
-    /// Get all chunk texts that are missing embeddings (for batch embedding).
-    pub fn chunks_without_embeddings(&self) -> Vec<(String, String, String)> {
+    /// Up to `limit` chunk texts that are missing embeddings (for batch embedding).
+    pub fn chunks_without_embeddings(&self, limit: usize) -> Vec<(String, String, String)> {
         self.enable_vectors().ok();
         let mut stmt = match self.conn.prepare(
             "SELECT c.doc_id, c.chunk_idx, c.content FROM chunks c
              LEFT JOIN chunk_embeddings ce ON ce.doc_id = c.doc_id AND ce.chunk_idx = c.chunk_idx
-             WHERE ce.embedding IS NULL"
+             WHERE ce.embedding IS NULL
+             LIMIT ?1"
         ) {
             Ok(s) => s,
             Err(_) => return Vec::new(),
         };
-        stmt.query_map([], |row| {
+        stmt.query_map([limit as i64], |row| {
             Ok((row.get(0)?, row.get(1)?, row.get(2)?))
         })
         .map(|rows| rows.filter_map(|r| r.ok()).collect())
         .unwrap_or_default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunker::SplitMode;
+
+    #[test]
+    fn test_chunk_options_stored_per_document() {
+        let store = KnowledgeStore::open(Path::new(":memory:")).unwrap();
+        let text = "Returns are accepted within 30 days. ".repeat(40);
+        let options = ChunkOptions {
+            mode: SplitMode::Sentences,
+            size: 300,
+            overlap: 60,
+        };
+        let chunks = store.add_text("returns", &text, "api", &options).unwrap();
+        let plain = store
+            .add_text("returns-plain", &text, "api", &ChunkOptions::default())
+            .unwrap();
+        assert!(chunks > plain);
+
+        let docs = store.list_documents();
+        assert_eq!(store.chunk_options(docs[1].0), Some(options));
+        assert_eq!(store.chunk_options(docs[0].0), Some(ChunkOptions::default()));
+        assert_eq!(store.chunk_options(999), None);
+    }
+
+    #[test]
+    fn test_chunks_without_embeddings_limited() {
+        let store = KnowledgeStore::open(Path::new(":memory:")).unwrap();
+        let text = "Returns are accepted within 30 days. ".repeat(40);
+        let options = ChunkOptions {
+            mode: SplitMode::Sentences,
+            size: 300,
+            overlap: 60,
+        };
+        let chunks = store.add_text("returns", &text, "api", &options).unwrap();
+        assert!(chunks > 2);
+        assert_eq!(store.chunks_without_embeddings(2).len(), 2);
+        assert_eq!(store.chunks_without_embeddings(usize::MAX).len(), chunks);
+    }
+}
@@ -62,11 +62,13 @@ impl Tool for McpToolBridge {
                 tool_call_id: String::new(),
                 output,
                 success: true,
+                data: None,
             }),
             Err(e) => Ok(ToolResult {
                 tool_call_id: String::new(),
                 output: format!("MCP tool error: {e}"),
                 success: false,
+                data: None,
             }),
         }
     }
@@ -3,6 +3,7 @@
 //! This enables connecting to MCP servers that expose an SSE endpoint
 //! instead of using stdio. Useful for remote MCP servers.
 
+use bizclaw_core::text::truncate_chars;
 use std::collections::HashMap;
 
 use crate::types::{JsonRpcRequest, JsonRpcResponse};
@@ -108,7 +109,7 @@ impl SseTransport {
         }
 
         // Try parsing the entire body as JSON
-        let truncated = &body[..body.len().min(200)];
+        let truncated = truncate_chars(&body, 200);
         serde_json::from_str::<JsonRpcResponse>(&body)
             .map_err(|e| format!("SSE parse error: {e} — body: {truncated}"))
     }
@@ -187,7 +188,7 @@ impl HttpTransport {
         if !response.status().is_success() {
             let status = response.status();
             let body: String = response.text().await.unwrap_or_default();
-            let truncated = &body[..body.len().min(500)];
+            let truncated = truncate_chars(&body, 500);
             return Err(format!("HTTP {} — {}", status, truncated));
         }
 
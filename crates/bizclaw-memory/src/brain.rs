@@ -20,6 +20,7 @@
 //! ```
 
 use bizclaw_core::error::Result;
+use bizclaw_core::text::truncate_chars;
 use std::path::{Path, PathBuf};
 
 /// Brain workspace — reads MD files to assemble dynamic system prompt.
@@ -111,9 +112,9 @@ impl BrainWorkspace {
             Self::collect_context_tree(&context_tree_dir, &mut ctx_content, &mut files_loaded);
 
             if !ctx_content.is_empty() {
-                // Limit to prevent context window overflow (max ~4KB from context tree)
+                // Limit to prevent context window overflow (max 4096 chars from context tree)
                 let truncated = if ctx_content.len() > 4096 {
-                    format!("{}...\n(truncated — {} total files)", &ctx_content[..4096], files_loaded)
+                    format!("{}...\n(truncated — {} total files)", truncate_chars(&ctx_content, 4096), files_loaded)
                 } else {
                     ctx_content
                 };
@@ -0,0 +1,91 @@
+//! Conformance checks every persistent [`MemoryBackend`] must pass.
+//!
+//! Backends call [`run`] from their own tests with a fresh, empty store.
+
+use bizclaw_core::traits::memory::{MemoryBackend, MemoryEntry};
+use chrono::{Duration, Utc};
+
+/// An entry created `id`-ordered in time, so list order is deterministic.
+pub fn entry(id: &str, content: &str) -> MemoryEntry {
+    let offset: i64 = id.bytes().map(i64::from).sum();
+    let at = Utc::now() - Duration::days(30) + Duration::seconds(offset);
+    MemoryEntry {
+        id: id.to_string(),
+        content: content.to_string(),
+        metadata: serde_json::json!({ "session_id": "conformance" }),
+        embedding: None,
+        created_at: at,
+        updated_at: at,
+    }
+}
+
+/// Store, recall, search, list, delete and clear against an empty backend.
+pub async fn run(backend: &dyn MemoryBackend) {
+    assert!(
+        backend.list(None).await.unwrap().is_empty(),
+        "backend must start empty"
+    );
+
+    // Store / recall keeps content, metadata and timestamps.
+    let first = entry("a1", "Customer Lan ordered 20 boxes of green tea");
+    backend.save(first.clone()).await.unwrap();
+    let got = backend
+        .get("a1")
+        .await
+        .unwrap()
+        .expect("saved entry is recalled");
+    assert_eq!(got.content, first.content);
+    assert_eq!(got.metadata["session_id"], "conformance");
+    assert_eq!(got.created_at.timestamp(), first.created_at.timestamp());
+    assert!(backend.get("missing").await.unwrap().is_none());
+
+    // Saving the same id replaces the entry instead of duplicating it.
+    backend
+        .save(entry("a1", "Customer Lan ordered 25 boxes of green tea"))
+        .await
+        .unwrap();
+    let got = backend.get("a1").await.unwrap().unwrap();
+    assert!(got.content.contains("25 boxes"));
+    let hits = backend.search("green tea", 10).await.unwrap();
+    assert_eq!(hits.len(), 1, "an update must not leave a stale search hit");
+    assert!(hits[0].entry.content.contains("25 boxes"));
+
+    backend
+        .save(entry("a2", "Shipping to Da Nang takes two days"))
+        .await
+        .unwrap();
+    backend
+        .save(entry("a3", "Lan prefers delivery before noon"))
+        .await
+        .unwrap();
+
+    // Keyword search is case-insensitive and only returns matches.
+    let hits = backend.search("LAN", 10).await.unwrap();
+    let mut ids: Vec<&str> = hits.iter().map(|h| h.entry.id.as_str()).collect();
+    ids.sort();
+    assert_eq!(ids, vec!["a1", "a3"]);
+    assert!(hits.iter().all(|h| h.score > 0.0));
+    assert!(backend.search("durian", 10).await.unwrap().is_empty());
+    assert_eq!(backend.search("lan", 1).await.unwrap().len(), 1);
+
+    // List is newest first and honours the limit.
+    let listed: Vec<String> = backend
+        .list(None)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.id)
+        .collect();
+    assert_eq!(listed, vec!["a3", "a2", "a1"]);
+    assert_eq!(backend.list(Some(2)).await.unwrap().len(), 2);
+
+    // Delete removes the entry from recall and search.
+    backend.delete("a3").await.unwrap();
+    assert!(backend.get("a3").await.unwrap().is_none());
+    let hits = backend.search("noon", 10).await.unwrap();
+    assert!(hits.is_empty());
+
+    backend.clear().await.unwrap();
+    assert!(backend.list(None).await.unwrap().is_empty());
+    assert!(backend.search("shipping", 10).await.unwrap().is_empty());
+}
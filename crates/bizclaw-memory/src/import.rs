@@ -0,0 +1,125 @@
+//! Bulk import of memory entries from JSONL.
+//!
+//! One entry per line: `{"content": "...", "id"?: "...", "kind"?: "...",
+//! "session_id"?: "...", "metadata"?: {...}}`. Entries are upserted: an
+//! entry with an existing id replaces it (keeping its creation time), and an
+//! entry without an id gets one derived from its content, so importing the
+//! same file twice does not duplicate anything.
+
+use bizclaw_core::traits::MemoryBackend;
+use bizclaw_core::traits::memory::MemoryEntry;
+use bizclaw_core::types::{
+    ImportReport, JsonRecord, jsonl_record_count, jsonl_records, record_str,
+};
+
+/// Import the JSONL `input` into `memory`. `progress` is called with
+/// (records processed, total records) after each record.
+pub async fn import_jsonl(
+    memory: &dyn MemoryBackend,
+    input: &str,
+    mut progress: impl FnMut(usize, usize),
+) -> ImportReport {
+    let total = jsonl_record_count(input);
+    let mut report = ImportReport::default();
+    for (line, record) in jsonl_records(input) {
+        let stored = match record.and_then(|r| to_entry(&r)) {
+            Ok(entry) => upsert(memory, entry).await,
+            Err(e) => Err(e),
+        };
+        match stored {
+            Ok(()) => report.imported += 1,
+            Err(e) => report.fail(line, e),
+        }
+        progress(report.processed(), total);
+    }
+    report
+}
+
+fn to_entry(record: &JsonRecord) -> Result<MemoryEntry, String> {
+    let content = record_str(record, "content").ok_or("missing `content`")?;
+    let mut metadata = match record.get("metadata") {
+        None | Some(serde_json::Value::Null) => serde_json::Map::new(),
+        Some(serde_json::Value::Object(map)) => map.clone(),
+        Some(_) => return Err("`metadata` must be an object".into()),
+    };
+    for key in ["kind", "session_id"] {
+        if let Some(value) = record_str(record, key) {
+            metadata.insert(key.into(), value.into());
+        }
+    }
+    metadata.insert("source".into(), "import".into());
+    let id = record_str(record, "id")
+        .map(str::to_string)
+        .unwrap_or_else(|| content_id(content));
+    let now = chrono::Utc::now();
+    Ok(MemoryEntry {
+        id,
+        content: content.to_string(),
+        metadata: serde_json::Value::Object(metadata),
+        embedding: None,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+async fn upsert(memory: &dyn MemoryBackend, mut entry: MemoryEntry) -> Result<(), String> {
+    if let Ok(Some(existing)) = memory.get(&entry.id).await {
+        entry.created_at = existing.created_at;
+    }
+    memory.save(entry).await.map_err(|e| e.to_string())
+}
+
+/// Stable id for an entry without one (FNV-1a of the content).
+fn content_id(content: &str) -> String {
+    let hash = content.bytes().fold(0xcbf29ce484222325u64, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("import-{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::SqliteMemory;
+
+    #[tokio::test]
+    async fn test_import_skips_malformed_line() {
+        let memory = SqliteMemory::in_memory().unwrap();
+        let input = r#"{"content": "Customer prefers email", "kind": "personal"}
+{"content": "broken
+{"id": "faq-1", "content": "Shop opens at 8am"}
+{"metadata": {"kind": "task"}}
+"#;
+        let mut calls = Vec::new();
+        let report = import_jsonl(&memory, input, |done, total| calls.push((done, total))).await;
+
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.failures.len(), 2);
+        assert_eq!(report.failures[0].line, 2);
+        assert!(report.failures[0].error.starts_with("invalid JSON"));
+        assert_eq!(report.failures[1].line, 4);
+        assert_eq!(report.failures[1].error, "missing `content`");
+        assert_eq!(calls, [(1, 4), (2, 4), (3, 4), (4, 4)]);
+
+        let faq = memory.get("faq-1").await.unwrap().unwrap();
+        assert_eq!(faq.content, "Shop opens at 8am");
+        let personal = memory.search("email", 5).await.unwrap();
+        assert_eq!(personal[0].entry.metadata["kind"], "personal");
+    }
+
+    #[tokio::test]
+    async fn test_reimport_upserts() {
+        let memory = SqliteMemory::in_memory().unwrap();
+        let input = "{\"content\": \"Likes green tea\"}\n{\"id\": \"x\", \"content\": \"v1\"}";
+        import_jsonl(&memory, input, |_, _| {}).await;
+        let report = import_jsonl(
+            &memory,
+            "{\"content\": \"Likes green tea\"}\n{\"id\": \"x\", \"content\": \"v2\"}",
+            |_, _| {},
+        )
+        .await;
+        assert_eq!(report.imported, 2);
+        assert_eq!(memory.list(None).await.unwrap().len(), 2);
+        assert_eq!(memory.get("x").await.unwrap().unwrap().content, "v2");
+    }
+}
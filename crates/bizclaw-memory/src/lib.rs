@@ -2,6 +2,9 @@
 //! Memory and persistence backends with 3-tier brain architecture
 
 pub mod brain;
+#[cfg(test)]
+mod conformance;
+pub mod import;
 pub mod noop;
 pub mod sqlite;
 pub mod vector;
@@ -1,24 +1,89 @@
 //! SQLite memory backend with FTS5 full-text search and session support.
+//!
+//! Standalone agents get the same four memory types as the platform's ReMe
+//! layer — personal facts, task lessons, tool tips and working summaries —
+//! alongside plain conversation memories. The type comes from
+//! `metadata["kind"]` (see [`MemoryKind`]) and every type shares one keyword
+//! index, so [`MemoryBackend::search`] covers all of them while
+//! [`SqliteMemory::search_kind`] narrows to one.
 
 use async_trait::async_trait;
-use bizclaw_core::error::Result;
-use bizclaw_core::traits::memory::{MemoryBackend, MemoryEntry, MemorySearchResult};
+use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::traits::memory::{MemoryBackend, MemoryEntry, MemorySearchResult, ToolUsage};
 use rusqlite::Connection;
+use std::path::Path;
 use std::sync::Mutex;
 
+/// Memory type, matching the platform's ReMe memory tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryKind {
+    /// Turn-by-turn conversation memories (the default).
+    Conversation,
+    /// User preferences and learned facts.
+    Personal,
+    /// Approaches and lessons from past tasks.
+    Task,
+    /// Tool usage tips.
+    Tool,
+    /// Session summaries.
+    Working,
+}
+
+impl MemoryKind {
+    pub const ALL: [MemoryKind; 5] = [
+        MemoryKind::Conversation,
+        MemoryKind::Personal,
+        MemoryKind::Task,
+        MemoryKind::Tool,
+        MemoryKind::Working,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Conversation => "conversation",
+            Self::Personal => "personal",
+            Self::Task => "task",
+            Self::Tool => "tool",
+            Self::Working => "working",
+        }
+    }
+
+    /// Kind named in an entry's metadata; unknown or missing means conversation.
+    pub fn of(entry: &MemoryEntry) -> Self {
+        let kind = entry.metadata.get("kind").and_then(|v| v.as_str());
+        Self::ALL
+            .into_iter()
+            .find(|k| Some(k.as_str()) == kind)
+            .unwrap_or(Self::Conversation)
+    }
+}
+
 pub struct SqliteMemory {
     conn: Mutex<Connection>,
 }
 
 impl SqliteMemory {
+    /// Open the default database at `~/.bizclaw/memory.db`.
     pub fn new() -> Result<Self> {
-        let db_path = bizclaw_core::config::BizClawConfig::home_dir().join("memory.db");
-        if let Some(parent) = db_path.parent() {
+        Self::open(&bizclaw_core::config::BizClawConfig::home_dir().join("memory.db"))
+    }
+
+    /// Open or create a memory database at `path`.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        let conn = Connection::open(&db_path)
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+        let conn = Connection::open(path).map_err(|e| BizClawError::Memory(e.to_string()))?;
+        Self::init(conn)
+    }
 
+    /// Open an in-memory database (for tests).
+    pub fn in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory().map_err(|e| BizClawError::Memory(e.to_string()))?;
+        Self::init(conn)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
         // Main table with session support
         conn.execute_batch(
             "CREATE TABLE IF NOT EXISTS memories (
@@ -31,11 +96,15 @@ impl SqliteMemory {
                 updated_at TEXT NOT NULL
             );",
         )
-        .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+        .map_err(|e| BizClawError::Memory(e.to_string()))?;
 
-        // Add session_id column if missing (migration from old schema)
+        // Add columns if missing (migration from old schema)
         conn.execute_batch("ALTER TABLE memories ADD COLUMN session_id TEXT DEFAULT 'default';")
             .ok(); // Silently ignore if column already exists
+        conn.execute_batch("ALTER TABLE memories ADD COLUMN kind TEXT DEFAULT 'conversation';")
+            .ok();
+        conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_memories_kind ON memories(kind);")
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
 
         // FTS5 virtual table for fast full-text search with BM25 ranking
         conn.execute_batch(
@@ -45,7 +114,7 @@ impl SqliteMemory {
                 tokenize='unicode61'
             );",
         )
-        .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+        .map_err(|e| BizClawError::Memory(e.to_string()))?;
 
         // Sessions table for tracking conversation threads
         conn.execute_batch(
@@ -58,7 +127,22 @@ impl SqliteMemory {
                 summary TEXT DEFAULT ''
             );",
         )
-        .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+        .map_err(|e| BizClawError::Memory(e.to_string()))?;
+
+        // Per-tool call stats (mirrors the platform's memory_tool table)
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS memory_tool (
+                tool_name TEXT PRIMARY KEY,
+                usage_count INTEGER NOT NULL DEFAULT 0,
+                success_count INTEGER NOT NULL DEFAULT 0,
+                failure_count INTEGER NOT NULL DEFAULT 0,
+                avg_duration_ms INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                tips TEXT,
+                last_used TEXT NOT NULL
+            );",
+        )
+        .map_err(|e| BizClawError::Memory(e.to_string()))?;
 
         // Ensure default session exists
         conn.execute(
@@ -104,69 +188,54 @@ impl SqliteMemory {
         let conn = self
             .conn
             .lock()
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
         conn.execute(
             "INSERT OR IGNORE INTO sessions (id, name) VALUES (?1, ?2)",
             rusqlite::params![id, name],
         )
-        .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+        .map_err(|e| BizClawError::Memory(e.to_string()))?;
         Ok(())
     }
-}
 
-#[async_trait]
-impl MemoryBackend for SqliteMemory {
-    fn name(&self) -> &str {
-        "sqlite"
+    /// Keyword search limited to one memory type.
+    pub fn search_kind(
+        &self,
+        kind: MemoryKind,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<MemorySearchResult>> {
+        self.search_filtered(query, limit, Some(kind))
     }
 
-    async fn save(&self, entry: MemoryEntry) -> Result<()> {
+    /// Most recent memories of one type.
+    pub fn list_kind(&self, kind: MemoryKind, limit: usize) -> Result<Vec<MemoryEntry>> {
         let conn = self
             .conn
             .lock()
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
-
-        // Extract session_id from metadata or use default
-        let session_id = entry
-            .metadata
-            .get("session_id")
-            .and_then(|v| v.as_str())
-            .unwrap_or("default")
-            .to_string();
-
-        conn.execute(
-            "INSERT OR REPLACE INTO memories (id, session_id, content, metadata, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            rusqlite::params![
-                entry.id,
-                session_id,
-                entry.content,
-                entry.metadata.to_string(),
-                entry.created_at.to_rfc3339(),
-                entry.updated_at.to_rfc3339(),
-            ],
-        ).map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
-
-        // Index in FTS5 for fast search
-        conn.execute(
-            "INSERT OR REPLACE INTO memories_fts (id, content) VALUES (?1, ?2)",
-            rusqlite::params![entry.id, entry.content],
-        )
-        .ok(); // Don't fail on FTS insert error
-
-        // Update session message count
-        conn.execute(
-            "UPDATE sessions SET message_count = message_count + 1, updated_at = datetime('now') WHERE id = ?1",
-            rusqlite::params![session_id],
-        ).ok();
-
-        Ok(())
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, content, metadata, created_at, updated_at FROM memories
+                 WHERE kind = ?1 ORDER BY created_at DESC LIMIT ?2",
+            )
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
+        let rows = stmt
+            .query_map(rusqlite::params![kind.as_str(), limit as i64], row_to_entry)
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
     }
 
-    async fn search(&self, query: &str, limit: usize) -> Result<Vec<MemorySearchResult>> {
+    fn search_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        kind: Option<MemoryKind>,
+    ) -> Result<Vec<MemorySearchResult>> {
         let conn = self
             .conn
             .lock()
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
+        let kind = kind.map(|k| k.as_str());
 
         // Clean query for FTS5
         let clean_query: String = query
@@ -179,55 +248,28 @@ impl MemoryBackend for SqliteMemory {
         }
 
         // Try FTS5 search first (faster, better ranking)
-        let fts_results = {
-            let mut stmt = conn.prepare(
-                "SELECT f.id, m.content, m.metadata, m.created_at, m.updated_at, bm25(memories_fts) as score
+        let fts_results: Vec<MemorySearchResult> = conn
+            .prepare(
+                "SELECT m.id, m.content, m.metadata, m.created_at, m.updated_at,
+                        bm25(memories_fts) as score
                  FROM memories_fts f
                  JOIN memories m ON m.id = f.id
-                 WHERE memories_fts MATCH ?1
+                 WHERE memories_fts MATCH ?1 AND (?3 IS NULL OR m.kind = ?3)
                  ORDER BY score
-                 LIMIT ?2"
-            );
-            match stmt {
-                Ok(ref mut s) => {
-                    let rows = s.query_map(rusqlite::params![clean_query, limit as i64], |row| {
+                 LIMIT ?2",
+            )
+            .and_then(|mut stmt| {
+                let rows =
+                    stmt.query_map(rusqlite::params![clean_query, limit as i64, kind], |row| {
                         Ok(MemorySearchResult {
-                            entry: MemoryEntry {
-                                id: row.get(0)?,
-                                content: row.get(1)?,
-                                metadata: row
-                                    .get::<_, String>(2)
-                                    .map(|s| serde_json::from_str(&s).unwrap_or_default())
-                                    .unwrap_or_default(),
-                                embedding: None,
-                                created_at: row
-                                    .get::<_, String>(3)
-                                    .map(|s| {
-                                        chrono::DateTime::parse_from_rfc3339(&s)
-                                            .map(|d| d.with_timezone(&chrono::Utc))
-                                            .unwrap_or_default()
-                                    })
-                                    .unwrap_or_default(),
-                                updated_at: row
-                                    .get::<_, String>(4)
-                                    .map(|s| {
-                                        chrono::DateTime::parse_from_rfc3339(&s)
-                                            .map(|d| d.with_timezone(&chrono::Utc))
-                                            .unwrap_or_default()
-                                    })
-                                    .unwrap_or_default(),
-                            },
-                            score: row.get::<_, f32>(5).unwrap_or(0.0).abs(), // BM25 returns negative scores
+                            entry: row_to_entry(row)?,
+                            // BM25 returns negative scores
+                            score: row.get::<_, f32>(5).unwrap_or(0.0).abs(),
                         })
-                    });
-                    match rows {
-                        Ok(r) => r.filter_map(|r| r.ok()).collect::<Vec<_>>(),
-                        Err(_) => Vec::new(),
-                    }
-                }
-                Err(_) => Vec::new(),
-            }
-        };
+                    })?;
+                Ok(rows.filter_map(|r| r.ok()).collect())
+            })
+            .unwrap_or_default();
 
         // If FTS5 returned results, use them
         if !fts_results.is_empty() {
@@ -235,41 +277,19 @@ impl MemoryBackend for SqliteMemory {
         }
 
         // Fallback to LIKE search (for queries that don't work well with FTS5)
-        let mut stmt = conn.prepare(
-            "SELECT id, content, metadata, created_at, updated_at FROM memories WHERE content LIKE ?1 ORDER BY created_at DESC LIMIT ?2"
-        ).map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, content, metadata, created_at, updated_at FROM memories
+                 WHERE content LIKE ?1 AND (?3 IS NULL OR kind = ?3)
+                 ORDER BY created_at DESC LIMIT ?2",
+            )
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
 
         let pattern = format!("%{}%", query.to_lowercase());
         let query_lower = query.to_lowercase();
         let rows = stmt
-            .query_map(rusqlite::params![pattern, limit], |row| {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    content: row.get(1)?,
-                    metadata: row
-                        .get::<_, String>(2)
-                        .map(|s| serde_json::from_str(&s).unwrap_or_default())
-                        .unwrap_or_default(),
-                    embedding: None,
-                    created_at: row
-                        .get::<_, String>(3)
-                        .map(|s| {
-                            chrono::DateTime::parse_from_rfc3339(&s)
-                                .map(|d| d.with_timezone(&chrono::Utc))
-                                .unwrap_or_default()
-                        })
-                        .unwrap_or_default(),
-                    updated_at: row
-                        .get::<_, String>(4)
-                        .map(|s| {
-                            chrono::DateTime::parse_from_rfc3339(&s)
-                                .map(|d| d.with_timezone(&chrono::Utc))
-                                .unwrap_or_default()
-                        })
-                        .unwrap_or_default(),
-                })
-            })
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+            .query_map(rusqlite::params![pattern, limit as i64, kind], row_to_entry)
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
 
         let results: Vec<MemorySearchResult> = rows
             .filter_map(|r| r.ok())
@@ -285,33 +305,100 @@ impl MemoryBackend for SqliteMemory {
             .collect();
         Ok(results)
     }
+}
+
+/// Map `id, content, metadata, created_at, updated_at` columns to an entry.
+fn row_to_entry(row: &rusqlite::Row<'_>) -> rusqlite::Result<MemoryEntry> {
+    Ok(MemoryEntry {
+        id: row.get(0)?,
+        content: row.get(1)?,
+        metadata: row
+            .get::<_, String>(2)
+            .map(|s| serde_json::from_str(&s).unwrap_or_default())
+            .unwrap_or_default(),
+        embedding: None,
+        created_at: parse_time(row.get::<_, String>(3).unwrap_or_default()),
+        updated_at: parse_time(row.get::<_, String>(4).unwrap_or_default()),
+    })
+}
+
+fn parse_time(s: String) -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::parse_from_rfc3339(&s)
+        .map(|d| d.with_timezone(&chrono::Utc))
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl MemoryBackend for SqliteMemory {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    async fn save(&self, entry: MemoryEntry) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
+
+        // Extract session_id from metadata or use default
+        let session_id = entry
+            .metadata
+            .get("session_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("default")
+            .to_string();
+
+        conn.execute(
+            "INSERT OR REPLACE INTO memories (id, session_id, kind, content, metadata, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                entry.id,
+                session_id,
+                MemoryKind::of(&entry).as_str(),
+                entry.content,
+                entry.metadata.to_string(),
+                entry.created_at.to_rfc3339(),
+                entry.updated_at.to_rfc3339(),
+            ],
+        ).map_err(|e| BizClawError::Memory(e.to_string()))?;
+
+        // Index in FTS5 for fast search (FTS5 has no unique key, so drop any
+        // stale row for this id first)
+        conn.execute(
+            "DELETE FROM memories_fts WHERE id = ?1",
+            rusqlite::params![entry.id],
+        )
+        .ok();
+        conn.execute(
+            "INSERT INTO memories_fts (id, content) VALUES (?1, ?2)",
+            rusqlite::params![entry.id, entry.content],
+        )
+        .ok(); // Don't fail on FTS insert error
+
+        // Update session message count
+        conn.execute(
+            "UPDATE sessions SET message_count = message_count + 1, updated_at = datetime('now') WHERE id = ?1",
+            rusqlite::params![session_id],
+        ).ok();
+
+        Ok(())
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<MemorySearchResult>> {
+        self.search_filtered(query, limit, None)
+    }
 
     async fn get(&self, id: &str) -> Result<Option<MemoryEntry>> {
         let conn = self
             .conn
             .lock()
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
         let mut stmt = conn
             .prepare(
                 "SELECT id, content, metadata, created_at, updated_at FROM memories WHERE id = ?1",
             )
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
-
-        let result = stmt
-            .query_row(rusqlite::params![id], |row| {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    content: row.get(1)?,
-                    metadata: row
-                        .get::<_, String>(2)
-                        .map(|s| serde_json::from_str(&s).unwrap_or_default())
-                        .unwrap_or_default(),
-                    embedding: None,
-                    created_at: chrono::Utc::now(),
-                    updated_at: chrono::Utc::now(),
-                })
-            })
-            .ok();
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
+
+        let result = stmt.query_row(rusqlite::params![id], row_to_entry).ok();
         Ok(result)
     }
 
@@ -319,9 +406,9 @@ impl MemoryBackend for SqliteMemory {
         let conn = self
             .conn
             .lock()
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
         conn.execute("DELETE FROM memories WHERE id = ?1", rusqlite::params![id])
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
         conn.execute(
             "DELETE FROM memories_fts WHERE id = ?1",
             rusqlite::params![id],
@@ -334,41 +421,15 @@ impl MemoryBackend for SqliteMemory {
         let conn = self
             .conn
             .lock()
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
         let lim = limit.unwrap_or(100) as i64;
         let mut stmt = conn.prepare(
             "SELECT id, content, metadata, created_at, updated_at FROM memories ORDER BY created_at DESC LIMIT ?1"
-        ).map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+        ).map_err(|e| BizClawError::Memory(e.to_string()))?;
 
         let results = stmt
-            .query_map(rusqlite::params![lim], |row| {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    content: row.get(1)?,
-                    metadata: row
-                        .get::<_, String>(2)
-                        .map(|s| serde_json::from_str(&s).unwrap_or_default())
-                        .unwrap_or_default(),
-                    embedding: None,
-                    created_at: row
-                        .get::<_, String>(3)
-                        .map(|s| {
-                            chrono::DateTime::parse_from_rfc3339(&s)
-                                .map(|d| d.with_timezone(&chrono::Utc))
-                                .unwrap_or_default()
-                        })
-                        .unwrap_or_default(),
-                    updated_at: row
-                        .get::<_, String>(4)
-                        .map(|s| {
-                            chrono::DateTime::parse_from_rfc3339(&s)
-                                .map(|d| d.with_timezone(&chrono::Utc))
-                                .unwrap_or_default()
-                        })
-                        .unwrap_or_default(),
-                })
-            })
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+            .query_map(rusqlite::params![lim], row_to_entry)
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
 
         Ok(results.filter_map(|r| r.ok()).collect())
     }
@@ -377,10 +438,161 @@ impl MemoryBackend for SqliteMemory {
         let conn = self
             .conn
             .lock()
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
         conn.execute("DELETE FROM memories", [])
-            .map_err(|e| bizclaw_core::error::BizClawError::Memory(e.to_string()))?;
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
         conn.execute("DELETE FROM memories_fts", []).ok();
         Ok(())
     }
+
+    async fn record_tool_usage(
+        &self,
+        tool_name: &str,
+        success: bool,
+        duration_ms: u64,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
+        let (ok, failed) = if success { (1, 0) } else { (0, 1) };
+        conn.execute(
+            "INSERT INTO memory_tool
+                (tool_name, usage_count, success_count, failure_count, avg_duration_ms,
+                 last_error, last_used)
+             VALUES (?1, 1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(tool_name) DO UPDATE SET
+               usage_count = usage_count + 1,
+               success_count = success_count + ?2,
+               failure_count = failure_count + ?3,
+               avg_duration_ms = (avg_duration_ms * usage_count + ?4) / (usage_count + 1),
+               last_error = COALESCE(?5, last_error),
+               last_used = ?6",
+            rusqlite::params![
+                tool_name,
+                ok,
+                failed,
+                duration_ms as i64,
+                error,
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )
+        .map_err(|e| BizClawError::Memory(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn tool_usage(&self) -> Result<Vec<ToolUsage>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT tool_name, usage_count, success_count, failure_count, avg_duration_ms,
+                        last_error, tips, last_used
+                 FROM memory_tool ORDER BY usage_count DESC, tool_name",
+            )
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(ToolUsage {
+                    tool_name: row.get(0)?,
+                    usage_count: row.get::<_, i64>(1)? as u64,
+                    success_count: row.get::<_, i64>(2)? as u64,
+                    failure_count: row.get::<_, i64>(3)? as u64,
+                    avg_duration_ms: row.get::<_, i64>(4)? as u64,
+                    last_error: row.get(5)?,
+                    tips: row.get(6)?,
+                    last_used: parse_time(row.get(7)?),
+                })
+            })
+            .map_err(|e| BizClawError::Memory(e.to_string()))?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conformance;
+
+    #[tokio::test]
+    async fn test_sqlite_conformance() {
+        conformance::run(&SqliteMemory::in_memory().unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn test_search_by_kind() {
+        let mem = SqliteMemory::in_memory().unwrap();
+        let kinds = [
+            ("k1", "personal", "Customer prefers Zalo for invoices"),
+            ("k2", "tool", "Zalo send tool needs an OA token"),
+            ("k3", "working", "Session summary: invoice follow-up"),
+        ];
+        for (id, kind, content) in kinds {
+            let mut entry = conformance::entry(id, content);
+            entry.metadata = serde_json::json!({ "kind": kind });
+            mem.save(entry).await.unwrap();
+        }
+
+        assert_eq!(mem.search("zalo", 10).await.unwrap().len(), 2);
+        let personal = mem.search_kind(MemoryKind::Personal, "zalo", 10).unwrap();
+        assert_eq!(personal.len(), 1);
+        assert_eq!(personal[0].entry.id, "k1");
+
+        let working = mem.list_kind(MemoryKind::Working, 10).unwrap();
+        assert_eq!(working.len(), 1);
+        assert_eq!(working[0].id, "k3");
+        assert!(mem.list_kind(MemoryKind::Task, 10).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tool_usage_stats() {
+        let mem = SqliteMemory::in_memory().unwrap();
+        assert!(mem.tool_usage().await.unwrap().is_empty());
+
+        mem.record_tool_usage("web_search", true, 100, None)
+            .await
+            .unwrap();
+        mem.record_tool_usage("web_search", false, 300, Some("timeout"))
+            .await
+            .unwrap();
+        mem.record_tool_usage("web_search", true, 200, None)
+            .await
+            .unwrap();
+        mem.record_tool_usage("shell", true, 10, None)
+            .await
+            .unwrap();
+
+        let stats = mem.tool_usage().await.unwrap();
+        assert_eq!(stats.len(), 2);
+        let ws = &stats[0];
+        assert_eq!(ws.tool_name, "web_search");
+        assert_eq!(ws.usage_count, 3);
+        assert_eq!(ws.success_count, 2);
+        assert_eq!(ws.failure_count, 1);
+        assert_eq!(ws.avg_duration_ms, 200);
+        assert_eq!(ws.last_error.as_deref(), Some("timeout"));
+        assert_eq!(stats[1].tool_name, "shell");
+        assert_eq!(stats[1].usage_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reopen_keeps_memories() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.db");
+        {
+            let mem = SqliteMemory::open(&path).unwrap();
+            mem.save(conformance::entry("m1", "Shop opens at 8am"))
+                .await
+                .unwrap();
+        }
+        let mem = SqliteMemory::open(&path).unwrap();
+        assert_eq!(
+            mem.get("m1").await.unwrap().unwrap().content,
+            "Shop opens at 8am"
+        );
+        assert_eq!(mem.search("opens", 5).await.unwrap().len(), 1);
+    }
 }
@@ -81,6 +81,7 @@ impl AdminServer {
             .route("/api/admin/tenants/{id}/start", post(start_tenant))
             .route("/api/admin/tenants/{id}/stop", post(stop_tenant))
             .route("/api/admin/tenants/{id}/restart", post(restart_tenant))
+            .route("/api/admin/maintenance", put(set_maintenance))
             .route("/api/admin/tenants/{id}/pairing", post(reset_pairing))
             // Channel Configuration
             .route("/api/admin/tenants/{id}/channels", get(list_channels))
@@ -108,6 +109,18 @@ impl AdminServer {
                 "/api/admin/tenants/{id}/agents/{name}",
                 delete(delete_tenant_agent),
             )
+            // Tenant Skills
+            .route("/api/admin/tenants/{id}/skills", get(list_tenant_skills))
+            .route("/api/admin/tenants/{id}/skills", post(install_tenant_skill))
+            .route("/api/admin/tenants/{id}/skills/{slug}", delete(delete_tenant_skill))
+            .route("/api/admin/tenants/{id}/skills/{slug}/toggle", post(toggle_tenant_skill))
+            // Tenant Feature Flags
+            .route("/api/admin/tenants/{id}/features", get(list_tenant_features))
+            .route("/api/admin/tenants/{id}/features/{flag}", put(set_tenant_feature))
+            // Tenant Custom Domains
+            .route("/api/admin/tenants/{id}/domains", get(list_tenant_domains).post(add_tenant_domain))
+            .route("/api/admin/tenants/{id}/domains/{domain}", delete(delete_tenant_domain))
+            .route("/api/admin/tenants/{id}/domains/{domain}/verify", post(verify_tenant_domain))
             // Users
             .route("/api/admin/users", get(list_users))
             .route("/api/admin/users", post(create_user_handler))
@@ -132,6 +145,7 @@ impl AdminServer {
             // ── ENTERPRISE: BI Analytics ────────────────────────────────────
             .route("/api/admin/tenants/{id}/analytics/summary", get(analytics_summary))
             .route("/api/admin/tenants/{id}/analytics/tokens", get(analytics_tokens))
+            .route("/api/admin/tenants/{id}/usage", get(tenant_llm_usage))
             // ── ENTERPRISE: Budget Quota Control ───────────────────────────
             .route("/api/admin/tenants/{id}/quotas", get(list_quotas))
             .route("/api/admin/tenants/{id}/quotas/{resource}", put(set_quota))
@@ -164,9 +178,22 @@ impl AdminServer {
             .route("/api/admin/login", post(login))
             .route("/api/admin/pairing/validate", post(validate_pairing))
             .route("/api/admin/register", post(crate::self_serve::register_handler))
+            .route("/api/admin/sandbox", post(crate::self_serve::sandbox_handler))
+            // Sandbox tenants' LLM calls (authenticated by the sandbox's own key)
+            .route(
+                &format!("{}/chat/completions", crate::self_serve::SANDBOX_LLM_BASE),
+                post(crate::self_serve::sandbox_llm_proxy),
+            )
             .route("/api/admin/password-reset", post(crate::self_serve::forgot_password_handler))
             .route("/api/admin/password-reset/confirm", post(crate::self_serve::reset_password_handler))
             .route("/api/admin/invitations/{token}/accept", post(accept_invitation))
+            // Tenant gateways report LLM usage (authenticated by X-Usage-Key)
+            .route("/api/internal/tenants/{id}/usage", post(report_llm_usage))
+            // Orchestrator probes: liveness and readiness
+            .route("/healthz", get(healthz))
+            .route("/readyz", get(readyz))
+            // Prometheus scrape: per-tenant usage and process metrics (scrape token)
+            .route("/metrics", get(prometheus_metrics))
             .route("/pixel-office", get(pixel_office_page))
             .route("/", get(admin_dashboard_page));
 
@@ -210,11 +237,17 @@ impl AdminServer {
             .layer(axum::middleware::from_fn(platform_security_headers))
             .layer(cors)
             .layer(DefaultBodyLimit::max(1_048_576)) // 1MB max request body
+            // Outermost: tenant subdomains never reach the admin routes.
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                crate::routing::subdomain_proxy,
+            ))
             .with_state(state)
     }
 
     /// Start the admin server.
     pub async fn start(state: Arc<AdminState>, port: u16) -> bizclaw_core::error::Result<()> {
+        start_sandbox_reaper(state.clone());
         let app = Self::router(state);
         // Bind to 127.0.0.1 — only accessible via reverse proxy (Nginx)
         // Set BIZCLAW_BIND_ALL=1 to allow direct external access (dev only)
@@ -238,6 +271,86 @@ impl AdminServer {
     }
 }
 
+// ── Sandbox Reaper ──────────────────────────────────
+
+/// How often expired sandbox tenants are looked for.
+const SANDBOX_REAP_INTERVAL_SECS: u64 = 300;
+
+/// Stop and delete every sandbox tenant past its `expires_at`, in SQLite
+/// and (when configured) PostgreSQL, along with its data directory.
+/// Returns the number of tenants removed; the caller re-syncs nginx.
+pub async fn reap_expired_sandboxes(state: &AdminState) -> usize {
+    let expired = match state.db.lock().await.list_expired_sandboxes() {
+        Ok(t) => t,
+        Err(e) => {
+            tracing::error!("[sandbox] Failed to list expired sandboxes: {e}");
+            Vec::new()
+        }
+    };
+    let mut reaped = 0;
+    for tenant in &expired {
+        state.manager.lock().await.stop_tenant(&tenant.id).ok();
+        // Separate lock scopes, as in delete_tenant.
+        let delete_result = state.db.lock().await.delete_tenant(&tenant.id);
+        match delete_result {
+            Ok(()) => {
+                remove_sandbox_data(state, tenant).await;
+                state
+                    .db
+                    .lock().await
+                    .log_event("sandbox_expired", "system", &tenant.id, Some(&tenant.slug))
+                    .ok();
+                reaped += 1;
+            }
+            Err(e) => tracing::error!("[sandbox] Failed to delete {}: {e}", tenant.slug),
+        }
+    }
+    if let Some(pg) = &state.pg_db {
+        let expired = pg.list_expired_sandboxes().await.unwrap_or_else(|e| {
+            tracing::error!("[sandbox] Failed to list expired PG sandboxes: {e}");
+            Vec::new()
+        });
+        for tenant in &expired {
+            state.manager.lock().await.stop_tenant(&tenant.id).ok();
+            match pg.delete_tenant(&tenant.id).await {
+                Ok(()) => {
+                    remove_sandbox_data(state, tenant).await;
+                    pg.log_event("sandbox_expired", "system", &tenant.id, Some(&tenant.slug))
+                        .await
+                        .ok();
+                    reaped += 1;
+                }
+                Err(e) => tracing::error!("[sandbox] Failed to delete {}: {e}", tenant.slug),
+            }
+        }
+    }
+    if reaped > 0 {
+        tracing::info!("🧹 Reaped {reaped} expired sandbox tenant(s)");
+    }
+    reaped
+}
+
+/// Remove a reaped sandbox's data directory.
+async fn remove_sandbox_data(state: &AdminState, tenant: &crate::db::Tenant) {
+    if let Err(e) = state.manager.lock().await.remove_tenant_data(&tenant.slug) {
+        tracing::warn!("[sandbox] {e}");
+    }
+}
+
+/// Run [`reap_expired_sandboxes`] in the background for the server's lifetime.
+fn start_sandbox_reaper(state: Arc<AdminState>) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(tokio::time::Duration::from_secs(SANDBOX_REAP_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            if reap_expired_sandboxes(&state).await > 0 {
+                sync_nginx_routing(&state).await;
+            }
+        }
+    });
+}
+
 // ── Security Headers (C1 FIX) ──────────────────────────
 
 /// Security headers middleware — HSTS, CSP, X-Frame-Options, X-Content-Type-Options.
@@ -268,6 +381,102 @@ fn internal_error(context: &str, e: impl std::fmt::Display) -> Json<serde_json::
     Json(serde_json::json!({"ok": false, "error": "An internal error occurred"}))
 }
 
+// ── Health Probes ──────────────────────────────────
+
+/// Liveness — 200 whenever the server is serving requests.
+async fn healthz() -> Json<serde_json::Value> {
+    Json(serde_json::json!({"ok": true, "status": "alive"}))
+}
+
+/// Readiness — 200 once the database is reachable and migrated, 503 otherwise.
+async fn readyz(
+    State(state): State<Arc<AdminState>>,
+) -> (axum::http::StatusCode, Json<serde_json::Value>) {
+    let db = state.db.lock().await.check_ready();
+    let pg = match &state.pg_db {
+        Some(pg) => Some(pg.check_ready().await),
+        None => None,
+    };
+    readiness(db, pg)
+}
+
+/// Readiness response from the SQLite check and the optional PostgreSQL check.
+fn readiness(
+    db: bizclaw_core::error::Result<()>,
+    pg: Option<bizclaw_core::error::Result<()>>,
+) -> (axum::http::StatusCode, Json<serde_json::Value>) {
+    let mut checks = serde_json::json!({"db": check_status(&db)});
+    if let Some(pg) = &pg {
+        checks["postgres"] = check_status(pg);
+    }
+    let ready = db.is_ok() && pg.as_ref().is_none_or(|r| r.is_ok());
+    if !ready {
+        tracing::warn!("[readyz] not ready: {checks}");
+    }
+    let status = if ready {
+        axum::http::StatusCode::OK
+    } else {
+        axum::http::StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(serde_json::json!({"ok": ready, "checks": checks})))
+}
+
+fn check_status(result: &bizclaw_core::error::Result<()>) -> serde_json::Value {
+    match result {
+        Ok(()) => serde_json::json!("ok"),
+        Err(e) => serde_json::json!(e.to_string()),
+    }
+}
+
+// ── Metrics ────────────────────────────────────────
+
+/// Prometheus metrics — per-tenant LLM usage and process gauges, labeled by slug.
+/// Requires `Authorization: Bearer` with the scrape token or a super-admin JWT.
+async fn prometheus_metrics(
+    State(state): State<Arc<AdminState>>,
+    headers: axum::http::HeaderMap,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+    let bearer = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+    let scrape_token = std::env::var(crate::metrics::TOKEN_ENV).ok();
+    let allowed = scrape_token
+        .is_some_and(|token| bizclaw_channels::webhook::verify_shared_secret(&token, Some(bearer)))
+        || crate::auth::validate_token(bearer, &state.jwt_secret)
+            .is_ok_and(|claims| is_super_admin(&claims));
+    if !allowed {
+        return axum::http::StatusCode::UNAUTHORIZED.into_response();
+    }
+    let data = {
+        let db = state.db.lock().await;
+        db.list_tenants()
+            .and_then(|tenants| db.llm_usage_totals().map(|usage| (tenants, usage)))
+    };
+    let (tenants, usage) = match data {
+        Ok(data) => data,
+        Err(e) => {
+            tracing::error!("[metrics] {e}");
+            return axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let (running, events) = {
+        let mut manager = state.manager.lock().await;
+        manager.reap_crashed();
+        (
+            manager.running_tenant_ids().into_iter().collect(),
+            manager.process_events().clone(),
+        )
+    };
+    (
+        [(axum::http::header::CONTENT_TYPE, crate::metrics::CONTENT_TYPE)],
+        crate::metrics::render(&tenants, &usage, &running, &events),
+    )
+        .into_response()
+}
+
 // ── Nginx Sync ─────────────────────────────────────
 
 
@@ -280,7 +489,7 @@ fn internal_error(context: &str, e: impl std::fmt::Display) -> Json<serde_json::
 ///   Default: uses the domain name. Example: `NGINX_SSL_CERT_DIR=bizclaw.vn-0001`
 /// - `NGINX_CONTAINER_NAME`: Docker container name for nginx. Default: `bizclaw-nginx`
 /// - `BIZCLAW_BIND_ALL`: If `1`, upstream connects to Docker service hostname; else `127.0.0.1`
-async fn sync_nginx_routing(state: &AdminState) {
+pub(crate) async fn sync_nginx_routing(state: &AdminState) {
     let domain = state.domain.clone();
     let tenants = match state.db.lock().await.list_tenants() {
         Ok(t) => t,
@@ -588,12 +797,17 @@ async fn create_tenant(
         return Json(serde_json::json!({"ok": false, "error": "Không có quyền tạo tenant. Liên hệ admin để nâng cấp role."}));
     }
 
-    // Sanitize slug: only ASCII alphanumeric + hyphens allowed
-    let clean_slug = crate::self_serve::generate_safe_slug(&req.slug);
-    let slug = if clean_slug.is_empty() { 
-        crate::self_serve::generate_safe_slug(&req.name) 
-    } else { 
-        clean_slug 
+    // An explicit slug must already be a valid subdomain; otherwise derive one from the name
+    let requested = req.slug.trim().to_lowercase();
+    let slug = if requested.is_empty() {
+        crate::self_serve::generate_safe_slug(&req.name)
+    } else {
+        if let Err(bizclaw_core::error::BizClawError::Config(msg)) =
+            crate::db::validate_tenant_slug(&requested)
+        {
+            return Json(serde_json::json!({"ok": false, "error": msg}));
+        }
+        requested
     };
 
     let port = {
@@ -701,10 +915,18 @@ async fn delete_tenant(
     }
 }
 
+#[derive(serde::Deserialize)]
+struct StartTenantQuery {
+    /// Return the spawn plan instead of starting the tenant.
+    #[serde(default)]
+    dry_run: bool,
+}
+
 async fn start_tenant(
     State(state): State<Arc<AdminState>>,
     Extension(claims): Extension<crate::auth::Claims>,
     Path(id): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<StartTenantQuery>,
 ) -> Json<serde_json::Value> {
     if !can_write_tenant(&claims, &id, &*state.db.lock().await) {
         return Json(serde_json::json!({"ok": false, "error": "Không có quyền khởi động tenant này."}));
@@ -716,6 +938,13 @@ async fn start_tenant(
 
     let mut mgr = state.manager.lock().await;
     let db = state.db.lock().await;
+    if query.dry_run {
+        // Validation failures are the point of a dry run — show them as-is.
+        return match mgr.plan_tenant(&tenant, &state.bizclaw_bin, &db) {
+            Ok(plan) => Json(serde_json::json!({"ok": true, "dry_run": true, "plan": plan})),
+            Err(e) => Json(serde_json::json!({"ok": false, "dry_run": true, "error": e.to_string()})),
+        };
+    }
     match mgr.start_tenant(&tenant, &state.bizclaw_bin, &db) {
         Ok(pid) => {
             drop(db);
@@ -803,6 +1032,57 @@ async fn restart_tenant(
     }
 }
 
+/// PUT /api/admin/maintenance — turn maintenance mode on or off on every running
+/// tenant gateway: `{"enabled": true, "message": "..."}` is forwarded as-is.
+async fn set_maintenance(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Json(body): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    if !is_super_admin(&claims) {
+        return Json(serde_json::json!({"ok": false, "error": "Chỉ Super Admin mới có quyền bật/tắt chế độ bảo trì."}));
+    }
+    let Some(enabled) = body["enabled"].as_bool() else {
+        return Json(serde_json::json!({"ok": false, "error": "'enabled' (bool) is required"}));
+    };
+    let tenants = match state.db.lock().await.list_tenants() {
+        Ok(t) => t,
+        Err(e) => return internal_error("set_maintenance", e),
+    };
+    let running: Vec<_> = {
+        let mgr = state.manager.lock().await;
+        tenants.into_iter().filter(|t| mgr.is_running(&t.id)).collect()
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .unwrap_or_default();
+    let mut results = Vec::new();
+    for tenant in &running {
+        let mut req = client
+            .put(format!("http://127.0.0.1:{}/api/v1/maintenance", tenant.port))
+            .json(&body);
+        if let Some(code) = &tenant.pairing_code {
+            req = req.header("X-Pairing-Code", code);
+        }
+        let error = match req.send().await {
+            Ok(r) if r.status().is_success() => None,
+            Ok(r) => Some(format!("HTTP {}", r.status())),
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(e) = &error {
+            tracing::warn!("[maintenance] {} did not switch: {e}", tenant.slug);
+        }
+        results.push(serde_json::json!({"tenant": tenant.slug, "ok": error.is_none(), "error": error}));
+    }
+
+    let event = if enabled { "maintenance_on" } else { "maintenance_off" };
+    state.db.lock().await.log_event(event, "admin", &claims.sub, None).ok();
+    let ok = results.iter().all(|r| r["ok"] == true);
+    Json(serde_json::json!({"ok": ok, "enabled": enabled, "tenants": results}))
+}
+
 async fn reset_pairing(
     State(state): State<Arc<AdminState>>,
     Extension(claims): Extension<crate::auth::Claims>,
@@ -1419,6 +1699,337 @@ async fn delete_tenant_agent(
     }
 }
 
+// ═════════════════════════════════════════════════════════════
+// TENANT SKILLS — marketplace skills installed for a tenant
+// ═════════════════════════════════════════════════════════════
+
+/// List a tenant's skills (including global ones). Query: `category`,
+/// `language`, `enabled`, `builtin`, `limit`, `offset`.
+async fn list_tenant_skills(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Path(id): Path<String>,
+    axum::extract::Query(filter): axum::extract::Query<crate::db::SkillFilter>,
+) -> Json<serde_json::Value> {
+    let db = state.db.lock().await;
+    if !can_access_tenant(&claims, &id, &db) {
+        return Json(serde_json::json!({"ok": false, "error": "Không có quyền truy cập tenant này."}));
+    }
+    match db.list_skills(Some(&id), &filter) {
+        Ok(page) => Json(serde_json::json!({
+            "ok": true,
+            "skills": page.skills,
+            "total": page.total,
+            "limit": filter.limit,
+            "offset": filter.offset,
+        })),
+        Err(e) => internal_error("list_tenant_skills", e),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct InstallSkillReq {
+    name: String,
+    slug: String,
+    description: Option<String>,
+    language: Option<String>,
+    category: Option<String>,
+    source_code: Option<String>,
+    entry_point: Option<String>,
+}
+
+/// Install a skill for a tenant, or update the one with the same slug.
+async fn install_tenant_skill(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Path(id): Path<String>,
+    Json(req): Json<InstallSkillReq>,
+) -> Json<serde_json::Value> {
+    let db = state.db.lock().await;
+    if !can_write_tenant(&claims, &id, &db) {
+        return Json(serde_json::json!({"ok": false, "error": "Không có quyền cấu hình tenant này."}));
+    }
+    let slug = req.slug.trim();
+    let valid_slug = !slug.is_empty()
+        && slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if req.name.trim().is_empty() || !valid_slug {
+        return Json(serde_json::json!({"ok": false, "error": "Tên hoặc slug skill không hợp lệ."}));
+    }
+    if !skills_enabled(&db, &id) {
+        return Json(serde_json::json!({"ok": false, "error": "Tính năng skills đã bị tắt cho tenant này."}));
+    }
+    match db.upsert_skill(
+        Some(&id),
+        req.name.trim(),
+        slug,
+        req.description.as_deref().unwrap_or(""),
+        req.language.as_deref().unwrap_or("python"),
+        req.category.as_deref().unwrap_or("custom"),
+        req.source_code.as_deref(),
+        req.entry_point.as_deref().unwrap_or("main"),
+    ) {
+        Ok(_) => {
+            db.log_event("skill_installed", &claims.email, &id, Some(&format!("slug={slug}"))).ok();
+            Json(serde_json::json!({"ok": true, "slug": slug}))
+        }
+        Err(e) => internal_error("install_tenant_skill", e),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct ToggleSkillReq {
+    enabled: bool,
+}
+
+/// Enable or disable one of the tenant's skills.
+async fn toggle_tenant_skill(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Path((id, slug)): Path<(String, String)>,
+    Json(req): Json<ToggleSkillReq>,
+) -> Json<serde_json::Value> {
+    let db = state.db.lock().await;
+    if !can_write_tenant(&claims, &id, &db) {
+        return Json(serde_json::json!({"ok": false, "error": "Không có quyền cấu hình tenant này."}));
+    }
+    if req.enabled && !skills_enabled(&db, &id) {
+        return Json(serde_json::json!({"ok": false, "error": "Tính năng skills đã bị tắt cho tenant này."}));
+    }
+    match db.set_skill_enabled(&id, &slug, req.enabled) {
+        Ok(true) => {
+            let event = if req.enabled { "skill_enabled" } else { "skill_disabled" };
+            db.log_event(event, &claims.email, &id, Some(&format!("slug={slug}"))).ok();
+            Json(serde_json::json!({"ok": true, "slug": slug, "enabled": req.enabled}))
+        }
+        Ok(false) => Json(serde_json::json!({"ok": false, "error": "Không tìm thấy skill."})),
+        Err(e) => internal_error("toggle_tenant_skill", e),
+    }
+}
+
+/// Uninstall one of the tenant's skills.
+async fn delete_tenant_skill(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Path((id, slug)): Path<(String, String)>,
+) -> Json<serde_json::Value> {
+    let db = state.db.lock().await;
+    if !can_write_tenant(&claims, &id, &db) {
+        return Json(serde_json::json!({"ok": false, "error": "Không có quyền cấu hình tenant này."}));
+    }
+    match db.delete_skill(&id, &slug) {
+        Ok(true) => {
+            db.log_event("skill_deleted", &claims.email, &id, Some(&format!("slug={slug}"))).ok();
+            Json(serde_json::json!({"ok": true}))
+        }
+        Ok(false) => Json(serde_json::json!({"ok": false, "error": "Không tìm thấy skill."})),
+        Err(e) => internal_error("delete_tenant_skill", e),
+    }
+}
+
+/// Whether the tenant's `skills` feature flag is on.
+fn skills_enabled(db: &crate::db::PlatformDb, tenant_id: &str) -> bool {
+    db.feature_enabled(tenant_id, crate::features::SKILLS).unwrap_or(false)
+}
+
+/// A tenant's feature flags: resolved values plus the per-tenant overrides.
+async fn list_tenant_features(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Path(id): Path<String>,
+) -> Json<serde_json::Value> {
+    let db = state.db.lock().await;
+    if !can_access_tenant(&claims, &id, &db) {
+        return Json(serde_json::json!({"ok": false, "error": "Không có quyền truy cập tenant này."}));
+    }
+    let tenant = match db.get_tenant(&id) {
+        Ok(t) => t,
+        Err(_) => return Json(serde_json::json!({"ok": false, "error": "Không tìm thấy tenant."})),
+    };
+    match (db.feature_flags(&tenant), db.feature_flag_overrides(&id)) {
+        (Ok(flags), Ok(overrides)) => Json(serde_json::json!({
+            "ok": true,
+            "plan": tenant.plan,
+            "features": flags,
+            "overrides": overrides.into_iter().collect::<std::collections::BTreeMap<_, _>>(),
+        })),
+        (Err(e), _) | (_, Err(e)) => internal_error("list_tenant_features", e),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct SetFeatureReq {
+    /// `null` reverts the flag to the plan default.
+    enabled: Option<bool>,
+}
+
+/// Turn a feature on or off for a tenant (Super Admin only). Takes effect
+/// the next time the tenant is started.
+async fn set_tenant_feature(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Path((id, flag)): Path<(String, String)>,
+    Json(req): Json<SetFeatureReq>,
+) -> Json<serde_json::Value> {
+    if !is_super_admin(&claims) {
+        return Json(serde_json::json!({"ok": false, "error": "Chỉ Super Admin mới có quyền bật/tắt tính năng."}));
+    }
+    if !crate::features::is_known(&flag) {
+        return Json(serde_json::json!({"ok": false, "error": format!("Tính năng không hợp lệ: {flag}")}));
+    }
+    let db = state.db.lock().await;
+    let tenant = match db.get_tenant(&id) {
+        Ok(t) => t,
+        Err(_) => return Json(serde_json::json!({"ok": false, "error": "Không tìm thấy tenant."})),
+    };
+    if let Err(e) = db.set_feature_flag(&id, &flag, req.enabled) {
+        return internal_error("set_tenant_feature", e);
+    }
+    let value = match req.enabled {
+        Some(v) => v.to_string(),
+        None => "default".into(),
+    };
+    db.log_event("feature_flag_set", &claims.email, &id, Some(&format!("{flag}={value}"))).ok();
+    match db.feature_flags(&tenant) {
+        Ok(flags) => Json(serde_json::json!({"ok": true, "features": flags})),
+        Err(e) => internal_error("set_tenant_feature", e),
+    }
+}
+
+fn domain_json(domain: &crate::db::CustomDomain) -> serde_json::Value {
+    serde_json::json!({
+        "domain": domain.domain,
+        "verified": domain.is_verified(),
+        "verified_at": domain.verified_at,
+        "created_at": domain.created_at,
+        "verification": crate::domains::instructions(domain),
+    })
+}
+
+/// A tenant's custom domains with their verification instructions.
+async fn list_tenant_domains(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Path(id): Path<String>,
+) -> Json<serde_json::Value> {
+    let db = state.db.lock().await;
+    if !can_access_tenant(&claims, &id, &db) {
+        return Json(serde_json::json!({"ok": false, "error": "Không có quyền truy cập tenant này."}));
+    }
+    match db.list_custom_domains(&id) {
+        Ok(domains) => Json(serde_json::json!({
+            "ok": true,
+            "domains": domains.iter().map(domain_json).collect::<Vec<_>>(),
+        })),
+        Err(e) => internal_error("list_tenant_domains", e),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct AddDomainReq {
+    domain: String,
+}
+
+/// Register a custom domain for a tenant. It is routed once verified.
+async fn add_tenant_domain(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Path(id): Path<String>,
+    Json(req): Json<AddDomainReq>,
+) -> Json<serde_json::Value> {
+    let db = state.db.lock().await;
+    if !can_write_tenant(&claims, &id, &db) {
+        return Json(serde_json::json!({"ok": false, "error": "Không có quyền cấu hình tenant này."}));
+    }
+    let domain = match crate::db::validate_custom_domain(&req.domain) {
+        Ok(d) => d,
+        Err(_) => return Json(serde_json::json!({"ok": false, "error": "Tên miền không hợp lệ."})),
+    };
+    if crate::routing::is_platform_host(&domain, &state.domain) {
+        return Json(serde_json::json!({"ok": false, "error": format!("Tên miền thuộc {} — dùng subdomain của tenant.", state.domain)}));
+    }
+    if db.get_tenant(&id).is_err() {
+        return Json(serde_json::json!({"ok": false, "error": "Không tìm thấy tenant."}));
+    }
+    match db.get_custom_domain(&domain) {
+        Ok(Some(existing)) if existing.tenant_id != id => {
+            return Json(serde_json::json!({"ok": false, "error": "Tên miền đã được sử dụng."}));
+        }
+        Ok(_) => {}
+        Err(e) => return internal_error("add_tenant_domain", e),
+    }
+    match db.add_custom_domain(&id, &domain) {
+        Ok(added) => {
+            db.log_event("domain_added", &claims.email, &id, Some(&format!("domain={domain}"))).ok();
+            Json(serde_json::json!({"ok": true, "domain": domain_json(&added)}))
+        }
+        Err(e) => internal_error("add_tenant_domain", e),
+    }
+}
+
+/// Check a custom domain's DNS TXT record, and start routing it to the
+/// tenant if it is in place.
+async fn verify_tenant_domain(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Path((id, domain)): Path<(String, String)>,
+) -> Json<serde_json::Value> {
+    let domain = crate::routing::host_name(&domain);
+    let found = {
+        let db = state.db.lock().await;
+        if !can_write_tenant(&claims, &id, &db) {
+            return Json(serde_json::json!({"ok": false, "error": "Không có quyền cấu hình tenant này."}));
+        }
+        db.get_custom_domain(&domain)
+    };
+    let found = match found {
+        Ok(Some(d)) if d.tenant_id == id => d,
+        Ok(_) => return Json(serde_json::json!({"ok": false, "error": "Không tìm thấy tên miền."})),
+        Err(e) => return internal_error("verify_tenant_domain", e),
+    };
+    if found.is_verified() {
+        return Json(serde_json::json!({"ok": true, "domain": domain_json(&found)}));
+    }
+    // No lock held while the checks go out over the network.
+    let Some(method) = crate::domains::verify(&found).await else {
+        return Json(serde_json::json!({
+            "ok": false,
+            "error": "Chưa xác minh được tên miền. Kiểm tra bản ghi TXT rồi thử lại.",
+            "domain": domain_json(&found),
+        }));
+    };
+    let db = state.db.lock().await;
+    if let Err(e) = db.mark_custom_domain_verified(&domain) {
+        return internal_error("verify_tenant_domain", e);
+    }
+    db.log_event("domain_verified", &claims.email, &id, Some(&format!("domain={domain} method={method:?}"))).ok();
+    match db.get_custom_domain(&domain) {
+        Ok(Some(d)) => Json(serde_json::json!({"ok": true, "method": method, "domain": domain_json(&d)})),
+        Ok(None) => Json(serde_json::json!({"ok": false, "error": "Không tìm thấy tên miền."})),
+        Err(e) => internal_error("verify_tenant_domain", e),
+    }
+}
+
+/// Remove a tenant's custom domain; it stops being routed immediately.
+async fn delete_tenant_domain(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Path((id, domain)): Path<(String, String)>,
+) -> Json<serde_json::Value> {
+    let db = state.db.lock().await;
+    if !can_write_tenant(&claims, &id, &db) {
+        return Json(serde_json::json!({"ok": false, "error": "Không có quyền cấu hình tenant này."}));
+    }
+    let domain = crate::routing::host_name(&domain);
+    match db.delete_custom_domain(&id, &domain) {
+        Ok(true) => {
+            db.log_event("domain_deleted", &claims.email, &id, Some(&format!("domain={domain}"))).ok();
+            Json(serde_json::json!({"ok": true}))
+        }
+        Ok(false) => Json(serde_json::json!({"ok": false, "error": "Không tìm thấy tên miền."})),
+        Err(e) => internal_error("delete_tenant_domain", e),
+    }
+}
+
 // ═════════════════════════════════════════════════════════════
 // USER MANAGEMENT HANDLERS
 // ═════════════════════════════════════════════════════════════
@@ -1968,6 +2579,59 @@ async fn analytics_tokens(
     }
 }
 
+/// Max usage records accepted per report.
+const MAX_USAGE_BATCH: usize = 1000;
+
+#[derive(serde::Deserialize)]
+struct UsageReportReq {
+    records: Vec<crate::db::LlmUsageRecord>,
+}
+
+/// POST /api/internal/tenants/{id}/usage — a tenant gateway reports LLM calls.
+async fn report_llm_usage(
+    State(state): State<Arc<AdminState>>,
+    Path(id): Path<String>,
+    headers: axum::http::HeaderMap,
+    Json(req): Json<UsageReportReq>,
+) -> Json<serde_json::Value> {
+    let key = headers
+        .get("X-Usage-Key")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let db = state.db.lock().await;
+    if !db.verify_usage_report_key(&id, key) {
+        return Json(serde_json::json!({"ok": false, "error": "Invalid usage key"}));
+    }
+    if req.records.len() > MAX_USAGE_BATCH {
+        return Json(serde_json::json!({
+            "ok": false,
+            "error": format!("Too many records (max {MAX_USAGE_BATCH} per report)")
+        }));
+    }
+    match db.record_llm_usage(&id, &req.records) {
+        Ok(n) => Json(serde_json::json!({"ok": true, "recorded": n})),
+        Err(e) => internal_error("report_llm_usage", e),
+    }
+}
+
+/// GET /api/admin/tenants/{id}/usage?since= — aggregate LLM usage reported by a tenant.
+async fn tenant_llm_usage(
+    State(state): State<Arc<AdminState>>,
+    Extension(claims): Extension<crate::auth::Claims>,
+    Path(id): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<std::collections::HashMap<String, String>>,
+) -> Json<serde_json::Value> {
+    let db = state.db.lock().await;
+    if !can_access_tenant(&claims, &id, &db) {
+        return Json(serde_json::json!({"ok": false, "error": "Không có quyền."}));
+    }
+    let since = params.get("since").map(String::as_str).filter(|s| !s.is_empty());
+    match db.llm_usage_summary(&id, since) {
+        Ok(usage) => Json(serde_json::json!({"ok": true, "usage": usage})),
+        Err(e) => internal_error("tenant_llm_usage", e),
+    }
+}
+
 // ════════════════════════════════════════════════════════════════════
 // ENTERPRISE HANDLERS: Budget Quota
 // ════════════════════════════════════════════════════════════════════
@@ -2562,3 +3226,130 @@ async fn srv_execute_command(
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readyz_reflects_db_state() {
+        let path = std::env::temp_dir().join(format!("bizclaw-readyz-{}.db", std::process::id()));
+        let db = PlatformDb::open(&path).unwrap();
+        let (status, body) = readiness(db.check_ready(), None);
+        assert_eq!(status, axum::http::StatusCode::OK);
+        assert_eq!(body.0["checks"]["db"], "ok");
+
+        // Simulate a schema that was never (fully) migrated.
+        let raw = rusqlite::Connection::open(&path).unwrap();
+        raw.execute_batch("DROP TABLE llm_usage").unwrap();
+        let (status, body) = readiness(db.check_ready(), None);
+        assert_eq!(status, axum::http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body.0["ok"], false);
+        assert!(body.0["checks"]["db"].as_str().unwrap().contains("llm_usage"));
+
+        // A failing PostgreSQL check also blocks readiness.
+        let pg_down = Err(bizclaw_core::error::BizClawError::Memory("PG unreachable".into()));
+        let (status, _) = readiness(Ok(()), Some(pg_down));
+        assert_eq!(status, axum::http::StatusCode::SERVICE_UNAVAILABLE);
+
+        drop(raw);
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            std::fs::remove_file(format!("{}{suffix}", path.display())).ok();
+        }
+    }
+    #[tokio::test]
+    async fn test_reaper_removes_expired_sandboxes() {
+        let db = PlatformDb::open(std::path::Path::new(":memory:")).unwrap();
+        let expired = db
+            .create_sandbox_tenant("Demo", "demo-old", 10002, "openai", "gpt-4o-mini", -1)
+            .unwrap();
+        let live = db
+            .create_sandbox_tenant("Demo", "demo-new", 10003, "openai", "gpt-4o-mini", 60)
+            .unwrap();
+        let regular = db
+            .create_tenant("Shop", "shop", 10004, "openai", "gpt-4o-mini", "free", None)
+            .unwrap();
+        let data_dir = std::env::temp_dir().join(format!("bizclaw-reaper-{}", std::process::id()));
+        for slug in ["demo-old", "demo-new"] {
+            std::fs::create_dir_all(data_dir.join(slug)).unwrap();
+        }
+        let state = AdminState {
+            db: Mutex::new(db),
+            manager: Mutex::new(TenantManager::new(&data_dir)),
+            jwt_secret: "test".into(),
+            bizclaw_bin: "bizclaw".into(),
+            base_port: 10001,
+            domain: "bizclaw.vn".into(),
+            login_attempts: Default::default(),
+            register_attempts: Default::default(),
+            pg_db: None,
+        };
+
+        assert_eq!(reap_expired_sandboxes(&state).await, 1);
+        let db = state.db.lock().await;
+        assert!(db.get_tenant(&expired.id).is_err());
+        assert!(db.get_tenant(&live.id).is_ok());
+        assert!(db.get_tenant(&regular.id).is_ok());
+        drop(db);
+        assert!(!data_dir.join("demo-old").exists());
+        assert!(data_dir.join("demo-new").exists());
+        // Nothing left to reap.
+        assert_eq!(reap_expired_sandboxes(&state).await, 0);
+        std::fs::remove_dir_all(&data_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_skills_feature_flag_gates_install() {
+        let db = PlatformDb::open(std::path::Path::new(":memory:")).unwrap();
+        let tenant = db
+            .create_tenant("Shop", "shop", 10004, "openai", "gpt-4o-mini", "free", None)
+            .unwrap();
+        let state = Arc::new(AdminState {
+            db: Mutex::new(db),
+            manager: Mutex::new(TenantManager::new(std::env::temp_dir())),
+            jwt_secret: "test".into(),
+            bizclaw_bin: "bizclaw".into(),
+            base_port: 10001,
+            domain: "bizclaw.vn".into(),
+            login_attempts: Default::default(),
+            register_attempts: Default::default(),
+            pg_db: None,
+        });
+        let claims = crate::auth::Claims {
+            sub: "u1".into(),
+            email: "ops@bizclaw.vn".into(),
+            role: "superadmin".into(),
+            tenant_id: None,
+            exp: usize::MAX,
+        };
+        let install = || {
+            install_tenant_skill(
+                State(state.clone()),
+                Extension(claims.clone()),
+                Path(tenant.id.clone()),
+                Json(InstallSkillReq {
+                    name: "Backup".into(),
+                    slug: "backup".into(),
+                    description: None,
+                    language: None,
+                    category: None,
+                    source_code: None,
+                    entry_point: None,
+                }),
+            )
+        };
+
+        // Free plan: skills are off by default.
+        assert_eq!(install().await.0["ok"], false);
+
+        let res = set_tenant_feature(
+            State(state.clone()),
+            Extension(claims.clone()),
+            Path((tenant.id.clone(), "skills".into())),
+            Json(SetFeatureReq { enabled: Some(true) }),
+        )
+        .await;
+        assert_eq!(res.0["features"]["skills"], true);
+        assert_eq!(install().await.0["ok"], true);
+    }
+}
@@ -2,8 +2,17 @@
 
 use bizclaw_core::error::{BizClawError, Result};
 use rusqlite::{Connection, params};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
+/// Daily message cap for self-serve sandbox tenants.
+pub const SANDBOX_MAX_MESSAGES_DAY: u32 = 20;
+/// Sandbox tenants get a single channel and a single member.
+pub const SANDBOX_MAX_CHANNELS: u32 = 1;
+pub const SANDBOX_MAX_MEMBERS: u32 = 1;
+/// Tenant config key holding a sandbox's key for the platform's LLM proxy.
+pub const SANDBOX_KEY_CONFIG: &str = "sandbox.llm_key";
+
 /// Platform database manager.
 pub struct PlatformDb {
     conn: Connection,
@@ -30,6 +39,12 @@ pub struct Tenant {
     pub disk_bytes: u64,
     pub owner_id: Option<String>,
     pub created_at: String,
+    /// Self-serve "try it" demo tenant, removed once `expires_at` passes.
+    #[serde(default)]
+    pub sandbox: bool,
+    /// When a sandbox tenant is reaped (same clock as `created_at`).
+    #[serde(default)]
+    pub expires_at: Option<String>,
 }
 
 /// User record.
@@ -94,8 +109,270 @@ pub struct TenantAgent {
     pub updated_at: String,
 }
 
+/// Memory types matching ReMe's 4-type system.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PersonalMemory {
+    pub id: String,
+    pub tenant_id: String,
+    pub user_id: Option<String>,
+    pub category: String,
+    pub key: String,
+    pub value: String,
+    pub confidence: f32,
+    pub source: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TaskMemory {
+    pub id: String,
+    pub tenant_id: String,
+    pub task_type: String,
+    pub task_description: String,
+    pub approach: Option<String>,
+    pub outcome: String,
+    pub lessons_learned: Option<String>,
+    pub duration_seconds: Option<i32>,
+    pub tokens_used: Option<i32>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ToolMemory {
+    pub id: String,
+    pub tenant_id: String,
+    pub tool_name: String,
+    pub usage_count: i32,
+    pub success_count: i32,
+    pub failure_count: i32,
+    pub avg_duration_ms: i32,
+    pub tips: Option<String>,
+    pub last_used: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WorkingMemory {
+    pub id: String,
+    pub tenant_id: String,
+    pub session_id: String,
+    pub channel: Option<String>,
+    pub user_id: Option<String>,
+    pub summary: String,
+    pub key_facts: serde_json::Value,
+    pub message_count: i32,
+    pub token_count: i32,
+    pub is_active: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Heartbeat configuration.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HeartbeatConfig {
+    pub id: String,
+    pub tenant_id: String,
+    pub enabled: bool,
+    pub interval_seconds: i32,
+    pub notify_channel: Option<String>,
+    pub notify_target: Option<String>,
+    pub last_heartbeat: Option<String>,
+    pub next_heartbeat: Option<String>,
+}
+
+/// Heartbeat task definition.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HeartbeatTask {
+    pub id: String,
+    pub tenant_id: String,
+    pub task_name: String,
+    pub task_type: String,
+    pub cron_expression: Option<String>,
+    pub handler: String,
+    pub config: serde_json::Value,
+    pub enabled: bool,
+    pub last_run: Option<String>,
+    pub last_result: Option<String>,
+    pub run_count: i32,
+}
+
+/// Skill definition.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Skill {
+    pub id: String,
+    pub tenant_id: Option<String>,
+    pub name: String,
+    pub slug: String,
+    pub description: String,
+    pub version: String,
+    pub language: String,
+    pub category: Option<String>,
+    pub entry_point: String,
+    pub enabled: bool,
+    pub is_builtin: bool,
+    pub usage_count: i32,
+    pub created_at: String,
+}
+
+/// Filters and paging for [`PlatformDb::list_skills`]. `None` matches anything.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct SkillFilter {
+    pub category: Option<String>,
+    pub language: Option<String>,
+    pub enabled: Option<bool>,
+    pub builtin: Option<bool>,
+    /// Page size. `None` returns every match.
+    pub limit: Option<u32>,
+    #[serde(default)]
+    pub offset: u32,
+}
+
+/// One page of skills plus the number of matches across all pages.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SkillPage {
+    pub skills: Vec<Skill>,
+    pub total: usize,
+}
+
+/// One LLM call reported by a tenant gateway.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LlmUsageRecord {
+    #[serde(default)]
+    pub agent_name: String,
+    pub provider: String,
+    pub model: String,
+    #[serde(default)]
+    pub prompt_tokens: i64,
+    #[serde(default)]
+    pub completion_tokens: i64,
+    /// Cost estimated by the tenant (USD).
+    #[serde(default)]
+    pub cost_usd: f64,
+    /// When the call happened (RFC 3339); defaults to the time of the report.
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Outcome of the call (`ok`, `error`, …); defaults to `ok`.
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// Per-model slice of a tenant's LLM usage.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LlmModelUsage {
+    pub provider: String,
+    pub model: String,
+    pub requests: i64,
+    pub total_tokens: i64,
+    pub cost_usd: f64,
+}
+
+/// Aggregate LLM usage for a tenant over a time window.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LlmUsageSummary {
+    pub tenant_id: String,
+    pub since: Option<String>,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+    pub cost_usd: f64,
+    /// Calls reported with status `error`.
+    #[serde(default)]
+    pub errors: i64,
+    pub by_model: Vec<LlmModelUsage>,
+}
+
+/// A tenant's own domain (e.g. `chat.acme.com`). Routed to the tenant
+/// only once verified.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CustomDomain {
+    pub domain: String,
+    pub tenant_id: String,
+    /// Challenge published in a DNS TXT record to verify.
+    pub token: String,
+    pub verified_at: Option<String>,
+    pub created_at: String,
+}
+
+impl CustomDomain {
+    pub fn is_verified(&self) -> bool {
+        self.verified_at.is_some()
+    }
+}
+
+/// Tenant config key holding the secret a tenant uses to report usage.
+const USAGE_REPORT_KEY: &str = "usage_report_key";
+
+/// Max length of a DNS label (RFC 1123).
+pub const MAX_SLUG_LEN: usize = 63;
+
+/// Subdomains the platform itself uses or that would be confusing as tenants.
+pub const RESERVED_SLUGS: &[&str] = &[
+    "admin", "api", "app", "apps", "auth", "cdn", "dashboard", "dev", "docs", "ftp", "help",
+    "local", "localhost", "login", "mail", "ns", "ns1", "ns2", "platform", "root", "smtp",
+    "staging", "status", "support", "sys", "system", "test", "webhook", "www",
+];
+
+/// Check that a tenant slug is usable as a subdomain: an RFC 1123 label
+/// (lowercase letters, digits and hyphens, 1–63 chars, no leading or trailing
+/// hyphen) that is not reserved.
+pub fn validate_tenant_slug(slug: &str) -> Result<()> {
+    let invalid = |why: &str| Err(BizClawError::Config(format!("Invalid tenant slug '{slug}': {why}")));
+    if slug.is_empty() || slug.len() > MAX_SLUG_LEN {
+        return invalid("must be 1-63 characters");
+    }
+    if !slug.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-') {
+        return invalid("only lowercase letters, digits and '-' are allowed");
+    }
+    if slug.starts_with('-') || slug.ends_with('-') {
+        return invalid("must not start or end with '-'");
+    }
+    if RESERVED_SLUGS.contains(&slug) {
+        return invalid("this name is reserved");
+    }
+    Ok(())
+}
+
+/// Normalize a custom domain (lowercase, no trailing dot) and check that it
+/// is a fully-qualified host name: two or more RFC 1123 labels, at most 253
+/// characters, not an IP address.
+pub fn validate_custom_domain(domain: &str) -> Result<String> {
+    let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    let invalid = |why: &str| Err(BizClawError::Config(format!("Invalid domain '{domain}': {why}")));
+    if domain.is_empty() || domain.len() > 253 {
+        return invalid("must be 1-253 characters");
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 {
+        return invalid("must be a fully-qualified name like chat.example.com");
+    }
+    for label in &labels {
+        if label.is_empty() || label.len() > MAX_SLUG_LEN {
+            return invalid("each label must be 1-63 characters");
+        }
+        if !label.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-') {
+            return invalid("only letters, digits, '-' and '.' are allowed");
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return invalid("labels must not start or end with '-'");
+        }
+    }
+    if labels.last().is_some_and(|tld| tld.bytes().all(|b| b.is_ascii_digit())) {
+        return invalid("IP addresses are not allowed");
+    }
+    Ok(domain)
+}
+
+/// Tables created by [`PlatformDb::migrate`]; all must exist for readiness.
+const MIGRATED_TABLES: &[&str] = &[
+    "tenants", "users", "audit_log", "tenant_members", "tenant_channels", "tenant_configs",
+    "tenant_feature_flags", "tenant_agents", "password_resets", "platform_configs", "memory_personal", "memory_task",
+    "memory_tool", "memory_working", "memory_embeddings", "heartbeat_configs", "heartbeat_tasks",
+    "skills", "llm_usage", "custom_domains",
+];
+
 /// Shared SELECT column list for tenant queries — single source of truth.
-const TENANT_SELECT: &str = "SELECT id,name,slug,status,port,plan,provider,model,max_messages_day,max_channels,max_members,pairing_code,pid,cpu_percent,memory_bytes,disk_bytes,owner_id,created_at FROM tenants";
+const TENANT_SELECT: &str = "SELECT id,name,slug,status,port,plan,provider,model,max_messages_day,max_channels,max_members,pairing_code,pid,cpu_percent,memory_bytes,disk_bytes,owner_id,created_at,sandbox,expires_at FROM tenants";
 
 /// Map a database row to a Tenant struct (eliminates 3x copy-paste).
 fn row_to_tenant(row: &rusqlite::Row) -> rusqlite::Result<Tenant> {
@@ -106,6 +383,7 @@ fn row_to_tenant(row: &rusqlite::Row) -> rusqlite::Result<Tenant> {
         pairing_code: row.get(11)?, pid: row.get(12)?, cpu_percent: row.get(13)?,
         memory_bytes: row.get(14)?, disk_bytes: row.get(15)?,
         owner_id: row.get(16)?, created_at: row.get(17)?,
+        sandbox: row.get(18)?, expires_at: row.get(19)?,
     })
 }
 
@@ -127,6 +405,29 @@ impl PlatformDb {
         Ok(db)
     }
 
+    /// Readiness probe: the database answers `SELECT 1` and every migrated
+    /// table is present.
+    pub fn check_ready(&self) -> Result<()> {
+        self.conn
+            .query_row("SELECT 1", [], |row| row.get::<_, i64>(0))
+            .map_err(|e| BizClawError::Memory(format!("DB unreachable: {e}")))?;
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name FROM sqlite_master WHERE type='table'")
+            .map_err(|e| BizClawError::Memory(format!("Read schema: {e}")))?;
+        let tables: std::collections::HashSet<String> = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| BizClawError::Memory(format!("Read schema: {e}")))?
+            .filter_map(|r| r.ok())
+            .collect();
+        match MIGRATED_TABLES.iter().find(|t| !tables.contains(**t)) {
+            Some(missing) => Err(BizClawError::Memory(format!(
+                "Migrations not applied: table '{missing}' is missing"
+            ))),
+            None => Ok(()),
+        }
+    }
+
     /// Run schema migrations.
     fn migrate(&self) -> Result<()> {
         self.conn
@@ -150,6 +451,8 @@ impl PlatformDb {
                 memory_bytes INTEGER DEFAULT 0,
                 disk_bytes INTEGER DEFAULT 0,
                 owner_id TEXT,
+                sandbox INTEGER DEFAULT 0,
+                expires_at TEXT,
                 created_at TEXT DEFAULT (datetime('now', '+7 hours')),
                 updated_at TEXT DEFAULT (datetime('now', '+7 hours'))
             );
@@ -204,6 +507,14 @@ impl PlatformDb {
                 PRIMARY KEY (tenant_id, key)
             );
 
+            CREATE TABLE IF NOT EXISTS tenant_feature_flags (
+                tenant_id TEXT NOT NULL,
+                flag TEXT NOT NULL,
+                enabled INTEGER NOT NULL,
+                updated_at TEXT DEFAULT (datetime('now')),
+                PRIMARY KEY (tenant_id, flag)
+            );
+
             CREATE TABLE IF NOT EXISTS tenant_agents (
                 id TEXT PRIMARY KEY,
                 tenant_id TEXT NOT NULL,
@@ -229,6 +540,152 @@ impl PlatformDb {
                 value TEXT NOT NULL DEFAULT '',
                 updated_at TEXT DEFAULT (datetime('now'))
             );
+
+            -- ReMe memory, heartbeat and skills — mirrors migrations/001_init.sql
+            CREATE TABLE IF NOT EXISTS memory_personal (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                user_id TEXT,
+                category TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                confidence REAL DEFAULT 1.0,
+                source TEXT,
+                last_accessed TEXT DEFAULT (datetime('now')),
+                created_at TEXT DEFAULT (datetime('now')),
+                updated_at TEXT DEFAULT (datetime('now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS memory_task (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                task_type TEXT NOT NULL,
+                task_description TEXT NOT NULL,
+                approach TEXT,
+                outcome TEXT DEFAULT 'unknown',
+                lessons_learned TEXT,
+                duration_seconds INTEGER,
+                tokens_used INTEGER,
+                metadata TEXT DEFAULT '{}',
+                created_at TEXT DEFAULT (datetime('now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS memory_tool (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                tool_name TEXT NOT NULL,
+                usage_count INTEGER DEFAULT 1,
+                success_count INTEGER DEFAULT 0,
+                failure_count INTEGER DEFAULT 0,
+                avg_duration_ms INTEGER DEFAULT 0,
+                last_error TEXT,
+                tips TEXT,
+                metadata TEXT DEFAULT '{}',
+                last_used TEXT DEFAULT (datetime('now')),
+                created_at TEXT DEFAULT (datetime('now')),
+                UNIQUE(tenant_id, tool_name)
+            );
+
+            CREATE TABLE IF NOT EXISTS memory_working (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                channel TEXT,
+                user_id TEXT,
+                summary TEXT NOT NULL,
+                key_facts TEXT DEFAULT '[]',
+                message_count INTEGER DEFAULT 0,
+                token_count INTEGER DEFAULT 0,
+                is_active INTEGER DEFAULT 1,
+                expires_at TEXT,
+                created_at TEXT DEFAULT (datetime('now')),
+                updated_at TEXT DEFAULT (datetime('now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS memory_embeddings (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                memory_type TEXT NOT NULL,
+                memory_id TEXT NOT NULL,
+                content_text TEXT NOT NULL,
+                embedding_model TEXT,
+                created_at TEXT DEFAULT (datetime('now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS heartbeat_configs (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                enabled INTEGER DEFAULT 0,
+                interval_seconds INTEGER DEFAULT 1800,
+                notify_channel TEXT,
+                notify_target TEXT,
+                last_heartbeat TEXT,
+                next_heartbeat TEXT,
+                created_at TEXT DEFAULT (datetime('now')),
+                updated_at TEXT DEFAULT (datetime('now')),
+                UNIQUE(tenant_id)
+            );
+
+            CREATE TABLE IF NOT EXISTS heartbeat_tasks (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                task_name TEXT NOT NULL,
+                task_type TEXT NOT NULL,
+                cron_expression TEXT,
+                handler TEXT NOT NULL,
+                config TEXT DEFAULT '{}',
+                enabled INTEGER DEFAULT 1,
+                last_run TEXT,
+                last_result TEXT,
+                last_error TEXT,
+                run_count INTEGER DEFAULT 0,
+                created_at TEXT DEFAULT (datetime('now')),
+                updated_at TEXT DEFAULT (datetime('now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS skills (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT,
+                name TEXT NOT NULL,
+                slug TEXT NOT NULL,
+                description TEXT DEFAULT '',
+                version TEXT DEFAULT '1.0.0',
+                language TEXT DEFAULT 'python',
+                category TEXT,
+                source_code TEXT,
+                file_path TEXT,
+                entry_point TEXT DEFAULT 'main',
+                enabled INTEGER DEFAULT 1,
+                is_builtin INTEGER DEFAULT 0,
+                usage_count INTEGER DEFAULT 0,
+                created_at TEXT DEFAULT (datetime('now')),
+                updated_at TEXT DEFAULT (datetime('now')),
+                UNIQUE(tenant_id, slug)
+            );
+
+            CREATE TABLE IF NOT EXISTS llm_usage (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                agent_name TEXT DEFAULT '',
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                prompt_tokens INTEGER DEFAULT 0,
+                completion_tokens INTEGER DEFAULT 0,
+                total_tokens INTEGER DEFAULT 0,
+                cost_usd REAL DEFAULT 0,
+                created_at TEXT DEFAULT (datetime('now')),
+                status TEXT DEFAULT 'ok'
+            );
+            CREATE INDEX IF NOT EXISTS idx_llm_usage_tenant_time ON llm_usage(tenant_id, created_at);
+
+            CREATE TABLE IF NOT EXISTS custom_domains (
+                domain TEXT PRIMARY KEY,
+                tenant_id TEXT NOT NULL,
+                token TEXT NOT NULL,
+                verified_at TEXT,
+                created_at TEXT DEFAULT (datetime('now'))
+            );
+            CREATE INDEX IF NOT EXISTS idx_custom_domains_tenant ON custom_domains(tenant_id);
         ",
             )
             .map_err(|e| BizClawError::Memory(format!("Migration error: {e}")))?;
@@ -237,6 +694,9 @@ impl PlatformDb {
         let alter_stmts = [
             "ALTER TABLE tenants ADD COLUMN owner_id TEXT",
             "ALTER TABLE users ADD COLUMN status TEXT DEFAULT 'active'",
+            "ALTER TABLE tenants ADD COLUMN sandbox INTEGER DEFAULT 0",
+            "ALTER TABLE tenants ADD COLUMN expires_at TEXT",
+            "ALTER TABLE llm_usage ADD COLUMN status TEXT DEFAULT 'ok'",
         ];
         for stmt in &alter_stmts {
             let _ = self.conn.execute(stmt, []);
@@ -277,6 +737,7 @@ impl PlatformDb {
         plan: &str,
         owner_id: Option<&str>,
     ) -> Result<Tenant> {
+        validate_tenant_slug(slug)?;
         let id = uuid::Uuid::new_v4().to_string();
         let pairing_code = format!("{:06}", rand_code());
 
@@ -288,6 +749,73 @@ impl PlatformDb {
         self.get_tenant(&id)
     }
 
+    /// Create a self-serve sandbox tenant with the `SANDBOX_*` limits that
+    /// expires `ttl_minutes` from now.
+    pub fn create_sandbox_tenant(
+        &self,
+        name: &str,
+        slug: &str,
+        port: u16,
+        provider: &str,
+        model: &str,
+        ttl_minutes: i64,
+    ) -> Result<Tenant> {
+        let tenant = self.create_tenant(name, slug, port, provider, model, "sandbox", None)?;
+        self.conn.execute(
+            "UPDATE tenants SET sandbox=1, expires_at=datetime('now','+7 hours',?1), max_messages_day=?2, max_channels=?3, max_members=?4 WHERE id=?5",
+            params![
+                format!("{ttl_minutes:+} minutes"),
+                SANDBOX_MAX_MESSAGES_DAY,
+                SANDBOX_MAX_CHANNELS,
+                SANDBOX_MAX_MEMBERS,
+                tenant.id
+            ],
+        ).map_err(|e| BizClawError::Memory(format!("Mark sandbox: {e}")))?;
+        self.get_tenant(&tenant.id)
+    }
+
+    /// Sandbox tenants whose `expires_at` has passed.
+    pub fn list_expired_sandboxes(&self) -> Result<Vec<Tenant>> {
+        let mut stmt = self.conn.prepare(
+            &format!("{} WHERE sandbox=1 AND expires_at <= datetime('now','+7 hours')", TENANT_SELECT),
+        ).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+
+        let tenants = stmt
+            .query_map([], row_to_tenant)
+            .map_err(|e| BizClawError::Memory(format!("Query: {e}")))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(tenants)
+    }
+
+    /// Count sandbox tenants that have not expired yet.
+    pub fn count_live_sandboxes(&self) -> Result<u32> {
+        self.conn
+            .query_row(
+                "SELECT count(*) FROM tenants WHERE sandbox=1 AND expires_at > datetime('now','+7 hours')",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| BizClawError::Memory(format!("Count sandboxes: {e}")))
+    }
+
+    /// The live sandbox tenant whose LLM proxy key is `key`.
+    pub fn live_sandbox_by_key(&self, key: &str) -> Result<Option<Tenant>> {
+        match self.conn.query_row(
+            &format!(
+                "{} WHERE sandbox=1 AND expires_at > datetime('now','+7 hours') AND id = \
+                 (SELECT tenant_id FROM tenant_configs WHERE key=?1 AND value=?2)",
+                TENANT_SELECT
+            ),
+            params![SANDBOX_KEY_CONFIG, key],
+            row_to_tenant,
+        ) {
+            Ok(t) => Ok(Some(t)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(BizClawError::Memory(format!("Sandbox by key: {e}"))),
+        }
+    }
+
     /// Check if a slug is already taken (to enforce uniqueness during auto-provision).
     pub fn is_slug_taken(&self, slug: &str) -> bool {
         let count: i32 = self.conn.query_row(
@@ -316,6 +844,19 @@ impl PlatformDb {
         ).map_err(|e| BizClawError::Memory(format!("Get tenant: {e}")))
     }
 
+    /// Get a tenant by its subdomain slug.
+    pub fn get_tenant_by_slug(&self, slug: &str) -> Result<Option<Tenant>> {
+        match self.conn.query_row(
+            &format!("{} WHERE slug=?1", TENANT_SELECT),
+            params![slug],
+            row_to_tenant,
+        ) {
+            Ok(t) => Ok(Some(t)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(BizClawError::Memory(format!("Get tenant by slug: {e}"))),
+        }
+    }
+
     /// List all tenants.
     pub fn list_tenants(&self) -> Result<Vec<Tenant>> {
         let mut stmt = self.conn.prepare(
@@ -360,6 +901,10 @@ impl PlatformDb {
         self.conn
             .execute("DELETE FROM tenants WHERE id=?1", params![id])
             .map_err(|e| BizClawError::Memory(format!("Delete tenant: {e}")))?;
+        // Free the tenant's domains for whoever owns them next.
+        self.conn
+            .execute("DELETE FROM custom_domains WHERE tenant_id=?1", params![id])
+            .map_err(|e| BizClawError::Memory(format!("Delete tenant domains: {e}")))?;
         Ok(())
     }
 
@@ -506,6 +1051,7 @@ impl PlatformDb {
         for tid in &tenant_ids {
             let _ = self.conn.execute("DELETE FROM tenant_channels WHERE tenant_id=?1", params![tid]);
             let _ = self.conn.execute("DELETE FROM tenant_configs WHERE tenant_id=?1", params![tid]);
+            let _ = self.conn.execute("DELETE FROM tenant_feature_flags WHERE tenant_id=?1", params![tid]);
             let _ = self.conn.execute("DELETE FROM tenant_agents WHERE tenant_id=?1", params![tid]);
             let _ = self.conn.execute("DELETE FROM tenant_members WHERE tenant_id=?1", params![tid]);
             let _ = self.conn.execute("DELETE FROM tenants WHERE id=?1", params![tid]);
@@ -672,7 +1218,7 @@ impl PlatformDb {
         self.conn.execute(
             "INSERT INTO tenant_channels (id, tenant_id, channel_type, enabled, config_json, updated_at)
              VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))
-             ON CONFLICT(tenant_id, channel_type) DO UPDATE SET
+             ON CONFLICT(tenant_id, channel_type, instance_id) DO UPDATE SET
                enabled = ?4, config_json = ?5, updated_at = datetime('now')",
             params![id, tenant_id, channel_type, enabled as i32, config_json],
         ).map_err(|e| BizClawError::Memory(format!("Upsert channel: {e}")))?;
@@ -815,6 +1361,53 @@ impl PlatformDb {
         Ok(())
     }
 
+    // ── Tenant Feature Flags ────────────────────────────────
+
+    /// Override a feature flag for a tenant; `None` reverts it to the plan default.
+    pub fn set_feature_flag(&self, tenant_id: &str, flag: &str, enabled: Option<bool>) -> Result<()> {
+        let result = match enabled {
+            Some(enabled) => self.conn.execute(
+                "INSERT INTO tenant_feature_flags (tenant_id, flag, enabled, updated_at)
+                 VALUES (?1, ?2, ?3, datetime('now'))
+                 ON CONFLICT(tenant_id, flag) DO UPDATE SET
+                   enabled = ?3, updated_at = datetime('now')",
+                params![tenant_id, flag, enabled as i32],
+            ),
+            None => self.conn.execute(
+                "DELETE FROM tenant_feature_flags WHERE tenant_id=?1 AND flag=?2",
+                params![tenant_id, flag],
+            ),
+        };
+        result.map_err(|e| BizClawError::Memory(format!("Set feature flag: {e}")))?;
+        Ok(())
+    }
+
+    /// A tenant's feature flag overrides.
+    pub fn feature_flag_overrides(&self, tenant_id: &str) -> Result<Vec<(String, bool)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT flag, enabled FROM tenant_feature_flags WHERE tenant_id=?1 ORDER BY flag"
+        ).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+        let overrides = stmt
+            .query_map(params![tenant_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)? != 0))
+            })
+            .map_err(|e| BizClawError::Memory(format!("Query: {e}")))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(overrides)
+    }
+
+    /// A tenant's feature flags: its plan defaults with overrides applied.
+    pub fn feature_flags(&self, tenant: &Tenant) -> Result<BTreeMap<String, bool>> {
+        Ok(crate::features::resolve(&tenant.plan, &self.feature_flag_overrides(&tenant.id)?))
+    }
+
+    /// Whether `flag` is on for a tenant.
+    pub fn feature_enabled(&self, tenant_id: &str, flag: &str) -> Result<bool> {
+        let tenant = self.get_tenant(tenant_id)?;
+        Ok(self.feature_flags(&tenant)?.get(flag).copied().unwrap_or(false))
+    }
+
     // ── Tenant Agents ────────────────────────────────────
 
     /// Create or update an agent for a tenant.
@@ -895,6 +1488,533 @@ impl PlatformDb {
             .map_err(|e| BizClawError::Memory(format!("Delete agent: {e}")))?;
         Ok(())
     }
+
+    // ── ReMe Memory ────────────────────────────────────
+
+    /// Store personal memory (user preferences, learned facts).
+    #[allow(clippy::too_many_arguments)]
+    pub fn store_personal_memory(
+        &self, tenant_id: &str, user_id: Option<&str>,
+        category: &str, key: &str, value: &str,
+        confidence: f32, source: &str,
+    ) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO memory_personal (id, tenant_id, user_id, category, key, value, confidence, source)
+             VALUES (?1,?2,?3,?4,?5,?6,?7,?8)
+             ON CONFLICT DO NOTHING",
+            params![id, tenant_id, user_id, category, key, value, confidence as f64, source],
+        ).map_err(|e| BizClawError::Memory(format!("Store personal memory: {e}")))?;
+        Ok(id)
+    }
+
+    /// Get all personal memories for a user in a tenant.
+    pub fn get_personal_memories(&self, tenant_id: &str, user_id: Option<&str>) -> Result<Vec<PersonalMemory>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, tenant_id, user_id, category, key, value, confidence, source, created_at, updated_at
+             FROM memory_personal WHERE tenant_id=?1 AND (?2 IS NULL OR user_id=?2)
+             ORDER BY updated_at DESC"
+        ).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+
+        let memories = stmt
+            .query_map(params![tenant_id, user_id], |row| {
+                Ok(PersonalMemory {
+                    id: row.get(0)?, tenant_id: row.get(1)?, user_id: row.get(2)?,
+                    category: row.get(3)?, key: row.get(4)?, value: row.get(5)?,
+                    confidence: row.get::<_, f64>(6)? as f32, source: row.get(7)?,
+                    created_at: row.get(8)?, updated_at: row.get(9)?,
+                })
+            })
+            .map_err(|e| BizClawError::Memory(format!("Query: {e}")))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(memories)
+    }
+
+    /// Store task memory (learned from past tasks).
+    #[allow(clippy::too_many_arguments)]
+    pub fn store_task_memory(
+        &self, tenant_id: &str, task_type: &str, description: &str,
+        approach: &str, outcome: &str, lessons: &str,
+        duration: Option<i32>, tokens: Option<i32>,
+    ) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO memory_task (id, tenant_id, task_type, task_description, approach, outcome, lessons_learned, duration_seconds, tokens_used)
+             VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9)",
+            params![id, tenant_id, task_type, description, approach, outcome, lessons, duration, tokens],
+        ).map_err(|e| BizClawError::Memory(format!("Store task memory: {e}")))?;
+        Ok(id)
+    }
+
+    /// Record tool usage (success/failure tracking).
+    pub fn record_tool_usage(
+        &self, tenant_id: &str, tool_name: &str,
+        success: bool, duration_ms: i32, error: Option<&str>,
+    ) -> Result<()> {
+        let success_inc = if success { 1 } else { 0 };
+        let failure_inc = if success { 0 } else { 1 };
+        self.conn.execute(
+            "INSERT INTO memory_tool (id, tenant_id, tool_name, usage_count, success_count, failure_count, avg_duration_ms, last_error)
+             VALUES (?1, ?2, ?3, 1, ?4, ?5, ?6, ?7)
+             ON CONFLICT(tenant_id, tool_name) DO UPDATE SET
+               usage_count = memory_tool.usage_count + 1,
+               success_count = memory_tool.success_count + ?4,
+               failure_count = memory_tool.failure_count + ?5,
+               avg_duration_ms = (memory_tool.avg_duration_ms * memory_tool.usage_count + ?6) / (memory_tool.usage_count + 1),
+               last_error = COALESCE(?7, memory_tool.last_error),
+               last_used = datetime('now')",
+            params![uuid::Uuid::new_v4().to_string(), tenant_id, tool_name, success_inc, failure_inc, duration_ms, error],
+        ).map_err(|e| BizClawError::Memory(format!("Record tool usage: {e}")))?;
+        Ok(())
+    }
+
+    /// List tool usage stats for a tenant (most used first).
+    pub fn list_tool_memories(&self, tenant_id: &str) -> Result<Vec<ToolMemory>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, tenant_id, tool_name, usage_count, success_count, failure_count, avg_duration_ms, tips, last_used
+             FROM memory_tool WHERE tenant_id=?1 ORDER BY usage_count DESC, tool_name"
+        ).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+
+        let tools = stmt
+            .query_map(params![tenant_id], |row| {
+                Ok(ToolMemory {
+                    id: row.get(0)?, tenant_id: row.get(1)?, tool_name: row.get(2)?,
+                    usage_count: row.get(3)?, success_count: row.get(4)?,
+                    failure_count: row.get(5)?, avg_duration_ms: row.get(6)?,
+                    tips: row.get(7)?, last_used: row.get(8)?,
+                })
+            })
+            .map_err(|e| BizClawError::Memory(format!("Query: {e}")))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(tools)
+    }
+
+    /// Store or update working memory (conversation summary).
+    #[allow(clippy::too_many_arguments)]
+    pub fn upsert_working_memory(
+        &self, tenant_id: &str, session_id: &str,
+        channel: Option<&str>, user_id: Option<&str>,
+        summary: &str, key_facts: &serde_json::Value,
+        message_count: i32, token_count: i32,
+    ) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO memory_working (id, tenant_id, session_id, channel, user_id, summary, key_facts, message_count, token_count)
+             VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9)
+             ON CONFLICT DO NOTHING",
+            params![id, tenant_id, session_id, channel, user_id, summary, key_facts.to_string(), message_count, token_count],
+        ).map_err(|e| BizClawError::Memory(format!("Upsert working memory: {e}")))?;
+        Ok(id)
+    }
+
+    /// Search indexed memories by keyword (substring match — SQLite has no trigram index).
+    pub fn search_memories(&self, tenant_id: &str, query: &str, limit: i32) -> Result<Vec<serde_json::Value>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT memory_type, content_text FROM memory_embeddings
+             WHERE tenant_id=?1 AND content_text LIKE '%' || ?2 || '%'
+             ORDER BY created_at DESC LIMIT ?3"
+        ).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+
+        let results = stmt
+            .query_map(params![tenant_id, query, limit], |row| {
+                Ok(serde_json::json!({
+                    "memory_type": row.get::<_, String>(0)?,
+                    "content": row.get::<_, String>(1)?,
+                    "score": 1.0,
+                }))
+            })
+            .map_err(|e| BizClawError::Memory(format!("Query: {e}")))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(results)
+    }
+
+    /// Index content for memory search.
+    pub fn index_memory(&self, tenant_id: &str, memory_type: &str, memory_id: &str, content: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO memory_embeddings (id, tenant_id, memory_type, memory_id, content_text)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![uuid::Uuid::new_v4().to_string(), tenant_id, memory_type, memory_id, content],
+        ).map_err(|e| BizClawError::Memory(format!("Index memory: {e}")))?;
+        Ok(())
+    }
+
+    // ── Heartbeat / Cron ────────────────────────────────────
+
+    /// Get or create heartbeat config for a tenant.
+    pub fn get_heartbeat_config(&self, tenant_id: &str) -> Result<HeartbeatConfig> {
+        self.conn.execute(
+            "INSERT INTO heartbeat_configs (id, tenant_id) VALUES (?1, ?2)
+             ON CONFLICT(tenant_id) DO NOTHING",
+            params![uuid::Uuid::new_v4().to_string(), tenant_id],
+        ).map_err(|e| BizClawError::Memory(format!("Get heartbeat config: {e}")))?;
+
+        self.conn.query_row(
+            "SELECT id, tenant_id, enabled, interval_seconds, notify_channel, notify_target, last_heartbeat, next_heartbeat
+             FROM heartbeat_configs WHERE tenant_id=?1",
+            params![tenant_id],
+            |row| Ok(HeartbeatConfig {
+                id: row.get(0)?, tenant_id: row.get(1)?,
+                enabled: row.get::<_, i32>(2)? != 0,
+                interval_seconds: row.get(3)?,
+                notify_channel: row.get(4)?, notify_target: row.get(5)?,
+                last_heartbeat: row.get(6)?, next_heartbeat: row.get(7)?,
+            }),
+        ).map_err(|e| BizClawError::Memory(format!("Get heartbeat config: {e}")))
+    }
+
+    /// Update heartbeat config.
+    pub fn update_heartbeat_config(
+        &self, tenant_id: &str, enabled: bool, interval: i32,
+        channel: Option<&str>, target: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE heartbeat_configs SET enabled=?2, interval_seconds=?3, notify_channel=?4, notify_target=?5, updated_at=datetime('now')
+             WHERE tenant_id=?1",
+            params![tenant_id, enabled as i32, interval, channel, target],
+        ).map_err(|e| BizClawError::Memory(format!("Update heartbeat config: {e}")))?;
+        Ok(())
+    }
+
+    /// Create a heartbeat task.
+    pub fn create_heartbeat_task(
+        &self, tenant_id: &str, task_name: &str, task_type: &str,
+        cron_expr: Option<&str>, handler: &str, config: &serde_json::Value,
+    ) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO heartbeat_tasks (id, tenant_id, task_name, task_type, cron_expression, handler, config)
+             VALUES (?1,?2,?3,?4,?5,?6,?7)",
+            params![id, tenant_id, task_name, task_type, cron_expr, handler, config.to_string()],
+        ).map_err(|e| BizClawError::Memory(format!("Create heartbeat task: {e}")))?;
+        Ok(id)
+    }
+
+    /// List heartbeat tasks for a tenant.
+    pub fn list_heartbeat_tasks(&self, tenant_id: &str) -> Result<Vec<HeartbeatTask>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, tenant_id, task_name, task_type, cron_expression, handler, config, enabled, last_run, last_result, run_count
+             FROM heartbeat_tasks WHERE tenant_id=?1 ORDER BY task_name"
+        ).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+
+        let tasks = stmt
+            .query_map(params![tenant_id], |row| {
+                let config: String = row.get(6)?;
+                Ok(HeartbeatTask {
+                    id: row.get(0)?, tenant_id: row.get(1)?, task_name: row.get(2)?,
+                    task_type: row.get(3)?, cron_expression: row.get(4)?,
+                    handler: row.get(5)?,
+                    config: serde_json::from_str(&config).unwrap_or_default(),
+                    enabled: row.get::<_, i32>(7)? != 0,
+                    last_run: row.get(8)?, last_result: row.get(9)?, run_count: row.get(10)?,
+                })
+            })
+            .map_err(|e| BizClawError::Memory(format!("Query: {e}")))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(tasks)
+    }
+
+    // ── Skills ────────────────────────────────────
+
+    /// List skills (global + tenant-specific) matching `filter`, builtins first.
+    pub fn list_skills(&self, tenant_id: Option<&str>, filter: &SkillFilter) -> Result<SkillPage> {
+        const WHERE: &str = "WHERE (tenant_id IS NULL OR tenant_id=?1)
+             AND (?2 IS NULL OR category=?2) AND (?3 IS NULL OR language=?3)
+             AND (?4 IS NULL OR enabled=?4) AND (?5 IS NULL OR is_builtin=?5)";
+        let enabled = filter.enabled.map(i32::from);
+        let builtin = filter.builtin.map(i32::from);
+
+        let total: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM skills {WHERE}"),
+            params![tenant_id, filter.category, filter.language, enabled, builtin],
+            |row| row.get(0),
+        ).map_err(|e| BizClawError::Memory(format!("Count skills: {e}")))?;
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, tenant_id, name, slug, description, version, language, category, entry_point, enabled, is_builtin, usage_count, created_at
+             FROM skills {WHERE} ORDER BY is_builtin DESC, name LIMIT ?6 OFFSET ?7"
+        )).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+
+        // SQLite treats a negative LIMIT as "no limit".
+        let limit = filter.limit.map_or(-1, i64::from);
+        let skills = stmt
+            .query_map(
+                params![tenant_id, filter.category, filter.language, enabled, builtin, limit, filter.offset],
+                |row| {
+                    Ok(Skill {
+                        id: row.get(0)?, tenant_id: row.get(1)?,
+                        name: row.get(2)?, slug: row.get(3)?, description: row.get(4)?,
+                        version: row.get(5)?, language: row.get(6)?,
+                        category: row.get(7)?, entry_point: row.get(8)?,
+                        enabled: row.get::<_, i32>(9)? != 0,
+                        is_builtin: row.get::<_, i32>(10)? != 0,
+                        usage_count: row.get(11)?, created_at: row.get(12)?,
+                    })
+                },
+            )
+            .map_err(|e| BizClawError::Memory(format!("Query: {e}")))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(SkillPage { skills, total: total as usize })
+    }
+
+    /// Create or update a skill.
+    #[allow(clippy::too_many_arguments)]
+    pub fn upsert_skill(
+        &self, tenant_id: Option<&str>, name: &str, slug: &str,
+        description: &str, language: &str, category: &str,
+        source_code: Option<&str>, entry_point: &str,
+    ) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.conn.execute(
+            "INSERT INTO skills (id, tenant_id, name, slug, description, language, category, source_code, entry_point)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(tenant_id, slug) DO UPDATE SET
+               name=?3, description=?5, language=?6, category=?7, source_code=?8, entry_point=?9, updated_at=datetime('now')",
+            params![id, tenant_id, name, slug, description, language, category, source_code, entry_point],
+        ).map_err(|e| BizClawError::Memory(format!("Upsert skill: {e}")))?;
+        Ok(id)
+    }
+
+    /// Enable or disable a tenant's skill. Returns false if it has none with `slug`.
+    pub fn set_skill_enabled(&self, tenant_id: &str, slug: &str, enabled: bool) -> Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE skills SET enabled=?3, updated_at=datetime('now') WHERE tenant_id=?1 AND slug=?2",
+            params![tenant_id, slug, enabled as i32],
+        ).map_err(|e| BizClawError::Memory(format!("Toggle skill: {e}")))?;
+        Ok(changed > 0)
+    }
+
+    /// Delete a tenant's skill. Global (builtin) skills are not affected.
+    pub fn delete_skill(&self, tenant_id: &str, slug: &str) -> Result<bool> {
+        let changed = self.conn.execute(
+            "DELETE FROM skills WHERE tenant_id=?1 AND slug=?2",
+            params![tenant_id, slug],
+        ).map_err(|e| BizClawError::Memory(format!("Delete skill: {e}")))?;
+        Ok(changed > 0)
+    }
+
+    /// Add the skills bundled with BizClaw as global builtins. Skills already
+    /// present (by slug) are left alone, so this is safe to run on every start.
+    /// Returns how many were added.
+    pub fn seed_builtin_skills(&self) -> Result<usize> {
+        let mut stmt = self.conn.prepare(
+            "INSERT INTO skills (id, tenant_id, name, slug, description, version, language, category, source_code, is_builtin)
+             SELECT ?1, NULL, ?2, ?3, ?4, ?5, 'markdown', ?6, ?7, 1
+             WHERE NOT EXISTS (SELECT 1 FROM skills WHERE tenant_id IS NULL AND slug=?3)"
+        ).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+        let mut added = 0;
+        for skill in bizclaw_skills::builtin::builtin_skills() {
+            let meta = &skill.metadata;
+            let name = if meta.display_name.is_empty() { &meta.name } else { &meta.display_name };
+            added += stmt.execute(params![
+                uuid::Uuid::new_v4().to_string(), name, meta.name, meta.description,
+                meta.version, meta.category, skill.content,
+            ]).map_err(|e| BizClawError::Memory(format!("Seed skill: {e}")))?;
+        }
+        Ok(added)
+    }
+
+    // ── Custom Domains ────────────────────────────────────
+
+    /// Register `domain` for a tenant, unverified, with a fresh challenge
+    /// token. Adding a domain the tenant already has returns it unchanged;
+    /// a domain registered by another tenant is refused.
+    pub fn add_custom_domain(&self, tenant_id: &str, domain: &str) -> Result<CustomDomain> {
+        let domain = validate_custom_domain(domain)?;
+        if let Some(existing) = self.get_custom_domain(&domain)? {
+            if existing.tenant_id == tenant_id {
+                return Ok(existing);
+            }
+            return Err(BizClawError::Config(format!("Domain '{domain}' is already in use")));
+        }
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        self.conn.execute(
+            "INSERT INTO custom_domains (domain, tenant_id, token) VALUES (?1, ?2, ?3)",
+            params![domain, tenant_id, token],
+        ).map_err(|e| BizClawError::Memory(format!("Add custom domain: {e}")))?;
+        self.get_custom_domain(&domain)?
+            .ok_or_else(|| BizClawError::Memory(format!("Custom domain '{domain}' vanished")))
+    }
+
+    /// Look up a registered domain, verified or not.
+    pub fn get_custom_domain(&self, domain: &str) -> Result<Option<CustomDomain>> {
+        match self.conn.query_row(
+            "SELECT domain, tenant_id, token, verified_at, created_at FROM custom_domains WHERE domain=?1",
+            params![domain],
+            row_to_custom_domain,
+        ) {
+            Ok(d) => Ok(Some(d)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(BizClawError::Memory(format!("Get custom domain: {e}"))),
+        }
+    }
+
+    /// A tenant's domains, verified or not.
+    pub fn list_custom_domains(&self, tenant_id: &str) -> Result<Vec<CustomDomain>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT domain, tenant_id, token, verified_at, created_at FROM custom_domains WHERE tenant_id=?1 ORDER BY domain"
+        ).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+        let domains = stmt
+            .query_map(params![tenant_id], row_to_custom_domain)
+            .map_err(|e| BizClawError::Memory(format!("Query: {e}")))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(domains)
+    }
+
+    /// Record that the owner proved control of `domain`; from now on it is routed.
+    pub fn mark_custom_domain_verified(&self, domain: &str) -> Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE custom_domains SET verified_at=COALESCE(verified_at, datetime('now')) WHERE domain=?1",
+            params![domain],
+        ).map_err(|e| BizClawError::Memory(format!("Verify custom domain: {e}")))?;
+        Ok(changed > 0)
+    }
+
+    /// Remove one of a tenant's domains. Returns false if it has no such domain.
+    pub fn delete_custom_domain(&self, tenant_id: &str, domain: &str) -> Result<bool> {
+        let changed = self.conn.execute(
+            "DELETE FROM custom_domains WHERE tenant_id=?1 AND domain=?2",
+            params![tenant_id, domain],
+        ).map_err(|e| BizClawError::Memory(format!("Delete custom domain: {e}")))?;
+        Ok(changed > 0)
+    }
+
+    /// The tenant a verified custom domain belongs to. Unverified domains
+    /// resolve to nothing.
+    pub fn get_tenant_by_domain(&self, domain: &str) -> Result<Option<Tenant>> {
+        match self.conn.query_row(
+            &format!(
+                "{} WHERE id=(SELECT tenant_id FROM custom_domains WHERE domain=?1 AND verified_at IS NOT NULL)",
+                TENANT_SELECT
+            ),
+            params![domain],
+            row_to_tenant,
+        ) {
+            Ok(t) => Ok(Some(t)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(BizClawError::Memory(format!("Get tenant by domain: {e}"))),
+        }
+    }
+
+    // ── LLM Usage (reported by tenant gateways) ────────────────────────────
+
+    /// Secret a tenant presents when reporting usage (created on first use).
+    pub fn usage_report_key(&self, tenant_id: &str) -> Result<String> {
+        if let Some(key) = self.find_usage_report_key(tenant_id)? {
+            return Ok(key);
+        }
+        let key = uuid::Uuid::new_v4().simple().to_string();
+        self.set_config(tenant_id, USAGE_REPORT_KEY, &key)?;
+        Ok(key)
+    }
+
+    /// The tenant's usage report key, if one was created yet.
+    pub fn find_usage_report_key(&self, tenant_id: &str) -> Result<Option<String>> {
+        self.get_config(tenant_id, USAGE_REPORT_KEY)
+    }
+
+    /// Check a usage report key against the tenant's stored key.
+    pub fn verify_usage_report_key(&self, tenant_id: &str, key: &str) -> bool {
+        matches!(self.get_config(tenant_id, USAGE_REPORT_KEY), Ok(Some(k)) if !key.is_empty() && k == key)
+    }
+
+    /// Store LLM calls reported by a tenant.
+    pub fn record_llm_usage(&self, tenant_id: &str, records: &[LlmUsageRecord]) -> Result<usize> {
+        let mut stmt = self.conn.prepare(
+            "INSERT INTO llm_usage (id, tenant_id, agent_name, provider, model, prompt_tokens, completion_tokens, total_tokens, cost_usd, created_at, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, COALESCE(datetime(?10), datetime('now')), COALESCE(?11, 'ok'))"
+        ).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+        for r in records {
+            stmt.execute(params![
+                uuid::Uuid::new_v4().to_string(), tenant_id, r.agent_name, r.provider, r.model,
+                r.prompt_tokens, r.completion_tokens, r.prompt_tokens + r.completion_tokens,
+                r.cost_usd, r.created_at, r.status,
+            ]).map_err(|e| BizClawError::Memory(format!("Record LLM usage: {e}")))?;
+        }
+        Ok(records.len())
+    }
+
+    /// Aggregate a tenant's LLM usage, optionally since a timestamp (RFC 3339 or `YYYY-MM-DD`).
+    pub fn llm_usage_summary(&self, tenant_id: &str, since: Option<&str>) -> Result<LlmUsageSummary> {
+        let mut stmt = self.conn.prepare(
+            "SELECT provider, model, COUNT(*), SUM(prompt_tokens), SUM(completion_tokens), SUM(total_tokens), SUM(cost_usd),
+                    SUM(CASE WHEN status = 'error' THEN 1 ELSE 0 END)
+             FROM llm_usage
+             WHERE tenant_id=?1 AND (?2 IS NULL OR created_at >= datetime(?2))
+             GROUP BY provider, model ORDER BY SUM(total_tokens) DESC"
+        ).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+
+        let mut summary = LlmUsageSummary {
+            tenant_id: tenant_id.to_string(),
+            since: since.map(String::from),
+            requests: 0, prompt_tokens: 0, completion_tokens: 0, total_tokens: 0, cost_usd: 0.0,
+            errors: 0, by_model: vec![],
+        };
+        let rows = stmt
+            .query_map(params![tenant_id, since], |row| {
+                Ok((
+                    row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?,
+                    row.get::<_, i64>(3)?, row.get::<_, i64>(4)?, row.get::<_, i64>(5)?,
+                    row.get::<_, f64>(6)?, row.get::<_, i64>(7)?,
+                ))
+            })
+            .map_err(|e| BizClawError::Memory(format!("Query: {e}")))?;
+        for (provider, model, requests, prompt, completion, total, cost, errors) in rows.flatten() {
+            summary.requests += requests;
+            summary.errors += errors;
+            summary.prompt_tokens += prompt;
+            summary.completion_tokens += completion;
+            summary.total_tokens += total;
+            summary.cost_usd += cost;
+            summary.by_model.push(LlmModelUsage { provider, model, requests, total_tokens: total, cost_usd: cost });
+        }
+        Ok(summary)
+    }
+
+    /// All-time LLM usage of every tenant that has reported any, keyed by tenant id.
+    /// `by_model` is left empty.
+    pub fn llm_usage_totals(&self) -> Result<HashMap<String, LlmUsageSummary>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tenant_id, COUNT(*), SUM(prompt_tokens), SUM(completion_tokens), SUM(total_tokens), SUM(cost_usd),
+                    SUM(CASE WHEN status = 'error' THEN 1 ELSE 0 END)
+             FROM llm_usage GROUP BY tenant_id"
+        ).map_err(|e| BizClawError::Memory(format!("Prepare: {e}")))?;
+
+        let totals = stmt
+            .query_map([], |row| {
+                let tenant_id: String = row.get(0)?;
+                Ok((tenant_id.clone(), LlmUsageSummary {
+                    tenant_id,
+                    since: None,
+                    requests: row.get(1)?,
+                    prompt_tokens: row.get(2)?,
+                    completion_tokens: row.get(3)?,
+                    total_tokens: row.get(4)?,
+                    cost_usd: row.get(5)?,
+                    errors: row.get(6)?,
+                    by_model: vec![],
+                }))
+            })
+            .map_err(|e| BizClawError::Memory(format!("Query: {e}")))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(totals)
+    }
+}
+
+fn row_to_custom_domain(row: &rusqlite::Row) -> rusqlite::Result<CustomDomain> {
+    Ok(CustomDomain {
+        domain: row.get(0)?,
+        tenant_id: row.get(1)?,
+        token: row.get(2)?,
+        verified_at: row.get(3)?,
+        created_at: row.get(4)?,
+    })
 }
 
 fn rand_code() -> u32 {
@@ -928,6 +2048,63 @@ mod tests {
         assert_eq!(tenants.len(), 1);
     }
 
+    #[test]
+    fn test_sandbox_tenant_has_limits_and_expiry() {
+        let db = temp_db();
+        let t = db
+            .create_sandbox_tenant("Demo", "demo-shop", 10001, "openai", "gpt-4o-mini", 60)
+            .unwrap();
+        assert!(t.sandbox);
+        assert_eq!(t.plan, "sandbox");
+        assert_eq!(t.max_messages_day, SANDBOX_MAX_MESSAGES_DAY);
+        assert_eq!(t.max_channels, SANDBOX_MAX_CHANNELS);
+        assert_eq!(t.max_members, SANDBOX_MAX_MEMBERS);
+        assert!(t.expires_at.as_deref().unwrap() > t.created_at.as_str());
+        assert!(db.list_expired_sandboxes().unwrap().is_empty());
+
+        // Regular tenants never expire.
+        let regular = db
+            .create_tenant("Shop", "shop", 10002, "openai", "gpt-4o-mini", "free", None)
+            .unwrap();
+        assert!(!regular.sandbox);
+        assert!(regular.expires_at.is_none());
+
+        let old = db
+            .create_sandbox_tenant("Demo", "demo-old", 10003, "openai", "gpt-4o-mini", -1)
+            .unwrap();
+        let expired = db.list_expired_sandboxes().unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, old.id);
+        assert_eq!(db.count_live_sandboxes().unwrap(), 1);
+
+        // The LLM proxy key only resolves while the sandbox is live.
+        db.set_config(&t.id, SANDBOX_KEY_CONFIG, "sbx-live").unwrap();
+        db.set_config(&old.id, SANDBOX_KEY_CONFIG, "sbx-old").unwrap();
+        assert_eq!(db.live_sandbox_by_key("sbx-live").unwrap().unwrap().id, t.id);
+        assert!(db.live_sandbox_by_key("sbx-old").unwrap().is_none());
+        assert!(db.live_sandbox_by_key("").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_tenant_slug_validation() {
+        let db = temp_db();
+        for slug in ["", "-shop", "shop-", "Shop", "shop_1", "shop.vn", "cửa-hàng", "admin", "www"] {
+            assert!(
+                db.create_tenant("X", slug, 10001, "openai", "gpt-4o-mini", "free", None).is_err(),
+                "slug {slug:?} should be rejected"
+            );
+        }
+        assert!(validate_tenant_slug(&"a".repeat(64)).is_err());
+        let err = validate_tenant_slug("api").unwrap_err().to_string();
+        assert!(err.contains("reserved"), "{err}");
+
+        for slug in ["a", "shop-1", "123", &"b".repeat(63)] {
+            assert!(validate_tenant_slug(slug).is_ok(), "slug {slug:?} should be accepted");
+        }
+        assert!(db.create_tenant("Shop", "shop-1", 10001, "openai", "gpt-4o-mini", "free", None).is_ok());
+        assert!(db.list_tenants().unwrap().iter().all(|t| t.slug == "shop-1"));
+    }
+
     #[test]
     fn test_tenant_status_update() {
         let db = temp_db();
@@ -1103,4 +2280,310 @@ mod tests {
         assert_eq!(updated.provider, "ollama");
         assert_eq!(updated.model, "llama3.2");
     }
+
+    #[test]
+    fn test_tenant_channels_upsert() {
+        let db = temp_db();
+        let t = db
+            .create_tenant("Bot", "bot", 10001, "openai", "gpt-4o-mini", "free", None)
+            .unwrap();
+
+        let ch = db.upsert_channel(&t.id, "telegram", true, r#"{"token":"a"}"#).unwrap();
+        assert_eq!(ch.channel_type, "telegram");
+        assert!(ch.enabled);
+
+        // Upsert existing — must hit ON CONFLICT(tenant_id, channel_type, instance_id)
+        let ch = db.upsert_channel(&t.id, "telegram", false, r#"{"token":"b"}"#).unwrap();
+        assert!(!ch.enabled);
+        assert_eq!(ch.config_json, r#"{"token":"b"}"#);
+
+        db.upsert_channel(&t.id, "discord", true, "{}").unwrap();
+        let channels = db.list_channels(&t.id).unwrap();
+        assert_eq!(channels.len(), 2);
+
+        db.update_channel_status(&ch.id, "connected", None).unwrap();
+        assert_eq!(db.get_channel(&ch.id).unwrap().status, "connected");
+
+        db.delete_channel(&ch.id).unwrap();
+        assert_eq!(db.list_channels(&t.id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_memory_personal_and_task() {
+        let db = temp_db();
+        db.store_personal_memory("t1", Some("u1"), "preference", "language", "vi", 0.9, "explicit")
+            .unwrap();
+        db.store_personal_memory("t1", Some("u2"), "fact", "name", "Lan", 1.0, "observed")
+            .unwrap();
+
+        assert_eq!(db.get_personal_memories("t1", None).unwrap().len(), 2);
+        let u1 = db.get_personal_memories("t1", Some("u1")).unwrap();
+        assert_eq!(u1.len(), 1);
+        assert_eq!(u1[0].value, "vi");
+
+        let id = db
+            .store_task_memory("t1", "research", "Compare CRMs", "web search", "success", "Use G2", Some(30), Some(1200))
+            .unwrap();
+        assert!(!id.is_empty());
+    }
+
+    #[test]
+    fn test_memory_tool_usage_upsert() {
+        let db = temp_db();
+        db.record_tool_usage("t1", "web_search", true, 100, None).unwrap();
+        db.record_tool_usage("t1", "web_search", false, 300, Some("timeout")).unwrap();
+        db.record_tool_usage("t1", "shell", true, 10, None).unwrap();
+
+        let tools = db.list_tool_memories("t1").unwrap();
+        assert_eq!(tools.len(), 2);
+        let ws = &tools[0];
+        assert_eq!(ws.tool_name, "web_search");
+        assert_eq!(ws.usage_count, 2);
+        assert_eq!(ws.success_count, 1);
+        assert_eq!(ws.failure_count, 1);
+        assert_eq!(ws.avg_duration_ms, 200);
+    }
+
+    #[test]
+    fn test_memory_search() {
+        let db = temp_db();
+        let id = db
+            .upsert_working_memory("t1", "s1", Some("telegram"), None, "Customer asked about pricing",
+                &serde_json::json!(["pricing"]), 4, 120)
+            .unwrap();
+        db.index_memory("t1", "working", &id, "Customer asked about pricing").unwrap();
+        db.index_memory("t2", "working", &id, "Other tenant pricing").unwrap();
+
+        let hits = db.search_memories("t1", "pricing", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0]["memory_type"], "working");
+    }
+
+    #[test]
+    fn test_heartbeat_config_and_tasks() {
+        let db = temp_db();
+        let cfg = db.get_heartbeat_config("t1").unwrap();
+        assert!(!cfg.enabled);
+        assert_eq!(cfg.interval_seconds, 1800);
+
+        // Second call returns the same row (UNIQUE(tenant_id))
+        assert_eq!(db.get_heartbeat_config("t1").unwrap().id, cfg.id);
+
+        db.update_heartbeat_config("t1", true, 600, Some("telegram"), Some("123")).unwrap();
+        let cfg = db.get_heartbeat_config("t1").unwrap();
+        assert!(cfg.enabled);
+        assert_eq!(cfg.interval_seconds, 600);
+        assert_eq!(cfg.notify_target.as_deref(), Some("123"));
+
+        db.create_heartbeat_task("t1", "rss_digest", "cron", Some("0 8 * * *"), "rss",
+            &serde_json::json!({"feeds": 3})).unwrap();
+        let tasks = db.list_heartbeat_tasks("t1").unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].config["feeds"], 3);
+        assert!(tasks[0].enabled);
+    }
+
+    #[test]
+    fn test_skills_upsert() {
+        let db = temp_db();
+        db.upsert_skill(Some("t1"), "Summarize", "summarize", "Summarize text", "python", "productivity", None, "main")
+            .unwrap();
+        db.upsert_skill(Some("t1"), "Summarize v2", "summarize", "Better", "python", "productivity", None, "main")
+            .unwrap();
+        db.upsert_skill(Some("t2"), "Other", "other", "", "shell", "desktop", None, "main")
+            .unwrap();
+
+        let page = db.list_skills(Some("t1"), &SkillFilter::default()).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.skills[0].name, "Summarize v2");
+    }
+
+    #[test]
+    fn test_skills_filter_and_paging() {
+        let db = temp_db();
+        for (slug, language, category) in [
+            ("summarize", "python", "productivity"),
+            ("translate", "python", "writing"),
+            ("backup", "shell", "devops"),
+            ("deploy", "shell", "devops"),
+        ] {
+            db.upsert_skill(Some("t1"), slug, slug, "", language, category, None, "main").unwrap();
+        }
+        let filter = |f: SkillFilter| db.list_skills(Some("t1"), &f).unwrap();
+
+        let devops = filter(SkillFilter { category: Some("devops".into()), ..Default::default() });
+        assert_eq!(devops.total, 2);
+        assert!(devops.skills.iter().all(|s| s.category.as_deref() == Some("devops")));
+
+        let python = filter(SkillFilter { language: Some("python".into()), ..Default::default() });
+        let slugs: Vec<_> = python.skills.iter().map(|s| s.slug.as_str()).collect();
+        assert_eq!(slugs, ["summarize", "translate"]);
+
+        let page = filter(SkillFilter { limit: Some(3), offset: 2, ..Default::default() });
+        assert_eq!(page.total, 4);
+        let slugs: Vec<_> = page.skills.iter().map(|s| s.slug.as_str()).collect();
+        assert_eq!(slugs, ["summarize", "translate"]);
+
+        assert_eq!(filter(SkillFilter { builtin: Some(true), ..Default::default() }).total, 0);
+    }
+
+    #[test]
+    fn test_skill_toggle_persists() {
+        let db = temp_db();
+        db.upsert_skill(Some("t1"), "Backup", "backup", "", "shell", "devops", None, "main").unwrap();
+        let enabled = |value| SkillFilter { enabled: Some(value), ..Default::default() };
+
+        assert!(db.set_skill_enabled("t1", "backup", false).unwrap());
+        assert_eq!(db.list_skills(Some("t1"), &enabled(false)).unwrap().total, 1);
+        assert_eq!(db.list_skills(Some("t1"), &enabled(true)).unwrap().total, 0);
+
+        // Re-installing keeps the admin's choice.
+        db.upsert_skill(Some("t1"), "Backup v2", "backup", "", "shell", "devops", None, "main").unwrap();
+        assert!(!db.list_skills(Some("t1"), &SkillFilter::default()).unwrap().skills[0].enabled);
+
+        assert!(db.set_skill_enabled("t1", "backup", true).unwrap());
+        assert_eq!(db.list_skills(Some("t1"), &enabled(true)).unwrap().total, 1);
+
+        // Another tenant cannot toggle or delete it.
+        assert!(!db.set_skill_enabled("t2", "backup", false).unwrap());
+        assert!(!db.delete_skill("t2", "backup").unwrap());
+        assert!(db.delete_skill("t1", "backup").unwrap());
+        assert_eq!(db.list_skills(Some("t1"), &SkillFilter::default()).unwrap().total, 0);
+    }
+
+    #[test]
+    fn test_seed_builtin_skills_once() {
+        let db = temp_db();
+        let builtins = bizclaw_skills::builtin::builtin_skills();
+        assert_eq!(db.seed_builtin_skills().unwrap(), builtins.len());
+
+        let builtin = SkillFilter { builtin: Some(true), ..Default::default() };
+        let page = db.list_skills(Some("t1"), &builtin).unwrap();
+        assert_eq!(page.total, builtins.len());
+        assert!(page.skills.iter().all(|s| s.tenant_id.is_none() && s.enabled));
+        assert!(page.skills.iter().any(|s| s.slug == "rust-expert" && s.name == "Rust Expert"));
+
+        assert_eq!(db.seed_builtin_skills().unwrap(), 0);
+        assert_eq!(db.list_skills(None, &SkillFilter::default()).unwrap().total, builtins.len());
+    }
+
+    #[test]
+    fn test_feature_flag_overrides_plan_default() {
+        let db = temp_db();
+        let t = db
+            .create_tenant("Bot", "bot", 10001, "openai", "gpt-4o-mini", "free", None)
+            .unwrap();
+        assert!(!db.feature_enabled(&t.id, "skills").unwrap());
+
+        db.set_feature_flag(&t.id, "skills", Some(true)).unwrap();
+        db.set_feature_flag(&t.id, "web_tool", Some(false)).unwrap();
+        assert!(db.feature_enabled(&t.id, "skills").unwrap());
+        assert!(!db.feature_enabled(&t.id, "web_tool").unwrap());
+        assert_eq!(db.feature_flag_overrides(&t.id).unwrap().len(), 2);
+
+        db.set_feature_flag(&t.id, "skills", None).unwrap();
+        assert!(!db.feature_enabled(&t.id, "skills").unwrap());
+        assert_eq!(db.feature_flag_overrides(&t.id).unwrap(), [("web_tool".to_string(), false)]);
+    }
+
+    fn usage(model: &str, prompt: i64, completion: i64, cost: f64, at: &str) -> LlmUsageRecord {
+        LlmUsageRecord {
+            agent_name: "default".into(),
+            provider: "openai".into(),
+            model: model.into(),
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            cost_usd: cost,
+            created_at: Some(at.into()),
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_llm_usage_aggregates_per_tenant_and_window() {
+        let db = temp_db();
+        db.record_llm_usage("t1", &[
+            usage("gpt-4o-mini", 100, 50, 0.01, "2026-01-01T08:00:00Z"),
+            usage("gpt-4o-mini", 200, 100, 0.02, "2026-01-05T08:00:00Z"),
+            usage("gpt-4o", 1000, 500, 0.50, "2026-01-06T09:30:00+07:00"),
+        ]).unwrap();
+        db.record_llm_usage("t2", &[usage("gpt-4o", 9000, 9000, 9.0, "2026-01-05T00:00:00Z")]).unwrap();
+
+        let all = db.llm_usage_summary("t1", None).unwrap();
+        assert_eq!(all.requests, 3);
+        assert_eq!(all.total_tokens, 1950);
+        assert!((all.cost_usd - 0.53).abs() < 1e-9);
+        assert_eq!(all.by_model[0].model, "gpt-4o");
+
+        let recent = db.llm_usage_summary("t1", Some("2026-01-05")).unwrap();
+        assert_eq!(recent.requests, 2);
+        assert_eq!(recent.prompt_tokens, 1200);
+        assert_eq!(recent.completion_tokens, 600);
+        assert_eq!(recent.by_model.len(), 2);
+
+        assert_eq!(db.llm_usage_summary("t3", None).unwrap().requests, 0);
+    }
+
+    #[test]
+    fn test_llm_usage_totals_per_tenant() {
+        let db = temp_db();
+        db.record_llm_usage("t1", &[
+            usage("gpt-4o-mini", 100, 50, 0.01, "2026-01-01T08:00:00Z"),
+            usage("gpt-4o", 1000, 500, 0.50, "2026-01-06T09:30:00Z"),
+        ]).unwrap();
+        db.record_llm_usage("t2", &[usage("gpt-4o", 9000, 9000, 9.0, "2026-01-05T00:00:00Z")]).unwrap();
+
+        let totals = db.llm_usage_totals().unwrap();
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals["t1"].requests, 2);
+        assert_eq!(totals["t1"].prompt_tokens, 1100);
+        assert_eq!(totals["t2"].total_tokens, 18000);
+    }
+
+    #[test]
+    fn test_custom_domain_resolves_only_when_verified() {
+        let db = temp_db();
+        let acme = db.create_tenant("Acme", "acme", 10001, "openai", "gpt-4o-mini", "free", None).unwrap();
+        let other = db.create_tenant("Other", "other", 10002, "openai", "gpt-4o-mini", "free", None).unwrap();
+
+        let domain = db.add_custom_domain(&acme.id, "Chat.Acme.com.").unwrap();
+        assert_eq!(domain.domain, "chat.acme.com");
+        assert!(!domain.is_verified());
+        assert!(db.get_tenant_by_domain("chat.acme.com").unwrap().is_none());
+
+        // Re-adding keeps the token; another tenant cannot take the domain.
+        assert_eq!(db.add_custom_domain(&acme.id, "chat.acme.com").unwrap().token, domain.token);
+        assert!(db.add_custom_domain(&other.id, "chat.acme.com").is_err());
+
+        assert!(db.mark_custom_domain_verified("chat.acme.com").unwrap());
+        let tenant = db.get_tenant_by_domain("chat.acme.com").unwrap().unwrap();
+        assert_eq!(tenant.slug, "acme");
+        assert_eq!(db.list_custom_domains(&acme.id).unwrap().len(), 1);
+        assert!(db.list_custom_domains(&other.id).unwrap().is_empty());
+
+        assert!(!db.delete_custom_domain(&other.id, "chat.acme.com").unwrap());
+        db.delete_tenant(&acme.id).unwrap();
+        assert!(db.get_custom_domain("chat.acme.com").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_validate_custom_domain() {
+        assert_eq!(validate_custom_domain(" Chat.Acme.COM ").unwrap(), "chat.acme.com");
+        assert_eq!(validate_custom_domain("bot.cong-ty.com.vn").unwrap(), "bot.cong-ty.com.vn");
+        assert!(validate_custom_domain("localhost").is_err());
+        assert!(validate_custom_domain("10.0.0.1").is_err());
+        assert!(validate_custom_domain("chat..acme.com").is_err());
+        assert!(validate_custom_domain("-chat.acme.com").is_err());
+        assert!(validate_custom_domain("chat.acme.com/path").is_err());
+    }
+
+    #[test]
+    fn test_usage_report_key() {
+        let db = temp_db();
+        let key = db.usage_report_key("t1").unwrap();
+        assert_eq!(db.usage_report_key("t1").unwrap(), key);
+        assert!(db.verify_usage_report_key("t1", &key));
+        assert!(!db.verify_usage_report_key("t2", &key));
+        assert!(!db.verify_usage_report_key("t1", ""));
+    }
 }
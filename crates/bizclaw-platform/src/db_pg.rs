@@ -5,112 +5,10 @@ use bizclaw_core::error::{BizClawError, Result};
 use sqlx::{PgPool, Row, postgres::PgPoolOptions};
 
 // Re-export shared types from the SQLite module
-pub use crate::db::{Tenant, User, AuditEntry, TenantChannel, TenantConfig, TenantAgent};
-
-/// Memory types matching ReMe's 4-type system.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct PersonalMemory {
-    pub id: String,
-    pub tenant_id: String,
-    pub user_id: Option<String>,
-    pub category: String,
-    pub key: String,
-    pub value: String,
-    pub confidence: f32,
-    pub source: Option<String>,
-    pub created_at: String,
-    pub updated_at: String,
-}
-
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct TaskMemory {
-    pub id: String,
-    pub tenant_id: String,
-    pub task_type: String,
-    pub task_description: String,
-    pub approach: Option<String>,
-    pub outcome: String,
-    pub lessons_learned: Option<String>,
-    pub duration_seconds: Option<i32>,
-    pub tokens_used: Option<i32>,
-    pub created_at: String,
-}
-
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct ToolMemory {
-    pub id: String,
-    pub tenant_id: String,
-    pub tool_name: String,
-    pub usage_count: i32,
-    pub success_count: i32,
-    pub failure_count: i32,
-    pub avg_duration_ms: i32,
-    pub tips: Option<String>,
-    pub last_used: String,
-}
-
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct WorkingMemory {
-    pub id: String,
-    pub tenant_id: String,
-    pub session_id: String,
-    pub channel: Option<String>,
-    pub user_id: Option<String>,
-    pub summary: String,
-    pub key_facts: serde_json::Value,
-    pub message_count: i32,
-    pub token_count: i32,
-    pub is_active: bool,
-    pub created_at: String,
-    pub updated_at: String,
-}
-
-/// Heartbeat configuration.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct HeartbeatConfig {
-    pub id: String,
-    pub tenant_id: String,
-    pub enabled: bool,
-    pub interval_seconds: i32,
-    pub notify_channel: Option<String>,
-    pub notify_target: Option<String>,
-    pub last_heartbeat: Option<String>,
-    pub next_heartbeat: Option<String>,
-}
-
-/// Heartbeat task definition.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct HeartbeatTask {
-    pub id: String,
-    pub tenant_id: String,
-    pub task_name: String,
-    pub task_type: String,
-    pub cron_expression: Option<String>,
-    pub handler: String,
-    pub config: serde_json::Value,
-    pub enabled: bool,
-    pub last_run: Option<String>,
-    pub last_result: Option<String>,
-    pub run_count: i32,
-}
-
-/// Skill definition.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct Skill {
-    pub id: String,
-    pub tenant_id: Option<String>,
-    pub name: String,
-    pub slug: String,
-    pub description: String,
-    pub version: String,
-    pub language: String,
-    pub category: Option<String>,
-    pub entry_point: String,
-    pub enabled: bool,
-    pub is_builtin: bool,
-    pub usage_count: i32,
-    pub created_at: String,
-}
+pub use crate::db::{
+    Tenant, User, AuditEntry, TenantChannel, TenantConfig, TenantAgent,
+    PersonalMemory, TaskMemory, ToolMemory, WorkingMemory, HeartbeatConfig, HeartbeatTask, Skill,
+};
 
 /// PostgreSQL-backed platform database with connection pool.
 #[derive(Clone)]
@@ -142,6 +40,22 @@ impl PgDb {
         Ok(db)
     }
 
+    /// Readiness probe: the pool answers `SELECT 1` and the schema is migrated.
+    pub async fn check_ready(&self) -> Result<()> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| BizClawError::Memory(format!("PG unreachable: {e}")))?;
+        let migrated: bool = sqlx::query_scalar("SELECT to_regclass('public.tenants') IS NOT NULL")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| BizClawError::Memory(format!("PG schema check: {e}")))?;
+        if !migrated {
+            return Err(BizClawError::Memory("PG migrations not applied".into()));
+        }
+        Ok(())
+    }
+
     /// Run schema migrations inline (for when docker-entrypoint-initdb.d wasn't used).
     async fn migrate(&self) -> Result<()> {
         // Run the migration SQL embedded at compile time using raw_sql
@@ -206,6 +120,14 @@ impl PgDb {
             })
             .ok();
 
+        // Sandbox migration — self-serve demo tenants that expire
+        let sandbox_sql = include_str!("../../../migrations/005_sandbox.sql");
+        sqlx::raw_sql(sandbox_sql)
+            .execute(self.pool())
+            .await
+            .map_err(|e| tracing::warn!("PG sandbox migration error: {e}"))
+            .ok();
+
         Ok(())
     }
 
@@ -226,6 +148,7 @@ impl PgDb {
         &self, name: &str, slug: &str, port: u16,
         provider: &str, model: &str, plan: &str, owner_id: Option<&str>,
     ) -> Result<Tenant> {
+        crate::db::validate_tenant_slug(slug)?;
         let id = uuid::Uuid::new_v4().to_string();
         let pairing_code = format!("{:06}", rand_code());
 
@@ -244,7 +167,7 @@ impl PgDb {
 
     pub async fn get_tenant(&self, id: &str) -> Result<Tenant> {
         let row = sqlx::query(
-            "SELECT id,name,slug,status,port,plan,provider,model,max_messages_day,max_channels,max_members,pairing_code,pid,cpu_percent,memory_bytes,disk_bytes,owner_id,created_at FROM tenants WHERE id=$1"
+            &format!("{PG_TENANT_SELECT} WHERE id=$1")
         )
         .bind(id)
         .fetch_one(&self.pool)
@@ -256,7 +179,7 @@ impl PgDb {
 
     pub async fn list_tenants(&self) -> Result<Vec<Tenant>> {
         let rows = sqlx::query(
-            "SELECT id,name,slug,status,port,plan,provider,model,max_messages_day,max_channels,max_members,pairing_code,pid,cpu_percent,memory_bytes,disk_bytes,owner_id,created_at FROM tenants ORDER BY created_at DESC"
+            &format!("{PG_TENANT_SELECT} ORDER BY created_at DESC")
         )
         .fetch_all(&self.pool)
         .await
@@ -267,7 +190,7 @@ impl PgDb {
 
     pub async fn list_tenants_by_owner(&self, owner_id: &str) -> Result<Vec<Tenant>> {
         let rows = sqlx::query(
-            "SELECT id,name,slug,status,port,plan,provider,model,max_messages_day,max_channels,max_members,pairing_code,pid,cpu_percent,memory_bytes,disk_bytes,owner_id,created_at FROM tenants WHERE owner_id=$1 ORDER BY created_at DESC"
+            &format!("{PG_TENANT_SELECT} WHERE owner_id=$1 ORDER BY created_at DESC")
         )
         .bind(owner_id)
         .fetch_all(&self.pool)
@@ -277,6 +200,46 @@ impl PgDb {
         Ok(rows.iter().map(pg_row_to_tenant).collect())
     }
 
+    /// Create a self-serve sandbox tenant with the `SANDBOX_*` limits that
+    /// expires `ttl_minutes` from now.
+    pub async fn create_sandbox_tenant(
+        &self, name: &str, slug: &str, port: u16,
+        provider: &str, model: &str, ttl_minutes: i64,
+    ) -> Result<Tenant> {
+        let tenant = self.create_tenant(name, slug, port, provider, model, "sandbox", None).await?;
+        sqlx::query(
+            "UPDATE tenants SET sandbox=TRUE, expires_at=NOW() + make_interval(mins => $1),
+             max_messages_day=$2, max_channels=$3, max_members=$4 WHERE id=$5"
+        )
+        .bind(ttl_minutes as i32)
+        .bind(crate::db::SANDBOX_MAX_MESSAGES_DAY as i32)
+        .bind(crate::db::SANDBOX_MAX_CHANNELS as i32)
+        .bind(crate::db::SANDBOX_MAX_MEMBERS as i32)
+        .bind(&tenant.id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| BizClawError::Memory(format!("Mark sandbox: {e}")))?;
+        self.get_tenant(&tenant.id).await
+    }
+
+    /// Sandbox tenants whose `expires_at` has passed.
+    pub async fn list_expired_sandboxes(&self) -> Result<Vec<Tenant>> {
+        let rows = sqlx::query(&format!("{PG_TENANT_SELECT} WHERE sandbox AND expires_at <= NOW()"))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| BizClawError::Memory(format!("List expired sandboxes: {e}")))?;
+        Ok(rows.iter().map(pg_row_to_tenant).collect())
+    }
+
+    /// Count sandbox tenants that have not expired yet.
+    pub async fn count_live_sandboxes(&self) -> Result<u32> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tenants WHERE sandbox AND expires_at > NOW()")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| BizClawError::Memory(format!("Count sandboxes: {e}")))?;
+        Ok(count as u32)
+    }
+
     pub async fn update_tenant_status(&self, id: &str, status: &str, pid: Option<u32>) -> Result<()> {
         sqlx::query("UPDATE tenants SET status=$1, pid=$2, updated_at=NOW() WHERE id=$3")
             .bind(status).bind(pid.map(|p| p as i32)).bind(id)
@@ -773,6 +736,22 @@ impl PgDb {
         Ok(())
     }
 
+    /// List tool usage stats for a tenant (most used first).
+    pub async fn list_tool_memories(&self, tenant_id: &str) -> Result<Vec<ToolMemory>> {
+        let rows = sqlx::query(
+            "SELECT id::text, tenant_id::text, tool_name, usage_count, success_count, failure_count, avg_duration_ms, tips, last_used::text
+             FROM memory_tool WHERE tenant_id=$1::uuid ORDER BY usage_count DESC, tool_name"
+        ).bind(tenant_id).fetch_all(&self.pool).await
+        .map_err(|e| BizClawError::Memory(format!("List tool memories: {e}")))?;
+
+        Ok(rows.iter().map(|r| ToolMemory {
+            id: r.get(0), tenant_id: r.get(1), tool_name: r.get(2),
+            usage_count: r.get(3), success_count: r.get(4),
+            failure_count: r.get(5), avg_duration_ms: r.get(6),
+            tips: r.try_get(7).ok().flatten(), last_used: r.get(8),
+        }).collect())
+    }
+
     /// Store or update working memory (conversation summary).
     #[allow(clippy::too_many_arguments)]
     pub async fn upsert_working_memory(
@@ -937,6 +916,10 @@ impl PgDb {
     }
 }
 
+/// Tenant columns in the order [`pg_row_to_tenant`] reads them. `expires_at`
+/// is rendered like the SQLite backend's (Vietnam time, no zone).
+const PG_TENANT_SELECT: &str = "SELECT id,name,slug,status,port,plan,provider,model,max_messages_day,max_channels,max_members,pairing_code,pid,cpu_percent,memory_bytes,disk_bytes,owner_id,created_at,sandbox,to_char(expires_at AT TIME ZONE 'Asia/Ho_Chi_Minh', 'YYYY-MM-DD HH24:MI:SS') FROM tenants";
+
 /// Convert a PostgreSQL row to Tenant struct.
 fn pg_row_to_tenant(row: &sqlx::postgres::PgRow) -> Tenant {
     Tenant {
@@ -958,6 +941,8 @@ fn pg_row_to_tenant(row: &sqlx::postgres::PgRow) -> Tenant {
         disk_bytes: row.get::<i64, _>(15) as u64,
         owner_id: row.try_get(16).ok().flatten(),
         created_at: row.get::<String, _>(17),
+        sandbox: row.try_get::<Option<bool>, _>(18).ok().flatten().unwrap_or(false),
+        expires_at: row.try_get(19).ok().flatten(),
     }
 }
 
@@ -0,0 +1,140 @@
+//! Custom domain verification.
+//!
+//! A tenant registers `chat.acme.com` and gets a challenge token. The domain
+//! is routed to the tenant once a TXT record `_bizclaw-challenge.chat.acme.com`
+//! holds `bizclaw-verify=<token>`, looked up over DNS-over-HTTPS
+//! (`BIZCLAW_DOH_URL`, default Google's JSON resolver).
+//!
+//! Only the domain's owner can publish that record. A domain merely pointed
+//! at the platform proves nothing (the platform would answer any challenge
+//! for it), and the platform never fetches URLs on a tenant's domain.
+
+use crate::db::CustomDomain;
+use std::time::Duration;
+
+/// Label prepended to the domain for the DNS TXT challenge.
+pub const TXT_LABEL: &str = "_bizclaw-challenge";
+
+const DEFAULT_DOH_URL: &str = "https://dns.google/resolve";
+
+/// How a domain was verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    Dns,
+}
+
+/// Name of the TXT record to create for `domain`.
+pub fn txt_record_name(domain: &str) -> String {
+    format!("{TXT_LABEL}.{domain}")
+}
+
+/// Value the TXT record must hold.
+pub fn txt_record_value(token: &str) -> String {
+    format!("bizclaw-verify={token}")
+}
+
+/// Setup instructions for a domain, as shown by the admin API.
+pub fn instructions(domain: &CustomDomain) -> serde_json::Value {
+    serde_json::json!({
+        "dns": {
+            "type": "TXT",
+            "name": txt_record_name(&domain.domain),
+            "value": txt_record_value(&domain.token),
+        },
+    })
+}
+
+/// Whether a DNS-over-HTTPS JSON answer (`{"Answer":[{"data":"\"…\""}]}`)
+/// contains the TXT challenge for `token`.
+pub fn txt_answer_matches(answer: &serde_json::Value, token: &str) -> bool {
+    let expected = txt_record_value(token);
+    answer["Answer"].as_array().is_some_and(|records| {
+        records.iter().any(|r| {
+            // Quoted, and split into several strings when long.
+            let data = r["data"].as_str().unwrap_or("");
+            let value: String = if data.contains('"') {
+                data.split('"').skip(1).step_by(2).collect()
+            } else {
+                data.trim().to_string()
+            };
+            value == expected
+        })
+    })
+}
+
+/// Check the TXT challenge for `domain`; `None` if it is not in place.
+pub async fn verify(domain: &CustomDomain) -> Option<Method> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap_or_default();
+    check_dns(&client, domain).await.then_some(Method::Dns)
+}
+
+async fn check_dns(client: &reqwest::Client, domain: &CustomDomain) -> bool {
+    let url = std::env::var("BIZCLAW_DOH_URL").unwrap_or_else(|_| DEFAULT_DOH_URL.into());
+    let name = txt_record_name(&domain.domain);
+    let resp = client
+        .get(&url)
+        .query(&[("name", name.as_str()), ("type", "TXT")])
+        .header("accept", "application/dns-json")
+        .send()
+        .await;
+    match resp {
+        Ok(r) => match r.json::<serde_json::Value>().await {
+            Ok(answer) => txt_answer_matches(&answer, &domain.token),
+            Err(e) => {
+                tracing::warn!("domains: bad DNS answer for {name}: {e}");
+                false
+            }
+        },
+        Err(e) => {
+            tracing::warn!("domains: DNS lookup for {name} failed: {e}");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_txt_answer_matches_token() {
+        let token = "0f3c9a";
+        let answer = json!({
+            "Status": 0,
+            "Answer": [
+                {"name": "_bizclaw-challenge.chat.acme.com.", "type": 16, "data": "\"v=spf1 -all\""},
+                {"name": "_bizclaw-challenge.chat.acme.com.", "type": 16, "data": "\"bizclaw-verify=0f3c9a\""},
+            ]
+        });
+        assert!(txt_answer_matches(&answer, token));
+        assert!(!txt_answer_matches(&answer, "other"));
+        assert!(!txt_answer_matches(&json!({"Status": 3}), token));
+        // Split into several character-strings.
+        let split = json!({"Answer": [{"data": "\"bizclaw-\" \"verify=0f3c9a\""}]});
+        assert!(txt_answer_matches(&split, token));
+        // Google's resolver returns the value unquoted.
+        let plain = json!({"Answer": [{"data": "bizclaw-verify=0f3c9a"}]});
+        assert!(txt_answer_matches(&plain, token));
+    }
+
+    #[test]
+    fn test_instructions_only_offer_dns() {
+        let domain = CustomDomain {
+            domain: "chat.acme.com".into(),
+            tenant_id: "t1".into(),
+            token: "0f3c9a".into(),
+            verified_at: None,
+            created_at: String::new(),
+        };
+        let steps = instructions(&domain);
+        assert_eq!(steps["dns"]["name"], "_bizclaw-challenge.chat.acme.com");
+        assert_eq!(steps["dns"]["value"], "bizclaw-verify=0f3c9a");
+        assert!(steps.get("http").is_none());
+    }
+}
@@ -0,0 +1,127 @@
+//! Tenant feature flags — turn features on or off per tenant or plan.
+//!
+//! Every plan has defaults ([`plan_default`]); operators can override single
+//! flags per tenant (`tenant_feature_flags` table). Resolved flags are applied
+//! when a tenant's config.toml is rendered and checked by the admin API.
+
+use std::collections::BTreeMap;
+
+/// Agent tools. Off = the tenant's agent gets no tools at all.
+pub const TOOLS: &str = "tools";
+/// Web access tools ([`WEB_TOOLS`]).
+pub const WEB_TOOL: &str = "web_tool";
+/// Installing and enabling skills.
+pub const SKILLS: &str = "skills";
+/// Scheduled tasks running in the background.
+pub const HEARTBEAT: &str = "heartbeat";
+/// Config, channel and provider settings in the tenant dashboard.
+pub const SETTINGS: &str = "settings";
+
+/// All known feature flags.
+pub const FEATURES: &[&str] = &[TOOLS, WEB_TOOL, SKILLS, HEARTBEAT, SETTINGS];
+
+/// Tools removed when [`WEB_TOOL`] is off.
+pub const WEB_TOOLS: &[&str] = &["web_search", "http_request", "browser"];
+
+/// Whether `feature` is a known flag.
+pub fn is_known(feature: &str) -> bool {
+    FEATURES.contains(&feature)
+}
+
+/// Whether `feature` is on by default for tenants on `plan`.
+/// Sandbox tenants get tools only, free tenants everything but skills.
+pub fn plan_default(plan: &str, feature: &str) -> bool {
+    match plan {
+        "sandbox" => feature == TOOLS,
+        "free" => feature != SKILLS,
+        _ => true,
+    }
+}
+
+/// Flags of a tenant on `plan`, with per-tenant `overrides` applied.
+/// Unknown flags in `overrides` are ignored.
+pub fn resolve(plan: &str, overrides: &[(String, bool)]) -> BTreeMap<String, bool> {
+    let mut flags: BTreeMap<String, bool> = FEATURES
+        .iter()
+        .map(|f| (f.to_string(), plan_default(plan, f)))
+        .collect();
+    for (flag, enabled) in overrides {
+        if let Some(value) = flags.get_mut(flag) {
+            *value = *enabled;
+        }
+    }
+    flags
+}
+
+/// `[autonomy]` section restricting the tenant agent's tools to `flags`
+/// (empty when no tool feature is off).
+pub fn autonomy_toml(flags: &BTreeMap<String, bool>) -> String {
+    let enabled = |f: &str| flags.get(f).copied().unwrap_or(true);
+    if !enabled(TOOLS) {
+        return "\n[autonomy]\nallowed_tools = []\n".into();
+    }
+    if !enabled(WEB_TOOL) {
+        let denied: Vec<String> = WEB_TOOLS.iter().map(|t| format!("\"{t}\"")).collect();
+        return format!("\n[autonomy]\ndenied_tools = [{}]\n", denied.join(", "));
+    }
+    String::new()
+}
+
+/// `[features]` section turning off the gateway features in `flags` that
+/// are off (empty when none are).
+pub fn features_toml(flags: &BTreeMap<String, bool>) -> String {
+    let off: Vec<String> = [SKILLS, HEARTBEAT, SETTINGS]
+        .iter()
+        .filter(|f| !flags.get(**f).copied().unwrap_or(true))
+        .map(|f| format!("{f} = false\n"))
+        .collect();
+    if off.is_empty() {
+        return String::new();
+    }
+    format!("\n[features]\n{}", off.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_applies_overrides_to_plan_defaults() {
+        let free = resolve("free", &[]);
+        assert!(free[TOOLS] && free[WEB_TOOL] && !free[SKILLS] && free[HEARTBEAT]);
+        let sandbox = resolve("sandbox", &[]);
+        assert!(sandbox[TOOLS] && !sandbox[WEB_TOOL] && !sandbox[SKILLS] && !sandbox[HEARTBEAT]);
+        assert!(!sandbox[SETTINGS] && free[SETTINGS]);
+        assert!(resolve("pro", &[]).values().all(|v| *v));
+
+        let flags = resolve(
+            "pro",
+            &[(WEB_TOOL.into(), false), ("unknown".into(), false)],
+        );
+        assert!(!flags[WEB_TOOL]);
+        assert!(!flags.contains_key("unknown"));
+    }
+
+    #[test]
+    fn test_autonomy_toml() {
+        assert_eq!(autonomy_toml(&resolve("pro", &[])), "");
+        assert_eq!(
+            autonomy_toml(&resolve("sandbox", &[])),
+            "\n[autonomy]\ndenied_tools = [\"web_search\", \"http_request\", \"browser\"]\n"
+        );
+        assert_eq!(
+            autonomy_toml(&resolve("pro", &[(TOOLS.into(), false)])),
+            "\n[autonomy]\nallowed_tools = []\n"
+        );
+    }
+
+    #[test]
+    fn test_features_toml() {
+        assert_eq!(features_toml(&resolve("pro", &[])), "");
+        assert_eq!(features_toml(&resolve("free", &[])), "\n[features]\nskills = false\n");
+        assert_eq!(
+            features_toml(&resolve("sandbox", &[])),
+            "\n[features]\nskills = false\nheartbeat = false\nsettings = false\n"
+        );
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Multi-tenant management platform — run multiple BizClaw agents on a single VPS.
 //! Includes admin dashboard, tenant lifecycle management, pairing security,
-//! subdomain routing, resource monitoring, and audit logging.
+//! subdomain and custom domain routing, resource monitoring, and audit logging.
 //! Now with PostgreSQL support, ReMe Memory, Heartbeat/Cron, and Skills.
 
 pub mod admin;
@@ -10,8 +10,12 @@ pub mod auth;
 pub mod config;
 pub mod db;
 pub mod db_pg;
+pub mod domains;
 pub mod enterprise;
+pub mod features;
+pub mod metrics;
 pub mod mission_control;
+pub mod routing;
 pub mod server_provisioner;
 pub mod tenant;
 pub mod self_serve;
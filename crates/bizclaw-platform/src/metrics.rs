@@ -0,0 +1,232 @@
+//! Prometheus metrics for the platform, one series per tenant.
+//!
+//! Served in the text exposition format from `GET /metrics`. Every series
+//! carries a `tenant` label with the tenant slug. LLM counters come from the
+//! usage tenant gateways report; process gauges and restart/crash counters
+//! come from the tenant manager and the resource figures stored with each
+//! tenant.
+//!
+//! The endpoint exposes every tenant, so it needs a bearer token: the scrape
+//! token from `BIZCLAW_METRICS_TOKEN`, or a super-admin JWT.
+
+use crate::db::{LlmUsageSummary, Tenant};
+use crate::tenant::ProcessEvents;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Content type of the Prometheus text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Env var holding the bearer token Prometheus scrapes with.
+pub const TOKEN_ENV: &str = "BIZCLAW_METRICS_TOKEN";
+
+/// Render per-tenant metrics.
+///
+/// `usage` is keyed by tenant id (see [`crate::db::PlatformDb::llm_usage_totals`]);
+/// `running` holds the ids of tenants with a live process; `events` their
+/// restart and crash counts (see [`crate::tenant::TenantManager::process_events`]).
+pub fn render(
+    tenants: &[Tenant],
+    usage: &HashMap<String, LlmUsageSummary>,
+    running: &HashSet<String>,
+    events: &HashMap<String, ProcessEvents>,
+) -> String {
+    let mut tenants: Vec<&Tenant> = tenants.iter().collect();
+    tenants.sort_by(|a, b| a.slug.cmp(&b.slug));
+    let usage_of = |t: &Tenant| usage.get(&t.id);
+    let events_of = |t: &Tenant| events.get(&t.id).copied().unwrap_or_default();
+
+    let mut out = String::new();
+    family(
+        &mut out,
+        "bizclaw_tenant_up",
+        "gauge",
+        "Whether the tenant process is running (1) or not (0).",
+        &tenants,
+        |t| if running.contains(&t.id) { 1.0 } else { 0.0 },
+    );
+    family(
+        &mut out,
+        "bizclaw_tenant_cpu_percent",
+        "gauge",
+        "CPU usage of the tenant process, in percent.",
+        &tenants,
+        |t| t.cpu_percent,
+    );
+    family(
+        &mut out,
+        "bizclaw_tenant_memory_bytes",
+        "gauge",
+        "Resident memory of the tenant process.",
+        &tenants,
+        |t| t.memory_bytes as f64,
+    );
+    family(
+        &mut out,
+        "bizclaw_tenant_disk_bytes",
+        "gauge",
+        "Disk space used by the tenant's data directory.",
+        &tenants,
+        |t| t.disk_bytes as f64,
+    );
+    family(
+        &mut out,
+        "bizclaw_tenant_restarts_total",
+        "counter",
+        "Restarts of the tenant process since the platform started.",
+        &tenants,
+        |t| events_of(t).restarts as f64,
+    );
+    family(
+        &mut out,
+        "bizclaw_tenant_crashes_total",
+        "counter",
+        "Times the tenant process exited without being stopped.",
+        &tenants,
+        |t| events_of(t).crashes as f64,
+    );
+    family(
+        &mut out,
+        "bizclaw_tenant_llm_requests_total",
+        "counter",
+        "LLM calls reported by the tenant.",
+        &tenants,
+        |t| usage_of(t).map_or(0, |u| u.requests) as f64,
+    );
+    family(
+        &mut out,
+        "bizclaw_tenant_llm_errors_total",
+        "counter",
+        "LLM calls the tenant reported as failed.",
+        &tenants,
+        |t| usage_of(t).map_or(0, |u| u.errors) as f64,
+    );
+    family(
+        &mut out,
+        "bizclaw_tenant_llm_prompt_tokens_total",
+        "counter",
+        "Prompt tokens reported by the tenant.",
+        &tenants,
+        |t| usage_of(t).map_or(0, |u| u.prompt_tokens) as f64,
+    );
+    family(
+        &mut out,
+        "bizclaw_tenant_llm_completion_tokens_total",
+        "counter",
+        "Completion tokens reported by the tenant.",
+        &tenants,
+        |t| usage_of(t).map_or(0, |u| u.completion_tokens) as f64,
+    );
+    family(
+        &mut out,
+        "bizclaw_tenant_llm_cost_usd_total",
+        "counter",
+        "Estimated LLM cost reported by the tenant, in USD.",
+        &tenants,
+        |t| usage_of(t).map_or(0.0, |u| u.cost_usd),
+    );
+    out
+}
+
+/// Write one metric family: its `HELP` and `TYPE` lines, then a sample per tenant.
+fn family(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    tenants: &[&Tenant],
+    value: impl Fn(&Tenant) -> f64,
+) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    for tenant in tenants {
+        let v = value(tenant);
+        let _ = writeln!(
+            out,
+            "{name}{{tenant=\"{}\"}} {v}",
+            escape_label(&tenant.slug)
+        );
+    }
+}
+
+/// Escape a label value: backslash, double quote and newline.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{LlmUsageRecord, PlatformDb};
+
+    fn usage(prompt: i64, completion: i64, cost: f64) -> LlmUsageRecord {
+        LlmUsageRecord {
+            agent_name: "default".into(),
+            provider: "openai".into(),
+            model: "gpt-4o-mini".into(),
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            cost_usd: cost,
+            created_at: None,
+            status: None,
+        }
+    }
+
+    #[test]
+    fn test_metrics_labeled_per_tenant() {
+        let db = PlatformDb::open(std::path::Path::new(":memory:")).unwrap();
+        let shop = db
+            .create_tenant("Shop", "shop", 10001, "openai", "gpt-4o-mini", "free", None)
+            .unwrap();
+        let cafe = db
+            .create_tenant("Cafe", "cafe", 10002, "openai", "gpt-4o-mini", "free", None)
+            .unwrap();
+        db.record_llm_usage(&shop.id, &[usage(100, 50, 0.25), usage(200, 100, 0.5)])
+            .unwrap();
+        let failed = LlmUsageRecord {
+            status: Some("error".into()),
+            ..usage(10, 0, 0.0)
+        };
+        db.record_llm_usage(&cafe.id, &[usage(10, 5, 0.125), failed])
+            .unwrap();
+
+        let running = HashSet::from([shop.id.clone()]);
+        let events = HashMap::from([(
+            cafe.id.clone(),
+            ProcessEvents {
+                restarts: 2,
+                crashes: 1,
+            },
+        )]);
+        let text = render(
+            &db.list_tenants().unwrap(),
+            &db.llm_usage_totals().unwrap(),
+            &running,
+            &events,
+        );
+
+        assert!(text.contains("# TYPE bizclaw_tenant_llm_requests_total counter\n"));
+        assert!(text.contains("bizclaw_tenant_llm_requests_total{tenant=\"shop\"} 2\n"));
+        assert!(text.contains("bizclaw_tenant_llm_requests_total{tenant=\"cafe\"} 2\n"));
+        assert!(text.contains("bizclaw_tenant_llm_errors_total{tenant=\"shop\"} 0\n"));
+        assert!(text.contains("bizclaw_tenant_llm_errors_total{tenant=\"cafe\"} 1\n"));
+        assert!(text.contains("bizclaw_tenant_restarts_total{tenant=\"cafe\"} 2\n"));
+        assert!(text.contains("bizclaw_tenant_crashes_total{tenant=\"cafe\"} 1\n"));
+        assert!(text.contains("bizclaw_tenant_crashes_total{tenant=\"shop\"} 0\n"));
+        assert!(text.contains("bizclaw_tenant_llm_prompt_tokens_total{tenant=\"shop\"} 300\n"));
+        assert!(text.contains("bizclaw_tenant_llm_completion_tokens_total{tenant=\"cafe\"} 5\n"));
+        assert!(text.contains("bizclaw_tenant_llm_cost_usd_total{tenant=\"shop\"} 0.75\n"));
+        assert!(text.contains("bizclaw_tenant_up{tenant=\"shop\"} 1\n"));
+        assert!(text.contains("bizclaw_tenant_up{tenant=\"cafe\"} 0\n"));
+        assert!(text.contains("bizclaw_tenant_memory_bytes{tenant=\"cafe\"} 0\n"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape_label("x\ny"), "x\\ny");
+    }
+}
@@ -0,0 +1,382 @@
+//! Subdomain routing — proxies `slug.domain` requests to the tenant's gateway.
+//!
+//! Production deployments normally route tenants through the generated nginx
+//! map (see `sync_nginx_routing`). This layer does the same inside the admin
+//! server, so a single-binary setup (or one where nginx forwards every host
+//! to the platform) still reaches the right tenant:
+//!
+//! - `acme.bizclaw.vn` → running tenant `acme` → `http://127.0.0.1:<port>`
+//! - a verified custom domain (`chat.acme.com`) → its tenant, the same way;
+//!   custom domains are matched before subdomains
+//! - unknown slug → 404, stopped tenant → 503
+//! - the bare domain and reserved subdomains (`apps`, `www`, …) fall through
+//!   to the admin routes.
+//!
+//! Plain HTTP only; WebSocket upgrades still need the nginx route.
+
+use crate::admin::AdminState;
+use crate::db::RESERVED_SLUGS;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderName, Request, StatusCode, header};
+use axum::response::Response;
+use std::sync::{Arc, OnceLock};
+
+/// Largest request body forwarded to a tenant.
+const MAX_PROXY_BODY: usize = 10 * 1024 * 1024;
+
+/// Where a tenant subdomain should go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TenantRoute {
+    /// Tenant is running on this local port.
+    Upstream(u16),
+    /// Tenant exists but is not running.
+    Stopped,
+    /// No tenant with this slug.
+    Unknown,
+}
+
+/// `host` without port or trailing dot, lowercased.
+pub fn host_name(host: &str) -> String {
+    let host = host.trim().to_ascii_lowercase();
+    let name = host.rsplit_once(':').map_or(host.as_str(), |(h, port)| {
+        if port.bytes().all(|b| b.is_ascii_digit()) {
+            h
+        } else {
+            host.as_str()
+        }
+    });
+    name.trim_end_matches('.').to_string()
+}
+
+/// Tenant slug for `host` under `domain`, if the host is a single-label
+/// subdomain that is not reserved. The port and letter case are ignored.
+pub fn tenant_slug(host: &str, domain: &str) -> Option<String> {
+    let host = host_name(host);
+    let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    let slug = host.strip_suffix(domain.as_str())?.strip_suffix('.')?;
+    if slug.is_empty() || slug.contains('.') || RESERVED_SLUGS.contains(&slug) {
+        return None;
+    }
+    Some(slug.to_string())
+}
+
+/// Look up the route for a tenant slug.
+pub async fn resolve(state: &AdminState, slug: &str) -> TenantRoute {
+    let tenant = match state.db.lock().await.get_tenant_by_slug(slug) {
+        Ok(Some(t)) => t,
+        Ok(None) => return TenantRoute::Unknown,
+        Err(e) => {
+            tracing::warn!("routing: tenant lookup for '{slug}' failed: {e}");
+            return TenantRoute::Unknown;
+        }
+    };
+    route_for(&tenant)
+}
+
+/// Look up the tenant behind a verified custom domain: its slug and route.
+/// `None` for unknown and unverified domains.
+pub async fn resolve_domain(state: &AdminState, host: &str) -> Option<(String, TenantRoute)> {
+    match state.db.lock().await.get_tenant_by_domain(host) {
+        Ok(tenant) => tenant.map(|t| (t.slug.clone(), route_for(&t))),
+        Err(e) => {
+            tracing::warn!("routing: domain lookup for '{host}' failed: {e}");
+            None
+        }
+    }
+}
+
+fn route_for(tenant: &crate::db::Tenant) -> TenantRoute {
+    if tenant.status == "running" {
+        TenantRoute::Upstream(tenant.port)
+    } else {
+        TenantRoute::Stopped
+    }
+}
+
+/// Whether `host` is the platform domain or one of its subdomains, which are
+/// never custom domains.
+pub fn is_platform_host(host: &str, domain: &str) -> bool {
+    let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    host == domain || host.strip_suffix(domain.as_str()).is_some_and(|h| h.ends_with('.'))
+}
+
+/// Middleware: proxy custom domains and tenant subdomains, pass everything
+/// else through.
+pub async fn subdomain_proxy(
+    State(state): State<Arc<AdminState>>,
+    req: Request<Body>,
+    next: axum::middleware::Next,
+) -> Response {
+    let host = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| req.uri().host())
+        .unwrap_or("")
+        .to_string();
+    let name = host_name(&host);
+    let custom = if is_platform_host(&name, &state.domain) {
+        None
+    } else {
+        resolve_domain(&state, &name).await
+    };
+    let (slug, route) = match custom {
+        Some(found) => found,
+        None => match tenant_slug(&host, &state.domain) {
+            Some(slug) => {
+                let route = resolve(&state, &slug).await;
+                (slug, route)
+            }
+            None => return next.run(req).await,
+        },
+    };
+    match route {
+        TenantRoute::Upstream(port) => forward(req, &host, port).await,
+        TenantRoute::Stopped => error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &format!("Tenant '{slug}' is not running"),
+        ),
+        TenantRoute::Unknown => {
+            error_response(StatusCode::NOT_FOUND, &format!("Unknown tenant '{slug}'"))
+        }
+    }
+}
+
+/// Shared client; redirects are passed back to the browser, not followed.
+fn proxy_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// Headers that describe one connection and must not be forwarded.
+fn is_hop_by_hop(name: &HeaderName) -> bool {
+    matches!(
+        name.as_str(),
+        "connection"
+            | "keep-alive"
+            | "proxy-authenticate"
+            | "proxy-authorization"
+            | "te"
+            | "trailer"
+            | "transfer-encoding"
+            | "upgrade"
+            | "host"
+            | "content-length"
+    )
+}
+
+async fn forward(req: Request<Body>, host: &str, port: u16) -> Response {
+    let (parts, body) = req.into_parts();
+    let path = parts.uri.path_and_query().map_or("/", |p| p.as_str());
+    let url = format!("http://127.0.0.1:{port}{path}");
+    let body = match axum::body::to_bytes(body, MAX_PROXY_BODY).await {
+        Ok(b) => b,
+        Err(_) => return error_response(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large"),
+    };
+
+    let mut upstream = proxy_client()
+        .request(parts.method, &url)
+        .header("x-forwarded-host", host)
+        .body(body);
+    for (name, value) in &parts.headers {
+        if !is_hop_by_hop(name) {
+            upstream = upstream.header(name, value);
+        }
+    }
+
+    let resp = match upstream.send().await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::warn!("routing: {host} → 127.0.0.1:{port} failed: {e}");
+            return error_response(StatusCode::BAD_GATEWAY, "Tenant is unreachable");
+        }
+    };
+    let mut out = Response::builder().status(resp.status());
+    for (name, value) in resp.headers() {
+        if !is_hop_by_hop(name) {
+            out = out.header(name, value);
+        }
+    }
+    out.body(Body::from_stream(resp.bytes_stream()))
+        .unwrap_or_else(|_| error_response(StatusCode::BAD_GATEWAY, "Invalid tenant response"))
+}
+
+fn error_response(status: StatusCode, error: &str) -> Response {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            serde_json::json!({"ok": false, "error": error}).to_string(),
+        ))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::PlatformDb;
+    use crate::tenant::TenantManager;
+    use axum::Router;
+    use axum::routing::get;
+    use tokio::sync::Mutex;
+    use tower::ServiceExt;
+
+    fn state(db: PlatformDb) -> Arc<AdminState> {
+        Arc::new(AdminState {
+            db: Mutex::new(db),
+            manager: Mutex::new(TenantManager::new(std::env::temp_dir())),
+            jwt_secret: "test".into(),
+            bizclaw_bin: "bizclaw".into(),
+            base_port: 10001,
+            domain: "bizclaw.vn".into(),
+            login_attempts: Default::default(),
+            register_attempts: Default::default(),
+            pg_db: None,
+        })
+    }
+
+    fn router(state: Arc<AdminState>) -> Router {
+        Router::new()
+            .route("/", get(|| async { "admin" }))
+            .layer(axum::middleware::from_fn_with_state(state, subdomain_proxy))
+    }
+
+    async fn call(app: Router, host: &str, path: &str) -> (StatusCode, String) {
+        let req = Request::builder()
+            .uri(path)
+            .header(header::HOST, host)
+            .body(Body::empty())
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        let status = resp.status();
+        let body = axum::body::to_bytes(resp.into_body(), 1 << 20)
+            .await
+            .unwrap();
+        (status, String::from_utf8_lossy(&body).into_owned())
+    }
+
+    #[test]
+    fn test_tenant_slug() {
+        let domain = "bizclaw.vn";
+        assert_eq!(
+            tenant_slug("acme.bizclaw.vn", domain).as_deref(),
+            Some("acme")
+        );
+        assert_eq!(
+            tenant_slug("ACME.BizClaw.vn:443", domain).as_deref(),
+            Some("acme")
+        );
+        assert_eq!(tenant_slug("bizclaw.vn", domain), None);
+        assert_eq!(tenant_slug("apps.bizclaw.vn", domain), None);
+        assert_eq!(tenant_slug("a.b.bizclaw.vn", domain), None);
+        assert_eq!(tenant_slug("acme.otherbizclaw.vn", domain), None);
+        assert_eq!(tenant_slug("acme.example.com", domain), None);
+
+        assert_eq!(host_name("Chat.Acme.com.:8443"), "chat.acme.com");
+        assert!(is_platform_host("bizclaw.vn", domain));
+        assert!(is_platform_host("acme.bizclaw.vn", domain));
+        assert!(!is_platform_host("chat.notbizclaw.vn", domain));
+    }
+
+    #[tokio::test]
+    async fn test_routes_subdomain_to_tenant_port() {
+        // Stand-in tenant gateway.
+        let upstream = Router::new().route(
+            "/api/v1/info",
+            get(|headers: axum::http::HeaderMap| async move {
+                format!("tenant:{}", headers["x-forwarded-host"].to_str().unwrap())
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        let db = PlatformDb::open(std::path::Path::new(":memory:")).unwrap();
+        let acme = db
+            .create_tenant("Acme", "acme", port, "openai", "gpt-4o-mini", "free", None)
+            .unwrap();
+        db.update_tenant_status(&acme.id, "running", Some(1))
+            .unwrap();
+        db.create_tenant(
+            "Idle",
+            "idle",
+            port + 1,
+            "openai",
+            "gpt-4o-mini",
+            "free",
+            None,
+        )
+        .unwrap();
+        let state = state(db);
+
+        assert_eq!(resolve(&state, "acme").await, TenantRoute::Upstream(port));
+        let (status, body) = call(router(state.clone()), "acme.bizclaw.vn", "/api/v1/info").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "tenant:acme.bizclaw.vn");
+
+        let (status, _) = call(router(state.clone()), "idle.bizclaw.vn", "/").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        // The admin host itself is not proxied.
+        let (status, body) = call(router(state), "bizclaw.vn", "/").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "admin");
+    }
+
+    #[tokio::test]
+    async fn test_routes_verified_custom_domain_only() {
+        let upstream = Router::new().route("/", get(|| async { "tenant" }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        let db = PlatformDb::open(std::path::Path::new(":memory:")).unwrap();
+        let acme = db
+            .create_tenant("Acme", "acme", port, "openai", "gpt-4o-mini", "free", None)
+            .unwrap();
+        db.update_tenant_status(&acme.id, "running", Some(1))
+            .unwrap();
+        db.add_custom_domain(&acme.id, "chat.acme.com").unwrap();
+        let state = state(db);
+
+        // Unverified: not routed, the admin routes answer.
+        assert_eq!(resolve_domain(&state, "chat.acme.com").await, None);
+        let (status, body) = call(router(state.clone()), "chat.acme.com", "/").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "admin");
+
+        state
+            .db
+            .lock()
+            .await
+            .mark_custom_domain_verified("chat.acme.com")
+            .unwrap();
+        assert_eq!(
+            resolve_domain(&state, "chat.acme.com").await,
+            Some(("acme".into(), TenantRoute::Upstream(port)))
+        );
+        let (status, body) = call(router(state.clone()), "Chat.Acme.com:443", "/").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "tenant");
+
+        // Subdomain routing still works alongside.
+        let (_, body) = call(router(state), "acme.bizclaw.vn", "/").await;
+        assert_eq!(body, "tenant");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_subdomain_is_404() {
+        let db = PlatformDb::open(std::path::Path::new(":memory:")).unwrap();
+        let state = state(db);
+        assert_eq!(resolve(&state, "ghost").await, TenantRoute::Unknown);
+        let (status, body) = call(router(state), "ghost.bizclaw.vn", "/").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body.contains("ghost"));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::admin::AdminState;
-use axum::{Extension, Json, extract::State};
+use axum::{Extension, Json, extract::State, http::HeaderMap};
 use serde::Deserialize;
 use std::sync::Arc;
 
@@ -10,6 +10,13 @@ pub struct RegisterReq {
     pub company_name: String,
 }
 
+#[derive(Deserialize)]
+pub struct SandboxReq {
+    pub email: String,
+    #[serde(default)]
+    pub company_name: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct ChangePasswordReq {
     pub current_password: String,
@@ -30,6 +37,57 @@ pub struct ResetPasswordReq {
 /// Minimum password length — unified across all endpoints
 const MIN_PASSWORD_LENGTH: usize = 8;
 
+/// Sandbox lifetime when `sandbox.ttl_minutes` is not configured (24 hours).
+const DEFAULT_SANDBOX_TTL_MINUTES: i64 = 24 * 60;
+
+/// Sandboxes a single email may create per day.
+const MAX_SANDBOXES_PER_EMAIL: u32 = 2;
+
+/// Sandboxes a single client IP may create per day.
+const MAX_SANDBOXES_PER_IP: u32 = 3;
+
+/// Live sandboxes allowed when `sandbox.max_live` is not configured.
+const DEFAULT_MAX_LIVE_SANDBOXES: u32 = 20;
+
+/// Base URL path of the LLM proxy sandbox tenants are configured with.
+pub const SANDBOX_LLM_BASE: &str = "/api/sandbox/v1";
+
+/// LLM calls a sandbox may make per allowed message; an agent turn with
+/// tool rounds takes several.
+const SANDBOX_LLM_CALLS_PER_MESSAGE: u32 = 5;
+
+/// Record one attempt for `key` and report whether it exceeds `max` within a day.
+fn daily_limit_exceeded(
+    attempts: &mut std::collections::HashMap<String, (u32, std::time::Instant)>,
+    key: String,
+    max: u32,
+) -> bool {
+    let now = std::time::Instant::now();
+    if let Some((_, first_at)) = attempts.get(&key)
+        && now.duration_since(*first_at).as_secs() >= 86_400
+    {
+        attempts.remove(&key);
+    }
+    let entry = attempts.entry(key).or_insert((0, now));
+    if entry.0 >= max {
+        return true;
+    }
+    entry.0 += 1;
+    false
+}
+
+/// Client IP as forwarded by Nginx (the platform only listens on localhost).
+fn client_ip(headers: &HeaderMap) -> String {
+    headers
+        .get("x-real-ip")
+        .or_else(|| headers.get("x-forwarded-for"))
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "unknown".into())
+}
+
 /// Validate email format (stricter than just `contains('@')`)
 fn is_valid_email(email: &str) -> bool {
     // Must contain exactly one @, at least one char before @, domain with dot
@@ -74,11 +132,11 @@ pub fn generate_safe_slug(company_name: &str) -> String {
     while slug.contains("--") {
         slug = slug.replace("--", "-");
     }
+    // Leave room for a "-N" uniqueness suffix within the 63-char DNS label limit
+    slug.truncate(crate::db::MAX_SLUG_LEN - 7);
     slug = slug.trim_matches('-').to_string();
-        
-    let blacklist = ["dev", "admin", "app", "apps", "www", "test", "staging", "api", "smtp", "mail", "ftp", "ns", "cdn", "local", "root", "sys", "system"];
-    
-    if blacklist.contains(&slug.as_str()) || slug.is_empty() {
+
+    if crate::db::RESERVED_SLUGS.contains(&slug.as_str()) || slug.is_empty() {
         slug = format!("tenant-{}", uuid::Uuid::new_v4().to_string().chars().take(8).collect::<String>());
     }
     
@@ -179,6 +237,202 @@ pub async fn register_handler(
     }
 }
 
+/// Self-serve "try it" endpoint: create and start a time-limited demo tenant
+/// without payment. The tenant never sees the shared `sandbox.api_key`: it
+/// gets a key of its own for [`sandbox_llm_proxy`], and is removed by the
+/// sandbox reaper once it expires.
+pub async fn sandbox_handler(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Json(req): Json<SandboxReq>,
+) -> Json<serde_json::Value> {
+    if !is_valid_email(&req.email) {
+        return Json(serde_json::json!({"ok": false, "error": "Email không hợp lệ"}));
+    }
+
+    // Rate limiting — per email and per client IP, per day
+    {
+        let mut attempts = state.register_attempts.lock().unwrap();
+        let ip = client_ip(&headers);
+        if daily_limit_exceeded(&mut attempts, format!("sandbox-ip:{ip}"), MAX_SANDBOXES_PER_IP)
+            || daily_limit_exceeded(&mut attempts, format!("sandbox:{}", req.email), MAX_SANDBOXES_PER_EMAIL)
+        {
+            tracing::warn!("[sandbox] Rate limit hit for ip={ip}");
+            return Json(serde_json::json!({
+                "ok": false,
+                "error": "Bạn đã tạo quá nhiều bản dùng thử. Vui lòng thử lại sau."
+            }));
+        }
+    }
+
+    if state.manager.lock().await.platform_url().is_none() {
+        tracing::warn!("[sandbox] No platform URL for the sandbox LLM proxy");
+        return Json(serde_json::json!({"ok": false, "error": "Bản dùng thử hiện không khả dụng."}));
+    }
+
+    let name = req.company_name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| "Demo".into());
+    let base_slug = generate_safe_slug(&format!("demo {name}"));
+
+    let db = state.db.lock().await;
+    if db.get_platform_config("sandbox.api_key").unwrap_or_default().is_empty() {
+        tracing::warn!("[sandbox] sandbox.api_key is not configured");
+        return Json(serde_json::json!({"ok": false, "error": "Bản dùng thử hiện không khả dụng."}));
+    }
+    let provider = db.get_platform_config("sandbox.provider").unwrap_or_else(|| "openai".into());
+    let model = db.get_platform_config("sandbox.model").unwrap_or_else(|| "gpt-4o-mini".into());
+    let ttl_minutes = db
+        .get_platform_config("sandbox.ttl_minutes")
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|m| *m > 0)
+        .unwrap_or(DEFAULT_SANDBOX_TTL_MINUTES);
+    let max_live = db
+        .get_platform_config("sandbox.max_live")
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_MAX_LIVE_SANDBOXES);
+    // Fail closed: a count error must not let sandboxes spawn unbounded.
+    if db.count_live_sandboxes().map_or(true, |live| live >= max_live) {
+        tracing::warn!("[sandbox] Live sandbox cap ({max_live}) reached");
+        return Json(serde_json::json!({"ok": false, "error": "Hệ thống đang quá tải bản dùng thử. Vui lòng thử lại sau."}));
+    }
+
+    let mut slug = base_slug.clone();
+    let mut counter = 1;
+    while db.is_slug_taken(&slug) {
+        slug = format!("{}-{}", base_slug, counter);
+        counter += 1;
+    }
+    let current_max = db.get_max_port().unwrap_or(Some(state.base_port)).unwrap_or(state.base_port);
+    let port = std::cmp::max(current_max, state.base_port) + 1;
+
+    let tenant = match db.create_sandbox_tenant(&name, &slug, port, &provider, &model, ttl_minutes) {
+        Ok(t) => t,
+        Err(e) => return Json(serde_json::json!({"ok": false, "error": sanitize_error(&format!("Failed to create sandbox: {e}"))})),
+    };
+    let llm_key = format!("sbx-{}", uuid::Uuid::new_v4().simple());
+    if let Err(e) = db.set_config(&tenant.id, crate::db::SANDBOX_KEY_CONFIG, &llm_key) {
+        let _ = db.delete_tenant(&tenant.id);
+        return Json(serde_json::json!({"ok": false, "error": sanitize_error(&format!("Failed to configure sandbox: {e}"))}));
+    }
+    db.log_event("sandbox_created", "visitor", &tenant.id, Some(&format!("email={},expires_at={}", req.email, tenant.expires_at.as_deref().unwrap_or_default()))).ok();
+
+    // Same lock order as start_tenant: manager, then db.
+    drop(db);
+    let started = {
+        let mut mgr = state.manager.lock().await;
+        let db = state.db.lock().await;
+        mgr.start_tenant(&tenant, &state.bizclaw_bin, &db)
+    };
+    match started {
+        Ok(pid) => {
+            state.db.lock().await.update_tenant_status(&tenant.id, "running", Some(pid)).ok();
+            crate::admin::sync_nginx_routing(&state).await;
+        }
+        Err(e) => {
+            // The tenant is kept (and still expires); an admin can retry the start.
+            tracing::error!("[sandbox] Failed to start {}: {e}", tenant.slug);
+            state.db.lock().await.update_tenant_status(&tenant.id, "error", None).ok();
+        }
+    }
+
+    Json(serde_json::json!({
+        "ok": true,
+        "slug": tenant.slug,
+        "pairing_code": tenant.pairing_code,
+        "expires_at": tenant.expires_at,
+        "max_messages_day": tenant.max_messages_day,
+    }))
+}
+
+/// OpenAI-compatible chat completions for sandbox tenants, authenticated by
+/// the sandbox's own key. Forwards upstream with the shared
+/// `sandbox.api_key`, pinned to `sandbox.model` and capped per day, and
+/// stops working when the sandbox expires.
+pub async fn sandbox_llm_proxy(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Json(mut body): Json<serde_json::Value>,
+) -> axum::response::Response {
+    use axum::http::StatusCode;
+
+    let key = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+    let (tenant, api_key, provider, model, base_url) = {
+        let db = state.db.lock().await;
+        let tenant = match db.live_sandbox_by_key(key) {
+            Ok(Some(t)) if !key.is_empty() => t,
+            _ => return proxy_error(StatusCode::UNAUTHORIZED, "Invalid or expired sandbox key"),
+        };
+        (
+            tenant,
+            db.get_platform_config("sandbox.api_key").unwrap_or_default(),
+            db.get_platform_config("sandbox.provider").unwrap_or_else(|| "openai".into()),
+            db.get_platform_config("sandbox.model").unwrap_or_else(|| "gpt-4o-mini".into()),
+            db.get_platform_config("sandbox.api_base_url").unwrap_or_default(),
+        )
+    };
+
+    let max_calls = tenant.max_messages_day.saturating_mul(SANDBOX_LLM_CALLS_PER_MESSAGE);
+    if daily_limit_exceeded(
+        &mut state.register_attempts.lock().unwrap(),
+        format!("sandbox-llm:{}", tenant.id),
+        max_calls,
+    ) {
+        return proxy_error(StatusCode::TOO_MANY_REQUESTS, "Sandbox daily limit reached");
+    }
+
+    use bizclaw_providers::provider_registry::{AuthStyle, get_provider_config};
+    let (url, auth_style) = if !base_url.is_empty() {
+        (format!("{}/chat/completions", base_url.trim_end_matches('/')), AuthStyle::Bearer)
+    } else if let Some(registry) = get_provider_config(&provider) {
+        (format!("{}{}", registry.base_url, registry.chat_path), registry.auth_style)
+    } else {
+        tracing::warn!("[sandbox] Unknown sandbox.provider '{provider}'");
+        return proxy_error(StatusCode::SERVICE_UNAVAILABLE, "Sandbox LLM is not configured");
+    };
+    if api_key.is_empty() {
+        return proxy_error(StatusCode::SERVICE_UNAVAILABLE, "Sandbox LLM is not configured");
+    }
+
+    body["model"] = serde_json::json!(model);
+    static CLIENT: std::sync::OnceLock<reqwest::Client> = std::sync::OnceLock::new();
+    let client = CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(120))
+            .build()
+            .unwrap_or_default()
+    });
+    let request = client.post(&url).json(&body);
+    let request = match auth_style {
+        AuthStyle::Bearer => request.bearer_auth(&api_key),
+        AuthStyle::ApiKeyHeader => request.header("api-key", &api_key),
+        AuthStyle::None => request,
+    };
+    match request.send().await {
+        Ok(resp) => {
+            let mut response = axum::response::Response::builder().status(resp.status().as_u16());
+            if let Some(content_type) = resp.headers().get(reqwest::header::CONTENT_TYPE) {
+                response = response.header(axum::http::header::CONTENT_TYPE, content_type.as_bytes());
+            }
+            response
+                .body(axum::body::Body::from_stream(resp.bytes_stream()))
+                .unwrap_or_else(|_| proxy_error(StatusCode::BAD_GATEWAY, "Invalid upstream response"))
+        }
+        Err(e) => {
+            tracing::warn!("[sandbox] LLM proxy for {} failed: {e}", tenant.slug);
+            proxy_error(StatusCode::BAD_GATEWAY, "Sandbox LLM is unreachable")
+        }
+    }
+}
+
+/// OpenAI-style error body for [`sandbox_llm_proxy`].
+fn proxy_error(status: axum::http::StatusCode, message: &str) -> axum::response::Response {
+    use axum::response::IntoResponse;
+    (status, Json(serde_json::json!({"error": {"message": message}}))).into_response()
+}
+
 pub async fn change_password_handler(
     State(state): State<Arc<AdminState>>,
     Extension(claims): Extension<crate::auth::Claims>,
@@ -356,11 +610,155 @@ mod tests {
         assert!(!is_valid_email("a@b.c")); // TLD too short
     }
 
+    #[test]
+    fn test_daily_limit_exceeded() {
+        let mut attempts = std::collections::HashMap::new();
+        assert!(!daily_limit_exceeded(&mut attempts, "sandbox-ip:1.2.3.4".into(), 2));
+        assert!(!daily_limit_exceeded(&mut attempts, "sandbox-ip:1.2.3.4".into(), 2));
+        assert!(daily_limit_exceeded(&mut attempts, "sandbox-ip:1.2.3.4".into(), 2));
+        assert!(!daily_limit_exceeded(&mut attempts, "sandbox-ip:5.6.7.8".into(), 2));
+    }
+
+    #[test]
+    fn test_client_ip_from_proxy_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_ip(&headers), "unknown");
+        headers.insert("x-forwarded-for", "203.0.113.7, 10.0.0.1".parse().unwrap());
+        assert_eq!(client_ip(&headers), "203.0.113.7");
+        headers.insert("x-real-ip", "198.51.100.2".parse().unwrap());
+        assert_eq!(client_ip(&headers), "198.51.100.2");
+    }
+
+    fn sandbox_state(platform_url: Option<&str>) -> Arc<AdminState> {
+        let db = crate::db::PlatformDb::open(std::path::Path::new(":memory:")).unwrap();
+        db.set_platform_config("sandbox.api_key", "sk-platform").unwrap();
+        db.set_platform_config("sandbox.model", "gpt-4o-mini").unwrap();
+        let mut manager = crate::tenant::TenantManager::new(
+            std::env::temp_dir().join(format!("bizclaw-sandbox-{}", std::process::id())),
+        );
+        if let Some(url) = platform_url {
+            manager = manager.with_platform_url(url);
+        }
+        Arc::new(AdminState {
+            db: tokio::sync::Mutex::new(db),
+            manager: tokio::sync::Mutex::new(manager),
+            jwt_secret: "test".into(),
+            bizclaw_bin: "/nonexistent/bizclaw".into(),
+            base_port: 10001,
+            domain: "bizclaw.vn".into(),
+            login_attempts: Default::default(),
+            register_attempts: Default::default(),
+            pg_db: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_gets_scoped_key_not_platform_key() {
+        let state = sandbox_state(Some("http://127.0.0.1:3000"));
+        let mut headers = HeaderMap::new();
+        headers.insert("x-real-ip", "203.0.113.9".parse().unwrap());
+        let create = |email: &str| {
+            sandbox_handler(
+                State(state.clone()),
+                headers.clone(),
+                Json(SandboxReq { email: email.into(), company_name: None }),
+            )
+        };
+
+        // Invalid emails do not use up the IP's sandboxes.
+        for _ in 0..MAX_SANDBOXES_PER_IP {
+            assert_eq!(create("not-an-email").await.0["ok"], false);
+        }
+        let json = create("visitor@example.com").await.0;
+        assert_eq!(json["ok"], true, "{json}");
+
+        let db = state.db.lock().await;
+        let tenant = db.get_tenant_by_slug(json["slug"].as_str().unwrap()).unwrap().unwrap();
+        let configs = db.list_configs(&tenant.id).unwrap();
+        assert!(configs.iter().all(|c| c.value != "sk-platform"));
+        let key = db.get_config(&tenant.id, crate::db::SANDBOX_KEY_CONFIG).unwrap().unwrap();
+        assert!(key.starts_with("sbx-"));
+        assert_eq!(db.live_sandbox_by_key(&key).unwrap().unwrap().id, tenant.id);
+        drop(db);
+
+        // Without a platform URL there is no proxy to send sandboxes to.
+        let state = sandbox_state(None);
+        let json = sandbox_handler(
+            State(state),
+            headers.clone(),
+            Json(SandboxReq { email: "visitor@example.com".into(), company_name: None }),
+        )
+        .await
+        .0;
+        assert_eq!(json["ok"], false);
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_llm_proxy() {
+        use axum::http::StatusCode;
+
+        // Upstream that echoes the key and model it was called with.
+        let upstream = axum::Router::new().route(
+            "/v1/chat/completions",
+            axum::routing::post(|headers: HeaderMap, Json(body): Json<serde_json::Value>| async move {
+                Json(serde_json::json!({
+                    "auth": headers["authorization"].to_str().unwrap(),
+                    "model": body["model"],
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+
+        let state = sandbox_state(Some("http://127.0.0.1:3000"));
+        let tenant = {
+            let db = state.db.lock().await;
+            db.set_platform_config("sandbox.api_base_url", &format!("http://{addr}/v1")).unwrap();
+            let tenant = db
+                .create_sandbox_tenant("Demo", "demo-proxy", 10002, "openai", "gpt-4o-mini", 60)
+                .unwrap();
+            db.set_config(&tenant.id, crate::db::SANDBOX_KEY_CONFIG, "sbx-proxy").unwrap();
+            tenant
+        };
+        let call = |key: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("authorization", format!("Bearer {key}").parse().unwrap());
+            sandbox_llm_proxy(
+                State(state.clone()),
+                headers,
+                Json(serde_json::json!({"model": "gpt-4o", "messages": []})),
+            )
+        };
+        let body = |resp: axum::response::Response| async move {
+            let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+        };
+
+        let resp = call("sbx-proxy").await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let json = body(resp).await;
+        assert_eq!(json["auth"], "Bearer sk-platform");
+        assert_eq!(json["model"], "gpt-4o-mini");
+
+        assert_eq!(call("sbx-wrong").await.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(call("").await.status(), StatusCode::UNAUTHORIZED);
+
+        // The daily cap applies per sandbox.
+        let max_calls = tenant.max_messages_day * SANDBOX_LLM_CALLS_PER_MESSAGE;
+        for _ in 1..max_calls {
+            assert_eq!(call("sbx-proxy").await.status(), StatusCode::OK);
+        }
+        assert_eq!(call("sbx-proxy").await.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
     #[test]
     fn test_slug_generation() {
         assert_eq!(generate_safe_slug("My Company"), "my-company");
         assert_eq!(generate_safe_slug("Hello World 123"), "hello-world-123");
         assert!(!generate_safe_slug("admin").starts_with("admin"));
         assert!(!generate_safe_slug("").is_empty());
+        let long = generate_safe_slug(&"Công ty TNHH ".repeat(10));
+        assert!(crate::db::validate_tenant_slug(&format!("{long}-99")).is_ok());
     }
 }
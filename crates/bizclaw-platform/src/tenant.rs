@@ -2,7 +2,8 @@
 
 use crate::db::{PlatformDb, Tenant};
 use bizclaw_core::error::{BizClawError, Result};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::Instant;
 
@@ -11,22 +12,72 @@ pub struct TenantProcess {
     pub pid: u32,
     pub port: u16,
     pub started_at: Instant,
+    /// Handle to the spawned process, polled to notice crashes.
+    child: Option<std::process::Child>,
+}
+
+/// Restarts and crashes of a tenant's process since the platform started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessEvents {
+    pub restarts: u64,
+    pub crashes: u64,
+}
+
+/// How a tenant would be launched, as resolved by
+/// [`TenantManager::plan_tenant`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SpawnPlan {
+    /// Resolved path of the bizclaw binary.
+    pub binary: PathBuf,
+    pub args: Vec<String>,
+    /// Environment set for the child, on top of the platform's own.
+    pub env: BTreeMap<String, String>,
+    /// The child inherits the platform's working directory.
+    pub working_dir: PathBuf,
+    /// Tenant data directory (`BIZCLAW_DATA_DIR`).
+    pub data_dir: PathBuf,
+    pub config_path: PathBuf,
+    /// Contents written to `config_path`.
+    pub config: String,
+    pub port: u16,
+    /// A config_sync.json is waiting; a real start imports it first, which
+    /// can change `config`.
+    pub pending_config_sync: bool,
+    /// Files the config refers to, written before the spawn.
+    #[serde(skip)]
+    files: Vec<(PathBuf, String)>,
 }
 
 /// Manages tenant lifecycle across the platform.
 pub struct TenantManager {
     processes: HashMap<String, TenantProcess>,
+    events: HashMap<String, ProcessEvents>,
     data_dir: std::path::PathBuf,
+    /// Admin server URL tenants report LLM usage to (None = no reporting).
+    platform_url: Option<String>,
 }
 
 impl TenantManager {
     pub fn new(data_dir: impl Into<std::path::PathBuf>) -> Self {
         Self {
             processes: HashMap::new(),
+            events: HashMap::new(),
             data_dir: data_dir.into(),
+            platform_url: None,
         }
     }
 
+    /// Have started tenants report their LLM usage to this admin server.
+    pub fn with_platform_url(mut self, url: impl Into<String>) -> Self {
+        self.platform_url = Some(url.into());
+        self
+    }
+
+    /// Admin server URL tenants reach the platform at, if configured.
+    pub fn platform_url(&self) -> Option<&str> {
+        self.platform_url.as_deref()
+    }
+
     /// Start a tenant as a child process.
     /// Config is ALWAYS regenerated from DB state — DB is the source of truth.
     pub fn start_tenant(
@@ -35,272 +86,20 @@ impl TenantManager {
         bizclaw_bin: &str,
         db: &crate::db::PlatformDb,
     ) -> Result<u32> {
-        if self.processes.contains_key(&tenant.id) {
-            return Err(BizClawError::provider(format!(
-                "Tenant {} already running",
-                tenant.slug
-            )));
-        }
+        self.check_startable(tenant)?;
 
         let tenant_dir = self.data_dir.join(&tenant.slug);
         std::fs::create_dir_all(&tenant_dir).ok();
-
-        // ── Import config_sync.json if gateway dashboard saved changes ──
-        let sync_path = tenant_dir.join("config_sync.json");
-        if sync_path.exists() {
-            tracing::info!("📥 Importing config_sync.json for tenant {}", tenant.slug);
-            if let Ok(content) = std::fs::read_to_string(&sync_path)
-                && let Ok(sync_data) = serde_json::from_str::<serde_json::Value>(&content)
-                    && let Some(obj) = sync_data.as_object() {
-                        for (key, value) in obj {
-                            if key == "updated_at" { continue; }
-                            let val_str = match value {
-                                serde_json::Value::String(s) => s.clone(),
-                                serde_json::Value::Bool(b) => b.to_string(),
-                                serde_json::Value::Number(n) => n.to_string(),
-                                other => other.to_string(),
-                            };
-                            if !val_str.is_empty() {
-                                db.set_config(&tenant.id, key, &val_str).ok();
-                            }
-                        }
-                        tracing::info!("  ✅ Imported {} config keys into DB", obj.len() - 1);
-                    }
-            // Remove sync file after import
-            std::fs::remove_file(&sync_path).ok();
-        }
+        import_config_sync(tenant, &tenant_dir, db);
 
         // ── Generate config.toml from DB (always regenerate) ──────────
-        let config_path = tenant_dir.join("config.toml");
         tracing::info!("📝 Generating config.toml for tenant {} from DB", tenant.slug);
-
-        // Start with tenant-level defaults
-        let mut provider = tenant.provider.clone();
-        let mut model = tenant.model.clone();
-        let mut api_key = String::new();
-        let mut api_base_url = String::new();
-        let mut identity_name = tenant.name.clone();
-        let mut identity_persona = String::new();
-        let mut system_prompt = String::new();
-
-        // Override with tenant_configs from DB (key-value pairs)
-        if let Ok(configs) = db.list_configs(&tenant.id) {
-            for cfg in &configs {
-                match cfg.key.as_str() {
-                    "default_provider" => provider = cfg.value.clone(),
-                    "default_model" => model = cfg.value.clone(),
-                    "api_key" => api_key = cfg.value.clone(),
-                    "api_base_url" => api_base_url = cfg.value.clone(),
-                    "identity.name" => identity_name = cfg.value.clone(),
-                    "identity.persona" => identity_persona = cfg.value.clone(),
-                    "identity.system_prompt" => system_prompt = cfg.value.clone(),
-                    _ => {} // other keys handled by TOML file directly
-                }
-            }
-        }
-
-        let mut config_content = format!(
-            r#"default_provider = "{provider}"
-default_model = "{model}"
-api_key = "{api_key}"
-api_base_url = "{api_base_url}"
-
-[identity]
-name = "{identity_name}"
-persona = "{identity_persona}"
-system_prompt = """{system_prompt}"""
-
-[gateway]
-host = "0.0.0.0"
-port = {}
-require_pairing = false
-"#,
-            tenant.port
-        );
-
-        // ── Inject brain/memory/autonomy configs from DB ──────────
-        if let Ok(configs) = db.list_configs(&tenant.id) {
-            let brain_keys: Vec<_> = configs.iter().filter(|c| c.key.starts_with("brain.")).collect();
-            if !brain_keys.is_empty() {
-                config_content.push_str("\n[brain]\n");
-                for cfg in &brain_keys {
-                    let field = cfg.key.strip_prefix("brain.").unwrap_or(&cfg.key);
-                    // Detect booleans and numbers
-                    if cfg.value == "true" || cfg.value == "false" || cfg.value.parse::<f64>().is_ok() {
-                        config_content.push_str(&format!("{} = {}\n", field, cfg.value));
-                    } else {
-                        config_content.push_str(&format!("{} = \"{}\"\n", field, cfg.value));
-                    }
-                }
-            }
+        let plan = self.plan(tenant, bizclaw_bin, db, false)?;
+        bizclaw_core::config::write_config_file(&plan.config_path, plan.config.as_bytes()).ok();
+        for (path, contents) in &plan.files {
+            std::fs::write(path, contents).ok();
         }
 
-        // ── Inject channel configs from DB ──────────
-        let mut has_db_channels = false;
-        if let Ok(channels) = db.list_channels(&tenant.id) {
-            for ch in &channels {
-                if !ch.enabled {
-                    continue;
-                }
-                has_db_channels = true;
-                if let Ok(cfg) = serde_json::from_str::<serde_json::Value>(&ch.config_json) {
-                    match ch.channel_type.as_str() {
-                        "telegram" => {
-                            let token = cfg["bot_token"].as_str().unwrap_or("");
-                            if !token.is_empty() {
-                                config_content.push_str(&format!(
-                                    "\n[channel.telegram]\nenabled = true\nbot_token = \"{}\"\n",
-                                    token
-                                ));
-                                if let Some(ids) = cfg["allowed_chat_ids"].as_str() {
-                                    let parsed: Vec<&str> = ids
-                                        .split(',')
-                                        .map(|s| s.trim())
-                                        .filter(|s| !s.is_empty())
-                                        .collect();
-                                    if !parsed.is_empty() {
-                                        config_content.push_str(&format!(
-                                            "allowed_chat_ids = [{}]\n",
-                                            parsed.join(", ")
-                                        ));
-                                    }
-                                }
-                            }
-                        }
-                        "zalo" => {
-                            let cookie = cfg["cookie"].as_str().unwrap_or("");
-                            if !cookie.is_empty() {
-                                let imei = cfg["imei"].as_str().unwrap_or("");
-                                config_content.push_str(&format!(
-                                    "\n[channel.zalo]\nenabled = true\nmode = \"personal\"\n\n[channel.zalo.personal]\ncookie_path = \"{}\"\nimei = \"{}\"\n",
-                                    tenant_dir.join("zalo_cookie.txt").display(),
-                                    imei
-                                ));
-                                std::fs::write(tenant_dir.join("zalo_cookie.txt"), cookie).ok();
-                            }
-                        }
-                        "discord" => {
-                            let token = cfg["bot_token"].as_str().unwrap_or("");
-                            if !token.is_empty() {
-                                config_content.push_str(&format!(
-                                    "\n[channel.discord]\nenabled = true\nbot_token = \"{}\"\n",
-                                    token
-                                ));
-                            }
-                        }
-                        "email" => {
-                            let email = cfg["email"].as_str().unwrap_or("");
-                            let password = cfg["password"].as_str().unwrap_or("");
-                            if !email.is_empty() && !password.is_empty() {
-                                config_content.push_str(&format!(
-                                    "\n[channel.email]\nenabled = true\nimap_host = \"{}\"\nimap_port = {}\nsmtp_host = \"{}\"\nsmtp_port = {}\nemail = \"{}\"\npassword = \"{}\"\n",
-                                    cfg["imap_host"].as_str().unwrap_or("imap.gmail.com"),
-                                    cfg["imap_port"].as_str().unwrap_or("993"),
-                                    cfg["smtp_host"].as_str().unwrap_or("smtp.gmail.com"),
-                                    cfg["smtp_port"].as_str().unwrap_or("587"),
-                                    email, password
-                                ));
-                            }
-                        }
-                        "webhook" => {
-                            let url = cfg["url"].as_str().unwrap_or("");
-                            if !url.is_empty() {
-                                config_content.push_str(&format!(
-                                    "\n[channel.webhook]\nurl = \"{}\"\nsecret = \"{}\"\n",
-                                    url,
-                                    cfg["secret"].as_str().unwrap_or("")
-                                ));
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-        }
-
-        // ── Fallback: read channels_sync.json from gateway dashboard saves ──
-        if !has_db_channels {
-            let channels_sync_path = tenant_dir.join("channels_sync.json");
-            if channels_sync_path.exists() {
-                tracing::info!("📥 Reading channels_sync.json for tenant {}", tenant.slug);
-                if let Ok(content) = std::fs::read_to_string(&channels_sync_path)
-                    && let Ok(channels) = serde_json::from_str::<serde_json::Value>(&content) {
-                        // Telegram
-                        if let Some(tg) = channels["telegram"].as_object()
-                            && tg.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
-                                let token = tg.get("bot_token").and_then(|v| v.as_str()).unwrap_or("");
-                                if !token.is_empty() {
-                                    config_content.push_str(&format!(
-                                        "\n[channel.telegram]\nenabled = true\nbot_token = \"{}\"\n", token
-                                    ));
-                                    if let Some(ids) = tg.get("allowed_chat_ids").and_then(|v| v.as_str()) {
-                                        let parsed: Vec<&str> = ids.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
-                                        if !parsed.is_empty() {
-                                            config_content.push_str(&format!("allowed_chat_ids = [{}]\n", parsed.join(", ")));
-                                        }
-                                    }
-                                }
-                            }
-                        // Discord
-                        if let Some(dc) = channels["discord"].as_object()
-                            && dc.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
-                                let token = dc.get("bot_token").and_then(|v| v.as_str()).unwrap_or("");
-                                if !token.is_empty() {
-                                    config_content.push_str(&format!(
-                                        "\n[channel.discord]\nenabled = true\nbot_token = \"{}\"\n", token
-                                    ));
-                                }
-                            }
-                        // Email
-                        if let Some(em) = channels["email"].as_object()
-                            && em.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
-                                let email = em.get("email").and_then(|v| v.as_str()).unwrap_or("");
-                                let password = em.get("password").and_then(|v| v.as_str()).unwrap_or("");
-                                if !email.is_empty() {
-                                    config_content.push_str(&format!(
-                                        "\n[channel.email]\nenabled = true\nsmtp_host = \"{}\"\nsmtp_port = {}\nemail = \"{}\"\npassword = \"{}\"\nimap_host = \"{}\"\nimap_port = {}\n",
-                                        em.get("smtp_host").and_then(|v| v.as_str()).unwrap_or("smtp.gmail.com"),
-                                        em.get("smtp_port").and_then(|v| v.as_u64()).unwrap_or(587),
-                                        email, password,
-                                        em.get("imap_host").and_then(|v| v.as_str()).unwrap_or("imap.gmail.com"),
-                                        em.get("imap_port").and_then(|v| v.as_u64()).unwrap_or(993),
-                                    ));
-                                }
-                            }
-                        // Webhook
-                        if let Some(wh) = channels["webhook"].as_object()
-                            && wh.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
-                                let url = wh.get("outbound_url").and_then(|v| v.as_str()).unwrap_or("");
-                                config_content.push_str(&format!(
-                                    "\n[channel.webhook]\nenabled = true\noutbound_url = \"{}\"\nsecret = \"{}\"\n",
-                                    url, wh.get("secret").and_then(|v| v.as_str()).unwrap_or("")
-                                ));
-                            }
-                        // WhatsApp
-                        if let Some(wa) = channels["whatsapp"].as_object()
-                            && wa.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
-                                config_content.push_str(&format!(
-                                    "\n[channel.whatsapp]\nenabled = true\nphone_number_id = \"{}\"\naccess_token = \"{}\"\nwebhook_verify_token = \"{}\"\n",
-                                    wa.get("phone_number_id").and_then(|v| v.as_str()).unwrap_or(""),
-                                    wa.get("access_token").and_then(|v| v.as_str()).unwrap_or(""),
-                                    wa.get("webhook_verify_token").and_then(|v| v.as_str()).unwrap_or(""),
-                                ));
-                            }
-                        // Zalo
-                        if let Some(zl) = channels["zalo"].as_object()
-                            && zl.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
-                                let imei = zl.get("imei").and_then(|v| v.as_str()).unwrap_or("");
-                                config_content.push_str(&format!(
-                                    "\n[channel.zalo]\nenabled = true\nmode = \"personal\"\n\n[channel.zalo.personal]\nimei = \"{}\"\n",
-                                    imei
-                                ));
-                            }
-                    }
-            }
-        }
-
-        std::fs::write(&config_path, &config_content).ok();
-
         // ── Import existing agents.json into DB if needed ──────────
         let agents_file = tenant_dir.join("agents.json");
         if agents_file.exists()
@@ -363,15 +162,8 @@ require_pairing = false
         let stdout = log_file.as_ref().map(|f| std::process::Stdio::from(f.try_clone().unwrap())).unwrap_or(std::process::Stdio::null());
         let stderr = log_file.map(std::process::Stdio::from).unwrap_or(std::process::Stdio::null());
 
-        let mut cmd = Command::new(bizclaw_bin);
-        cmd.args(["serve", "--port", &tenant.port.to_string(), "--config", config_path.to_str().unwrap_or("")])
-            .env("BIZCLAW_CONFIG", config_path.to_str().unwrap_or(""))
-            .env("BIZCLAW_DATA_DIR", tenant_dir.to_str().unwrap_or(""));
-
-        // Pass pairing code directly via env var — most reliable method
-        if let Some(ref code) = tenant.pairing_code {
-            cmd.env("BIZCLAW_PAIRING_CODE", code);
-        }
+        let mut cmd = Command::new(&plan.binary);
+        cmd.args(&plan.args).envs(plan.env.iter());
 
         let child = cmd
             .stdout(stdout)
@@ -387,6 +179,7 @@ require_pairing = false
                 pid,
                 port: tenant.port,
                 started_at: Instant::now(),
+                child: Some(child),
             },
         );
 
@@ -399,6 +192,102 @@ require_pairing = false
         Ok(pid)
     }
 
+    /// Resolve what [`start_tenant`](Self::start_tenant) would launch —
+    /// binary, arguments, environment and generated config — running the same
+    /// checks but writing nothing and spawning nothing (`--dry-run`).
+    pub fn plan_tenant(
+        &self,
+        tenant: &Tenant,
+        bizclaw_bin: &str,
+        db: &PlatformDb,
+    ) -> Result<SpawnPlan> {
+        self.plan(tenant, bizclaw_bin, db, true)
+    }
+
+    /// Shared by [`start_tenant`](Self::start_tenant) and
+    /// [`plan_tenant`](Self::plan_tenant); a dry run leaves the usage report
+    /// key uncreated.
+    fn plan(
+        &self,
+        tenant: &Tenant,
+        bizclaw_bin: &str,
+        db: &PlatformDb,
+        dry_run: bool,
+    ) -> Result<SpawnPlan> {
+        self.check_startable(tenant)?;
+        let binary = resolve_binary(bizclaw_bin).ok_or_else(|| {
+            BizClawError::provider(format!("BizClaw binary not found: {bizclaw_bin}"))
+        })?;
+
+        let tenant_dir = self.data_dir.join(&tenant.slug);
+        let config_path = tenant_dir.join("config.toml");
+        let (config, files) = render_config(tenant, &tenant_dir, db, self.platform_url());
+
+        let args = vec![
+            "serve".to_string(),
+            "--port".to_string(),
+            tenant.port.to_string(),
+            "--config".to_string(),
+            config_path.to_string_lossy().into_owned(),
+        ];
+        let mut env = BTreeMap::from([
+            ("BIZCLAW_CONFIG".to_string(), config_path.to_string_lossy().into_owned()),
+            ("BIZCLAW_DATA_DIR".to_string(), tenant_dir.to_string_lossy().into_owned()),
+        ]);
+        // Pass pairing code directly via env var — most reliable method
+        if let Some(ref code) = tenant.pairing_code {
+            env.insert("BIZCLAW_PAIRING_CODE".into(), code.clone());
+        }
+        // Usage reporting back to the platform (see gateway usage_report)
+        if let Some(ref url) = self.platform_url {
+            let key = if dry_run {
+                db.find_usage_report_key(&tenant.id)
+                    .map(|key| key.unwrap_or_else(|| "<created on start>".into()))
+            } else {
+                db.usage_report_key(&tenant.id)
+            };
+            match key {
+                Ok(key) => {
+                    env.insert("BIZCLAW_PLATFORM_URL".into(), url.clone());
+                    env.insert("BIZCLAW_TENANT_ID".into(), tenant.id.clone());
+                    env.insert("BIZCLAW_USAGE_KEY".into(), key);
+                }
+                Err(e) => tracing::warn!("Usage reporting disabled for {}: {e}", tenant.slug),
+            }
+        }
+
+        Ok(SpawnPlan {
+            binary,
+            args,
+            env,
+            working_dir: std::env::current_dir().unwrap_or_default(),
+            pending_config_sync: tenant_dir.join("config_sync.json").exists(),
+            data_dir: tenant_dir,
+            config_path,
+            config,
+            port: tenant.port,
+            files,
+        })
+    }
+
+    /// Refuse to start a tenant that is already running or whose port
+    /// another running tenant holds.
+    fn check_startable(&self, tenant: &Tenant) -> Result<()> {
+        if self.processes.contains_key(&tenant.id) {
+            return Err(BizClawError::provider(format!(
+                "Tenant {} already running",
+                tenant.slug
+            )));
+        }
+        if self.processes.values().any(|p| p.port == tenant.port) {
+            return Err(BizClawError::provider(format!(
+                "Port {} is already used by another tenant",
+                tenant.port
+            )));
+        }
+        Ok(())
+    }
+
     /// Stop a tenant process.
     pub fn stop_tenant(&mut self, tenant_id: &str) -> Result<()> {
         if let Some(proc) = self.processes.remove(tenant_id) {
@@ -409,6 +298,18 @@ require_pairing = false
         Ok(())
     }
 
+    /// Remove a deleted tenant's data directory.
+    pub fn remove_tenant_data(&self, slug: &str) -> Result<()> {
+        crate::db::validate_tenant_slug(slug)?;
+        let dir = self.data_dir.join(slug);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| {
+                BizClawError::provider(format!("Failed to remove {}: {e}", dir.display()))
+            })?;
+        }
+        Ok(())
+    }
+
     /// Restart a tenant.
     pub fn restart_tenant(
         &mut self,
@@ -423,9 +324,34 @@ require_pairing = false
             .ok();
         db.log_event("tenant_restarted", "system", &tenant.id, None)
             .ok();
+        self.events.entry(tenant.id.clone()).or_default().restarts += 1;
         Ok(pid)
     }
 
+    /// Forget tenants whose process exited without being stopped, counting
+    /// each as a crash. Returns their ids.
+    pub fn reap_crashed(&mut self) -> Vec<String> {
+        let crashed: Vec<String> = self
+            .processes
+            .iter_mut()
+            .filter_map(|(id, proc)| {
+                let status = proc.child.as_mut()?.try_wait().ok()??;
+                tracing::warn!("💥 Tenant {id} (pid={}) exited: {status}", proc.pid);
+                Some(id.clone())
+            })
+            .collect();
+        for id in &crashed {
+            self.processes.remove(id);
+            self.events.entry(id.clone()).or_default().crashes += 1;
+        }
+        crashed
+    }
+
+    /// Restart and crash counts, keyed by tenant id.
+    pub fn process_events(&self) -> &HashMap<String, ProcessEvents> {
+        &self.events
+    }
+
     /// Get list of running tenant IDs.
     pub fn running_tenant_ids(&self) -> Vec<String> {
         self.processes.keys().cloned().collect()
@@ -452,6 +378,336 @@ require_pairing = false
     }
 }
 
+/// Import config_sync.json (settings saved from the gateway dashboard) into
+/// the DB, then remove it.
+fn import_config_sync(tenant: &Tenant, tenant_dir: &Path, db: &PlatformDb) {
+    let sync_path = tenant_dir.join("config_sync.json");
+    if sync_path.exists() {
+        tracing::info!("📥 Importing config_sync.json for tenant {}", tenant.slug);
+        if let Ok(content) = std::fs::read_to_string(&sync_path)
+            && let Ok(sync_data) = serde_json::from_str::<serde_json::Value>(&content)
+                && let Some(obj) = sync_data.as_object() {
+                    for (key, value) in obj {
+                        if key == "updated_at" { continue; }
+                        let val_str = match value {
+                            serde_json::Value::String(s) => s.clone(),
+                            serde_json::Value::Bool(b) => b.to_string(),
+                            serde_json::Value::Number(n) => n.to_string(),
+                            other => other.to_string(),
+                        };
+                        if !val_str.is_empty() {
+                            db.set_config(&tenant.id, key, &val_str).ok();
+                        }
+                    }
+                    tracing::info!("  ✅ Imported {} config keys into DB", obj.len() - 1);
+                }
+        // Remove sync file after import
+        std::fs::remove_file(&sync_path).ok();
+    }
+}
+
+/// Generate a tenant's config.toml from DB state. Also returns the side
+/// files the config points at (e.g. the Zalo cookie), to write alongside it.
+fn render_config(
+    tenant: &Tenant,
+    tenant_dir: &Path,
+    db: &PlatformDb,
+    platform_url: Option<&str>,
+) -> (String, Vec<(PathBuf, String)>) {
+    let mut files = Vec::new();
+    // Start with tenant-level defaults
+    let mut provider = tenant.provider.clone();
+    let mut model = tenant.model.clone();
+    let mut api_key = String::new();
+    let mut api_base_url = String::new();
+    let mut identity_name = tenant.name.clone();
+    let mut identity_persona = String::new();
+    let mut system_prompt = String::new();
+    let mut channel_prompts: Vec<(String, String)> = Vec::new();
+
+    // Override with tenant_configs from DB (key-value pairs)
+    if let Ok(configs) = db.list_configs(&tenant.id) {
+        for cfg in &configs {
+            match cfg.key.as_str() {
+                "default_provider" => provider = cfg.value.clone(),
+                "default_model" => model = cfg.value.clone(),
+                "api_key" => api_key = cfg.value.clone(),
+                "api_base_url" => api_base_url = cfg.value.clone(),
+                "identity.name" => identity_name = cfg.value.clone(),
+                "identity.persona" => identity_persona = cfg.value.clone(),
+                "identity.system_prompt" => system_prompt = cfg.value.clone(),
+                key => {
+                    // Per-channel prompts: "identity.channel_prompts.<channel>"
+                    if let Some(channel) = key.strip_prefix("identity.channel_prompts.")
+                        && !channel.is_empty()
+                        && !cfg.value.trim().is_empty()
+                    {
+                        channel_prompts.push((channel.to_string(), cfg.value.clone()));
+                    }
+                } // other keys handled by TOML file directly
+            }
+        }
+    }
+
+    // Sandboxes never get the platform's provider key: their LLM calls go
+    // through the platform's proxy with a key scoped to the sandbox.
+    if tenant.sandbox {
+        if let Some(url) = platform_url {
+            provider = format!(
+                "custom:{}{}",
+                url.trim_end_matches('/'),
+                crate::self_serve::SANDBOX_LLM_BASE
+            );
+        }
+        api_key = db
+            .get_config(&tenant.id, crate::db::SANDBOX_KEY_CONFIG)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        api_base_url = String::new();
+    }
+
+    let mut config_content = format!(
+        r#"default_provider = "{provider}"
+default_model = "{model}"
+api_key = "{api_key}"
+api_base_url = "{api_base_url}"
+
+[identity]
+name = "{identity_name}"
+persona = "{identity_persona}"
+system_prompt = """{system_prompt}"""
+{}
+[gateway]
+host = "0.0.0.0"
+port = {}
+require_pairing = false
+"#,
+        channel_prompts_toml(&channel_prompts),
+        tenant.port
+    );
+
+    // ── Inject brain/memory/autonomy configs from DB ──────────
+    if let Ok(configs) = db.list_configs(&tenant.id) {
+        let brain_keys: Vec<_> = configs.iter().filter(|c| c.key.starts_with("brain.")).collect();
+        if !brain_keys.is_empty() {
+            config_content.push_str("\n[brain]\n");
+            for cfg in &brain_keys {
+                let field = cfg.key.strip_prefix("brain.").unwrap_or(&cfg.key);
+                // Detect booleans and numbers
+                if cfg.value == "true" || cfg.value == "false" || cfg.value.parse::<f64>().is_ok() {
+                    config_content.push_str(&format!("{} = {}\n", field, cfg.value));
+                } else {
+                    config_content.push_str(&format!("{} = \"{}\"\n", field, cfg.value));
+                }
+            }
+        }
+    }
+
+    // ── Feature flags: drop tools and features the tenant's plan or overrides turn off ──
+    match db.feature_flags(tenant) {
+        Ok(flags) => {
+            config_content.push_str(&crate::features::autonomy_toml(&flags));
+            config_content.push_str(&crate::features::features_toml(&flags));
+        }
+        Err(e) => tracing::warn!("Feature flags for tenant {}: {e}", tenant.slug),
+    }
+
+    // ── Inject channel configs from DB ──────────
+    let mut has_db_channels = false;
+    if let Ok(channels) = db.list_channels(&tenant.id) {
+        for ch in &channels {
+            if !ch.enabled {
+                continue;
+            }
+            has_db_channels = true;
+            if let Ok(cfg) = serde_json::from_str::<serde_json::Value>(&ch.config_json) {
+                match ch.channel_type.as_str() {
+                    "telegram" => {
+                        let token = cfg["bot_token"].as_str().unwrap_or("");
+                        if !token.is_empty() {
+                            config_content.push_str(&format!(
+                                "\n[channel.telegram]\nenabled = true\nbot_token = \"{}\"\n",
+                                token
+                            ));
+                            if let Some(ids) = cfg["allowed_chat_ids"].as_str() {
+                                let parsed: Vec<&str> = ids
+                                    .split(',')
+                                    .map(|s| s.trim())
+                                    .filter(|s| !s.is_empty())
+                                    .collect();
+                                if !parsed.is_empty() {
+                                    config_content.push_str(&format!(
+                                        "allowed_chat_ids = [{}]\n",
+                                        parsed.join(", ")
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                    "zalo" => {
+                        let cookie = cfg["cookie"].as_str().unwrap_or("");
+                        if !cookie.is_empty() {
+                            let imei = cfg["imei"].as_str().unwrap_or("");
+                            config_content.push_str(&format!(
+                                "\n[channel.zalo]\nenabled = true\nmode = \"personal\"\n\n[channel.zalo.personal]\ncookie_path = \"{}\"\nimei = \"{}\"\n",
+                                tenant_dir.join("zalo_cookie.txt").display(),
+                                imei
+                            ));
+                            files.push((tenant_dir.join("zalo_cookie.txt"), cookie.to_string()));
+                        }
+                    }
+                    "discord" => {
+                        let token = cfg["bot_token"].as_str().unwrap_or("");
+                        if !token.is_empty() {
+                            config_content.push_str(&format!(
+                                "\n[channel.discord]\nenabled = true\nbot_token = \"{}\"\n",
+                                token
+                            ));
+                        }
+                    }
+                    "email" => {
+                        let email = cfg["email"].as_str().unwrap_or("");
+                        let password = cfg["password"].as_str().unwrap_or("");
+                        if !email.is_empty() && !password.is_empty() {
+                            config_content.push_str(&format!(
+                                "\n[channel.email]\nenabled = true\nimap_host = \"{}\"\nimap_port = {}\nsmtp_host = \"{}\"\nsmtp_port = {}\nemail = \"{}\"\npassword = \"{}\"\n",
+                                cfg["imap_host"].as_str().unwrap_or("imap.gmail.com"),
+                                cfg["imap_port"].as_str().unwrap_or("993"),
+                                cfg["smtp_host"].as_str().unwrap_or("smtp.gmail.com"),
+                                cfg["smtp_port"].as_str().unwrap_or("587"),
+                                email, password
+                            ));
+                        }
+                    }
+                    "webhook" => {
+                        let url = cfg["url"].as_str().unwrap_or("");
+                        if !url.is_empty() {
+                            config_content.push_str(&format!(
+                                "\n[channel.webhook]\nurl = \"{}\"\nsecret = \"{}\"\n",
+                                url,
+                                cfg["secret"].as_str().unwrap_or("")
+                            ));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // ── Fallback: read channels_sync.json from gateway dashboard saves ──
+    if !has_db_channels {
+        let channels_sync_path = tenant_dir.join("channels_sync.json");
+        if channels_sync_path.exists() {
+            tracing::info!("📥 Reading channels_sync.json for tenant {}", tenant.slug);
+            if let Ok(content) = std::fs::read_to_string(&channels_sync_path)
+                && let Ok(channels) = serde_json::from_str::<serde_json::Value>(&content) {
+                    // Telegram
+                    if let Some(tg) = channels["telegram"].as_object()
+                        && tg.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
+                            let token = tg.get("bot_token").and_then(|v| v.as_str()).unwrap_or("");
+                            if !token.is_empty() {
+                                config_content.push_str(&format!(
+                                    "\n[channel.telegram]\nenabled = true\nbot_token = \"{}\"\n", token
+                                ));
+                                if let Some(ids) = tg.get("allowed_chat_ids").and_then(|v| v.as_str()) {
+                                    let parsed: Vec<&str> = ids.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
+                                    if !parsed.is_empty() {
+                                        config_content.push_str(&format!("allowed_chat_ids = [{}]\n", parsed.join(", ")));
+                                    }
+                                }
+                            }
+                        }
+                    // Discord
+                    if let Some(dc) = channels["discord"].as_object()
+                        && dc.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
+                            let token = dc.get("bot_token").and_then(|v| v.as_str()).unwrap_or("");
+                            if !token.is_empty() {
+                                config_content.push_str(&format!(
+                                    "\n[channel.discord]\nenabled = true\nbot_token = \"{}\"\n", token
+                                ));
+                            }
+                        }
+                    // Email
+                    if let Some(em) = channels["email"].as_object()
+                        && em.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
+                            let email = em.get("email").and_then(|v| v.as_str()).unwrap_or("");
+                            let password = em.get("password").and_then(|v| v.as_str()).unwrap_or("");
+                            if !email.is_empty() {
+                                config_content.push_str(&format!(
+                                    "\n[channel.email]\nenabled = true\nsmtp_host = \"{}\"\nsmtp_port = {}\nemail = \"{}\"\npassword = \"{}\"\nimap_host = \"{}\"\nimap_port = {}\n",
+                                    em.get("smtp_host").and_then(|v| v.as_str()).unwrap_or("smtp.gmail.com"),
+                                    em.get("smtp_port").and_then(|v| v.as_u64()).unwrap_or(587),
+                                    email, password,
+                                    em.get("imap_host").and_then(|v| v.as_str()).unwrap_or("imap.gmail.com"),
+                                    em.get("imap_port").and_then(|v| v.as_u64()).unwrap_or(993),
+                                ));
+                            }
+                        }
+                    // Webhook
+                    if let Some(wh) = channels["webhook"].as_object()
+                        && wh.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
+                            let url = wh.get("outbound_url").and_then(|v| v.as_str()).unwrap_or("");
+                            config_content.push_str(&format!(
+                                "\n[channel.webhook]\nenabled = true\noutbound_url = \"{}\"\nsecret = \"{}\"\n",
+                                url, wh.get("secret").and_then(|v| v.as_str()).unwrap_or("")
+                            ));
+                        }
+                    // WhatsApp
+                    if let Some(wa) = channels["whatsapp"].as_object()
+                        && wa.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
+                            config_content.push_str(&format!(
+                                "\n[channel.whatsapp]\nenabled = true\nphone_number_id = \"{}\"\naccess_token = \"{}\"\nwebhook_verify_token = \"{}\"\napp_secret = \"{}\"\n",
+                                wa.get("phone_number_id").and_then(|v| v.as_str()).unwrap_or(""),
+                                wa.get("access_token").and_then(|v| v.as_str()).unwrap_or(""),
+                                wa.get("webhook_verify_token").and_then(|v| v.as_str()).unwrap_or(""),
+                                wa.get("app_secret").and_then(|v| v.as_str()).unwrap_or(""),
+                            ));
+                        }
+                    // Zalo
+                    if let Some(zl) = channels["zalo"].as_object()
+                        && zl.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false) {
+                            let imei = zl.get("imei").and_then(|v| v.as_str()).unwrap_or("");
+                            config_content.push_str(&format!(
+                                "\n[channel.zalo]\nenabled = true\nmode = \"personal\"\n\n[channel.zalo.personal]\nimei = \"{}\"\n",
+                                imei
+                            ));
+                        }
+                }
+        }
+    }
+
+    (config_content, files)
+}
+
+/// Resolve `bin` like `Command` would: a path is used as given, a bare name
+/// is looked up on `PATH`.
+fn resolve_binary(bin: &str) -> Option<PathBuf> {
+    let path = Path::new(bin);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(bin))
+        .find(|p| p.is_file())
+}
+
+/// `[identity.channel_prompts]` table for a tenant's config.toml (empty when none are set).
+fn channel_prompts_toml(prompts: &[(String, String)]) -> String {
+    if prompts.is_empty() {
+        return String::new();
+    }
+    let mut sorted: Vec<_> = prompts.iter().collect();
+    sorted.sort();
+    let mut out = String::from("\n[identity.channel_prompts]\n");
+    for (channel, prompt) in sorted {
+        out.push_str(&format!("\"{channel}\" = \"\"\"{prompt}\"\"\"\n"));
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -467,8 +723,159 @@ mod tests {
                 pid: 1,
                 port: 10001,
                 started_at: Instant::now(),
+                child: None,
             },
         );
         assert_eq!(mgr.next_port(10001), 10002);
     }
+
+    #[test]
+    fn test_exited_process_counted_as_crash() {
+        let mut mgr = TenantManager::new("/tmp/bizclaw-test");
+        let mut child = Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        mgr.processes.insert(
+            "t1".into(),
+            TenantProcess {
+                pid: child.id(),
+                port: 10001,
+                started_at: Instant::now(),
+                child: Some(child),
+            },
+        );
+
+        assert_eq!(mgr.reap_crashed(), vec!["t1".to_string()]);
+        assert!(!mgr.is_running("t1"));
+        assert_eq!(mgr.process_events()["t1"].crashes, 1);
+        assert!(mgr.reap_crashed().is_empty());
+    }
+
+    #[test]
+    fn test_channel_prompts_toml() {
+        assert_eq!(channel_prompts_toml(&[]), "");
+        let section = channel_prompts_toml(&[
+            ("telegram".into(), "Be brief.".into()),
+            ("email".into(), "Write formally.".into()),
+        ]);
+        let toml_str = format!(
+            "[identity]\nname = \"Shop\"\npersona = \"\"\nsystem_prompt = \"Default\"\n{section}"
+        );
+        let path =
+            std::env::temp_dir().join(format!("bizclaw-prompts-{}.toml", std::process::id()));
+        std::fs::write(&path, toml_str).unwrap();
+        let config = bizclaw_core::config::BizClawConfig::load_from(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(config.identity.prompt_for("telegram"), "Be brief.");
+        assert_eq!(config.identity.prompt_for("email"), "Write formally.");
+        assert_eq!(config.identity.prompt_for("cli"), "Default");
+    }
+
+    #[test]
+    fn test_disabled_feature_flags_reach_tenant_config() {
+        let db = PlatformDb::open(Path::new(":memory:")).unwrap();
+        let tenant = db
+            .create_tenant("Shop", "shop", 10006, "openai", "gpt-4o-mini", "pro", None)
+            .unwrap();
+        let tenant_dir = std::env::temp_dir().join("bizclaw-features-shop");
+        let load = |db: &PlatformDb| {
+            let (content, _) = render_config(&tenant, &tenant_dir, db, None);
+            let path = std::env::temp_dir()
+                .join(format!("bizclaw-features-{}.toml", std::process::id()));
+            std::fs::write(&path, content).unwrap();
+            let config = bizclaw_core::config::BizClawConfig::load_from(&path).unwrap();
+            std::fs::remove_file(&path).ok();
+            config
+        };
+
+        let config = load(&db);
+        assert!(config.autonomy.allows_tool("web_search"));
+        assert!(config.features.skills && config.features.heartbeat);
+
+        db.set_feature_flag(&tenant.id, "web_tool", Some(false)).unwrap();
+        let autonomy = load(&db).autonomy;
+        assert!(!autonomy.allows_tool("web_search"));
+        assert!(!autonomy.allows_tool("browser"));
+        assert!(autonomy.allows_tool("calendar"));
+
+        db.set_feature_flag(&tenant.id, "tools", Some(false)).unwrap();
+        assert!(!load(&db).autonomy.allows_tool("calendar"));
+
+        db.set_feature_flag(&tenant.id, "skills", Some(false)).unwrap();
+        db.set_feature_flag(&tenant.id, "heartbeat", Some(false)).unwrap();
+        let features = load(&db).features;
+        assert!(!features.skills && !features.heartbeat);
+    }
+
+    #[test]
+    fn test_sandbox_config_uses_platform_proxy() {
+        let db = PlatformDb::open(Path::new(":memory:")).unwrap();
+        let tenant = db
+            .create_sandbox_tenant("Demo", "demo-shop", 10007, "openai", "gpt-4o-mini", 60)
+            .unwrap();
+        db.set_config(&tenant.id, crate::db::SANDBOX_KEY_CONFIG, "sbx-demo").unwrap();
+        db.set_config(&tenant.id, "api_key", "sk-platform").unwrap();
+        let tenant_dir = std::env::temp_dir().join("bizclaw-sandbox-demo");
+
+        let (content, _) = render_config(&tenant, &tenant_dir, &db, Some("http://127.0.0.1:3000/"));
+        assert!(!content.contains("sk-platform"), "{content}");
+        let path = std::env::temp_dir().join(format!("bizclaw-sandbox-{}.toml", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        let config = bizclaw_core::config::BizClawConfig::load_from(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(config.default_provider, "custom:http://127.0.0.1:3000/api/sandbox/v1");
+        assert_eq!(config.api_key, "sbx-demo");
+        assert!(!config.features.settings);
+    }
+
+    #[test]
+    fn test_dry_run_plan() {
+        let db = PlatformDb::open(Path::new(":memory:")).unwrap();
+        let tenant = db
+            .create_tenant("Shop", "shop", 10005, "openai", "gpt-4o-mini", "free", None)
+            .unwrap();
+        db.set_config(&tenant.id, "api_key", "sk-shop").unwrap();
+        let data_dir =
+            std::env::temp_dir().join(format!("bizclaw-dry-run-{}", std::process::id()));
+        let mut mgr = TenantManager::new(&data_dir).with_platform_url("http://127.0.0.1:3000");
+        let bin = std::env::current_exe().unwrap();
+
+        let plan = mgr.plan_tenant(&tenant, bin.to_str().unwrap(), &db).unwrap();
+        let tenant_dir = data_dir.join("shop");
+        let config_path = tenant_dir.join("config.toml");
+        assert_eq!(plan.binary, bin);
+        assert_eq!(plan.port, 10005);
+        assert_eq!(plan.config_path, config_path);
+        assert_eq!(
+            plan.args,
+            ["serve", "--port", "10005", "--config", config_path.to_str().unwrap()]
+        );
+        assert_eq!(plan.env["BIZCLAW_DATA_DIR"], tenant_dir.to_str().unwrap());
+        assert_eq!(plan.env["BIZCLAW_PAIRING_CODE"], tenant.pairing_code.clone().unwrap());
+        assert_eq!(plan.env["BIZCLAW_TENANT_ID"], tenant.id);
+        assert!(plan.config.contains("api_key = \"sk-shop\""));
+        assert!(plan.config.contains("port = 10005"));
+        assert!(!plan.pending_config_sync);
+
+        assert_eq!(plan.env["BIZCLAW_USAGE_KEY"], "<created on start>");
+
+        // Nothing was written or spawned.
+        assert!(!tenant_dir.exists());
+        assert_eq!(db.find_usage_report_key(&tenant.id).unwrap(), None);
+        assert!(!mgr.is_running(&tenant.id));
+
+        // Same validation as a real start.
+        let err = mgr.plan_tenant(&tenant, "/nonexistent/bizclaw", &db).unwrap_err();
+        assert!(err.to_string().contains("binary not found"), "{err}");
+        mgr.processes.insert(
+            "other".into(),
+            TenantProcess {
+                pid: 1,
+                port: 10005,
+                started_at: Instant::now(),
+                child: None,
+            },
+        );
+        let err = mgr.plan_tenant(&tenant, bin.to_str().unwrap(), &db).unwrap_err();
+        assert!(err.to_string().contains("Port 10005"), "{err}");
+    }
 }
@@ -0,0 +1,499 @@
+//! Anthropic Messages API streaming — text and tool calls from SSE events.
+//!
+//! A streamed turn interleaves text and `tool_use` content blocks. Tool
+//! input arrives as `input_json_delta` fragments that only form valid JSON
+//! once the block is complete, so fragments are buffered per block index and
+//! decoded when the stream ends:
+//!
+//! ```text
+//! content_block_start {"index":1,"content_block":{"type":"tool_use","id":"toolu_1","name":"weather"}}
+//! content_block_delta {"index":1,"delta":{"type":"input_json_delta","partial_json":"{\"ci"}}
+//! content_block_delta {"index":1,"delta":{"type":"input_json_delta","partial_json":"ty\":\"Hanoi\"}"}}
+//! content_block_stop  {"index":1}
+//! ```
+//!
+//! The result is a regular [`ProviderResponse`], so the agent loop runs the
+//! tools and continues the turn with `tool_result` blocks as usual.
+
+use std::collections::BTreeMap;
+
+use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::traits::provider::{GenerateParams, OnText};
+use bizclaw_core::types::{
+    FINISH_CONTENT_FILTER, Message, ProviderResponse, Refusal, Role, ToolCall, ToolChoice,
+    ToolDefinition, Usage,
+};
+use serde_json::{Value, json};
+
+use crate::system_prompt::SystemPromptFormat;
+use crate::tool_format::ToolWireFormat;
+
+/// `anthropic-version` header sent with Messages API requests.
+pub const API_VERSION: &str = "2023-06-01";
+
+/// Build a streaming Messages API request body.
+pub fn request_body(
+    messages: &[Message],
+    tools: &[ToolDefinition],
+    params: &GenerateParams,
+    vision: bool,
+) -> Value {
+    let (system, messages) = encode_messages(messages, vision);
+    let mut body = json!({
+        "model": params.model,
+        "max_tokens": params.max_tokens,
+        "temperature": params.temperature,
+        "messages": messages,
+        "stream": true,
+    });
+    if !system.is_empty() {
+        body["system"] = SystemPromptFormat::Anthropic.encode(&system.join("\n\n"));
+    }
+    if !params.stop.is_empty() {
+        body["stop_sequences"] = json!(params.stop);
+    }
+    if !tools.is_empty() {
+        body["tools"] = ToolWireFormat::Anthropic.encode_tools(tools);
+        if params.tool_choice != ToolChoice::Auto {
+            body["tool_choice"] = ToolWireFormat::Anthropic.encode_tool_choice(&params.tool_choice);
+        }
+    }
+    body
+}
+
+/// Split out system text and encode the rest as content blocks.
+///
+/// Tool results become `tool_result` blocks in a user turn, and consecutive
+/// turns with the same role are merged, as the API requires alternation.
+fn encode_messages(messages: &[Message], vision: bool) -> (Vec<String>, Vec<Value>) {
+    let mut system = Vec::new();
+    let mut out: Vec<Value> = Vec::new();
+    for msg in messages {
+        let text = if vision {
+            msg.content.clone()
+        } else {
+            msg.content_with_image_notes()
+        };
+        let images = msg.images.iter().filter(|_| vision).map(|img| {
+            let source = match &img.url {
+                Some(url) => json!({"type": "url", "url": url}),
+                None => json!({"type": "base64", "media_type": img.media_type, "data": img.data}),
+            };
+            json!({"type": "image", "source": source})
+        });
+        let mut blocks = Vec::new();
+        let role = match msg.role {
+            Role::System => {
+                system.push(msg.content.clone());
+                continue;
+            }
+            Role::User => {
+                blocks.extend(text_block(&text));
+                blocks.extend(images);
+                "user"
+            }
+            Role::Assistant => {
+                blocks.extend(text_block(&text));
+                for call in msg.tool_calls.iter().flatten() {
+                    let input: Value = serde_json::from_str(&call.function.arguments)
+                        .unwrap_or_else(|_| json!({}));
+                    blocks.push(json!({
+                        "type": "tool_use",
+                        "id": call.id,
+                        "name": call.function.name,
+                        "input": input,
+                    }));
+                }
+                "assistant"
+            }
+            Role::Tool => {
+                let mut content: Vec<Value> = text_block(&text).into_iter().collect();
+                content.extend(images);
+                blocks.push(json!({
+                    "type": "tool_result",
+                    "tool_use_id": msg.tool_call_id.as_deref().unwrap_or(""),
+                    "content": content,
+                }));
+                "user"
+            }
+        };
+        if blocks.is_empty() {
+            continue;
+        }
+        match out.last_mut() {
+            Some(last) if last["role"] == role => {
+                if let Some(content) = last["content"].as_array_mut() {
+                    content.extend(blocks);
+                }
+            }
+            _ => out.push(json!({"role": role, "content": blocks})),
+        }
+    }
+    (system, out)
+}
+
+/// The API rejects empty text blocks.
+fn text_block(text: &str) -> Option<Value> {
+    (!text.is_empty()).then(|| json!({"type": "text", "text": text}))
+}
+
+/// Splits an SSE byte stream into the JSON payloads of its `data:` lines.
+/// Chunks may end anywhere, including mid-line or mid-character.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buf: Vec<u8>,
+}
+
+impl SseDecoder {
+    /// Feed one chunk and return the events it completed.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<Value> {
+        self.buf.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(pos) = self.buf.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buf.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(data) = line.trim_end().strip_prefix("data:")
+                && let Ok(event) = serde_json::from_str(data.trim())
+            {
+                events.push(event);
+            }
+        }
+        events
+    }
+}
+
+#[derive(Debug)]
+enum Block {
+    Text(String),
+    Thinking(String),
+    ToolUse {
+        id: String,
+        name: String,
+        /// Concatenated `partial_json` fragments.
+        input_json: String,
+        /// Input given whole in `content_block_start` (non-empty only when
+        /// the server does not stream it).
+        initial: Value,
+    },
+}
+
+/// Folds stream events into a [`ProviderResponse`].
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    blocks: BTreeMap<u64, Block>,
+    stop_reason: Option<String>,
+    input_tokens: u32,
+    output_tokens: u32,
+}
+
+impl StreamAccumulator {
+    /// Apply one event. Returns the text to show the user, if any.
+    pub fn apply(&mut self, event: &Value) -> Result<Option<String>> {
+        let index = event["index"].as_u64().unwrap_or(0);
+        match event["type"].as_str().unwrap_or("") {
+            "message_start" => {
+                let usage = &event["message"]["usage"];
+                self.input_tokens = usage["input_tokens"].as_u64().unwrap_or(0) as u32;
+                self.output_tokens = usage["output_tokens"].as_u64().unwrap_or(0) as u32;
+            }
+            "content_block_start" => {
+                let block = &event["content_block"];
+                let str_field = |key: &str| block[key].as_str().unwrap_or("").to_string();
+                match block["type"].as_str().unwrap_or("") {
+                    "text" => {
+                        let text = str_field("text");
+                        self.blocks.insert(index, Block::Text(text.clone()));
+                        return Ok((!text.is_empty()).then_some(text));
+                    }
+                    "thinking" => {
+                        self.blocks
+                            .insert(index, Block::Thinking(str_field("thinking")));
+                    }
+                    "tool_use" => {
+                        let block = Block::ToolUse {
+                            id: str_field("id"),
+                            name: str_field("name"),
+                            input_json: String::new(),
+                            initial: block["input"].clone(),
+                        };
+                        self.blocks.insert(index, block);
+                    }
+                    _ => {}
+                }
+            }
+            "content_block_delta" => {
+                let delta = &event["delta"];
+                let part = |key: &str| delta[key].as_str().unwrap_or("");
+                match (delta["type"].as_str(), self.blocks.get_mut(&index)) {
+                    (Some("text_delta"), Some(Block::Text(text))) => {
+                        text.push_str(part("text"));
+                        return Ok(Some(part("text").to_string()));
+                    }
+                    (Some("thinking_delta"), Some(Block::Thinking(thinking))) => {
+                        thinking.push_str(part("thinking"));
+                    }
+                    (Some("input_json_delta"), Some(Block::ToolUse { input_json, .. })) => {
+                        input_json.push_str(part("partial_json"));
+                    }
+                    _ => {}
+                }
+            }
+            "message_delta" => {
+                if let Some(reason) = event["delta"]["stop_reason"].as_str() {
+                    self.stop_reason = Some(reason.to_string());
+                }
+                if let Some(out) = event["usage"]["output_tokens"].as_u64() {
+                    self.output_tokens = out as u32;
+                }
+            }
+            "error" => {
+                return Err(BizClawError::Provider(format!(
+                    "anthropic stream error: {}",
+                    event["error"]["message"]
+                        .as_str()
+                        .unwrap_or("unknown error")
+                )));
+            }
+            // ping, content_block_stop, message_stop
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    /// Assemble the final response. Fails if a tool call's input never
+    /// became valid JSON (e.g. the stream was cut off).
+    pub fn finish(self) -> Result<ProviderResponse> {
+        let mut content = String::new();
+        let mut reasoning = Vec::new();
+        let mut tool_calls = Vec::new();
+        for block in self.blocks.into_values() {
+            match block {
+                Block::Text(text) => content.push_str(&text),
+                Block::Thinking(thinking) => reasoning.push(thinking),
+                Block::ToolUse {
+                    id,
+                    name,
+                    input_json,
+                    initial,
+                } => {
+                    let arguments = if !input_json.trim().is_empty() {
+                        serde_json::from_str::<Value>(&input_json).map_err(|e| {
+                            BizClawError::Provider(format!(
+                                "anthropic: incomplete input for tool '{name}': {e}"
+                            ))
+                        })?;
+                        input_json
+                    } else if initial.is_object() {
+                        initial.to_string()
+                    } else {
+                        "{}".to_string()
+                    };
+                    tool_calls.push(ToolCall::function(id, name, arguments));
+                }
+            }
+        }
+
+        let refusal = Refusal::detect(self.stop_reason.as_deref(), None);
+        let finish_reason = self.stop_reason.map(|r| {
+            match r.as_str() {
+                "tool_use" => "tool_calls",
+                "end_turn" | "stop_sequence" => "stop",
+                "max_tokens" => "length",
+                "refusal" => FINISH_CONTENT_FILTER,
+                other => other,
+            }
+            .to_string()
+        });
+        let usage = (self.input_tokens + self.output_tokens > 0).then(|| Usage {
+            prompt_tokens: self.input_tokens,
+            completion_tokens: self.output_tokens,
+            total_tokens: self.input_tokens + self.output_tokens,
+        });
+        let reasoning = reasoning.join("\n\n");
+        Ok(ProviderResponse {
+            content: (!content.is_empty()).then_some(content),
+            tool_calls,
+            finish_reason,
+            usage,
+            reasoning: (!reasoning.trim().is_empty()).then(|| reasoning.trim().to_string()),
+            refusal,
+            fallback: None,
+        })
+    }
+}
+
+/// Read a streaming Messages API response, passing text deltas to `on_text`.
+pub async fn read_stream(
+    mut resp: reqwest::Response,
+    on_text: &OnText<'_>,
+) -> Result<ProviderResponse> {
+    let mut decoder = SseDecoder::default();
+    let mut acc = StreamAccumulator::default();
+    while let Some(chunk) = resp
+        .chunk()
+        .await
+        .map_err(|e| BizClawError::Http(format!("anthropic stream read failed: {e}")))?
+    {
+        for event in decoder.push(&chunk) {
+            if let Some(text) = acc.apply(&event)? {
+                on_text(&text);
+            }
+        }
+    }
+    acc.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A turn with text, then a tool call whose JSON is split mid-token.
+    const TOOL_STREAM: &str = concat!(
+        "event: message_start\n",
+        "data: {\"type\":\"message_start\",\"message\":{\"usage\":{\"input_tokens\":12,\"output_tokens\":1}}}\n\n",
+        "event: content_block_start\n",
+        "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Checking \"}}\n\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"the weather.\"}}\n\n",
+        "data: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+        "event: ping\ndata: {\"type\":\"ping\"}\n\n",
+        "data: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_1\",\"name\":\"weather\",\"input\":{}}}\n\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"\"}}\n\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"ci\"}}\n\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"ty\\\": \\\"Hà Nội\\\", \\\"da\"}}\n\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"ys\\\": 3}\"}}\n\n",
+        "data: {\"type\":\"content_block_stop\",\"index\":1}\n\n",
+        "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\"},\"usage\":{\"output_tokens\":30}}\n\n",
+        "data: {\"type\":\"message_stop\"}\n\n",
+    );
+
+    #[test]
+    fn test_tool_input_assembled_across_deltas() {
+        // Feed the stream in awkward 7-byte chunks, splitting lines and
+        // multi-byte characters.
+        let mut decoder = SseDecoder::default();
+        let mut acc = StreamAccumulator::default();
+        let mut shown = String::new();
+        for chunk in TOOL_STREAM.as_bytes().chunks(7) {
+            for event in decoder.push(chunk) {
+                if let Some(text) = acc.apply(&event).unwrap() {
+                    shown.push_str(&text);
+                }
+            }
+        }
+        let resp = acc.finish().unwrap();
+
+        assert_eq!(shown, "Checking the weather.");
+        assert_eq!(resp.content.as_deref(), Some("Checking the weather."));
+        assert_eq!(resp.finish_reason.as_deref(), Some("tool_calls"));
+        assert_eq!(resp.tool_calls.len(), 1);
+        let call = &resp.tool_calls[0];
+        assert_eq!(call.id, "toolu_1");
+        assert_eq!(call.function.name, "weather");
+        let args: Value = serde_json::from_str(&call.function.arguments).unwrap();
+        assert_eq!(args, json!({"city": "Hà Nội", "days": 3}));
+        assert_eq!(resp.usage.unwrap().total_tokens, 42);
+    }
+
+    #[test]
+    fn test_refusal_stop_reason() {
+        let mut acc = StreamAccumulator::default();
+        for event in [
+            json!({"type": "content_block_start", "index": 0,
+                   "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "refusal"}}),
+        ] {
+            acc.apply(&event).unwrap();
+        }
+        let resp = acc.finish().unwrap();
+        assert_eq!(resp.finish_reason.as_deref(), Some(FINISH_CONTENT_FILTER));
+        assert_eq!(resp.refusal.unwrap().reason, "refusal");
+        assert!(resp.content.is_none());
+    }
+
+    #[test]
+    fn test_truncated_tool_input_is_an_error() {
+        let mut acc = StreamAccumulator::default();
+        for event in [
+            json!({"type": "content_block_start", "index": 0,
+                   "content_block": {"type": "tool_use", "id": "t", "name": "shell", "input": {}}}),
+            json!({"type": "content_block_delta", "index": 0,
+                   "delta": {"type": "input_json_delta", "partial_json": "{\"command\": \"l"}}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "max_tokens"}}),
+        ] {
+            acc.apply(&event).unwrap();
+        }
+        let err = acc.finish().unwrap_err().to_string();
+        assert!(err.contains("shell"), "{err}");
+
+        let mut acc = StreamAccumulator::default();
+        let event = json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}});
+        assert!(acc.apply(&event).is_err());
+    }
+
+    #[test]
+    fn test_tool_round_trip_encoding() {
+        let mut assistant = Message::assistant("Let me check.");
+        assistant.tool_calls = Some(vec![ToolCall::function(
+            "toolu_1",
+            "weather",
+            r#"{"city":"Hue"}"#,
+        )]);
+        let messages = vec![
+            Message::system("You are helpful."),
+            Message::user("Weather in Hue?"),
+            assistant,
+            Message::tool("Sunny, 31°C", "toolu_1"),
+            Message::system("[Knowledge Base]"),
+        ];
+        let params = GenerateParams {
+            model: "claude-sonnet-4-20250514".into(),
+            ..Default::default()
+        };
+        let body = request_body(&messages, &[], &params, false);
+
+        assert_eq!(body["stream"], true);
+        assert_eq!(
+            body["system"][0]["text"],
+            "You are helpful.\n\n[Knowledge Base]"
+        );
+        let wire = body["messages"].as_array().unwrap();
+        assert_eq!(wire.len(), 3);
+        assert_eq!(wire[1]["content"][1]["type"], "tool_use");
+        assert_eq!(wire[1]["content"][1]["input"]["city"], "Hue");
+        assert_eq!(wire[2]["role"], "user");
+        assert_eq!(wire[2]["content"][0]["type"], "tool_result");
+        assert_eq!(wire[2]["content"][0]["tool_use_id"], "toolu_1");
+    }
+
+    #[test]
+    fn test_user_image_encoding() {
+        use bizclaw_core::types::ImageContent;
+
+        let messages = vec![Message::user("What is in these photos?").with_images(vec![
+            ImageContent::new("image/jpeg", "/9j/4AAQ"),
+            ImageContent::from_url("image/png", "https://cdn.example.com/a.png"),
+        ])];
+        let body = request_body(&messages, &[], &GenerateParams::default(), true);
+        let content = body["messages"][0]["content"].as_array().unwrap();
+        assert_eq!(content.len(), 3);
+        assert_eq!(content[0]["text"], "What is in these photos?");
+        assert_eq!(content[1]["type"], "image");
+        assert_eq!(
+            content[1]["source"],
+            json!({"type": "base64", "media_type": "image/jpeg", "data": "/9j/4AAQ"})
+        );
+        assert_eq!(
+            content[2]["source"],
+            json!({"type": "url", "url": "https://cdn.example.com/a.png"})
+        );
+
+        // Without vision the images are described in the text block.
+        let body = request_body(&messages, &[], &GenerateParams::default(), false);
+        let content = body["messages"][0]["content"].as_array().unwrap();
+        assert_eq!(content.len(), 1);
+        let text = content[0]["text"].as_str().unwrap();
+        assert!(text.contains("[Image attachment (image/png) omitted"));
+    }
+}
@@ -1,40 +1,118 @@
 use async_trait::async_trait;
 use bizclaw_core::config::BizClawConfig;
 use bizclaw_core::error::{BizClawError, Result};
-use bizclaw_core::traits::provider::{GenerateParams, Provider};
+use bizclaw_core::traits::provider::{GenerateParams, Provider, ProviderCapabilities};
 use bizclaw_core::types::{Message, ModelInfo, ProviderResponse, Role, ToolDefinition};
-use tokio::sync::Mutex;
+use bizclaw_brain::cache::{CacheLimits, ModelCache};
+use bizclaw_brain::scheduler::Scheduler;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
+/// Local GGUF inference. Each engine lives on a scheduler thread so
+/// concurrent chats are decoded round-robin instead of waiting for each other.
+/// Loaded models are shared process-wide through an LRU cache
+/// (`brain.max_loaded_models`, `brain.max_loaded_mb`, `brain.idle_unload_secs`),
+/// so agents on the same model and sampling settings share one copy and an
+/// evicted model is reloaded on its next request.
 pub struct BrainProvider {
-    engine: Mutex<bizclaw_brain::BrainEngine>,
+    /// `None` when no model could be loaded.
+    model: Option<BrainModel>,
+    model_info: Option<String>,
+    context_length: u32,
+}
+
+/// A model and its scheduler thread.
+struct LoadedModel {
+    scheduler: Scheduler,
+    info: Option<String>,
+}
+
+/// What is needed to (re)load this provider's model.
+#[derive(Clone)]
+struct BrainModel {
+    path: PathBuf,
+    bytes: u64,
+    engine_config: bizclaw_brain::BrainConfig,
+    max_in_flight: usize,
+    cache: &'static ModelCache<LoadedModel>,
+}
+
+impl BrainModel {
+    /// The cached model, loading it if it is not (or no longer) loaded.
+    fn get(&self) -> Result<Arc<LoadedModel>> {
+        self.cache.get_or_load(&self.cache_key(), self.bytes, || {
+            let mut engine = bizclaw_brain::BrainEngine::new(self.engine_config.clone());
+            engine.load_model(&self.path)?;
+            Ok(LoadedModel {
+                info: engine.model_info(),
+                scheduler: Scheduler::spawn(engine, self.max_in_flight),
+            })
+        })
+    }
+
+    /// The engine samples with the settings it was loaded with, so agents
+    /// share a model only when those settings match too.
+    fn cache_key(&self) -> String {
+        let c = &self.engine_config;
+        format!(
+            "{} (ctx {}, temperature {}, top_p {}, json {}, seed {:?})",
+            self.path.display(),
+            c.context_length,
+            c.temperature,
+            c.top_p,
+            c.json_mode,
+            c.seed
+        )
+    }
+}
+
+static MODELS: OnceLock<ModelCache<LoadedModel>> = OnceLock::new();
+
+/// The process-wide model cache. Limits are fixed by the first
+/// configuration that creates it.
+fn model_cache(config: &BizClawConfig) -> &'static ModelCache<LoadedModel> {
+    MODELS.get_or_init(|| {
+        ModelCache::new(CacheLimits {
+            max_models: config.brain.max_loaded_models,
+            max_bytes: config.brain.max_loaded_mb.saturating_mul(1024 * 1024),
+            idle_ttl: (config.brain.idle_unload_secs > 0)
+                .then(|| Duration::from_secs(config.brain.idle_unload_secs)),
+        })
+    })
 }
 
 impl BrainProvider {
     pub fn new(config: &BizClawConfig) -> Result<Self> {
-        let brain_config = bizclaw_brain::BrainConfig {
+        let engine_config = bizclaw_brain::BrainConfig {
             threads: config.brain.threads,
             max_tokens: config.brain.max_tokens,
             context_length: config.brain.context_length,
             temperature: config.brain.temperature,
             top_p: config.brain.top_p,
             json_mode: config.brain.json_mode,
+            seed: config.brain.seed,
+            warm_up: config.brain.warm_up,
         };
 
-        let mut engine = bizclaw_brain::BrainEngine::new(brain_config);
-
         // Try to load model from configured path
-        let model_dir = BizClawConfig::home_dir().join("models");
-        let model_path = if !config.brain.model_path.is_empty() {
-            std::path::PathBuf::from(&config.brain.model_path)
-        } else {
-            // Auto-detect: find first .gguf file in models directory
-            find_gguf_model(&model_dir).unwrap_or_else(|| model_dir.join("model.gguf"))
-        };
+        let model_path = model_path(config);
 
+        let mut model = None;
+        let mut model_info = None;
         if model_path.exists() {
-            match engine.load_model(&model_path) {
-                Ok(()) => {
-                    tracing::info!("Brain provider: model loaded from {}", model_path.display())
+            let candidate = BrainModel {
+                bytes: std::fs::metadata(&model_path).map(|m| m.len()).unwrap_or(0),
+                path: model_path.clone(),
+                engine_config,
+                max_in_flight: config.brain.max_in_flight as usize,
+                cache: model_cache(config),
+            };
+            match candidate.get() {
+                Ok(loaded) => {
+                    tracing::info!("Brain provider: model loaded from {}", model_path.display());
+                    model_info = loaded.info.clone();
+                    model = Some(candidate);
                 }
                 Err(e) => tracing::warn!("Brain provider: failed to load model: {e}"),
             }
@@ -46,9 +124,83 @@ impl BrainProvider {
         }
 
         Ok(Self {
-            engine: Mutex::new(engine),
+            model,
+            model_info,
+            context_length: config.brain.context_length,
         })
     }
+
+    /// Whether a model was found and loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.model.is_some()
+    }
+}
+
+/// Model file the brain provider loads: `brain.model_path`, else the first
+/// `.gguf` file in `~/.bizclaw/models`.
+pub fn model_path(config: &BizClawConfig) -> PathBuf {
+    if !config.brain.model_path.is_empty() {
+        return PathBuf::from(&config.brain.model_path);
+    }
+    let model_dir = BizClawConfig::home_dir().join("models");
+    find_gguf_model(&model_dir).unwrap_or_else(|| model_dir.join("model.gguf"))
+}
+
+/// Whether the brain provider has a model to serve with, checked when
+/// `bizclaw serve` starts so a missing model is reported once, up front,
+/// instead of failing every request.
+#[derive(Debug, PartialEq)]
+pub enum BrainCheck {
+    /// The configured provider is not the brain.
+    NotUsed,
+    /// A GGUF model is in place.
+    Ready(PathBuf),
+    /// No loadable model at `path`.
+    Missing { path: PathBuf, reason: String },
+}
+
+impl BrainCheck {
+    /// Check the model the brain provider would load, if it is the
+    /// configured provider.
+    pub fn run(config: &BizClawConfig) -> Self {
+        let provider = if !config.llm.provider.is_empty() {
+            config.llm.provider.as_str()
+        } else {
+            config.default_provider.as_str()
+        };
+        if provider != "brain" {
+            return Self::NotUsed;
+        }
+        let path = model_path(config);
+        match read_gguf_header(&path) {
+            Ok(()) => Self::Ready(path),
+            Err(reason) => Self::Missing { path, reason },
+        }
+    }
+
+    /// What to tell the user when the model is missing.
+    pub fn guidance(&self) -> Option<String> {
+        let Self::Missing { path, reason } = self else {
+            return None;
+        };
+        Some(format!(
+            "Brain provider has no usable model: {reason} ({}).\n   \
+             Run: bizclaw brain download tinyllama-1.1b\n   \
+             (see `bizclaw brain list`), or set brain.model_path in config.",
+            path.display()
+        ))
+    }
+}
+
+/// Check that `path` is a readable GGUF file, without loading its weights.
+fn read_gguf_header(path: &std::path::Path) -> std::result::Result<(), String> {
+    let file = std::fs::File::open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => "model file not found".to_string(),
+        _ => format!("cannot open model file: {e}"),
+    })?;
+    bizclaw_brain::gguf::GgufFile::parse(&mut std::io::BufReader::new(file))
+        .map(|_| ())
+        .map_err(|e| format!("not a valid GGUF model: {e}"))
 }
 
 /// Find the first .gguf file in a directory.
@@ -82,11 +234,15 @@ impl Provider for BrainProvider {
         _tools: &[ToolDefinition],
         params: &GenerateParams,
     ) -> Result<ProviderResponse> {
-        if !self.engine.lock().await.is_loaded() {
+        let Some(model) = self.model.clone() else {
             return Err(BizClawError::Brain(
                 "No model loaded. Place a .gguf file in ~/.bizclaw/models/ or set brain.model_path in config.".into()
             ));
-        }
+        };
+        // Reloading an evicted model reads the file; keep it off the runtime.
+        let loaded = tokio::task::spawn_blocking(move || model.get())
+            .await
+            .map_err(|e| BizClawError::Brain(format!("Model load task failed: {e}")))??;
 
         // Format messages into a chat prompt (Llama-style)
         let prompt = format_chat_prompt(messages);
@@ -97,14 +253,14 @@ impl Provider for BrainProvider {
             256
         };
 
-        let response = self.engine.lock().await.generate(&prompt, max_tokens)?;
-        Ok(ProviderResponse::text(response))
+        let response = loaded.scheduler.generate(&prompt, max_tokens, params.seed).await?;
+        Ok(ProviderResponse::text(cut_at_stop(response, &params.stop)))
     }
 
     async fn list_models(&self) -> Result<Vec<ModelInfo>> {
         let mut models = vec![];
 
-        if let Some(info) = self.engine.lock().await.model_info() {
+        if let Some(info) = self.model_info.clone() {
             models.push(ModelInfo {
                 id: "local-model".into(),
                 name: info,
@@ -156,8 +312,34 @@ impl Provider for BrainProvider {
     }
 
     async fn health_check(&self) -> Result<bool> {
-        Ok(self.engine.lock().await.is_loaded())
+        Ok(self.model.is_some())
     }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        // The local engine ignores tool definitions and images, and returns
+        // the whole completion at once.
+        ProviderCapabilities {
+            supports_streaming: false,
+            supports_tools: false,
+            supports_vision: false,
+            supports_json: false,
+            max_context: self.context_length,
+        }
+    }
+}
+
+/// The engine has no stop sequences of its own, so cut the completion at the
+/// earliest one instead.
+fn cut_at_stop(mut text: String, stop: &[String]) -> String {
+    let end = stop
+        .iter()
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| text.find(s.as_str()))
+        .min();
+    if let Some(end) = end {
+        text.truncate(end);
+    }
+    text
 }
 
 /// Format messages into a LLaMA-style chat prompt.
@@ -165,21 +347,115 @@ fn format_chat_prompt(messages: &[Message]) -> String {
     let mut prompt = String::new();
 
     for msg in messages {
+        // The local brain is text-only: images become short notes.
+        let content = msg.content_with_image_notes();
         match msg.role {
             Role::System => {
-                prompt.push_str(&format!("[INST] <<SYS>>\n{}\n<</SYS>>\n\n", msg.content));
+                prompt.push_str(&format!("[INST] <<SYS>>\n{content}\n<</SYS>>\n\n"));
             }
             Role::User => {
-                prompt.push_str(&format!("{} [/INST]", msg.content));
+                prompt.push_str(&format!("{content} [/INST]"));
             }
             Role::Assistant => {
-                prompt.push_str(&format!(" {} </s><s>[INST] ", msg.content));
+                prompt.push_str(&format!(" {content} </s><s>[INST] "));
             }
             Role::Tool => {
-                prompt.push_str(&format!("Tool result: {} [/INST]", msg.content));
+                prompt.push_str(&format!("Tool result: {content} [/INST]"));
             }
         }
     }
 
     prompt
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brain_capabilities() {
+        let mut config = BizClawConfig::default();
+        config.brain.model_path = "/nonexistent/model.gguf".into();
+        config.brain.context_length = 2048;
+        let caps = BrainProvider::new(&config).unwrap().capabilities();
+        assert!(!caps.supports_tools);
+        assert!(!caps.supports_vision);
+        assert_eq!(caps.max_context, 2048);
+    }
+
+    fn brain_config(model_path: &std::path::Path) -> BizClawConfig {
+        let mut config = BizClawConfig::default();
+        config.llm.provider = "brain".into();
+        config.brain.model_path = model_path.to_string_lossy().into_owned();
+        config
+    }
+
+    #[test]
+    fn test_cache_key_includes_sampling_settings() {
+        let model = |temperature: f32, seed: Option<u64>| BrainModel {
+            path: PathBuf::from("/models/tiny.gguf"),
+            bytes: 1,
+            engine_config: bizclaw_brain::BrainConfig {
+                temperature,
+                seed,
+                ..Default::default()
+            },
+            max_in_flight: 1,
+            cache: model_cache(&BizClawConfig::default()),
+        };
+        assert_eq!(model(0.7, None).cache_key(), model(0.7, None).cache_key());
+        assert_ne!(model(0.7, None).cache_key(), model(0.0, None).cache_key());
+        assert_ne!(model(0.7, None).cache_key(), model(0.7, Some(42)).cache_key());
+    }
+
+    #[test]
+    fn test_brain_check_with_model_present() {
+        let dir = std::env::temp_dir().join(format!("bizclaw-brain-check-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("tiny.gguf");
+        // Header only: magic, version 3, no tensors, no metadata.
+        let mut header = b"GGUF".to_vec();
+        header.extend_from_slice(&3u32.to_le_bytes());
+        header.extend_from_slice(&0u64.to_le_bytes());
+        header.extend_from_slice(&0u64.to_le_bytes());
+        std::fs::write(&path, header).unwrap();
+
+        let check = BrainCheck::run(&brain_config(&path));
+        assert_eq!(check, BrainCheck::Ready(path));
+        assert!(check.guidance().is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_brain_check_with_model_missing() {
+        let path = std::path::Path::new("/nonexistent/model.gguf");
+        let check = BrainCheck::run(&brain_config(path));
+        assert!(matches!(check, BrainCheck::Missing { .. }), "{check:?}");
+        let guidance = check.guidance().unwrap();
+        assert!(guidance.contains("bizclaw brain download"), "{guidance}");
+        assert!(guidance.contains("/nonexistent/model.gguf"), "{guidance}");
+
+        // Not a GGUF file.
+        let path = std::env::temp_dir().join(format!("bizclaw-not-gguf-{}", std::process::id()));
+        std::fs::write(&path, "not a model").unwrap();
+        let check = BrainCheck::run(&brain_config(&path));
+        let _ = std::fs::remove_file(&path);
+        assert!(
+            matches!(&check, BrainCheck::Missing { reason, .. } if reason.contains("GGUF")),
+            "{check:?}"
+        );
+
+        // Only checked when the brain is the configured provider.
+        let mut config = brain_config(std::path::Path::new("/nonexistent/model.gguf"));
+        config.llm.provider = "openai".into();
+        assert_eq!(BrainCheck::run(&config), BrainCheck::NotUsed);
+    }
+
+    #[test]
+    fn test_cut_at_stop() {
+        let stop = vec!["User:".to_string(), "END".to_string()];
+        assert_eq!(cut_at_stop("Hi there END User: more".into(), &stop), "Hi there ");
+        assert_eq!(cut_at_stop("no stops here".into(), &stop), "no stops here");
+        assert_eq!(cut_at_stop("keep".into(), &[String::new()]), "keep");
+    }
+}
@@ -0,0 +1,378 @@
+//! Circuit breaker — stop calling a provider that keeps failing.
+//!
+//! After `failure_threshold` consecutive failures the breaker opens and calls
+//! fail immediately, without touching the network, for the cooldown. Then it
+//! half-opens: one probe call goes through, and its result closes or re-opens
+//! the breaker. Breakers created with [`CircuitBreaker::shared`] are
+//! process-wide per provider name, so every agent sees the same state and
+//! [`states`] can report them.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use bizclaw_core::config::CircuitBreakerConfig;
+use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::traits::provider::{GenerateParams, OnText, Provider, ProviderCapabilities};
+use bizclaw_core::types::{Message, ModelInfo, ProviderResponse, ToolDefinition};
+
+/// Where a breaker is in its cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Calls go through.
+    Closed,
+    /// Calls fail fast until the cooldown ends.
+    Open,
+    /// The cooldown ended; one probe call decides the next state.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: BreakerState,
+    failures: u32,
+    opened_at: Instant,
+    /// When the in-flight half-open probe started.
+    probe_started: Option<Instant>,
+}
+
+/// Consecutive-failure circuit breaker for one provider.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<Inner>,
+}
+
+/// Snapshot of one shared breaker, for metrics.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BreakerStatus {
+    pub provider: String,
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+}
+
+static BREAKERS: OnceLock<Mutex<HashMap<String, Arc<CircuitBreaker>>>> = OnceLock::new();
+
+/// State of every shared breaker, sorted by provider name.
+pub fn states() -> Vec<BreakerStatus> {
+    let Some(breakers) = BREAKERS.get() else {
+        return Vec::new();
+    };
+    let breakers = breakers.lock().unwrap_or_else(|p| p.into_inner());
+    let mut states: Vec<_> = breakers
+        .iter()
+        .map(|(provider, breaker)| BreakerStatus {
+            provider: provider.clone(),
+            state: breaker.state(),
+            consecutive_failures: breaker.consecutive_failures(),
+        })
+        .collect();
+    states.sort_by(|a, b| a.provider.cmp(&b.provider));
+    states
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            inner: Mutex::new(Inner {
+                state: BreakerState::Closed,
+                failures: 0,
+                opened_at: Instant::now(),
+                probe_started: None,
+            }),
+        }
+    }
+
+    pub fn from_config(config: &CircuitBreakerConfig) -> Self {
+        Self::new(
+            config.failure_threshold,
+            Duration::from_secs(config.cooldown_secs),
+        )
+    }
+
+    /// The process-wide breaker for `provider_name`. Settings are fixed by the
+    /// first configuration that creates it.
+    pub fn shared(config: &CircuitBreakerConfig, provider_name: &str) -> Arc<Self> {
+        BREAKERS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .entry(provider_name.to_string())
+            .or_insert_with(|| Arc::new(Self::from_config(config)))
+            .clone()
+    }
+
+    /// Whether a call may go out now. Moves an open breaker whose cooldown
+    /// has passed to half-open and admits one probe.
+    pub fn allow(&self) -> bool {
+        let mut inner = self.lock();
+        match inner.state {
+            BreakerState::Closed => true,
+            BreakerState::Open if inner.opened_at.elapsed() < self.cooldown => false,
+            BreakerState::Open => {
+                inner.state = BreakerState::HalfOpen;
+                inner.probe_started = Some(Instant::now());
+                true
+            }
+            BreakerState::HalfOpen => {
+                // A probe whose caller went away never reports back; allow
+                // another once it has been out for a full cooldown.
+                let stale = inner
+                    .probe_started
+                    .is_none_or(|t| t.elapsed() >= self.cooldown);
+                if stale {
+                    inner.probe_started = Some(Instant::now());
+                }
+                stale
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.lock();
+        inner.state = BreakerState::Closed;
+        inner.failures = 0;
+        inner.probe_started = None;
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.lock();
+        inner.failures = inner.failures.saturating_add(1);
+        inner.probe_started = None;
+        if inner.state == BreakerState::HalfOpen || inner.failures >= self.threshold {
+            inner.state = BreakerState::Open;
+            inner.opened_at = Instant::now();
+        }
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.lock().state
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.lock().failures
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+/// Whether `error` says the provider is unhealthy, as opposed to the request
+/// being bad. 4xx responses other than 408/429 are the caller's problem and
+/// must not open the breaker.
+pub fn is_provider_failure(error: &BizClawError) -> bool {
+    match error {
+        BizClawError::Http(_) | BizClawError::Timeout(_) => true,
+        BizClawError::Provider(msg) => match http_status(msg) {
+            Some(status) => !(400..500).contains(&status) || status == 408 || status == 429,
+            None => true,
+        },
+        _ => false,
+    }
+}
+
+/// Status code from a "<provider> API error <code> …" message.
+fn http_status(msg: &str) -> Option<u16> {
+    let rest = &msg[msg.find("API error ")? + "API error ".len()..];
+    rest.get(..3)?.parse().ok()
+}
+
+/// A provider whose calls pass through a [`CircuitBreaker`].
+pub struct BreakerProvider {
+    inner: Box<dyn Provider>,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl BreakerProvider {
+    pub fn new(inner: Box<dyn Provider>, breaker: Arc<CircuitBreaker>) -> Self {
+        Self { inner, breaker }
+    }
+
+    fn admit(&self) -> Result<()> {
+        if self.breaker.allow() {
+            Ok(())
+        } else {
+            Err(BizClawError::Provider(format!(
+                "{} circuit open after {} consecutive failures",
+                self.inner.name(),
+                self.breaker.consecutive_failures()
+            )))
+        }
+    }
+
+    fn record<T>(&self, result: Result<T>) -> Result<T> {
+        match &result {
+            Ok(_) => self.breaker.record_success(),
+            Err(e) if is_provider_failure(e) => {
+                self.breaker.record_failure();
+                if self.breaker.state() == BreakerState::Open {
+                    tracing::warn!("🔌 Circuit open for {}: {e}", self.inner.name());
+                }
+            }
+            // The provider answered; only the request was rejected.
+            Err(_) => self.breaker.record_success(),
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl Provider for BreakerProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn chat(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        params: &GenerateParams,
+    ) -> Result<ProviderResponse> {
+        self.admit()?;
+        self.record(self.inner.chat(messages, tools, params).await)
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        params: &GenerateParams,
+        on_text: &OnText<'_>,
+    ) -> Result<ProviderResponse> {
+        self.admit()?;
+        self.record(
+            self.inner
+                .chat_stream(messages, tools, params, on_text)
+                .await,
+        )
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.inner.list_models().await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        self.inner.health_check().await
+    }
+
+    fn supports_json_mode(&self) -> bool {
+        self.inner.supports_json_mode()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Fails while `failing` is set; counts calls that reach it.
+    #[derive(Default)]
+    struct Counters {
+        failing: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    struct FlakyProvider(Arc<Counters>);
+
+    #[async_trait]
+    impl Provider for FlakyProvider {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            self.0.calls.fetch_add(1, Ordering::SeqCst);
+            if self.0.failing.load(Ordering::SeqCst) {
+                return Err(BizClawError::Provider(
+                    "flaky API error 503 Service Unavailable: down".into(),
+                ));
+            }
+            Ok(ProviderResponse::text("ok"))
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    #[test]
+    fn test_failures_open_and_success_closes() {
+        let breaker = CircuitBreaker::new(3, Duration::ZERO);
+        breaker.record_failure();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        // Zero cooldown: the next call is the half-open probe. A failed
+        // probe re-opens the breaker at once.
+        assert!(breaker.allow());
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        assert!(breaker.allow());
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert_eq!(breaker.consecutive_failures(), 0);
+    }
+
+    #[test]
+    fn test_client_errors_do_not_count() {
+        let api_error = |m: &str| BizClawError::Provider(m.into());
+        assert!(is_provider_failure(&api_error(
+            "openai API error 502 Bad Gateway: x"
+        )));
+        assert!(is_provider_failure(&api_error(
+            "openai API error 429 Too Many Requests: x"
+        )));
+        assert!(!is_provider_failure(&api_error(
+            "openai API error 400 Bad Request: context_length_exceeded"
+        )));
+        assert!(is_provider_failure(&BizClawError::Http("refused".into())));
+        assert!(!is_provider_failure(&BizClawError::Tool("x".into())));
+    }
+
+    #[tokio::test]
+    async fn test_open_breaker_fails_fast() {
+        let counters = Arc::new(Counters::default());
+        counters.failing.store(true, Ordering::SeqCst);
+        let breaker = Arc::new(CircuitBreaker::new(3, Duration::from_millis(50)));
+        let provider =
+            BreakerProvider::new(Box::new(FlakyProvider(counters.clone())), breaker.clone());
+        let params = GenerateParams::default();
+
+        for _ in 0..5 {
+            assert!(provider.chat(&[], &[], &params).await.is_err());
+        }
+        // The last two calls were rejected without reaching the provider.
+        assert_eq!(counters.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        // After the cooldown a successful probe closes it.
+        counters.failing.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(provider.chat(&[], &[], &params).await.is_ok());
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert_eq!(counters.calls.load(Ordering::SeqCst), 4);
+    }
+}
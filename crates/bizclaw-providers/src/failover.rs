@@ -1,65 +1,57 @@
 //! Provider Failover — automatic fallback when primary provider fails.
 //!
 //! Lightweight failover chain: try primary → fallback₁ → fallback₂.
-//! No heavyweight circuit breaker, no thread pools.
-//! RAM: ~100 bytes per provider entry.
+//! Each slot has a [`CircuitBreaker`] set up from `[llm.circuit_breaker]`;
+//! a slot whose breaker is open is skipped at once, as is one wrapping an
+//! open [`BreakerProvider`].
+//!
+//! [`BreakerProvider`]: crate::circuit_breaker::BreakerProvider
 
 use async_trait::async_trait;
+use bizclaw_core::config::CircuitBreakerConfig;
 use bizclaw_core::error::{BizClawError, Result};
-use bizclaw_core::traits::provider::{GenerateParams, Provider};
+use bizclaw_core::traits::provider::{GenerateParams, OnText, Provider, ProviderCapabilities};
 use bizclaw_core::types::{Message, ModelInfo, ProviderResponse, ToolDefinition};
-use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::circuit_breaker::{BreakerState, CircuitBreaker, is_provider_failure};
 
-/// Per-provider health tracking (64 bytes).
+/// A provider in the chain and its breaker.
 struct ProviderSlot {
     provider: Box<dyn Provider>,
-    /// Consecutive failure count.
-    failures: AtomicU32,
-    /// Timestamp of last failure (unix secs, 0 = never failed).
-    last_failure: AtomicU64,
-    /// Max failures before skip (default: 3).
-    max_failures: u32,
-    /// Cool-down period in seconds before retrying a failed provider.
-    cooldown_secs: u64,
+    breaker: CircuitBreaker,
 }
 
 impl ProviderSlot {
-    fn new(provider: Box<dyn Provider>) -> Self {
+    fn new(provider: Box<dyn Provider>, config: &CircuitBreakerConfig) -> Self {
+        // A threshold of 0 disables the breaker: it never opens.
+        let threshold = match config.failure_threshold {
+            0 => u32::MAX,
+            n => n,
+        };
         Self {
             provider,
-            failures: AtomicU32::new(0),
-            last_failure: AtomicU64::new(0),
-            max_failures: 3,
-            cooldown_secs: 60,
+            breaker: CircuitBreaker::new(threshold, Duration::from_secs(config.cooldown_secs)),
         }
     }
 
-    /// Check if this provider is healthy (below failure threshold or cooldown expired).
+    /// Whether this slot is worth trying (its breaker is not open).
     fn is_healthy(&self) -> bool {
-        let fails = self.failures.load(Ordering::Relaxed);
-        if fails < self.max_failures {
-            return true;
-        }
-        // Check cooldown
-        let last = self.last_failure.load(Ordering::Relaxed);
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-        now.saturating_sub(last) > self.cooldown_secs
+        self.breaker.state() != BreakerState::Open
     }
 
     fn record_success(&self) {
-        self.failures.store(0, Ordering::Relaxed);
+        self.breaker.record_success();
     }
 
-    fn record_failure(&self) {
-        self.failures.fetch_add(1, Ordering::Relaxed);
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-        self.last_failure.store(now, Ordering::Relaxed);
+    fn record_failure(&self, error: &BizClawError) {
+        if is_provider_failure(error) {
+            self.breaker.record_failure();
+        } else {
+            // The provider answered; only the request was rejected.
+            self.breaker.record_success();
+        }
     }
 }
 
@@ -71,16 +63,23 @@ pub struct FailoverProvider {
 impl FailoverProvider {
     /// Create a failover chain from a list of providers.
     /// First provider is primary, rest are fallbacks.
-    pub fn new(providers: Vec<Box<dyn Provider>>) -> Self {
+    pub fn new(providers: Vec<Box<dyn Provider>>, breaker: &CircuitBreakerConfig) -> Self {
         assert!(!providers.is_empty(), "Need at least one provider");
         Self {
-            slots: providers.into_iter().map(ProviderSlot::new).collect(),
+            slots: providers
+                .into_iter()
+                .map(|provider| ProviderSlot::new(provider, breaker))
+                .collect(),
         }
     }
 
     /// Create from a primary + single fallback.
-    pub fn with_fallback(primary: Box<dyn Provider>, fallback: Box<dyn Provider>) -> Self {
-        Self::new(vec![primary, fallback])
+    pub fn with_fallback(
+        primary: Box<dyn Provider>,
+        fallback: Box<dyn Provider>,
+        breaker: &CircuitBreakerConfig,
+    ) -> Self {
+        Self::new(vec![primary, fallback], breaker)
     }
 
     /// Number of providers in the chain.
@@ -96,7 +95,7 @@ impl FailoverProvider {
                 (
                     s.provider.name(),
                     s.is_healthy(),
-                    s.failures.load(Ordering::Relaxed),
+                    s.breaker.consecutive_failures(),
                 )
             })
             .collect()
@@ -122,11 +121,11 @@ impl Provider for FailoverProvider {
         let mut last_error = None;
 
         for (idx, slot) in self.slots.iter().enumerate() {
-            if !slot.is_healthy() {
+            if !slot.breaker.allow() {
                 tracing::debug!(
                     "⏭️ Skipping unhealthy provider: {} ({} failures)",
                     slot.provider.name(),
-                    slot.failures.load(Ordering::Relaxed)
+                    slot.breaker.consecutive_failures()
                 );
                 continue;
             }
@@ -144,11 +143,11 @@ impl Provider for FailoverProvider {
                     return Ok(response);
                 }
                 Err(e) => {
-                    slot.record_failure();
+                    slot.record_failure(&e);
                     tracing::warn!(
                         "⚠️ Provider {} failed (attempt {}): {}",
                         slot.provider.name(),
-                        slot.failures.load(Ordering::Relaxed),
+                        slot.breaker.consecutive_failures(),
                         e
                     );
                     last_error = Some(e);
@@ -161,6 +160,48 @@ impl Provider for FailoverProvider {
         }))
     }
 
+    /// Like [`Provider::chat`], but once a slot has streamed text to the
+    /// caller its error is returned as-is: failing over would repeat output.
+    async fn chat_stream(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        params: &GenerateParams,
+        on_text: &OnText<'_>,
+    ) -> Result<ProviderResponse> {
+        let mut last_error = None;
+
+        for slot in self.slots.iter().filter(|s| s.breaker.allow()) {
+            let streamed = AtomicBool::new(false);
+            let forward = |text: &str| {
+                streamed.store(true, Ordering::Relaxed);
+                on_text(text);
+            };
+            match slot.provider.chat_stream(messages, tools, params, &forward).await {
+                Ok(response) => {
+                    slot.record_success();
+                    return Ok(response);
+                }
+                Err(e) => {
+                    slot.record_failure(&e);
+                    tracing::warn!(
+                        "⚠️ Provider {} failed (streaming): {}",
+                        slot.provider.name(),
+                        e
+                    );
+                    if streamed.load(Ordering::Relaxed) {
+                        return Err(e);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            BizClawError::Provider("All providers unhealthy".into())
+        }))
+    }
+
     async fn list_models(&self) -> Result<Vec<ModelInfo>> {
         // Aggregate models from all healthy providers
         let mut all = Vec::new();
@@ -183,11 +224,32 @@ impl Provider for FailoverProvider {
         }
         Ok(false)
     }
+
+    fn supports_json_mode(&self) -> bool {
+        // Any slot may answer, so native JSON mode needs every one of them.
+        self.slots.iter().all(|s| s.provider.supports_json_mode())
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        // Only advertise what every provider in the chain can do.
+        self.slots
+            .iter()
+            .map(|s| s.provider.capabilities())
+            .reduce(|a, b| ProviderCapabilities {
+                supports_streaming: a.supports_streaming && b.supports_streaming,
+                supports_tools: a.supports_tools && b.supports_tools,
+                supports_vision: a.supports_vision && b.supports_vision,
+                supports_json: a.supports_json && b.supports_json,
+                max_context: a.max_context.min(b.max_context),
+            })
+            .unwrap_or_default()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicU32, AtomicU64};
 
     #[test]
     fn test_health_tracking() {
@@ -225,4 +287,97 @@ mod tests {
         failures.store(0, Ordering::Relaxed); // success reset
         assert!(is_healthy()); // back to 0
     }
+
+    /// Always fails with a 503, counting the calls that reach it.
+    struct DownProvider(std::sync::Arc<AtomicU32>);
+
+    #[async_trait]
+    impl Provider for DownProvider {
+        fn name(&self) -> &str {
+            "down"
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Err(BizClawError::Provider("down API error 503 Service Unavailable: x".into()))
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(false)
+        }
+    }
+
+    struct UpProvider;
+
+    #[async_trait]
+    impl Provider for UpProvider {
+        fn name(&self) -> &str {
+            "up"
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            Ok(ProviderResponse::text("ok"))
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_open_breaker_skips_to_fallback() {
+        let calls = std::sync::Arc::new(AtomicU32::new(0));
+        let breaker = CircuitBreakerConfig {
+            failure_threshold: 3,
+            cooldown_secs: 60,
+        };
+        let chain = FailoverProvider::with_fallback(
+            Box::new(DownProvider(calls.clone())),
+            Box::new(UpProvider),
+            &breaker,
+        );
+        let params = GenerateParams::default();
+        for _ in 0..5 {
+            assert!(chain.chat(&[], &[], &params).await.is_ok());
+        }
+        // The primary's breaker opened after the configured 3 failures;
+        // later calls went straight to the fallback.
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(chain.health_status()[0], ("down", false, 3));
+
+        // With the breaker disabled every call tries the primary first.
+        calls.store(0, Ordering::SeqCst);
+        let disabled = CircuitBreakerConfig {
+            failure_threshold: 0,
+            ..breaker
+        };
+        let chain = FailoverProvider::with_fallback(
+            Box::new(DownProvider(calls.clone())),
+            Box::new(UpProvider),
+            &disabled,
+        );
+        for _ in 0..5 {
+            assert!(chain.chat(&[], &[], &params).await.is_ok());
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+        assert!(chain.health_status()[0].1);
+    }
 }
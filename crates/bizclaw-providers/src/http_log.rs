@@ -0,0 +1,135 @@
+//! Debug logging of provider HTTP calls, with secrets redacted.
+//!
+//! Auth headers are always replaced by [`REDACTED`]. Request and response
+//! bodies carry user content, so they are only logged when
+//! `llm.log_request_bodies` is on; otherwise just their size is.
+
+/// Placeholder for redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Headers that carry credentials (lowercase).
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "api-key",
+    "x-api-key",
+    "x-goog-api-key",
+    "cookie",
+    "set-cookie",
+];
+
+/// Logs provider requests and responses at debug level.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HttpLog {
+    log_bodies: bool,
+}
+
+impl HttpLog {
+    pub fn new(log_bodies: bool) -> Self {
+        Self { log_bodies }
+    }
+
+    /// Log an outgoing request.
+    pub fn request(&self, provider: &str, request: &reqwest::Request) {
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            tracing::debug!("[{provider}] → {}", self.describe_request(request));
+        }
+    }
+
+    /// Log a response's status and headers.
+    pub fn response(&self, provider: &str, response: &reqwest::Response) {
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            tracing::debug!(
+                "[{provider}] ← {} {}",
+                response.status(),
+                redact_headers(response.headers())
+            );
+        }
+    }
+
+    /// Log a response body that has been read.
+    pub fn response_body(&self, provider: &str, body: &str) {
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            tracing::debug!("[{provider}] ← body: {}", self.body(body.as_bytes()));
+        }
+    }
+
+    /// One-line description of `request`: method, URL, redacted headers and
+    /// the body (or its size, unless bodies are logged).
+    pub fn describe_request(&self, request: &reqwest::Request) -> String {
+        let body = match request.body().and_then(|b| b.as_bytes()) {
+            Some(bytes) => self.body(bytes),
+            None => "none".into(),
+        };
+        format!(
+            "{} {} {} body: {body}",
+            request.method(),
+            request.url(),
+            redact_headers(request.headers())
+        )
+    }
+
+    fn body(&self, bytes: &[u8]) -> String {
+        if self.log_bodies {
+            String::from_utf8_lossy(bytes).into_owned()
+        } else {
+            format!("<omitted, {} bytes>", bytes.len())
+        }
+    }
+}
+
+/// `{name: value, …}` with credential headers replaced by [`REDACTED`].
+pub fn redact_headers(headers: &reqwest::header::HeaderMap) -> String {
+    let pairs: Vec<String> = headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SENSITIVE_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            format!("{name}: {value}")
+        })
+        .collect();
+    format!("{{{}}}", pairs.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chat_request() -> reqwest::Request {
+        reqwest::Client::new()
+            .post("https://api.openai.com/v1/chat/completions")
+            .header("Authorization", "Bearer sk-live-secret")
+            .header("x-api-key", "sk-ant-secret")
+            .json(&serde_json::json!({
+                "messages": [{"role": "user", "content": "my card is 4111"}]
+            }))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_auth_headers_redacted_and_body_omitted_by_default() {
+        let line = HttpLog::default().describe_request(&chat_request());
+        assert!(line.starts_with("POST https://api.openai.com/v1/chat/completions"));
+        assert!(
+            line.contains(&format!("authorization: {REDACTED}")),
+            "{line}"
+        );
+        assert!(line.contains(&format!("x-api-key: {REDACTED}")), "{line}");
+        assert!(line.contains("content-type: application/json"), "{line}");
+        assert!(!line.contains("secret"), "{line}");
+        assert!(!line.contains("4111"), "{line}");
+        assert!(line.contains("<omitted, "), "{line}");
+    }
+
+    #[test]
+    fn test_body_logged_only_when_enabled() {
+        let line = HttpLog::new(true).describe_request(&chat_request());
+        assert!(line.contains("my card is 4111"), "{line}");
+        // Headers stay redacted even with bodies on.
+        assert!(!line.contains("secret"), "{line}");
+    }
+}
@@ -0,0 +1,342 @@
+//! Structured JSON responses across providers.
+//!
+//! Providers with native JSON mode get `response_format: json_object`.
+//! Everything else gets a prompt instruction instead, and the reply is
+//! repaired (code fences, surrounding prose, trailing commas) before parsing.
+//! Either way, an unparseable reply is retried once with the parse error fed
+//! back to the model.
+
+use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::traits::provider::{GenerateParams, Provider};
+use bizclaw_core::types::{Message, Usage};
+use serde_json::Value;
+
+/// System instruction used when the provider has no native JSON mode.
+const JSON_INSTRUCTION: &str = "Respond with a single valid JSON object only. \
+Do not wrap it in markdown code fences and do not add any text before or after it.";
+
+/// How JSON output was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonStrategy {
+    /// Backend-enforced `response_format: json_object`.
+    Native,
+    /// Prompt instruction plus parse-and-repair.
+    Prompt,
+}
+
+impl JsonStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Native => "native",
+            Self::Prompt => "prompt",
+        }
+    }
+}
+
+/// A parsed JSON reply.
+#[derive(Debug, Clone)]
+pub struct JsonResponse {
+    pub value: Value,
+    pub strategy: JsonStrategy,
+    /// Number of provider calls made (1, or 2 after a retry).
+    pub attempts: u32,
+    /// Usage of the last call, if reported.
+    pub usage: Option<Usage>,
+}
+
+/// Ask `provider` for a JSON object reply, falling back to prompt-based JSON
+/// when the provider has no native JSON mode.
+pub async fn chat_json(
+    provider: &dyn Provider,
+    messages: &[Message],
+    params: &GenerateParams,
+) -> Result<JsonResponse> {
+    let strategy = if provider.supports_json_mode() {
+        JsonStrategy::Native
+    } else {
+        JsonStrategy::Prompt
+    };
+
+    let mut params = params.clone();
+    params.json_mode = strategy == JsonStrategy::Native;
+
+    let mut conversation = messages.to_vec();
+    if strategy == JsonStrategy::Prompt {
+        conversation.insert(0, Message::system(JSON_INSTRUCTION));
+    }
+
+    let mut last_error = String::new();
+    for attempt in 1..=2 {
+        let resp = provider.chat(&conversation, &[], &params).await?;
+        let text = resp.content.unwrap_or_default();
+        match parse_json_lenient(&text) {
+            Ok(value) => {
+                tracing::debug!(
+                    "🧾 JSON reply from {} via {} strategy (attempt {attempt})",
+                    provider.name(),
+                    strategy.as_str()
+                );
+                return Ok(JsonResponse {
+                    value,
+                    strategy,
+                    attempts: attempt,
+                    usage: resp.usage,
+                });
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "⚠️ {} returned invalid JSON ({} strategy, attempt {attempt}): {e}",
+                    provider.name(),
+                    strategy.as_str()
+                );
+                last_error = e;
+                conversation.push(Message::assistant(&text));
+                conversation.push(Message::user(format!(
+                    "That was not valid JSON ({last_error}). Reply again with only the corrected JSON object."
+                )));
+            }
+        }
+    }
+
+    Err(BizClawError::Provider(format!(
+        "{} did not return valid JSON after retry: {last_error}",
+        provider.name()
+    )))
+}
+
+/// Parse model output as JSON, repairing common formatting mistakes.
+pub fn parse_json_lenient(text: &str) -> std::result::Result<Value, String> {
+    let trimmed = text.trim();
+    if let Ok(v) = serde_json::from_str(trimmed) {
+        return Ok(v);
+    }
+    let candidate = extract_json_block(strip_code_fence(trimmed)).ok_or("no JSON object found")?;
+    serde_json::from_str(candidate)
+        .or_else(|_| serde_json::from_str(&remove_trailing_commas(candidate)))
+        .map_err(|e| e.to_string())
+}
+
+/// Strip a surrounding markdown code fence (```json ... ```).
+fn strip_code_fence(text: &str) -> &str {
+    let Some(start) = text.find("```") else {
+        return text;
+    };
+    let after = &text[start + 3..];
+    // Skip the language tag on the opening fence line.
+    let body = after
+        .split_once('\n')
+        .map(|(_, rest)| rest)
+        .unwrap_or(after);
+    body.rfind("```").map(|end| &body[..end]).unwrap_or(body)
+}
+
+/// Find the first balanced `{...}` or `[...]` block, ignoring brackets inside strings.
+fn extract_json_block(text: &str) -> Option<&str> {
+    let start = text.find(['{', '['])?;
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text[start..].char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[start..start + i + 1]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Drop commas that directly precede a closing `}` or `]`.
+fn remove_trailing_commas(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let chars: Vec<char> = text.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use bizclaw_core::types::{ModelInfo, ProviderResponse, Role, ToolDefinition};
+    use std::sync::Mutex;
+
+    /// Replays canned replies and records the requests it saw.
+    struct ScriptedProvider {
+        native_json: bool,
+        replies: Mutex<Vec<&'static str>>,
+        seen: Mutex<Vec<(Vec<Message>, bool)>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(native_json: bool, replies: &[&'static str]) -> Self {
+            Self {
+                native_json,
+                replies: Mutex::new(replies.iter().rev().copied().collect()),
+                seen: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn chat(
+            &self,
+            messages: &[Message],
+            _tools: &[ToolDefinition],
+            params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            self.seen
+                .lock()
+                .unwrap()
+                .push((messages.to_vec(), params.json_mode));
+            let reply = self.replies.lock().unwrap().pop().unwrap_or("");
+            Ok(ProviderResponse {
+                content: Some(reply.to_string()),
+                tool_calls: vec![],
+                finish_reason: Some("stop".into()),
+                usage: None,
+                reasoning: None,
+                refusal: None,
+                fallback: None,
+            })
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn supports_json_mode(&self) -> bool {
+            self.native_json
+        }
+    }
+
+    #[tokio::test]
+    async fn test_native_provider_uses_json_mode() {
+        let provider = ScriptedProvider::new(true, &[r#"{"city": "Hanoi"}"#]);
+        let resp = chat_json(
+            &provider,
+            &[Message::user("Where?")],
+            &GenerateParams::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.strategy, JsonStrategy::Native);
+        assert_eq!(resp.value["city"], "Hanoi");
+
+        let seen = provider.seen.lock().unwrap();
+        let (messages, json_mode) = &seen[0];
+        assert!(*json_mode);
+        assert_eq!(messages.len(), 1, "no extra instruction in native mode");
+    }
+
+    #[tokio::test]
+    async fn test_prompt_strategy_repairs_output() {
+        let provider = ScriptedProvider::new(
+            false,
+            &[
+                "Sure! Here it is:\n```json\n{\"items\": [1, 2,], \"ok\": true,}\n```\nHope that helps.",
+            ],
+        );
+        let resp = chat_json(
+            &provider,
+            &[Message::user("List")],
+            &GenerateParams::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.strategy, JsonStrategy::Prompt);
+        assert_eq!(resp.attempts, 1);
+        assert_eq!(resp.value, serde_json::json!({"items": [1, 2], "ok": true}));
+
+        let seen = provider.seen.lock().unwrap();
+        let (messages, json_mode) = &seen[0];
+        assert!(!*json_mode);
+        assert_eq!(messages[0].role, Role::System);
+        assert!(messages[0].content.contains("valid JSON"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_json_retried_once() {
+        let provider = ScriptedProvider::new(false, &["I cannot do that", r#"{"a": 1}"#]);
+        let resp = chat_json(
+            &provider,
+            &[Message::user("Go")],
+            &GenerateParams::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.attempts, 2);
+        assert_eq!(resp.value["a"], 1);
+        let retry_prompt = provider.seen.lock().unwrap()[1]
+            .0
+            .last()
+            .unwrap()
+            .content
+            .clone();
+        assert!(retry_prompt.contains("not valid JSON"));
+
+        let provider = ScriptedProvider::new(false, &["nope", "still nope"]);
+        assert!(
+            chat_json(
+                &provider,
+                &[Message::user("Go")],
+                &GenerateParams::default()
+            )
+            .await
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_json_lenient() {
+        assert!(parse_json_lenient(r#"{"a": "}"}"#).is_ok());
+        assert_eq!(
+            parse_json_lenient(r#"Result: {"s": "a, }"} done"#).unwrap()["s"],
+            "a, }"
+        );
+        assert!(parse_json_lenient("no json here").is_err());
+    }
+}
@@ -6,21 +6,81 @@
 //! Ollama, LlamaCpp, OpenRouter) are handled by a single `OpenAiCompatibleProvider`.
 //! The `BrainProvider` handles local GGUF models separately.
 
+pub mod anthropic_stream;
 pub mod brain;
+pub mod circuit_breaker;
 pub mod failover;
+pub mod http_log;
+pub mod json_mode;
+pub mod local_fallback;
+pub mod model_alias;
+pub mod model_probe;
 pub mod openai_compatible;
+pub mod provider_error;
 pub mod provider_registry;
+pub mod system_prompt;
+pub mod throttle;
+pub mod tool_format;
 
 use bizclaw_core::config::BizClawConfig;
 use bizclaw_core::error::{BizClawError, Result};
 use bizclaw_core::traits::Provider;
 
-/// Create a provider from configuration.
+/// Create a provider from configuration, wrapped in the `[LLM.limits]`
+/// concurrency and rate limits and the `[LLM.circuit_breaker]`, with the
+/// local brain behind it when `[LLM] fallback_to_brain` is set.
 ///
 /// Resolution order for provider name:
 /// 1. `config.llm.provider` (from `[LLM]` section)
 /// 2. `config.default_provider` (legacy top-level field)
 pub fn create_provider(config: &BizClawConfig) -> Result<Box<dyn Provider>> {
+    let provider = create_guarded(config)?;
+    if !config.llm.fallback_to_brain || provider.name() == "brain" {
+        return Ok(provider);
+    }
+    match brain::BrainProvider::new(config) {
+        Ok(brain) if brain.is_loaded() => Ok(Box::new(
+            local_fallback::LocalFallbackProvider::new(provider, Box::new(brain)),
+        )),
+        Ok(_) => {
+            tracing::warn!("fallback_to_brain is set but no brain model is loaded");
+            Ok(provider)
+        }
+        Err(e) => {
+            tracing::warn!("fallback_to_brain is set but the brain failed to start: {e}");
+            Ok(provider)
+        }
+    }
+}
+
+/// The configured provider behind its limits and circuit breaker.
+fn create_guarded(config: &BizClawConfig) -> Result<Box<dyn Provider>> {
+    let provider = create_unthrottled(config)?;
+    let name = provider.name().to_string();
+    let throttle = throttle::Throttle::shared(&config.llm.limits, &name);
+    let provider: Box<dyn Provider> = if throttle.is_unlimited() {
+        provider
+    } else {
+        Box::new(throttle::ThrottledProvider::new(provider, throttle))
+    };
+
+    // Outside the throttle, so an open breaker fails fast instead of queueing.
+    let breaker_config = &config.llm.circuit_breaker;
+    if breaker_config.failure_threshold == 0 {
+        return Ok(provider);
+    }
+    // Same provider at a different endpoint is a different upstream.
+    let key = if config.llm.endpoint.is_empty() {
+        name
+    } else {
+        format!("{name}@{}", config.llm.endpoint)
+    };
+    let breaker = circuit_breaker::CircuitBreaker::shared(breaker_config, &key);
+    Ok(Box::new(circuit_breaker::BreakerProvider::new(provider, breaker)))
+}
+
+/// Create the provider without the shared concurrency/rate limits.
+fn create_unthrottled(config: &BizClawConfig) -> Result<Box<dyn Provider>> {
     // Prefer [LLM] section, fallback to legacy top-level field
     let provider_name = if !config.llm.provider.is_empty() {
         config.llm.provider.as_str()
@@ -0,0 +1,245 @@
+//! Local brain fallback — answer offline when the cloud provider is unreachable.
+//!
+//! Enabled with `[LLM] fallback_to_brain = true`. Only connectivity failures
+//! fall back: the connection failed or timed out. An error response (400 for
+//! a bad request, 401 for a bad key, 5xx) means the provider was reached, and
+//! is returned as usual. So is an open circuit breaker, since 5xx and 429
+//! responses open it too.
+//!
+//! A fallback answer has [`ProviderResponse::fallback`] set to the local
+//! provider's name, so traces can tell it apart from a cloud answer.
+
+use async_trait::async_trait;
+use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::traits::provider::{GenerateParams, OnText, Provider, ProviderCapabilities};
+use bizclaw_core::types::{Message, ModelInfo, ProviderResponse, ToolDefinition};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether `error` means the provider could not be reached at all, as
+/// opposed to answering with an error.
+pub fn is_unreachable(error: &BizClawError) -> bool {
+    matches!(error, BizClawError::Http(_) | BizClawError::Timeout(_))
+}
+
+/// A cloud provider that falls back to a local one when unreachable.
+pub struct LocalFallbackProvider {
+    cloud: Box<dyn Provider>,
+    local: Box<dyn Provider>,
+}
+
+impl LocalFallbackProvider {
+    pub fn new(cloud: Box<dyn Provider>, local: Box<dyn Provider>) -> Self {
+        Self { cloud, local }
+    }
+
+    fn mark(&self, mut response: ProviderResponse) -> ProviderResponse {
+        response.fallback = Some(self.local.name().to_string());
+        response
+    }
+}
+
+#[async_trait]
+impl Provider for LocalFallbackProvider {
+    fn name(&self) -> &str {
+        self.cloud.name()
+    }
+
+    async fn chat(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        params: &GenerateParams,
+    ) -> Result<ProviderResponse> {
+        match self.cloud.chat(messages, tools, params).await {
+            Err(e) if is_unreachable(&e) => {
+                tracing::warn!(
+                    "📴 {} unreachable ({e}) — answering with the local {}",
+                    self.cloud.name(),
+                    self.local.name()
+                );
+                let response = self.local.chat(messages, tools, params).await?;
+                Ok(self.mark(response))
+            }
+            result => result,
+        }
+    }
+
+    /// Like [`Provider::chat`]; a stream that already sent text is not
+    /// restarted on the local provider.
+    async fn chat_stream(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        params: &GenerateParams,
+        on_text: &OnText<'_>,
+    ) -> Result<ProviderResponse> {
+        let streamed = AtomicBool::new(false);
+        let forward = |text: &str| {
+            streamed.store(true, Ordering::Relaxed);
+            on_text(text);
+        };
+        match self
+            .cloud
+            .chat_stream(messages, tools, params, &forward)
+            .await
+        {
+            Err(e) if is_unreachable(&e) && !streamed.load(Ordering::Relaxed) => {
+                tracing::warn!(
+                    "📴 {} unreachable ({e}) — answering with the local {}",
+                    self.cloud.name(),
+                    self.local.name()
+                );
+                let response = self
+                    .local
+                    .chat_stream(messages, tools, params, on_text)
+                    .await?;
+                Ok(self.mark(response))
+            }
+            result => result,
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.cloud.list_models().await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        // Chats still get answered while only the local provider is up.
+        if let Ok(true) = self.cloud.health_check().await {
+            return Ok(true);
+        }
+        self.local.health_check().await
+    }
+
+    fn supports_json_mode(&self) -> bool {
+        self.cloud.supports_json_mode()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.cloud.capabilities()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Fails every call with `error`.
+    struct FailingProvider(fn() -> BizClawError);
+
+    #[async_trait]
+    impl Provider for FailingProvider {
+        fn name(&self) -> &str {
+            "openai"
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            Err((self.0)())
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(false)
+        }
+    }
+
+    /// Stands in for the local brain; counts its calls.
+    struct LocalProvider(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Provider for LocalProvider {
+        fn name(&self) -> &str {
+            "brain"
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(ProviderResponse::text("Xin chào!"))
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    fn provider(
+        error: fn() -> BizClawError,
+        local_calls: &Arc<AtomicUsize>,
+    ) -> LocalFallbackProvider {
+        LocalFallbackProvider::new(
+            Box::new(FailingProvider(error)),
+            Box::new(LocalProvider(local_calls.clone())),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_network_error_falls_back_to_brain() {
+        let local_calls = Arc::new(AtomicUsize::new(0));
+        let provider = provider(
+            || {
+                BizClawError::Http(
+                    "openai connection failed (https://api.openai.com): dns error".into(),
+                )
+            },
+            &local_calls,
+        );
+        let messages = [Message::user("Chào")];
+        let params = GenerateParams::default();
+
+        let response = provider.chat(&messages, &[], &params).await.unwrap();
+        assert_eq!(response.content.as_deref(), Some("Xin chào!"));
+        assert_eq!(response.fallback.as_deref(), Some("brain"));
+
+        let text = std::sync::Mutex::new(String::new());
+        let on_text = |t: &str| text.lock().unwrap().push_str(t);
+        let response = provider
+            .chat_stream(&messages, &[], &params, &on_text)
+            .await
+            .unwrap();
+        assert_eq!(response.fallback.as_deref(), Some("brain"));
+        assert_eq!(*text.lock().unwrap(), "Xin chào!");
+        assert_eq!(local_calls.load(Ordering::SeqCst), 2);
+        assert!(provider.health_check().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_error_response_does_not_fall_back() {
+        let local_calls = Arc::new(AtomicUsize::new(0));
+        let provider = provider(
+            || BizClawError::Provider("openai API error 400 Bad Request: invalid model".into()),
+            &local_calls,
+        );
+        let result = provider.chat(&[], &[], &GenerateParams::default()).await;
+        assert!(result.is_err());
+        assert_eq!(local_calls.load(Ordering::SeqCst), 0);
+
+        assert!(is_unreachable(&BizClawError::Timeout("30s".into())));
+        // Rate limits and 5xx open the breaker too, so an open circuit does
+        // not prove the provider is unreachable.
+        assert!(!is_unreachable(&BizClawError::Provider(
+            "openai circuit open after 5 consecutive failures".into()
+        )));
+        assert!(!is_unreachable(&BizClawError::Provider(
+            "openai API error 503 Service Unavailable: overloaded".into()
+        )));
+    }
+}
@@ -0,0 +1,183 @@
+//! Model aliases — `fast`, `smart`, … mapped to a concrete provider + model.
+//!
+//! Aliases live in [`BizClawConfig::model_aliases`]. A target may name its
+//! provider as `provider:model` (`anthropic:claude-sonnet-4`); the prefix only
+//! counts when it is a known provider, so Ollama tags such as `llama3.2:3b`
+//! stay whole. Names that are not aliases pass through unchanged.
+
+use bizclaw_core::config::BizClawConfig;
+
+use crate::provider_registry;
+
+/// A model selected by name, after alias resolution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelChoice {
+    /// Provider to use, or `None` to keep the configured one.
+    pub provider: Option<String>,
+    pub model: String,
+}
+
+/// Resolve `name` through `config.model_aliases`.
+pub fn resolve(config: &BizClawConfig, name: &str) -> ModelChoice {
+    let Some(target) = config.model_aliases.get(name) else {
+        return ModelChoice {
+            provider: None,
+            model: name.to_string(),
+        };
+    };
+    match target.split_once(':') {
+        Some((provider, model)) if !model.is_empty() && is_provider(provider) => ModelChoice {
+            provider: Some(provider.to_string()),
+            model: model.to_string(),
+        },
+        _ => ModelChoice {
+            provider: None,
+            model: target.clone(),
+        },
+    }
+}
+
+/// Whether `name` is a configured alias.
+pub fn is_alias(config: &BizClawConfig, name: &str) -> bool {
+    config.model_aliases.contains_key(name)
+}
+
+/// Resolve the configured default model in place. When the alias switches
+/// provider, the configured API key and endpoint belong to the old provider
+/// and are cleared, so the new one falls back to its environment variables.
+/// Returns whether an alias was applied.
+pub fn apply(config: &mut BizClawConfig) -> bool {
+    if !is_alias(config, &config.default_model) {
+        return false;
+    }
+    let choice = resolve(config, &config.default_model);
+    tracing::debug!(
+        "Model alias '{}' → {}",
+        config.default_model,
+        config.model_aliases[&config.default_model]
+    );
+    if let Some(provider) = choice.provider {
+        let current = if config.llm.provider.is_empty() {
+            &config.default_provider
+        } else {
+            &config.llm.provider
+        };
+        if *current != provider {
+            config.llm.api_key.clear();
+            config.llm.endpoint.clear();
+            config.api_key.clear();
+        }
+        config.default_provider = provider.clone();
+        config.llm.provider = provider;
+    }
+    config.default_model = choice.model.clone();
+    config.llm.model = choice.model;
+    true
+}
+
+fn is_provider(name: &str) -> bool {
+    name == "brain" || provider_registry::get_provider_config(name).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> BizClawConfig {
+        let mut config = BizClawConfig::default();
+        for (alias, target) in [
+            ("smart", "anthropic:claude-sonnet-4"),
+            ("fast", "gpt-4o-mini"),
+            ("local", "llama3.2:3b"),
+        ] {
+            config.model_aliases.insert(alias.into(), target.into());
+        }
+        config
+    }
+
+    #[test]
+    fn test_alias_resolves_provider_and_model() {
+        let config = config();
+        assert_eq!(
+            resolve(&config, "smart"),
+            ModelChoice {
+                provider: Some("anthropic".into()),
+                model: "claude-sonnet-4".into(),
+            }
+        );
+        assert_eq!(resolve(&config, "fast").provider, None);
+        assert_eq!(resolve(&config, "fast").model, "gpt-4o-mini");
+        // An Ollama tag is not mistaken for a provider prefix.
+        assert_eq!(resolve(&config, "local").provider, None);
+        assert_eq!(resolve(&config, "local").model, "llama3.2:3b");
+    }
+
+    #[test]
+    fn test_non_alias_passes_through() {
+        let config = config();
+        assert_eq!(
+            resolve(&config, "gpt-4o"),
+            ModelChoice {
+                provider: None,
+                model: "gpt-4o".into(),
+            }
+        );
+
+        let mut unchanged = config.clone();
+        unchanged.default_model = "gpt-4o".into();
+        assert!(!apply(&mut unchanged));
+        assert_eq!(unchanged.default_model, "gpt-4o");
+        assert_eq!(unchanged.default_provider, "openai");
+    }
+
+    #[test]
+    fn test_apply_switches_provider() {
+        let mut config = config();
+        config.default_model = "smart".into();
+        config.llm.provider = "openai".into();
+        config.llm.api_key = "sk-openai".into();
+        assert!(apply(&mut config));
+        assert_eq!(config.default_provider, "anthropic");
+        assert_eq!(config.llm.provider, "anthropic");
+        assert_eq!(config.default_model, "claude-sonnet-4");
+        assert_eq!(config.llm.model, "claude-sonnet-4");
+        // The OpenAI key must not be sent to Anthropic.
+        assert!(config.llm.api_key.is_empty());
+
+        let provider = crate::create_provider(&config).unwrap();
+        assert_eq!(provider.name(), "anthropic");
+    }
+
+    #[test]
+    fn test_channel_model_with_alias() {
+        use bizclaw_core::config::ChannelModel;
+
+        let mut config = config();
+        config.channel.models.insert(
+            "telegram".into(),
+            ChannelModel {
+                provider: Some("groq".into()),
+                model: Some("llama-3.1-8b-instant".into()),
+            },
+        );
+        config.channel.models.insert(
+            "email".into(),
+            ChannelModel {
+                provider: None,
+                model: Some("smart".into()),
+            },
+        );
+
+        let telegram = config.for_channel("telegram");
+        assert!(!apply(&mut telegram.clone()));
+        assert_eq!(crate::create_provider(&telegram).unwrap().name(), "groq");
+
+        let mut email = config.for_channel("email");
+        assert!(apply(&mut email));
+        assert_eq!(email.default_model, "claude-sonnet-4");
+        assert_eq!(crate::create_provider(&email).unwrap().name(), "anthropic");
+
+        let cli = config.for_channel("cli");
+        assert_eq!(crate::create_provider(&cli).unwrap().name(), "openai");
+    }
+}
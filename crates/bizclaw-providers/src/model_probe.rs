@@ -0,0 +1,226 @@
+//! Model probe — ask a provider which models it really offers.
+//!
+//! Backs `bizclaw models`. OpenAI-compatible servers are asked via
+//! `/v1/models` and Ollama via `/api/tags`; Anthropic is shown from the
+//! built-in list. When a server cannot be reached the built-in list is shown
+//! too, along with the error.
+
+use bizclaw_core::config::BizClawConfig;
+use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::types::ModelInfo;
+
+use crate::model_alias;
+use crate::openai_compatible::OpenAiCompatibleProvider;
+use crate::provider_registry;
+
+/// Where a probe's model list came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelSource {
+    /// Returned by the provider just now.
+    Live,
+    /// BizClaw's built-in list for the provider.
+    Catalog,
+}
+
+/// Result of probing one provider.
+#[derive(Debug, Clone)]
+pub struct ModelProbe {
+    pub provider: String,
+    pub models: Vec<ModelInfo>,
+    pub source: ModelSource,
+    /// The configured default model, when the probed provider is the
+    /// configured one.
+    pub configured: Option<String>,
+    /// Why the provider could not be queried, if it could not.
+    pub error: Option<String>,
+}
+
+impl ModelProbe {
+    /// Whether `model` is in the list. An untagged Ollama name matches its
+    /// `:latest` tag.
+    pub fn offers(&self, model: &str) -> bool {
+        self.models.iter().any(|m| same_model(&m.id, model))
+    }
+
+    /// Whether `listed` is the configured default model.
+    pub fn is_configured(&self, listed: &ModelInfo) -> bool {
+        self.configured
+            .as_deref()
+            .is_some_and(|c| same_model(&listed.id, c))
+    }
+
+    /// Warning to show when the configured model is not in a live list.
+    /// A built-in list is not authoritative, so it never warns.
+    pub fn warning(&self) -> Option<String> {
+        let configured = self.configured.as_deref()?;
+        (self.source == ModelSource::Live && !self.offers(configured)).then(|| {
+            format!(
+                "Configured model '{configured}' is not offered by {}",
+                self.provider
+            )
+        })
+    }
+}
+
+fn same_model(listed: &str, wanted: &str) -> bool {
+    listed == wanted || listed.strip_suffix(":latest") == Some(wanted)
+}
+
+/// Probe `provider`, or the configured provider when `None`.
+pub async fn probe(config: &BizClawConfig, provider: Option<&str>) -> Result<ModelProbe> {
+    let mut config = config.clone();
+    model_alias::apply(&mut config);
+    let configured_provider = if config.llm.provider.is_empty() {
+        config.default_provider.clone()
+    } else {
+        config.llm.provider.clone()
+    };
+    let provider = provider.unwrap_or(&configured_provider).to_string();
+    let configured = if provider == configured_provider {
+        Some(config.default_model.clone()).filter(|m| !m.is_empty())
+    } else {
+        // Credentials and endpoint belong to the configured provider.
+        config.llm.api_key.clear();
+        config.llm.endpoint.clear();
+        config.api_key.clear();
+        None
+    };
+
+    if provider == "brain" {
+        return Err(BizClawError::Config(
+            "the local brain has no model server; use `bizclaw brain list`".into(),
+        ));
+    }
+    let client = if provider.starts_with("custom:") {
+        OpenAiCompatibleProvider::custom(&provider, &config)?
+    } else {
+        let registry = provider_registry::get_provider_config(&provider)
+            .ok_or_else(|| BizClawError::ProviderNotFound(provider.clone()))?;
+        OpenAiCompatibleProvider::from_registry(registry, &config)?
+    };
+
+    let mut probe = ModelProbe {
+        provider,
+        models: client.catalog().to_vec(),
+        source: ModelSource::Catalog,
+        configured,
+        error: None,
+    };
+    if probe.provider == "anthropic" {
+        return Ok(probe);
+    }
+    match client.fetch_models().await {
+        Ok(models) => {
+            probe.models = models;
+            probe.source = ModelSource::Live;
+        }
+        Err(e) => probe.error = Some(e.to_string()),
+    }
+    Ok(probe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Accept one HTTP request, answer `status` with `body`, and return the
+    /// request line.
+    async fn serve_once(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !raw.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = sock.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                raw.extend_from_slice(&buf[..n]);
+            }
+            let resp = format!(
+                "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            sock.write_all(resp.as_bytes()).await.unwrap();
+            let raw = String::from_utf8_lossy(&raw).to_string();
+            raw.lines().next().unwrap_or_default().to_string()
+        });
+        (addr, handle)
+    }
+
+    fn config(provider: &str, model: &str, endpoint: &str) -> BizClawConfig {
+        let mut config = BizClawConfig::default();
+        config.llm.provider = provider.into();
+        config.default_model = model.into();
+        config.llm.api_key = "sk-test".into();
+        config.llm.endpoint = endpoint.into();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_openai_models_listed() {
+        let (addr, server) = serve_once(
+            "200 OK",
+            r#"{"data":[{"id":"gpt-4o"},{"id":"gpt-4o-mini"}]}"#,
+        )
+        .await;
+        let config = config("openai", "gpt-4o", &format!("{addr}/v1"));
+
+        let probe = probe(&config, None).await.unwrap();
+        assert_eq!(server.await.unwrap(), "GET /v1/models HTTP/1.1");
+        assert_eq!(probe.source, ModelSource::Live);
+        let ids: Vec<&str> = probe.models.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["gpt-4o", "gpt-4o-mini"]);
+        assert!(probe.offers("gpt-4o"));
+        assert_eq!(probe.warning(), None);
+    }
+
+    #[tokio::test]
+    async fn test_missing_ollama_model_warns() {
+        let (addr, server) = serve_once(
+            "200 OK",
+            r#"{"models":[{"name":"llama3.2:latest"},{"name":"qwen2.5:7b"}]}"#,
+        )
+        .await;
+        let config = config("ollama", "mistral", &format!("{addr}/v1"));
+
+        let probe = probe(&config, None).await.unwrap();
+        assert_eq!(server.await.unwrap(), "GET /api/tags HTTP/1.1");
+        assert!(probe.offers("llama3.2"));
+        assert!(probe.offers("qwen2.5:7b"));
+        assert_eq!(
+            probe.warning().as_deref(),
+            Some("Configured model 'mistral' is not offered by ollama")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_falls_back_to_catalog() {
+        let (addr, server) = serve_once("401 Unauthorized", r#"{"error":"bad key"}"#).await;
+        let config = config("openai", "gpt-5-turbo", &format!("{addr}/v1"));
+
+        let probe = probe(&config, None).await.unwrap();
+        server.await.unwrap();
+        assert_eq!(probe.source, ModelSource::Catalog);
+        assert!(probe.error.as_deref().unwrap().contains("401"));
+        assert!(!probe.models.is_empty());
+        // A built-in list may be out of date; no warning.
+        assert_eq!(probe.warning(), None);
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_uses_catalog() {
+        let config = config("openai", "gpt-4o", "http://127.0.0.1:9/v1");
+        let probe = probe(&config, Some("anthropic")).await.unwrap();
+        assert_eq!(probe.source, ModelSource::Catalog);
+        assert!(probe.models.iter().any(|m| m.id.contains("claude")));
+        // Another provider than the configured one: no default to check.
+        assert_eq!(probe.configured, None);
+    }
+}
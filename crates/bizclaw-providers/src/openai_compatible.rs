@@ -6,15 +6,21 @@
 //! Different providers are distinguished only by endpoint URL, auth style, and API key.
 
 use async_trait::async_trait;
-use bizclaw_core::config::BizClawConfig;
+use bizclaw_core::config::{BizClawConfig, LlmConfig};
 use bizclaw_core::error::{BizClawError, Result};
-use bizclaw_core::traits::provider::{GenerateParams, Provider};
+use bizclaw_core::traits::provider::{GenerateParams, OnText, Provider, ProviderCapabilities};
 use bizclaw_core::types::{
-    FunctionCall, Message, ModelInfo, ProviderResponse, ToolCall, ToolDefinition, Usage,
+    FINISH_CONTENT_FILTER, Message, ModelInfo, ProviderResponse, Refusal, Role, ToolCall,
+    ToolChoice, ToolDefinition, Usage,
 };
 use serde_json::{Value, json};
 
+use crate::anthropic_stream;
+use crate::http_log::HttpLog;
+use crate::provider_error::ProviderError;
 use crate::provider_registry::{AuthStyle, ProviderConfig};
+use crate::system_prompt::SystemPromptFormat;
+use crate::tool_format::{ToolWireFormat, normalize_tool_calls};
 
 /// A unified provider that works with any OpenAI-compatible API.
 pub struct OpenAiCompatibleProvider {
@@ -32,8 +38,14 @@ pub struct OpenAiCompatibleProvider {
     auth_style: AuthStyle,
     /// Default models to return from `list_models`.
     default_models: Vec<ModelInfo>,
-    /// HTTP client.
+    /// HTTP client (carries configured extra headers and proxy).
     client: reqwest::Client,
+    /// Azure OpenAI `api-version`; when set, chat requests use the deployment URL.
+    api_version: Option<String>,
+    /// Azure OpenAI deployment name (falls back to the request model).
+    deployment: Option<String>,
+    /// Redacted debug logging of requests and responses.
+    http_log: HttpLog,
     /// Models that have been detected as incapable of tool calling.
     /// Once a model fails tool calling, we skip sending tools on subsequent calls.
     no_tool_models: std::sync::Mutex<std::collections::HashSet<String>>,
@@ -83,15 +95,25 @@ impl OpenAiCompatibleProvider {
             .map(|m| m.to_model_info(registry.name))
             .collect();
 
+        let api_version = non_empty(&config.llm.api_version);
+        let auth_style = if api_version.is_some() {
+            AuthStyle::ApiKeyHeader
+        } else {
+            registry.auth_style
+        };
+
         Ok(Self {
             name: registry.name.to_string(),
             api_key,
-            base_url,
+            base_url: base_url.trim_end_matches('/').to_string(),
             chat_path: registry.chat_path.to_string(),
             models_path: registry.models_path.to_string(),
-            auth_style: registry.auth_style,
+            auth_style,
             default_models,
-            client: reqwest::Client::new(),
+            client: build_http_client(&config.llm)?,
+            api_version,
+            deployment: non_empty(&config.llm.deployment),
+            http_log: HttpLog::new(config.llm.log_request_bodies),
             no_tool_models: std::sync::Mutex::new(std::collections::HashSet::new()),
         })
     }
@@ -124,22 +146,275 @@ impl OpenAiCompatibleProvider {
             models_path: "/models".to_string(),
             auth_style,
             default_models: vec![],
-            client: reqwest::Client::new(),
+            client: build_http_client(&config.llm)?,
+            api_version: None,
+            deployment: None,
+            http_log: HttpLog::new(config.llm.log_request_bodies),
             no_tool_models: std::sync::Mutex::new(std::collections::HashSet::new()),
         })
     }
 
+    /// Chat completions URL for `model`.
+    ///
+    /// Azure OpenAI addresses deployments rather than models:
+    /// `{base}/openai/deployments/{deployment}/chat/completions?api-version=...`
+    fn chat_url(&self, model: &str) -> String {
+        match &self.api_version {
+            Some(version) => format!(
+                "{}/openai/deployments/{}/chat/completions?api-version={}",
+                self.base_url,
+                self.deployment.as_deref().unwrap_or(model),
+                version
+            ),
+            None => format!("{}{}", self.base_url, self.chat_path),
+        }
+    }
+
+    fn is_anthropic(&self) -> bool {
+        self.name == "anthropic" || self.base_url.contains("anthropic")
+    }
+
+    /// Where chat requests carry the system prompt. Gemini is reached through
+    /// its OpenAI-compatible endpoint, so only Anthropic differs.
+    fn system_prompt_format(&self) -> SystemPromptFormat {
+        if self.is_anthropic() {
+            SystemPromptFormat::Anthropic
+        } else {
+            SystemPromptFormat::OpenAi
+        }
+    }
+
+    /// PRE-FLIGHT: skip tools for models already detected as incapable of
+    /// tool calling. This saves tokens and avoids hallucinated tool calls/dumps.
+    fn usable_tools<'a>(&self, tools: &'a [ToolDefinition], model: &str) -> &'a [ToolDefinition] {
+        let lock = self.no_tool_models.lock().unwrap_or_else(|p| p.into_inner());
+        if lock.contains(model) {
+            tracing::debug!("🚫 Skipping tools for model '{}' (known no-tool)", model);
+            &[]
+        } else {
+            tools
+        }
+    }
+
+    /// Built-in model list for this provider (empty for custom endpoints).
+    pub fn catalog(&self) -> &[ModelInfo] {
+        &self.default_models
+    }
+
+    /// Ask the server which models it offers. Unlike `list_models`, failures
+    /// are returned instead of falling back to the built-in list.
+    ///
+    /// Ollama is asked via its native `/api/tags`, which lists every pulled
+    /// tag; other servers via `{base_url}/models`.
+    pub async fn fetch_models(&self) -> Result<Vec<ModelInfo>> {
+        let url = if self.name == "ollama" {
+            format!("{}/api/tags", self.base_url.trim_end_matches("/v1"))
+        } else {
+            format!("{}{}", self.base_url, self.models_path)
+        };
+        let resp = self
+            .send(self.apply_auth(self.client.get(&url)))
+            .await
+            .map_err(|e| BizClawError::Http(format!("{} models request failed: {e}", self.name)))?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ProviderError::parse(&self.name, status, &text).into());
+        }
+        let json: Value = resp
+            .json()
+            .await
+            .map_err(|e| BizClawError::Provider(format!("{} models response: {e}", self.name)))?;
+        // OpenAI: {"data": [{"id": ...}]}; Ollama: {"models": [{"name": ...}]}
+        let (list, key) = if self.name == "ollama" {
+            (&json["models"], "name")
+        } else {
+            (&json["data"], "id")
+        };
+        Ok(list
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|m| {
+                        let id = m[key].as_str()?;
+                        Some(ModelInfo {
+                            id: id.to_string(),
+                            name: id.to_string(),
+                            provider: self.name.clone(),
+                            context_length: 4096,
+                            max_output_tokens: Some(4096),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Send `req`, logging it and the response status (redacted) at debug level.
+    async fn send(&self, req: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+        let request = req.build()?;
+        self.http_log.request(&self.name, &request);
+        let resp = self.client.execute(request).await?;
+        self.http_log.response(&self.name, &resp);
+        Ok(resp)
+    }
+
     /// Build the auth header for the request.
     fn apply_auth(&self, req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
         match self.auth_style {
             AuthStyle::Bearer if !self.api_key.is_empty() => {
                 req.header("Authorization", format!("Bearer {}", self.api_key))
             }
+            AuthStyle::ApiKeyHeader if !self.api_key.is_empty() => {
+                req.header("api-key", &self.api_key)
+            }
             _ => req,
         }
     }
 }
 
+/// Providers whose chat endpoint accepts `response_format: {"type": "json_object"}`.
+const NATIVE_JSON_PROVIDERS: &[&str] = &[
+    "openai", "openrouter", "deepseek", "gemini", "groq", "ollama", "vllm", "together", "mistral",
+    "xai",
+];
+
+/// Providers whose flagship models accept image inputs.
+const VISION_PROVIDERS: &[&str] = &["openai", "openrouter", "anthropic", "gemini", "xai"];
+
+/// Context window assumed when a provider has no static model list (local/custom servers).
+const DEFAULT_CONTEXT: u32 = 8192;
+
+/// A body that arrived but isn't JSON is the provider's fault, not the network's,
+/// so it must not look like an unreachable endpoint to the local fallback.
+fn body_error(name: &str, e: reqwest::Error) -> BizClawError {
+    if e.is_decode() {
+        BizClawError::Provider(format!("{name} returned invalid JSON: {e}"))
+    } else {
+        BizClawError::Http(e.to_string())
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim();
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+/// `finish_reason` of an OpenAI-style choice and the refusal it signals.
+/// Refusals are reported as [`FINISH_CONTENT_FILTER`] whichever name the
+/// provider used.
+fn finish_reason(choice: &Value) -> (Option<String>, Option<Refusal>) {
+    let raw = choice["finish_reason"].as_str();
+    let refusal = Refusal::detect(raw, choice["message"]["refusal"].as_str());
+    let reason = match refusal {
+        Some(_) => Some(FINISH_CONTENT_FILTER.to_string()),
+        None => raw.map(String::from),
+    };
+    (reason, refusal)
+}
+
+/// Separate a reasoning model's chain of thought from its answer.
+///
+/// DeepSeek returns it as `reasoning_content`, OpenRouter as `reasoning`, and
+/// R1-style models served by Ollama or vLLM inline it as `<think>…</think>`
+/// at the start of the content. Returns `(answer, reasoning)`.
+fn split_reasoning(message: &Value) -> (Option<String>, Option<String>) {
+    let mut content = message["content"].as_str().map(String::from);
+    let mut reasoning = message["reasoning_content"]
+        .as_str()
+        .or(message["reasoning"].as_str())
+        .map(|r| r.trim().to_string());
+
+    if let Some(text) = content.as_deref()
+        && let Some(start) = text.find("<think>")
+    {
+        let after = &text[start + "<think>".len()..];
+        // An unterminated block (answer cut off) is all reasoning.
+        let (thought, answer) = match after.find("</think>") {
+            Some(end) => (&after[..end], &after[end + "</think>".len()..]),
+            None => (after, ""),
+        };
+        reasoning = Some(match reasoning {
+            Some(r) => format!("{r}\n\n{}", thought.trim()),
+            None => thought.trim().to_string(),
+        });
+        content = Some(format!("{}{}", &text[..start], answer).trim().to_string());
+    }
+
+    (content, reasoning.filter(|r| !r.is_empty()))
+}
+
+/// Encode messages for the chat API.
+///
+/// Image attachments become `image_url` content parts when `vision` is set.
+/// Tool messages can only carry text, so images returned by tools follow the
+/// run of tool results as one user message. Without vision, each image is
+/// replaced by a short text note.
+fn wire_messages(messages: &[Message], vision: bool) -> Vec<Value> {
+    let mut out = Vec::with_capacity(messages.len());
+    let mut tool_images: Vec<Value> = Vec::new();
+    for msg in messages {
+        if msg.role != Role::Tool && !tool_images.is_empty() {
+            out.push(json!({"role": "user", "content": std::mem::take(&mut tool_images)}));
+        }
+        let mut value = serde_json::to_value(msg).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.remove("images");
+            obj.remove("pinned");
+        }
+        if msg.images.is_empty() {
+            out.push(value);
+            continue;
+        }
+        if !vision {
+            value["content"] = json!(msg.content_with_image_notes());
+            out.push(value);
+            continue;
+        }
+        let image_parts = msg
+            .images
+            .iter()
+            .map(|img| json!({"type": "image_url", "image_url": {"url": img.image_url()}}));
+        if msg.role == Role::Tool {
+            let call_id = msg.tool_call_id.as_deref().unwrap_or("tool");
+            let label = format!("Image(s) returned by tool call {call_id}:");
+            tool_images.push(json!({"type": "text", "text": label}));
+            tool_images.extend(image_parts);
+        } else {
+            let mut parts = vec![json!({"type": "text", "text": msg.content})];
+            parts.extend(image_parts);
+            value["content"] = Value::Array(parts);
+        }
+        out.push(value);
+    }
+    if !tool_images.is_empty() {
+        out.push(json!({"role": "user", "content": tool_images}));
+    }
+    out
+}
+
+/// Build the HTTP client with the configured extra headers and proxy.
+pub fn build_http_client(llm: &LlmConfig) -> Result<reqwest::Client> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in &llm.extra_headers {
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| BizClawError::Config(format!("Invalid header name '{name}': {e}")))?;
+        let value = reqwest::header::HeaderValue::from_str(value)
+            .map_err(|e| BizClawError::Config(format!("Invalid value for header '{name}': {e}")))?;
+        headers.insert(name, value);
+    }
+
+    let mut builder = reqwest::Client::builder().default_headers(headers);
+    if !llm.proxy_url.trim().is_empty() {
+        let proxy = reqwest::Proxy::all(llm.proxy_url.trim())
+            .map_err(|e| BizClawError::Config(format!("Invalid proxy_url: {e}")))?;
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|e| BizClawError::Config(format!("HTTP client: {e}")))
+}
+
 #[async_trait]
 impl Provider for OpenAiCompatibleProvider {
     fn name(&self) -> &str {
@@ -157,20 +432,9 @@ impl Provider for OpenAiCompatibleProvider {
             return Err(BizClawError::ApiKeyMissing(self.name.clone()));
         }
 
-        let is_anthropic = self.name == "anthropic" || self.base_url.contains("anthropic");
-
-        // ═══ PRE-FLIGHT: Skip tools for known-incapable models ═══
-        // If we've already detected this model can't handle tools, don't send them.
-        // This saves tokens and avoids hallucinated tool calls/dumps.
-        let tools = {
-            let lock = self.no_tool_models.lock().unwrap_or_else(|p| p.into_inner());
-            if lock.contains(&params.model) {
-                tracing::debug!("🚫 Skipping tools for model '{}' (known no-tool)", params.model);
-                &[] as &[ToolDefinition]
-            } else {
-                tools
-            }
-        };
+        let is_anthropic = self.is_anthropic();
+        let vision = self.capabilities().supports_vision;
+        let tools = self.usable_tools(tools, &params.model);
 
         // Build request body — standard OpenAI format
         let mut body = json!({
@@ -179,64 +443,39 @@ impl Provider for OpenAiCompatibleProvider {
             "max_tokens": params.max_tokens,
         });
 
-        // ═══════════════════════════════════════
-        // Anthropic Prompt Caching — cache_control
-        // ═══════════════════════════════════════
-        if is_anthropic {
-            // Anthropic uses top-level "system" field (not messages[0])
-            // with cache_control for prompt caching
-            let mut non_system_msgs: Vec<Value> = Vec::new();
-            let mut system_blocks: Vec<Value> = Vec::new();
-
-            for msg in messages {
-                if msg.role == bizclaw_core::types::Role::System {
-                    system_blocks.push(json!({
-                        "type": "text",
-                        "text": msg.content,
-                        "cache_control": { "type": "ephemeral" }
-                    }));
-                } else {
-                    non_system_msgs.push(serde_json::to_value(msg).unwrap_or_default());
-                }
-            }
-
-            if !system_blocks.is_empty() {
-                body["system"] = Value::Array(system_blocks);
-            }
-            body["messages"] = Value::Array(non_system_msgs);
+        // Anthropic takes the system prompt as a top-level `system` field,
+        // with cache_control for prompt caching
+        let messages = wire_messages(messages, vision);
+        body["messages"] = Value::Array(self.system_prompt_format().place(&mut body, messages));
 
-            tracing::debug!(
-                "🧊 Anthropic prompt caching enabled (system blocks with cache_control)"
-            );
-        } else {
-            body["messages"] = serde_json::to_value(messages).unwrap_or_default();
+        if params.json_mode && self.supports_json_mode() {
+            body["response_format"] = json!({ "type": "json_object" });
+        }
+        if !params.stop.is_empty() {
+            let field = if is_anthropic { "stop_sequences" } else { "stop" };
+            body[field] = json!(params.stop);
+        }
+        if let Some(seed) = params.seed {
+            body["seed"] = json!(seed);
         }
 
         // Add tools if present
         if !tools.is_empty() {
-            let tool_defs: Vec<Value> = tools
-                .iter()
-                .map(|t| {
-                    let mut def = json!({
-                        "type": "function",
-                        "function": {
-                            "name": t.name,
-                            "description": t.description,
-                            "parameters": t.parameters,
-                        }
-                    });
-                    // Cache tool definitions for Anthropic (they rarely change)
-                    if is_anthropic {
-                        def["cache_control"] = json!({ "type": "ephemeral" });
-                    }
-                    def
-                })
-                .collect();
-            body["tools"] = Value::Array(tool_defs);
+            let mut tool_defs = ToolWireFormat::OpenAi.encode_tools(tools);
+            // Cache tool definitions for Anthropic (they rarely change)
+            if is_anthropic && let Some(defs) = tool_defs.as_array_mut() {
+                for def in defs {
+                    def["cache_control"] = json!({ "type": "ephemeral" });
+                }
+            }
+            body["tools"] = tool_defs;
+            if params.tool_choice != ToolChoice::Auto {
+                body["tool_choice"] = ToolWireFormat::OpenAi.encode_tool_choice(&params.tool_choice);
+            }
         }
 
         // Send request
-        let url = format!("{}{}", self.base_url, self.chat_path);
+        let url = self.chat_url(&params.model);
         let req = self
             .client
             .post(&url)
@@ -244,7 +483,7 @@ impl Provider for OpenAiCompatibleProvider {
             .json(&body);
         let req = self.apply_auth(req);
 
-        let resp = req.send().await.map_err(|e| {
+        let resp = self.send(req).await.map_err(|e| {
             BizClawError::Http(format!("{} connection failed ({}): {}", self.name, url, e))
         })?;
 
@@ -272,74 +511,64 @@ impl Provider for OpenAiCompatibleProvider {
                     .header("Content-Type", "application/json")
                     .json(&body);
                 let retry_req = self.apply_auth(retry_req);
-                let retry_resp = retry_req.send().await.map_err(|e| {
+                let retry_resp = self.send(retry_req).await.map_err(|e| {
                     BizClawError::Http(format!("{} retry failed: {}", self.name, e))
                 })?;
                 if !retry_resp.status().is_success() {
                     let rs = retry_resp.status();
                     let rt = retry_resp.text().await.unwrap_or_default();
-                    return Err(BizClawError::Provider(format!(
-                        "{} API error {} (retry without tools): {}",
-                        self.name, rs, rt
-                    )));
+                    let mut error = ProviderError::parse(&self.name, rs, &rt);
+                    error.message.push_str(" (retry without tools)");
+                    return Err(error.into());
                 }
                 // Parse the retry response (same flow as below)
                 let json: Value = retry_resp
                     .json()
                     .await
-                    .map_err(|e| BizClawError::Http(e.to_string()))?;
+                    .map_err(|e| body_error(&self.name, e))?;
                 let choice = json["choices"]
                     .get(0)
                     .ok_or_else(|| BizClawError::Provider("No choices in retry response".into()))?;
-                let content = choice["message"]["content"].as_str().map(String::from);
+                let (content, reasoning) = split_reasoning(&choice["message"]);
                 let usage = json["usage"].as_object().map(|u| Usage {
                     prompt_tokens: u.get("prompt_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
                     completion_tokens: u.get("completion_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
                     total_tokens: u.get("total_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
                 });
+                let (finish_reason, refusal) = finish_reason(choice);
                 return Ok(ProviderResponse {
                     content,
                     tool_calls: vec![], // No tools available
-                    finish_reason: choice["finish_reason"].as_str().map(String::from),
+                    finish_reason,
                     usage,
+                    reasoning,
+                    refusal,
+                    fallback: None,
                 });
             }
 
-            return Err(BizClawError::Provider(format!(
-                "{} API error {}: {}",
-                self.name, status, text
-            )));
+            return Err(ProviderError::parse(&self.name, status, &text).into());
         }
 
         // Parse response — standard OpenAI format
-        let json: Value = resp
-            .json()
+        let text = resp
+            .text()
             .await
             .map_err(|e| BizClawError::Http(e.to_string()))?;
+        self.http_log.response_body(&self.name, &text);
+        let json: Value =
+            serde_json::from_str(&text).map_err(|e| {
+            BizClawError::Provider(format!("{} returned invalid JSON: {e}", self.name))
+        })?;
 
         let choice = json["choices"]
             .get(0)
             .ok_or_else(|| BizClawError::Provider("No choices in response".into()))?;
 
-        let content = choice["message"]["content"].as_str().map(String::from);
+        let (content, reasoning) = split_reasoning(&choice["message"]);
 
         // Parse tool_calls FIRST so we can inspect them in detection below
-        let tool_calls: Vec<ToolCall> = if let Some(tc) = choice["message"]["tool_calls"].as_array() {
-            tc.iter()
-                .filter_map(|t| {
-                    Some(ToolCall {
-                        id: t["id"].as_str().unwrap_or("").to_string(),
-                        r#type: "function".to_string(),
-                        function: FunctionCall {
-                            name: t["function"]["name"].as_str()?.to_string(),
-                            arguments: t["function"]["arguments"].as_str()?.to_string(),
-                        },
-                    })
-                })
-                .collect()
-        } else {
-            vec![]
-        };
+        let tool_calls: Vec<ToolCall> = normalize_tool_calls(&json);
 
         // ═══ SMART DETECTION: Model dumping tool schemas as text ═══
         // Small models (e.g., llama3.2:1b, phi, tinyllama) can't handle tool calling
@@ -426,28 +655,32 @@ impl Provider for OpenAiCompatibleProvider {
                     .header("Content-Type", "application/json")
                     .json(&body);
                 let retry_req = self.apply_auth(retry_req);
-                let retry_resp = retry_req.send().await.map_err(|e| {
+                let retry_resp = self.send(retry_req).await.map_err(|e| {
                     BizClawError::Http(format!("{} retry (no tools) failed: {}", self.name, e))
                 })?;
                 if retry_resp.status().is_success() {
                     let rjson: Value = retry_resp
                         .json()
                         .await
-                        .map_err(|e| BizClawError::Http(e.to_string()))?;
+                        .map_err(|e| body_error(&self.name, e))?;
                     let rchoice = rjson["choices"]
                         .get(0)
                         .ok_or_else(|| BizClawError::Provider("No choices in retry".into()))?;
-                    let rcontent = rchoice["message"]["content"].as_str().map(String::from);
+                    let (rcontent, rreasoning) = split_reasoning(&rchoice["message"]);
                     let rusage = rjson["usage"].as_object().map(|u| Usage {
                         prompt_tokens: u.get("prompt_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
                         completion_tokens: u.get("completion_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
                         total_tokens: u.get("total_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
                     });
+                    let (rfinish, rrefusal) = finish_reason(rchoice);
                     return Ok(ProviderResponse {
                         content: rcontent,
                         tool_calls: vec![],
-                        finish_reason: rchoice["finish_reason"].as_str().map(String::from),
+                        finish_reason: rfinish,
                         usage: rusage,
+                        reasoning: rreasoning,
+                        refusal: rrefusal,
+                        fallback: None,
                     });
                 }
                 // If retry also failed, fall through to return original (garbled) response
@@ -463,46 +696,73 @@ impl Provider for OpenAiCompatibleProvider {
             total_tokens: u.get("total_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
         });
 
+        let (finish_reason, refusal) = finish_reason(choice);
+        if let Some(refusal) = &refusal {
+            tracing::warn!("🛑 {} refused to answer ({})", self.name, refusal.reason);
+        }
         Ok(ProviderResponse {
             content,
             tool_calls,
-            finish_reason: choice["finish_reason"].as_str().map(String::from),
+            finish_reason,
             usage,
+            reasoning,
+            refusal,
+            fallback: None,
         })
     }
 
-    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
-        // Try to fetch models from the API
-        let url = format!("{}{}", self.base_url, self.models_path);
-        let req = self.client.get(&url);
-        let req = self.apply_auth(req);
+    /// Anthropic streams through the native Messages API, where tool calls
+    /// arrive as `tool_use` blocks assembled from `input_json_delta` events.
+    /// Other providers answer in one piece.
+    async fn chat_stream(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        params: &GenerateParams,
+        on_text: &OnText<'_>,
+    ) -> Result<ProviderResponse> {
+        if !self.is_anthropic() {
+            let resp = self.chat(messages, tools, params).await?;
+            if let Some(text) = &resp.content
+                && !text.is_empty()
+            {
+                on_text(text);
+            }
+            return Ok(resp);
+        }
+        if self.api_key.is_empty() {
+            return Err(BizClawError::ApiKeyMissing(self.name.clone()));
+        }
 
-        match req.send().await {
-            Ok(r) if r.status().is_success() => {
-                let json: Value = r.json().await.unwrap_or_default();
-                let models: Vec<ModelInfo> = json["data"]
-                    .as_array()
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|m| {
-                                Some(ModelInfo {
-                                    id: m["id"].as_str()?.to_string(),
-                                    name: m["id"].as_str()?.to_string(),
-                                    provider: self.name.clone(),
-                                    context_length: 4096,
-                                    max_output_tokens: Some(4096),
-                                })
-                            })
-                            .collect()
-                    })
-                    .unwrap_or_default();
+        let tools = self.usable_tools(tools, &params.model);
+        let vision = self.capabilities().supports_vision;
+        let body = anthropic_stream::request_body(messages, tools, params, vision);
+        let url = format!("{}/messages", self.base_url);
+        let req = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", anthropic_stream::API_VERSION)
+            .json(&body);
+        let resp = self
+            .send(req)
+            .await
+            .map_err(|e| {
+                BizClawError::Http(format!("{} connection failed ({}): {}", self.name, url, e))
+            })?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ProviderError::parse(&self.name, status, &text).into());
+        }
+        anthropic_stream::read_stream(resp, on_text).await
+    }
 
-                if models.is_empty() {
-                    Ok(self.default_models.clone())
-                } else {
-                    Ok(models)
-                }
-            }
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        // Try to fetch models from the API, else fall back to the built-in list
+        match self.fetch_models().await {
+            Ok(models) if !models.is_empty() => Ok(models),
             _ => Ok(self.default_models.clone()),
         }
     }
@@ -515,7 +775,450 @@ impl Provider for OpenAiCompatibleProvider {
 
         // For local servers (ollama, llamacpp), try to connect
         let url = format!("{}{}", self.base_url, self.models_path);
-        let resp = self.client.get(&url).send().await;
+        let resp = self.send(self.client.get(&url)).await;
         Ok(resp.is_ok())
     }
+
+    fn supports_json_mode(&self) -> bool {
+        NATIVE_JSON_PROVIDERS.contains(&self.name.as_str())
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            // Every OpenAI-compatible API streams over SSE.
+            supports_streaming: true,
+            // Models that turn out not to support tools are detected per request.
+            supports_tools: true,
+            supports_vision: VISION_PROVIDERS.contains(&self.name.as_str()),
+            supports_json: self.supports_json_mode(),
+            max_context: self
+                .default_models
+                .iter()
+                .map(|m| m.context_length)
+                .max()
+                .unwrap_or(DEFAULT_CONTEXT),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Accept one HTTP request, answer with a minimal chat completion, and
+    /// return the raw request text.
+    async fn capture_one_request() -> (String, tokio::task::JoinHandle<String>) {
+        serve_one_request(r#"{"choices":[{"message":{"content":"ok"},"finish_reason":"stop"}]}"#)
+            .await
+    }
+
+    /// Accept one HTTP request and answer with `body`.
+    async fn serve_one_request(
+        body: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = sock.read(&mut buf).await.unwrap();
+                raw.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&raw).to_string();
+                if let Some(head_end) = text.find("\r\n\r\n") {
+                    let len = text
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                        })
+                        .unwrap_or(0);
+                    if raw.len() >= head_end + 4 + len || n == 0 {
+                        break;
+                    }
+                }
+            }
+            let resp = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            sock.write_all(resp.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&raw).to_string()
+        });
+        (addr, handle)
+    }
+
+    fn openai_config(endpoint: &str) -> BizClawConfig {
+        let mut config = BizClawConfig::default();
+        config.llm.provider = "openai".into();
+        config.llm.api_key = "sk-test".into();
+        config.llm.endpoint = endpoint.into();
+        config
+    }
+
+    fn params(model: &str) -> GenerateParams {
+        GenerateParams {
+            model: model.into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extra_headers_are_sent() {
+        let (addr, server) = capture_one_request().await;
+        let mut config = openai_config(&format!("{addr}/v1"));
+        config
+            .llm
+            .extra_headers
+            .insert("OpenAI-Organization".into(), "org-42".into());
+        let registry = crate::provider_registry::get_provider_config("openai").unwrap();
+        let provider = OpenAiCompatibleProvider::from_registry(registry, &config).unwrap();
+
+        let resp = provider
+            .chat(&[Message::user("hi")], &[], &params("gpt-4o-mini"))
+            .await
+            .unwrap();
+        assert_eq!(resp.content.as_deref(), Some("ok"));
+
+        let raw = server.await.unwrap().to_ascii_lowercase();
+        assert!(raw.starts_with("post /v1/chat/completions "), "{raw}");
+        assert!(raw.contains("openai-organization: org-42"));
+        assert!(raw.contains("authorization: bearer sk-test"));
+    }
+
+    #[tokio::test]
+    async fn test_proxy_is_used() {
+        let (proxy_addr, server) = capture_one_request().await;
+        let mut config = openai_config("http://llm.internal.example/v1");
+        config.llm.proxy_url = proxy_addr;
+        let registry = crate::provider_registry::get_provider_config("openai").unwrap();
+        let provider = OpenAiCompatibleProvider::from_registry(registry, &config).unwrap();
+
+        provider
+            .chat(&[Message::user("hi")], &[], &params("gpt-4o-mini"))
+            .await
+            .unwrap();
+
+        // A forward proxy receives the absolute target URL.
+        let raw = server.await.unwrap();
+        assert!(
+            raw.starts_with("POST http://llm.internal.example/v1/chat/completions "),
+            "{raw}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_azure_deployment_url() {
+        let (addr, server) = capture_one_request().await;
+        let mut config = openai_config(&format!("{addr}/"));
+        config.llm.api_version = "2024-06-01".into();
+        config.llm.deployment = "gpt4o-prod".into();
+        let registry = crate::provider_registry::get_provider_config("openai").unwrap();
+        let provider = OpenAiCompatibleProvider::from_registry(registry, &config).unwrap();
+        assert_eq!(
+            provider.chat_url("gpt-4o"),
+            format!("{addr}/openai/deployments/gpt4o-prod/chat/completions?api-version=2024-06-01")
+        );
+
+        provider
+            .chat(&[Message::user("hi")], &[], &params("gpt-4o"))
+            .await
+            .unwrap();
+        let raw = server.await.unwrap().to_ascii_lowercase();
+        assert!(raw.starts_with(
+            "post /openai/deployments/gpt4o-prod/chat/completions?api-version=2024-06-01 "
+        ));
+        assert!(raw.contains("api-key: sk-test"));
+        assert!(!raw.contains("authorization:"));
+    }
+
+    #[tokio::test]
+    async fn test_native_json_mode_sets_response_format() {
+        let (addr, server) = capture_one_request().await;
+        let config = openai_config(&format!("{addr}/v1"));
+        let registry = crate::provider_registry::get_provider_config("openai").unwrap();
+        let provider = OpenAiCompatibleProvider::from_registry(registry, &config).unwrap();
+        assert!(provider.supports_json_mode());
+
+        let params = GenerateParams {
+            json_mode: true,
+            ..params("gpt-4o-mini")
+        };
+        provider
+            .chat(&[Message::user("hi")], &[], &params)
+            .await
+            .unwrap();
+        let raw = server.await.unwrap();
+        assert!(raw.contains(r#""response_format":{"type":"json_object"}"#), "{raw}");
+
+        let anthropic = crate::provider_registry::get_provider_config("anthropic").unwrap();
+        let provider = OpenAiCompatibleProvider::from_registry(anthropic, &config).unwrap();
+        assert!(!provider.supports_json_mode());
+    }
+
+    #[tokio::test]
+    async fn test_max_tokens_and_stop_are_sent() {
+        let params = GenerateParams {
+            max_tokens: 321,
+            stop: vec!["END".into(), "User:".into()],
+            ..params("gpt-4o-mini")
+        };
+
+        let (addr, server) = capture_one_request().await;
+        let config = openai_config(&format!("{addr}/v1"));
+        let registry = crate::provider_registry::get_provider_config("openai").unwrap();
+        let provider = OpenAiCompatibleProvider::from_registry(registry, &config).unwrap();
+        provider
+            .chat(&[Message::user("hi")], &[], &params)
+            .await
+            .unwrap();
+        let raw = server.await.unwrap();
+        assert!(raw.contains(r#""max_tokens":321"#), "{raw}");
+        assert!(raw.contains(r#""stop":["END","User:"]"#), "{raw}");
+
+        // Anthropic names the field `stop_sequences`.
+        let (addr, server) = capture_one_request().await;
+        let config = openai_config(&format!("{addr}/v1"));
+        let registry = crate::provider_registry::get_provider_config("anthropic").unwrap();
+        let provider = OpenAiCompatibleProvider::from_registry(registry, &config).unwrap();
+        let _ = provider.chat(&[Message::user("hi")], &[], &params).await;
+        let raw = server.await.unwrap();
+        assert!(raw.contains(r#""max_tokens":321"#), "{raw}");
+        assert!(raw.contains(r#""stop_sequences":["END","User:"]"#), "{raw}");
+        assert!(!raw.contains(r#""stop":"#), "{raw}");
+    }
+
+    #[tokio::test]
+    async fn test_system_prompt_placement_per_provider() {
+        let messages = [Message::system("You are BizClaw."), Message::user("hi")];
+        for name in ["openai", "gemini", "anthropic"] {
+            let (addr, server) = capture_one_request().await;
+            let config = openai_config(&format!("{addr}/v1"));
+            let registry = crate::provider_registry::get_provider_config(name).unwrap();
+            let provider = OpenAiCompatibleProvider::from_registry(registry, &config).unwrap();
+            let _ = provider.chat(&messages, &[], &params("m")).await;
+            let raw = server.await.unwrap();
+            let body: Value = serde_json::from_str(raw.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+
+            if name == "anthropic" {
+                assert_eq!(body["system"][0]["text"], "You are BizClaw.", "{body}");
+                assert_eq!(body["messages"].as_array().unwrap().len(), 1, "{body}");
+                assert_eq!(body["messages"][0]["role"], "user");
+            } else {
+                assert!(body.get("system").is_none(), "{name}: {body}");
+                assert_eq!(body["messages"][0]["role"], "system", "{name}: {body}");
+                assert_eq!(body["messages"][0]["content"], "You are BizClaw.");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deepseek_reasoning_is_separated() {
+        let (addr, server) = serve_one_request(
+            r#"{"choices":[{"message":{"role":"assistant","content":"The answer is 42.",
+                "reasoning_content":"6 times 7 is 42."},"finish_reason":"stop"}],
+                "usage":{"prompt_tokens":12,"completion_tokens":30,"total_tokens":42}}"#,
+        )
+        .await;
+        let mut config = openai_config(&addr);
+        config.llm.provider = "deepseek".into();
+        let registry = crate::provider_registry::get_provider_config("deepseek").unwrap();
+        let provider = OpenAiCompatibleProvider::from_registry(registry, &config).unwrap();
+
+        let resp = provider
+            .chat(&[Message::user("6*7?")], &[], &params("deepseek-reasoner"))
+            .await
+            .unwrap();
+        server.await.unwrap();
+        assert_eq!(resp.content.as_deref(), Some("The answer is 42."));
+        assert_eq!(resp.reasoning.as_deref(), Some("6 times 7 is 42."));
+
+        // Inline <think> blocks (R1 via Ollama) are split the same way.
+        let (content, reasoning) = split_reasoning(
+            &json!({"content": "<think>\nUser greets me.\n</think>\n\nHello!"}),
+        );
+        assert_eq!(content.as_deref(), Some("Hello!"));
+        assert_eq!(reasoning.as_deref(), Some("User greets me."));
+        let (content, reasoning) = split_reasoning(&json!({"content": "Plain answer"}));
+        assert_eq!(content.as_deref(), Some("Plain answer"));
+        assert!(reasoning.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_openai_content_filter_is_a_refusal() {
+        let (addr, server) = serve_one_request(
+            r#"{"choices":[{"message":{"role":"assistant","content":null},
+                "finish_reason":"content_filter"}]}"#,
+        )
+        .await;
+        let config = openai_config(&format!("{addr}/v1"));
+        let registry = crate::provider_registry::get_provider_config("openai").unwrap();
+        let provider = OpenAiCompatibleProvider::from_registry(registry, &config).unwrap();
+        let resp = provider
+            .chat(&[Message::user("…")], &[], &params("gpt-4o"))
+            .await
+            .unwrap();
+        server.await.unwrap();
+        assert_eq!(resp.finish_reason.as_deref(), Some(FINISH_CONTENT_FILTER));
+        assert_eq!(resp.refusal.unwrap().reason, "content_filter");
+
+        // Structured-output refusals finish normally but carry `message.refusal`.
+        let (finish, refusal) = finish_reason(&json!({
+            "message": {"content": null, "refusal": "I can't help with that."},
+            "finish_reason": "stop"
+        }));
+        assert_eq!(finish.as_deref(), Some(FINISH_CONTENT_FILTER));
+        assert_eq!(refusal.unwrap().message.as_deref(), Some("I can't help with that."));
+
+        let (finish, refusal) = finish_reason(&json!({"finish_reason": "stop"}));
+        assert_eq!(finish.as_deref(), Some("stop"));
+        assert!(refusal.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_json_is_not_a_transport_error() {
+        let (addr, server) = serve_one_request("<html>gateway</html>").await;
+        let config = openai_config(&format!("{addr}/v1"));
+        let registry = crate::provider_registry::get_provider_config("openai").unwrap();
+        let provider = OpenAiCompatibleProvider::from_registry(registry, &config).unwrap();
+        let err = provider
+            .chat(&[Message::user("hi")], &[], &params("gpt-4o"))
+            .await
+            .unwrap_err();
+        server.await.unwrap();
+        assert!(matches!(err, BizClawError::Provider(_)), "{err:?}");
+        assert!(!crate::local_fallback::is_unreachable(&err));
+    }
+
+    #[test]
+    fn test_user_image_encoding() {
+        use bizclaw_core::types::ImageContent;
+
+        let messages = vec![Message::user("What is in these photos?").with_images(vec![
+            ImageContent::new("image/jpeg", "/9j/4AAQ"),
+            ImageContent::from_url("image/png", "https://cdn.example.com/a.png"),
+        ])];
+        let wire = wire_messages(&messages, true);
+        assert_eq!(wire.len(), 1);
+        assert_eq!(
+            wire[0]["content"],
+            json!([
+                {"type": "text", "text": "What is in these photos?"},
+                {"type": "image_url", "image_url": {"url": "data:image/jpeg;base64,/9j/4AAQ"}},
+                {"type": "image_url", "image_url": {"url": "https://cdn.example.com/a.png"}},
+            ])
+        );
+        assert!(wire[0].get("images").is_none());
+    }
+
+    #[test]
+    fn test_tool_image_forwarding() {
+        use bizclaw_core::types::ImageContent;
+
+        let image = ImageContent::new("image/png", "iVBORw0KGgo=");
+        let messages = vec![
+            Message::user("Chart last week's sales"),
+            Message {
+                tool_calls: Some(vec![ToolCall::function("call_1", "chart", "{}")]),
+                ..Message::assistant("")
+            },
+            Message::tool("Chart of sales", "call_1").with_images(vec![image]),
+        ];
+
+        // Vision: the tool result stays text, the image follows as a user content part.
+        let wire = wire_messages(&messages, true);
+        assert_eq!(wire.len(), 4);
+        assert_eq!(wire[2]["role"], "tool");
+        assert_eq!(wire[2]["content"], "Chart of sales");
+        assert!(wire[2].get("images").is_none());
+        assert_eq!(wire[3]["role"], "user");
+        let parts = wire[3]["content"].as_array().unwrap();
+        assert_eq!(parts[1]["type"], "image_url");
+        assert_eq!(parts[1]["image_url"]["url"], "data:image/png;base64,iVBORw0KGgo=");
+
+        // Text-only: the image is described in the tool message.
+        let wire = wire_messages(&messages, false);
+        assert_eq!(wire.len(), 3);
+        let content = wire[2]["content"].as_str().unwrap();
+        assert!(content.starts_with("Chart of sales\n[Image attachment (image/png"));
+        assert!(!content.contains("base64"));
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_stream_assembles_tool_call() {
+        const STREAM: &str = concat!(
+            "data: {\"type\":\"content_block_start\",\"index\":0,",
+            "\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_9\",\"name\":\"shell\",\"input\":{}}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,",
+            "\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"comm\"}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,",
+            "\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"and\\\": \\\"ls -la\\\"}\"}}\n\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\"}}\n\n",
+        );
+        let (addr, server) = serve_one_request(STREAM).await;
+        let mut config = openai_config(&format!("{addr}/v1"));
+        config.llm.provider = "anthropic".into();
+        let registry = crate::provider_registry::get_provider_config("anthropic").unwrap();
+        let provider = OpenAiCompatibleProvider::from_registry(registry, &config).unwrap();
+
+        let resp = provider
+            .chat_stream(&[Message::user("list files")], &[], &params("claude-sonnet-4"), &|_| {})
+            .await
+            .unwrap();
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /v1/messages "), "{request}");
+        assert!(request.to_ascii_lowercase().contains("x-api-key: sk-test"));
+        assert!(request.contains(r#""stream":true"#));
+
+        assert_eq!(resp.tool_calls.len(), 1);
+        assert_eq!(resp.tool_calls[0].function.name, "shell");
+        assert_eq!(resp.tool_calls[0].function.arguments, r#"{"command": "ls -la"}"#);
+        assert_eq!(resp.finish_reason.as_deref(), Some("tool_calls"));
+    }
+
+    #[test]
+    fn test_capabilities_per_provider() {
+        let config = openai_config("");
+        let caps = |name: &str| {
+            let registry = crate::provider_registry::get_provider_config(name).unwrap();
+            OpenAiCompatibleProvider::from_registry(registry, &config)
+                .unwrap()
+                .capabilities()
+        };
+
+        let openai = caps("openai");
+        assert!(openai.supports_tools && openai.supports_vision && openai.supports_json);
+        assert!(openai.supports_streaming);
+        assert_eq!(openai.max_context, 128000);
+
+        let anthropic = caps("anthropic");
+        assert!(anthropic.supports_vision);
+        assert!(!anthropic.supports_json);
+
+        let ollama = caps("ollama");
+        assert!(!ollama.supports_vision);
+        assert!(ollama.max_context > 0);
+
+        let custom = OpenAiCompatibleProvider::custom("custom:http://localhost:9000/v1", &config)
+            .unwrap()
+            .capabilities();
+        assert_eq!(custom.max_context, DEFAULT_CONTEXT);
+        assert!(!custom.supports_json);
+    }
+
+    #[test]
+    fn test_invalid_header_and_proxy_rejected() {
+        let mut llm = LlmConfig::default();
+        llm.extra_headers.insert("bad header".into(), "x".into());
+        assert!(build_http_client(&llm).is_err());
+
+        let llm = LlmConfig {
+            proxy_url: "::not a url::".into(),
+            ..Default::default()
+        };
+        assert!(build_http_client(&llm).is_err());
+    }
 }
@@ -0,0 +1,182 @@
+//! Error responses from OpenAI-compatible APIs.
+//!
+//! Failed requests usually carry a JSON envelope:
+//!
+//! ```json
+//! {"error": {"message": "You exceeded your current quota…", "type": "insufficient_quota", "code": "insufficient_quota"}}
+//! ```
+//!
+//! [`ProviderError::parse`] pulls the message, type and code out of it, so
+//! logs and the agent see the provider's own explanation instead of a raw
+//! body. Anthropic's `{"type":"error","error":{…}}` and Ollama's
+//! `{"error":"…"}` shapes are read the same way; anything else is kept as
+//! text.
+//!
+//! The error is carried as [`BizClawError::Provider`], formatted as
+//! `"<provider> API error <status>: <message> (<type>, <code>)"`. The
+//! circuit breaker reads the status from that prefix, and the agent
+//! classifies errors by the type and code (e.g. `context_length_exceeded`).
+
+use bizclaw_core::error::BizClawError;
+use reqwest::StatusCode;
+use serde_json::Value;
+
+/// Longest non-JSON body kept in the message.
+const MAX_RAW_BODY: usize = 500;
+
+/// A parsed error response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderError {
+    /// Provider name, e.g. `openai`.
+    pub provider: String,
+    pub status: StatusCode,
+    /// The provider's own message, or the raw body if it had none.
+    pub message: String,
+    /// `error.type`, e.g. `invalid_request_error`, `insufficient_quota`.
+    pub error_type: Option<String>,
+    /// `error.code`, e.g. `context_length_exceeded`, `invalid_api_key`.
+    pub code: Option<String>,
+}
+
+impl ProviderError {
+    /// Parse an error response body.
+    pub fn parse(provider: &str, status: StatusCode, body: &str) -> Self {
+        let json: Value = serde_json::from_str(body).unwrap_or(Value::Null);
+        let error = &json["error"];
+        let field = |key: &str| match &error[key] {
+            Value::String(s) if !s.is_empty() => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        };
+        let message = match error {
+            Value::String(s) => Some(s.clone()),
+            _ => field("message"),
+        };
+        let message = message.unwrap_or_else(|| {
+            let body = body.trim();
+            match body.char_indices().nth(MAX_RAW_BODY) {
+                Some((end, _)) => format!("{}…", &body[..end]),
+                None => body.to_string(),
+            }
+        });
+        Self {
+            provider: provider.to_string(),
+            status,
+            message,
+            error_type: field("type"),
+            code: field("code"),
+        }
+    }
+}
+
+impl std::fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} API error {}: {}",
+            self.provider, self.status, self.message
+        )?;
+        match (&self.error_type, &self.code) {
+            (Some(t), Some(c)) if t != c => write!(f, " ({t}, {c})"),
+            (Some(x), _) | (None, Some(x)) => write!(f, " ({x})"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl From<ProviderError> for BizClawError {
+    fn from(error: ProviderError) -> Self {
+        BizClawError::Provider(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_openai_error_bodies() {
+        let quota = ProviderError::parse(
+            "openai",
+            StatusCode::TOO_MANY_REQUESTS,
+            r#"{"error":{"message":"You exceeded your current quota, please check your plan and billing details.","type":"insufficient_quota","param":null,"code":"insufficient_quota"}}"#,
+        );
+        assert_eq!(
+            quota.message,
+            "You exceeded your current quota, please check your plan and billing details."
+        );
+        assert_eq!(quota.error_type.as_deref(), Some("insufficient_quota"));
+        assert_eq!(quota.code.as_deref(), Some("insufficient_quota"));
+        assert_eq!(
+            quota.to_string(),
+            "openai API error 429 Too Many Requests: You exceeded your current quota, \
+             please check your plan and billing details. (insufficient_quota)"
+        );
+
+        let context = ProviderError::parse(
+            "openai",
+            StatusCode::BAD_REQUEST,
+            r#"{"error":{"message":"This model's maximum context length is 8192 tokens.","type":"invalid_request_error","param":"messages","code":"context_length_exceeded"}}"#,
+        );
+        assert_eq!(context.error_type.as_deref(), Some("invalid_request_error"));
+        assert_eq!(context.code.as_deref(), Some("context_length_exceeded"));
+        let err = BizClawError::from(context);
+        assert_eq!(
+            err.to_string(),
+            "Provider error: openai API error 400 Bad Request: This model's maximum context \
+             length is 8192 tokens. (invalid_request_error, context_length_exceeded)"
+        );
+
+        // Key errors have `code` but a null `type`.
+        let key = ProviderError::parse(
+            "openai",
+            StatusCode::UNAUTHORIZED,
+            r#"{"error":{"message":"Incorrect API key provided.","type":null,"code":"invalid_api_key"}}"#,
+        );
+        assert_eq!(key.error_type, None);
+        assert_eq!(key.code.as_deref(), Some("invalid_api_key"));
+    }
+
+    #[test]
+    fn test_parse_other_error_shapes() {
+        let anthropic = ProviderError::parse(
+            "anthropic",
+            StatusCode::from_u16(529).unwrap(),
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        );
+        assert_eq!(anthropic.message, "Overloaded");
+        assert_eq!(anthropic.error_type.as_deref(), Some("overloaded_error"));
+
+        // Gemini's OpenAI endpoint answers with a numeric code.
+        let gemini = ProviderError::parse(
+            "gemini",
+            StatusCode::BAD_REQUEST,
+            r#"{"error":{"code":400,"message":"API key not valid.","status":"INVALID_ARGUMENT"}}"#,
+        );
+        assert_eq!(gemini.message, "API key not valid.");
+        assert_eq!(gemini.code.as_deref(), Some("400"));
+
+        let ollama = ProviderError::parse(
+            "ollama",
+            StatusCode::NOT_FOUND,
+            r#"{"error":"model \"llama9\" not found, try pulling it first"}"#,
+        );
+        assert_eq!(
+            ollama.message,
+            "model \"llama9\" not found, try pulling it first"
+        );
+        assert_eq!(ollama.code, None);
+
+        let gateway = ProviderError::parse(
+            "openrouter",
+            StatusCode::BAD_GATEWAY,
+            &format!("<html>{}</html>", "x".repeat(1000)),
+        );
+        assert_eq!(gateway.message.chars().count(), MAX_RAW_BODY + 1);
+        assert!(
+            gateway
+                .to_string()
+                .starts_with("openrouter API error 502 Bad Gateway: <html>")
+        );
+    }
+}
@@ -10,6 +10,8 @@ use bizclaw_core::types::ModelInfo;
 pub enum AuthStyle {
     /// `Authorization: Bearer <key>`
     Bearer,
+    /// `api-key: <key>` (Azure OpenAI)
+    ApiKeyHeader,
     /// No authentication required (local servers).
     None,
 }
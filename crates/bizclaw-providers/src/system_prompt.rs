@@ -0,0 +1,125 @@
+//! System prompt placement — where each chat API expects the system prompt.
+//!
+//! | Format | System prompt |
+//! |--------|---------------|
+//! | OpenAI | `messages[]` entries with `role: "system"` |
+//! | Anthropic | top-level `system` content blocks |
+//!
+//! Anthropic ignores `system` role messages, so they are pulled out of the
+//! conversation and joined into one prompt. Every other provider, Gemini
+//! included, is reached through an OpenAI-compatible endpoint and takes the
+//! OpenAI format; a native Gemini client would need `systemInstruction`.
+
+use serde_json::{Value, json};
+
+/// Native system prompt format of a provider API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemPromptFormat {
+    OpenAi,
+    Anthropic,
+}
+
+impl SystemPromptFormat {
+    /// Top-level request field holding the system prompt, if separate.
+    pub fn field(self) -> Option<&'static str> {
+        match self {
+            Self::OpenAi => None,
+            Self::Anthropic => Some("system"),
+        }
+    }
+
+    /// Encode `prompt` as the value of [`field`](Self::field).
+    ///
+    /// Anthropic gets a single block with one cache breakpoint, as the
+    /// system prompt rarely changes between turns.
+    pub fn encode(self, prompt: &str) -> Value {
+        match self {
+            Self::OpenAi => json!({"role": "system", "content": prompt}),
+            Self::Anthropic => json!([{
+                "type": "text",
+                "text": prompt,
+                "cache_control": { "type": "ephemeral" }
+            }]),
+        }
+    }
+
+    /// Move the system messages out of wire-encoded `messages` into the
+    /// field of `body` this API reads them from. Returns the remaining
+    /// messages; for OpenAI, all of them unchanged.
+    pub fn place(self, body: &mut Value, messages: Vec<Value>) -> Vec<Value> {
+        let Some(field) = self.field() else {
+            return messages;
+        };
+        let (system, rest): (Vec<Value>, Vec<Value>) =
+            messages.into_iter().partition(|m| m["role"] == "system");
+        let prompt = system
+            .iter()
+            .map(|m| message_text(&m["content"]))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if !prompt.is_empty() {
+            body[field] = self.encode(&prompt);
+        }
+        rest
+    }
+}
+
+/// Text of an OpenAI message `content`: a string or an array of parts.
+fn message_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|p| p["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> Vec<Value> {
+        vec![
+            json!({"role": "system", "content": "You are helpful."}),
+            json!({"role": "user", "content": "hi"}),
+            json!({"role": "system", "content": [{"type": "text", "text": "[Knowledge Base]"}]}),
+            json!({"role": "assistant", "content": "hello"}),
+        ]
+    }
+
+    #[test]
+    fn test_openai_keeps_system_inline() {
+        let mut body = json!({});
+        let messages = SystemPromptFormat::OpenAi.place(&mut body, conversation());
+        assert_eq!(messages, conversation());
+        assert_eq!(body, json!({}));
+    }
+
+    #[test]
+    fn test_anthropic_system_is_top_level() {
+        let mut body = json!({});
+        let messages = SystemPromptFormat::Anthropic.place(&mut body, conversation());
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|m| m["role"] != "system"));
+        assert_eq!(
+            body["system"],
+            json!([{
+                "type": "text",
+                "text": "You are helpful.\n\n[Knowledge Base]",
+                "cache_control": {"type": "ephemeral"}
+            }])
+        );
+    }
+
+    #[test]
+    fn test_no_system_messages_adds_no_field() {
+        let mut body = json!({});
+        let messages = vec![json!({"role": "user", "content": "hi"})];
+        SystemPromptFormat::Anthropic.place(&mut body, messages);
+        assert!(body.get("system").is_none());
+    }
+}
@@ -0,0 +1,305 @@
+//! Provider throttling — concurrency caps and a requests-per-minute limit.
+//!
+//! A burst of channel messages would otherwise fire one provider request per
+//! message at once, tripping upstream rate limits and spiking cost. Calls
+//! over a limit wait in line; nothing is rejected. Pools created with
+//! [`Throttle::shared`] are process-wide, so every agent's provider draws
+//! from the same global and per-provider limits.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use bizclaw_core::config::ProviderLimitsConfig;
+use bizclaw_core::error::Result;
+use bizclaw_core::traits::provider::{GenerateParams, OnText, Provider, ProviderCapabilities};
+use bizclaw_core::types::{Message, ModelInfo, ProviderResponse, ToolDefinition};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Calls that had to wait for a limit, process-wide.
+static THROTTLED_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+/// Number of provider calls delayed by a limit since startup.
+pub fn throttled_total() -> u64 {
+    THROTTLED_TOTAL.load(Ordering::Relaxed)
+}
+
+/// Sliding-window limiter: at most `limit` calls per `window`.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: usize,
+    window: Duration,
+    stamps: tokio::sync::Mutex<VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit: limit.max(1),
+            window,
+            stamps: tokio::sync::Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn per_minute(limit: u32) -> Self {
+        Self::new(limit as usize, Duration::from_secs(60))
+    }
+
+    /// Wait until a call is allowed. Returns whether the caller had to wait.
+    pub async fn acquire(&self) -> bool {
+        let mut waited = false;
+        loop {
+            let wait = {
+                let mut stamps = self.stamps.lock().await;
+                let now = Instant::now();
+                while stamps
+                    .front()
+                    .is_some_and(|t| now.duration_since(*t) >= self.window)
+                {
+                    stamps.pop_front();
+                }
+                if stamps.len() < self.limit {
+                    stamps.push_back(now);
+                    return waited;
+                }
+                self.window.saturating_sub(now.duration_since(stamps[0]))
+            };
+            waited = true;
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// The limits that apply to one provider.
+#[derive(Debug, Clone, Default)]
+pub struct Throttle {
+    global: Option<Arc<Semaphore>>,
+    provider: Option<Arc<Semaphore>>,
+    rate: Option<Arc<RateLimiter>>,
+}
+
+/// Permits held for the duration of one provider call.
+#[derive(Debug)]
+pub struct ThrottlePermit {
+    _permits: Vec<OwnedSemaphorePermit>,
+}
+
+impl Throttle {
+    /// Private pools (not shared with other providers).
+    pub fn new(limits: &ProviderLimitsConfig) -> Self {
+        Self {
+            global: (limits.max_concurrent > 0)
+                .then(|| Arc::new(Semaphore::new(limits.max_concurrent))),
+            provider: (limits.max_concurrent_per_provider > 0)
+                .then(|| Arc::new(Semaphore::new(limits.max_concurrent_per_provider))),
+            rate: (limits.requests_per_minute > 0)
+                .then(|| Arc::new(RateLimiter::per_minute(limits.requests_per_minute))),
+        }
+    }
+
+    /// Process-wide pools: one global pool and one set per provider name.
+    /// Pool sizes are fixed by the first configuration that creates them.
+    pub fn shared(limits: &ProviderLimitsConfig, provider_name: &str) -> Self {
+        static GLOBAL: OnceLock<Arc<Semaphore>> = OnceLock::new();
+        static PER_PROVIDER: OnceLock<Mutex<HashMap<String, Throttle>>> = OnceLock::new();
+
+        let global = (limits.max_concurrent > 0).then(|| {
+            GLOBAL
+                .get_or_init(|| Arc::new(Semaphore::new(limits.max_concurrent)))
+                .clone()
+        });
+        let mut per_provider = PER_PROVIDER
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|p| p.into_inner());
+        let own = per_provider
+            .entry(provider_name.to_string())
+            .or_insert_with(|| Self::new(limits));
+        Self {
+            global,
+            provider: own.provider.clone(),
+            rate: own.rate.clone(),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.global.is_none() && self.provider.is_none() && self.rate.is_none()
+    }
+
+    /// Wait for every limit to admit one call. The per-provider slot is taken
+    /// before the global one so a queued provider does not hold global slots.
+    pub async fn acquire(&self, provider_name: &str) -> ThrottlePermit {
+        let start = Instant::now();
+        let mut permits = Vec::with_capacity(2);
+        let mut throttled = false;
+        if let Some(sem) = &self.provider {
+            let (permit, waited) = acquire_permit(sem).await;
+            permits.extend(permit);
+            throttled |= waited;
+        }
+        if let Some(rate) = &self.rate {
+            throttled |= rate.acquire().await;
+        }
+        if let Some(sem) = &self.global {
+            let (permit, waited) = acquire_permit(sem).await;
+            permits.extend(permit);
+            throttled |= waited;
+        }
+        if throttled {
+            THROTTLED_TOTAL.fetch_add(1, Ordering::Relaxed);
+            tracing::info!(
+                "⏳ Throttled {provider_name} call for {}ms (total throttled: {})",
+                start.elapsed().as_millis(),
+                throttled_total()
+            );
+        }
+        ThrottlePermit { _permits: permits }
+    }
+}
+
+/// Take a permit, reporting whether the caller had to queue for it.
+async fn acquire_permit(sem: &Arc<Semaphore>) -> (Option<OwnedSemaphorePermit>, bool) {
+    if let Ok(permit) = sem.clone().try_acquire_owned() {
+        return (Some(permit), false);
+    }
+    // Only fails if the semaphore is closed, which never happens here.
+    (sem.clone().acquire_owned().await.ok(), true)
+}
+
+/// A provider whose calls pass through a [`Throttle`].
+pub struct ThrottledProvider {
+    inner: Box<dyn Provider>,
+    throttle: Throttle,
+}
+
+impl ThrottledProvider {
+    pub fn new(inner: Box<dyn Provider>, throttle: Throttle) -> Self {
+        Self { inner, throttle }
+    }
+}
+
+#[async_trait]
+impl Provider for ThrottledProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn chat(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        params: &GenerateParams,
+    ) -> Result<ProviderResponse> {
+        let _permit = self.throttle.acquire(self.inner.name()).await;
+        self.inner.chat(messages, tools, params).await
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        params: &GenerateParams,
+        on_text: &OnText<'_>,
+    ) -> Result<ProviderResponse> {
+        let _permit = self.throttle.acquire(self.inner.name()).await;
+        self.inner
+            .chat_stream(messages, tools, params, on_text)
+            .await
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.inner.list_models().await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        self.inner.health_check().await
+    }
+
+    fn supports_json_mode(&self) -> bool {
+        self.inner.supports_json_mode()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Tracks how many calls run at once.
+    #[derive(Default)]
+    struct Counters {
+        running: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    struct SlowProvider(Arc<Counters>);
+
+    #[async_trait]
+    impl Provider for SlowProvider {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _tools: &[ToolDefinition],
+            _params: &GenerateParams,
+        ) -> Result<ProviderResponse> {
+            let now = self.0.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.0.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.0.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(ProviderResponse::text("ok"))
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_cap_queues_excess() {
+        let counters = Arc::new(Counters::default());
+        let limits = ProviderLimitsConfig {
+            max_concurrent: 2,
+            max_concurrent_per_provider: 0,
+            requests_per_minute: 0,
+        };
+        let provider = ThrottledProvider::new(
+            Box::new(SlowProvider(counters.clone())),
+            Throttle::new(&limits),
+        );
+        let params = GenerateParams::default();
+        let before = throttled_total();
+
+        let (a, b, c) = tokio::join!(
+            provider.chat(&[], &[], &params),
+            provider.chat(&[], &[], &params),
+            provider.chat(&[], &[], &params),
+        );
+        assert!(a.is_ok() && b.is_ok() && c.is_ok());
+        assert_eq!(counters.peak.load(Ordering::SeqCst), 2);
+        assert!(throttled_total() > before);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_waits_for_window() {
+        let limiter = RateLimiter::new(2, Duration::from_millis(100));
+        assert!(!limiter.acquire().await);
+        assert!(!limiter.acquire().await);
+        let start = Instant::now();
+        assert!(limiter.acquire().await);
+        assert!(start.elapsed() >= Duration::from_millis(80));
+    }
+}
@@ -0,0 +1,266 @@
+//! Tool-calling wire formats — translate between BizClaw's provider-agnostic
+//! [`ToolCall`] / [`ToolChoice`] types and each vendor's native JSON.
+//!
+//! | Format | Tool calls in response | Tool definitions |
+//! |--------|------------------------|------------------|
+//! | OpenAI | `choices[0].message.tool_calls[]` | `tools[].function` |
+//! | Anthropic | `content[]` blocks with `type: "tool_use"` | `tools[]` with `input_schema` |
+//! | Gemini | `candidates[0].content.parts[].functionCall` | `tools[].functionDeclarations` |
+//!
+//! The agent loop only ever sees `Vec<ToolCall>`, including parallel calls.
+
+use bizclaw_core::types::{ToolCall, ToolChoice, ToolDefinition};
+use serde_json::{Value, json};
+
+/// Native tool-calling wire format of a provider API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolWireFormat {
+    OpenAi,
+    Anthropic,
+    Gemini,
+}
+
+impl ToolWireFormat {
+    /// Encode tool definitions into the provider's `tools` field.
+    pub fn encode_tools(self, tools: &[ToolDefinition]) -> Value {
+        match self {
+            Self::OpenAi => Value::Array(
+                tools
+                    .iter()
+                    .map(|t| {
+                        json!({
+                            "type": "function",
+                            "function": {
+                                "name": t.name,
+                                "description": t.description,
+                                "parameters": t.parameters,
+                            }
+                        })
+                    })
+                    .collect(),
+            ),
+            Self::Anthropic => Value::Array(
+                tools
+                    .iter()
+                    .map(|t| {
+                        json!({
+                            "name": t.name,
+                            "description": t.description,
+                            "input_schema": t.parameters,
+                        })
+                    })
+                    .collect(),
+            ),
+            Self::Gemini => json!([{
+                "functionDeclarations": tools
+                    .iter()
+                    .map(|t| json!({
+                        "name": t.name,
+                        "description": t.description,
+                        "parameters": t.parameters,
+                    }))
+                    .collect::<Vec<_>>(),
+            }]),
+        }
+    }
+
+    /// Encode a [`ToolChoice`] into the provider's native selector.
+    pub fn encode_tool_choice(self, choice: &ToolChoice) -> Value {
+        match (self, choice) {
+            (Self::OpenAi, ToolChoice::Auto) => json!("auto"),
+            (Self::OpenAi, ToolChoice::None) => json!("none"),
+            (Self::OpenAi, ToolChoice::Required) => json!("required"),
+            (Self::OpenAi, ToolChoice::Function(name)) => {
+                json!({"type": "function", "function": {"name": name}})
+            }
+            (Self::Anthropic, ToolChoice::Auto) => json!({"type": "auto"}),
+            (Self::Anthropic, ToolChoice::None) => json!({"type": "none"}),
+            (Self::Anthropic, ToolChoice::Required) => json!({"type": "any"}),
+            (Self::Anthropic, ToolChoice::Function(name)) => json!({"type": "tool", "name": name}),
+            (Self::Gemini, ToolChoice::Auto) => json!({"functionCallingConfig": {"mode": "AUTO"}}),
+            (Self::Gemini, ToolChoice::None) => json!({"functionCallingConfig": {"mode": "NONE"}}),
+            (Self::Gemini, ToolChoice::Required) => json!({"functionCallingConfig": {"mode": "ANY"}}),
+            (Self::Gemini, ToolChoice::Function(name)) => json!({
+                "functionCallingConfig": {"mode": "ANY", "allowedFunctionNames": [name]}
+            }),
+        }
+    }
+
+    /// Extract all tool calls from a full response body in this format.
+    pub fn decode_tool_calls(self, response: &Value) -> Vec<ToolCall> {
+        match self {
+            Self::OpenAi => response["choices"]
+                .get(0)
+                .and_then(|c| c["message"]["tool_calls"].as_array())
+                .map(|calls| calls.iter().filter_map(decode_openai_call).collect())
+                .unwrap_or_default(),
+            Self::Anthropic => response["content"]
+                .as_array()
+                .map(|blocks| {
+                    blocks
+                        .iter()
+                        .filter(|b| b["type"].as_str() == Some("tool_use"))
+                        .filter_map(|b| {
+                            Some(ToolCall::function(
+                                b["id"].as_str().unwrap_or(""),
+                                b["name"].as_str()?,
+                                arguments_to_string(&b["input"]),
+                            ))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            Self::Gemini => response["candidates"]
+                .get(0)
+                .and_then(|c| c["content"]["parts"].as_array())
+                .map(|parts| {
+                    parts
+                        .iter()
+                        .filter_map(|p| p.get("functionCall"))
+                        .enumerate()
+                        .filter_map(|(i, fc)| {
+                            let name = fc["name"].as_str()?;
+                            // Gemini has no call ids — synthesize stable ones.
+                            Some(ToolCall::function(
+                                format!("call_{i}_{name}"),
+                                name,
+                                arguments_to_string(&fc["args"]),
+                            ))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Guess the format from the shape of a response body.
+    pub fn detect(response: &Value) -> Self {
+        if response.get("candidates").is_some() {
+            Self::Gemini
+        } else if response.get("choices").is_none() && response["content"].is_array() {
+            Self::Anthropic
+        } else {
+            Self::OpenAi
+        }
+    }
+}
+
+/// Decode tool calls from any supported response format.
+pub fn normalize_tool_calls(response: &Value) -> Vec<ToolCall> {
+    ToolWireFormat::detect(response).decode_tool_calls(response)
+}
+
+fn decode_openai_call(t: &Value) -> Option<ToolCall> {
+    Some(ToolCall::function(
+        t["id"].as_str().unwrap_or(""),
+        t["function"]["name"].as_str()?,
+        arguments_to_string(&t["function"]["arguments"]),
+    ))
+}
+
+/// Arguments arrive as a JSON string (OpenAI) or an object (Anthropic, Gemini,
+/// some OpenAI-compatible servers). Normalize to a JSON-encoded string.
+fn arguments_to_string(args: &Value) -> String {
+    match args {
+        Value::String(s) => s.clone(),
+        Value::Null => "{}".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(calls: &[ToolCall]) -> Vec<(String, Value)> {
+        calls
+            .iter()
+            .map(|c| {
+                (
+                    c.function.name.clone(),
+                    serde_json::from_str(&c.function.arguments).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_anthropic_and_openai_normalize_identically() {
+        let openai = json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [
+                        {"id": "call_1", "type": "function",
+                         "function": {"name": "web_search", "arguments": "{\"query\":\"bizclaw\"}"}},
+                        {"id": "call_2", "type": "function",
+                         "function": {"name": "shell", "arguments": "{\"command\":\"ls\"}"}}
+                    ]
+                },
+                "finish_reason": "tool_calls"
+            }]
+        });
+        let anthropic = json!({
+            "role": "assistant",
+            "content": [
+                {"type": "text", "text": "Let me check."},
+                {"type": "tool_use", "id": "toolu_1", "name": "web_search", "input": {"query": "bizclaw"}},
+                {"type": "tool_use", "id": "toolu_2", "name": "shell", "input": {"command": "ls"}}
+            ],
+            "stop_reason": "tool_use"
+        });
+
+        let from_openai = normalize_tool_calls(&openai);
+        let from_anthropic = normalize_tool_calls(&anthropic);
+        assert_eq!(from_openai.len(), 2);
+        assert_eq!(summary(&from_openai), summary(&from_anthropic));
+        assert_eq!(from_anthropic[0].id, "toolu_1");
+        assert_eq!(from_anthropic[1].r#type, "function");
+    }
+
+    #[test]
+    fn test_gemini_function_calls() {
+        let gemini = json!({
+            "candidates": [{
+                "content": {"parts": [
+                    {"functionCall": {"name": "web_search", "args": {"query": "bizclaw"}}}
+                ]}
+            }]
+        });
+        let calls = normalize_tool_calls(&gemini);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].function.name, "web_search");
+        assert!(!calls[0].id.is_empty());
+    }
+
+    #[test]
+    fn test_no_tool_calls() {
+        let openai = json!({"choices": [{"message": {"content": "hi"}}]});
+        assert!(normalize_tool_calls(&openai).is_empty());
+    }
+
+    #[test]
+    fn test_encode_tool_choice() {
+        let f = ToolChoice::Function("shell".into());
+        assert_eq!(ToolWireFormat::OpenAi.encode_tool_choice(&f)["function"]["name"], "shell");
+        assert_eq!(ToolWireFormat::Anthropic.encode_tool_choice(&ToolChoice::Required)["type"], "any");
+        assert_eq!(
+            ToolWireFormat::Gemini.encode_tool_choice(&ToolChoice::None)["functionCallingConfig"]["mode"],
+            "NONE"
+        );
+    }
+
+    #[test]
+    fn test_encode_tools_anthropic_uses_input_schema() {
+        let defs = vec![ToolDefinition {
+            name: "shell".into(),
+            description: "Run a command".into(),
+            parameters: json!({"type": "object"}),
+        }];
+        let encoded = ToolWireFormat::Anthropic.encode_tools(&defs);
+        assert_eq!(encoded[0]["input_schema"]["type"], "object");
+        let encoded = ToolWireFormat::OpenAi.encode_tools(&defs);
+        assert_eq!(encoded[0]["function"]["name"], "shell");
+    }
+}
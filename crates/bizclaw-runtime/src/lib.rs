@@ -8,6 +8,7 @@ pub mod native;
 
 use async_trait::async_trait;
 use bizclaw_core::error::Result;
+use bizclaw_core::text::truncate_chars;
 use bizclaw_core::traits::runtime::RuntimeAdapter;
 
 /// Native runtime adapter — runs commands directly on the host.
@@ -59,7 +60,7 @@ impl RuntimeAdapter for NativeRuntime {
                 "Command timed out after {}s ({}min): {}",
                 self.timeout_secs,
                 self.timeout_secs / 60,
-                &command[..command.len().min(100)]
+                truncate_chars(command, 100)
             ))
         })??;
 
@@ -10,9 +10,12 @@
 //! tick after the retry delay has elapsed. Permanently failed tasks
 //! (exhausted all retries) generate an urgent notification to the admin.
 
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 
+use bizclaw_core::cancel::CancelToken;
+use bizclaw_core::text::truncate_chars;
 use chrono::Utc;
 use tokio::sync::Mutex;
 
@@ -30,6 +33,8 @@ pub struct SchedulerEngine {
     /// In practice, this sends a prompt to the Agent or fires a webhook.
     #[allow(clippy::type_complexity)]
     on_trigger: Option<Arc<dyn Fn(&Task) -> String + Send + Sync>>,
+    /// Cancellation tokens for runs currently executing, keyed by task ID.
+    running: HashMap<String, CancelToken>,
 }
 
 impl SchedulerEngine {
@@ -42,6 +47,7 @@ impl SchedulerEngine {
             store,
             router: NotifyRouter::new(),
             on_trigger: None,
+            running: HashMap::new(),
         };
         // Compute next_run for all cron tasks
         engine.recompute_cron_times();
@@ -109,6 +115,45 @@ impl SchedulerEngine {
         }
     }
 
+    /// Mark a task's run as started and return its cancellation token.
+    pub fn start_run(&mut self, id: &str) -> CancelToken {
+        let token = CancelToken::new();
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
+            task.status = TaskStatus::Running;
+        }
+        self.running.insert(id.to_string(), token.clone());
+        token
+    }
+
+    /// Forget a finished run. Returns `true` if the run had been cancelled.
+    pub fn finish_run(&mut self, id: &str) -> bool {
+        self.running
+            .remove(id)
+            .is_some_and(|token| token.is_cancelled())
+    }
+
+    /// Whether a run of this task is currently executing.
+    pub fn is_running(&self, id: &str) -> bool {
+        self.running.contains_key(id)
+    }
+
+    /// Cancel the in-flight run of a task. The current run is not retried,
+    /// but the task keeps its schedule and fires again next cycle.
+    /// Returns `false` if the task is not running.
+    pub fn cancel_task(&mut self, id: &str) -> bool {
+        let Some(token) = self.running.get(id) else {
+            return false;
+        };
+        token.cancel();
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
+            tracing::info!("🚫 Task cancelled: '{}'", task.name);
+            task.status = TaskStatus::Cancelled;
+            task.last_error = Some("Cancelled by user".into());
+        }
+        self.save();
+        true
+    }
+
     /// Tick — called periodically to check and fire due tasks.
     /// Returns list of triggered task names + notification bodies.
     /// Now also handles RetryPending tasks whose retry_at has elapsed.
@@ -310,47 +355,55 @@ pub async fn spawn_scheduler_with_agent<F, Fut, R, RFut>(
 
         // Execute each triggered action with retry support
         for (task_id, task_name, action) in &triggered_tasks {
-            let execution_result: Result<String, String> = match action {
-                TaskAction::AgentPrompt(prompt) => {
-                    tracing::info!(
-                        "🤖 Executing agent prompt for task '{}': {}",
-                        task_name,
-                        if prompt.len() > 100 {
-                            &prompt[..100]
-                        } else {
-                            prompt
-                        }
-                    );
-                    agent_callback(prompt.clone()).await
-                }
-                TaskAction::Webhook {
-                    url,
-                    method,
-                    body,
-                    headers,
-                } => {
-                    tracing::info!(
-                        "🌐 Firing webhook for task '{}': {} {}",
-                        task_name,
+            let cancel = engine.lock().await.start_run(task_id);
+            let execution = async {
+                match action {
+                    TaskAction::AgentPrompt(prompt) => {
+                        tracing::info!(
+                            "🤖 Executing agent prompt for task '{}': {}",
+                            task_name,
+                            truncate_chars(prompt, 100)
+                        );
+                        agent_callback(prompt.clone()).await
+                    }
+                    TaskAction::Webhook {
+                        url,
                         method,
-                        url
-                    );
-                    execute_webhook(&http_client, url, method, body.as_deref(), headers).await
-                }
-                TaskAction::Notify(msg) => {
-                    tracing::info!("📢 Notification for task '{}': {}", task_name, msg);
-                    Ok(msg.clone())
+                        body,
+                        headers,
+                    } => {
+                        tracing::info!(
+                            "🌐 Firing webhook for task '{}': {} {}",
+                            task_name,
+                            method,
+                            url
+                        );
+                        execute_webhook(&http_client, url, method, body.as_deref(), headers).await
+                    }
+                    TaskAction::Notify(msg) => {
+                        tracing::info!("📢 Notification for task '{}': {}", task_name, msg);
+                        Ok(msg.clone())
+                    }
                 }
             };
+            let execution_result: Result<String, String> = tokio::select! {
+                result = execution => result,
+                _ = cancel.cancelled() => Err("Cancelled by user".into()),
+            };
 
             // Handle result with retry logic
             let mut eng = engine.lock().await;
+            if eng.finish_run(task_id) {
+                // Cancelled: no retry and no result dispatch; the schedule is untouched.
+                tracing::info!("🚫 Task '{}' run cancelled", task_name);
+                continue;
+            }
             if let Some(task) = eng.tasks_mut().iter_mut().find(|t| t.id == *task_id) {
                 match execution_result {
                     Ok(ref response) => {
                         task.mark_success();
-                        let truncated = if response.len() > 200 {
-                            format!("{}...", &response[..200])
+                        let truncated = if response.chars().count() > 200 {
+                            format!("{}...", truncate_chars(response, 200))
                         } else {
                             response.clone()
                         };
@@ -368,7 +421,7 @@ pub async fn spawn_scheduler_with_agent<F, Fut, R, RFut>(
                                      Action: {}",
                                     task_name,
                                     task.fail_count,
-                                    if e.len() > 200 { &e[..200] } else { e },
+                                    truncate_chars(e, 200),
                                     action_summary(action),
                                 ),
                                 "scheduler",
@@ -434,12 +487,12 @@ async fn execute_webhook(
 fn action_summary(action: &TaskAction) -> String {
     match action {
         TaskAction::AgentPrompt(p) => {
-            let truncated = if p.len() > 100 { &p[..100] } else { p };
+            let truncated = truncate_chars(p, 100);
             format!("Agent: {}", truncated)
         }
         TaskAction::Webhook { url, method, .. } => format!("Webhook: {} {}", method, url),
         TaskAction::Notify(m) => {
-            let truncated = if m.len() > 100 { &m[..100] } else { m };
+            let truncated = truncate_chars(m, 100);
             format!("Notify: {}", truncated)
         }
     }
@@ -503,6 +556,86 @@ mod tests {
         std::fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn test_cancel_running_task() {
+        let dir = std::env::temp_dir().join("bizclaw-test-cancel");
+        let mut engine = SchedulerEngine::new(&dir);
+        let task = Task::interval("long", 60, TaskAction::Notify("hello".into()));
+        let id = task.id.clone();
+        engine.add_task(task);
+
+        // Nothing to cancel while idle
+        assert!(!engine.cancel_task(&id));
+
+        let token = engine.start_run(&id);
+        assert!(engine.is_running(&id));
+        assert!(engine.cancel_task(&id));
+        assert!(token.is_cancelled());
+
+        let t = engine.list_tasks().iter().find(|t| t.id == id).unwrap();
+        assert_eq!(t.status, TaskStatus::Cancelled);
+        assert!(t.enabled);
+        assert!(engine.finish_run(&id));
+        assert!(!engine.is_running(&id));
+
+        // Still scheduled for the next cycle
+        engine.tasks_mut()[0].next_run = Some(Utc::now() - chrono::Duration::seconds(1));
+        assert!(engine.list_tasks()[0].should_run());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_halts_agent_run() {
+        let dir = std::env::temp_dir().join("bizclaw-test-cancel-loop");
+        std::fs::remove_dir_all(&dir).ok();
+        let mut engine = SchedulerEngine::new(&dir);
+        let mut task = Task::interval("hang", 3600, TaskAction::AgentPrompt("wait".into()));
+        task.next_run = Some(Utc::now() - chrono::Duration::seconds(1));
+        let id = task.id.clone();
+        engine.add_task(task);
+        let engine = Arc::new(Mutex::new(engine));
+
+        let delivered = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let delivered_flag = delivered.clone();
+        let handle = tokio::spawn(spawn_scheduler_with_agent(
+            engine.clone(),
+            |_prompt| std::future::pending::<Result<String, String>>(),
+            move |_name, _result| {
+                delivered_flag.store(true, std::sync::atomic::Ordering::SeqCst);
+                async {}
+            },
+            3600,
+        ));
+
+        let wait_until = |want_running: bool| {
+            let engine = engine.clone();
+            let id = id.clone();
+            async move {
+                for _ in 0..200 {
+                    if engine.lock().await.is_running(&id) == want_running {
+                        return;
+                    }
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                }
+                panic!("task running state never became {want_running}");
+            }
+        };
+
+        wait_until(true).await;
+        assert!(engine.lock().await.cancel_task(&id));
+        wait_until(false).await;
+
+        {
+            let eng = engine.lock().await;
+            let t = &eng.list_tasks()[0];
+            assert_eq!(t.status, TaskStatus::Cancelled);
+            assert_eq!(t.fail_count, 0, "cancelled runs are not retried");
+        }
+        assert!(!delivered.load(std::sync::atomic::Ordering::SeqCst));
+        handle.abort();
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn test_enable_resets_retry_state() {
         let dir = std::env::temp_dir().join("bizclaw-test-enable-reset");
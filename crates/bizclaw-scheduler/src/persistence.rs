@@ -144,6 +144,7 @@ impl SchedulerDb {
             TaskStatus::Completed => "completed",
             TaskStatus::Failed(_) => "failed",
             TaskStatus::Disabled => "disabled",
+            TaskStatus::Cancelled => "cancelled",
             TaskStatus::RetryPending { .. } => "retry_pending",
         };
 
@@ -265,6 +266,7 @@ impl SchedulerDb {
                     "completed" => TaskStatus::Completed,
                     "failed" => TaskStatus::Failed(last_error.clone().unwrap_or_else(|| "unknown".into())),
                     "disabled" => TaskStatus::Disabled,
+                    "cancelled" => TaskStatus::Cancelled,
                     "retry_pending" => {
                         // Reconstruct retry_at from next_run
                         let retry_at = next_run_str.as_ref()
@@ -7,6 +7,7 @@
 //! - Permanent failure notification after exhausting retries
 //! - fail_count resets on success
 
+use bizclaw_core::text::truncate_chars;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -142,6 +143,8 @@ pub enum TaskStatus {
     Completed,
     Failed(String),
     Disabled,
+    /// The current run was stopped by the user; the task runs again next cycle.
+    Cancelled,
     /// Waiting for retry after failure (with scheduled retry time and attempt number).
     RetryPending {
         retry_at: DateTime<Utc>,
@@ -305,7 +308,7 @@ impl Task {
                     "Failed after {}/{} attempts: {}",
                     self.fail_count,
                     self.retry.max_retries,
-                    if e.len() > 80 { &e[..80] } else { e }
+                    truncate_chars(e, 80)
                 )
             }
             _ => String::new(),
@@ -4,6 +4,7 @@
 //! can be accessed by the agent.
 
 use bizclaw_core::config::AutonomyConfig;
+use bizclaw_core::text::truncate_chars;
 use std::collections::HashSet;
 
 /// Manages command and path allowlists for security enforcement.
@@ -31,7 +32,7 @@ impl Allowlist {
         if command.chars().any(|c| DANGEROUS_CHARS.contains(&c)) {
             tracing::warn!(
                 "[security] Blocked command with shell metacharacters: {:?}",
-                &command[..command.len().min(80)]
+                truncate_chars(command, 80)
             );
             return false;
         }
@@ -105,6 +106,9 @@ mod tests {
             allowed_commands: commands.iter().map(|s| s.to_string()).collect(),
             forbidden_paths: paths.iter().map(|s| s.to_string()).collect(),
             workspace_only: false,
+            workspace_dir: String::new(),
+            allowed_tools: None,
+            denied_tools: Vec::new(),
         }
     }
 
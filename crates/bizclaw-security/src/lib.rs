@@ -9,6 +9,7 @@ pub mod secrets;
 use async_trait::async_trait;
 use bizclaw_core::config::AutonomyConfig;
 use bizclaw_core::error::Result;
+use bizclaw_core::text::truncate_chars;
 use bizclaw_core::traits::SecurityPolicy;
 
 /// Default security policy based on configuration.
@@ -32,7 +33,7 @@ impl SecurityPolicy for DefaultSecurityPolicy {
                 tracing::warn!(
                     "Security: command contains dangerous operator '{}': '{}'",
                     pattern,
-                    &command[..command.len().min(80)]
+                    truncate_chars(command, 80)
                 );
                 return Ok(false);
             }
@@ -15,6 +15,7 @@
 
 use async_trait::async_trait;
 use bizclaw_core::error::Result;
+use bizclaw_core::text::truncate_chars;
 use bizclaw_core::traits::Tool;
 use bizclaw_core::types::{ToolDefinition, ToolResult};
 
@@ -205,6 +206,7 @@ impl Tool for BrowserTool {
                 tool_call_id: String::new(),
                 output: format!("⏳ Waited {}ms", ms),
                 success: true,
+                data: None,
             });
         }
 
@@ -221,6 +223,7 @@ impl Tool for BrowserTool {
                     self.base_url
                 ),
                 success: false,
+                data: None,
             });
         }
 
@@ -260,6 +263,7 @@ impl Tool for BrowserTool {
                         result["tabId"].as_str().unwrap_or("—")
                     ),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -283,7 +287,7 @@ impl Tool for BrowserTool {
                 let body = resp.text().await.unwrap_or_default();
                 // Truncate if too large
                 let display = if body.len() > 6000 {
-                    format!("{}...\n[truncated, {} bytes total]", &body[..6000], body.len())
+                    format!("{}...\n[truncated, {} bytes total]", truncate_chars(&body, 6000), body.len())
                 } else {
                     body
                 };
@@ -292,6 +296,7 @@ impl Tool for BrowserTool {
                     tool_call_id: String::new(),
                     output: format!("📸 Page snapshot (filter={}):\n{}", filter, display),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -318,6 +323,7 @@ impl Tool for BrowserTool {
                     tool_call_id: String::new(),
                     output: format!("🖱️ Clicked element: {}\nResult: {}", elem_ref, result),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -351,6 +357,7 @@ impl Tool for BrowserTool {
                     tool_call_id: String::new(),
                     output: format!("⌨️ Filled '{}' into element: {}\n{}", value, elem_ref, result),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -369,7 +376,7 @@ impl Tool for BrowserTool {
 
                 let body = resp.text().await.unwrap_or_default();
                 let display = if body.len() > 6000 {
-                    format!("{}...\n[truncated, {} bytes]", &body[..6000], body.len())
+                    format!("{}...\n[truncated, {} bytes]", truncate_chars(&body, 6000), body.len())
                 } else {
                     body
                 };
@@ -378,6 +385,7 @@ impl Tool for BrowserTool {
                     tool_call_id: String::new(),
                     output: format!("📄 Page text (~{} tokens):\n{}", display.split_whitespace().count(), display),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -409,6 +417,7 @@ impl Tool for BrowserTool {
                     tool_call_id: String::new(),
                     output: format!("⌨️ Pressed '{}' on {}\n{}", key, elem_ref, result),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -435,6 +444,7 @@ impl Tool for BrowserTool {
                     tool_call_id: String::new(),
                     output: format!("🔧 JS result:\n{}", serde_json::to_string_pretty(&result).unwrap_or_default()),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -461,6 +471,7 @@ impl Tool for BrowserTool {
                     tool_call_id: String::new(),
                     output: format!("📜 Scrolled {}\n{}", direction, result),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -472,6 +483,7 @@ impl Tool for BrowserTool {
                     tool_call_id: String::new(),
                     output: format!("⏳ Waited {}ms", ms),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -488,6 +500,7 @@ impl Tool for BrowserTool {
                     tool_call_id: String::new(),
                     output: format!("🌐 Browser instances:\n{}", serde_json::to_string_pretty(&result).unwrap_or_default()),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -504,6 +517,7 @@ impl Tool for BrowserTool {
                     tool_call_id: String::new(),
                     output: format!("📑 Open tabs:\n{}", serde_json::to_string_pretty(&result).unwrap_or_default()),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -523,6 +537,7 @@ impl Tool for BrowserTool {
                     tool_call_id: String::new(),
                     output: format!("🔴 Closed instance: {}", inst_id),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -530,6 +545,7 @@ impl Tool for BrowserTool {
                 tool_call_id: String::new(),
                 output: format!("Unknown action: {}. Available: navigate, snapshot, click, fill, text, press, evaluate, scroll, wait, instances, tabs, close", action),
                 success: false,
+                data: None,
             }),
         }
     }
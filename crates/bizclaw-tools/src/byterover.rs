@@ -17,6 +17,7 @@
 
 use async_trait::async_trait;
 use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::text::truncate_chars;
 use bizclaw_core::traits::Tool;
 use bizclaw_core::types::{ToolDefinition, ToolResult};
 use std::path::PathBuf;
@@ -111,6 +112,7 @@ impl Tool for ByteRoverQueryTool {
                                 stdout.trim()
                             ),
                             success: true,
+                            data: None,
                         });
                     } else {
                         debug!("brv query returned empty or failed: {}", stderr);
@@ -134,6 +136,7 @@ impl Tool for ByteRoverQueryTool {
                         results
                     ),
                     success: true,
+                    data: None,
                 });
             }
         }
@@ -147,6 +150,7 @@ impl Tool for ByteRoverQueryTool {
                 query
             ),
             success: true,
+            data: None,
         })
     }
 }
@@ -233,6 +237,7 @@ impl Tool for ByteRoverCurateTool {
                                 stdout.trim()
                             ),
                             success: true,
+                            data: None,
                         });
                     } else {
                         warn!("brv curate failed: {}", stderr);
@@ -276,6 +281,7 @@ impl Tool for ByteRoverCurateTool {
                 filepath.display()
             ),
             success: true,
+            data: None,
         })
     }
 }
@@ -311,7 +317,7 @@ fn search_context_tree(dir: &std::path::Path, query: &str) -> Result<String> {
 
                             // Extract first ~500 chars
                             let snippet = if content.len() > 500 {
-                                format!("{}...", &content[..500])
+                                format!("{}...", truncate_chars(&content, 500))
                             } else {
                                 content.clone()
                             };
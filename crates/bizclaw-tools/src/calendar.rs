@@ -372,6 +372,7 @@ impl Tool for CalendarTool {
             tool_call_id: String::new(),
             output,
             success: true,
+            data: None,
         })
     }
 }
@@ -75,12 +75,14 @@ impl Tool for ConfigManagerTool {
                         tool_call_id: String::new(),
                         output: format!("Config path: {}\n\n{}", config_path.display(), masked),
                         success: true,
+                        data: None,
                     })
                 } else {
                     Ok(ToolResult {
                         tool_call_id: String::new(),
                         output: format!("Config file not found at {}", config_path.display()),
                         success: false,
+                        data: None,
                     })
                 }
             }
@@ -111,6 +113,7 @@ impl Tool for ConfigManagerTool {
                         None => format!("Key '{key}' not found in config"),
                     },
                     success: value.is_some(),
+                    data: None,
                 })
             }
 
@@ -131,6 +134,7 @@ impl Tool for ConfigManagerTool {
                             key
                         ),
                         success: false,
+                        data: None,
                     });
                 }
 
@@ -162,6 +166,7 @@ impl Tool for ConfigManagerTool {
                     tool_call_id: String::new(),
                     output: format!("Updated: {} = {}", key, new_value),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -179,6 +184,7 @@ impl Tool for ConfigManagerTool {
                     tool_call_id: String::new(),
                     output: format!("Config keys ({}):\n{}", keys.len(), keys.join("\n")),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -205,6 +205,7 @@ impl Tool for DocumentReaderTool {
             tool_call_id: String::new(),
             output: format!("Extracted content from {}:\n\n{}", path.display(), content),
             success: true,
+            data: None,
         })
     }
 }
@@ -83,6 +83,7 @@ impl Tool for EditFileTool {
                     "No match found for the specified text in {path}. Make sure old_text matches exactly (including whitespace and newlines)."
                 ),
                 success: false,
+                data: None,
             });
         }
 
@@ -93,6 +94,7 @@ impl Tool for EditFileTool {
                     "DRY RUN: Found {count} occurrence(s) of old_text in {path}. Would replace with new_text."
                 ),
                 success: true,
+                data: None,
             });
         }
 
@@ -110,6 +112,7 @@ impl Tool for EditFileTool {
                 new_content.len()
             ),
             success: true,
+            data: None,
         })
     }
 }
@@ -4,6 +4,7 @@
 
 use async_trait::async_trait;
 use bizclaw_core::error::Result;
+use bizclaw_core::text::truncate_chars;
 use bizclaw_core::traits::Tool;
 use bizclaw_core::types::{ToolDefinition, ToolResult};
 
@@ -177,6 +178,7 @@ impl Tool for ExecuteCodeTool {
                 tool_call_id: String::new(),
                 output: block_reason,
                 success: false,
+                data: None,
             });
         }
 
@@ -230,6 +232,7 @@ impl Tool for ExecuteCodeTool {
                         tool_call_id: String::new(),
                         output: format!("Compilation failed:\n{}", stderr),
                         success: false,
+                        data: None,
                     });
                 }
                 Err(e) => {
@@ -238,6 +241,7 @@ impl Tool for ExecuteCodeTool {
                         tool_call_id: String::new(),
                         output: format!("Compiler not found ({}): {}", config.command, e),
                         success: false,
+                        data: None,
                     });
                 }
                 _ => {}
@@ -287,7 +291,7 @@ impl Tool for ExecuteCodeTool {
                     let stdout_display = if stdout.len() > 5000 {
                         format!(
                             "{}...\n[truncated, {} bytes total]",
-                            &stdout[..5000],
+                            truncate_chars(&stdout, 5000),
                             stdout.len()
                         )
                     } else {
@@ -297,7 +301,7 @@ impl Tool for ExecuteCodeTool {
                 }
                 if !stderr.is_empty() {
                     let stderr_display = if stderr.len() > 2000 {
-                        format!("{}...\n[truncated]", &stderr[..2000])
+                        format!("{}...\n[truncated]", truncate_chars(&stderr, 2000))
                     } else {
                         stderr.to_string()
                     };
@@ -311,6 +315,7 @@ impl Tool for ExecuteCodeTool {
                     tool_call_id: String::new(),
                     output: result,
                     success: o.status.success(),
+                    data: None,
                 })
             }
             Ok(Err(e)) => Ok(ToolResult {
@@ -320,11 +325,13 @@ impl Tool for ExecuteCodeTool {
                     config.command, e
                 ),
                 success: false,
+                data: None,
             }),
             Err(_) => Ok(ToolResult {
                 tool_call_id: String::new(),
                 output: format!("⏰ Execution timed out after {}s", timeout),
                 success: false,
+                data: None,
             }),
         }
     }
@@ -4,6 +4,7 @@
 
 use async_trait::async_trait;
 use bizclaw_core::error::Result;
+use bizclaw_core::text::truncate_chars;
 use bizclaw_core::traits::Tool;
 use bizclaw_core::types::{ToolDefinition, ToolResult};
 
@@ -146,6 +147,7 @@ impl Tool for FileTool {
                 tool_call_id: String::new(),
                 output: block_reason,
                 success: false,
+                data: None,
             });
         }
 
@@ -182,11 +184,11 @@ impl Tool for FileTool {
                     let line_count = content.lines().count();
                     if content.len() > 10000 {
                         format!(
-                            "File: {} ({} lines, {} bytes):\n{}...\n[truncated at 10000 bytes]",
+                            "File: {} ({} lines, {} bytes):\n{}...\n[truncated at 10000 characters]",
                             path,
                             line_count,
                             content.len(),
-                            &content[..10000]
+                            truncate_chars(&content, 10000)
                         )
                     } else {
                         content
@@ -279,6 +281,7 @@ impl Tool for FileTool {
             tool_call_id: String::new(),
             output: result,
             success: true,
+            data: None,
         })
     }
 }
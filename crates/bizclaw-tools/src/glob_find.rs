@@ -114,6 +114,7 @@ impl Tool for GlobTool {
             tool_call_id: String::new(),
             output,
             success: true,
+            data: None,
         })
     }
 }
@@ -2,6 +2,7 @@
 
 use async_trait::async_trait;
 use bizclaw_core::error::Result;
+use bizclaw_core::text::truncate_chars;
 use bizclaw_core::traits::Tool;
 use bizclaw_core::types::{ToolDefinition, ToolResult};
 use std::path::Path;
@@ -93,6 +94,7 @@ impl Tool for GrepTool {
                 tool_call_id: String::new(),
                 output: format!("Path not found: {path}"),
                 success: false,
+                data: None,
             });
         }
 
@@ -118,6 +120,7 @@ impl Tool for GrepTool {
             tool_call_id: String::new(),
             output,
             success: true,
+            data: None,
         })
     }
 }
@@ -140,7 +143,7 @@ fn search_file(path: &Path, re: &regex::Regex, matches: &mut Vec<String>, max: u
         }
         if re.is_match(line) {
             let display_line = if line.len() > 200 {
-                format!("{}...", &line[..200])
+                format!("{}...", truncate_chars(line, 200))
             } else {
                 line.to_string()
             };
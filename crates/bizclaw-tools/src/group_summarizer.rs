@@ -270,6 +270,7 @@ impl Tool for GroupSummarizerTool {
             tool_call_id: String::new(),
             output,
             success: true,
+            data: None,
         })
     }
 }
@@ -2,6 +2,7 @@
 
 use async_trait::async_trait;
 use bizclaw_core::error::Result;
+use bizclaw_core::text::truncate_chars;
 use bizclaw_core::traits::Tool;
 use bizclaw_core::types::{ToolDefinition, ToolResult};
 
@@ -77,6 +78,7 @@ impl Tool for HttpRequestTool {
                 tool_call_id: String::new(),
                 output: "Blocked: Only HTTP/HTTPS schemes allowed".into(),
                 success: false,
+                data: None,
             });
         }
         // Block private/internal destinations
@@ -99,6 +101,7 @@ impl Tool for HttpRequestTool {
                 tool_call_id: String::new(),
                 output: format!("Blocked: Cannot access internal/private network ({host_no_port})"),
                 success: false,
+                data: None,
             });
         }
 
@@ -163,15 +166,39 @@ impl Tool for HttpRequestTool {
             .collect::<Vec<_>>()
             .join("\n");
 
+        let is_json = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.contains("json"));
+
         let body_text = response.text().await.map_err(|e| {
             bizclaw_core::error::BizClawError::Tool(format!("Read body failed: {e}"))
         })?;
 
+        // Small JSON bodies are returned as structured data instead of raw text
+        if is_json
+            && body_text.len() <= 8000
+            && let Ok(value) = serde_json::from_str::<serde_json::Value>(&body_text)
+        {
+            let summary = format!(
+                "HTTP {} {} → {} ({:.0}ms)\n\nHeaders:\n{}\n\nBody (JSON):",
+                method,
+                url,
+                status,
+                elapsed.as_millis(),
+                headers
+            );
+            let mut result = ToolResult::json(summary, value);
+            result.success = status.is_success();
+            return Ok(result);
+        }
+
         // Truncate very large responses
         let body_display = if body_text.len() > 8000 {
             format!(
                 "{}...\n\n[truncated, {} total bytes]",
-                &body_text[..8000],
+                truncate_chars(&body_text, 8000),
                 body_text.len()
             )
         } else {
@@ -192,34 +219,13 @@ impl Tool for HttpRequestTool {
             tool_call_id: String::new(),
             output,
             success: status.is_success(),
+            data: None,
         })
     }
 }
 
-/// Check if a URL is blocked by SSRF protection.
-/// Extracted for testability.
-pub fn is_url_blocked(url: &str) -> Option<String> {
-    let lower_url = url.to_lowercase();
-    if !lower_url.starts_with("http://") && !lower_url.starts_with("https://") {
-        return Some("Only HTTP/HTTPS schemes allowed".into());
-    }
-    let blocked_patterns = [
-        "127.0.0.1", "localhost", "0.0.0.0", "[::1]", "[::0]",
-        "169.254.", "metadata.google", "metadata.aws",
-        "10.",  "192.168.",
-        "172.16.", "172.17.", "172.18.", "172.19.",
-        "172.20.", "172.21.", "172.22.", "172.23.",
-        "172.24.", "172.25.", "172.26.", "172.27.",
-        "172.28.", "172.29.", "172.30.", "172.31.",
-    ];
-    let host_part = lower_url.split("://").nth(1).unwrap_or("");
-    let host = host_part.split('/').next().unwrap_or("");
-    let host_no_port = host.split(':').next().unwrap_or("");
-    if blocked_patterns.iter().any(|p| host_no_port.contains(p)) {
-        return Some(format!("Cannot access internal/private network ({host_no_port})"));
-    }
-    None
-}
+/// SSRF check, shared with the knowledge base URL fetcher.
+pub use bizclaw_core::net::is_url_blocked;
 
 #[cfg(test)]
 mod tests {
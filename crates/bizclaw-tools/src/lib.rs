@@ -22,6 +22,9 @@
 //! | document_reader | Offline PDF/DOCX/XLSX/CSV reader |
 //! | brv_query | ByteRover Context Tree search (92% accuracy) |
 //! | brv_curate | Add knowledge to ByteRover Context Tree |
+//! | usage_stats | Agent's own token usage, requests, latency |
+//! | notes | Persistent key-value scratchpad per agent |
+//! | message_agent | Direct messages to linked agents |
 //! + MCP server tools (dynamic)
 
 pub mod browser;
@@ -37,19 +40,26 @@ pub mod grep_search;
 pub mod group_summarizer;
 pub mod http_request;
 pub mod memory_search;
+pub mod message_agent;
+pub mod notes;
 pub mod orchestration;
 pub mod plan_tool;
 pub mod plan_store;
 pub mod registry;
 pub mod session_context;
 pub mod shell;
+pub mod usage_stats;
 pub mod web_search;
 
 use bizclaw_core::traits::Tool;
 
 /// Tool registry — manages available tools.
+///
+/// Clones share the tool instances, so an agent can speak with another
+/// agent's tools without building them again.
+#[derive(Clone)]
 pub struct ToolRegistry {
-    tools: Vec<Box<dyn Tool>>,
+    tools: Vec<std::sync::Arc<dyn Tool>>,
 }
 
 impl ToolRegistry {
@@ -58,7 +68,7 @@ impl ToolRegistry {
     }
 
     pub fn register(&mut self, tool: Box<dyn Tool>) {
-        self.tools.push(tool);
+        self.tools.push(tool.into());
     }
 
     pub fn get(&self, name: &str) -> Option<&dyn Tool> {
@@ -133,14 +143,49 @@ impl ToolRegistry {
         self.register(Box::new(session_context::SessionContextTool::new(info)));
     }
 
+    /// Register the usage_stats tool for an agent, replacing any previous one.
+    pub fn register_usage_stats(
+        &mut self,
+        agent_name: &str,
+        store: std::sync::Arc<dyn bizclaw_db::store::DataStore>,
+    ) {
+        self.tools.retain(|t| t.name() != "usage_stats");
+        self.register(Box::new(usage_stats::UsageStatsTool::new(agent_name, store)));
+    }
+
+    /// Register the notes tool for an agent, replacing any previous one.
+    pub fn register_notes(
+        &mut self,
+        agent_name: &str,
+        store: std::sync::Arc<dyn bizclaw_db::store::DataStore>,
+    ) {
+        self.tools.retain(|t| t.name() != "notes");
+        self.register(Box::new(notes::NotesTool::new(agent_name, store)));
+    }
+
+    /// Register the message_agent tool for an agent, replacing any previous one.
+    pub fn register_message_agent(
+        &mut self,
+        agent_name: &str,
+        store: std::sync::Arc<dyn bizclaw_db::store::DataStore>,
+    ) {
+        self.tools.retain(|t| t.name() != "message_agent");
+        self.register(Box::new(message_agent::MessageAgentTool::new(agent_name, store)));
+    }
+
     /// Register multiple tools at once (e.g., from MCP bridge).
     pub fn register_many(&mut self, tools: Vec<Box<dyn Tool>>) {
         for tool in tools {
             tracing::debug!("📦 Registered tool: {}", tool.name());
-            self.tools.push(tool);
+            self.tools.push(tool.into());
         }
     }
 
+    /// Keep only the tools whose name passes `keep`.
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.tools.retain(|t| keep(t.name()));
+    }
+
     /// Get the count of registered tools.
     pub fn count(&self) -> usize {
         self.tools.len()
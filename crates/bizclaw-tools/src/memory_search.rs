@@ -2,6 +2,7 @@
 
 use async_trait::async_trait;
 use bizclaw_core::error::Result;
+use bizclaw_core::text::truncate_chars;
 use bizclaw_core::traits::Tool;
 use bizclaw_core::types::{ToolDefinition, ToolResult};
 
@@ -68,6 +69,7 @@ impl Tool for MemorySearchTool {
                     tool_call_id: String::new(),
                     output: "Memory backend not available.".into(),
                     success: false,
+                    data: None,
                 });
             }
         };
@@ -79,6 +81,7 @@ impl Tool for MemorySearchTool {
                         tool_call_id: String::new(),
                         output: format!("No memories found matching '{query}'."),
                         success: true,
+                        data: None,
                     })
                 } else {
                     let mut output = format!(
@@ -88,7 +91,7 @@ impl Tool for MemorySearchTool {
                     );
                     for (i, r) in results.iter().enumerate() {
                         let content = if r.entry.content.len() > 500 {
-                            format!("{}...", &r.entry.content[..500])
+                            format!("{}...", truncate_chars(&r.entry.content, 500))
                         } else {
                             r.entry.content.clone()
                         };
@@ -104,6 +107,7 @@ impl Tool for MemorySearchTool {
                         tool_call_id: String::new(),
                         output,
                         success: true,
+                        data: None,
                     })
                 }
             }
@@ -111,6 +115,7 @@ impl Tool for MemorySearchTool {
                 tool_call_id: String::new(),
                 output: format!("Memory search error: {e}"),
                 success: false,
+                data: None,
             }),
         }
     }
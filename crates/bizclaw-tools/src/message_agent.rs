@@ -0,0 +1,247 @@
+//! Message agent tool — direct messages between linked agents.
+//!
+//! Messages travel over agent links, so an agent can only message agents a
+//! link lets it reach, and a link holds at most `max_concurrent` undelivered
+//! messages. Each message is pending until the recipient checks its inbox,
+//! delivered once fetched, and read once acknowledged.
+
+use async_trait::async_trait;
+use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::traits::Tool;
+use bizclaw_core::types::{ToolDefinition, ToolResult};
+use bizclaw_db::store::DataStore;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Largest message, in bytes.
+pub const MAX_CONTENT_BYTES: usize = 8000;
+
+/// Sends and receives the owning agent's link messages.
+pub struct MessageAgentTool {
+    agent_name: String,
+    store: Arc<dyn DataStore>,
+}
+
+impl MessageAgentTool {
+    pub fn new(agent_name: &str, store: Arc<dyn DataStore>) -> Self {
+        Self {
+            agent_name: agent_name.to_string(),
+            store,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct MessageAgentArgs {
+    action: String,
+    #[serde(default)]
+    to_agent: Option<String>,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    message_ids: Vec<String>,
+}
+
+fn ok(output: String) -> ToolResult {
+    ToolResult {
+        tool_call_id: String::new(),
+        output,
+        success: true,
+        data: None,
+    }
+}
+
+fn failed(output: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        ..ok(output)
+    }
+}
+
+#[async_trait]
+impl Tool for MessageAgentTool {
+    fn name(&self) -> &str {
+        "message_agent"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "message_agent".to_string(),
+            description: "Send direct messages to linked agents, read your inbox, and check whether your messages were delivered.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["send", "inbox", "ack", "status"],
+                        "description": "send: message an agent; inbox: fetch new messages; ack: mark messages read; status: delivery status of sent messages"
+                    },
+                    "to_agent": {
+                        "type": "string",
+                        "description": "Recipient agent (required for send)"
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "Message text (required for send)"
+                    },
+                    "message_ids": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Message IDs (required for ack and status)"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<ToolResult> {
+        let args: MessageAgentArgs = serde_json::from_str(arguments)
+            .map_err(|e| BizClawError::Tool(format!("Invalid args: {e}")))?;
+        let agent = self.agent_name.as_str();
+
+        match args.action.as_str() {
+            "send" => {
+                let to = args
+                    .to_agent
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .ok_or_else(|| BizClawError::Tool("'to_agent' is required for send".into()))?;
+                let content = args
+                    .content
+                    .filter(|s| !s.trim().is_empty())
+                    .ok_or_else(|| BizClawError::Tool("'content' is required for send".into()))?;
+                if content.len() > MAX_CONTENT_BYTES {
+                    return Err(BizClawError::Tool(format!(
+                        "Message too large ({} bytes, max {MAX_CONTENT_BYTES})",
+                        content.len()
+                    )));
+                }
+                match self.store.send_link_message(agent, to, &content).await {
+                    Ok(msg) => Ok(ToolResult::json(
+                        format!("Message {} sent to {to}.", msg.id),
+                        serde_json::json!({ "message_id": msg.id }),
+                    )),
+                    // Policy refusals are answers for the model, not failures.
+                    Err(BizClawError::NoPermission(e) | BizClawError::RateLimited(e)) => {
+                        Ok(failed(format!("Not sent: {e}")))
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            "inbox" => {
+                let messages = self.store.fetch_link_messages(agent).await?;
+                if messages.is_empty() {
+                    return Ok(ok("No new messages.".into()));
+                }
+                let mut out = format!("{} new message(s):\n", messages.len());
+                for msg in &messages {
+                    out.push_str(&format!(
+                        "- [{}] from {}: {}\n",
+                        msg.id, msg.from_agent, msg.content
+                    ));
+                }
+                Ok(ok(out.trim_end().to_string()))
+            }
+            "ack" => {
+                if args.message_ids.is_empty() {
+                    return Err(BizClawError::Tool(
+                        "'message_ids' is required for ack".into(),
+                    ));
+                }
+                // Only the recipient may acknowledge a message.
+                let mut own = Vec::new();
+                for id in &args.message_ids {
+                    if let Some(msg) = self.store.get_link_message(id).await?
+                        && msg.to_agent == agent
+                    {
+                        own.push(msg.id);
+                    }
+                }
+                self.store.mark_link_messages_read(&own).await?;
+                Ok(ok(format!("Marked {} message(s) read.", own.len())))
+            }
+            "status" => {
+                if args.message_ids.is_empty() {
+                    return Err(BizClawError::Tool(
+                        "'message_ids' is required for status".into(),
+                    ));
+                }
+                let mut out = String::new();
+                for id in &args.message_ids {
+                    let status = match self.store.get_link_message(id).await? {
+                        Some(msg) if msg.from_agent == agent || msg.to_agent == agent => {
+                            format!("{} (to {})", msg.status, msg.to_agent)
+                        }
+                        _ => "not found".to_string(),
+                    };
+                    out.push_str(&format!("- {id}: {status}\n"));
+                }
+                Ok(ok(out.trim_end().to_string()))
+            }
+            other => Err(BizClawError::Tool(format!(
+                "Unknown action '{other}'. Use: send, inbox, ack, status"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bizclaw_core::types::{AgentLink, LinkDirection};
+    use bizclaw_db::SqliteStore;
+
+    #[tokio::test]
+    async fn test_message_agent_round_trip() {
+        let store = SqliteStore::in_memory().unwrap();
+        store.migrate().await.unwrap();
+        store
+            .create_link(&AgentLink::new(
+                "support",
+                "research",
+                LinkDirection::Outbound,
+            ))
+            .await
+            .unwrap();
+        let store: Arc<dyn DataStore> = Arc::new(store);
+        let support = MessageAgentTool::new("support", store.clone());
+        let research = MessageAgentTool::new("research", store);
+
+        let res = support
+            .execute(r#"{"action": "send", "to_agent": "research", "content": "Check order 42"}"#)
+            .await
+            .unwrap();
+        assert!(res.success);
+        let id = res.data.unwrap().value["message_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        // The link is outbound-only.
+        let res = research
+            .execute(r#"{"action": "send", "to_agent": "support", "content": "Done"}"#)
+            .await
+            .unwrap();
+        assert!(!res.success);
+
+        let status = serde_json::json!({"action": "status", "message_ids": [id]}).to_string();
+        let res = support.execute(&status).await.unwrap();
+        assert_eq!(res.output, format!("- {id}: pending (to research)"));
+
+        let res = research.execute(r#"{"action": "inbox"}"#).await.unwrap();
+        assert!(res.output.contains("from support: Check order 42"));
+        let res = support.execute(&status).await.unwrap();
+        assert_eq!(res.output, format!("- {id}: delivered (to research)"));
+
+        // The sender cannot acknowledge on the recipient's behalf.
+        let ack = serde_json::json!({"action": "ack", "message_ids": [id]}).to_string();
+        support.execute(&ack).await.unwrap();
+        let res = support.execute(&status).await.unwrap();
+        assert!(res.output.contains("delivered"));
+        research.execute(&ack).await.unwrap();
+        let res = support.execute(&status).await.unwrap();
+        assert_eq!(res.output, format!("- {id}: read (to research)"));
+    }
+}
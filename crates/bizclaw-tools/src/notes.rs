@@ -0,0 +1,264 @@
+//! Notes tool — a persistent key-value scratchpad for the agent.
+//!
+//! Notes live in the DataStore's `agent_notes` table, keyed by agent name,
+//! so they survive restarts and each agent only sees its own. Tenants are
+//! isolated by their separate data stores. Keys, values and the number of
+//! notes are bounded so the scratchpad cannot grow without limit.
+
+use async_trait::async_trait;
+use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::traits::Tool;
+use bizclaw_core::types::{ToolDefinition, ToolResult};
+use bizclaw_db::store::DataStore;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Longest key, in characters.
+pub const MAX_KEY_CHARS: usize = 100;
+/// Largest value, in bytes.
+pub const MAX_VALUE_BYTES: usize = 4000;
+/// Most notes one agent may keep.
+pub const MAX_NOTES: usize = 200;
+
+/// Reads and writes the owning agent's notes.
+pub struct NotesTool {
+    agent_name: String,
+    store: Arc<dyn DataStore>,
+}
+
+impl NotesTool {
+    pub fn new(agent_name: &str, store: Arc<dyn DataStore>) -> Self {
+        Self {
+            agent_name: agent_name.to_string(),
+            store,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct NotesArgs {
+    action: String,
+    #[serde(default)]
+    key: Option<String>,
+    #[serde(default)]
+    value: Option<String>,
+}
+
+fn require_key(key: Option<&str>) -> Result<&str> {
+    let key = key.map(str::trim).unwrap_or_default();
+    if key.is_empty() {
+        return Err(BizClawError::Tool("'key' is required".into()));
+    }
+    if key.chars().count() > MAX_KEY_CHARS {
+        return Err(BizClawError::Tool(format!(
+            "Key too long (max {MAX_KEY_CHARS} characters)"
+        )));
+    }
+    Ok(key)
+}
+
+fn ok(output: String) -> ToolResult {
+    ToolResult {
+        tool_call_id: String::new(),
+        output,
+        success: true,
+        data: None,
+    }
+}
+
+#[async_trait]
+impl Tool for NotesTool {
+    fn name(&self) -> &str {
+        "notes"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "notes".to_string(),
+            description: "Your persistent scratchpad: save short key-value notes (task lists, reminders, progress) that survive restarts.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["set", "get", "list", "delete"],
+                        "description": "set: save a note; get: read one; list: show all keys; delete: remove one"
+                    },
+                    "key": {
+                        "type": "string",
+                        "description": "Note name (required for set, get, delete)"
+                    },
+                    "value": {
+                        "type": "string",
+                        "description": "Note content (required for set)"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<ToolResult> {
+        let args: NotesArgs = serde_json::from_str(arguments)
+            .map_err(|e| BizClawError::Tool(format!("Invalid args: {e}")))?;
+        let agent = self.agent_name.as_str();
+
+        match args.action.as_str() {
+            "set" => {
+                let key = require_key(args.key.as_deref())?;
+                let value = args
+                    .value
+                    .ok_or_else(|| BizClawError::Tool("'value' is required for set".into()))?;
+                if value.len() > MAX_VALUE_BYTES {
+                    return Err(BizClawError::Tool(format!(
+                        "Value too large ({} bytes, max {MAX_VALUE_BYTES})",
+                        value.len()
+                    )));
+                }
+                let exists = self.store.get_note(agent, key).await?.is_some();
+                if !exists && self.store.list_notes(agent).await?.len() >= MAX_NOTES {
+                    return Err(BizClawError::Tool(format!(
+                        "Note limit reached ({MAX_NOTES}); delete some notes first"
+                    )));
+                }
+                self.store.set_note(agent, key, &value).await?;
+                Ok(ok(format!("Saved note '{key}'")))
+            }
+            "get" => {
+                let key = require_key(args.key.as_deref())?;
+                Ok(match self.store.get_note(agent, key).await? {
+                    Some(note) => ok(note.value),
+                    None => ToolResult {
+                        success: false,
+                        ..ok(format!("No note named '{key}'"))
+                    },
+                })
+            }
+            "list" => {
+                let notes = self.store.list_notes(agent).await?;
+                if notes.is_empty() {
+                    return Ok(ok("No notes saved.".into()));
+                }
+                let mut out = format!("{} note(s):\n", notes.len());
+                for note in &notes {
+                    let preview: String = note.value.chars().take(80).collect();
+                    let more = if preview.len() < note.value.len() {
+                        "…"
+                    } else {
+                        ""
+                    };
+                    out.push_str(&format!("- {}: {preview}{more}\n", note.key));
+                }
+                Ok(ok(out.trim_end().to_string()))
+            }
+            "delete" => {
+                let key = require_key(args.key.as_deref())?;
+                Ok(if self.store.delete_note(agent, key).await? {
+                    ok(format!("Deleted note '{key}'"))
+                } else {
+                    ToolResult {
+                        success: false,
+                        ..ok(format!("No note named '{key}'"))
+                    }
+                })
+            }
+            other => Err(BizClawError::Tool(format!(
+                "Unknown action '{other}'. Use: set, get, list, delete"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bizclaw_db::SqliteStore;
+
+    async fn store() -> Arc<dyn DataStore> {
+        let store = SqliteStore::in_memory().unwrap();
+        store.migrate().await.unwrap();
+        Arc::new(store)
+    }
+
+    #[tokio::test]
+    async fn test_notes_actions() {
+        let tool = NotesTool::new("support", store().await);
+
+        let res = tool.execute(r#"{"action": "list"}"#).await.unwrap();
+        assert_eq!(res.output, "No notes saved.");
+
+        let res = tool
+            .execute(r#"{"action": "set", "key": "todo", "value": "1. refund order 42"}"#)
+            .await
+            .unwrap();
+        assert!(res.success);
+        tool.execute(r#"{"action": "set", "key": "customer", "value": "Lan"}"#)
+            .await
+            .unwrap();
+
+        let res = tool
+            .execute(r#"{"action": "get", "key": "todo"}"#)
+            .await
+            .unwrap();
+        assert_eq!(res.output, "1. refund order 42");
+
+        let res = tool.execute(r#"{"action": "list"}"#).await.unwrap();
+        assert!(res.output.starts_with("2 note(s):"), "{}", res.output);
+        assert!(res.output.contains("- customer: Lan"));
+
+        let res = tool
+            .execute(r#"{"action": "delete", "key": "todo"}"#)
+            .await
+            .unwrap();
+        assert!(res.success);
+        let res = tool
+            .execute(r#"{"action": "get", "key": "todo"}"#)
+            .await
+            .unwrap();
+        assert!(!res.success);
+        let res = tool
+            .execute(r#"{"action": "delete", "key": "todo"}"#)
+            .await
+            .unwrap();
+        assert!(!res.success);
+
+        assert!(
+            tool.execute(r#"{"action": "set", "value": "x"}"#)
+                .await
+                .is_err()
+        );
+        assert!(tool.execute(r#"{"action": "purge"}"#).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_notes_are_isolated_between_agents() {
+        let store = store().await;
+        let support = NotesTool::new("support", store.clone());
+        let sales = NotesTool::new("sales", store);
+
+        support
+            .execute(r#"{"action": "set", "key": "todo", "value": "call back"}"#)
+            .await
+            .unwrap();
+        let res = sales
+            .execute(r#"{"action": "get", "key": "todo"}"#)
+            .await
+            .unwrap();
+        assert!(!res.success);
+        let res = sales.execute(r#"{"action": "list"}"#).await.unwrap();
+        assert_eq!(res.output, "No notes saved.");
+    }
+
+    #[tokio::test]
+    async fn test_notes_size_bounds() {
+        let tool = NotesTool::new("support", store().await);
+        let big = serde_json::json!({
+            "action": "set", "key": "big", "value": "x".repeat(MAX_VALUE_BYTES + 1)
+        });
+        assert!(tool.execute(&big.to_string()).await.is_err());
+        let long_key = serde_json::json!({
+            "action": "set", "key": "k".repeat(MAX_KEY_CHARS + 1), "value": "v"
+        });
+        assert!(tool.execute(&long_key.to_string()).await.is_err());
+    }
+}
@@ -115,6 +115,7 @@ impl Tool for DelegateTool {
                     available.join(", ")
                 ),
                 success: false,
+                data: None,
             });
         }
 
@@ -132,6 +133,7 @@ impl Tool for DelegateTool {
                 args.to_agent, args.mode
             ),
             success: true,
+            data: None,
         })
     }
 }
@@ -213,6 +215,7 @@ impl Tool for HandoffTool {
                     from, args.to_agent, session_id, args.reason
                 ),
                 success: true,
+                data: None,
             })
         } else {
             Ok(ToolResult {
@@ -222,6 +225,7 @@ impl Tool for HandoffTool {
                     from, args.to_agent
                 ),
                 success: true,
+                data: None,
             })
         }
     }
@@ -270,6 +274,7 @@ impl Tool for ListAgentsTool {
             tool_call_id: String::new(),
             output: format!("Available Agents:\n{}", agents_info.join("\n")),
             success: true,
+            data: None,
         })
     }
 }
@@ -343,6 +348,7 @@ impl Tool for TeamTasksTool {
                     tool_call_id: String::new(),
                     output: "Team tasks not available (no data store configured)".to_string(),
                     success: false,
+                    data: None,
                 });
             }
         };
@@ -370,6 +376,7 @@ impl Tool for TeamTasksTool {
                             format!("Team Tasks:\n{}", list.join("\n"))
                         },
                         success: true,
+                        data: None,
                     })
                 } else {
                     // List tasks assigned to this agent
@@ -390,6 +397,7 @@ impl Tool for TeamTasksTool {
                             format!("Your Tasks:\n{}", list.join("\n"))
                         },
                         success: true,
+                        data: None,
                     })
                 }
             }
@@ -397,19 +405,30 @@ impl Tool for TeamTasksTool {
                 let task_id = args.task_id.ok_or_else(|| {
                     bizclaw_core::error::BizClawError::Tool("task_id required for 'claim'".into())
                 })?;
-                store
-                    .update_task(
-                        &task_id,
-                        bizclaw_core::types::TaskStatus::InProgress,
-                        Some(&state.agent_name),
-                        None,
-                    )
+                let claimed = store
+                    .claim_task(&task_id, &state.agent_name)
                     .await
                     .map_err(|e| bizclaw_core::error::BizClawError::Tool(e.to_string()))?;
+                if !claimed {
+                    let task = store
+                        .get_task(&task_id)
+                        .await
+                        .map_err(|e| bizclaw_core::error::BizClawError::Tool(e.to_string()))?;
+                    return Ok(ToolResult {
+                        tool_call_id: String::new(),
+                        output: match task.and_then(|t| t.assigned_to) {
+                            Some(owner) => format!("Task '{}' already claimed by '{}'", task_id, owner),
+                            None => format!("Task '{}' not found", task_id),
+                        },
+                        success: false,
+                        data: None,
+                    });
+                }
                 Ok(ToolResult {
                     tool_call_id: String::new(),
                     output: format!("Task '{}' claimed by '{}'", task_id, state.agent_name),
                     success: true,
+                    data: None,
                 })
             }
             "complete" => {
@@ -430,12 +449,14 @@ impl Tool for TeamTasksTool {
                     tool_call_id: String::new(),
                     output: format!("Task '{}' completed.", task_id),
                     success: true,
+                    data: None,
                 })
             }
             _ => Ok(ToolResult {
                 tool_call_id: String::new(),
                 output: format!("Unknown action: '{}'. Use: list, claim, complete", args.action),
                 success: false,
+                data: None,
             }),
         }
     }
@@ -510,6 +531,7 @@ impl Tool for TeamMessageTool {
                     tool_call_id: String::new(),
                     output: "Team messages not available (no data store configured)".to_string(),
                     success: false,
+                    data: None,
                 });
             }
         };
@@ -538,6 +560,7 @@ impl Tool for TeamMessageTool {
                     tool_call_id: String::new(),
                     output: "Message sent.".to_string(),
                     success: true,
+                    data: None,
                 })
             }
             "read" => {
@@ -550,6 +573,7 @@ impl Tool for TeamMessageTool {
                         tool_call_id: String::new(),
                         output: "No unread messages.".to_string(),
                         success: true,
+                        data: None,
                     })
                 } else {
                     let ids: Vec<String> = messages.iter().map(|m| m.id.clone()).collect();
@@ -562,6 +586,7 @@ impl Tool for TeamMessageTool {
                         tool_call_id: String::new(),
                         output: format!("Unread Messages:\n{}", list.join("\n")),
                         success: true,
+                        data: None,
                     })
                 }
             }
@@ -569,6 +594,7 @@ impl Tool for TeamMessageTool {
                 tool_call_id: String::new(),
                 output: format!("Unknown action: '{}'. Use: send, read", args.action),
                 success: false,
+                data: None,
             }),
         }
     }
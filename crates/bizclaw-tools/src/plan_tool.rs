@@ -371,6 +371,7 @@ impl Tool for PlanTool {
                         id
                     ),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -383,6 +384,7 @@ impl Tool for PlanTool {
                             "Cannot add tasks — plan is not in Draft status. Create a new plan."
                                 .into(),
                         success: false,
+                        data: None,
                     });
                 }
                 let title = args["title"].as_str().unwrap_or("Untitled Task");
@@ -416,6 +418,7 @@ impl Tool for PlanTool {
                     tool_call_id: String::new(),
                     output: format!("✅ Task #{} added: {}", task_id, title),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -426,6 +429,7 @@ impl Tool for PlanTool {
                         tool_call_id: String::new(),
                         output: "Cannot finalize — plan has no tasks.".into(),
                         success: false,
+                        data: None,
                     });
                 }
                 plan.status = PlanStatus::PendingApproval;
@@ -438,6 +442,7 @@ impl Tool for PlanTool {
                     tool_call_id: String::new(),
                     output: format!("✅ Plan finalized and ready for review!\n\n{}", display),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -451,6 +456,7 @@ impl Tool for PlanTool {
                             plan.status
                         ),
                         success: false,
+                        data: None,
                     });
                 }
                 plan.status = PlanStatus::Approved;
@@ -466,6 +472,7 @@ impl Tool for PlanTool {
                         title
                     ),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -481,6 +488,7 @@ impl Tool for PlanTool {
                     tool_call_id: String::new(),
                     output: format!("❌ Plan '{}' rejected.", title),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -491,6 +499,7 @@ impl Tool for PlanTool {
                         tool_call_id: String::new(),
                         output: "Cannot start task — plan must be Approved first.".into(),
                         success: false,
+                        data: None,
                     });
                 }
                 plan.status = PlanStatus::InProgress;
@@ -510,6 +519,7 @@ impl Tool for PlanTool {
                                         task_id, dep_id, dep.status
                                     ),
                                     success: false,
+                                    data: None,
                                 });
                             }
                     }
@@ -521,12 +531,14 @@ impl Tool for PlanTool {
                         tool_call_id: String::new(),
                         output: format!("▶ Task #{} started: {}", task_id, title),
                         success: true,
+                        data: None,
                     })
                 } else {
                     Ok(ToolResult {
                         tool_call_id: String::new(),
                         output: format!("Task #{} not found", task_id),
                         success: false,
+                        data: None,
                     })
                 }
             }
@@ -572,12 +584,14 @@ impl Tool for PlanTool {
                         tool_call_id: String::new(),
                         output: msg,
                         success: true,
+                        data: None,
                     })
                 } else {
                     Ok(ToolResult {
                         tool_call_id: String::new(),
                         output: format!("Task #{} not found", task_id),
                         success: false,
+                        data: None,
                     })
                 }
             }
@@ -595,12 +609,14 @@ impl Tool for PlanTool {
                         tool_call_id: String::new(),
                         output: format!("❌ Task #{} failed: {}", task_id, title),
                         success: true,
+                        data: None,
                     })
                 } else {
                     Ok(ToolResult {
                         tool_call_id: String::new(),
                         output: format!("Task #{} not found", task_id),
                         success: false,
+                        data: None,
                     })
                 }
             }
@@ -616,12 +632,14 @@ impl Tool for PlanTool {
                         tool_call_id: String::new(),
                         output: format!("⏭ Task #{} skipped: {}", task_id, title),
                         success: true,
+                        data: None,
                     })
                 } else {
                     Ok(ToolResult {
                         tool_call_id: String::new(),
                         output: format!("Task #{} not found", task_id),
                         success: false,
+                        data: None,
                     })
                 }
             }
@@ -632,6 +650,7 @@ impl Tool for PlanTool {
                         tool_call_id: String::new(),
                         output: "No plans exist yet.".into(),
                         success: true,
+                        data: None,
                     });
                 }
                 let mut out = format!("📋 {} plan(s):\n\n", store.len());
@@ -646,6 +665,7 @@ impl Tool for PlanTool {
                     tool_call_id: String::new(),
                     output: out,
                     success: true,
+                    data: None,
                 })
             }
 
@@ -655,6 +675,7 @@ impl Tool for PlanTool {
                     tool_call_id: String::new(),
                     output: plan.display(),
                     success: true,
+                    data: None,
                 })
             }
 
@@ -669,6 +690,7 @@ impl Tool for PlanTool {
                     tool_call_id: String::new(),
                     output: format!("🗑️ Plan {} deleted.", plan_id),
                     success: true,
+                    data: None,
                 })
             }
 
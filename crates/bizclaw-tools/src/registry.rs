@@ -2,6 +2,7 @@
 
 use bizclaw_core::traits::Tool;
 use bizclaw_core::types::ToolDefinition;
+use serde_json::Value;
 
 /// Find a tool by name from a list.
 pub fn find_tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> Option<&'a dyn Tool> {
@@ -13,18 +14,103 @@ pub fn list_definitions(tools: &[Box<dyn Tool>]) -> Vec<ToolDefinition> {
     tools.iter().map(|t| t.definition()).collect()
 }
 
-/// Validate that a tool call has the required arguments.
+/// Validate tool call arguments against the tool's JSON Schema
+/// (`definition.parameters`). Checks `type`, `required`, `properties`,
+/// `enum`, array `items` and `additionalProperties: false`; other keywords
+/// are ignored. The error lists every problem, one per line, worded for the
+/// model to correct its call.
 pub fn validate_args(definition: &ToolDefinition, args: &serde_json::Value) -> Result<(), String> {
-    let params = &definition.parameters;
-    if let Some(required) = params.get("required").and_then(|r| r.as_array()) {
-        for req in required {
-            if let Some(key) = req.as_str()
-                && args.get(key).is_none() {
-                    return Err(format!("Missing required argument: {key}"));
+    let mut errors = Vec::new();
+    check_value(&definition.parameters, args, "arguments", &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+fn check_value(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            errors.push(format!(
+                "{path}: expected {}, got {}",
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+        errors.push(format!("{path}: must be one of {}", allowed.join(", ")));
+    }
+
+    match value {
+        Value::Object(map) => {
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(key) {
+                        errors.push(format!("{path}: missing required argument `{key}`"));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, item) in map {
+                match properties.and_then(|p| p.get(key)) {
+                    Some(sub) => check_value(sub, item, &format!("{path}.{key}"), errors),
+                    None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                        errors.push(format!("{path}: unknown argument `{key}`"));
+                    }
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check_value(item_schema, item, &format!("{path}[{i}]"), errors);
                 }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64()
+                || value.is_u64()
+                || value.as_f64().is_some_and(|f| f.fract() == 0.0)
         }
+        // Unknown type keywords are not ours to enforce.
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
     }
-    Ok(())
 }
 
 #[cfg(test)]
@@ -53,6 +139,44 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validate_args_types_and_enums() {
+        let def = ToolDefinition {
+            name: "search".into(),
+            description: "search tool".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "required": ["query"],
+                "additionalProperties": false,
+                "properties": {
+                    "query": { "type": "string" },
+                    "limit": { "type": "integer" },
+                    "mode": { "type": "string", "enum": ["fast", "deep"] },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                }
+            }),
+        };
+
+        let ok = serde_json::json!({"query": "gold", "limit": 5, "mode": "fast", "tags": ["a"]});
+        assert!(validate_args(&def, &ok).is_ok());
+
+        let bad = serde_json::json!({"limit": "5", "mode": "slow", "tags": ["a", 1], "x": 1});
+        let err = validate_args(&def, &bad).unwrap_err();
+        assert_eq!(
+            err.lines().collect::<Vec<_>>(),
+            vec![
+                "arguments: missing required argument `query`",
+                "arguments.limit: expected integer, got string",
+                "arguments.mode: must be one of \"fast\", \"deep\"",
+                "arguments.tags[1]: expected string, got number",
+                "arguments: unknown argument `x`",
+            ]
+        );
+
+        let err = validate_args(&def, &serde_json::json!("gold")).unwrap_err();
+        assert_eq!(err, "arguments: expected object, got string");
+    }
+
     #[test]
     fn test_validate_args_no_required() {
         let def = ToolDefinition {
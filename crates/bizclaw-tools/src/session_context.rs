@@ -184,6 +184,7 @@ impl Tool for SessionContextTool {
             tool_call_id: String::new(),
             output,
             success: true,
+            data: None,
         })
     }
 }
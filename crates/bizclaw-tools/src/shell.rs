@@ -5,7 +5,9 @@
 
 use async_trait::async_trait;
 use bizclaw_core::error::Result;
+use bizclaw_core::text::truncate_chars;
 use bizclaw_core::traits::Tool;
+use bizclaw_core::traits::tool::OnOutput;
 use bizclaw_core::types::{ToolDefinition, ToolResult};
 
 /// Forbidden paths that should never appear in shell commands.
@@ -40,7 +42,7 @@ impl ShellTool {
         if command.chars().any(|c| DANGEROUS_CHARS.contains(&c)) {
             return Some(format!(
                 "🔒 Blocked: command contains shell metacharacters (;|&`$(){{}}><). Use simple commands without chaining. Attempted: '{}'",
-                &command[..command.len().min(60)]
+                truncate_chars(command, 60)
             ));
         }
 
@@ -49,7 +51,7 @@ impl ShellTool {
             if lower.contains(pattern) {
                 return Some(format!(
                     "🔒 Blocked: command matches dangerous pattern '{}'. Command: '{}'",
-                    pattern, &command[..command.len().min(60)]
+                    pattern, truncate_chars(command, 60)
                 ));
             }
         }
@@ -59,7 +61,7 @@ impl ShellTool {
             if lower.contains(path) {
                 return Some(format!(
                     "🔒 Blocked: command accesses forbidden path '{}'. Command: '{}'",
-                    path, &command[..command.len().min(60)]
+                    path, truncate_chars(command, 60)
                 ));
             }
         }
@@ -106,6 +108,21 @@ impl Tool for ShellTool {
     }
 
     async fn execute(&self, arguments: &str) -> Result<ToolResult> {
+        self.run(arguments, None).await
+    }
+
+    /// Streams stdout and stderr line by line while the command runs.
+    async fn execute_streaming(
+        &self,
+        arguments: &str,
+        on_output: &OnOutput<'_>,
+    ) -> Result<ToolResult> {
+        self.run(arguments, Some(on_output)).await
+    }
+}
+
+impl ShellTool {
+    async fn run(&self, arguments: &str, on_output: Option<&OnOutput<'_>>) -> Result<ToolResult> {
         let args: serde_json::Value = serde_json::from_str(arguments)
             .map_err(|e| bizclaw_core::error::BizClawError::Tool(e.to_string()))?;
 
@@ -132,6 +149,7 @@ impl Tool for ShellTool {
                 tool_call_id: String::new(),
                 output: block_reason,
                 success: false,
+                data: None,
             });
         }
 
@@ -150,19 +168,35 @@ impl Tool for ShellTool {
         if let Some(dir) = workdir {
             cmd.current_dir(dir);
         }
-
-        tracing::info!("🖥️ ShellTool: executing (timeout={}s): {}", timeout_secs, &command[..command.len().min(100)]);
-
+        // Piped so output can be passed on while the command runs; no stdin,
+        // so commands waiting for input end at EOF instead of hanging.
+        // A timed-out command is killed when its handle drops.
+        cmd.stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+
+        tracing::info!("🖥️ ShellTool: executing (timeout={}s): {}", timeout_secs, truncate_chars(command, 100));
+
+        let run = async {
+            let mut child = cmd.spawn()?;
+            let (stdout, stderr, status) = tokio::try_join!(
+                read_streaming(child.stdout.take(), on_output),
+                read_streaming(child.stderr.take(), on_output),
+                child.wait(),
+            )?;
+            Ok::<_, std::io::Error>(std::process::Output { status, stdout, stderr })
+        };
         let output = tokio::time::timeout(
             std::time::Duration::from_secs(timeout_secs),
-            cmd.output(),
+            run,
         )
         .await
         .map_err(|_| {
-            tracing::warn!("⏰ ShellTool: command timed out after {}s: {}", timeout_secs, &command[..command.len().min(100)]);
+            tracing::warn!("⏰ ShellTool: command timed out after {}s: {}", timeout_secs, truncate_chars(command, 100));
             bizclaw_core::error::BizClawError::Timeout(
                 format!("Command timed out after {}s ({}min). Command: {}. Increase timeout with timeout_secs parameter or BIZCLAW_SHELL_TIMEOUT_SECS env var.",
-                    timeout_secs, timeout_secs / 60, &command[..command.len().min(80)])
+                    timeout_secs, timeout_secs / 60, truncate_chars(command, 80))
             )
         })?
         .map_err(|e| bizclaw_core::error::BizClawError::Tool(e.to_string()))?;
@@ -188,10 +222,33 @@ impl Tool for ShellTool {
             tool_call_id: String::new(),
             output: result,
             success: output.status.success(),
+            data: None,
         })
     }
 }
 
+/// Read a child's output pipe to the end, passing each line to `on_output`
+/// as it arrives.
+async fn read_streaming(
+    pipe: Option<impl tokio::io::AsyncRead + Unpin>,
+    on_output: Option<&OnOutput<'_>>,
+) -> std::io::Result<Vec<u8>> {
+    use tokio::io::AsyncBufReadExt;
+    let mut out = Vec::new();
+    let Some(pipe) = pipe else {
+        return Ok(out);
+    };
+    let mut reader = tokio::io::BufReader::new(pipe);
+    let mut line = Vec::new();
+    while reader.read_until(b'\n', &mut line).await? > 0 {
+        if let Some(on_output) = on_output {
+            on_output(&String::from_utf8_lossy(&line));
+        }
+        out.append(&mut line);
+    }
+    Ok(out)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
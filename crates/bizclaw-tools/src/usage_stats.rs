@@ -0,0 +1,195 @@
+//! Usage stats tool — lets an agent answer "how many tokens did I use today?"
+//!
+//! Aggregates the agent's own LLM traces from the DataStore over a time
+//! window: request count, token totals, and average latency.
+
+use async_trait::async_trait;
+use bizclaw_core::error::{BizClawError, Result};
+use bizclaw_core::traits::Tool;
+use bizclaw_core::types::{ToolDefinition, ToolResult, UsageStats};
+use bizclaw_db::store::DataStore;
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Reports token usage for the agent that owns it.
+pub struct UsageStatsTool {
+    agent_name: String,
+    store: Arc<dyn DataStore>,
+}
+
+impl UsageStatsTool {
+    pub fn new(agent_name: &str, store: Arc<dyn DataStore>) -> Self {
+        Self {
+            agent_name: agent_name.to_string(),
+            store,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct UsageArgs {
+    /// "today", "24h", "7d", "30d" or "all".
+    #[serde(default)]
+    period: Option<String>,
+    /// Explicit RFC 3339 bounds (override `period`).
+    #[serde(default)]
+    since: Option<String>,
+    #[serde(default)]
+    until: Option<String>,
+}
+
+/// Resolve a named period into a `since` bound relative to `now`.
+fn period_start(period: &str, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+    Ok(match period {
+        "today" => now.date_naive().and_hms_opt(0, 0, 0).map(|t| t.and_utc()),
+        "24h" => Some(now - Duration::hours(24)),
+        "7d" => Some(now - Duration::days(7)),
+        "30d" => Some(now - Duration::days(30)),
+        "all" => None,
+        other => {
+            return Err(BizClawError::Tool(format!(
+                "Unknown period '{other}'. Use: today, 24h, 7d, 30d, all"
+            )));
+        }
+    })
+}
+
+fn parse_time(s: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| BizClawError::Tool(format!("Invalid timestamp '{s}': {e}")))
+}
+
+/// Human-readable summary of usage stats.
+pub fn format_summary(stats: &UsageStats, label: &str) -> String {
+    let mut out = format!("Usage for agent '{}' ({label}):\n", stats.agent_name);
+    out.push_str(&format!(
+        "- Requests: {} ({} errors)\n",
+        stats.requests, stats.errors
+    ));
+    out.push_str(&format!(
+        "- Tokens: {} total ({} prompt, {} completion)\n",
+        stats.total_tokens, stats.prompt_tokens, stats.completion_tokens
+    ));
+    out.push_str(&format!("- Avg latency: {:.0} ms", stats.avg_latency_ms));
+    out
+}
+
+#[async_trait]
+impl Tool for UsageStatsTool {
+    fn name(&self) -> &str {
+        "usage_stats"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "usage_stats".to_string(),
+            description: "Report your own LLM usage: token totals, request count and average latency over a time range.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "period": {
+                        "type": "string",
+                        "enum": ["today", "24h", "7d", "30d", "all"],
+                        "default": "today",
+                        "description": "Time range to summarize (UTC)"
+                    },
+                    "since": {
+                        "type": "string",
+                        "description": "Optional start time (RFC 3339), overrides period"
+                    },
+                    "until": {
+                        "type": "string",
+                        "description": "Optional end time (RFC 3339, exclusive)"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn execute(&self, arguments: &str) -> Result<ToolResult> {
+        let args: UsageArgs = if arguments.trim().is_empty() {
+            UsageArgs::default()
+        } else {
+            serde_json::from_str(arguments)
+                .map_err(|e| BizClawError::Tool(format!("Invalid args: {e}")))?
+        };
+
+        let now = Utc::now();
+        let (since, label) = match &args.since {
+            Some(s) => (Some(parse_time(s)?), format!("since {s}")),
+            None => {
+                let period = args.period.as_deref().unwrap_or("today");
+                (period_start(period, now)?, period.to_string())
+            }
+        };
+        let until = args.until.as_deref().map(parse_time).transpose()?;
+
+        let stats = self
+            .store
+            .usage_stats(&self.agent_name, since, until)
+            .await?;
+        Ok(ToolResult {
+            tool_call_id: String::new(),
+            output: format_summary(&stats, &label),
+            success: true,
+            data: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bizclaw_core::types::LlmTrace;
+    use bizclaw_db::SqliteStore;
+
+    async fn seeded_store() -> Arc<dyn DataStore> {
+        let store = SqliteStore::in_memory().unwrap();
+        store.migrate().await.unwrap();
+        let now = Utc::now();
+        for (agent, hours_ago, prompt, completion, latency) in [
+            ("support", 1, 120, 30, 800),
+            ("support", 3, 80, 20, 1200),
+            ("support", 24 * 10, 1000, 1000, 5000),
+            ("sales", 1, 400, 100, 300),
+        ] {
+            let mut t = LlmTrace::new(agent, "openai", "gpt-4o-mini");
+            t.prompt_tokens = prompt;
+            t.completion_tokens = completion;
+            t.total_tokens = prompt + completion;
+            t.latency_ms = latency;
+            t.created_at = now - Duration::hours(hours_ago);
+            store.record_trace(&t).await.unwrap();
+        }
+        Arc::new(store)
+    }
+
+    #[tokio::test]
+    async fn test_usage_stats_reports_window_totals() {
+        let tool = UsageStatsTool::new("support", seeded_store().await);
+
+        let res = tool.execute(r#"{"period": "24h"}"#).await.unwrap();
+        assert!(res.success);
+        assert!(
+            res.output.contains("Requests: 2 (0 errors)"),
+            "{}",
+            res.output
+        );
+        assert!(
+            res.output
+                .contains("Tokens: 250 total (200 prompt, 50 completion)")
+        );
+        assert!(res.output.contains("Avg latency: 1000 ms"));
+
+        let res = tool.execute(r#"{"period": "30d"}"#).await.unwrap();
+        assert!(res.output.contains("Requests: 3"));
+    }
+
+    #[tokio::test]
+    async fn test_usage_stats_rejects_unknown_period() {
+        let tool = UsageStatsTool::new("support", seeded_store().await);
+        assert!(tool.execute(r#"{"period": "fortnight"}"#).await.is_err());
+    }
+}
@@ -90,6 +90,7 @@ impl Tool for WebSearchTool {
             tool_call_id: String::new(),
             output,
             success: true,
+            data: None,
         })
     }
 }
@@ -3,12 +3,18 @@
 //! The engine is designed to be agent-agnostic: it relies on a callback function
 //! to actually invoke agents, making it easy to integrate with any agent system.
 
+use bizclaw_core::cancel::CancelToken;
 use chrono::Utc;
 use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tracing::{debug, error, info, warn};
 
+use crate::event::{ProgressCallback, WorkflowEvent};
 use crate::state::{WorkflowState, WorkflowStatus};
-use crate::step::{CollectStrategy, StepResultStatus, StepType, Workflow, WorkflowStepResult};
+use crate::step::{
+    CollectStrategy, StepResultStatus, StepType, Workflow, WorkflowStepResult, render_template,
+};
 
 /// Callback type for agent execution.
 /// Takes (agent_name, prompt) and returns (output, tokens_used).
@@ -65,6 +71,32 @@ impl WorkflowEngine {
         workflow_name: &str,
         input: &str,
         agent_fn: &AgentCallback,
+    ) -> Result<WorkflowState, String> {
+        let no_progress: ProgressCallback = Box::new(|_: &WorkflowEvent| {});
+        self.execute_with_progress(workflow_name, input, agent_fn, &no_progress)
+    }
+
+    /// Execute a workflow, emitting a [`WorkflowEvent`] for each step transition.
+    /// Returns the same final state as [`Self::execute`].
+    pub fn execute_with_progress(
+        &mut self,
+        workflow_name: &str,
+        input: &str,
+        agent_fn: &AgentCallback,
+        on_event: &ProgressCallback,
+    ) -> Result<WorkflowState, String> {
+        self.execute_cancellable(workflow_name, input, agent_fn, on_event, &CancelToken::new())
+    }
+
+    /// Execute a workflow that stops before its next step once `cancel` fires.
+    /// A cancelled run returns its partial state with status `Cancelled`.
+    pub fn execute_cancellable(
+        &mut self,
+        workflow_name: &str,
+        input: &str,
+        agent_fn: &AgentCallback,
+        on_event: &ProgressCallback,
+        cancel: &CancelToken,
     ) -> Result<WorkflowState, String> {
         let workflow = self
             .workflows
@@ -82,18 +114,35 @@ impl WorkflowEngine {
         );
 
         for (idx, step) in workflow.steps.iter().enumerate() {
+            if cancel.is_cancelled() {
+                warn!("🚫 Workflow '{}' cancelled before step '{}'", workflow.name, step.name);
+                state.cancel();
+                Self::emit_completed(&state, on_event);
+                self.history.push(state.clone());
+                return Ok(state);
+            }
             debug!("→ Step {}/{}: '{}' (agent: {})", idx + 1, workflow.step_count(), step.name, step.agent);
+            on_event(&WorkflowEvent::StepStarted {
+                index: idx,
+                name: step.name.clone(),
+                agent: step.agent.clone(),
+            });
 
             let step_start = Utc::now();
             let current_input = state.last_output().to_string();
 
             let result = match &step.step_type {
                 StepType::Sequential => {
-                    self.execute_sequential(step, &current_input, agent_fn)
-                }
-                StepType::FanOut { parallel_steps } => {
-                    self.execute_fanout(step, &current_input, parallel_steps, &workflow, agent_fn)
+                    self.execute_sequential(step, &current_input, &state, agent_fn)
                 }
+                StepType::FanOut { parallel_steps } => self.execute_fanout(
+                    step,
+                    &current_input,
+                    parallel_steps,
+                    &workflow,
+                    &state,
+                    agent_fn,
+                ),
                 StepType::Collect { strategy, evaluator } => {
                     self.execute_collect(step, &state, strategy, evaluator.as_deref(), agent_fn)
                 }
@@ -104,28 +153,29 @@ impl WorkflowEngine {
                         if_false
                     };
                     if let Some(target_step) = workflow.get_step(target) {
-                        self.execute_sequential(target_step, &current_input, agent_fn)
+                        self.execute_sequential(target_step, &current_input, &state, agent_fn)
                     } else {
                         Err(format!("Conditional target step '{}' not found", target))
                     }
                 }
                 StepType::Loop { body_step, config } => {
-                    self.execute_loop(step, &current_input, body_step, config, &workflow, agent_fn)
+                    self.execute_loop(step, body_step, config, &workflow, &state, agent_fn)
                 }
                 StepType::Transform { template } => {
-                    let output = template.replace("{{input}}", &current_input);
-                    let elapsed = (Utc::now() - step_start).num_milliseconds().max(0) as u64;
-                    Ok(WorkflowStepResult {
-                        step_name: step.name.clone(),
-                        agent: "transform".to_string(),
-                        output,
-                        tokens_used: 0,
-                        latency_ms: elapsed,
-                        status: StepResultStatus::Success,
-                        error: None,
-                        started_at: step_start,
-                        completed_at: Utc::now(),
-                        retries: 0,
+                    render_template(template, &current_input, &state).map(|output| {
+                        let elapsed = (Utc::now() - step_start).num_milliseconds().max(0) as u64;
+                        WorkflowStepResult {
+                            step_name: step.name.clone(),
+                            agent: "transform".to_string(),
+                            output,
+                            tokens_used: 0,
+                            latency_ms: elapsed,
+                            status: StepResultStatus::Success,
+                            error: None,
+                            started_at: step_start,
+                            completed_at: Utc::now(),
+                            retries: 0,
+                        }
                     })
                 }
             };
@@ -136,10 +186,25 @@ impl WorkflowEngine {
                         "  ✅ Step '{}' completed ({} tokens, {}ms)",
                         step.name, step_result.tokens_used, step_result.latency_ms
                     );
+                    let (tokens_used, latency_ms) = (step_result.tokens_used, step_result.latency_ms);
                     state.record_step(step_result);
+                    on_event(&WorkflowEvent::StepCompleted {
+                        index: idx,
+                        name: step.name.clone(),
+                        tokens_used,
+                        latency_ms,
+                        total_tokens: state.total_tokens,
+                    });
                 }
                 Err(e) => {
                     error!("  ❌ Step '{}' failed: {}", step.name, e);
+                    on_event(&WorkflowEvent::StepFailed {
+                        index: idx,
+                        name: step.name.clone(),
+                        error: e.clone(),
+                        optional: step.optional,
+                        total_tokens: state.total_tokens,
+                    });
                     if step.optional {
                         warn!("  ⚠ Step '{}' is optional — continuing", step.name);
                         let skip_result = WorkflowStepResult {
@@ -157,6 +222,7 @@ impl WorkflowEngine {
                         state.record_step(skip_result);
                     } else if workflow.stop_on_failure {
                         state.fail(&e);
+                        Self::emit_completed(&state, on_event);
                         self.history.push(state.clone());
                         return Ok(state);
                     }
@@ -171,18 +237,29 @@ impl WorkflowEngine {
             state.total_tokens,
             state.duration_secs()
         );
+        Self::emit_completed(&state, on_event);
         self.history.push(state.clone());
         Ok(state)
     }
 
+    fn emit_completed(state: &WorkflowState, on_event: &ProgressCallback) {
+        on_event(&WorkflowEvent::WorkflowCompleted {
+            workflow: state.workflow_name.clone(),
+            status: state.status.clone(),
+            steps_completed: state.step_results.len(),
+            total_tokens: state.total_tokens,
+        });
+    }
+
     /// Execute a sequential step.
     fn execute_sequential(
         &self,
         step: &crate::step::WorkflowStep,
         input: &str,
+        state: &WorkflowState,
         agent_fn: &AgentCallback,
     ) -> Result<WorkflowStepResult, String> {
-        let prompt = step.build_prompt(input);
+        let prompt = step.build_prompt(input, state)?;
         let start = Utc::now();
 
         let mut last_err = String::new();
@@ -226,30 +303,61 @@ impl WorkflowEngine {
         ))
     }
 
-    /// Execute fan-out: run multiple sub-steps in parallel (simulated sequentially for sync callback).
+    /// Execute fan-out: run the sub-steps on worker threads, at most
+    /// [`Workflow::parallel_limit`] at a time. Outputs keep the order of
+    /// `parallel_step_names`.
     fn execute_fanout(
         &self,
         parent_step: &crate::step::WorkflowStep,
         input: &str,
         parallel_step_names: &[String],
         workflow: &Workflow,
+        state: &WorkflowState,
         agent_fn: &AgentCallback,
     ) -> Result<WorkflowStepResult, String> {
         let start = Utc::now();
-        let mut results = Vec::new();
-        let mut total_tokens = 0u64;
+        let sub_steps: Vec<_> = parallel_step_names
+            .iter()
+            .filter_map(|name| workflow.get_step(name))
+            .collect();
+        let workers = workflow.parallel_limit(parent_step).min(sub_steps.len());
+        debug!(
+            "  ⇉ Fan-out '{}': {} sub-steps, {} at a time",
+            parent_step.name,
+            sub_steps.len(),
+            workers
+        );
 
-        for step_name in parallel_step_names {
-            if let Some(sub_step) = workflow.get_step(step_name) {
-                match self.execute_sequential(sub_step, input, agent_fn) {
-                    Ok(r) => {
-                        total_tokens += r.tokens_used;
-                        results.push(r);
-                    }
-                    Err(e) => {
-                        warn!("  ⚠ Fan-out sub-step '{}' failed: {}", step_name, e);
+        let next = AtomicUsize::new(0);
+        let slots: Vec<Mutex<Option<Result<WorkflowStepResult, String>>>> =
+            sub_steps.iter().map(|_| Mutex::new(None)).collect();
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(sub_step) = sub_steps.get(i) else {
+                            break;
+                        };
+                        let result = self.execute_sequential(sub_step, input, state, agent_fn);
+                        *slots[i].lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
                     }
+                });
+            }
+        });
+
+        let mut results = Vec::new();
+        let mut total_tokens = 0u64;
+        for (sub_step, slot) in sub_steps.iter().zip(slots) {
+            match slot.into_inner().unwrap_or_else(|e| e.into_inner()) {
+                Some(Ok(r)) => {
+                    total_tokens += r.tokens_used;
+                    results.push(r);
                 }
+                Some(Err(e)) => {
+                    warn!("  ⚠ Fan-out sub-step '{}' failed: {}", sub_step.name, e);
+                }
+                None => {}
             }
         }
 
@@ -320,6 +428,16 @@ impl WorkflowEngine {
                 let (result, _) = agent_fn(&step.agent, &prompt)?;
                 result
             }
+            CollectStrategy::Rank => {
+                let eval_agent = evaluator.unwrap_or(&step.agent);
+                let candidates = crate::rank::split_candidates(input);
+                let (reply, _) = agent_fn(eval_agent, &crate::rank::scoring_prompt(&candidates))?;
+                let scores = crate::rank::parse_scores(&reply, candidates.len());
+                if scores.is_none() {
+                    warn!("  ⚠ Could not parse scores from '{}' — keeping fan-out order", eval_agent);
+                }
+                crate::rank::render_ranking(&candidates, scores.as_deref())
+            }
             CollectStrategy::First => {
                 // Take first result section
                 input
@@ -348,13 +466,14 @@ impl WorkflowEngine {
     }
 
     /// Execute loop: repeat a step until condition is met or max iterations reached.
+    /// The first iteration takes the previous step's output as input.
     fn execute_loop(
         &self,
         parent_step: &crate::step::WorkflowStep,
-        initial_input: &str,
         body_step_name: &str,
         config: &crate::step::LoopConfig,
         workflow: &Workflow,
+        state: &WorkflowState,
         agent_fn: &AgentCallback,
     ) -> Result<WorkflowStepResult, String> {
         let start = Utc::now();
@@ -362,7 +481,7 @@ impl WorkflowEngine {
             .get_step(body_step_name)
             .ok_or_else(|| format!("Loop body step '{}' not found", body_step_name))?;
 
-        let mut current_input = initial_input.to_string();
+        let mut current_input = state.last_output().to_string();
         let mut total_tokens = 0u64;
         let mut iterations = 0u32;
 
@@ -375,7 +494,7 @@ impl WorkflowEngine {
                 break;
             }
 
-            let result = self.execute_sequential(body_step, &current_input, agent_fn)?;
+            let result = self.execute_sequential(body_step, &current_input, state, agent_fn)?;
             total_tokens += result.tokens_used;
             iterations += 1;
 
@@ -424,11 +543,12 @@ impl Default for WorkflowEngine {
 mod tests {
     use super::*;
     use crate::step::{StepType, Workflow, WorkflowStep};
+    use bizclaw_core::text::truncate_chars;
 
     fn mock_agent_fn() -> AgentCallback {
         Box::new(|agent: &str, prompt: &str| {
             Ok((
-                format!("[{}] processed: {}", agent, &prompt[..prompt.len().min(50)]),
+                format!("[{}] processed: {}", agent, truncate_chars(prompt, 50)),
                 100,
             ))
         })
@@ -470,6 +590,42 @@ mod tests {
         assert_eq!(state.total_tokens, 0);
     }
 
+    #[test]
+    fn test_engine_step_uses_two_earlier_outputs() {
+        let mut engine = WorkflowEngine::new();
+        let wf = Workflow::new("refs", "Step reference test")
+            .add_step(WorkflowStep::new("facts", "researcher", StepType::Sequential))
+            .add_step(WorkflowStep::new("tone", "stylist", StepType::Sequential))
+            .add_step(
+                WorkflowStep::new("write", "writer", StepType::Sequential).with_prompt(
+                    "{{workflow.input}} | {{steps.facts.output}} | {{steps.tone.output}}",
+                ),
+            );
+        engine.register(wf);
+
+        let echo: AgentCallback = Box::new(|agent: &str, prompt: &str| {
+            Ok((format!("{agent}({prompt})"), 1))
+        });
+        let state = engine.execute("refs", "tea", &echo).unwrap();
+        assert_eq!(state.status, WorkflowStatus::Completed);
+        assert_eq!(
+            state.last_output(),
+            "writer(tea | researcher(tea) | stylist(researcher(tea)))"
+        );
+
+        // A reference to a step that has not run fails the workflow.
+        let wf = Workflow::new("bad_ref", "Forward reference")
+            .add_step(
+                WorkflowStep::new("first", "writer", StepType::Sequential)
+                    .with_prompt("{{steps.later.output}}"),
+            )
+            .add_step(WorkflowStep::new("later", "editor", StepType::Sequential));
+        engine.register(wf);
+        let state = engine.execute("bad_ref", "tea", &echo).unwrap();
+        assert_eq!(state.status, WorkflowStatus::Failed);
+        assert!(state.error.unwrap().contains("'later'"));
+    }
+
     #[test]
     fn test_engine_fanout() {
         let mut engine = WorkflowEngine::new();
@@ -489,6 +645,102 @@ mod tests {
         assert_eq!(state.status, WorkflowStatus::Completed);
     }
 
+    #[test]
+    fn test_fanout_respects_max_parallel() {
+        use std::sync::Arc;
+
+        let names = ["a", "b", "c", "d"];
+        let mut wf = Workflow::new("limited", "Fan-out limit").with_max_parallel(2);
+        for name in names {
+            wf = wf.add_step(WorkflowStep::new(name, name, StepType::Sequential));
+        }
+        let wf = wf.add_step(WorkflowStep::new(
+            "all",
+            "coordinator",
+            StepType::FanOut {
+                parallel_steps: names.iter().map(|n| n.to_string()).collect(),
+            },
+        ));
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (r, p) = (running.clone(), peak.clone());
+        let agent_fn: AgentCallback = Box::new(move |agent: &str, _prompt: &str| {
+            let now = r.fetch_add(1, Ordering::SeqCst) + 1;
+            p.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            r.fetch_sub(1, Ordering::SeqCst);
+            Ok((format!("done by {agent}"), 1))
+        });
+
+        let mut engine = WorkflowEngine::new();
+        engine.register(wf.clone());
+        let state = engine.execute("limited", "go", &agent_fn).unwrap();
+        assert_eq!(state.status, WorkflowStatus::Completed);
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        let output = state.step_output("all").unwrap();
+        let order: Vec<_> = names
+            .iter()
+            .map(|n| output.find(&format!("done by {n}")).unwrap())
+            .collect();
+        assert!(order.windows(2).all(|w| w[0] < w[1]));
+
+        // A per-step override wins over the workflow default.
+        let fanout = wf.get_step("all").unwrap();
+        assert_eq!(wf.parallel_limit(fanout), 2);
+        assert_eq!(wf.parallel_limit(&fanout.clone().with_max_parallel(1)), 1);
+    }
+
+    #[test]
+    fn test_engine_rank_collect() {
+        let mut engine = WorkflowEngine::new();
+        let wf = Workflow::new("slogans", "Generate and rank")
+            .add_step(WorkflowStep::new("a", "writer_a", StepType::Sequential))
+            .add_step(WorkflowStep::new("b", "writer_b", StepType::Sequential))
+            .add_step(WorkflowStep::new("c", "writer_c", StepType::Sequential))
+            .add_step(WorkflowStep::new(
+                "options",
+                "coordinator",
+                StepType::FanOut {
+                    parallel_steps: vec!["a".into(), "b".into(), "c".into()],
+                },
+            ))
+            .add_step(WorkflowStep::new(
+                "ranked",
+                "coordinator",
+                StepType::Collect {
+                    strategy: CollectStrategy::Rank,
+                    evaluator: Some("judge".into()),
+                },
+            ));
+        engine.register(wf);
+
+        let agents: AgentCallback = Box::new(|agent: &str, prompt: &str| match agent {
+            "judge" => {
+                assert!(prompt.contains("Candidate 3:\nslogan from writer_c"));
+                Ok(("1: 6\n2: 9\n3: 7.5".into(), 10))
+            }
+            writer => Ok((format!("slogan from {writer}"), 10)),
+        });
+        let state = engine.execute("slogans", "coffee", &agents).unwrap();
+        assert_eq!(state.status, WorkflowStatus::Completed);
+        assert_eq!(
+            state.last_output(),
+            "=== #1 (score 9) b (by writer_b) ===\nslogan from writer_b\n\n\
+             === #2 (score 7.5) c (by writer_c) ===\nslogan from writer_c\n\n\
+             === #3 (score 6) a (by writer_a) ===\nslogan from writer_a"
+        );
+
+        // An unparseable verdict keeps the fan-out order.
+        let vague: AgentCallback = Box::new(|agent: &str, _prompt: &str| match agent {
+            "judge" => Ok(("All good.".into(), 10)),
+            writer => Ok((format!("slogan from {writer}"), 10)),
+        });
+        let state = engine.execute("slogans", "coffee", &vague).unwrap();
+        assert!(state.last_output().starts_with("=== #1 (unscored) a (by writer_a) ==="));
+    }
+
     #[test]
     fn test_engine_optional_step() {
         let mut engine = WorkflowEngine::new();
@@ -519,6 +771,92 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_engine_progress_events() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = WorkflowEngine::new();
+        let wf = Workflow::new("test_progress", "Progress test")
+            .add_step(WorkflowStep::new("draft", "writer", StepType::Sequential))
+            .add_step(WorkflowStep::new("review", "editor", StepType::Sequential));
+        engine.register(wf);
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let on_event: ProgressCallback = Box::new(move |e: &WorkflowEvent| {
+            sink.lock().unwrap().push(e.clone());
+        });
+
+        let state = engine
+            .execute_with_progress("test_progress", "topic", &mock_agent_fn(), &on_event)
+            .unwrap();
+        assert_eq!(state.status, WorkflowStatus::Completed);
+
+        let events = events.lock().unwrap();
+        assert_eq!(
+            *events,
+            vec![
+                WorkflowEvent::StepStarted { index: 0, name: "draft".into(), agent: "writer".into() },
+                WorkflowEvent::StepCompleted {
+                    index: 0,
+                    name: "draft".into(),
+                    tokens_used: 100,
+                    latency_ms: state.step_results[0].latency_ms,
+                    total_tokens: 100,
+                },
+                WorkflowEvent::StepStarted { index: 1, name: "review".into(), agent: "editor".into() },
+                WorkflowEvent::StepCompleted {
+                    index: 1,
+                    name: "review".into(),
+                    tokens_used: 100,
+                    latency_ms: state.step_results[1].latency_ms,
+                    total_tokens: 200,
+                },
+                WorkflowEvent::WorkflowCompleted {
+                    workflow: "test_progress".into(),
+                    status: WorkflowStatus::Completed,
+                    steps_completed: 2,
+                    total_tokens: 200,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_engine_cancel_halts_remaining_steps() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let mut engine = WorkflowEngine::new();
+        let wf = Workflow::new("cancel_me", "Cancel test")
+            .add_step(WorkflowStep::new("s1", "a", StepType::Sequential))
+            .add_step(WorkflowStep::new("s2", "b", StepType::Sequential))
+            .add_step(WorkflowStep::new("s3", "c", StepType::Sequential));
+        engine.register(wf);
+
+        // The first step cancels the run while it is executing.
+        let token = CancelToken::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let agent_fn: AgentCallback = {
+            let token = token.clone();
+            let calls = calls.clone();
+            Box::new(move |_agent: &str, _prompt: &str| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                token.cancel();
+                Ok(("done".into(), 10))
+            })
+        };
+        let no_progress: ProgressCallback = Box::new(|_: &WorkflowEvent| {});
+
+        let state = engine
+            .execute_cancellable("cancel_me", "go", &agent_fn, &no_progress, &token)
+            .unwrap();
+        assert_eq!(state.status, WorkflowStatus::Cancelled);
+        assert_eq!(state.step_results.len(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(state.completed_at.is_some());
+    }
+
     #[test]
     fn test_engine_history() {
         let mut engine = WorkflowEngine::new();
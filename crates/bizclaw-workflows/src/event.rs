@@ -0,0 +1,47 @@
+//! Workflow progress events — emitted while a workflow is executing.
+//!
+//! Events are serialized with a `type` tag so the gateway can relay them
+//! verbatim over WebSocket, e.g. `{"type":"step_started","index":0,...}`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::WorkflowStatus;
+
+/// A progress event emitted by [`crate::WorkflowEngine::execute_with_progress`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkflowEvent {
+    /// A step is about to run.
+    StepStarted {
+        index: usize,
+        name: String,
+        agent: String,
+    },
+    /// A step finished successfully.
+    StepCompleted {
+        index: usize,
+        name: String,
+        tokens_used: u64,
+        latency_ms: u64,
+        /// Running token total across the workflow so far.
+        total_tokens: u64,
+    },
+    /// A step failed. `optional` steps are skipped and the workflow continues.
+    StepFailed {
+        index: usize,
+        name: String,
+        error: String,
+        optional: bool,
+        total_tokens: u64,
+    },
+    /// The workflow finished (successfully or not).
+    WorkflowCompleted {
+        workflow: String,
+        status: WorkflowStatus,
+        steps_completed: usize,
+        total_tokens: u64,
+    },
+}
+
+/// Callback invoked for every progress event.
+pub type ProgressCallback = Box<dyn Fn(&WorkflowEvent) + Send + Sync>;
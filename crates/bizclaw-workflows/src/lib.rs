@@ -22,11 +22,14 @@
 //! ```
 
 pub mod engine;
+pub mod event;
+pub mod rank;
 pub mod state;
 pub mod step;
 pub mod templates;
 
 pub use engine::WorkflowEngine;
+pub use event::{ProgressCallback, WorkflowEvent};
 pub use state::{WorkflowState, WorkflowStatus};
 pub use step::{
     CollectStrategy, Condition, LoopConfig, StepType, Workflow, WorkflowStep, WorkflowStepResult,
@@ -0,0 +1,192 @@
+//! Ranking of fan-out results for [`CollectStrategy::Rank`](crate::CollectStrategy::Rank).
+//!
+//! A fan-out step's output is a series of `=== step (by agent) ===` sections.
+//! The evaluator agent scores each section; the sections are then returned
+//! best first, each header carrying its rank and score. Evaluator replies
+//! are parsed leniently — `1: 8`, `Candidate 2 - 7.5/10`, `{"1": 8}` or
+//! `[8, 6, 9]` all work. If not every candidate gets a score, the original
+//! order is kept.
+
+/// One fan-out result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// Section header, e.g. `expert1 (by analyst)`.
+    pub label: String,
+    pub content: String,
+}
+
+/// Split fan-out output into its sections. Text without section headers is
+/// a single candidate.
+pub fn split_candidates(input: &str) -> Vec<Candidate> {
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for line in input.lines() {
+        let header = line
+            .strip_prefix("=== ")
+            .and_then(|rest| rest.strip_suffix(" ==="));
+        match (header, candidates.last_mut()) {
+            (Some(label), _) => candidates.push(Candidate {
+                label: label.to_string(),
+                content: String::new(),
+            }),
+            (None, Some(current)) => {
+                current.content.push_str(line);
+                current.content.push('\n');
+            }
+            (None, None) => {}
+        }
+    }
+    if candidates.is_empty() {
+        return vec![Candidate {
+            label: "result".into(),
+            content: input.trim().to_string(),
+        }];
+    }
+    for candidate in &mut candidates {
+        candidate.content = candidate.content.trim().to_string();
+    }
+    candidates
+}
+
+/// Prompt asking the evaluator to score `candidates`.
+pub fn scoring_prompt(candidates: &[Candidate]) -> String {
+    let listing = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, c)| format!("Candidate {}:\n{}", i + 1, c.content))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    format!(
+        "Score each of the following {} candidates from 0 to 10 for quality and usefulness. \
+         Reply with one line per candidate in the form `<candidate number>: <score>` \
+         and nothing else.\n\n{listing}",
+        candidates.len()
+    )
+}
+
+/// Scores for `count` candidates, in candidate order, or `None` unless the
+/// reply scores every one of them.
+pub fn parse_scores(reply: &str, count: usize) -> Option<Vec<f64>> {
+    let mut scores = vec![None; count];
+    let trimmed = reply
+        .trim()
+        .trim_start_matches("```json")
+        .trim_matches('`')
+        .trim();
+    match serde_json::from_str::<serde_json::Value>(trimmed) {
+        Ok(serde_json::Value::Object(map)) => {
+            for (key, score) in map {
+                let index = numbers(&key).first().copied();
+                set_score(&mut scores, index, score.as_f64());
+            }
+        }
+        Ok(serde_json::Value::Array(list)) => {
+            for (i, score) in list.iter().enumerate() {
+                set_score(&mut scores, Some((i + 1) as f64), score.as_f64());
+            }
+        }
+        _ => {
+            for line in reply.lines() {
+                if let [index, score, ..] = numbers(line)[..] {
+                    set_score(&mut scores, Some(index), Some(score));
+                }
+            }
+        }
+    }
+    scores.into_iter().collect()
+}
+
+/// Record `score` for the 1-based candidate `index`; the first score wins.
+fn set_score(scores: &mut [Option<f64>], index: Option<f64>, score: Option<f64>) {
+    let (Some(index), Some(score)) = (index, score) else {
+        return;
+    };
+    if index.fract() != 0.0 || index < 1.0 {
+        return;
+    }
+    if let Some(slot) = scores.get_mut(index as usize - 1)
+        && slot.is_none()
+    {
+        *slot = Some(score);
+    }
+}
+
+/// Every decimal number in `text`, in order.
+fn numbers(text: &str) -> Vec<f64> {
+    let mut found = Vec::new();
+    let mut current = String::new();
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if c.is_ascii_digit() || (c == '.' && !current.is_empty() && !current.contains('.')) {
+            current.push(c);
+        } else if !current.is_empty() {
+            if let Ok(n) = current.trim_end_matches('.').parse() {
+                found.push(n);
+            }
+            current.clear();
+        }
+    }
+    found
+}
+
+/// Candidates best first, each section headed by its rank and score. With
+/// no scores the original order is kept and marked unscored.
+pub fn render_ranking(candidates: &[Candidate], scores: Option<&[f64]>) -> String {
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    if let Some(scores) = scores {
+        // Stable: ties keep their fan-out order.
+        order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+    }
+    order
+        .iter()
+        .enumerate()
+        .map(|(rank, &i)| {
+            let score = match scores {
+                Some(scores) => format!("score {}", scores[i]),
+                None => "unscored".to_string(),
+            };
+            format!(
+                "=== #{} ({score}) {} ===\n{}",
+                rank + 1,
+                candidates[i].label,
+                candidates[i].content
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_candidates() {
+        let input = "=== a (by x) ===\nfirst\nline\n\n=== b (by y) ===\nsecond";
+        let candidates = split_candidates(input);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].label, "a (by x)");
+        assert_eq!(candidates[0].content, "first\nline");
+        assert_eq!(candidates[1].content, "second");
+
+        assert_eq!(split_candidates("plain text")[0].content, "plain text");
+    }
+
+    #[test]
+    fn test_parse_scores_formats() {
+        assert_eq!(
+            parse_scores("1: 8\n2: 6\n3: 9", 3),
+            Some(vec![8.0, 6.0, 9.0])
+        );
+        assert_eq!(
+            parse_scores("Candidate 2 - 7.5/10\nCandidate 1 - 9/10.", 2),
+            Some(vec![9.0, 7.5])
+        );
+        assert_eq!(
+            parse_scores("```json\n{\"1\": 3, \"2\": 4}\n```", 2),
+            Some(vec![3.0, 4.0])
+        );
+        assert_eq!(parse_scores("[5, 1]", 2), Some(vec![5.0, 1.0]));
+        // Missing or out-of-range candidates are a parse failure.
+        assert_eq!(parse_scores("1: 8\n4: 2", 2), None);
+        assert_eq!(parse_scores("They are all great!", 2), None);
+    }
+}
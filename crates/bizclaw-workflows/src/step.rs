@@ -3,6 +3,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::state::WorkflowState;
+
 /// Strategy for collecting results from fan-out steps.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -17,6 +19,8 @@ pub enum CollectStrategy {
     Merge,
     /// Take the first result that matches criteria.
     First,
+    /// All results, best first, with the evaluator's score for each.
+    Rank,
 }
 
 /// Condition for conditional steps.
@@ -94,7 +98,7 @@ pub enum StepType {
     Collect {
         /// Strategy for combining results.
         strategy: CollectStrategy,
-        /// Optional evaluator agent (for "best" strategy).
+        /// Optional evaluator agent (for the "best" and "rank" strategies).
         evaluator: Option<String>,
     },
     /// Conditional: run different steps based on a condition.
@@ -113,7 +117,7 @@ pub enum StepType {
     },
     /// Transform: apply a transformation to the input without an agent.
     Transform {
-        /// Template string with {{input}} placeholder.
+        /// Template string with the same placeholders as a prompt template.
         template: String,
     },
 }
@@ -127,7 +131,7 @@ pub struct WorkflowStep {
     pub agent: String,
     /// Step type and configuration.
     pub step_type: StepType,
-    /// Custom prompt template ({{input}} is replaced with previous output).
+    /// Custom prompt template — see [`render_template`] for placeholders.
     pub prompt_template: Option<String>,
     /// Maximum execution time in seconds.
     pub timeout_secs: u64,
@@ -135,6 +139,9 @@ pub struct WorkflowStep {
     pub optional: bool,
     /// Retry count on failure.
     pub max_retries: u32,
+    /// Fan-out only: overrides [`Workflow::max_parallel`] for this step.
+    #[serde(default)]
+    pub max_parallel: Option<usize>,
 }
 
 impl WorkflowStep {
@@ -147,6 +154,7 @@ impl WorkflowStep {
             timeout_secs: 300,
             optional: false,
             max_retries: 0,
+            max_parallel: None,
         }
     }
 
@@ -170,15 +178,63 @@ impl WorkflowStep {
         self
     }
 
-    /// Build the actual prompt by replacing {{input}} with previous output.
-    pub fn build_prompt(&self, input: &str) -> String {
+    pub fn with_max_parallel(mut self, limit: usize) -> Self {
+        self.max_parallel = Some(limit);
+        self
+    }
+
+    /// Build the actual prompt from the template, with `input` as this step's
+    /// input and earlier step outputs taken from `state`.
+    pub fn build_prompt(&self, input: &str, state: &WorkflowState) -> Result<String, String> {
         match &self.prompt_template {
-            Some(template) => template.replace("{{input}}", input),
-            None => input.to_string(),
+            Some(template) => render_template(template, input, state)
+                .map_err(|e| format!("Step '{}': {e}", self.name)),
+            None => Ok(input.to_string()),
         }
     }
 }
 
+/// Fill a step template.
+///
+/// - `{{input}}` — this step's input (the previous step's output, or the
+///   workflow input for the first step)
+/// - `{{workflow.input}}` — the input the workflow was started with
+/// - `{{steps.<name>.output}}` — the output of an earlier step
+///
+/// Referencing a step that has not run yet is an error. Any other `{{...}}`
+/// is left as-is.
+pub fn render_template(
+    template: &str,
+    input: &str,
+    state: &WorkflowState,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open + 2..].find("}}") else {
+            break;
+        };
+        let key = rest[open + 2..open + 2 + close].trim();
+        out.push_str(&rest[..open]);
+        match key {
+            "input" => out.push_str(input),
+            "workflow.input" => out.push_str(&state.initial_input),
+            _ => match key.strip_prefix("steps.").and_then(|k| k.strip_suffix(".output")) {
+                Some(step) => {
+                    let output = state.step_output(step).ok_or_else(|| {
+                        format!("template references step '{step}', which has not run yet")
+                    })?;
+                    out.push_str(output);
+                }
+                None => out.push_str(&rest[open..open + 2 + close + 2]),
+            },
+        }
+        rest = &rest[open + 2 + close + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
 /// Result from executing a workflow step.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowStepResult {
@@ -204,6 +260,13 @@ pub enum StepResultStatus {
     TimedOut,
 }
 
+/// Default limit on fan-out sub-steps running at once.
+pub const DEFAULT_MAX_PARALLEL: usize = 4;
+
+fn default_max_parallel() -> usize {
+    DEFAULT_MAX_PARALLEL
+}
+
 /// A complete workflow definition.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workflow {
@@ -219,6 +282,9 @@ pub struct Workflow {
     pub max_runtime_secs: u64,
     /// Whether to stop on first failure.
     pub stop_on_failure: bool,
+    /// Maximum fan-out sub-steps running at once.
+    #[serde(default = "default_max_parallel")]
+    pub max_parallel: usize,
     /// Tags for categorization.
     pub tags: Vec<String>,
     /// Created timestamp.
@@ -234,6 +300,7 @@ impl Workflow {
             steps: Vec::new(),
             max_runtime_secs: 1800,
             stop_on_failure: true,
+            max_parallel: DEFAULT_MAX_PARALLEL,
             tags: Vec::new(),
             created_at: Utc::now(),
         }
@@ -254,6 +321,16 @@ impl Workflow {
         self
     }
 
+    pub fn with_max_parallel(mut self, limit: usize) -> Self {
+        self.max_parallel = limit;
+        self
+    }
+
+    /// How many sub-steps of fan-out `step` may run at once (at least 1).
+    pub fn parallel_limit(&self, step: &WorkflowStep) -> usize {
+        step.max_parallel.unwrap_or(self.max_parallel).max(1)
+    }
+
     pub fn with_tags(mut self, tags: Vec<&str>) -> Self {
         self.tags = tags.into_iter().map(|t| t.to_string()).collect();
         self
@@ -309,11 +386,47 @@ mod tests {
         let step = WorkflowStep::new("review", "reviewer", StepType::Sequential)
             .with_prompt("Review the following content and provide feedback:\n\n{{input}}");
 
-        let prompt = step.build_prompt("Hello world article");
+        let state = WorkflowState::new("wf-1", "test", "topic");
+        let prompt = step.build_prompt("Hello world article", &state).unwrap();
         assert!(prompt.contains("Hello world article"));
         assert!(prompt.starts_with("Review the following"));
     }
 
+    #[test]
+    fn test_template_references_earlier_steps() {
+        let mut state = WorkflowState::new("wf-1", "test", "solar panels");
+        for (name, output) in [("outline", "1. Intro 2. Cost"), ("draft", "Solar is cheap.")] {
+            state.record_step(WorkflowStepResult {
+                step_name: name.into(),
+                agent: "writer".into(),
+                output: output.into(),
+                tokens_used: 0,
+                latency_ms: 0,
+                status: StepResultStatus::Success,
+                error: None,
+                started_at: Utc::now(),
+                completed_at: Utc::now(),
+                retries: 0,
+            });
+        }
+
+        let step = WorkflowStep::new("polish", "editor", StepType::Sequential).with_prompt(
+            "Topic: {{workflow.input}}\nOutline: {{ steps.outline.output }}\n\
+             Draft: {{steps.draft.output}}\nLast: {{input}} {{unknown}}",
+        );
+        let prompt = step.build_prompt(state.last_output(), &state).unwrap();
+        assert_eq!(
+            prompt,
+            "Topic: solar panels\nOutline: 1. Intro 2. Cost\nDraft: Solar is cheap.\n\
+             Last: Solar is cheap. {{unknown}}"
+        );
+
+        let ahead = WorkflowStep::new("early", "editor", StepType::Sequential)
+            .with_prompt("Review {{steps.review.output}}");
+        let err = ahead.build_prompt("x", &state).unwrap_err();
+        assert!(err.contains("'review'"), "{err}");
+    }
+
     #[test]
     fn test_workflow_builder_pattern() {
         let wf = Workflow::new("pipeline", "Content pipeline")
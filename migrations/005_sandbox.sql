@@ -0,0 +1,9 @@
+-- ════════════════════════════════════════════════════════════════
+-- Migration 005: Self-serve sandbox tenants
+-- Time-limited demo tenants, removed by the sandbox reaper
+-- ════════════════════════════════════════════════════════════════
+
+ALTER TABLE tenants ADD COLUMN IF NOT EXISTS sandbox BOOLEAN DEFAULT FALSE;
+ALTER TABLE tenants ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_tenants_sandbox_expiry ON tenants(expires_at) WHERE sandbox;
@@ -10,8 +10,10 @@
 //!   bizclaw onboard                    # First-time setup
 //!   bizclaw brain download             # Download local model
 //!   bizclaw config show                # Show configuration
+//!   bizclaw models                     # List provider models
 
 use anyhow::Result;
+use bizclaw_core::text::truncate_chars;
 use clap::{Parser, Subcommand};
 use tracing_subscriber::EnvFilter;
 
@@ -80,6 +82,23 @@ enum Commands {
     /// Show system info
     Info,
 
+    /// List the models a provider offers and check the configured one
+    Models {
+        /// Provider to query (default: the configured provider)
+        #[arg(short, long)]
+        provider: Option<String>,
+    },
+
+    /// Bulk-import JSONL records into the knowledge base or memory
+    Import {
+        /// Where the records go
+        #[arg(short, long, value_enum)]
+        kind: ImportKind,
+        /// JSONL file, one record per line
+        #[arg(short, long)]
+        file: std::path::PathBuf,
+    },
+
     /// Quick interactive chat (alias for agent --interactive)
     Chat {
         /// Override provider
@@ -89,6 +108,10 @@ enum Commands {
         /// Override model
         #[arg(long)]
         model: Option<String>,
+
+        /// Continue the most recently saved conversation
+        #[arg(long)]
+        resume: bool,
     },
 
     /// Start web dashboard + API server
@@ -106,6 +129,14 @@ enum Commands {
     Init,
 }
 
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ImportKind {
+    /// Documents and FAQs, chunked and indexed for search
+    Knowledge,
+    /// Memory entries, upserted by id
+    Memory,
+}
+
 #[derive(Subcommand)]
 enum ChannelAction {
     /// Start listening on configured channels
@@ -127,7 +158,11 @@ enum BrainAction {
         model: String,
     },
     /// List available models
-    List,
+    List {
+        /// Only show models that fit in this much RAM (MB); "auto" detects system RAM
+        #[arg(long)]
+        max_ram: Option<String>,
+    },
     /// Test inference
     Test {
         /// Prompt to test
@@ -164,18 +199,24 @@ async fn main() -> Result<()> {
         .init();
 
     // Load config: CLI flag → BIZCLAW_CONFIG env var → default path
-    let mut config = if let Some(path) = &cli.config {
-        bizclaw_core::BizClawConfig::load_from(std::path::Path::new(path))?
-    } else if let Ok(env_path) = std::env::var("BIZCLAW_CONFIG") {
-        let p = std::path::Path::new(&env_path);
-        if p.exists() {
-            bizclaw_core::BizClawConfig::load_from(p)?
-        } else {
-            bizclaw_core::BizClawConfig::load()?
-        }
+    let config_path = match &cli.config {
+        Some(path) => std::path::PathBuf::from(path),
+        None => std::env::var("BIZCLAW_CONFIG")
+            .map(std::path::PathBuf::from)
+            .ok()
+            .filter(|p| p.exists())
+            .unwrap_or_else(bizclaw_core::BizClawConfig::default_path),
+    };
+    let mut config = if cli.config.is_some() || config_path.exists() {
+        bizclaw_core::BizClawConfig::load_from(&config_path)?
     } else {
-        bizclaw_core::BizClawConfig::load()?
+        bizclaw_core::BizClawConfig::default()
     };
+    // Databases (knowledge, orchestration) live next to the config file
+    let data_dir = config_path
+        .parent()
+        .unwrap_or(std::path::Path::new("."))
+        .to_path_buf();
 
     match cli.command {
         Commands::Agent {
@@ -312,33 +353,35 @@ async fn main() -> Result<()> {
                     let model_dir = bizclaw_core::BizClawConfig::home_dir().join("models");
                     std::fs::create_dir_all(&model_dir)?;
 
-                    let (url, filename) = match model.as_str() {
-                        "tinyllama-1.1b" | "tinyllama" => (
-                            "https://huggingface.co/TheBloke/TinyLlama-1.1B-Chat-v1.0-GGUF/resolve/main/tinyllama-1.1b-chat-v1.0.Q4_K_M.gguf",
-                            "tinyllama-1.1b-chat-v1.0.Q4_K_M.gguf",
-                        ),
-                        "phi-2" => (
-                            "https://huggingface.co/TheBloke/phi-2-GGUF/resolve/main/phi-2.Q4_K_M.gguf",
-                            "phi-2.Q4_K_M.gguf",
+                    let catalog = load_model_catalog(&config).await;
+                    let (url, filename, sha256) = match catalog.resolve(&model) {
+                        Some(entry) => (
+                            entry.url.clone(),
+                            bizclaw_brain::catalog::file_name(entry),
+                            entry.sha256.clone(),
                         ),
-                        "llama-3.2-1b" | "llama3.2" => (
-                            "https://huggingface.co/bartowski/Llama-3.2-1B-Instruct-GGUF/resolve/main/Llama-3.2-1B-Instruct-Q4_K_M.gguf",
-                            "Llama-3.2-1B-Instruct-Q4_K_M.gguf",
-                        ),
-                        other if other.starts_with("http") => (other, "custom-model.gguf"),
-                        _ => {
+                        None if model.starts_with("http") => {
+                            (model.clone(), "custom-model.gguf".to_string(), None)
+                        }
+                        None => {
+                            let names: Vec<&str> =
+                                catalog.entries().iter().map(|e| e.name.as_str()).collect();
                             println!("❌ Unknown model: {model}");
-                            println!("   Available: tinyllama-1.1b, phi-2, llama-3.2-1b");
+                            println!("   Available: {}", names.join(", "));
                             println!("   Or provide a direct URL to a .gguf file");
                             return Ok(());
                         }
                     };
 
-                    let dest = model_dir.join(filename);
+                    let dest = model_dir.join(&filename);
                     if dest.exists() {
                         println!("✅ Model already downloaded: {}", dest.display());
                         return Ok(());
                     }
+                    let sha256 = match sha256 {
+                        Some(sha256) => Some(sha256),
+                        None => published_sha256(&url).await,
+                    };
 
                     println!("🧠 Downloading: {filename}");
                     println!("   From: {url}");
@@ -348,7 +391,7 @@ async fn main() -> Result<()> {
                     // Stream download with progress
                     let client = reqwest::Client::new();
                     let response = client
-                        .get(url)
+                        .get(&url)
                         .send()
                         .await
                         .map_err(|e| anyhow::anyhow!("Download failed: {e}"))?;
@@ -384,10 +427,18 @@ async fn main() -> Result<()> {
                     }
 
                     file.flush().await?;
+                    if let Some(expected) = sha256 {
+                        print!("\n   🔒 Verifying checksum...");
+                        if !bizclaw_brain::catalog::verify_sha256(&dest, &expected)? {
+                            std::fs::remove_file(&dest).ok();
+                            anyhow::bail!("Checksum mismatch for {filename} — download removed");
+                        }
+                        println!(" ok");
+                    }
                     println!("\n\n✅ Download complete: {}", dest.display());
                     println!("   Test with: bizclaw brain test \"Hello!\"");
                 }
-                BrainAction::List => {
+                BrainAction::List { max_ram } => {
                     println!("🧠 Brain Models\n");
 
                     // List installed models
@@ -417,46 +468,58 @@ async fn main() -> Result<()> {
                         println!("  (no models directory)");
                     }
 
-                    println!("\n📦 Available for download:");
-                    println!("  - tinyllama-1.1b  (~638 MB, recommended for Pi)");
-                    println!("  - phi-2           (~1.6 GB)");
-                    println!("  - llama-3.2-1b    (~750 MB)");
+                    let catalog = load_model_catalog(&config).await;
+                    let ram_limit = match max_ram.as_deref() {
+                        Some("auto") => bizclaw_brain::catalog::system_ram_mb(),
+                        Some(mb) => Some(
+                            mb.parse::<u64>()
+                                .map_err(|_| anyhow::anyhow!("--max-ram expects MB or 'auto'"))?,
+                        ),
+                        None => None,
+                    };
+                    let models = match ram_limit {
+                        Some(mb) => {
+                            println!("\n📦 Available for download (fits in {mb} MB RAM):");
+                            catalog.fitting_ram(mb)
+                        }
+                        None => {
+                            println!("\n📦 Available for download:");
+                            catalog.entries().iter().collect()
+                        }
+                    };
+                    if models.is_empty() {
+                        println!("  (no models fit — try a larger --max-ram)");
+                    }
+                    for m in models {
+                        println!(
+                            "  - {:<16} {:>6} MB  {:<7} RAM ≥{} MB  {}",
+                            m.name, m.size_mb, m.quant, m.min_ram_mb, m.description
+                        );
+                    }
                     println!("\n  Use: bizclaw brain download <model-name>");
                 }
                 BrainAction::Test { prompt } => {
                     println!("🧠 Testing brain inference...\n");
 
-                    // Try to find and load a model
-                    let model_dir = bizclaw_core::BizClawConfig::home_dir().join("models");
-                    let model_path = std::fs::read_dir(&model_dir).ok().and_then(|entries| {
-                        entries
-                            .filter_map(|e| e.ok())
-                            .find(|e| {
-                                e.path().extension().and_then(|ext| ext.to_str()) == Some("gguf")
-                            })
-                            .map(|e| e.path())
-                    });
-
-                    match model_path {
-                        Some(path) => {
-                            println!("   Model: {}", path.display());
-                            match bizclaw_brain::BrainEngine::load(&path) {
-                                Ok(mut engine) => {
-                                    if let Some(info) = engine.model_info() {
-                                        println!("   Info: {info}");
-                                    }
-                                    println!("   Prompt: \"{prompt}\"\n");
-                                    match engine.generate(&prompt, 100) {
-                                        Ok(response) => println!("🤖 {response}"),
-                                        Err(e) => println!("❌ Inference error: {e}"),
-                                    }
+                    // Same model the brain provider would load
+                    let path = bizclaw_providers::brain::model_path(&config);
+                    if !path.exists() {
+                        println!("❌ No model found at {}", path.display());
+                        println!("   Run: bizclaw brain download tinyllama-1.1b");
+                    } else {
+                        println!("   Model: {}", path.display());
+                        match bizclaw_brain::BrainEngine::load(&path) {
+                            Ok(mut engine) => {
+                                if let Some(info) = engine.model_info() {
+                                    println!("   Info: {info}");
+                                }
+                                println!("   Prompt: \"{prompt}\"\n");
+                                match engine.generate(&prompt, 100) {
+                                    Ok(response) => println!("🤖 {response}"),
+                                    Err(e) => println!("❌ Inference error: {e}"),
                                 }
-                                Err(e) => println!("❌ Failed to load model: {e}"),
                             }
-                        }
-                        None => {
-                            println!("❌ No model found in {}", model_dir.display());
-                            println!("   Run: bizclaw brain download tinyllama-1.1b");
+                            Err(e) => println!("❌ Failed to load model: {e}"),
                         }
                     }
                 }
@@ -509,7 +572,81 @@ async fn main() -> Result<()> {
             }
         }
 
-        Commands::Chat { provider, model } => {
+        Commands::Models { provider } => {
+            use bizclaw_providers::model_probe::{self, ModelSource};
+
+            let probe = model_probe::probe(&config, provider.as_deref()).await?;
+            if let Some(err) = &probe.error {
+                println!("⚠️  Could not query {}: {err}", probe.provider);
+            }
+            let source = match probe.source {
+                ModelSource::Live => "live",
+                ModelSource::Catalog => "built-in list",
+            };
+            println!("📋 Models for {} ({source}):", probe.provider);
+            for model in &probe.models {
+                if probe.is_configured(model) {
+                    println!("   ★ {} (configured default)", model.id);
+                } else {
+                    println!("   • {}", model.id);
+                }
+            }
+            if probe.models.is_empty() {
+                println!("   (none)");
+            }
+            if let Some(warning) = probe.warning() {
+                println!("⚠️  {warning}");
+            }
+        }
+
+        Commands::Import { kind, file } => {
+            use std::io::Write;
+
+            let input = std::fs::read_to_string(&file)?;
+            let file_name = file
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let progress = |done: usize, total: usize| {
+                if done.is_multiple_of(100) || done == total {
+                    print!("\r   ⏳ {done}/{total} records");
+                    std::io::stdout().flush().ok();
+                }
+            };
+            println!("📥 Importing {}...", file.display());
+            let report = match kind {
+                ImportKind::Knowledge => {
+                    let kb_path = data_dir.join("knowledge.db");
+                    let store = bizclaw_knowledge::KnowledgeStore::open(&kb_path)
+                        .map_err(|e| anyhow::anyhow!(e))?;
+                    bizclaw_knowledge::import::import_jsonl(&store, &input, &file_name, progress)
+                }
+                ImportKind::Memory => {
+                    let memory = bizclaw_memory::create_memory(&config.memory)?;
+                    bizclaw_memory::import::import_jsonl(memory.as_ref(), &input, progress).await
+                }
+            };
+            println!();
+            match kind {
+                ImportKind::Knowledge => println!(
+                    "✅ Imported {} document(s), {} chunk(s) indexed",
+                    report.imported, report.chunks
+                ),
+                ImportKind::Memory => println!("✅ Imported {} memory entries", report.imported),
+            }
+            if !report.failures.is_empty() {
+                println!("⚠️  {} record(s) failed:", report.failures.len());
+                for failure in &report.failures {
+                    println!("   line {}: {}", failure.line, failure.error);
+                }
+            }
+        }
+
+        Commands::Chat {
+            provider,
+            model,
+            resume,
+        } => {
             if let Some(p) = provider {
                 config.default_provider = p;
             }
@@ -517,11 +654,30 @@ async fn main() -> Result<()> {
                 config.default_model = m;
             }
 
+            use bizclaw_agent::history::ChatHistory;
+            let history = ChatHistory::from_config(&config.chat_history);
+            let autosave = config.chat_history.enabled;
             let mut agent = bizclaw_agent::Agent::new(config)?;
 
             println!("🦀 BizClaw v{} — Chat Mode", env!("CARGO_PKG_VERSION"));
             println!("   Provider: {}", agent.provider_name());
-            println!("   Type /quit to exit, /clear to reset conversation\n");
+            println!(
+                "   Type /quit to exit, /clear to reset conversation, \
+                 /save [name] and /load [name] for history\n"
+            );
+
+            let mut session = ChatHistory::new_session_id();
+            if resume {
+                match history.latest() {
+                    Some(latest) => {
+                        let messages = history.load(&latest)?;
+                        println!("📂 Resumed '{latest}' ({} messages)\n", messages.len());
+                        agent.restore_conversation(messages);
+                        session = latest;
+                    }
+                    None => println!("📂 No saved conversation to resume.\n"),
+                }
+            }
 
             let mut cli_channel = bizclaw_channels::cli::CliChannel::new();
             cli_channel.connect().await?;
@@ -543,6 +699,39 @@ async fn main() -> Result<()> {
                     continue;
                 }
 
+                if let Some(rest) = incoming.content.strip_prefix("/save") {
+                    let name = rest.trim();
+                    if !name.is_empty() {
+                        session = name.to_string();
+                    }
+                    match history.save(&session, agent.conversation()) {
+                        Ok(path) => println!("💾 Saved '{session}' to {}\n", path.display()),
+                        Err(e) => println!("\n❌ Error: {e}\n"),
+                    }
+                    print!("You: ");
+                    std::io::stdout().flush()?;
+                    continue;
+                }
+
+                if let Some(rest) = incoming.content.strip_prefix("/load") {
+                    let name = match rest.trim() {
+                        "" => history.latest(),
+                        name => Some(name.to_string()),
+                    };
+                    match name.map(|n| history.load(&n).map(|m| (n, m))) {
+                        Some(Ok((name, messages))) => {
+                            println!("📂 Loaded '{name}' ({} messages)\n", messages.len());
+                            agent.restore_conversation(messages);
+                            session = name;
+                        }
+                        Some(Err(e)) => println!("\n❌ Error: {e}\n"),
+                        None => println!("📂 No saved conversations.\n"),
+                    }
+                    print!("You: ");
+                    std::io::stdout().flush()?;
+                    continue;
+                }
+
                 if incoming.content == "/info" {
                     let conv = agent.conversation();
                     println!(
@@ -558,6 +747,9 @@ async fn main() -> Result<()> {
                 match agent.handle_incoming(&incoming).await {
                     Ok(response) => {
                         cli_channel.send(response).await?;
+                        if autosave && let Err(e) = history.save(&session, agent.conversation()) {
+                            tracing::warn!("Failed to save conversation: {e}");
+                        }
                     }
                     Err(e) => {
                         println!("\n❌ Error: {e}\n");
@@ -573,6 +765,18 @@ async fn main() -> Result<()> {
         Commands::Serve { port, open } => {
             println!("🦀 BizClaw v{} — Web Dashboard", env!("CARGO_PKG_VERSION"));
 
+            // Local brain: refuse to start without a model rather than
+            // failing every request
+            use bizclaw_providers::brain::BrainCheck;
+            let brain_check = BrainCheck::run(&config);
+            if let Some(guidance) = brain_check.guidance() {
+                println!("❌ {guidance}");
+                anyhow::bail!("no model for the brain provider");
+            }
+            if let BrainCheck::Ready(path) = &brain_check {
+                println!("   🧠 Brain model: {}", path.display());
+            }
+
             let mut gw_config = config.gateway.clone();
             gw_config.port = port;
 
@@ -596,59 +800,78 @@ async fn main() -> Result<()> {
             let channel_config = config.channel.clone();
             let agent_config = config.clone();
 
+            // Ctrl-C stops the server and every channel loop together
+            let shutdown = bizclaw_channels::shutdown::Shutdown::new();
+            let mut channel_tasks = Vec::new();
+            {
+                let shutdown = shutdown.clone();
+                tokio::spawn(async move {
+                    if tokio::signal::ctrl_c().await.is_ok() {
+                        println!("\n🛑 Shutting down...");
+                        shutdown.trigger();
+                    }
+                });
+            }
+
             // Telegram channel
             if let Some(tg_config) = &channel_config.telegram
                 && tg_config.enabled && !tg_config.bot_token.is_empty() {
                     println!("   🤖 Telegram: starting bot...");
-                    let tg = bizclaw_channels::telegram::TelegramChannel::new(
-                        bizclaw_channels::telegram::TelegramConfig {
-                            bot_token: tg_config.bot_token.clone(),
-                            enabled: true,
-                            poll_interval: 1,
-                        },
-                    );
+                    let tg_cfg =
+                        bizclaw_channels::telegram::TelegramConfig::from_channel_config(tg_config);
+                    let tg = bizclaw_channels::telegram::TelegramChannel::new(tg_cfg);
+                    // Shares connection state with the polling loop
+                    let handle = Box::new(tg.clone());
                     let cfg_clone = agent_config.clone();
-                    tokio::spawn(async move {
-                        run_channel_loop("telegram", tg.start_polling(), cfg_clone).await;
-                    });
+                    let dir = data_dir.clone();
+                    let stop = shutdown.clone();
+                    channel_tasks.push(tokio::spawn(async move {
+                        run_channel_loop(handle, tg.start_polling(), cfg_clone, dir, stop).await;
+                    }));
                 }
 
             // Discord channel
             if let Some(dc_config) = &channel_config.discord
                 && dc_config.enabled && !dc_config.bot_token.is_empty() {
                     println!("   🎮 Discord: starting bot...");
-                    let dc = bizclaw_channels::discord::DiscordChannel::new(
-                        bizclaw_channels::discord::DiscordConfig {
-                            bot_token: dc_config.bot_token.clone(),
-                            enabled: true,
-                            intents: (1 << 0) | (1 << 9) | (1 << 12) | (1 << 15),
-                        },
-                    );
+                    let dc_cfg = bizclaw_channels::discord::DiscordConfig {
+                        bot_token: dc_config.bot_token.clone(),
+                        enabled: true,
+                        intents: (1 << 0) | (1 << 9) | (1 << 12) | (1 << 15),
+                    };
+                    let dc = bizclaw_channels::discord::DiscordChannel::new(dc_cfg);
+                    // Shares pending slash commands with the gateway listener
+                    let handle = Box::new(dc.clone());
                     let cfg_clone = agent_config.clone();
-                    tokio::spawn(async move {
-                        run_channel_loop("discord", dc.start_gateway(), cfg_clone).await;
-                    });
+                    let dir = data_dir.clone();
+                    let stop = shutdown.clone();
+                    channel_tasks.push(tokio::spawn(async move {
+                        run_channel_loop(handle, dc.start_gateway(), cfg_clone, dir, stop).await;
+                    }));
                 }
 
             // Email channel
             if let Some(ref email_cfg) = channel_config.email
                 && email_cfg.enabled && !email_cfg.email.is_empty() {
                     println!("   📧 Email: starting listener ({})...", email_cfg.email);
-                    let em = bizclaw_channels::email::EmailChannel::new(
-                        bizclaw_channels::email::EmailConfig {
-                            imap_host: email_cfg.imap_host.clone(),
-                            imap_port: email_cfg.imap_port,
-                            smtp_host: email_cfg.smtp_host.clone(),
-                            smtp_port: email_cfg.smtp_port,
-                            email: email_cfg.email.clone(),
-                            password: email_cfg.password.clone(),
-                            ..Default::default()
-                        },
-                    );
+                    let em_cfg = bizclaw_channels::email::EmailConfig {
+                        imap_host: email_cfg.imap_host.clone(),
+                        imap_port: email_cfg.imap_port,
+                        smtp_host: email_cfg.smtp_host.clone(),
+                        smtp_port: email_cfg.smtp_port,
+                        email: email_cfg.email.clone(),
+                        password: email_cfg.password.clone(),
+                        ..Default::default()
+                    };
+                    let em = bizclaw_channels::email::EmailChannel::new(em_cfg);
+                    // Shares connection state with the polling loop
+                    let handle = Box::new(em.clone());
                     let cfg_clone = agent_config.clone();
-                    tokio::spawn(async move {
-                        run_channel_loop("email", em.start_polling(), cfg_clone).await;
-                    });
+                    let dir = data_dir.clone();
+                    let stop = shutdown.clone();
+                    channel_tasks.push(tokio::spawn(async move {
+                        run_channel_loop(handle, em.start_polling(), cfg_clone, dir, stop).await;
+                    }));
                 }
 
             // Zalo channel (Personal mode — requires cookie)
@@ -692,7 +915,22 @@ async fn main() -> Result<()> {
                 let _ = std::process::Command::new("open").arg(&url).spawn();
             }
 
-            bizclaw_gateway::start_server(&gw_config).await?;
+            let server_stop = shutdown.clone();
+            bizclaw_gateway::start_server_with_shutdown(&gw_config, async move {
+                server_stop.wait().await
+            })
+            .await?;
+
+            // Let channels finish in-flight replies and disconnect
+            shutdown.trigger();
+            let drain = futures::future::join_all(channel_tasks);
+            if tokio::time::timeout(std::time::Duration::from_secs(30), drain)
+                .await
+                .is_err()
+            {
+                tracing::warn!("Channels did not stop within 30s — exiting anyway");
+            }
+            println!("👋 Goodbye!");
         }
 
         Commands::Init => {
@@ -703,6 +941,40 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Model catalog: remote (`brain.catalog_url`) or embedded, plus custom entries from config.
+async fn load_model_catalog(
+    config: &bizclaw_core::BizClawConfig,
+) -> bizclaw_brain::catalog::ModelCatalog {
+    use bizclaw_brain::catalog::ModelCatalog;
+
+    let mut catalog = None;
+    if let Some(url) = &config.brain.catalog_url {
+        let fetched = async {
+            let body = reqwest::get(url).await?.error_for_status()?.text().await?;
+            anyhow::Ok(ModelCatalog::from_json(&body)?)
+        };
+        match fetched.await {
+            Ok(c) => catalog = Some(c),
+            Err(e) => eprintln!("⚠️  Remote catalog unavailable ({e}), using built-in list"),
+        }
+    }
+    catalog
+        .unwrap_or_else(ModelCatalog::embedded)
+        .with_custom(&config.brain.catalog)
+}
+
+/// SHA-256 Hugging Face publishes for the file at `url` (its `X-Linked-Etag`,
+/// sent before the redirect to the CDN). `None` for other hosts.
+async fn published_sha256(url: &str) -> Option<String> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .ok()?;
+    let response = client.head(url).send().await.ok()?;
+    let etag = response.headers().get("x-linked-etag")?.to_str().ok()?;
+    bizclaw_brain::catalog::sha256_from_etag(etag)
+}
+
 /// Interactive setup wizard.
 async fn run_init_wizard() -> Result<()> {
     use std::io::{self, BufRead, Write};
@@ -871,20 +1143,41 @@ async fn run_init_wizard() -> Result<()> {
 
 /// Run a channel listener loop — receives messages, routes through Agent, sends replies.
 /// Works for any channel that produces a Stream<Item = IncomingMessage>.
-async fn run_channel_loop<S>(channel_name: &str, mut stream: S, config: bizclaw_core::BizClawConfig)
-where
+/// Stops taking messages when `shutdown` fires, finishes the reply in flight,
+/// then disconnects `channel`. Dead letters go to `orchestration.db` in `data_dir`.
+async fn run_channel_loop<S>(
+    mut channel: Box<dyn bizclaw_core::traits::Channel>,
+    mut stream: S,
+    config: bizclaw_core::BizClawConfig,
+    data_dir: std::path::PathBuf,
+    shutdown: bizclaw_channels::shutdown::Shutdown,
+) where
     S: futures::Stream<Item = bizclaw_core::types::IncomingMessage> + Unpin,
 {
-    use futures::StreamExt;
+    use bizclaw_channels::shutdown::{disconnect, next_message};
+    use bizclaw_core::i18n::{t, tr};
 
+    let channel_name = channel.name().to_string();
+    let channel_name = channel_name.as_str();
     tracing::info!("📡 Channel '{channel_name}' listener started");
-
-    // Create a dedicated Agent for this channel
-    let mut agent = match bizclaw_agent::Agent::new(config.clone()) {
-        Ok(a) => {
+    let locale = config.locale();
+
+    // Create a dedicated Agent for this channel, on its own provider/model if configured
+    let mut agent_config = config.for_channel(channel_name);
+    if agent_config.default_provider != config.default_provider {
+        // The channel's provider brings its own key and endpoint, as saved from the dashboard
+        match bizclaw_gateway::db::GatewayDb::open(&data_dir.join("gateway.db")) {
+            Ok(db) => bizclaw_gateway::routes::apply_provider_config_from_db(&db, &mut agent_config),
+            Err(e) => tracing::warn!("[{channel_name}] Provider settings unavailable: {e}"),
+        }
+    }
+    let mut agent = match bizclaw_agent::Agent::new(agent_config) {
+        Ok(mut a) => {
+            a.restrict_to_channel(channel_name);
             tracing::info!(
-                "✅ Agent for channel '{channel_name}' initialized (provider={})",
-                a.provider_name()
+                "✅ Agent for channel '{channel_name}' initialized (provider={}, model={})",
+                a.provider_name(),
+                a.model_name()
             );
             a
         }
@@ -898,21 +1191,56 @@ where
     // We need a way to send messages back. For now, use the provider-specific send.
     let send_client = reqwest::Client::new();
 
-    while let Some(incoming) = stream.next().await {
+    // Acknowledges messages (reaction / read receipt) when processing starts
+    let acker: Option<Box<dyn bizclaw_core::traits::Channel>> = match channel_name {
+        "telegram" => config.channel.telegram.as_ref().map(|tg_cfg| {
+            Box::new(bizclaw_channels::telegram::TelegramChannel::new(
+                bizclaw_channels::telegram::TelegramConfig::from_channel_config(tg_cfg),
+            )) as Box<dyn bizclaw_core::traits::Channel>
+        }),
+        _ => None,
+    };
+
+    // Orchestration store: keeps failed messages as dead letters and tracks
+    // which senders were already welcomed
+    let store: Option<bizclaw_db::SqliteStore> = {
+        let path = data_dir.join("orchestration.db");
+        match bizclaw_db::SqliteStore::open(&path) {
+            Ok(store) => {
+                use bizclaw_db::DataStore;
+                if let Err(e) = store.migrate().await {
+                    tracing::warn!("[{channel_name}] Orchestration store migration failed: {e}");
+                }
+                Some(store)
+            }
+            Err(e) => {
+                tracing::warn!("[{channel_name}] Dead letters and welcome messages disabled: {e}");
+                None
+            }
+        }
+    };
+
+    let status = bizclaw_channels::status::StatusRegistry::global();
+    let maintenance = bizclaw_core::maintenance::Maintenance::global();
+    while let Some(incoming) = next_message(&mut stream, &shutdown).await {
+        status.event(channel_name);
         tracing::info!(
             "[{channel_name}] Message from {}: {}",
             incoming
                 .sender_name
                 .as_deref()
                 .unwrap_or(&incoming.sender_id),
-            &incoming.content[..incoming.content.len().min(100)]
+            truncate_chars(&incoming.content, 100)
         );
 
         let content = incoming.content.trim();
 
         // ═══ Slash Command Handling ═══
-        // Intercept /hand, /run, /help, /status commands before forwarding to Agent
-        let response = if content.starts_with('/') {
+        // Intercept /hand, /run, /help, /status commands before forwarding to Agent.
+        // In maintenance mode, answer with the maintenance message instead.
+        let response = if let Some(message) = maintenance.message() {
+            Some(message)
+        } else if content.starts_with('/') {
             let parts: Vec<&str> = content.splitn(3, ' ').collect();
             let cmd = parts[0].to_lowercase();
             let sub = parts.get(1).map(|s| s.to_lowercase()).unwrap_or_default();
@@ -925,9 +1253,27 @@ where
                         ▶️ `/hand run <name>` — Chạy Hand ngay\n\
                         🔄 `/run <workflow>` — Chạy Workflow\n\
                         📊 `/status` — Trạng thái hệ thống\n\
+                        📌 `/pin` — Ghim tin nhắn vừa gửi vào ngữ cảnh\n\
+                        🧹 `/unpin` — Bỏ ghim mọi tin nhắn\n\
+                        🕒 `/timezone <múi giờ>` — Đặt múi giờ của bạn (vd: Asia/Ho_Chi_Minh, +07:00)\n\
                         ℹ️ `/help` — Hiện menu này\n\n\
                         _Gửi tin nhắn bình thường để chat với AI agent._".to_string())
                 }
+                "/pin" => Some(match agent.pin_last_user_message() {
+                    Some(_) => t(locale, "pin.done").to_string(),
+                    None => t(locale, "pin.nothing").to_string(),
+                }),
+                "/unpin" => {
+                    let count = agent.unpin_all().to_string();
+                    Some(tr(locale, "unpin.done", &[("count", &count)]))
+                }
+                "/timezone" => {
+                    let tz = parts.get(1).copied().unwrap_or("");
+                    Some(match agent.set_user_timezone(tz).await {
+                        Ok(()) => tr(locale, "timezone.done", &[("tz", tz)]),
+                        Err(_) => tr(locale, "timezone.invalid", &[("tz", tz)]),
+                    })
+                }
                 "/status" => {
                     let provider = agent.provider_name().to_string();
                     let conv_len = agent.conversation().len();
@@ -959,7 +1305,7 @@ where
                                         if let Some(tasks) = tasks {
                                             let mut msg = "🤚 *Autonomous Hands*\n\n".to_string();
                                             if tasks.is_empty() {
-                                                msg.push_str("_Chưa có Hand nào._");
+                                                msg.push_str(t(locale, "hand.none"));
                                             }
                                             for t in tasks {
                                                 let name = t["name"].as_str().unwrap_or("?");
@@ -971,21 +1317,22 @@ where
                                                     status_icon, name, runs
                                                 ));
                                             }
-                                            msg.push_str("\n_Chạy: `/hand run <tên>`_");
+                                            msg.push('\n');
+                                            msg.push_str(t(locale, "hand.run_hint"));
                                             Some(msg)
                                         } else {
-                                            Some("⚠️ Không lấy được danh sách Hands.".to_string())
+                                            Some(t(locale, "hand.list_failed").to_string())
                                         }
                                     } else {
-                                        Some("⚠️ Lỗi đọc dữ liệu Hands.".to_string())
+                                        Some(t(locale, "hand.read_failed").to_string())
                                     }
                                 }
-                                Err(e) => Some(format!("❌ Lỗi kết nối API: {}", e)),
+                                Err(e) => Some(tr(locale, "api.connect_failed", &[("error", &e.to_string())])),
                             }
                         }
                         "run" | "trigger" => {
                             if arg.is_empty() {
-                                Some("⚠️ Cần tên Hand. Ví dụ: `/hand run Research Hand`".to_string())
+                                Some(t(locale, "hand.name_required").to_string())
                             } else {
                                 // Find and execute Hand by name
                                 let search_name = arg.to_lowercase();
@@ -1010,7 +1357,7 @@ where
                                                     .to_string();
 
                                                 // Execute the prompt through the Agent
-                                                let indicator = format!("⏳ Đang chạy *{}*...", task_name);
+                                                let indicator = tr(locale, "hand.running", &[("name", task_name)]);
                                                 // Send typing indicator
                                                 match channel_name {
                                                     "telegram" => {
@@ -1036,41 +1383,39 @@ where
                                                 match agent.process(&prompt).await {
                                                     Ok(result) => {
                                                         Some(format!(
-                                                            "🤚 *{}* — Hoàn thành!\n\n{}\n\n_⏱ Executed at {}_",
-                                                            task_name,
+                                                            "{}\n\n{}\n\n_⏱ Executed at {}_",
+                                                            tr(locale, "hand.done", &[("name", task_name)]),
                                                             if result.len() > 3500 {
-                                                                format!("{}...", &result[..3500])
+                                                                format!("{}...", truncate_chars(&result, 3500))
                                                             } else {
                                                                 result
                                                             },
                                                             chrono::Utc::now().format("%H:%M:%S UTC")
                                                         ))
                                                     }
-                                                    Err(e) => Some(format!("❌ Lỗi chạy {}: {}", task_name, e)),
+                                                    Err(e) => Some(tr(
+                                                        locale,
+                                                        "hand.run_failed",
+                                                        &[("name", task_name), ("error", &e.to_string())],
+                                                    )),
                                                 }
                                             } else {
-                                                Some(format!(
-                                                    "❌ Không tìm thấy Hand: '{}'\n_Dùng `/hand list` để xem danh sách._",
-                                                    arg
-                                                ))
+                                                Some(tr(locale, "hand.not_found", &[("name", arg)]))
                                             }
                                         } else {
-                                            Some("⚠️ Lỗi đọc dữ liệu.".to_string())
+                                            Some(t(locale, "data.read_failed").to_string())
                                         }
                                     }
-                                    Err(e) => Some(format!("❌ Lỗi API: {}", e)),
+                                    Err(e) => Some(tr(locale, "api.error", &[("error", &e.to_string())])),
                                 }
                             }
                         }
-                        _ => Some(format!(
-                            "⚠️ Lệnh không hợp lệ: `/hand {}`\n_Dùng `/hand list` hoặc `/hand run <tên>`_",
-                            sub
-                        )),
+                        _ => Some(tr(locale, "hand.invalid_command", &[("sub", sub.as_str())])),
                     }
                 }
                 "/run" => {
                     if sub.is_empty() {
-                        Some("⚠️ Cần tên workflow. Ví dụ: `/run content-creation`\n_Dùng `/hand list` để xem danh sách._".to_string())
+                        Some(t(locale, "workflow.name_required").to_string())
                     } else {
                         // Try to run as workflow via API
                         let client = reqwest::Client::new();
@@ -1090,23 +1435,23 @@ where
                                             .as_str()
                                             .unwrap_or("Workflow completed");
                                         Some(format!(
-                                            "🔄 *Workflow '{}' — Done!*\n\n{}",
-                                            sub,
+                                            "{}\n\n{}",
+                                            tr(locale, "workflow.done", &[("name", sub.as_str())]),
                                             if result.len() > 3500 {
-                                                format!("{}...", &result[..3500])
+                                                format!("{}...", truncate_chars(result, 3500))
                                             } else {
                                                 result.to_string()
                                             }
                                         ))
                                     } else {
                                         let err = data["error"].as_str().unwrap_or("Unknown error");
-                                        Some(format!("❌ Workflow error: {}", err))
+                                        Some(tr(locale, "workflow.error", &[("error", err)]))
                                     }
                                 } else {
-                                    Some("⚠️ Lỗi đọc kết quả.".to_string())
+                                    Some(t(locale, "result.read_failed").to_string())
                                 }
                             }
-                            Err(e) => Some(format!("❌ Lỗi API: {}", e)),
+                            Err(e) => Some(tr(locale, "api.error", &[("error", &e.to_string())])),
                         }
                     }
                 }
@@ -1121,55 +1466,88 @@ where
         let final_response = if let Some(cmd_resp) = response {
             cmd_resp
         } else {
-            // Process through Agent Engine (tools + memory + providers)
-            match agent.process(&incoming.content).await {
+            if let (Some(acker), Some(id)) = (&acker, &incoming.message_id)
+                && let Err(e) = acker.ack(&incoming.thread_id, id).await
+            {
+                tracing::debug!("[{channel_name}] Ack failed: {e}");
+            }
+            // Process through Agent Engine (tools + memory + providers),
+            // keeping a typing indicator up until the reply is ready
+            let work = async {
+                match &store {
+                    Some(store) => {
+                        bizclaw_agent::dead_letter::process_or_dead_letter(
+                            &mut agent, store, &incoming,
+                        )
+                        .await
+                    }
+                    None => agent.process_incoming(&incoming).await,
+                }
+            };
+            let typing_channel = acker.as_deref().unwrap_or(channel.as_ref());
+            let result = bizclaw_channels::typing::while_typing(
+                typing_channel,
+                &incoming.thread_id,
+                &config.channel.typing,
+                work,
+            )
+            .await;
+            match result {
                 Ok(r) => r,
                 Err(e) => {
-                    tracing::error!("[{channel_name}] Agent error: {e}");
-                    format!("❌ Error: {e}")
+                    tracing::error!("[{channel_name}] Agent error ({}): {e}", e.kind.code());
+                    e.user_message(locale).to_string()
                 }
             }
         };
 
+        // First message from this sender: lead with the channel's welcome
+        let final_response = match &store {
+            Some(store) => {
+                match bizclaw_agent::welcome::welcome_for(store, &config, &incoming).await {
+                    Ok(Some(welcome)) => format!("{welcome}\n\n{final_response}"),
+                    Ok(None) => final_response,
+                    Err(e) => {
+                        tracing::warn!("[{channel_name}] Welcome check failed: {e}");
+                        final_response
+                    }
+                }
+            }
+            None => final_response,
+        };
+
         tracing::info!(
             "[{channel_name}] Response: {}...",
-            &final_response[..final_response.len().min(80)]
+            truncate_chars(&final_response, 80)
         );
 
         // Send response back through the same channel
         match channel_name {
             "telegram" => {
-                if let Some(ref tg_cfg) = config.channel.telegram {
-                    let url = format!(
-                        "https://api.telegram.org/bot{}/sendMessage",
-                        tg_cfg.bot_token
+                if let Some(ref tg_cfg) = config.channel.telegram
+                    && let Ok(chat_id) = incoming.thread_id.parse::<i64>()
+                {
+                    // Escapes for the chat's parse mode, retries as plain text on 400.
+                    let tg = bizclaw_channels::telegram::TelegramChannel::new(
+                        bizclaw_channels::telegram::TelegramConfig::from_channel_config(tg_cfg),
                     );
-                    let body = serde_json::json!({
-                        "chat_id": incoming.thread_id,
-                        "text": &final_response,
-                        "parse_mode": "Markdown",
-                    });
-                    if let Err(e) = send_client.post(&url).json(&body).send().await {
+                    if let Err(e) = tg.send_message(chat_id, &final_response).await {
                         tracing::error!("[telegram] Send failed: {e}");
                     }
                 }
             }
             "discord" => {
-                if let Some(ref dc_cfg) = config.channel.discord {
-                    let url = format!(
-                        "https://discord.com/api/v10/channels/{}/messages",
-                        incoming.thread_id
-                    );
-                    let body = serde_json::json!({ "content": &final_response });
-                    if let Err(e) = send_client
-                        .post(&url)
-                        .header("Authorization", format!("Bot {}", dc_cfg.bot_token))
-                        .json(&body)
-                        .send()
-                        .await
-                    {
-                        tracing::error!("[discord] Send failed: {e}");
-                    }
+                // Replies to the message, or answers the deferred `/ask` interaction
+                let reply = bizclaw_core::types::OutgoingMessage {
+                    thread_id: incoming.thread_id.clone(),
+                    content: final_response.clone(),
+                    thread_type: incoming.thread_type.clone(),
+                    reply_to: None,
+                    reply_to_message_id: incoming.message_id.clone(),
+                    quote: None,
+                };
+                if let Err(e) = channel.send(reply).await {
+                    tracing::error!("[discord] Send failed: {e}");
                 }
             }
             "email" => {
@@ -1177,7 +1555,7 @@ where
                     tracing::info!(
                         "[email] Reply to {}: {}...",
                         incoming.sender_id,
-                        &final_response[..final_response.len().min(60)]
+                        truncate_chars(&final_response, 60)
                     );
                 }
             }
@@ -1187,5 +1565,119 @@ where
         }
     }
 
-    tracing::warn!("📡 Channel '{channel_name}' stream ended — channel may have disconnected");
+    if shutdown.is_triggered() {
+        tracing::info!("📡 Channel '{channel_name}' shutting down");
+    } else {
+        tracing::warn!("📡 Channel '{channel_name}' stream ended — channel may have disconnected");
+    }
+    disconnect(channel.as_mut(), stream).await;
+    status.disconnected(channel_name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bizclaw_core::error::Result;
+    use bizclaw_core::types::{IncomingMessage, OutgoingMessage, ThreadType};
+    use std::sync::{Arc, Mutex};
+
+    /// Records typing indicators (sent when a reply starts) and disconnects;
+    /// triggers shutdown as soon as the reply to `stop_on` starts.
+    struct MockChannel {
+        typing: Arc<Mutex<Vec<String>>>,
+        disconnected: Arc<Mutex<bool>>,
+        shutdown: bizclaw_channels::shutdown::Shutdown,
+        stop_on: &'static str,
+    }
+
+    #[async_trait::async_trait]
+    impl bizclaw_core::traits::Channel for MockChannel {
+        fn name(&self) -> &str {
+            "mock"
+        }
+        async fn connect(&mut self) -> Result<()> {
+            Ok(())
+        }
+        async fn disconnect(&mut self) -> Result<()> {
+            *self.disconnected.lock().unwrap() = true;
+            Ok(())
+        }
+        fn is_connected(&self) -> bool {
+            !*self.disconnected.lock().unwrap()
+        }
+        async fn listen(
+            &self,
+        ) -> Result<Box<dyn futures::Stream<Item = IncomingMessage> + Send + Unpin>> {
+            Ok(Box::new(tokio_stream::empty()))
+        }
+        async fn send(&self, _message: OutgoingMessage) -> Result<()> {
+            Ok(())
+        }
+        async fn send_typing(&self, thread_id: &str) -> Result<()> {
+            self.typing.lock().unwrap().push(thread_id.to_string());
+            if thread_id == self.stop_on {
+                self.shutdown.trigger();
+            }
+            Ok(())
+        }
+    }
+
+    fn incoming(thread_id: &str) -> IncomingMessage {
+        IncomingMessage {
+            channel: "mock".into(),
+            thread_id: thread_id.into(),
+            sender_id: "u1".into(),
+            sender_name: None,
+            content: "hello".into(),
+            thread_type: ThreadType::Direct,
+            timestamp: chrono::Utc::now(),
+            reply_to: None,
+            message_id: None,
+            images: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_channel_loop_finishes_in_flight_reply_on_shutdown() {
+        use bizclaw_db::DataStore;
+
+        let data_dir = std::env::temp_dir().join(format!("bizclaw-loop-{}", std::process::id()));
+        std::fs::create_dir_all(&data_dir).unwrap();
+        // Nothing listens there, so every reply fails and is dead-lettered.
+        let mut config = bizclaw_core::BizClawConfig::default();
+        config.llm.provider = "custom:http://127.0.0.1:1/v1".into();
+        config.memory.backend = "none".into();
+
+        let shutdown = bizclaw_channels::shutdown::Shutdown::new();
+        let typing = Arc::new(Mutex::new(Vec::new()));
+        let disconnected = Arc::new(Mutex::new(false));
+        let channel = MockChannel {
+            typing: typing.clone(),
+            disconnected: disconnected.clone(),
+            shutdown: shutdown.clone(),
+            stop_on: "first",
+        };
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        tx.send(incoming("first")).unwrap();
+        tx.send(incoming("second")).unwrap();
+        let stream = tokio_stream::wrappers::UnboundedReceiverStream::new(rx);
+
+        tokio::time::timeout(
+            std::time::Duration::from_secs(30),
+            run_channel_loop(Box::new(channel), stream, config, data_dir.clone(), shutdown.clone()),
+        )
+        .await
+        .expect("loop exits after shutdown");
+
+        // The reply in flight finished; the queued message was never started.
+        assert_eq!(*typing.lock().unwrap(), ["first"]);
+        let store = bizclaw_db::SqliteStore::open(&data_dir.join("orchestration.db")).unwrap();
+        let letters = store.list_dead_letters(10).await.unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].message.thread_id, "first");
+        // Disconnected, and the stream was dropped.
+        assert!(*disconnected.lock().unwrap());
+        assert!(tx.is_closed());
+        std::fs::remove_dir_all(&data_dir).ok();
+    }
 }
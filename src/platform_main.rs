@@ -7,6 +7,7 @@
 //!   bizclaw-platform                     # Start admin server (default port 3000)
 //!   bizclaw-platform --port 8080         # Custom port
 //!   bizclaw-platform --init-admin        # Create default admin user
+//!   bizclaw-platform --dry-run-tenant shop  # Show how tenant `shop` would be started
 
 use anyhow::Result;
 use clap::Parser;
@@ -60,6 +61,11 @@ struct Cli {
     #[arg(long, default_value = "BizClaw@2026")]
     admin_password: String,
 
+    /// Print how the tenant with this slug would be started (command, env,
+    /// generated config, port) without starting it, then exit
+    #[arg(long, value_name = "SLUG")]
+    dry_run_tenant: Option<String>,
+
     /// Verbose logging
     #[arg(short, long)]
     verbose: bool,
@@ -128,6 +134,25 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // --dry-run-tenant: print the tenant's spawn plan and exit
+    if let Some(slug) = &cli.dry_run_tenant {
+        let tenant = db
+            .get_tenant_by_slug(slug)?
+            .ok_or_else(|| anyhow::anyhow!("Tenant '{slug}' not found"))?;
+        let manager = bizclaw_platform::TenantManager::new(&data_dir)
+            .with_platform_url(format!("http://127.0.0.1:{}", cli.port));
+        let plan = manager.plan_tenant(&tenant, &cli.bizclaw_bin, &db)?;
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+        return Ok(());
+    }
+
+    // Make the bundled skills available to every tenant
+    match db.seed_builtin_skills() {
+        Ok(0) => {}
+        Ok(n) => tracing::info!("🧩 Added {n} built-in skills"),
+        Err(e) => tracing::warn!("⚠️  Could not seed built-in skills: {e}"),
+    }
+
     // Ensure at least one admin exists — auto-create on first run
     let users = db.list_users().unwrap_or_default();
     if users.is_empty() {
@@ -184,7 +209,10 @@ async fn main() -> Result<()> {
     // Build admin state
     let state = Arc::new(bizclaw_platform::admin::AdminState {
         db: tokio::sync::Mutex::new(db),
-        manager: tokio::sync::Mutex::new(bizclaw_platform::TenantManager::new(&data_dir)),
+        manager: tokio::sync::Mutex::new(
+            bizclaw_platform::TenantManager::new(&data_dir)
+                .with_platform_url(format!("http://127.0.0.1:{}", cli.port)),
+        ),
         jwt_secret,
         bizclaw_bin: cli.bizclaw_bin.clone(),
         base_port: cli.base_port,